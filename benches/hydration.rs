@@ -0,0 +1,86 @@
+//! Row hydration cost on large scans.
+//!
+//! The first two rounds are the floor any hydration mode shares: turso copies every row out of the statement while
+//! stepping and every text or blob again in `get_value`, and offers no borrowed accessor a batch arena or inline string
+//! could decode from. `Select::all` adds little on top of the second round, which is why there is no arena mode.
+//!
+//! Run with `cargo bench --bench hydration`; set `TURSORM_BENCH_ROWS` to change the table size (default 1M).
+
+use std::time::Duration;
+use std::time::Instant;
+
+use tursorm::TableSelectExt;
+use tursorm::migration::Migrator;
+use tursorm::prelude::*;
+
+const SQL: &str = "SELECT id, name, email, note, score FROM item";
+const ROUNDS: usize = 3;
+
+#[derive(Clone, Debug, Table)]
+pub struct Item {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:    i64,
+    pub name:  String,
+    pub email: String,
+    pub note:  Option<String>,
+    pub score: i64,
+}
+
+async fn seed(conn: &Connection, rows: usize) {
+    Migrator::migrate::<ItemTable>(conn).await.unwrap();
+
+    conn.execute("BEGIN", ()).await.unwrap();
+    let mut statement = conn.prepare("INSERT INTO item (name, email, note, score) VALUES (?, ?, ?, ?)").await.unwrap();
+    for idx in 0..rows {
+        let note = if idx % 2 == 0 { Value::Text(format!("note for item {}", idx)) } else { Value::Null };
+        statement
+            .execute((format!("item {}", idx), format!("item{}@example.com", idx), note, idx as i64))
+            .await
+            .unwrap();
+    }
+    conn.execute("COMMIT", ()).await.unwrap();
+}
+
+/// Runs `$body` (which evaluates to a row count) `ROUNDS` times and prints the fastest run.
+macro_rules! best_of {
+    ($label:expr, $body:expr) => {{
+        let mut best = Duration::MAX;
+        let mut count = 0;
+        for _ in 0..ROUNDS {
+            let start = Instant::now();
+            count = $body;
+            best = best.min(start.elapsed());
+        }
+        println!("{:<28} {:>10.1?} ({} rows)", $label, best, count);
+    }};
+}
+
+#[tokio::main]
+async fn main() {
+    let rows = std::env::var("TURSORM_BENCH_ROWS").ok().and_then(|v| v.parse().ok()).unwrap_or(1_000_000);
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    seed(&conn, rows).await;
+
+    best_of!("turso step", {
+        let mut rows = conn.query(SQL, ()).await.unwrap();
+        let mut count = 0;
+        while rows.next().await.unwrap().is_some() {
+            count += 1;
+        }
+        count
+    });
+
+    best_of!("turso step + get_value", {
+        let mut rows = conn.query(SQL, ()).await.unwrap();
+        let mut count = 0;
+        while let Some(row) = rows.next().await.unwrap() {
+            for idx in 0..row.column_count() {
+                std::hint::black_box(row.get_value(idx).unwrap());
+            }
+            count += 1;
+        }
+        count
+    });
+
+    best_of!("Select::all", ItemTable::find().all(&conn).await.unwrap().len());
+}
@@ -0,0 +1,277 @@
+use std::io::Read;
+use std::io::Write;
+
+use crate::ColumnTrait;
+use crate::TableTrait;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::error::Error;
+use crate::error::Result;
+use crate::traits::table::qualified_table_name;
+use crate::value::Value;
+
+const HEADER_PREFIX: &str = "-- tursorm table backup: ";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestoreMode {
+    /// Delete every existing row before restoring.
+    #[default]
+    Replace,
+    /// Keep existing rows; restored rows overwrite rows with the same primary key.
+    Merge,
+}
+
+/// Dumps a single table as portable SQL `INSERT` statements and restores it again.
+///
+/// Restores never execute the dump as SQL: every statement is checked against the table's columns and its literals
+/// are bound as parameters.
+pub struct TableBackup;
+
+impl TableBackup {
+    pub async fn dump<Table: TableTrait>(conn: &crate::Connection, mut writer: impl Write) -> Result<u64> {
+        let columns = Table::all_columns();
+        let table = qualified_table_name::<Table>(None);
+        let sql = format!("SELECT {} FROM {} ORDER BY {}", columns, table, Table::primary_key().name());
+
+        writeln!(writer, "{}{} ({})", HEADER_PREFIX, Table::table_name(), columns)?;
+
+        let mut rows = conn.query(&sql, ()).await?;
+        let mut count = 0;
+
+        while let Some(row) = rows.next().await? {
+            let values = (0..Table::column_count()).map(|idx| row.get_value(idx)).collect::<turso::Result<Vec<_>>>()?;
+            let literals: Vec<String> = values.iter().map(value_to_literal).collect();
+
+            writeln!(writer, "{}({});", insert_prefix::<Table>(), literals.join(", "))?;
+            count += 1;
+        }
+
+        writer.flush()?;
+        Ok(count)
+    }
+
+    pub async fn restore<Table: TableTrait>(
+        conn: &crate::Connection,
+        mut reader: impl Read,
+        mode: RestoreMode,
+    ) -> Result<u64> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+
+        let rows = parse_dump::<Table>(&input)?;
+
+        conn.in_transaction(Self::restore_rows::<Table>(conn, rows, mode)).await
+    }
+
+    async fn restore_rows<Table: TableTrait>(
+        conn: &crate::Connection,
+        rows: Vec<Vec<Value>>,
+        mode: RestoreMode,
+    ) -> Result<u64> {
+        if mode == RestoreMode::Replace {
+            conn.execute(&format!("DELETE FROM {}", qualified_table_name::<Table>(None)), ()).await?;
+        }
+
+        let placeholders = vec!["?"; Table::column_count()].join(", ");
+        let table = qualified_table_name::<Table>(None);
+        let mut sql = format!("INSERT INTO {} ({}) VALUES ({})", table, Table::all_columns(), placeholders);
+
+        if mode == RestoreMode::Merge {
+            let primary_key = Table::primary_key().name();
+            let updates: Vec<String> = Table::Column::all()
+                .iter()
+                .map(|column| column.name())
+                .filter(|name| *name != primary_key)
+                .map(|name| format!("{} = excluded.{}", name, name))
+                .collect();
+
+            if updates.is_empty() {
+                sql.push_str(&format!(" ON CONFLICT ({}) DO NOTHING", primary_key));
+            } else {
+                sql.push_str(&format!(" ON CONFLICT ({}) DO UPDATE SET {}", primary_key, updates.join(", ")));
+            }
+        }
+
+        let mut count = 0;
+        for values in rows {
+            conn.execute_cached(&sql, values).await?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+fn insert_prefix<Table: TableTrait>() -> String {
+    format!("INSERT INTO {} ({}) VALUES ", Table::table_name(), Table::all_columns())
+}
+
+fn value_to_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Integer(v) => v.to_string(),
+        Value::Real(v) if v.is_nan() => "NULL".to_string(),
+        Value::Real(v) if v.is_infinite() => if *v > 0.0 { "9e999" } else { "-9e999" }.to_string(),
+        Value::Real(v) => format!("{:?}", v),
+        Value::Text(v) => format!("'{}'", v.replace('\'', "''")),
+        Value::Blob(v) => format!("X'{}'", v.iter().map(|b| format!("{:02X}", b)).collect::<String>()),
+    }
+}
+
+fn parse_dump<Table: TableTrait>(input: &str) -> Result<Vec<Vec<Value>>> {
+    let mut rest = input.trim_start();
+
+    let expected_header = format!("{}{} ({})", HEADER_PREFIX, Table::table_name(), Table::all_columns());
+    let header = rest.lines().next().unwrap_or_default();
+    if header.trim_end() != expected_header {
+        return Err(Error::Query(format!("Backup header `{}` does not match `{}`", header, expected_header)));
+    }
+    rest = rest[header.len()..].trim_start();
+
+    let prefix = insert_prefix::<Table>();
+    let mut rows = Vec::new();
+
+    while !rest.is_empty() {
+        rest = rest.strip_prefix(prefix.as_str()).ok_or_else(|| {
+            Error::Query(format!("Expected `{}` in backup of {}", prefix.trim_end(), Table::table_name()))
+        })?;
+
+        let (values, remaining) = parse_values(rest)?;
+        if values.len() != Table::column_count() {
+            return Err(Error::Query(format!(
+                "Backup row has {} values, expected {}",
+                values.len(),
+                Table::column_count()
+            )));
+        }
+
+        rows.push(values);
+        rest = remaining
+            .trim_start()
+            .strip_prefix(';')
+            .ok_or_else(|| Error::Query("Expected `;` after backup row".to_string()))?
+            .trim_start();
+    }
+
+    Ok(rows)
+}
+
+fn parse_values(input: &str) -> Result<(Vec<Value>, &str)> {
+    let mut rest =
+        input.strip_prefix('(').ok_or_else(|| Error::Query("Expected `(` in backup row".to_string()))?.trim_start();
+    let mut values = Vec::new();
+
+    loop {
+        let (value, remaining) = parse_literal(rest)?;
+        values.push(value);
+        rest = remaining.trim_start();
+
+        if let Some(remaining) = rest.strip_prefix(',') {
+            rest = remaining.trim_start();
+        } else if let Some(remaining) = rest.strip_prefix(')') {
+            return Ok((values, remaining));
+        } else {
+            return Err(Error::Query("Expected `,` or `)` in backup row".to_string()));
+        }
+    }
+}
+
+fn parse_literal(input: &str) -> Result<(Value, &str)> {
+    if let Some(rest) = input.strip_prefix("NULL") {
+        return Ok((Value::Null, rest));
+    }
+
+    if let Some(rest) = input.strip_prefix("X'") {
+        let end = rest.find('\'').ok_or_else(|| Error::Query("Unterminated blob literal in backup".to_string()))?;
+        let hex = &rest[..end];
+        if hex.len() % 2 != 0 || !hex.is_ascii() {
+            return Err(Error::Query("Invalid blob literal in backup".to_string()));
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<std::result::Result<Vec<u8>, _>>()
+            .map_err(|e| Error::Query(format!("Invalid blob literal in backup: {}", e)))?;
+        return Ok((Value::Blob(bytes), &rest[end + 1..]));
+    }
+
+    if let Some(rest) = input.strip_prefix('\'') {
+        let mut text = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((idx, c)) = chars.next() {
+            if c == '\'' {
+                if rest[idx + 1..].starts_with('\'') {
+                    text.push('\'');
+                    chars.next();
+                } else {
+                    return Ok((Value::Text(text), &rest[idx + 1..]));
+                }
+            } else {
+                text.push(c);
+            }
+        }
+        return Err(Error::Query("Unterminated text literal in backup".to_string()));
+    }
+
+    let end = input.find(|c: char| c == ',' || c == ')' || c.is_whitespace()).unwrap_or(input.len());
+    let literal = &input[..end];
+    let value = if let Ok(v) = literal.parse::<i64>() {
+        Value::Integer(v)
+    } else if let Ok(v) = literal.parse::<f64>() {
+        Value::Real(v)
+    } else {
+        return Err(Error::Query(format!("Invalid literal `{}` in backup", literal)));
+    };
+
+    Ok((value, &input[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_to_literal() {
+        assert_eq!(value_to_literal(&Value::Null), "NULL");
+        assert_eq!(value_to_literal(&Value::Integer(-42)), "-42");
+        assert_eq!(value_to_literal(&Value::Real(1.0)), "1.0");
+        assert_eq!(value_to_literal(&Value::Real(f64::INFINITY)), "9e999");
+        assert_eq!(value_to_literal(&Value::Real(f64::NAN)), "NULL");
+        assert_eq!(value_to_literal(&Value::Text("it's".to_string())), "'it''s'");
+        assert_eq!(value_to_literal(&Value::Blob(vec![0, 171, 255])), "X'00ABFF'");
+    }
+
+    #[test]
+    fn test_literal_round_trip() {
+        let values = vec![
+            Value::Null,
+            Value::Integer(i64::MIN),
+            Value::Real(0.1),
+            Value::Real(-2.5e-300),
+            Value::Text("multi\nline, with 'quotes' and (parens);".to_string()),
+            Value::Text(String::new()),
+            Value::Blob(vec![]),
+            Value::Blob(vec![1, 2, 3]),
+        ];
+        let literals: Vec<String> = values.iter().map(value_to_literal).collect();
+        let input = format!("({});", literals.join(", "));
+
+        let (parsed, rest) = parse_values(&input).unwrap();
+        assert_eq!(parsed, values);
+        assert_eq!(rest, ";");
+    }
+
+    #[test]
+    fn test_parse_literal_infinity() {
+        assert_eq!(parse_literal("9e999").unwrap().0, Value::Real(f64::INFINITY));
+        assert_eq!(parse_literal("-9e999").unwrap().0, Value::Real(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn test_parse_literal_rejects_expressions() {
+        assert!(parse_literal("(SELECT 1)").is_err());
+        assert!(parse_literal("'unterminated").is_err());
+        assert!(parse_literal("X'ABC'").is_err());
+        assert!(parse_literal("X'ZZ'").is_err());
+        assert!(parse_literal("X'é'").is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! Tables holding the result of an expensive query, refreshed on demand like a materialized view.
+//!
+//! The backing table is an ordinary derived table, so [`Migrator`](crate::migration::Migrator) creates it like any
+//! other. Declaring it `readonly` keeps everything but [`CachedTableTrait::refresh`] from writing to it:
+//!
+//! ```ignore
+//! #[derive(Clone, Debug, Table)]
+//! #[tursorm(readonly)]
+//! pub struct DailySales {
+//!     #[tursorm(primary_key)]
+//!     pub day:   String,
+//!     pub total: i64,
+//! }
+//!
+//! impl CachedTableTrait for DailySalesTable {
+//!     fn source() -> CacheSource {
+//!         CacheSource::sql("SELECT date(created_at), SUM(amount) FROM orders GROUP BY date(created_at)")
+//!     }
+//! }
+//!
+//! Migrator::migrate::<DailySalesTable>(&conn).await?;
+//! DailySalesTable::refresh(&conn).await?;
+//! ```
+
+use crate::ConnectionLike;
+use crate::FromRow;
+use crate::Result;
+use crate::Select;
+use crate::SelectModel;
+use crate::StatementKind;
+use crate::TableTrait;
+use crate::Value;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::traits::table::qualified_table_name;
+
+/// The query a cached table is filled from. Its result columns must line up with the table's columns, in order.
+#[derive(Clone, Debug)]
+pub struct CacheSource {
+    sql:    String,
+    params: Vec<Value>,
+}
+
+impl CacheSource {
+    pub fn sql(sql: impl Into<String>) -> Self {
+        Self::with_params(sql, Vec::new())
+    }
+
+    pub fn with_params(sql: impl Into<String>, params: Vec<Value>) -> Self {
+        Self { sql: sql.into(), params }
+    }
+}
+
+impl<Table: TableTrait> From<Select<Table>> for CacheSource {
+    fn from(select: Select<Table>) -> Self {
+        let (sql, params) = select.build();
+        Self { sql, params }
+    }
+}
+
+impl<Table: TableTrait, Model: FromRow> From<SelectModel<Table, Model>> for CacheSource {
+    fn from(select: SelectModel<Table, Model>) -> Self {
+        let (sql, params) = select.build();
+        Self { sql, params }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait CachedTableTrait: TableTrait {
+    fn source() -> CacheSource;
+
+    /// Replaces the table's rows with the current result of [`source`](CachedTableTrait::source) in one
+    /// transaction, so readers see either the old rows or the new ones, and returns how many rows were written.
+    ///
+    /// Refreshing bypasses `readonly`, which only guards the table against the other write paths.
+    #[tracing::instrument(skip(conn))]
+    async fn refresh<Conn: ConnectionLike>(conn: &Conn) -> Result<u64> {
+        conn.in_transaction(refresh_rows::<Self>(conn)).await
+    }
+}
+
+async fn refresh_rows<Table: CachedTableTrait>(conn: &impl ConnectionLike) -> Result<u64> {
+    let table = qualified_table_name::<Table>(None);
+    let source = Table::source();
+
+    let sql = format!("DELETE FROM {}", table);
+    let timer = conn.start_query(Table::table_name(), StatementKind::Delete, &sql);
+    let result = conn.execute_statement(&sql, Vec::new()).await;
+    timer.finish(result.as_ref().ok().copied());
+    result?;
+
+    let sql = format!("INSERT INTO {} ({}) {}", table, Table::all_columns(), source.sql);
+    let timer = conn.start_query(Table::table_name(), StatementKind::Insert, &sql);
+    let result = conn.execute_statement(&sql, source.params).await;
+    timer.finish(result.as_ref().ok().copied());
+    result
+}
@@ -0,0 +1,389 @@
+//! Alternative storage formats for field types, such as integers outside SQLite's signed 64-bit INTEGER range.
+//!
+//! Each module provides the `to_value`/`from_value` pair expected by `#[tursorm(with = "...")]`:
+//!
+//! ```ignore
+//! #[derive(Table)]
+//! struct Account {
+//!     #[tursorm(primary_key, auto_increment)]
+//!     id:          i64,
+//!     #[tursorm(with = "tursorm::codec::u64_blob")]
+//!     external_id: u64,
+//! }
+//! ```
+
+use crate::Error;
+use crate::Result;
+
+/// Stores a `u64` as its decimal TEXT representation. Readable, but sorts lexicographically.
+pub mod u64_text {
+    use crate::Error;
+    use crate::FromValue;
+    use crate::Result;
+    use crate::Value;
+
+    pub fn to_value(value: &u64) -> Value {
+        Value::Text(value.to_string())
+    }
+
+    pub fn from_value(value: Value) -> Result<u64> {
+        match value {
+            Value::Text(s) => s.parse().map_err(|_| Error::TypeConversion {
+                expected: "u64",
+                actual:   s,
+                error:    "Expected decimal integer".to_string(),
+            }),
+            other => u64::from_value(other),
+        }
+    }
+}
+
+/// Stores a `u64` as an 8-byte big-endian BLOB, which keeps numeric order under BLOB comparison.
+pub mod u64_blob {
+    use crate::Error;
+    use crate::FromValue;
+    use crate::Result;
+    use crate::Value;
+
+    pub fn to_value(value: &u64) -> Value {
+        Value::Blob(value.to_be_bytes().to_vec())
+    }
+
+    pub fn from_value(value: Value) -> Result<u64> {
+        match value {
+            Value::Blob(bytes) => match <[u8; 8]>::try_from(bytes.as_slice()) {
+                Ok(bytes) => Ok(u64::from_be_bytes(bytes)),
+                Err(_) => Err(Error::TypeConversion {
+                    expected: "u64",
+                    actual:   format!("{:?}", bytes),
+                    error:    "Expected 8-byte blob".to_string(),
+                }),
+            },
+            other => u64::from_value(other),
+        }
+    }
+}
+
+/// Stores an `i128` as its decimal TEXT representation.
+pub mod i128_text {
+    use crate::FromValue;
+    use crate::Result;
+    use crate::Value;
+
+    pub fn to_value(value: &i128) -> Value {
+        Value::Text(value.to_string())
+    }
+
+    pub fn from_value(value: Value) -> Result<i128> {
+        i128::from_value(value)
+    }
+}
+
+/// Stores a chrono `DateTime` as RFC 3339 text (`2024-01-31T12:00:00.5Z`), keeping fractional seconds and the
+/// offset, instead of the default `YYYY-MM-DD HH:MM:SS` form.
+#[cfg(feature = "with-chrono")]
+pub mod rfc3339 {
+    use chrono::DateTime;
+    use chrono::SecondsFormat;
+    use chrono::TimeZone;
+
+    use crate::FromValue;
+    use crate::Result;
+    use crate::Value;
+
+    pub fn to_value<Tz: TimeZone>(value: &DateTime<Tz>) -> Value
+    where Tz::Offset: std::fmt::Display {
+        Value::Text(value.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+
+    pub fn from_value<T: FromValue>(value: Value) -> Result<T> {
+        T::from_value(value)
+    }
+}
+
+/// Stores a `Uuid` (or `Option<Uuid>`) as its 16-byte BLOB form instead of 36 characters of TEXT.
+///
+/// Filters bind plain `Uuid`s as TEXT, so compare against `uuid_blob::to_value(&id)`, e.g.
+/// `Condition::eq(DocColumn::OwnerId, uuid_blob::to_value(&owner_id))`. `find_by_id` converts the key itself.
+#[cfg(feature = "with-uuid")]
+pub mod uuid_blob {
+    use uuid::Uuid;
+
+    use crate::FromValue;
+    use crate::Result;
+    use crate::Value;
+
+    pub trait UuidField: FromValue {
+        fn to_blob_value(&self) -> Value;
+    }
+
+    impl UuidField for Uuid {
+        fn to_blob_value(&self) -> Value {
+            Value::Blob(self.as_bytes().to_vec())
+        }
+    }
+
+    impl UuidField for Option<Uuid> {
+        fn to_blob_value(&self) -> Value {
+            self.as_ref().map_or(Value::Null, Uuid::to_blob_value)
+        }
+    }
+
+    pub fn to_value<T: UuidField>(value: &T) -> Value {
+        value.to_blob_value()
+    }
+
+    /// Reads blobs as well as TEXT written before the column switched to `uuid_blob`.
+    pub fn from_value<T: UuidField>(value: Value) -> Result<T> {
+        T::from_value(value)
+    }
+}
+
+/// A datetime that can be stored as a Unix timestamp by [`timestamp_seconds`] and [`timestamp_millis`].
+pub trait Timestamp: Sized {
+    /// Milliseconds since the Unix epoch.
+    fn unix_millis(&self) -> i64;
+
+    fn from_unix_millis(millis: i64) -> Option<Self>;
+}
+
+/// A [`Timestamp`] or an optional one, so nullable columns can use the timestamp codecs too.
+pub trait TimestampField: Sized {
+    fn to_unix_millis(&self) -> Option<i64>;
+
+    fn from_unix_millis_value(millis: Option<i64>) -> Result<Self>;
+}
+
+impl<T: Timestamp> TimestampField for T {
+    fn to_unix_millis(&self) -> Option<i64> {
+        Some(self.unix_millis())
+    }
+
+    fn from_unix_millis_value(millis: Option<i64>) -> Result<Self> {
+        let millis = millis.ok_or(Error::UnexpectedNull)?;
+        T::from_unix_millis(millis).ok_or_else(|| Error::OutOfRange { target: "timestamp", value: millis.to_string() })
+    }
+}
+
+impl<T: Timestamp> TimestampField for Option<T> {
+    fn to_unix_millis(&self) -> Option<i64> {
+        self.as_ref().map(T::unix_millis)
+    }
+
+    fn from_unix_millis_value(millis: Option<i64>) -> Result<Self> {
+        millis.map(|millis| T::from_unix_millis_value(Some(millis))).transpose()
+    }
+}
+
+/// Stores a datetime as whole seconds since the Unix epoch in an INTEGER column; sub-second precision is dropped.
+pub mod timestamp_seconds {
+    use super::TimestampField;
+    use crate::FromValue;
+    use crate::IntoValue;
+    use crate::Result;
+    use crate::Value;
+
+    pub fn to_value<T: TimestampField>(value: &T) -> Value {
+        value.to_unix_millis().map(|millis| millis.div_euclid(1000)).into_value()
+    }
+
+    pub fn from_value<T: TimestampField>(value: Value) -> Result<T> {
+        let seconds = Option::<i64>::from_value(value)?;
+        T::from_unix_millis_value(seconds.map(|seconds| seconds.saturating_mul(1000)))
+    }
+}
+
+/// Stores a datetime as milliseconds since the Unix epoch in an INTEGER column.
+pub mod timestamp_millis {
+    use super::TimestampField;
+    use crate::FromValue;
+    use crate::IntoValue;
+    use crate::Result;
+    use crate::Value;
+
+    pub fn to_value<T: TimestampField>(value: &T) -> Value {
+        value.to_unix_millis().into_value()
+    }
+
+    pub fn from_value<T: TimestampField>(value: Value) -> Result<T> {
+        T::from_unix_millis_value(Option::<i64>::from_value(value)?)
+    }
+}
+
+#[cfg(feature = "with-chrono")]
+mod chrono_timestamp {
+    use chrono::DateTime;
+    use chrono::FixedOffset;
+    use chrono::NaiveDateTime;
+    use chrono::Utc;
+
+    use super::Timestamp;
+
+    impl Timestamp for DateTime<Utc> {
+        fn unix_millis(&self) -> i64 {
+            self.timestamp_millis()
+        }
+
+        fn from_unix_millis(millis: i64) -> Option<Self> {
+            DateTime::from_timestamp_millis(millis)
+        }
+    }
+
+    impl Timestamp for DateTime<FixedOffset> {
+        fn unix_millis(&self) -> i64 {
+            self.timestamp_millis()
+        }
+
+        fn from_unix_millis(millis: i64) -> Option<Self> {
+            DateTime::<Utc>::from_unix_millis(millis).map(|dt| dt.fixed_offset())
+        }
+    }
+
+    /// Naive datetimes are taken to be UTC.
+    impl Timestamp for NaiveDateTime {
+        fn unix_millis(&self) -> i64 {
+            self.and_utc().timestamp_millis()
+        }
+
+        fn from_unix_millis(millis: i64) -> Option<Self> {
+            DateTime::<Utc>::from_unix_millis(millis).map(|dt| dt.naive_utc())
+        }
+    }
+}
+
+#[cfg(feature = "with-time")]
+mod time_timestamp {
+    use time::OffsetDateTime;
+    use time::PrimitiveDateTime;
+
+    use super::Timestamp;
+
+    impl Timestamp for OffsetDateTime {
+        fn unix_millis(&self) -> i64 {
+            self.unix_timestamp_nanos().div_euclid(1_000_000) as i64
+        }
+
+        fn from_unix_millis(millis: i64) -> Option<Self> {
+            OffsetDateTime::from_unix_timestamp_nanos(millis as i128 * 1_000_000).ok()
+        }
+    }
+
+    /// Primitive datetimes are taken to be UTC.
+    impl Timestamp for PrimitiveDateTime {
+        fn unix_millis(&self) -> i64 {
+            self.assume_utc().unix_millis()
+        }
+
+        fn from_unix_millis(millis: i64) -> Option<Self> {
+            OffsetDateTime::from_unix_millis(millis).map(|dt| PrimitiveDateTime::new(dt.date(), dt.time()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use crate::Value;
+
+    #[test]
+    fn test_u64_text_round_trip() {
+        assert_eq!(u64_text::to_value(&u64::MAX), Value::Text("18446744073709551615".to_string()));
+        assert_eq!(u64_text::from_value(u64_text::to_value(&u64::MAX)).unwrap(), u64::MAX);
+        assert_eq!(u64_text::from_value(Value::Integer(7)).unwrap(), 7);
+        assert!(u64_text::from_value(Value::Text("-1".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_u64_blob_round_trip_preserves_order() {
+        let small = u64_blob::to_value(&1);
+        let large = u64_blob::to_value(&u64::MAX);
+        match (&small, &large) {
+            (Value::Blob(a), Value::Blob(b)) => assert!(a < b),
+            _ => panic!("expected blobs"),
+        }
+        assert_eq!(u64_blob::from_value(large).unwrap(), u64::MAX);
+        assert!(u64_blob::from_value(Value::Blob(vec![1, 2, 3])).is_err());
+    }
+
+    #[test]
+    fn test_i128_text_round_trip() {
+        assert_eq!(i128_text::from_value(i128_text::to_value(&i128::MIN)).unwrap(), i128::MIN);
+        assert!(matches!(i128_text::from_value(Value::Null), Err(Error::UnexpectedNull)));
+    }
+
+    #[cfg(feature = "with-chrono")]
+    #[test]
+    fn test_rfc3339_round_trip() {
+        use chrono::DateTime;
+        use chrono::FixedOffset;
+        use chrono::Utc;
+
+        let stored = Value::Text("2024-01-31T12:00:00.250Z".to_string());
+        let parsed: DateTime<Utc> = rfc3339::from_value(stored.clone()).unwrap();
+        assert_eq!(rfc3339::to_value(&parsed), stored);
+
+        let stored = Value::Text("2024-01-31T12:00:00+02:00".to_string());
+        let parsed: DateTime<FixedOffset> = rfc3339::from_value(stored.clone()).unwrap();
+        assert_eq!(rfc3339::to_value(&parsed), stored);
+    }
+
+    #[cfg(feature = "with-chrono")]
+    #[test]
+    fn test_chrono_timestamps() {
+        use chrono::DateTime;
+        use chrono::Utc;
+
+        let dt = DateTime::<Utc>::from_timestamp_millis(1_706_702_400_250).unwrap();
+        assert_eq!(timestamp_millis::to_value(&dt), Value::Integer(1_706_702_400_250));
+        assert_eq!(timestamp_seconds::to_value(&dt), Value::Integer(1_706_702_400));
+
+        let parsed: DateTime<Utc> = timestamp_millis::from_value(Value::Integer(1_706_702_400_250)).unwrap();
+        assert_eq!(parsed, dt);
+        let parsed: DateTime<Utc> = timestamp_seconds::from_value(Value::Integer(1_706_702_400)).unwrap();
+        assert_eq!(parsed.timestamp(), 1_706_702_400);
+
+        assert_eq!(timestamp_seconds::to_value(&None::<DateTime<Utc>>), Value::Null);
+        let missing: Option<DateTime<Utc>> = timestamp_seconds::from_value(Value::Null).unwrap();
+        assert_eq!(missing, None);
+        assert!(matches!(timestamp_millis::from_value::<DateTime<Utc>>(Value::Null), Err(Error::UnexpectedNull)));
+        assert!(matches!(
+            timestamp_millis::from_value::<DateTime<Utc>>(Value::Integer(i64::MAX)),
+            Err(Error::OutOfRange { .. })
+        ));
+    }
+
+    #[cfg(feature = "with-time")]
+    #[test]
+    fn test_time_timestamps() {
+        use time::OffsetDateTime;
+        use time::PrimitiveDateTime;
+
+        let dt = OffsetDateTime::from_unix_timestamp(-1).unwrap();
+        assert_eq!(timestamp_millis::to_value(&dt), Value::Integer(-1000));
+        assert_eq!(timestamp_millis::from_value::<OffsetDateTime>(Value::Integer(-1000)).unwrap(), dt);
+
+        let primitive = PrimitiveDateTime::new(dt.date(), dt.time());
+        assert_eq!(timestamp_seconds::to_value(&primitive), Value::Integer(-1));
+        assert_eq!(timestamp_seconds::from_value::<PrimitiveDateTime>(Value::Integer(-1)).unwrap(), primitive);
+
+        // Like chrono, sub-millisecond instants before the epoch round down rather than towards zero.
+        let dt = OffsetDateTime::from_unix_timestamp_nanos(-1_500_000).unwrap();
+        assert_eq!(timestamp_millis::to_value(&dt), Value::Integer(-2));
+        assert_eq!(timestamp_seconds::to_value(&dt), Value::Integer(-1));
+    }
+
+    #[cfg(feature = "with-uuid")]
+    #[test]
+    fn test_uuid_blob_round_trip() {
+        use uuid::Uuid;
+
+        let id = Uuid::new_v4();
+        assert_eq!(uuid_blob::to_value(&id), Value::Blob(id.as_bytes().to_vec()));
+        assert_eq!(uuid_blob::from_value::<Uuid>(uuid_blob::to_value(&id)).unwrap(), id);
+        assert_eq!(uuid_blob::from_value::<Uuid>(Value::Text(id.to_string())).unwrap(), id);
+
+        assert_eq!(uuid_blob::to_value(&None::<Uuid>), Value::Null);
+        assert_eq!(uuid_blob::from_value::<Option<Uuid>>(Value::Null).unwrap(), None);
+    }
+}
@@ -1,22 +1,72 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::metrics::MetricsObserver;
+use super::opts::Cipher;
+use super::opts::DEFAULT_MAX_PARAMETERS;
+use super::opts::DatabaseEncryption;
+use super::opts::InsertStrategy;
+use super::replica::ReplicaSync;
+use super::retry::RetryPolicy;
+use super::shared_memory::SharedMemory;
+use super::statement_cache::DEFAULT_STATEMENT_CACHE_CAPACITY;
+use crate::encryption::Encryptor;
+use crate::type_registry::TypeRegistry;
+
 pub struct Builder {
-    pub(super) path:              String,
-    pub(super) enable_mvcc:       bool,
-    pub(super) enable_encryption: bool,
-    pub(super) vfs:               Option<String>,
-    pub(super) encryption_opts:   Option<turso::EncryptionOpts>,
+    pub(super) path:                     String,
+    pub(super) enable_mvcc:              bool,
+    pub(super) enable_encryption:        bool,
+    pub(super) vfs:                      Option<String>,
+    pub(super) encryption:               Option<DatabaseEncryption>,
+    pub(super) metrics_observer:         Option<Arc<dyn MetricsObserver>>,
+    pub(super) optimize_on_close:        bool,
+    pub(super) statement_cache_capacity: usize,
+    pub(super) insert_strategy:          InsertStrategy,
+    pub(super) require_filter:           bool,
+    pub(super) max_parameters:           usize,
+    pub(super) max_blob_size:            Option<usize>,
+    pub(super) retry_policy:             RetryPolicy,
+    pub(super) column_encryptor:         Option<Arc<dyn Encryptor>>,
+    pub(super) shared_memory:            Option<String>,
+    pub(super) pragmas:                  Vec<(String, String)>,
+    pub(super) application_name:         Option<String>,
+    pub(super) type_registry:            Arc<TypeRegistry>,
+    pub(super) replica_sync:             Option<Arc<dyn ReplicaSync>>,
 }
 
 impl Builder {
     pub fn new_local(path: &str) -> Self {
         Self {
-            path:              path.to_string(),
-            enable_mvcc:       false,
-            enable_encryption: false,
-            vfs:               None,
-            encryption_opts:   None,
+            path:                     path.to_string(),
+            enable_mvcc:              false,
+            enable_encryption:        false,
+            vfs:                      None,
+            encryption:               None,
+            metrics_observer:         None,
+            optimize_on_close:        true,
+            statement_cache_capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
+            insert_strategy:          InsertStrategy::default(),
+            require_filter:           false,
+            max_parameters:           DEFAULT_MAX_PARAMETERS,
+            max_blob_size:            None,
+            retry_policy:             RetryPolicy::default(),
+            column_encryptor:         None,
+            shared_memory:            None,
+            pragmas:                  Vec::new(),
+            application_name:         None,
+            type_registry:            Arc::new(TypeRegistry::new()),
+            replica_sync:             None,
         }
     }
 
+    /// An in-memory database shared by every builder using the same `name`, e.g. to hand several connections the
+    /// same fixture data in tests. It is discarded once the last `Database` and `Connection` built from it are
+    /// dropped; options other than those of the first builder to open it are ignored while it is alive.
+    pub fn new_memory_shared(name: &str) -> Self {
+        Self { shared_memory: Some(name.to_string()), ..Self::new_local(":memory:") }
+    }
+
     pub fn with_mvcc(mut self, mvcc: bool) -> Self {
         self.enable_mvcc = mvcc;
         self
@@ -27,8 +77,18 @@ impl Builder {
         self
     }
 
+    /// Like [`Builder::encryption`], with the cipher named and the key hex encoded as turso expects.
     pub fn with_encryption(mut self, opts: turso::EncryptionOpts) -> Self {
-        self.encryption_opts = Some(opts);
+        self.encryption = Some(DatabaseEncryption { cipher: opts.cipher, hexkey: opts.hexkey });
+        self.enable_encryption = true;
+        self
+    }
+
+    /// Encrypts the database file with turso's experimental page encryption. `key` must be [`Cipher::key_len`]
+    /// bytes; opening an existing database needs the key and cipher it was created with.
+    pub fn encryption(mut self, key: impl AsRef<[u8]>, cipher: Cipher) -> Self {
+        self.encryption = Some(DatabaseEncryption::new(key.as_ref(), cipher));
+        self.enable_encryption = true;
         self
     }
 
@@ -37,15 +97,139 @@ impl Builder {
         self
     }
 
-    pub async fn build(self) -> super::ConnectionResult<super::database::Database> {
-        let opts = super::opts::DatabaseOpts::from(&self);
+    pub fn with_metrics_observer(mut self, observer: Arc<dyn MetricsObserver>) -> Self {
+        self.metrics_observer = Some(observer);
+        self
+    }
+
+    pub fn optimize_on_close(mut self, optimize: bool) -> Self {
+        self.optimize_on_close = optimize;
+        self
+    }
+
+    pub fn insert_strategy(mut self, strategy: InsertStrategy) -> Self {
+        self.insert_strategy = strategy;
+        self
+    }
 
+    /// Makes updates and deletes without a filter fail with [`Error::UnfilteredMutation`](crate::Error::UnfilteredMutation) unless they
+    /// call `allow_full_table()`, so a forgotten `filter` can't rewrite or empty a whole table.
+    pub fn require_filter_on_mutation(mut self, require: bool) -> Self {
+        self.require_filter = require;
+        self
+    }
+
+    /// The most parameters one statement may bind, 32766 by default as in SQLite; lower it to match a server built
+    /// with a smaller `SQLITE_MAX_VARIABLE_NUMBER`. Selects filtered by a longer `is_in` list run in chunks within one
+    /// read transaction, with their ordering, limit and offset applied to the combined rows.
+    pub fn max_parameters(mut self, max: usize) -> Self {
+        self.max_parameters = max;
+        self
+    }
+
+    /// Makes writes of a text or blob value bigger than `bytes` fail with
+    /// [`Error::ValueTooLarge`](crate::Error::ValueTooLarge) before they run.
+    pub fn max_blob_size(mut self, bytes: usize) -> Self {
+        self.max_blob_size = Some(bytes);
+        self
+    }
+
+    /// Retries statements that fail because the database is busy or locked.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Number of prepared statements kept per connection; `0` disables the cache.
+    pub fn statement_cache_capacity(mut self, capacity: usize) -> Self {
+        self.statement_cache_capacity = capacity;
+        self
+    }
+
+    /// Encrypts `#[tursorm(encrypted)]` columns with `encryptor`; see [`crate::encryption`].
+    pub fn column_encryptor(mut self, encryptor: impl Encryptor + 'static) -> Self {
+        self.column_encryptor = Some(Arc::new(encryptor));
+        self
+    }
+
+    /// Sets `PRAGMA name = value` on every connection right after it opens, in the order given; setting the same
+    /// pragma again replaces its value. The values each connection reports back are available from
+    /// [`Connection::pragmas`](super::Connection::pragmas).
+    pub fn pragma(mut self, name: &str, value: impl std::fmt::Display) -> Self {
+        let value = value.to_string();
+        match self.pragmas.iter_mut().find(|(existing, _)| existing.eq_ignore_ascii_case(name)) {
+            Some((_, existing)) => *existing = value,
+            None => self.pragmas.push((name.to_string(), value)),
+        }
+        self
+    }
+
+    /// How long a statement waits on a locked database before failing as busy.
+    pub fn busy_timeout(self, timeout: Duration) -> Self {
+        self.pragma("busy_timeout", timeout.as_millis())
+    }
+
+    pub fn foreign_keys(self, enabled: bool) -> Self {
+        self.pragma("foreign_keys", if enabled { "ON" } else { "OFF" })
+    }
+
+    /// Prepends `/* name */` to every statement the query builders run, ahead of any per-query
+    /// [`Select::comment`](crate::Select::comment), so slow-query logs show which application sent them.
+    pub fn application_name(mut self, name: impl Into<String>) -> Self {
+        self.application_name = Some(name.into());
+        self
+    }
+
+    /// Conversions for types without [`IntoValue`](crate::IntoValue) and [`FromValue`](crate::FromValue)
+    /// implementations, shared by every connection; more can be registered later through
+    /// [`Connection::type_registry`](crate::Connection::type_registry).
+    pub fn type_registry(mut self, registry: TypeRegistry) -> Self {
+        self.type_registry = Arc::new(registry);
+        self
+    }
+
+    /// Opens the database as an embedded replica kept up to date by `sync`. Reads can then ask for fresher data with
+    /// [`Select::read_your_writes`](crate::Select::read_your_writes) and
+    /// [`Select::max_staleness`](crate::Select::max_staleness), and
+    /// [`Connection::sync_status`](crate::Connection::sync_status) reports when the replica last synced.
+    pub fn replica_sync(mut self, sync: impl ReplicaSync + 'static) -> Self {
+        self.replica_sync = Some(Arc::new(sync));
+        self
+    }
+
+    pub async fn build(mut self) -> super::ConnectionResult<super::database::Database> {
+        if let Some(encryption) = &self.encryption {
+            encryption.validate()?;
+        }
+        for (name, value) in &self.pragmas {
+            super::opts::validate_pragma(name, value)?;
+        }
+
+        let mut opts = super::opts::DatabaseOpts::from(&self);
+
+        let db = match self.shared_memory.take() {
+            Some(name) => {
+                let shared = SharedMemory::get_or_open(&name, self.open()).await?;
+                let db = shared.database();
+                opts.schema_epoch = shared.schema_epoch();
+                opts.shared_memory = Some(shared);
+                db
+            }
+            None => self.open().await?,
+        };
+
+        Ok(super::database::Database::new(db, opts))
+    }
+
+    async fn open(self) -> super::ConnectionResult<turso::Database> {
         let mut turso_builder = turso::Builder::new_local(&self.path);
         turso_builder = turso_builder.with_mvcc(self.enable_mvcc);
         turso_builder = turso_builder.experimental_encryption(self.enable_encryption);
 
-        turso_builder = match self.encryption_opts {
-            Some(opts) => turso_builder.with_encryption(opts),
+        // turso only uses these to read the schema of an existing database; every connection also sets them.
+        turso_builder = match self.encryption {
+            Some(encryption) => turso_builder
+                .with_encryption(turso::EncryptionOpts { cipher: encryption.cipher, hexkey: encryption.hexkey }),
             None => turso_builder,
         };
         turso_builder = match self.vfs {
@@ -53,8 +237,6 @@ impl Builder {
             None => turso_builder,
         };
 
-        let db = turso_builder.build().await?;
-
-        Ok(super::database::Database::new(db, opts))
+        turso_builder.build().await
     }
 }
@@ -0,0 +1,184 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use super::Connection;
+use crate::Condition;
+use crate::Result;
+use crate::Select;
+use crate::TableTrait;
+use crate::migration::INTERNAL_TABLE_PREFIX;
+
+/// Where turso's change data capture logs this connection's writes.
+const CHANGES_TABLE: &str = "_tursorm_changes";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A committed write to one row, as handed to [`Connection::on_change`] hooks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub table: String,
+    pub op:    ChangeOp,
+    pub rowid: i64,
+}
+
+#[async_trait::async_trait]
+trait ChangeListener: Send + Sync {
+    async fn notify(&self, conn: &Connection, change: &Change) -> Result<()>;
+}
+
+struct RowListener<F>(F);
+
+#[async_trait::async_trait]
+impl<F: Fn(&str, ChangeOp, i64) + Send + Sync> ChangeListener for RowListener<F> {
+    async fn notify(&self, _conn: &Connection, change: &Change) -> Result<()> {
+        (self.0)(&change.table, change.op, change.rowid);
+        Ok(())
+    }
+}
+
+struct RecordListener<Table, F> {
+    f:      F,
+    _table: PhantomData<fn() -> Table>,
+}
+
+#[async_trait::async_trait]
+impl<Table, F> ChangeListener for RecordListener<Table, F>
+where
+    Table: TableTrait,
+    Table::Record: Send,
+    F: Fn(ChangeOp, i64, Option<Table::Record>) + Send + Sync,
+{
+    async fn notify(&self, conn: &Connection, change: &Change) -> Result<()> {
+        if change.table != Table::table_name() {
+            return Ok(());
+        }
+
+        let record = match change.op {
+            ChangeOp::Delete => None,
+            ChangeOp::Insert | ChangeOp::Update => {
+                let by_rowid = Condition::raw("rowid = ?", vec![change.rowid.into()]);
+                Select::<Table>::new().filter(by_rowid).one(conn).await?
+            }
+        };
+        (self.f)(change.op, change.rowid, record);
+        Ok(())
+    }
+}
+
+/// The change hooks of a connection and its clones, which share one turso connection and so one change log.
+#[derive(Default)]
+pub(crate) struct ChangeHooks {
+    listeners: Mutex<Vec<Arc<dyn ChangeListener>>>,
+    capturing: AtomicBool,
+}
+
+impl ChangeHooks {
+    pub(crate) fn is_active(&self) -> bool {
+        self.capturing.load(Ordering::Acquire)
+    }
+}
+
+impl std::fmt::Debug for ChangeHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangeHooks").field("listeners", &self.listeners.lock().unwrap().len()).finish()
+    }
+}
+
+impl Connection {
+    /// Calls `f` with the table, operation and rowid of every row written through this connection and its clones,
+    /// once the write is committed, e.g. to invalidate caches or push updates to clients.
+    ///
+    /// turso has no update hook, so this turns on its change data capture for the connection, which logs each write
+    /// to `_tursorm_changes` in the same transaction. The log is read back after every write the query builders make
+    /// and after commits; [`Connection::poll_changes`] picks up writes made with raw statements.
+    pub async fn on_change(&self, f: impl Fn(&str, ChangeOp, i64) + Send + Sync + 'static) -> Result<()> {
+        self.add_change_listener(Arc::new(RowListener(f))).await
+    }
+
+    /// Like [`Connection::on_change`], for the rows of `Table`, refetched after inserts and updates. The record is
+    /// `None` after a delete, or if the row was deleted again before the hook ran.
+    pub async fn on_record_change<Table>(
+        &self,
+        f: impl Fn(ChangeOp, i64, Option<Table::Record>) + Send + Sync + 'static,
+    ) -> Result<()>
+    where
+        Table: TableTrait,
+        Table::Record: Send,
+    {
+        self.add_change_listener(Arc::new(RecordListener::<Table, _> { f, _table: PhantomData })).await
+    }
+
+    async fn add_change_listener(&self, listener: Arc<dyn ChangeListener>) -> Result<()> {
+        if !self.changes.is_active() {
+            let sql = format!("PRAGMA unstable_capture_data_changes_conn('id,{}')", CHANGES_TABLE);
+            self.inner.execute(&sql, ()).await?;
+            self.changes.capturing.store(true, Ordering::Release);
+        }
+
+        self.changes.listeners.lock().unwrap().push(listener);
+        Ok(())
+    }
+
+    /// Hands the committed changes logged since the last call to the [`Connection::on_change`] hooks and returns how
+    /// many there were. Changes of an open transaction wait for its commit.
+    pub async fn poll_changes(&self) -> Result<usize> {
+        if !self.changes.is_active() || !self.inner.is_autocommit()? {
+            return Ok(0);
+        }
+
+        let sql = format!("SELECT change_id, change_type, table_name, id FROM {} ORDER BY change_id", CHANGES_TABLE);
+        let mut rows = self.inner.query(&sql, ()).await?;
+        let mut last_id = None;
+        let mut changes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            last_id = Some(row.get::<i64>(0)?);
+
+            let table: String = row.get(2)?;
+            if table.starts_with("sqlite_") || table.starts_with(INTERNAL_TABLE_PREFIX) {
+                continue;
+            }
+            let op = match row.get::<i64>(1)? {
+                1 => ChangeOp::Insert,
+                -1 => ChangeOp::Delete,
+                _ => ChangeOp::Update,
+            };
+            changes.push(Change { table, op, rowid: row.get(3)? });
+        }
+        drop(rows);
+
+        let Some(last_id) = last_id else {
+            return Ok(0);
+        };
+        self.inner.execute(&format!("DELETE FROM {} WHERE change_id <= ?", CHANGES_TABLE), [last_id]).await?;
+
+        let listeners = self.changes.listeners.lock().unwrap().clone();
+        for change in &changes {
+            for listener in &listeners {
+                if let Err(e) = listener.notify(self, change).await {
+                    tracing::warn!("Change hook for {} row {} failed: {}", change.table, change.rowid, e);
+                }
+            }
+        }
+
+        Ok(changes.len())
+    }
+
+    /// Runs the change hooks after a write; the write itself already succeeded, so failures are only logged.
+    pub(crate) async fn dispatch_changes(&self) {
+        if !self.changes.is_active() {
+            return;
+        }
+
+        if let Err(e) = self.poll_changes().await {
+            tracing::warn!("Reading the change log failed: {}", e);
+        }
+    }
+}
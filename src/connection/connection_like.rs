@@ -0,0 +1,440 @@
+use std::sync::Arc;
+
+use super::Connection;
+use super::diagnostics::StatsRecorder;
+use super::dynamic_row::DynamicRow;
+use super::metrics::MetricsHook;
+use super::metrics::MetricsObserver;
+use super::metrics::QueryTimer;
+use super::metrics::StatementKind;
+use super::opts::DEFAULT_MAX_PARAMETERS;
+use super::opts::InsertStrategy;
+use super::replica::Freshness;
+use super::statement_cache::CachedStatement;
+use crate::Result;
+use crate::RowColumns;
+use crate::Value;
+use crate::encryption::Encryptor;
+use crate::query_cache::QueryCache;
+
+/// Anything the query builders can run statements against.
+///
+/// [`Connection`] is the real implementation; references and `Arc`s of an implementation work too. Implement it to
+/// route statements elsewhere, e.g. a transaction handle or a mock that records the SQL it receives.
+#[async_trait::async_trait]
+pub trait ConnectionLike: Send + Sync {
+    /// Runs a statement that returns rows.
+    async fn query_rows(&self, sql: &str, params: Vec<Value>) -> Result<QueryRows>;
+
+    /// Runs a query and collects its rows as [`DynamicRow`]s, for admin tooling and ad-hoc inspection where no
+    /// entity struct exists.
+    async fn query_dynamic(&self, sql: &str, params: Vec<Value>) -> Result<Vec<DynamicRow>> {
+        let mut rows = self.query_rows(sql, params).await?;
+        let columns = Arc::new(rows.columns());
+
+        let mut dynamic_rows = Vec::new();
+        while let Some(row) = rows.next().await? {
+            dynamic_rows.push(DynamicRow::new(columns.clone(), &row)?);
+        }
+
+        Ok(dynamic_rows)
+    }
+
+    /// Runs a read once the data it sees is as fresh as `freshness` asks, e.g. by syncing an embedded replica first.
+    ///
+    /// Executors without replicas keep the default, which reads right away.
+    async fn query_rows_fresh(&self, sql: &str, params: Vec<Value>, _freshness: Freshness) -> Result<QueryRows> {
+        self.query_rows(sql, params).await
+    }
+
+    /// Runs a statement and returns the number of rows it changed.
+    async fn execute_statement(&self, sql: &str, params: Vec<Value>) -> Result<u64>;
+
+    fn last_insert_rowid(&self) -> i64;
+
+    fn insert_strategy(&self) -> InsertStrategy {
+        InsertStrategy::default()
+    }
+
+    fn metrics_observer(&self) -> Option<Arc<dyn MetricsObserver>> {
+        None
+    }
+
+    /// Encrypts and decrypts `#[tursorm(encrypted)]` columns.
+    fn column_encryptor(&self) -> Option<Arc<dyn Encryptor>> {
+        None
+    }
+
+    /// Keeps the results of [`Select::cached`](crate::Select::cached) selects.
+    fn query_cache(&self) -> Option<Arc<QueryCache>> {
+        None
+    }
+
+    /// Whether updates and deletes must be filtered or call `allow_full_table()`; see
+    /// [`Builder::require_filter_on_mutation`](crate::Builder::require_filter_on_mutation).
+    fn requires_filter_on_mutation(&self) -> bool {
+        false
+    }
+
+    /// Whether the database runs in MVCC mode, where turso can't create indexes and inserts check unique columns
+    /// themselves.
+    fn is_mvcc_enabled(&self) -> bool {
+        false
+    }
+
+    /// The most parameters one statement may bind; see [`Builder::max_parameters`](crate::Builder::max_parameters).
+    fn max_parameters(&self) -> usize {
+        DEFAULT_MAX_PARAMETERS
+    }
+
+    /// The largest text or blob value a write may store; see
+    /// [`Builder::max_blob_size`](crate::Builder::max_blob_size).
+    fn max_blob_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Opens a write transaction unless one is already active; returns whether this call opened it.
+    ///
+    /// Executors without transactions keep the default, which never opens one.
+    async fn begin_if_autocommit(&self) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Like [`ConnectionLike::begin_if_autocommit`], for a transaction that only reads.
+    async fn begin_read_if_autocommit(&self) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn end_transaction(&self, _commit: bool) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Rows returned by [`ConnectionLike::query_rows`], either streamed from a statement or buffered in memory.
+pub struct QueryRows {
+    source: RowSource,
+}
+
+enum RowSource {
+    // `rows` is declared before the statement so it is dropped before the statement goes back to the cache.
+    Statement {
+        peeked:    Option<turso::Row>,
+        done:      bool,
+        rows:      turso::Rows,
+        statement: CachedStatement,
+        stats:     Arc<StatsRecorder>,
+    },
+    Buffered {
+        rows:    std::vec::IntoIter<turso::Row>,
+        columns: Vec<String>,
+    },
+}
+
+impl QueryRows {
+    pub(crate) fn from_statement(
+        statement: CachedStatement,
+        rows: turso::Rows,
+        peeked: Option<turso::Row>,
+        stats: Arc<StatsRecorder>,
+    ) -> Self {
+        let done = peeked.is_none();
+        Self { source: RowSource::Statement { peeked, done, rows, statement, stats } }
+    }
+
+    /// Rows that are already in memory, e.g. canned results returned by a mock executor.
+    pub fn from_values(rows: Vec<Vec<Value>>) -> Self {
+        Self::from_named_values(Vec::new(), rows)
+    }
+
+    /// Like [`QueryRows::from_values`], for rows whose columns are named `columns`.
+    pub fn from_named_values(columns: Vec<String>, rows: Vec<Vec<Value>>) -> Self {
+        let rows = rows
+            .into_iter()
+            .map(|values| {
+                let values: Vec<turso_core::Value> = values.into_iter().map(Into::into).collect();
+                values.iter().collect()
+            })
+            .collect::<Vec<turso::Row>>();
+
+        Self::from_rows(columns, rows)
+    }
+
+    pub(crate) fn from_rows(columns: Vec<String>, rows: Vec<turso::Row>) -> Self {
+        Self { source: RowSource::Buffered { rows: rows.into_iter(), columns } }
+    }
+
+    /// The names of the result's columns, for decoding rows by name.
+    pub fn columns(&self) -> RowColumns {
+        match &self.source {
+            RowSource::Statement { statement, .. } => {
+                RowColumns::new(statement.columns().iter().map(|column| column.name().to_string()).collect())
+            }
+            RowSource::Buffered { columns, .. } => RowColumns::new(columns.clone()),
+        }
+    }
+
+    pub async fn next(&mut self) -> Result<Option<turso::Row>> {
+        match &mut self.source {
+            RowSource::Statement { peeked, done, rows, stats, .. } => {
+                if let Some(row) = peeked.take() {
+                    stats.record_row();
+                    return Ok(Some(row));
+                }
+                // Stepping a finished statement would run it again.
+                if *done {
+                    return Ok(None);
+                }
+
+                let row = rows.next().await.inspect_err(|e| stats.record_error(e))?;
+                *done = row.is_none();
+                if row.is_some() {
+                    stats.record_row();
+                }
+                Ok(row)
+            }
+            RowSource::Buffered { rows, .. } => Ok(rows.next()),
+        }
+    }
+}
+
+impl std::fmt::Debug for QueryRows {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self.source {
+            RowSource::Statement { .. } => "Statement",
+            RowSource::Buffered { .. } => "Buffered",
+        };
+        f.debug_struct("QueryRows").field("source", &source).finish()
+    }
+}
+
+pub(crate) trait ConnectionLikeExt: ConnectionLike {
+    fn start_query(&self, table_name: &'static str, kind: StatementKind, sql: &str) -> QueryTimer {
+        MetricsHook::new(self.metrics_observer()).start(table_name, kind, sql).invalidating(self.query_cache())
+    }
+
+    /// Runs `work` in a write transaction unless one is already open, committing it when `work` succeeds and rolling
+    /// it back otherwise. A failed rollback is only logged, so the error returned is the one `work` failed with.
+    fn in_transaction<'a, T: Send + 'a>(
+        &'a self,
+        work: impl Future<Output = Result<T>> + Send + 'a,
+    ) -> impl Future<Output = Result<T>> + Send + 'a {
+        async move {
+            let owns_transaction = self.begin_if_autocommit().await?;
+            self.finish_transaction(owns_transaction, work.await).await
+        }
+    }
+
+    /// Like [`ConnectionLikeExt::in_transaction`], for work that only reads.
+    fn in_read_transaction<'a, T: Send + 'a>(
+        &'a self,
+        work: impl Future<Output = Result<T>> + Send + 'a,
+    ) -> impl Future<Output = Result<T>> + Send + 'a {
+        async move {
+            let owns_transaction = self.begin_read_if_autocommit().await?;
+            self.finish_transaction(owns_transaction, work.await).await
+        }
+    }
+
+    /// Commits or rolls back the transaction `result` was produced in, if this call's caller opened it.
+    fn finish_transaction<'a, T: Send + 'a>(
+        &'a self,
+        owns_transaction: bool,
+        result: Result<T>,
+    ) -> impl Future<Output = Result<T>> + Send + 'a {
+        async move {
+            if !owns_transaction {
+                return result;
+            }
+
+            let commit = result.is_ok();
+            match self.end_transaction(commit).await {
+                Err(e) if commit => Err(e),
+                Err(e) => {
+                    tracing::warn!("Rolling back transaction failed: {}", e);
+                    result
+                }
+                Ok(()) => result,
+            }
+        }
+    }
+}
+
+impl<C: ConnectionLike + ?Sized> ConnectionLikeExt for C {}
+
+#[async_trait::async_trait]
+impl ConnectionLike for Connection {
+    async fn query_rows(&self, sql: &str, params: Vec<Value>) -> Result<QueryRows> {
+        self.query(sql, params).await
+    }
+
+    async fn query_rows_fresh(&self, sql: &str, params: Vec<Value>, freshness: Freshness) -> Result<QueryRows> {
+        self.ensure_fresh(freshness).await?;
+        self.query_rows(sql, params).await
+    }
+
+    async fn execute_statement(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+        let affected = self.execute_cached(&self.with_application_name(sql), params).await?;
+        self.record_write();
+        self.dispatch_changes().await;
+        Ok(affected)
+    }
+
+    fn last_insert_rowid(&self) -> i64 {
+        Connection::last_insert_rowid(self)
+    }
+
+    fn insert_strategy(&self) -> InsertStrategy {
+        Connection::insert_strategy(self)
+    }
+
+    fn metrics_observer(&self) -> Option<Arc<dyn MetricsObserver>> {
+        self.opts.metrics.observer()
+    }
+
+    fn column_encryptor(&self) -> Option<Arc<dyn Encryptor>> {
+        self.opts.column_encryptor.clone()
+    }
+
+    fn query_cache(&self) -> Option<Arc<QueryCache>> {
+        self.query_cache.clone()
+    }
+
+    fn requires_filter_on_mutation(&self) -> bool {
+        Connection::requires_filter_on_mutation(self)
+    }
+
+    fn is_mvcc_enabled(&self) -> bool {
+        self.opts.enable_mvcc
+    }
+
+    fn max_parameters(&self) -> usize {
+        Connection::max_parameters(self)
+    }
+
+    fn max_blob_size(&self) -> Option<usize> {
+        Connection::max_blob_size(self)
+    }
+
+    async fn begin_if_autocommit(&self) -> Result<bool> {
+        Ok(Connection::begin_if_autocommit(self).await?)
+    }
+
+    async fn begin_read_if_autocommit(&self) -> Result<bool> {
+        Ok(Connection::begin_read_if_autocommit(self).await?)
+    }
+
+    async fn end_transaction(&self, commit: bool) -> Result<()> {
+        Connection::end_transaction(self, commit).await?;
+        if commit {
+            self.dispatch_changes().await;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! forward_connection_like {
+    ($($ty:ty),+) => {$(
+        #[async_trait::async_trait]
+        impl<C: ConnectionLike + ?Sized> ConnectionLike for $ty {
+            async fn query_rows(&self, sql: &str, params: Vec<Value>) -> Result<QueryRows> {
+                (**self).query_rows(sql, params).await
+            }
+
+            async fn query_rows_fresh(&self, sql: &str, params: Vec<Value>, freshness: Freshness) -> Result<QueryRows> {
+                (**self).query_rows_fresh(sql, params, freshness).await
+            }
+
+            async fn execute_statement(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+                (**self).execute_statement(sql, params).await
+            }
+
+            fn last_insert_rowid(&self) -> i64 {
+                (**self).last_insert_rowid()
+            }
+
+            fn insert_strategy(&self) -> InsertStrategy {
+                (**self).insert_strategy()
+            }
+
+            fn metrics_observer(&self) -> Option<Arc<dyn MetricsObserver>> {
+                (**self).metrics_observer()
+            }
+
+            fn column_encryptor(&self) -> Option<Arc<dyn Encryptor>> {
+                (**self).column_encryptor()
+            }
+
+            fn query_cache(&self) -> Option<Arc<QueryCache>> {
+                (**self).query_cache()
+            }
+
+            fn requires_filter_on_mutation(&self) -> bool {
+                (**self).requires_filter_on_mutation()
+            }
+
+            fn is_mvcc_enabled(&self) -> bool {
+                (**self).is_mvcc_enabled()
+            }
+
+            fn max_parameters(&self) -> usize {
+                (**self).max_parameters()
+            }
+
+            fn max_blob_size(&self) -> Option<usize> {
+                (**self).max_blob_size()
+            }
+
+            async fn begin_if_autocommit(&self) -> Result<bool> {
+                (**self).begin_if_autocommit().await
+            }
+
+            async fn begin_read_if_autocommit(&self) -> Result<bool> {
+                (**self).begin_read_if_autocommit().await
+            }
+
+            async fn end_transaction(&self, commit: bool) -> Result<()> {
+                (**self).end_transaction(commit).await
+            }
+        }
+    )+};
+}
+
+forward_connection_like!(&C, Arc<C>, Box<C>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Builder;
+
+    #[tokio::test]
+    async fn test_query_rows_from_values() {
+        let mut rows = QueryRows::from_values(vec![
+            vec![Value::Integer(1), Value::Text("a".to_string())],
+            vec![Value::Null, Value::Blob(vec![1, 2])],
+        ]);
+
+        let first = rows.next().await.unwrap().unwrap();
+        assert_eq!(first.get_value(1).unwrap(), Value::Text("a".to_string()));
+        let second = rows.next().await.unwrap().unwrap();
+        assert_eq!(second.get_value(0).unwrap(), Value::Null);
+        assert!(rows.next().await.unwrap().is_none());
+    }
+
+    async fn count_rows(conn: &impl ConnectionLike) -> i64 {
+        let mut rows = conn.query_rows("SELECT COUNT(*) FROM t", Vec::new()).await.unwrap();
+        rows.next().await.unwrap().unwrap().get::<i64>(0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_connection_like_forwards_through_wrappers() {
+        let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY)", ()).await.unwrap();
+        assert_eq!(conn.execute_statement("INSERT INTO t (id) VALUES (?)", vec![Value::Integer(7)]).await.unwrap(), 1);
+
+        assert_eq!(count_rows(&conn).await, 1);
+        assert_eq!(count_rows(&&conn).await, 1);
+        let shared: Arc<dyn ConnectionLike> = Arc::new(conn.clone());
+        assert_eq!(count_rows(&shared).await, 1);
+        assert_eq!(ConnectionLike::last_insert_rowid(&conn), 7);
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Database {
     db:   turso::Database,
     opts: super::opts::DatabaseOpts,
@@ -9,8 +9,26 @@ impl Database {
         Self { db, opts }
     }
 
-    pub fn connect(self) -> super::ConnectionResult<super::Connection> {
+    pub fn connect(mut self) -> super::ConnectionResult<super::Connection> {
         let conn = self.db.connect()?;
+        if let Some(encryption) = &self.opts.encryption {
+            encryption.apply(&conn)?;
+        }
+        self.opts.pragmas = super::opts::apply_pragmas(&conn, &self.opts.pragmas)?;
+
         Ok(super::Connection::new(conn, self.opts))
     }
+
+    /// Runs `PRAGMA integrity_check` on a fresh connection.
+    pub async fn integrity_check(&self) -> crate::Result<super::diagnostics::IntegrityReport> {
+        self.clone().connect()?.integrity_check().await
+    }
+
+    /// Runs the configured maintenance, flushes caches, and checkpoints and truncates the WAL.
+    pub async fn close(self) -> super::ConnectionResult<()> {
+        let conn = self.connect()?;
+        conn.run_close_maintenance().await;
+        conn.cacheflush()?;
+        conn.execute_pragma("PRAGMA wal_checkpoint(TRUNCATE)").await
+    }
 }
@@ -0,0 +1,110 @@
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+/// Activity of a connection and its clones since it was opened.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// Statements run, including failed ones.
+    pub statements:    u64,
+    /// Rows read from queries.
+    pub rows_read:     u64,
+    /// Rows changed by writes.
+    pub rows_affected: u64,
+    /// Statements prepared by the statement cache on a miss or by [`Connection::prepare`](super::Connection::prepare).
+    pub prepared:      u64,
+    pub errors:        u64,
+    pub last_error:    Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct StatsRecorder {
+    statements:    AtomicU64,
+    rows_read:     AtomicU64,
+    rows_affected: AtomicU64,
+    prepared:      AtomicU64,
+    errors:        AtomicU64,
+    last_error:    Mutex<Option<String>>,
+}
+
+impl StatsRecorder {
+    /// Counts a statement; `affected` is the number of rows a successful write changed.
+    pub(crate) fn record<T, E: std::fmt::Display>(&self, result: &Result<T, E>, affected: impl FnOnce(&T) -> u64) {
+        self.statements.fetch_add(1, Ordering::Relaxed);
+        match result {
+            Ok(value) => {
+                self.rows_affected.fetch_add(affected(value), Ordering::Relaxed);
+            }
+            Err(e) => self.record_error(e),
+        }
+    }
+
+    pub(crate) fn record_row(&self) {
+        self.rows_read.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_prepare(&self) {
+        self.prepared.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_error(&self, error: &impl std::fmt::Display) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(error.to_string());
+    }
+
+    pub(crate) fn snapshot(&self) -> ConnectionStats {
+        ConnectionStats {
+            statements:    self.statements.load(Ordering::Relaxed),
+            rows_read:     self.rows_read.load(Ordering::Relaxed),
+            rows_affected: self.rows_affected.load(Ordering::Relaxed),
+            prepared:      self.prepared.load(Ordering::Relaxed),
+            errors:        self.errors.load(Ordering::Relaxed),
+            last_error:    self.last_error.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        }
+    }
+}
+
+/// Result of `PRAGMA integrity_check`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// One entry per problem found; empty when the database is intact.
+    pub problems: Vec<String>,
+}
+
+impl IntegrityReport {
+    /// Parses the rows of `PRAGMA integrity_check`, which is a single `ok` when nothing is wrong.
+    pub(crate) fn from_messages(messages: Vec<String>) -> Self {
+        let problems = messages.into_iter().filter(|message| !message.eq_ignore_ascii_case("ok")).collect();
+        Self { problems }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_recorder() {
+        let recorder = StatsRecorder::default();
+        recorder.record(&Ok::<u64, String>(3), |affected| *affected);
+        recorder.record(&Err::<u64, _>("database is locked"), |affected| *affected);
+        recorder.record_row();
+
+        let stats = recorder.snapshot();
+        assert_eq!((stats.statements, stats.rows_read, stats.rows_affected, stats.errors), (2, 1, 3, 1));
+        assert_eq!(stats.last_error.as_deref(), Some("database is locked"));
+    }
+
+    #[test]
+    fn test_integrity_report_from_messages() {
+        assert!(IntegrityReport::from_messages(vec!["ok".to_string()]).is_ok());
+
+        let report = IntegrityReport::from_messages(vec!["Page 3 is never used".to_string()]);
+        assert!(!report.is_ok());
+        assert_eq!(report.problems, vec!["Page 3 is never used"]);
+    }
+}
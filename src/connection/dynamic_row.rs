@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::Error;
+use crate::FromValue;
+use crate::Result;
+use crate::RowColumns;
+use crate::Value;
+
+/// A row of an ad-hoc query, read by column name or position without an entity or `FromRow` struct, from
+/// [`ConnectionLike::query_dynamic`](crate::ConnectionLike::query_dynamic).
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicRow {
+    columns: Arc<RowColumns>,
+    values:  Vec<Value>,
+}
+
+impl DynamicRow {
+    pub(crate) fn new(columns: Arc<RowColumns>, row: &turso::Row) -> Result<Self> {
+        let values = (0..row.column_count()).map(|idx| row.get_value(idx)).collect::<turso::Result<_>>()?;
+        Ok(Self { columns, values })
+    }
+
+    /// The value of the column called `name`, ignoring ASCII case; fails with [`Error::ColumnNotFound`] if the
+    /// result has no such column.
+    pub fn get<T: FromValue>(&self, name: &str) -> Result<T> {
+        self.get_index(self.columns.index(name)?)
+    }
+
+    pub fn get_index<T: FromValue>(&self, idx: usize) -> Result<T> {
+        let value = self.values.get(idx).ok_or_else(|| Error::ColumnNotFound(idx.to_string()))?;
+        T::from_value(value.clone())
+    }
+
+    pub fn value(&self, name: &str) -> Option<&Value> {
+        self.columns.index(name).ok().and_then(|idx| self.values.get(idx))
+    }
+
+    pub fn columns(&self) -> &[String] {
+        self.columns.names()
+    }
+
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// `(column name, value)` pairs in result order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.columns.names().iter().map(String::as_str).zip(&self.values)
+    }
+
+    /// The row keyed by column name; of duplicate names, the last column wins.
+    pub fn into_map(self) -> HashMap<String, Value> {
+        self.columns.names().iter().cloned().zip(self.values).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Builder;
+    use crate::ConnectionLike;
+
+    #[tokio::test]
+    async fn test_query_dynamic() {
+        let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT, email TEXT)", ()).await.unwrap();
+        conn.execute("INSERT INTO t (name, email) VALUES ('alice', NULL), ('bob', 'b@example.com')", ()).await.unwrap();
+
+        let rows = conn.query_dynamic("SELECT id, name, email FROM t ORDER BY id", Vec::new()).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].columns(), ["id", "name", "email"]);
+        assert_eq!(rows[0].get::<String>("NAME").unwrap(), "alice");
+        assert_eq!(rows[0].get::<Option<String>>("email").unwrap(), None);
+        assert_eq!(rows[1].get_index::<i64>(0).unwrap(), 2);
+        assert_eq!(rows[1].value("email"), Some(&Value::Text("b@example.com".to_string())));
+        assert!(matches!(rows[1].get::<i64>("missing"), Err(Error::ColumnNotFound(_))));
+        assert!(matches!(rows[1].get_index::<i64>(3), Err(Error::ColumnNotFound(_))));
+
+        let pairs: Vec<(&str, &Value)> = rows[1].iter().collect();
+        assert_eq!(pairs[1], ("name", &Value::Text("bob".to_string())));
+        let map = rows[1].clone().into_map();
+        assert_eq!(map["id"], Value::Integer(2));
+    }
+}
@@ -0,0 +1,199 @@
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::query_cache::QueryCache;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatementKind {
+    Select,
+    Insert,
+    Update,
+    Delete,
+}
+
+impl std::fmt::Display for StatementKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatementKind::Select => write!(f, "SELECT"),
+            StatementKind::Insert => write!(f, "INSERT"),
+            StatementKind::Update => write!(f, "UPDATE"),
+            StatementKind::Delete => write!(f, "DELETE"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct QueryEvent<'a> {
+    pub table_name: &'a str,
+    pub kind:       StatementKind,
+    pub sql:        &'a str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueryOutcome {
+    pub duration:  Duration,
+    pub rows:      u64,
+    pub succeeded: bool,
+}
+
+/// Receives a callback around every statement executed by the query builders.
+///
+/// `rows` is the number of rows returned for selects and the number of rows affected otherwise.
+pub trait MetricsObserver: Send + Sync {
+    fn on_query_start(&self, _event: &QueryEvent<'_>) {}
+
+    fn on_query_end(&self, _event: &QueryEvent<'_>, _outcome: &QueryOutcome) {}
+
+    fn on_statement_cache_lookup(&self, _sql: &str, _hit: bool) {}
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct MetricsHook(Option<Arc<dyn MetricsObserver>>);
+
+impl MetricsHook {
+    pub(crate) fn new(observer: Option<Arc<dyn MetricsObserver>>) -> Self {
+        Self(observer)
+    }
+
+    pub(crate) fn start(&self, table_name: &'static str, kind: StatementKind, sql: &str) -> QueryTimer {
+        let observer = self.0.clone();
+        let sql = if observer.is_some() { sql.to_string() } else { String::new() };
+
+        if let Some(observer) = &observer {
+            observer.on_query_start(&QueryEvent { table_name, kind, sql: &sql });
+        }
+
+        QueryTimer { observer, table_name, kind, sql, started_at: Instant::now(), cache: None }
+    }
+
+    pub(crate) fn observer(&self) -> Option<Arc<dyn MetricsObserver>> {
+        self.0.clone()
+    }
+
+    pub(crate) fn statement_cache_lookup(&self, sql: &str, hit: bool) {
+        if let Some(observer) = &self.0 {
+            observer.on_statement_cache_lookup(sql, hit);
+        }
+    }
+}
+
+impl std::fmt::Debug for MetricsHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MetricsHook").field(&self.0.as_ref().map(|_| "MetricsObserver")).finish()
+    }
+}
+
+pub(crate) struct QueryTimer {
+    observer:   Option<Arc<dyn MetricsObserver>>,
+    table_name: &'static str,
+    kind:       StatementKind,
+    sql:        String,
+    started_at: Instant,
+    /// Invalidated for the table once the statement is done, if it writes.
+    cache:      Option<Arc<QueryCache>>,
+}
+
+impl QueryTimer {
+    pub(crate) fn invalidating(mut self, cache: Option<Arc<QueryCache>>) -> Self {
+        self.cache = cache.filter(|_| self.kind != StatementKind::Select);
+        self
+    }
+
+    /// Reports the end of the statement; `rows` is `None` when the statement failed.
+    pub(crate) fn finish(self, rows: Option<u64>) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(self.table_name);
+        }
+        if let Some(observer) = &self.observer {
+            let event = QueryEvent { table_name: self.table_name, kind: self.kind, sql: &self.sql };
+            let outcome = QueryOutcome {
+                duration:  self.started_at.elapsed(),
+                rows:      rows.unwrap_or(0),
+                succeeded: rows.is_some(),
+            };
+            observer.on_query_end(&event, &outcome);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl MetricsObserver for RecordingObserver {
+        fn on_query_start(&self, event: &QueryEvent<'_>) {
+            self.events.lock().unwrap().push(format!("start {} {} {}", event.kind, event.table_name, event.sql));
+        }
+
+        fn on_query_end(&self, event: &QueryEvent<'_>, outcome: &QueryOutcome) {
+            self.events.lock().unwrap().push(format!(
+                "end {} {} rows={} ok={}",
+                event.kind, event.table_name, outcome.rows, outcome.succeeded
+            ));
+        }
+
+        fn on_statement_cache_lookup(&self, sql: &str, hit: bool) {
+            self.events.lock().unwrap().push(format!("cache {} hit={}", sql, hit));
+        }
+    }
+
+    #[test]
+    fn test_statement_kind_display() {
+        assert_eq!(StatementKind::Select.to_string(), "SELECT");
+        assert_eq!(StatementKind::Insert.to_string(), "INSERT");
+        assert_eq!(StatementKind::Update.to_string(), "UPDATE");
+        assert_eq!(StatementKind::Delete.to_string(), "DELETE");
+    }
+
+    #[test]
+    fn test_metrics_hook_reports_start_and_end() {
+        let observer = Arc::new(RecordingObserver::default());
+        let hook = MetricsHook::new(Some(observer.clone()));
+
+        hook.start("users", StatementKind::Select, "SELECT id FROM users").finish(Some(3));
+
+        let events = observer.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], "start SELECT users SELECT id FROM users");
+        assert_eq!(events[1], "end SELECT users rows=3 ok=true");
+    }
+
+    #[test]
+    fn test_metrics_hook_reports_failure() {
+        let observer = Arc::new(RecordingObserver::default());
+        let hook = MetricsHook::new(Some(observer.clone()));
+
+        hook.start("users", StatementKind::Delete, "DELETE FROM users").finish(None);
+
+        let events = observer.events.lock().unwrap();
+        assert_eq!(events[1], "end DELETE users rows=0 ok=false");
+    }
+
+    #[test]
+    fn test_metrics_hook_reports_statement_cache_lookup() {
+        let observer = Arc::new(RecordingObserver::default());
+        let hook = MetricsHook::new(Some(observer.clone()));
+
+        hook.statement_cache_lookup("SELECT 1", false);
+        hook.statement_cache_lookup("SELECT 1", true);
+
+        let events = observer.events.lock().unwrap();
+        assert_eq!(*events, vec!["cache SELECT 1 hit=false".to_string(), "cache SELECT 1 hit=true".to_string()]);
+    }
+
+    #[test]
+    fn test_metrics_hook_without_observer() {
+        let hook = MetricsHook::default();
+        hook.start("users", StatementKind::Insert, "INSERT INTO users DEFAULT VALUES").finish(Some(1));
+
+        assert!(format!("{:?}", hook).contains("None"));
+    }
+}
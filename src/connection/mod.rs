@@ -1,23 +1,64 @@
 pub(crate) mod builder;
+pub(crate) mod changes;
+pub(crate) mod connection_like;
 pub(crate) mod database;
+pub(crate) mod diagnostics;
+pub(crate) mod dynamic_row;
+pub(crate) mod metrics;
 pub(crate) mod opts;
+pub(crate) mod replica;
+pub(crate) mod retry;
+pub(crate) mod routing;
+pub(crate) mod shared_memory;
+pub(crate) mod statement_cache;
+pub(crate) mod write_queue;
+
+use std::sync::Arc;
 
 pub mod prelude {
     pub use super::Connection;
     pub use super::builder::Builder;
+    pub use super::changes::Change;
+    pub use super::changes::ChangeOp;
+    pub use super::connection_like::ConnectionLike;
+    pub use super::connection_like::QueryRows;
+    pub use super::diagnostics::ConnectionStats;
+    pub use super::diagnostics::IntegrityReport;
+    pub use super::dynamic_row::DynamicRow;
+    pub use super::metrics::MetricsObserver;
+    pub use super::metrics::QueryEvent;
+    pub use super::metrics::QueryOutcome;
+    pub use super::metrics::StatementKind;
+    pub use super::opts::Cipher;
+    pub use super::opts::InsertStrategy;
+    pub use super::replica::Freshness;
+    pub use super::replica::ReplicaSync;
+    pub use super::replica::SyncStatus;
+    pub use super::retry::RetryPolicy;
+    pub use super::routing::RoutingConnection;
+    pub use super::statement_cache::StatementCacheStats;
+    pub use super::write_queue::ConflictResolution;
+    pub use super::write_queue::QueuedWrite;
+    pub use super::write_queue::ReplayReport;
+    pub use super::write_queue::WriteQueue;
 }
 
-pub(self) type ConnectionResult<T> = std::result::Result<T, turso::Error>;
+type ConnectionResult<T> = std::result::Result<T, turso::Error>;
 
 #[derive(Debug, Clone)]
 pub struct Connection {
-    inner: turso::Connection,
-    opts:  opts::DatabaseOpts,
+    inner:       turso::Connection,
+    opts:        opts::DatabaseOpts,
+    statements:  Arc<statement_cache::StatementCache>,
+    stats:       Arc<diagnostics::StatsRecorder>,
+    changes:     Arc<changes::ChangeHooks>,
+    query_cache: Option<Arc<crate::query_cache::QueryCache>>,
 }
 
 impl Connection {
     fn new(inner: turso::Connection, opts: opts::DatabaseOpts) -> Self {
-        Self { inner, opts }
+        let statements = Arc::new(statement_cache::StatementCache::new(opts.statement_cache_capacity));
+        Self { inner, opts, statements, stats: Arc::default(), changes: Arc::default(), query_cache: None }
     }
 
     // TODO: Investigate failures when using transactions
@@ -41,19 +82,267 @@ impl Connection {
         self.opts.path.as_str()
     }
 
-    pub async fn query(&self, sql: &str, params: impl turso::IntoParams) -> turso::Result<turso::Rows> {
-        self.inner.query(sql, params).await
+    pub fn insert_strategy(&self) -> opts::InsertStrategy {
+        self.opts.insert_strategy
+    }
+
+    pub fn requires_filter_on_mutation(&self) -> bool {
+        self.opts.require_filter
+    }
+
+    pub fn max_parameters(&self) -> usize {
+        self.opts.max_parameters
+    }
+
+    pub fn max_blob_size(&self) -> Option<usize> {
+        self.opts.max_blob_size
+    }
+
+    pub fn retry_policy(&self) -> retry::RetryPolicy {
+        self.opts.retry_policy
+    }
+
+    pub fn set_metrics_observer(&mut self, observer: Arc<dyn metrics::MetricsObserver>) {
+        self.opts.metrics = metrics::MetricsHook::new(Some(observer));
+    }
+
+    pub fn with_metrics_observer(mut self, observer: Arc<dyn metrics::MetricsObserver>) -> Self {
+        self.set_metrics_observer(observer);
+        self
+    }
+
+    /// Keeps the results of [`Select::cached`](crate::Select::cached) selects run through this connection and its
+    /// clones in `store`; see [`query_cache`](crate::query_cache).
+    pub fn set_query_cache(&mut self, store: impl crate::query_cache::CacheStore + 'static) {
+        self.query_cache = Some(Arc::new(crate::query_cache::QueryCache::new(store)));
+    }
+
+    pub fn with_query_cache(mut self, store: impl crate::query_cache::CacheStore + 'static) -> Self {
+        self.set_query_cache(store);
+        self
+    }
+
+    pub fn statement_cache_stats(&self) -> statement_cache::StatementCacheStats {
+        self.statements.stats()
+    }
+
+    /// Statements, rows and errors seen by this connection and its clones.
+    pub fn stats(&self) -> diagnostics::ConnectionStats {
+        self.stats.snapshot()
+    }
+
+    /// Runs `SELECT 1`, e.g. for a readiness probe.
+    pub async fn ping(&self) -> crate::Result<()> {
+        let mut rows = self.query("SELECT 1", ()).await?;
+        rows.next().await?;
+        Ok(())
+    }
+
+    /// Runs `PRAGMA integrity_check`.
+    pub async fn integrity_check(&self) -> crate::Result<diagnostics::IntegrityReport> {
+        let mut rows = self.query("PRAGMA integrity_check", ()).await?;
+        let mut messages = Vec::new();
+        while let Some(row) = rows.next().await? {
+            messages.push(row.get::<String>(0)?);
+        }
+
+        Ok(diagnostics::IntegrityReport::from_messages(messages))
+    }
+
+    /// When the embedded replica last synced and whether writes happened since; `None` unless the database was
+    /// built with [`Builder::replica_sync`](builder::Builder::replica_sync).
+    pub fn sync_status(&self) -> Option<replica::SyncStatus> {
+        self.opts.replica.as_ref().map(|replica| replica.status())
+    }
+
+    /// Syncs the embedded replica now.
+    pub async fn sync(&self) -> crate::Result<()> {
+        match &self.opts.replica {
+            Some(replica) => replica.sync().await,
+            None => Err(crate::Error::Query("The database is not an embedded replica".to_string())),
+        }
+    }
+
+    /// Syncs the embedded replica first if it is less fresh than `freshness` allows; a no-op on other databases.
+    pub async fn ensure_fresh(&self, freshness: replica::Freshness) -> crate::Result<()> {
+        match &self.opts.replica {
+            Some(replica) => replica.ensure_fresh(freshness).await,
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn record_write(&self) {
+        if let Some(replica) = &self.opts.replica {
+            replica.record_write();
+        }
+    }
+
+    pub fn clear_statement_cache(&self) {
+        self.statements.clear();
+    }
+
+    pub fn application_name(&self) -> Option<&str> {
+        self.opts.application_name.as_deref()
     }
 
+    /// Runtime conversions for raw query parameters and results, shared with the other connections of the database.
+    pub fn type_registry(&self) -> &crate::TypeRegistry {
+        &self.opts.type_registry
+    }
+
+    pub(crate) fn with_application_name<'a>(&self, sql: &'a str) -> std::borrow::Cow<'a, str> {
+        match self.application_name() {
+            Some(name) => crate::query::comment::with_comment(Some(name), sql.to_string()).into(),
+            None => sql.into(),
+        }
+    }
+
+    pub(crate) async fn prepare_cached(&self, sql: &str) -> ConnectionResult<statement_cache::CachedStatement> {
+        let epoch = self.opts.schema_epoch.current();
+        let (statement, changes) = match self.statements.take(sql, epoch) {
+            Some(cached) => {
+                self.opts.metrics.statement_cache_lookup(sql, true);
+                cached
+            }
+            None => {
+                if self.opts.statement_cache_capacity > 0 {
+                    self.opts.metrics.statement_cache_lookup(sql, false);
+                }
+                self.stats.record_prepare();
+                (self.inner.prepare(sql).await?, 0)
+            }
+        };
+
+        Ok(statement_cache::CachedStatement::new(self.statements.clone(), sql.to_string(), statement, changes, epoch))
+    }
+
+    /// Runs `run`, advancing the schema epoch before and after it when `sql` changes the schema, so no connection of
+    /// the database reuses a statement cached against the old schema.
+    pub(crate) async fn tracking_schema<T>(&self, sql: &str, run: impl Future<Output = T>) -> T {
+        if !statement_cache::changes_schema(sql) {
+            return run.await;
+        }
+
+        self.opts.schema_epoch.advance();
+        let result = run.await;
+        self.opts.schema_epoch.advance();
+        result
+    }
+
+    pub(crate) async fn execute_cached(&self, sql: &str, params: impl turso::IntoParams) -> ConnectionResult<u64> {
+        let params = params.into_params()?;
+        let run =
+            self.opts.retry_policy.run(|| async { self.prepare_cached(sql).await?.execute(params.clone()).await });
+        let result = self.tracking_schema(sql, run).await;
+        self.stats.record(&result, |affected| *affected);
+        result
+    }
+
+    /// Opens a write transaction unless one is already active; returns whether this call opened it.
+    pub(crate) async fn begin_if_autocommit(&self) -> ConnectionResult<bool> {
+        if !self.inner.is_autocommit()? {
+            return Ok(false);
+        }
+
+        self.inner.execute("BEGIN IMMEDIATE", ()).await?;
+        Ok(true)
+    }
+
+    /// Opens a deferred transaction unless one is already active, so several reads see one snapshot; returns whether
+    /// this call opened it.
+    pub(crate) async fn begin_read_if_autocommit(&self) -> ConnectionResult<bool> {
+        if !self.inner.is_autocommit()? {
+            return Ok(false);
+        }
+
+        self.inner.execute("BEGIN", ()).await?;
+        Ok(true)
+    }
+
+    pub(crate) async fn end_transaction(&self, commit: bool) -> ConnectionResult<()> {
+        let sql = if commit { "COMMIT" } else { "ROLLBACK" };
+        let result = self.inner.execute(sql, ()).await.map(|_| ());
+        // Selects inside the transaction may have cached rows it wrote.
+        if !commit && let Some(cache) = &self.query_cache {
+            cache.invalidate_all();
+        }
+        result
+    }
+
+    /// Runs a query, retrying per the connection's [`RetryPolicy`](retry::RetryPolicy) while the database is busy.
+    /// Steps to the first row before returning, since turso only reports a busy database once the query runs.
+    pub async fn query(&self, sql: &str, params: impl turso::IntoParams) -> crate::Result<connection_like::QueryRows> {
+        let params = params.into_params()?;
+        let sql = &self.with_application_name(sql);
+        let query = || async {
+            let mut statement = self.prepare_cached(sql).await?;
+            let mut rows = statement.query(params.clone()).await?;
+            let first = rows.next().await?;
+
+            Ok(connection_like::QueryRows::from_statement(statement, rows, first, self.stats.clone()))
+        };
+
+        let result = self.tracking_schema(sql, self.opts.retry_policy.run(query)).await;
+        self.stats.record(&result, |_| 0);
+        if routing::is_read(sql) {
+            return Ok(result?);
+        }
+
+        let mut rows = result?;
+        self.record_write();
+        if !self.changes.is_active() {
+            return Ok(rows);
+        }
+
+        // The write only completes once its rows are read, and the change hooks must see it committed.
+        let columns = rows.columns().names().to_vec();
+        let mut buffered = Vec::new();
+        while let Some(row) = rows.next().await? {
+            buffered.push(row);
+        }
+        drop(rows);
+        self.dispatch_changes().await;
+
+        Ok(connection_like::QueryRows::from_rows(columns, buffered))
+    }
+
+    /// Runs a statement, retrying per the connection's [`RetryPolicy`](retry::RetryPolicy) while the database is busy.
     pub async fn execute(&self, sql: &str, params: impl turso::IntoParams) -> turso::Result<u64> {
-        self.inner.execute(sql, params).await
+        let params = params.into_params()?;
+        let run = self.opts.retry_policy.run(|| self.inner.execute(sql, params.clone()));
+        let result = self.tracking_schema(sql, run).await;
+        self.stats.record(&result, |affected| *affected);
+        if result.is_ok() {
+            self.record_write();
+            self.dispatch_changes().await;
+        }
+        result
+    }
+
+    /// Attaches the database file at `path` as `alias`, so tables declared with `#[tursorm(database = "alias")]` or
+    /// queried through `with_schema(alias)` resolve to it. turso currently attaches databases read-only.
+    pub async fn attach(&self, path: &str, alias: &str) -> crate::Result<()> {
+        validate_schema_alias(alias)?;
+        let sql = format!("ATTACH DATABASE '{}' AS {}", path.replace('\'', "''"), alias);
+        self.tracking_schema(&sql, self.inner.execute(&sql, ())).await?;
+        Ok(())
+    }
+
+    pub async fn detach(&self, alias: &str) -> crate::Result<()> {
+        validate_schema_alias(alias)?;
+        let sql = format!("DETACH DATABASE {}", alias);
+        self.tracking_schema(&sql, self.inner.execute(&sql, ())).await?;
+        Ok(())
     }
 
     pub async fn execute_batch(&self, sql: &str) -> turso::Result<()> {
-        self.inner.execute_batch(sql).await
+        self.tracking_schema(sql, self.inner.execute_batch(sql)).await
     }
 
+    /// Prepares `sql` outside the statement cache. Schema changes run through the returned statement aren't tracked;
+    /// call [`clear_statement_cache`](Self::clear_statement_cache) on every connection of the database after one.
     pub async fn prepare(&self, sql: &str) -> turso::Result<turso::Statement> {
+        self.stats.record_prepare();
         self.inner.prepare(sql).await
     }
 
@@ -77,7 +366,329 @@ impl Connection {
         self.inner.is_autocommit()
     }
 
+    /// The pragmas set with [`Builder::pragma`](builder::Builder::pragma) and the values this connection reported
+    /// after applying them.
+    pub fn pragmas(&self) -> &[(String, String)] {
+        &self.opts.pragmas
+    }
+
     pub fn busy_timeout(&self, duration: std::time::Duration) -> turso::Result<()> {
         self.inner.busy_timeout(duration)
     }
+
+    /// Runs the configured maintenance and flushes dirty pages before the connection is dropped.
+    pub async fn close(self) -> ConnectionResult<()> {
+        self.run_close_maintenance().await;
+        self.statements.clear();
+        self.inner.cacheflush()
+    }
+
+    pub(crate) async fn run_close_maintenance(&self) {
+        if self.opts.optimize_on_close {
+            // Best effort: engines without `PRAGMA optimize` reject it, which must not fail the close.
+            if let Err(e) = self.execute_pragma("PRAGMA optimize").await {
+                tracing::debug!("PRAGMA optimize skipped: {}", e);
+            }
+        }
+    }
+
+    pub(crate) async fn execute_pragma(&self, sql: &str) -> ConnectionResult<()> {
+        let mut rows = self.inner.query(sql, ()).await?;
+        while rows.next().await?.is_some() {}
+        Ok(())
+    }
+}
+
+/// The first keyword of `sql`, after the `/* ... */` comments builders and application names put in front.
+fn leading_keyword(sql: &str) -> &str {
+    let mut sql = sql.trim_start();
+    while let Some(rest) = sql.strip_prefix("/*") {
+        sql = rest.split_once("*/").map_or("", |(_, rest)| rest).trim_start();
+    }
+
+    let end = sql.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(sql.len());
+    &sql[..end]
+}
+
+fn validate_schema_alias(alias: &str) -> crate::Result<()> {
+    let valid = alias.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid { Ok(()) } else { Err(crate::Error::Query(format!("Invalid database alias `{}`", alias))) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::connection_like::ConnectionLike;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connection_close() {
+        let db = builder::Builder::new_local(":memory:").build().await.unwrap();
+        let conn = db.connect().unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY)", ()).await.unwrap();
+
+        conn.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_reuses_statements() {
+        let conn = builder::Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)", ()).await.unwrap();
+
+        for i in 0..3 {
+            let changes = conn.execute_cached("INSERT INTO t (name) VALUES (?)", [format!("row {}", i)]).await.unwrap();
+            assert_eq!(changes, 1);
+        }
+
+        let stats = conn.statement_cache_stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.len, 1);
+
+        for _ in 0..2 {
+            let mut statement = conn.prepare_cached("SELECT COUNT(*) FROM t").await.unwrap();
+            let mut rows = statement.query(()).await.unwrap();
+            let row = rows.next().await.unwrap().unwrap();
+            assert_eq!(row.get_value(0).unwrap(), turso::Value::Integer(3));
+        }
+
+        conn.clear_statement_cache();
+        assert_eq!(conn.statement_cache_stats().len, 0);
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_reports_changes_per_run() {
+        let conn = builder::Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT UNIQUE)", ()).await.unwrap();
+        conn.execute("INSERT INTO t (name) VALUES ('a'), ('b'), ('c')", ()).await.unwrap();
+
+        conn.execute("BEGIN", ()).await.unwrap();
+        for _ in 0..3 {
+            assert_eq!(conn.execute_cached("UPDATE t SET name = name || ?", ["x"]).await.unwrap(), 3);
+        }
+        conn.execute("COMMIT", ()).await.unwrap();
+
+        let insert = "INSERT INTO t (name) VALUES (?)";
+        assert_eq!(conn.execute_cached(insert, ["d"]).await.unwrap(), 1);
+        assert!(conn.execute_cached(insert, ["d"]).await.is_err());
+        assert_eq!(conn.statement_cache_stats().len, 1);
+        assert_eq!(conn.execute_cached(insert, ["e"]).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_survives_schema_change() {
+        let conn = builder::Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY, n INTEGER NOT NULL DEFAULT 0)", ()).await.unwrap();
+        let insert = "INSERT INTO t (id) VALUES (?)";
+        let update = "UPDATE t SET n = n + 1";
+        assert_eq!(conn.execute_cached(insert, [1]).await.unwrap(), 1);
+        assert_eq!(conn.execute_cached(insert, [2]).await.unwrap(), 1);
+        assert_eq!(conn.execute_cached(update, ()).await.unwrap(), 2);
+
+        conn.execute("ALTER TABLE t ADD COLUMN name TEXT", ()).await.unwrap();
+        assert_eq!(conn.execute_cached(insert, [3]).await.unwrap(), 1);
+        assert_eq!(conn.execute_cached(update, ()).await.unwrap(), 3);
+        assert_eq!(conn.execute_cached(update, ()).await.unwrap(), 3);
+
+        let stats = conn.statement_cache_stats();
+        assert_eq!((stats.hits, stats.misses), (2, 4));
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_hit_prepares_nothing() {
+        let conn = builder::Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY)", ()).await.unwrap();
+        let insert = "INSERT INTO t (id) VALUES (?)";
+
+        let prepared = conn.stats().prepared;
+        for id in 1..=3 {
+            conn.execute_cached(insert, [id]).await.unwrap();
+        }
+        assert_eq!(conn.stats().prepared - prepared, 1);
+
+        conn.execute("CREATE INDEX t_id ON t (id)", ()).await.unwrap();
+        conn.execute_cached(insert, [4]).await.unwrap();
+        conn.execute_cached(insert, [5]).await.unwrap();
+        assert_eq!(conn.stats().prepared - prepared, 2);
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_sees_schema_changes_of_other_connections() {
+        let db = builder::Builder::new_local(":memory:").build().await.unwrap();
+        let first = db.clone().connect().unwrap();
+        let second = db.connect().unwrap();
+        first.execute("CREATE TABLE t (id INTEGER PRIMARY KEY)", ()).await.unwrap();
+
+        let insert = "INSERT INTO t (id) VALUES (?)";
+        assert_eq!(first.execute_cached(insert, [1]).await.unwrap(), 1);
+
+        // turso reprepares the cached insert with a fresh change counter; reused, it would report 0 rows.
+        second.execute("ALTER TABLE t ADD COLUMN name TEXT", ()).await.unwrap();
+        assert_eq!(first.execute_cached(insert, [2]).await.unwrap(), 1);
+        assert_eq!(first.statement_cache_stats().hits, 0);
+    }
+
+    #[test]
+    fn test_changes_schema() {
+        assert!(statement_cache::changes_schema("CREATE TABLE t (id INTEGER)"));
+        assert!(statement_cache::changes_schema("/* app */ drop index i"));
+        assert!(statement_cache::changes_schema("INSERT INTO t VALUES (1); ALTER TABLE t ADD COLUMN n"));
+        assert!(!statement_cache::changes_schema("SELECT created FROM t"));
+        assert!(!statement_cache::changes_schema("/* create */ UPDATE t SET n = 1"));
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_keeps_the_transaction_open() {
+        let conn = builder::Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY)", ()).await.unwrap();
+
+        conn.execute("BEGIN", ()).await.unwrap();
+        for id in 1..=3 {
+            assert!(conn.query_dynamic("SELECT id FROM t", Vec::new()).await.unwrap().len() < 3);
+            conn.execute_cached("INSERT INTO t (id) VALUES (?)", [id]).await.unwrap();
+        }
+        assert!(!conn.is_autocommit().unwrap());
+        conn.execute("COMMIT", ()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_disabled() {
+        let db = builder::Builder::new_local(":memory:").statement_cache_capacity(0).build().await.unwrap();
+        let conn = db.connect().unwrap();
+
+        conn.execute_cached("SELECT 1", ()).await.ok();
+        conn.execute_cached("SELECT 1", ()).await.ok();
+
+        let stats = conn.statement_cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (0, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_memory_shared_databases() {
+        let first = builder::Builder::new_memory_shared("mod_tests_shared").build().await.unwrap().connect().unwrap();
+        first.execute("CREATE TABLE t (id INTEGER PRIMARY KEY)", ()).await.unwrap();
+        first.execute("INSERT INTO t (id) VALUES (1)", ()).await.unwrap();
+
+        let second = builder::Builder::new_memory_shared("mod_tests_shared").build().await.unwrap().connect().unwrap();
+        let mut rows = second.query("SELECT COUNT(*) FROM t", ()).await.unwrap();
+        assert_eq!(rows.next().await.unwrap().unwrap().get::<i64>(0).unwrap(), 1);
+
+        let other = builder::Builder::new_memory_shared("mod_tests_other").build().await.unwrap().connect().unwrap();
+        assert!(other.query("SELECT COUNT(*) FROM t", ()).await.is_err());
+
+        drop((first, second, rows));
+        let fresh = builder::Builder::new_memory_shared("mod_tests_shared").build().await.unwrap().connect().unwrap();
+        assert!(fresh.query("SELECT COUNT(*) FROM t", ()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_database_encryption() {
+        let dir = std::env::temp_dir().join(format!("tursorm_encryption_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("encrypted.db");
+        let path = path.to_str().unwrap();
+        let key = [7u8; 32];
+
+        let conn = builder::Builder::new_local(path).encryption(key, opts::Cipher::Aegis256).build().await.unwrap();
+        let conn = conn.connect().unwrap();
+        assert!(conn.is_encryption_enabled());
+        conn.execute("CREATE TABLE t (name TEXT)", ()).await.unwrap();
+        conn.execute("INSERT INTO t (name) VALUES ('plaintext-marker')", ()).await.unwrap();
+        drop(conn);
+
+        for file in [path.to_string(), format!("{}-wal", path)] {
+            let bytes = std::fs::read(&file).unwrap_or_default();
+            assert!(!bytes.windows(16).any(|window| window == b"plaintext-marker"));
+        }
+
+        let reopened = builder::Builder::new_local(path).encryption(key, opts::Cipher::Aegis256).build().await;
+        let reopened = reopened.unwrap().connect().unwrap();
+        let mut rows = reopened.query("SELECT name FROM t", ()).await.unwrap();
+        assert_eq!(rows.next().await.unwrap().unwrap().get::<String>(0).unwrap(), "plaintext-marker");
+
+        let short_key = builder::Builder::new_local(path).encryption([7u8; 16], opts::Cipher::Aes256Gcm).build().await;
+        assert!(short_key.err().unwrap().to_string().contains("needs a 32-byte key"));
+        let unknown = turso::EncryptionOpts { cipher: "rot13".to_string(), hexkey: "00".repeat(32) };
+        let unknown = builder::Builder::new_local(path).with_encryption(unknown).build().await;
+        assert!(unknown.err().unwrap().to_string().contains("Unknown database encryption cipher `rot13`"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_builder_pragmas_apply_to_every_connection() {
+        let db = builder::Builder::new_local(":memory:")
+            .pragma("cache_size", -4000)
+            .busy_timeout(std::time::Duration::from_millis(250))
+            .foreign_keys(false)
+            .foreign_keys(true)
+            .build()
+            .await
+            .unwrap();
+
+        for conn in [db.clone().connect().unwrap(), db.connect().unwrap()] {
+            let applied = [("cache_size", "-4000"), ("busy_timeout", "250"), ("foreign_keys", "1")];
+            let applied = applied.map(|(name, value)| (name.to_string(), value.to_string()));
+            assert_eq!(conn.pragmas(), applied);
+
+            let mut rows = conn.query("PRAGMA foreign_keys", ()).await.unwrap();
+            assert_eq!(rows.next().await.unwrap().unwrap().get::<i64>(0).unwrap(), 1);
+        }
+
+        let injected = builder::Builder::new_local(":memory:").pragma("foreign_keys", "ON; DROP TABLE t").build().await;
+        assert!(injected.err().unwrap().to_string().contains("Invalid value"));
+        let unknown = builder::Builder::new_local(":memory:").pragma("bogus", 1).build().await.unwrap().connect();
+        assert!(unknown.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ping_stats_and_integrity_check() {
+        let db = builder::Builder::new_local(":memory:").build().await.unwrap();
+        let conn = db.clone().connect().unwrap();
+        conn.ping().await.unwrap();
+
+        conn.execute("CREATE TABLE t (name TEXT)", ()).await.unwrap();
+        conn.execute_statement("INSERT INTO t (name) VALUES ('a'), ('b')", Vec::new()).await.unwrap();
+        let mut rows = conn.query_rows("SELECT name FROM t", Vec::new()).await.unwrap();
+        while rows.next().await.unwrap().is_some() {}
+        assert!(conn.execute("INSERT INTO missing DEFAULT VALUES", ()).await.is_err());
+
+        let stats = conn.clone().stats();
+        assert_eq!((stats.statements, stats.rows_read, stats.rows_affected, stats.errors), (5, 3, 2, 1));
+        assert!(stats.last_error.unwrap().contains("missing"));
+
+        assert!(conn.integrity_check().await.unwrap().is_ok());
+        assert!(db.integrity_check().await.unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_cipher_from_name() {
+        assert_eq!(opts::Cipher::from_name("AES-256-GCM"), Some(opts::Cipher::Aes256Gcm));
+        assert_eq!(opts::Cipher::from_name("aegis_128l"), Some(opts::Cipher::Aegis128L));
+        assert_eq!(opts::Cipher::from_name("des"), None);
+        assert_eq!(opts::Cipher::Aegis128X2.key_len(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_database_close_truncates_wal() {
+        let dir = std::env::temp_dir().join(format!("tursorm_close_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("close.db");
+        let path = path.to_str().unwrap();
+
+        let db = builder::Builder::new_local(path).optimize_on_close(false).build().await.unwrap();
+        let conn = db.clone().connect().unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)", ()).await.unwrap();
+        for i in 0..50 {
+            conn.execute("INSERT INTO t (name) VALUES (?)", [format!("row {}", i)]).await.unwrap();
+        }
+        drop(conn);
+
+        db.close().await.unwrap();
+
+        let wal_len = std::fs::metadata(format!("{}-wal", path)).map(|m| m.len()).unwrap_or(0);
+        assert_eq!(wal_len, 0);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -1,18 +1,236 @@
+use std::sync::Arc;
+
 use super::builder::Builder;
+use super::metrics::MetricsHook;
+use super::replica::ReplicaState;
+use super::retry::RetryPolicy;
+use super::shared_memory::SharedMemory;
+use super::statement_cache::SchemaEpoch;
+use crate::encryption::Encryptor;
+use crate::type_registry::TypeRegistry;
+
+/// SQLite's default `SQLITE_MAX_VARIABLE_NUMBER`, the most parameters one statement can bind.
+pub(crate) const DEFAULT_MAX_PARAMETERS: usize = 32766;
+
+/// How `ChangeSetTrait::insert` reads back the row it just inserted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InsertStrategy {
+    /// `INSERT ... RETURNING`, a single statement so concurrent writers cannot interleave.
+    #[default]
+    Returning,
+    /// `last_insert_rowid()` followed by a select by rowid, run inside one transaction.
+    LastInsertRowid,
+}
+
+/// Page cipher for turso's experimental database encryption.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Cipher {
+    Aes128Gcm,
+    Aes256Gcm,
+    Aegis256,
+    Aegis128L,
+    Aegis128X2,
+    Aegis128X4,
+    Aegis256X2,
+    Aegis256X4,
+}
+
+impl Cipher {
+    const ALL: [Cipher; 8] = [
+        Cipher::Aes128Gcm,
+        Cipher::Aes256Gcm,
+        Cipher::Aegis256,
+        Cipher::Aegis128L,
+        Cipher::Aegis128X2,
+        Cipher::Aegis128X4,
+        Cipher::Aegis256X2,
+        Cipher::Aegis256X4,
+    ];
+
+    /// The name turso's `cipher` pragma expects, e.g. `aes256gcm`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Cipher::Aes128Gcm => "aes128gcm",
+            Cipher::Aes256Gcm => "aes256gcm",
+            Cipher::Aegis256 => "aegis256",
+            Cipher::Aegis128L => "aegis128l",
+            Cipher::Aegis128X2 => "aegis128x2",
+            Cipher::Aegis128X4 => "aegis128x4",
+            Cipher::Aegis256X2 => "aegis256x2",
+            Cipher::Aegis256X4 => "aegis256x4",
+        }
+    }
+
+    /// Key size in bytes.
+    pub fn key_len(self) -> usize {
+        match self {
+            Cipher::Aes128Gcm | Cipher::Aegis128L | Cipher::Aegis128X2 | Cipher::Aegis128X4 => 16,
+            Cipher::Aes256Gcm | Cipher::Aegis256 | Cipher::Aegis256X2 | Cipher::Aegis256X4 => 32,
+        }
+    }
+
+    /// Accepts the spellings turso does, e.g. `aes256gcm`, `AES-256-GCM` or `aes_256_gcm`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name: String = name.chars().filter(|c| *c != '-' && *c != '_').collect();
+        Self::ALL.into_iter().find(|cipher| cipher.name().eq_ignore_ascii_case(&name))
+    }
+}
+
+impl std::fmt::Display for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The cipher and hex key set on every connection through the `cipher` and `hexkey` pragmas.
+#[derive(Clone)]
+pub(super) struct DatabaseEncryption {
+    pub(super) cipher: String,
+    pub(super) hexkey: String,
+}
+
+impl DatabaseEncryption {
+    pub(super) fn new(key: &[u8], cipher: Cipher) -> Self {
+        Self { cipher: cipher.name().to_string(), hexkey: key.iter().map(|byte| format!("{:02x}", byte)).collect() }
+    }
+
+    /// Checks the cipher name and key size up front; turso reports neither clearly once pages are being read.
+    pub(super) fn validate(&self) -> Result<(), turso::Error> {
+        let invalid = |message: String| turso::Error::SqlExecutionFailure(message);
+        let cipher = Cipher::from_name(&self.cipher)
+            .ok_or_else(|| invalid(format!("Unknown database encryption cipher `{}`", self.cipher)))?;
+
+        if !self.hexkey.bytes().all(|byte| byte.is_ascii_hexdigit()) || self.hexkey.len() != cipher.key_len() * 2 {
+            return Err(invalid(format!(
+                "Cipher `{}` needs a {}-byte key ({} hex digits)",
+                cipher,
+                cipher.key_len(),
+                cipher.key_len() * 2
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn apply(&self, conn: &turso::Connection) -> Result<(), turso::Error> {
+        let pragmas = [format!("cipher = '{}'", self.cipher), format!("hexkey = '{}'", self.hexkey)];
+        for pragma in &pragmas {
+            conn.pragma_query(pragma, |_| Ok(())).map_err(|err| {
+                turso::Error::SqlExecutionFailure(format!(
+                    "Database encryption is unavailable; turso must be built with its `encryption` feature: {}",
+                    err
+                ))
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for DatabaseEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DatabaseEncryption").field("cipher", &self.cipher).finish_non_exhaustive()
+    }
+}
+
+/// Checks a pragma set with [`Builder::pragma`]: the name must be an identifier and the value a number, keyword or
+/// quoted string, since both are spliced into the statement.
+pub(super) fn validate_pragma(name: &str, value: &str) -> Result<(), turso::Error> {
+    let is_identifier = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let is_quoted = value.len() >= 2
+        && value.starts_with('\'')
+        && value.ends_with('\'')
+        && !value[1..value.len() - 1].contains('\'');
+    let is_literal =
+        !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '.'));
+
+    if !is_identifier {
+        return Err(turso::Error::SqlExecutionFailure(format!("Invalid pragma name `{}`", name)));
+    }
+    if !is_quoted && !is_literal {
+        return Err(turso::Error::SqlExecutionFailure(format!("Invalid value `{}` for pragma `{}`", value, name)));
+    }
+
+    Ok(())
+}
+
+/// Sets each pragma on `conn` and returns the values it reports afterwards, which may differ from the requested
+/// ones, e.g. `wal` for a `journal_mode` of `WAL`.
+pub(super) fn apply_pragmas(
+    conn: &turso::Connection,
+    pragmas: &[(String, String)],
+) -> Result<Vec<(String, String)>, turso::Error> {
+    pragmas
+        .iter()
+        .map(|(name, value)| {
+            conn.pragma_query(&format!("{} = {}", name, value), |_| Ok(()))?;
+
+            let mut applied = value.clone();
+            conn.pragma_query(name, |row| {
+                match row.get_value(0) {
+                    Ok(turso::Value::Integer(i)) => applied = i.to_string(),
+                    Ok(turso::Value::Real(f)) => applied = f.to_string(),
+                    Ok(turso::Value::Text(text)) => applied = text,
+                    _ => {}
+                }
+                Ok(())
+            })?;
+
+            Ok((name.clone(), applied))
+        })
+        .collect()
+}
 
 #[derive(Debug, Clone)]
 pub struct DatabaseOpts {
-    pub(super) path:              String,
-    pub(super) enable_mvcc:       bool,
-    pub(super) enable_encryption: bool,
+    pub(super) path:                     String,
+    pub(super) enable_mvcc:              bool,
+    pub(super) enable_encryption:        bool,
+    pub(super) encryption:               Option<DatabaseEncryption>,
+    pub(super) metrics:                  MetricsHook,
+    pub(super) optimize_on_close:        bool,
+    pub(super) statement_cache_capacity: usize,
+    /// Shared by all connections of the database, so each statement cache sees the others' schema changes.
+    pub(super) schema_epoch:             Arc<SchemaEpoch>,
+    pub(super) insert_strategy:          InsertStrategy,
+    pub(super) require_filter:           bool,
+    pub(super) max_parameters:           usize,
+    pub(super) max_blob_size:            Option<usize>,
+    pub(super) retry_policy:             RetryPolicy,
+    pub(super) column_encryptor:         Option<Arc<dyn Encryptor>>,
+    /// Requested by the builder until a connection applies them, then the values the connection reports.
+    pub(super) pragmas:                  Vec<(String, String)>,
+    pub(super) application_name:         Option<String>,
+    pub(super) type_registry:            Arc<TypeRegistry>,
+    /// Keeps a named in-memory database alive while connections to it exist.
+    pub(super) shared_memory:            Option<Arc<SharedMemory>>,
+    /// Set for an embedded replica, shared by all of its connections.
+    pub(super) replica:                  Option<Arc<ReplicaState>>,
 }
 
 impl From<&Builder> for DatabaseOpts {
     fn from(builder: &Builder) -> Self {
         Self {
-            path:              builder.path.clone(),
-            enable_mvcc:       builder.enable_mvcc.clone(),
-            enable_encryption: builder.enable_encryption.clone(),
+            path:                     builder.path.clone(),
+            enable_mvcc:              builder.enable_mvcc,
+            enable_encryption:        builder.enable_encryption,
+            encryption:               builder.encryption.clone(),
+            metrics:                  MetricsHook::new(builder.metrics_observer.clone()),
+            optimize_on_close:        builder.optimize_on_close,
+            statement_cache_capacity: builder.statement_cache_capacity,
+            schema_epoch:             Arc::default(),
+            insert_strategy:          builder.insert_strategy,
+            require_filter:           builder.require_filter,
+            max_parameters:           builder.max_parameters,
+            max_blob_size:            builder.max_blob_size,
+            retry_policy:             builder.retry_policy,
+            column_encryptor:         builder.column_encryptor.clone(),
+            pragmas:                  builder.pragmas.clone(),
+            application_name:         builder.application_name.clone(),
+            type_registry:            builder.type_registry.clone(),
+            shared_memory:            None,
+            replica:                  builder.replica_sync.clone().map(|sync| Arc::new(ReplicaState::new(sync))),
         }
     }
 }
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use crate::Result;
+
+/// Pulls the primary's changes into an embedded replica, and pushes local writes if the replica takes any.
+///
+/// turso 0.3 has no sync client of its own, so the application hands its sync engine to
+/// [`Builder::replica_sync`](crate::Builder::replica_sync); the connection then calls it when a read asks for
+/// fresher data than the replica holds, see [`Select::read_your_writes`](crate::Select::read_your_writes) and
+/// [`Select::max_staleness`](crate::Select::max_staleness).
+#[async_trait::async_trait]
+pub trait ReplicaSync: Send + Sync {
+    async fn sync(&self) -> Result<()>;
+}
+
+/// How fresh a replica must be before a read runs on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Freshness {
+    /// Syncs first if the database was written to since the last sync.
+    ReadYourWrites,
+    /// Syncs first if the last sync is older than this, or there was none yet.
+    MaxStaleness(Duration),
+}
+
+/// The sync metadata of an embedded replica, from [`Connection::sync_status`](crate::Connection::sync_status).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyncStatus {
+    pub last_synced_at: Option<SystemTime>,
+    /// Whether the database was written to since the last sync, so the replica may not show those writes yet.
+    pub pending_writes: bool,
+    /// Syncs completed by the connections of the database.
+    pub syncs:          u64,
+}
+
+/// Sync bookkeeping shared by the connections of a replica database.
+pub(crate) struct ReplicaState {
+    sync:          Arc<dyn ReplicaSync>,
+    last_sync:     Mutex<Option<(Instant, SystemTime)>>,
+    /// Bumped by every write; a sync covers the writes counted when it started.
+    writes:        AtomicU64,
+    synced_writes: AtomicU64,
+    syncs:         AtomicU64,
+}
+
+impl ReplicaState {
+    pub(crate) fn new(sync: Arc<dyn ReplicaSync>) -> Self {
+        Self {
+            sync,
+            last_sync: Mutex::new(None),
+            writes: AtomicU64::new(0),
+            synced_writes: AtomicU64::new(0),
+            syncs: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn record_write(&self) {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) async fn sync(&self) -> Result<()> {
+        let writes = self.writes.load(Ordering::Relaxed);
+        self.sync.sync().await?;
+
+        self.synced_writes.fetch_max(writes, Ordering::Relaxed);
+        self.syncs.fetch_add(1, Ordering::Relaxed);
+        *self.last_sync.lock().unwrap() = Some((Instant::now(), SystemTime::now()));
+        Ok(())
+    }
+
+    pub(crate) async fn ensure_fresh(&self, freshness: Freshness) -> Result<()> {
+        let stale = match freshness {
+            Freshness::ReadYourWrites => self.has_pending_writes(),
+            Freshness::MaxStaleness(max) => {
+                self.last_sync.lock().unwrap().is_none_or(|(synced_at, _)| synced_at.elapsed() > max)
+            }
+        };
+
+        if stale { self.sync().await } else { Ok(()) }
+    }
+
+    pub(crate) fn status(&self) -> SyncStatus {
+        SyncStatus {
+            last_synced_at: self.last_sync.lock().unwrap().map(|(_, synced_at)| synced_at),
+            pending_writes: self.has_pending_writes(),
+            syncs:          self.syncs.load(Ordering::Relaxed),
+        }
+    }
+
+    fn has_pending_writes(&self) -> bool {
+        self.writes.load(Ordering::Relaxed) > self.synced_writes.load(Ordering::Relaxed)
+    }
+}
+
+impl std::fmt::Debug for ReplicaState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplicaState").field("status", &self.status()).finish_non_exhaustive()
+    }
+}
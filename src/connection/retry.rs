@@ -0,0 +1,137 @@
+use std::future::Future;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::SystemTime;
+
+/// How statements are retried when the database is busy or locked, e.g. by another writer on the same file.
+///
+/// The delay doubles after every attempt, starting at `base_delay` and capped at `max_delay`. `jitter` is the fraction
+/// of each delay that is randomized, so concurrent writers don't retry in lockstep. The default makes one attempt.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay:   Duration,
+    pub max_delay:    Duration,
+    pub jitter:       f64,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self { max_attempts, base_delay, max_delay: Duration::from_secs(1), jitter: 0.5 }
+    }
+
+    pub fn none() -> Self {
+        Self { max_attempts: 1, base_delay: Duration::ZERO, max_delay: Duration::ZERO, jitter: 0.0 }
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// The pause before retry number `retry` (starting at 1).
+    pub fn delay(&self, retry: u32) -> Duration {
+        let backoff = self.base_delay.saturating_mul(1 << retry.saturating_sub(1).min(16)).min(self.max_delay);
+        backoff.mul_f64(1.0 - self.jitter * random_fraction())
+    }
+
+    /// Runs `op` until it succeeds, fails with a non-busy error, or the attempts run out.
+    pub(crate) async fn run<T, Fut>(&self, mut op: impl FnMut() -> Fut) -> turso::Result<T>
+    where Fut: Future<Output = turso::Result<T>> {
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Err(err) if attempt < self.max_attempts && crate::error::is_busy_error(&err) => {
+                    tracing::debug!("Database busy, retrying (attempt {}): {}", attempt, err);
+                    crate::timer::sleep(self.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// A value in `[0, 1)` from a xorshift generator; good enough to spread retries apart.
+fn random_fraction() -> f64 {
+    static STATE: AtomicU64 = AtomicU64::new(0);
+
+    let mut x = STATE.load(Ordering::Relaxed);
+    if x == 0 {
+        x = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(1) | 1;
+    }
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    STATE.store(x, Ordering::Relaxed);
+
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy_delay_backs_off() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(10)).max_delay(Duration::from_millis(35)).jitter(0.0);
+
+        assert_eq!(policy.delay(1), Duration::from_millis(10));
+        assert_eq!(policy.delay(2), Duration::from_millis(20));
+        assert_eq!(policy.delay(3), Duration::from_millis(35));
+
+        let jittered = RetryPolicy::new(5, Duration::from_millis(100)).jitter(0.5);
+        for _ in 0..20 {
+            let delay = jittered.delay(1);
+            assert!(delay > Duration::from_millis(50) && delay <= Duration::from_millis(100));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_retries_busy_errors() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(1));
+        let busy = || turso::Error::SqlExecutionFailure("database is locked".to_string());
+
+        let mut calls = 0;
+        let result = policy
+            .run(|| {
+                calls += 1;
+                let result = if calls < 3 { Err(busy()) } else { Ok(calls) };
+                async move { result }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let result: turso::Result<()> = policy
+            .run(|| {
+                calls += 1;
+                async { Err(busy()) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+
+        let mut calls = 0;
+        let result: turso::Result<()> = policy
+            .run(|| {
+                calls += 1;
+                async { Err(turso::Error::SqlExecutionFailure("Parse error: nope".to_string())) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
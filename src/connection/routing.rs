@@ -0,0 +1,186 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use super::Connection;
+use super::connection_like::ConnectionLike;
+use super::connection_like::QueryRows;
+use super::metrics::MetricsObserver;
+use super::opts::InsertStrategy;
+use super::replica::Freshness;
+use crate::Result;
+use crate::Value;
+use crate::encryption::Encryptor;
+use crate::query_cache::QueryCache;
+
+/// Splits reads from writes: `SELECT`s go to the read replicas in turn, everything else to the primary.
+///
+/// Replicas may lag behind the primary. Run reads that must see a preceding write against [`use_primary`], and
+/// migrations too, since [`Migrator`](crate::migration::Migrator) always works on a single connection. Once a
+/// transaction is open on the primary, reads follow it there.
+///
+/// [`use_primary`]: RoutingConnection::use_primary
+#[derive(Debug, Clone)]
+pub struct RoutingConnection {
+    primary:  Connection,
+    replicas: Arc<[Connection]>,
+    next:     Arc<AtomicUsize>,
+}
+
+impl RoutingConnection {
+    /// Without replicas, every statement runs on the primary.
+    pub fn new(primary: Connection, replicas: Vec<Connection>) -> Self {
+        Self { primary, replicas: replicas.into(), next: Arc::new(AtomicUsize::new(0)) }
+    }
+
+    /// The write connection, for read-after-write consistency and migrations.
+    pub fn use_primary(&self) -> &Connection {
+        &self.primary
+    }
+
+    pub fn replicas(&self) -> &[Connection] {
+        &self.replicas
+    }
+
+    fn route(&self, sql: &str) -> &Connection {
+        if self.replicas.is_empty() || !is_read(sql) || !self.primary.is_autocommit().unwrap_or(false) {
+            return &self.primary;
+        }
+
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        &self.replicas[next % self.replicas.len()]
+    }
+}
+
+pub(super) fn is_read(sql: &str) -> bool {
+    super::leading_keyword(sql).eq_ignore_ascii_case("SELECT")
+}
+
+#[async_trait::async_trait]
+impl ConnectionLike for RoutingConnection {
+    async fn query_rows(&self, sql: &str, params: Vec<Value>) -> Result<QueryRows> {
+        self.route(sql).query_rows(sql, params).await
+    }
+
+    /// Reads that must see this connection's writes go to the primary.
+    async fn query_rows_fresh(&self, sql: &str, params: Vec<Value>, freshness: Freshness) -> Result<QueryRows> {
+        match freshness {
+            Freshness::ReadYourWrites => self.primary.query_rows_fresh(sql, params, freshness).await,
+            Freshness::MaxStaleness(_) => self.route(sql).query_rows_fresh(sql, params, freshness).await,
+        }
+    }
+
+    async fn execute_statement(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+        self.primary.execute_statement(sql, params).await
+    }
+
+    fn last_insert_rowid(&self) -> i64 {
+        self.primary.last_insert_rowid()
+    }
+
+    fn insert_strategy(&self) -> InsertStrategy {
+        self.primary.insert_strategy()
+    }
+
+    fn metrics_observer(&self) -> Option<Arc<dyn MetricsObserver>> {
+        ConnectionLike::metrics_observer(&self.primary)
+    }
+
+    fn column_encryptor(&self) -> Option<Arc<dyn Encryptor>> {
+        ConnectionLike::column_encryptor(&self.primary)
+    }
+
+    fn query_cache(&self) -> Option<Arc<QueryCache>> {
+        ConnectionLike::query_cache(&self.primary)
+    }
+
+    fn requires_filter_on_mutation(&self) -> bool {
+        self.primary.requires_filter_on_mutation()
+    }
+
+    fn is_mvcc_enabled(&self) -> bool {
+        self.primary.is_mvcc_enabled()
+    }
+
+    fn max_parameters(&self) -> usize {
+        self.primary.max_parameters()
+    }
+
+    fn max_blob_size(&self) -> Option<usize> {
+        self.primary.max_blob_size()
+    }
+
+    async fn begin_if_autocommit(&self) -> Result<bool> {
+        ConnectionLike::begin_if_autocommit(&self.primary).await
+    }
+
+    async fn begin_read_if_autocommit(&self) -> Result<bool> {
+        ConnectionLike::begin_read_if_autocommit(&self.primary).await
+    }
+
+    async fn end_transaction(&self, commit: bool) -> Result<()> {
+        ConnectionLike::end_transaction(&self.primary, commit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Builder;
+
+    async fn connection_named(name: &str) -> Connection {
+        let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        conn.execute("CREATE TABLE t (name TEXT)", ()).await.unwrap();
+        conn.execute("INSERT INTO t (name) VALUES (?)", [name]).await.unwrap();
+        conn
+    }
+
+    async fn read_name(conn: &impl ConnectionLike) -> String {
+        let mut rows = conn.query_rows("SELECT name FROM t LIMIT 1", Vec::new()).await.unwrap();
+        rows.next().await.unwrap().unwrap().get::<String>(0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_routing_connection_splits_reads_and_writes() {
+        let primary = connection_named("primary").await;
+        let replicas = vec![connection_named("replica-1").await, connection_named("replica-2").await];
+        let conn = RoutingConnection::new(primary, replicas);
+
+        assert_eq!(read_name(&conn).await, "replica-1");
+        assert_eq!(read_name(&conn).await, "replica-2");
+        assert_eq!(read_name(&conn).await, "replica-1");
+        assert_eq!(read_name(conn.use_primary()).await, "primary");
+
+        conn.execute_statement("DELETE FROM t", Vec::new()).await.unwrap();
+        let mut primary_rows = conn.use_primary().query_rows("SELECT name FROM t", Vec::new()).await.unwrap();
+        assert!(primary_rows.next().await.unwrap().is_none());
+        assert_eq!(read_name(&conn).await, "replica-2");
+
+        assert!(conn.begin_if_autocommit().await.unwrap());
+        conn.execute_statement("INSERT INTO t (name) VALUES ('pending')", Vec::new()).await.unwrap();
+        assert_eq!(read_name(&conn).await, "pending");
+        conn.end_transaction(false).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_routing_connection_reads_own_writes_from_primary() {
+        let conn = RoutingConnection::new(connection_named("primary").await, vec![connection_named("replica").await]);
+        let sql = "SELECT name FROM t LIMIT 1";
+
+        let mut rows = conn.query_rows_fresh(sql, Vec::new(), Freshness::ReadYourWrites).await.unwrap();
+        assert_eq!(rows.next().await.unwrap().unwrap().get::<String>(0).unwrap(), "primary");
+        let staleness = Freshness::MaxStaleness(std::time::Duration::from_secs(1));
+        let mut rows = conn.query_rows_fresh(sql, Vec::new(), staleness).await.unwrap();
+        assert_eq!(rows.next().await.unwrap().unwrap().get::<String>(0).unwrap(), "replica");
+    }
+
+    #[tokio::test]
+    async fn test_routing_connection_without_replicas_uses_primary() {
+        let conn = RoutingConnection::new(connection_named("primary").await, Vec::new());
+        assert_eq!(read_name(&conn).await, "primary");
+        assert!(is_read("  select 1"));
+        assert!(is_read("/* app */ /* cart:get */ SELECT 1"));
+        assert!(!is_read("/* select */ DELETE FROM t"));
+        assert!(!is_read("INSERT INTO t DEFAULT VALUES RETURNING name"));
+    }
+}
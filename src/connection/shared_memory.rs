@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::Weak;
+
+use super::statement_cache::SchemaEpoch;
+
+static REGISTRY: LazyLock<Mutex<HashMap<String, Weak<SharedMemory>>>> = LazyLock::new(Default::default);
+
+/// A named in-memory database; it lives as long as a `Database` or `Connection` built from it.
+pub(crate) struct SharedMemory {
+    name:         String,
+    db:           turso::Database,
+    schema_epoch: Arc<SchemaEpoch>,
+}
+
+impl SharedMemory {
+    /// Returns the live database registered under `name`, or registers the one `open` opens.
+    pub(crate) async fn get_or_open(
+        name: &str,
+        open: impl Future<Output = turso::Result<turso::Database>>,
+    ) -> turso::Result<Arc<Self>> {
+        if let Some(shared) = Self::lookup(name) {
+            return Ok(shared);
+        }
+
+        let db = open.await?;
+
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        // Another caller may have registered the name while this one was opening.
+        if let Some(shared) = registry.get(name).and_then(Weak::upgrade) {
+            return Ok(shared);
+        }
+        let shared = Arc::new(Self { name: name.to_string(), db, schema_epoch: Arc::default() });
+        registry.insert(name.to_string(), Arc::downgrade(&shared));
+
+        Ok(shared)
+    }
+
+    fn lookup(name: &str) -> Option<Arc<Self>> {
+        REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).get(name).and_then(Weak::upgrade)
+    }
+
+    pub(crate) fn database(&self) -> turso::Database {
+        self.db.clone()
+    }
+
+    pub(crate) fn schema_epoch(&self) -> Arc<SchemaEpoch> {
+        self.schema_epoch.clone()
+    }
+}
+
+impl Drop for SharedMemory {
+    fn drop(&mut self) {
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        if registry.get(&self.name).is_some_and(|entry| entry.strong_count() == 0) {
+            registry.remove(&self.name);
+        }
+    }
+}
+
+impl std::fmt::Debug for SharedMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedMemory").field("name", &self.name).finish()
+    }
+}
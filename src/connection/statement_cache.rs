@@ -0,0 +1,287 @@
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+pub(crate) const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StatementCacheStats {
+    pub capacity: usize,
+    pub len:      usize,
+    pub hits:     u64,
+    pub misses:   u64,
+}
+
+/// Counts the schema changes run through the connections of one database; see [`StatementCache`].
+#[derive(Debug, Default)]
+pub(crate) struct SchemaEpoch(AtomicU64);
+
+impl SchemaEpoch {
+    pub(crate) fn current(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn advance(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Whether `sql` holds a statement that changes the schema, judged by the leading keyword of each statement.
+pub(crate) fn changes_schema(sql: &str) -> bool {
+    const KEYWORDS: [&str; 5] = ["ALTER", "ATTACH", "CREATE", "DETACH", "DROP"];
+
+    sql.split(';').any(|statement| {
+        let keyword = super::leading_keyword(statement);
+        KEYWORDS.iter().any(|schema_keyword| keyword.eq_ignore_ascii_case(schema_keyword))
+    })
+}
+
+/// Least-recently-used cache of prepared statements keyed by SQL text.
+///
+/// Statements are checked out while in use so two callers never step the same handle concurrently.
+///
+/// turso never resets a statement's change counter, and reprepares a statement after a schema change with a new
+/// counter starting from zero. Each entry keeps the [`SchemaEpoch`] it ran under, and a handle is only reused in the
+/// same epoch, so the counter it reported last is still the one it will add to.
+pub(crate) struct StatementCache {
+    capacity: usize,
+    entries:  Mutex<Vec<Entry>>,
+    hits:     AtomicU64,
+    misses:   AtomicU64,
+}
+
+struct Entry {
+    sql:       String,
+    statement: turso::Statement,
+    changes:   u64,
+    epoch:     u64,
+}
+
+impl StatementCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self { capacity, entries: Mutex::new(Vec::new()), hits: AtomicU64::new(0), misses: AtomicU64::new(0) }
+    }
+
+    /// Checks out the statement for `sql` along with the change total it last reported. A statement prepared in
+    /// another schema epoch is dropped instead.
+    pub(crate) fn take(&self, sql: &str, epoch: u64) -> Option<(turso::Statement, u64)> {
+        if self.capacity == 0 {
+            return None;
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = entries.iter().rposition(|entry| entry.sql == sql).map(|idx| entries.remove(idx));
+        match entry {
+            Some(entry) if entry.epoch == epoch => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some((entry.statement, entry.changes))
+            }
+            _ => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn put(&self, sql: String, statement: turso::Statement, changes: u64, epoch: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        statement.reset();
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.iter().any(|entry| entry.sql == sql) {
+            return;
+        }
+        if entries.len() >= self.capacity {
+            entries.remove(0);
+        }
+        entries.push(Entry { sql, statement, changes, epoch });
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    pub(crate) fn stats(&self) -> StatementCacheStats {
+        StatementCacheStats {
+            capacity: self.capacity,
+            len:      self.entries.lock().unwrap_or_else(|e| e.into_inner()).len(),
+            hits:     self.hits.load(Ordering::Relaxed),
+            misses:   self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    #[cfg(test)]
+    fn keys(&self) -> Vec<String> {
+        self.entries.lock().unwrap().iter().map(|entry| entry.sql.clone()).collect()
+    }
+}
+
+impl std::fmt::Debug for StatementCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatementCache").field("stats", &self.stats()).finish()
+    }
+}
+
+/// A statement checked out of the cache; it is reset and returned to the cache when dropped.
+pub(crate) struct CachedStatement {
+    cache:     Arc<StatementCache>,
+    sql:       String,
+    statement: Option<turso::Statement>,
+    changes:   u64,
+    epoch:     u64,
+    reusable:  bool,
+}
+
+impl CachedStatement {
+    pub(crate) fn new(
+        cache: Arc<StatementCache>,
+        sql: String,
+        statement: turso::Statement,
+        changes: u64,
+        epoch: u64,
+    ) -> Self {
+        Self { cache, sql, statement: Some(statement), changes, epoch, reusable: true }
+    }
+
+    /// turso never resets a statement's change counter, so `turso::Statement::execute` reports the total across every
+    /// run of the handle. Returns only this run's changes instead.
+    pub(crate) async fn execute(&mut self, params: impl turso::IntoParams) -> turso::Result<u64> {
+        let result = self.deref_mut().execute(params).await;
+
+        match result {
+            Ok(total) if total >= self.changes => {
+                let changes = total - self.changes;
+                self.changes = total;
+                Ok(changes)
+            }
+            Ok(total) => {
+                // The schema changed without advancing the epoch, e.g. from another process, so turso reprepared the
+                // statement and its counter restarted. The run's own changes are the whole new total, but the handle's
+                // epoch is stale.
+                self.reusable = false;
+                Ok(total)
+            }
+            Err(e) => {
+                // The counter may include rows from the failed run; don't trust it again.
+                self.reusable = false;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Deref for CachedStatement {
+    type Target = turso::Statement;
+
+    fn deref(&self) -> &Self::Target {
+        self.statement.as_ref().expect("statement present until drop")
+    }
+}
+
+impl DerefMut for CachedStatement {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.statement.as_mut().expect("statement present until drop")
+    }
+}
+
+impl Drop for CachedStatement {
+    fn drop(&mut self) {
+        if let Some(statement) = self.statement.take()
+            && self.reusable
+        {
+            self.cache.put(std::mem::take(&mut self.sql), statement, self.changes, self.epoch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn statement(conn: &turso::Connection, sql: &str) -> turso::Statement {
+        conn.prepare(sql).await.unwrap()
+    }
+
+    async fn connection() -> turso::Connection {
+        turso::Builder::new_local(":memory:").build().await.unwrap().connect().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_hit_and_miss() {
+        let conn = connection().await;
+        let cache = StatementCache::new(2);
+
+        assert!(cache.take("SELECT 1", 0).is_none());
+        cache.put("SELECT 1".to_string(), statement(&conn, "SELECT 1").await, 0, 0);
+        assert!(cache.take("SELECT 1", 0).is_some());
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.len, 0);
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_evicts_least_recently_used() {
+        let conn = connection().await;
+        let cache = StatementCache::new(2);
+
+        cache.put("SELECT 1".to_string(), statement(&conn, "SELECT 1").await, 0, 0);
+        cache.put("SELECT 2".to_string(), statement(&conn, "SELECT 2").await, 0, 0);
+
+        let (reused, changes) = cache.take("SELECT 1", 0).unwrap();
+        cache.put("SELECT 1".to_string(), reused, changes, 0);
+        cache.put("SELECT 3".to_string(), statement(&conn, "SELECT 3").await, 0, 0);
+
+        assert_eq!(cache.keys(), vec!["SELECT 1".to_string(), "SELECT 3".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_drops_statements_of_another_epoch() {
+        let conn = connection().await;
+        let cache = StatementCache::new(2);
+
+        cache.put("SELECT 1".to_string(), statement(&conn, "SELECT 1").await, 3, 1);
+        assert!(cache.take("SELECT 1", 2).is_none());
+        assert!(cache.take("SELECT 1", 1).is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (0, 2, 0));
+    }
+
+    #[tokio::test]
+    async fn test_statement_cache_disabled() {
+        let conn = connection().await;
+        let cache = StatementCache::new(0);
+
+        cache.put("SELECT 1".to_string(), statement(&conn, "SELECT 1").await, 0, 0);
+        assert!(cache.take("SELECT 1", 0).is_none());
+        assert_eq!(cache.stats(), StatementCacheStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_cached_statement_returns_on_drop() {
+        let conn = connection().await;
+        let cache = Arc::new(StatementCache::new(4));
+
+        {
+            let mut cached =
+                CachedStatement::new(cache.clone(), "SELECT 1".to_string(), statement(&conn, "SELECT 1").await, 0, 0);
+            let mut rows = cached.query(()).await.unwrap();
+            assert!(rows.next().await.unwrap().is_some());
+        }
+
+        assert_eq!(cache.keys(), vec!["SELECT 1".to_string()]);
+
+        let (statement, changes) = cache.take("SELECT 1", 0).unwrap();
+        let mut reused = CachedStatement::new(cache.clone(), "SELECT 1".to_string(), statement, changes, 0);
+        let mut rows = reused.query(()).await.unwrap();
+        assert_eq!(rows.next().await.unwrap().unwrap().get_value(0).unwrap(), turso::Value::Integer(1));
+    }
+}
@@ -0,0 +1,679 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Poll;
+use std::task::Waker;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use super::Connection;
+use super::connection_like::ConnectionLike;
+use super::connection_like::ConnectionLikeExt;
+use super::connection_like::QueryRows;
+use super::metrics::MetricsObserver;
+use super::opts::InsertStrategy;
+use crate::Error;
+use crate::ErrorKind;
+use crate::Result;
+use crate::Value;
+use crate::encryption::Encryptor;
+use crate::query_cache::QueryCache;
+
+const QUEUE_TABLE: &str = "_tursorm_write_queue";
+const PARAMS_TABLE: &str = "_tursorm_write_queue_params";
+const JOURNAL_TABLE: &str = "_tursorm_write_queue_journal";
+/// Kept in the remote database: the groups of writes a replay applied, so a group is never applied twice.
+const REPLAYED_TABLE: &str = "_tursorm_replayed_writes";
+
+/// A write that couldn't reach the remote database, waiting in the local journal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedWrite {
+    pub id:        i64,
+    pub sql:       String,
+    pub params:    Vec<Value>,
+    /// Writes queued inside one transaction share a batch, which is replayed in one transaction.
+    pub batch:     Option<i64>,
+    /// Milliseconds since the Unix epoch.
+    pub queued_at: i64,
+}
+
+/// What [`WriteQueue::replay`] does with a write the remote rejects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Drops the write, along with the rest of its batch, and goes on with the next one.
+    Discard,
+    /// Leaves the write queued and stops, so later writes don't overtake it.
+    Keep,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub replayed:  u64,
+    pub discarded: u64,
+    /// Writes still queued because the remote became unreachable again or a conflict kept them.
+    pub pending:   u64,
+}
+
+type ConflictHandler = Arc<dyn Fn(&QueuedWrite, &Error) -> ConflictResolution + Send + Sync>;
+
+/// Statements and their parameters, in the order they were run.
+type Writes = Vec<(String, Vec<Value>)>;
+
+/// Runs statements against a remote database and, while it is unreachable, journals writes in a local database
+/// instead, for apps that are only occasionally connected.
+///
+/// Reads always go to the remote. Writes through [`ConnectionLike::execute_statement`], such as `insert_exec`,
+/// [`Update::exec`](crate::Update::exec) and [`Delete::exec`](crate::Delete::exec), are queued when the remote fails
+/// with an [`ErrorKind::Io`] error and then fail with [`Error::Queued`]; writes that read rows back, like `insert`,
+/// fail with the remote's error instead. A transaction begun while the remote is unreachable queues its writes as
+/// one batch when it commits, and the commit fails with [`Error::Queued`]. Each clone of a queue is a separate
+/// handle with its own transaction.
+///
+/// [`WriteQueue::run`] replays the journal in the background. Each replayed write, or batch, is recorded in the
+/// remote's `_tursorm_replayed_writes` table in the same transaction, so a replay whose outcome was lost, e.g. to a
+/// dropped connection during the commit, is not applied again by the next one.
+///
+/// ```ignore
+/// let queue = WriteQueue::open(local, remote).await?.on_conflict(|write, error| {
+///     tracing::warn!("dropping {}: {}", write.sql, error);
+///     ConflictResolution::Discard
+/// });
+/// tokio::spawn(queue.clone().run(Duration::from_secs(30)));
+/// match new_note.insert_exec(&queue).await {
+///     Ok(_) => {}
+///     Err(e) if e.is_queued() => show_pending_badge(),
+///     Err(e) => return Err(e),
+/// }
+/// ```
+pub struct WriteQueue {
+    local:       Connection,
+    remote:      Arc<dyn ConnectionLike>,
+    on_conflict: Option<ConflictHandler>,
+    is_offline:  fn(&Error) -> bool,
+    /// Identifies this journal's writes among those other journals replay to the same remote.
+    journal:     Arc<str>,
+    /// Clones share the local connection, and with it its transaction, so journal changes take turns.
+    lock:        Arc<JournalLock>,
+    /// The writes of the transaction this handle began while the remote was unreachable.
+    batch:       Mutex<Option<Writes>>,
+}
+
+impl WriteQueue {
+    /// Creates the journal tables in `local` if needed.
+    pub async fn open(local: Connection, remote: impl ConnectionLike + 'static) -> Result<Self> {
+        local
+            .execute(
+                &format!(
+                    "CREATE TABLE IF NOT EXISTS {} (id INTEGER PRIMARY KEY AUTOINCREMENT, sql TEXT NOT NULL, batch \
+                     INTEGER, queued_at INTEGER NOT NULL)",
+                    QUEUE_TABLE
+                ),
+                (),
+            )
+            .await?;
+        // `value` has no type, so parameters keep the storage class they were bound with.
+        local
+            .execute(
+                &format!(
+                    "CREATE TABLE IF NOT EXISTS {} (write_id INTEGER NOT NULL, position INTEGER NOT NULL, value, \
+                     PRIMARY KEY (write_id, position))",
+                    PARAMS_TABLE
+                ),
+                (),
+            )
+            .await?;
+        local.execute(&format!("CREATE TABLE IF NOT EXISTS {} (id TEXT NOT NULL)", JOURNAL_TABLE), ()).await?;
+        local
+            .execute(
+                &format!(
+                    "INSERT INTO {0} (id) SELECT lower(hex(randomblob(16))) WHERE NOT EXISTS (SELECT 1 FROM {0})",
+                    JOURNAL_TABLE
+                ),
+                (),
+            )
+            .await?;
+        let rows = local.query_dynamic(&format!("SELECT id FROM {}", JOURNAL_TABLE), Vec::new()).await?;
+        let journal: String = rows[0].get_index(0)?;
+
+        Ok(Self {
+            local,
+            remote: Arc::new(remote),
+            on_conflict: None,
+            is_offline: |error| error.kind() == ErrorKind::Io,
+            journal: journal.into(),
+            lock: Arc::default(),
+            batch: Mutex::new(None),
+        })
+    }
+
+    /// Decides what happens to queued writes the remote rejects during a replay; without a handler they are kept.
+    pub fn on_conflict(
+        mut self,
+        handler: impl Fn(&QueuedWrite, &Error) -> ConflictResolution + Send + Sync + 'static,
+    ) -> Self {
+        self.on_conflict = Some(Arc::new(handler));
+        self
+    }
+
+    /// Which remote errors mean it is unreachable, rather than that it rejected the statement; by default those of
+    /// [`ErrorKind::Io`].
+    pub fn offline_when(mut self, is_offline: fn(&Error) -> bool) -> Self {
+        self.is_offline = is_offline;
+        self
+    }
+
+    pub fn remote(&self) -> &dyn ConnectionLike {
+        &*self.remote
+    }
+
+    /// The queued writes, oldest first.
+    pub async fn pending(&self) -> Result<Vec<QueuedWrite>> {
+        let _guard = self.lock.lock().await;
+
+        let sql = format!("SELECT id, sql, batch, queued_at FROM {} ORDER BY id", QUEUE_TABLE);
+        let mut writes = Vec::new();
+        for row in self.local.query_dynamic(&sql, Vec::new()).await? {
+            writes.push(QueuedWrite {
+                id:        row.get_index(0)?,
+                sql:       row.get_index(1)?,
+                params:    Vec::new(),
+                batch:     row.get_index(2)?,
+                queued_at: row.get_index(3)?,
+            });
+        }
+
+        let sql = format!("SELECT write_id, value FROM {} ORDER BY write_id, position", PARAMS_TABLE);
+        for row in self.local.query_dynamic(&sql, Vec::new()).await? {
+            let write_id: i64 = row.get_index(0)?;
+            if let Some(write) = writes.iter_mut().find(|write| write.id == write_id) {
+                write.params.push(row.values()[1].clone());
+            }
+        }
+
+        Ok(writes)
+    }
+
+    /// Sends the queued writes to the remote in order, removing each once it is applied. Stops early when the remote
+    /// is unreachable or a rejected write is kept. Replays must not overlap, so don't call this while
+    /// [`WriteQueue::run`] is running.
+    pub async fn replay(&self) -> Result<ReplayReport> {
+        let writes = self.pending().await?;
+        let mut report = ReplayReport { pending: writes.len() as u64, ..Default::default() };
+        if writes.is_empty() {
+            return Ok(report);
+        }
+
+        let create = format!(
+            "CREATE TABLE IF NOT EXISTS {} (journal TEXT NOT NULL, write_id INTEGER NOT NULL, PRIMARY KEY (journal, \
+             write_id))",
+            REPLAYED_TABLE
+        );
+        match self.remote.execute_statement(&create, Vec::new()).await {
+            Err(error) if (self.is_offline)(&error) => return Ok(report),
+            result => result?,
+        };
+
+        let mut rest = writes.as_slice();
+        while let Some(first) = rest.first() {
+            let len = match first.batch {
+                Some(batch) => rest.iter().take_while(|write| write.batch == Some(batch)).count(),
+                None => 1,
+            };
+            let (group, remaining) = rest.split_at(len);
+
+            match self.replay_group(group).await {
+                Ok(()) => report.replayed += group.len() as u64,
+                Err((_, error)) if (self.is_offline)(&error) => break,
+                Err((write, error)) => match self.on_conflict.as_ref().map(|handler| handler(write, &error)) {
+                    Some(ConflictResolution::Discard) => report.discarded += group.len() as u64,
+                    Some(ConflictResolution::Keep) | None => break,
+                },
+            }
+
+            self.remove(group).await?;
+            rest = remaining;
+        }
+
+        report.pending = rest.len() as u64;
+        if report.replayed > 0 {
+            self.forget_replayed(rest.first().map_or(i64::MAX, |write| write.id)).await;
+        }
+        Ok(report)
+    }
+
+    /// Replays the journal every `interval` until a replay fails, e.g. because the local database went away. Spawn
+    /// it on the application's runtime.
+    pub async fn run(self, interval: Duration) -> Result<()> {
+        loop {
+            self.replay().await?;
+            crate::timer::sleep(interval).await;
+        }
+    }
+
+    /// Applies `group` in one remote transaction along with its record in the replayed table, unless an earlier
+    /// replay already did.
+    async fn replay_group<'a>(&self, group: &'a [QueuedWrite]) -> std::result::Result<(), (&'a QueuedWrite, Error)> {
+        let first = &group[0];
+        // The write a failure is reported against; the replay bookkeeping counts as the group's first write.
+        let mut failed = first;
+
+        let result = self
+            .remote
+            .in_transaction(async {
+                let replayed = format!("SELECT 1 FROM {} WHERE journal = ? AND write_id = ?", REPLAYED_TABLE);
+                let params = vec![Value::Text(self.journal.to_string()), Value::Integer(first.id)];
+                if !self.remote.query_dynamic(&replayed, params.clone()).await?.is_empty() {
+                    return Ok(());
+                }
+
+                for write in group {
+                    failed = write;
+                    self.remote.execute_statement(&write.sql, write.params.clone()).await?;
+                }
+
+                failed = first;
+                let record = format!("INSERT INTO {} (journal, write_id) VALUES (?, ?)", REPLAYED_TABLE);
+                self.remote.execute_statement(&record, params).await?;
+                Ok(())
+            })
+            .await;
+
+        result.map_err(|error| (failed, error))
+    }
+
+    /// Drops the remote's records of writes before `next_id`, which are no longer in the journal to be replayed
+    /// again. Best effort: records left behind are only dropped by a later replay.
+    async fn forget_replayed(&self, next_id: i64) {
+        let sql = format!("DELETE FROM {} WHERE journal = ? AND write_id < ?", REPLAYED_TABLE);
+        let params = vec![Value::Text(self.journal.to_string()), Value::Integer(next_id)];
+        if let Err(e) = self.remote.execute_statement(&sql, params).await {
+            tracing::debug!("Keeping the replayed write records of journal {}: {}", self.journal, e);
+        }
+    }
+
+    async fn remove(&self, writes: &[QueuedWrite]) -> Result<()> {
+        self.in_journal_transaction(async {
+            for write in writes {
+                self.local.execute(&format!("DELETE FROM {} WHERE write_id = ?", PARAMS_TABLE), [write.id]).await?;
+                self.local.execute(&format!("DELETE FROM {} WHERE id = ?", QUEUE_TABLE), [write.id]).await?;
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Journals `writes`, as one batch if `batched`, and returns the id of the first.
+    async fn enqueue(&self, writes: Writes, batched: bool) -> Result<i64> {
+        self.in_journal_transaction(async {
+            let batch: Option<i64> = if batched {
+                let sql = format!("SELECT COALESCE(MAX(batch), 0) + 1 FROM {}", QUEUE_TABLE);
+                Some(self.local.query_dynamic(&sql, Vec::new()).await?[0].get_index(0)?)
+            } else {
+                None
+            };
+
+            let mut first_id = None;
+            for (sql, params) in writes {
+                let insert = format!("INSERT INTO {} (sql, batch, queued_at) VALUES (?, ?, ?)", QUEUE_TABLE);
+                self.local.execute_statement(&insert, vec![sql.into(), batch.into(), now_millis().into()]).await?;
+
+                let id = self.local.last_insert_rowid();
+                first_id.get_or_insert(id);
+                let insert = format!("INSERT INTO {} (write_id, position, value) VALUES (?, ?, ?)", PARAMS_TABLE);
+                for (position, value) in params.into_iter().enumerate() {
+                    self.local.execute_statement(&insert, vec![id.into(), (position as i64).into(), value]).await?;
+                }
+            }
+
+            Ok(first_id.unwrap_or_default())
+        })
+        .await
+    }
+
+    async fn in_journal_transaction<T: Send>(&self, work: impl Future<Output = Result<T>> + Send) -> Result<T> {
+        let _guard = self.lock.lock().await;
+
+        self.local.in_transaction(work).await
+    }
+
+    fn is_batching(&self) -> bool {
+        self.batch.lock().unwrap().is_some()
+    }
+}
+
+/// A clone shares the journal and the remote, but not the transaction of the handle it was cloned from.
+impl Clone for WriteQueue {
+    fn clone(&self) -> Self {
+        Self {
+            local:       self.local.clone(),
+            remote:      self.remote.clone(),
+            on_conflict: self.on_conflict.clone(),
+            is_offline:  self.is_offline,
+            journal:     self.journal.clone(),
+            lock:        self.lock.clone(),
+            batch:       Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ConnectionLike for WriteQueue {
+    async fn query_rows(&self, sql: &str, params: Vec<Value>) -> Result<QueryRows> {
+        self.remote.query_rows(sql, params).await
+    }
+
+    /// Inside a transaction begun while the remote was unreachable, the write is held until the transaction commits
+    /// and reports no changed rows.
+    async fn execute_statement(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+        if let Some(writes) = self.batch.lock().unwrap().as_mut() {
+            writes.push((sql.to_string(), params));
+            return Ok(0);
+        }
+
+        match self.remote.execute_statement(sql, params.clone()).await {
+            Err(error) if (self.is_offline)(&error) => {
+                let write_id = self.enqueue(vec![(sql.to_string(), params)], false).await?;
+                Err(Error::Queued { write_id })
+            }
+            result => result,
+        }
+    }
+
+    fn last_insert_rowid(&self) -> i64 {
+        self.remote.last_insert_rowid()
+    }
+
+    fn insert_strategy(&self) -> InsertStrategy {
+        self.remote.insert_strategy()
+    }
+
+    fn metrics_observer(&self) -> Option<Arc<dyn MetricsObserver>> {
+        self.remote.metrics_observer()
+    }
+
+    fn column_encryptor(&self) -> Option<Arc<dyn Encryptor>> {
+        self.remote.column_encryptor()
+    }
+
+    fn query_cache(&self) -> Option<Arc<QueryCache>> {
+        self.remote.query_cache()
+    }
+
+    fn requires_filter_on_mutation(&self) -> bool {
+        self.remote.requires_filter_on_mutation()
+    }
+
+    fn is_mvcc_enabled(&self) -> bool {
+        self.remote.is_mvcc_enabled()
+    }
+
+    fn max_parameters(&self) -> usize {
+        self.remote.max_parameters()
+    }
+
+    fn max_blob_size(&self) -> Option<usize> {
+        self.remote.max_blob_size()
+    }
+
+    /// While the remote is unreachable, the transaction's writes are held by this handle and queued as one batch when
+    /// it commits.
+    async fn begin_if_autocommit(&self) -> Result<bool> {
+        if self.is_batching() {
+            return Ok(false);
+        }
+
+        match self.remote.begin_if_autocommit().await {
+            Err(error) if (self.is_offline)(&error) => {
+                *self.batch.lock().unwrap() = Some(Vec::new());
+                Ok(true)
+            }
+            result => result,
+        }
+    }
+
+    /// Reads go to the remote, so they share its transaction.
+    async fn begin_read_if_autocommit(&self) -> Result<bool> {
+        if self.is_batching() {
+            return Ok(false);
+        }
+
+        self.remote.begin_read_if_autocommit().await
+    }
+
+    /// Committing a batch journals its writes and fails with [`Error::Queued`].
+    async fn end_transaction(&self, commit: bool) -> Result<()> {
+        let batch = self.batch.lock().unwrap().take();
+        match batch {
+            Some(writes) if commit && !writes.is_empty() => {
+                let write_id = self.enqueue(writes, true).await?;
+                Err(Error::Queued { write_id })
+            }
+            Some(_) => Ok(()),
+            None => self.remote.end_transaction(commit).await,
+        }
+    }
+}
+
+impl std::fmt::Debug for WriteQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriteQueue")
+            .field("local", &self.local)
+            .field("journal", &self.journal)
+            .field("batching", &self.is_batching())
+            .finish()
+    }
+}
+
+/// An async mutex without a guarded value, so waiting for the journal doesn't block the executor.
+#[derive(Default)]
+struct JournalLock {
+    state: Mutex<LockState>,
+}
+
+#[derive(Default)]
+struct LockState {
+    locked:  bool,
+    waiters: VecDeque<Waker>,
+}
+
+impl JournalLock {
+    fn lock(&self) -> impl Future<Output = JournalGuard<'_>> {
+        std::future::poll_fn(move |cx| {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if state.locked {
+                if !state.waiters.iter().any(|waker| waker.will_wake(cx.waker())) {
+                    state.waiters.push_back(cx.waker().clone());
+                }
+                Poll::Pending
+            } else {
+                state.locked = true;
+                Poll::Ready(JournalGuard(self))
+            }
+        })
+    }
+}
+
+struct JournalGuard<'a>(&'a JournalLock);
+
+impl Drop for JournalGuard<'_> {
+    fn drop(&mut self) {
+        let waiters = {
+            let mut state = self.0.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.locked = false;
+            std::mem::take(&mut state.waiters)
+        };
+        for waker in waiters {
+            waker.wake();
+        }
+    }
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::Builder;
+
+    /// The remote database, with a switch to make it unreachable.
+    #[derive(Clone)]
+    struct FlakyRemote {
+        conn:        Connection,
+        online:      Arc<AtomicBool>,
+        /// Commits, then reports the remote unreachable, as when the connection drops before the reply arrives.
+        lose_commit: Arc<AtomicBool>,
+    }
+
+    impl FlakyRemote {
+        fn check(&self) -> Result<()> {
+            if self.online.load(Ordering::SeqCst) {
+                Ok(())
+            } else {
+                Err(std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into())
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ConnectionLike for FlakyRemote {
+        async fn query_rows(&self, sql: &str, params: Vec<Value>) -> Result<QueryRows> {
+            self.check()?;
+            self.conn.query_rows(sql, params).await
+        }
+
+        async fn execute_statement(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+            self.check()?;
+            self.conn.execute_statement(sql, params).await
+        }
+
+        fn last_insert_rowid(&self) -> i64 {
+            self.conn.last_insert_rowid()
+        }
+
+        async fn begin_if_autocommit(&self) -> Result<bool> {
+            self.check()?;
+            ConnectionLike::begin_if_autocommit(&self.conn).await
+        }
+
+        async fn end_transaction(&self, commit: bool) -> Result<()> {
+            ConnectionLike::end_transaction(&self.conn, commit).await?;
+            if commit && self.lose_commit.swap(false, Ordering::SeqCst) {
+                self.online.store(false, Ordering::SeqCst);
+            }
+            self.check()
+        }
+    }
+
+    async fn setup() -> (WriteQueue, FlakyRemote) {
+        let remote = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        remote.execute("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT, score REAL)", ()).await.unwrap();
+        let remote = FlakyRemote {
+            conn:        remote,
+            online:      Arc::new(AtomicBool::new(false)),
+            lose_commit: Arc::new(AtomicBool::new(false)),
+        };
+
+        let local = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+        (WriteQueue::open(local, remote.clone()).await.unwrap(), remote)
+    }
+
+    async fn bodies(remote: &FlakyRemote) -> Vec<String> {
+        let rows = remote.conn.query_dynamic("SELECT body FROM notes ORDER BY id", Vec::new()).await.unwrap();
+        rows.iter().map(|row| row.get_index(0).unwrap()).collect()
+    }
+
+    const INSERT: &str = "INSERT INTO notes (id, body, score) VALUES (?, ?, ?)";
+
+    fn note(id: i64, body: &str) -> Vec<Value> {
+        vec![Value::Integer(id), Value::Text(body.to_string()), Value::Real(0.5)]
+    }
+
+    #[tokio::test]
+    async fn test_write_queue_journals_writes_while_offline() {
+        let (queue, remote) = setup().await;
+
+        let error = queue.execute_statement(INSERT, note(1, "first")).await.unwrap_err();
+        assert!(matches!(error, Error::Queued { write_id: 1 }), "{:?}", error);
+        assert!(queue.execute_statement(INSERT, note(2, "second")).await.unwrap_err().is_queued());
+        assert!(queue.query_rows("SELECT * FROM notes", Vec::new()).await.is_err());
+
+        let pending = queue.pending().await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].sql, INSERT);
+        assert_eq!(pending[0].params, note(1, "first"));
+        assert_eq!(pending[0].batch, None);
+
+        assert_eq!(queue.replay().await.unwrap(), ReplayReport { replayed: 0, discarded: 0, pending: 2 });
+
+        remote.online.store(true, Ordering::SeqCst);
+        assert_eq!(queue.replay().await.unwrap(), ReplayReport { replayed: 2, discarded: 0, pending: 0 });
+        assert_eq!(bodies(&remote).await, ["first", "second"]);
+        assert!(queue.pending().await.unwrap().is_empty());
+
+        assert_eq!(queue.execute_statement(INSERT, note(3, "online")).await.unwrap(), 1);
+        assert!(queue.pending().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_queue_replays_a_write_once_when_the_commit_reply_is_lost() {
+        let (queue, remote) = setup().await;
+        let insert = "INSERT INTO notes (body) VALUES (?)";
+        queue.execute_statement(insert, vec![Value::Text("once".to_string())]).await.unwrap_err();
+
+        remote.online.store(true, Ordering::SeqCst);
+        remote.lose_commit.store(true, Ordering::SeqCst);
+        assert_eq!(queue.replay().await.unwrap(), ReplayReport { replayed: 0, discarded: 0, pending: 1 });
+        assert_eq!(bodies(&remote).await, ["once"]);
+
+        remote.online.store(true, Ordering::SeqCst);
+        assert_eq!(queue.replay().await.unwrap(), ReplayReport { replayed: 1, discarded: 0, pending: 0 });
+        assert_eq!(bodies(&remote).await, ["once"]);
+
+        let records = format!("SELECT COUNT(*) FROM {}", REPLAYED_TABLE);
+        let rows = remote.conn.query_dynamic(&records, Vec::new()).await.unwrap();
+        assert_eq!(rows[0].get_index::<i64>(0).unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_write_queue_batches_offline_transactions() {
+        let (queue, remote) = setup().await;
+
+        assert!(queue.begin_if_autocommit().await.unwrap());
+        queue.execute_statement(INSERT, note(1, "dropped")).await.unwrap();
+        queue.end_transaction(false).await.unwrap();
+        assert!(queue.pending().await.unwrap().is_empty());
+
+        assert!(queue.begin_if_autocommit().await.unwrap());
+        assert!(!queue.begin_if_autocommit().await.unwrap());
+        assert_eq!(queue.execute_statement(INSERT, note(1, "kept")).await.unwrap(), 0);
+        queue.execute_statement(INSERT, note(1, "duplicate")).await.unwrap();
+        assert!(matches!(queue.end_transaction(true).await, Err(Error::Queued { write_id: 1 })));
+        assert!(queue.execute_statement(INSERT, note(2, "after")).await.unwrap_err().is_queued());
+
+        let pending = queue.pending().await.unwrap();
+        assert_eq!(pending[0].batch, Some(1));
+        assert_eq!(pending[1].batch, Some(1));
+        assert_eq!(pending[2].batch, None);
+
+        // The batch fails as a whole, so its first write is rolled back too.
+        remote.online.store(true, Ordering::SeqCst);
+        assert_eq!(queue.replay().await.unwrap(), ReplayReport { replayed: 0, discarded: 0, pending: 3 });
+        assert!(bodies(&remote).await.is_empty());
+
+        let queue = queue.on_conflict(|write, error| {
+            assert_eq!(write.params[1], Value::Text("duplicate".to_string()));
+            assert!(error.is_conflict());
+            ConflictResolution::Discard
+        });
+        assert_eq!(queue.replay().await.unwrap(), ReplayReport { replayed: 1, discarded: 2, pending: 0 });
+        assert_eq!(bodies(&remote).await, ["after"]);
+    }
+}
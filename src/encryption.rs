@@ -0,0 +1,231 @@
+//! Application-level encryption of `#[tursorm(encrypted)]` columns.
+//!
+//! Marked columns are stored as `BLOB`s holding the output of the connection's [`Encryptor`], configured with
+//! [`Builder::column_encryptor`](crate::Builder::column_encryptor). `NULL` is stored as is. Encryption is randomized,
+//! so encrypted columns can be read and written but not filtered, sorted or indexed on.
+//!
+//! ```ignore
+//! #[derive(Clone, Debug, Table)]
+//! pub struct Patient {
+//!     #[tursorm(primary_key, auto_increment)]
+//!     pub id:  i64,
+//!     #[tursorm(encrypted)]
+//!     pub ssn: String,
+//! }
+//!
+//! let db = Builder::new_local("app.db").column_encryptor(AesGcmEncryptor::new(&key)).build().await?;
+//! ```
+
+use std::sync::Arc;
+
+use crate::ColumnTrait;
+use crate::ConnectionLike;
+use crate::Error;
+use crate::Result;
+use crate::TableTrait;
+use crate::Value;
+
+/// Encrypts and decrypts the values of encrypted columns.
+pub trait Encryptor: std::fmt::Debug + Send + Sync {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>>;
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>>;
+}
+
+const INTEGER_TAG: u8 = 1;
+const REAL_TAG: u8 = 2;
+const TEXT_TAG: u8 = 3;
+const BLOB_TAG: u8 = 4;
+
+/// Encrypts `value` into a blob; the first plaintext byte records the value's type so it decrypts to the same value.
+pub fn encrypt_value(encryptor: &dyn Encryptor, value: Value) -> Result<Value> {
+    let plaintext = match value {
+        Value::Null => return Ok(Value::Null),
+        Value::Integer(i) => [&[INTEGER_TAG][..], &i.to_le_bytes()].concat(),
+        Value::Real(f) => [&[REAL_TAG][..], &f.to_le_bytes()].concat(),
+        Value::Text(text) => [&[TEXT_TAG][..], text.as_bytes()].concat(),
+        Value::Blob(blob) => [&[BLOB_TAG][..], &blob].concat(),
+    };
+
+    encryptor.encrypt(&plaintext).map(Value::Blob)
+}
+
+/// Reverses [`encrypt_value`] for a value read from `column`. Fails without an encryptor unless the value is `NULL`.
+pub fn decrypt_value(encryptor: Option<&dyn Encryptor>, column: &str, value: Value) -> Result<Value> {
+    let ciphertext = match value {
+        Value::Null => return Ok(Value::Null),
+        Value::Blob(ciphertext) => ciphertext,
+        other => {
+            return Err(Error::TypeConversion {
+                expected: "encrypted blob",
+                actual:   format!("{:?}", other),
+                error:    format!("column `{}`", column),
+            });
+        }
+    };
+    let encryptor = encryptor.ok_or_else(|| missing_encryptor(column))?;
+
+    let plaintext = encryptor.decrypt(&ciphertext)?;
+    let invalid = || Error::Query(format!("Invalid decrypted value in column `{}`", column));
+    let (tag, payload) = plaintext.split_first().ok_or_else(invalid)?;
+
+    match *tag {
+        INTEGER_TAG => payload.try_into().map(|bytes| Value::Integer(i64::from_le_bytes(bytes))).map_err(|_| invalid()),
+        REAL_TAG => payload.try_into().map(|bytes| Value::Real(f64::from_le_bytes(bytes))).map_err(|_| invalid()),
+        TEXT_TAG => String::from_utf8(payload.to_vec()).map(Value::Text).map_err(|_| invalid()),
+        BLOB_TAG => Ok(Value::Blob(payload.to_vec())),
+        _ => Err(invalid()),
+    }
+}
+
+fn missing_encryptor(column: &str) -> Error {
+    Error::Query(format!("Column `{}` is encrypted but the connection has no column encryptor", column))
+}
+
+pub(crate) fn is_encrypted<Table: TableTrait>(name: &str) -> bool {
+    Table::Column::all().iter().any(|column| column.name() == name && column.is_encrypted())
+}
+
+/// The connection's encryptor for a write to `Table`. Fails when the table has encrypted columns and none is
+/// configured, rather than writing them in plaintext.
+pub(crate) fn write_encryptor<Table: TableTrait>(conn: &impl ConnectionLike) -> Result<Option<Arc<dyn Encryptor>>> {
+    match Table::Column::all().iter().find(|column| column.is_encrypted()) {
+        Some(column) => conn.column_encryptor().map(Some).ok_or_else(|| missing_encryptor(column.name())),
+        None => Ok(None),
+    }
+}
+
+/// Encrypts the values of encrypted columns in place. Without an encryptor the values are left as they are, which
+/// only the SQL renderers of the builders rely on.
+pub(crate) fn encrypt_columns<Table: TableTrait>(
+    encryptor: Option<&dyn Encryptor>,
+    columns: &[&str],
+    values: &mut [Value],
+) -> Result<()> {
+    let Some(encryptor) = encryptor else {
+        return Ok(());
+    };
+
+    for (column, value) in columns.iter().zip(values.iter_mut()) {
+        if is_encrypted::<Table>(column) {
+            *value = encrypt_value(encryptor, std::mem::replace(value, Value::Null))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "with-aes-gcm")]
+mod aes_gcm_encryptor {
+    use aes_gcm::Aes256Gcm;
+    use aes_gcm::KeyInit;
+    use aes_gcm::Nonce;
+    use aes_gcm::aead::Aead;
+    use aes_gcm::aead::AeadCore;
+    use aes_gcm::aead::OsRng;
+
+    use super::*;
+
+    const NONCE_LEN: usize = 12;
+
+    /// AES-256-GCM with a random nonce per value, stored in front of the ciphertext.
+    #[derive(Clone)]
+    pub struct AesGcmEncryptor {
+        cipher: Aes256Gcm,
+    }
+
+    impl AesGcmEncryptor {
+        pub fn new(key: &[u8; 32]) -> Self {
+            Self { cipher: Aes256Gcm::new(key.into()) }
+        }
+    }
+
+    impl std::fmt::Debug for AesGcmEncryptor {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("AesGcmEncryptor").finish_non_exhaustive()
+        }
+    }
+
+    impl Encryptor for AesGcmEncryptor {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+            let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+            let ciphertext =
+                self.cipher.encrypt(&nonce, plaintext).map_err(|_| Error::Query("Encryption failed".to_string()))?;
+
+            Ok([nonce.as_slice(), &ciphertext].concat())
+        }
+
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+            if ciphertext.len() < NONCE_LEN {
+                return Err(Error::Query("Encrypted value is too short".to_string()));
+            }
+
+            let (nonce, ciphertext) = ciphertext.split_at(NONCE_LEN);
+            self.cipher
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|_| Error::Query("Decryption failed; wrong key or corrupted value".to_string()))
+        }
+    }
+}
+
+#[cfg(feature = "with-aes-gcm")]
+pub use aes_gcm_encryptor::AesGcmEncryptor;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// XORs with a fixed byte; enough to tell ciphertext from plaintext.
+    #[derive(Debug)]
+    struct XorEncryptor;
+
+    impl Encryptor for XorEncryptor {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+            Ok(plaintext.iter().map(|b| b ^ 0x5a).collect())
+        }
+
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    #[test]
+    fn test_encrypt_value_round_trips() {
+        let values =
+            [Value::Integer(-42), Value::Real(1.5), Value::Text("secret".to_string()), Value::Blob(vec![0, 1, 2])];
+
+        for value in values {
+            let encrypted = encrypt_value(&XorEncryptor, value.clone()).unwrap();
+            assert!(matches!(encrypted, Value::Blob(_)));
+            assert_ne!(encrypted, value);
+            assert_eq!(decrypt_value(Some(&XorEncryptor), "c", encrypted).unwrap(), value);
+        }
+
+        assert_eq!(encrypt_value(&XorEncryptor, Value::Null).unwrap(), Value::Null);
+        assert_eq!(decrypt_value(None, "c", Value::Null).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_decrypt_value_errors() {
+        let encrypted = encrypt_value(&XorEncryptor, Value::Integer(1)).unwrap();
+        assert!(decrypt_value(None, "ssn", encrypted).unwrap_err().to_string().contains("`ssn` is encrypted"));
+        assert!(matches!(
+            decrypt_value(Some(&XorEncryptor), "ssn", Value::Text("plain".to_string())),
+            Err(Error::TypeConversion { .. })
+        ));
+        assert!(decrypt_value(Some(&XorEncryptor), "ssn", Value::Blob(vec![0x5a ^ 9])).is_err());
+    }
+
+    #[cfg(feature = "with-aes-gcm")]
+    #[test]
+    fn test_aes_gcm_encryptor() {
+        let encryptor = AesGcmEncryptor::new(&[7; 32]);
+        let first = encryptor.encrypt(b"secret").unwrap();
+        let second = encryptor.encrypt(b"secret").unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(encryptor.decrypt(&first).unwrap(), b"secret");
+        assert!(AesGcmEncryptor::new(&[8; 32]).decrypt(&first).is_err());
+        assert!(encryptor.decrypt(&first[..4]).is_err());
+    }
+}
@@ -2,14 +2,36 @@ use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Errors returned by tursorm.
+///
+/// New variants may be added in minor releases; match on [`Error::kind`] or the `is_*` helpers when the exact variant
+/// doesn't matter.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     #[error("Database error: {0}")]
-    Database(#[from] turso::Error),
+    Database(turso::Error),
+
+    /// A UNIQUE or PRIMARY KEY constraint rejected the write; `constraint` names the columns, e.g. `users.email`.
+    #[error("Unique constraint violated: {constraint}")]
+    UniqueViolation { constraint: String },
+
+    #[error("Foreign key constraint violated")]
+    ForeignKeyViolation,
+
+    #[error("NOT NULL constraint violated: {column}")]
+    NotNullViolation { column: String },
+
+    /// The database was busy or locked.
+    #[error("Database is busy: {0}")]
+    Busy(String),
 
     #[error("Type conversion error: expected {expected}, got {actual}. Error: {error}")]
     TypeConversion { expected: &'static str, actual: String, error: String },
 
+    #[error("Value {value} is out of range for {target}")]
+    OutOfRange { target: &'static str, value: String },
+
     #[error("Unexpected null value for non-nullable field")]
     UnexpectedNull,
 
@@ -19,21 +41,217 @@ pub enum Error {
     #[error("No rows affected")]
     NoRowsAffected,
 
+    /// A lookup that required a row found none; `pk` is the primary key looked up, if the select was by primary key.
+    #[error("No {table} record found{}", pk.as_ref().map(|pk| format!(" with primary key {}", pk)).unwrap_or_default())]
+    RecordNotFound { table: &'static str, pk: Option<String> },
+
+    /// An update or delete with [`expect_affected`](crate::Update::expect_affected) changed a different number of
+    /// rows; the write was rolled back.
+    #[error("Expected {expected} affected rows, got {actual}")]
+    UnexpectedAffectedRows { expected: u64, actual: u64 },
+
+    /// An update or delete without a filter on a connection built with `require_filter_on_mutation`.
+    #[error("Refusing to update or delete every row of {table}; call allow_full_table() to do so")]
+    UnfilteredMutation { table: &'static str },
+
     #[error("Primary key must be set for update operation")]
     PrimaryKeyNotSet,
 
+    /// A value longer than its column's `max_length` or bigger than the connection's `max_blob_size`, rejected before
+    /// the write ran; `column` is e.g. `users.bio`.
+    #[error("Value of {column} is too large: {size} exceeds the limit of {max}")]
+    ValueTooLarge { column: String, size: usize, max: usize },
+
+    /// A write to a table derived with `#[tursorm(readonly)]`.
+    #[error("Table {table} is read-only")]
+    ReadOnlyTable { table: &'static str },
+
     #[error("Query error: {0}")]
     Query(String),
 
-    #[cfg(any(feature = "with-json", feature = "with-arrays"))]
+    /// A write through a [`WriteQueue`](crate::WriteQueue) while its remote was unreachable. It was
+    /// journaled locally as `write_id`, the first write of its batch if it was part of a transaction, and will be
+    /// replayed later.
+    #[error("Write queued for replay as #{write_id}")]
+    Queued { write_id: i64 },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[cfg(feature = "serde")]
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+
+    #[cfg(feature = "with-csv")]
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+/// Coarse, stable classification of an [`Error`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A row or an affected row was expected but none matched.
+    NotFound,
+    /// A unique or primary key constraint rejected the write, or a concurrent transaction wrote the same rows.
+    Conflict,
+    /// Any other constraint (NOT NULL, CHECK, FOREIGN KEY, a size limit) rejected the write.
+    Constraint,
+    /// The database was busy or locked; retrying may succeed.
+    Busy,
+    /// A value could not be converted to or from its Rust type.
+    Conversion,
+    /// The query was rejected before execution: invalid SQL, unknown columns, a missing primary key.
+    InvalidQuery,
+    Io,
+    Serialization,
+    /// The write was journaled to be replayed later instead of being applied.
+    Queued,
+    /// Any other database failure.
+    Database,
+}
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Database(err) => database_error_kind(err),
+            Error::UniqueViolation { .. } => ErrorKind::Conflict,
+            Error::ForeignKeyViolation | Error::NotNullViolation { .. } | Error::ValueTooLarge { .. } => {
+                ErrorKind::Constraint
+            }
+            Error::Busy(_) => ErrorKind::Busy,
+            Error::TypeConversion { .. } | Error::OutOfRange { .. } | Error::UnexpectedNull => ErrorKind::Conversion,
+            Error::ColumnNotFound(_)
+            | Error::PrimaryKeyNotSet
+            | Error::ReadOnlyTable { .. }
+            | Error::UnfilteredMutation { .. }
+            | Error::Query(_) => ErrorKind::InvalidQuery,
+            Error::NoRowsAffected | Error::RecordNotFound { .. } => ErrorKind::NotFound,
+            Error::UnexpectedAffectedRows { expected, actual } if actual < expected => ErrorKind::NotFound,
+            Error::UnexpectedAffectedRows { .. } => ErrorKind::InvalidQuery,
+            Error::Io(_) => ErrorKind::Io,
+            Error::Queued { .. } => ErrorKind::Queued,
+            #[cfg(feature = "serde")]
+            Error::Json(_) => ErrorKind::Serialization,
+            #[cfg(feature = "with-csv")]
+            Error::Csv(_) => ErrorKind::Serialization,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == ErrorKind::NotFound
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        self.kind() == ErrorKind::Conflict
+    }
+
+    pub fn is_unique_violation(&self) -> bool {
+        matches!(self, Error::UniqueViolation { .. })
+    }
+
+    pub fn is_foreign_key_violation(&self) -> bool {
+        matches!(self, Error::ForeignKeyViolation)
+    }
+
+    pub fn is_not_null_violation(&self) -> bool {
+        matches!(self, Error::NotNullViolation { .. })
+    }
+
+    pub fn is_queued(&self) -> bool {
+        self.kind() == ErrorKind::Queued
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.kind() == ErrorKind::Busy
+    }
+
+    /// Whether retrying the same operation, typically in a new transaction, may succeed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Database(turso::Error::SqlExecutionFailure(message)) => {
+                self.kind() == ErrorKind::Busy || message.contains("Write-write conflict")
+            }
+            _ => self.kind() == ErrorKind::Busy,
+        }
+    }
+}
+
+/// turso reports execution failures as strings, so the ones with a typed variant are recognized by their message.
+impl From<turso::Error> for Error {
+    fn from(err: turso::Error) -> Self {
+        let turso::Error::SqlExecutionFailure(message) = &err else {
+            return Error::Database(err);
+        };
+        let detail = message.strip_prefix("Runtime error: ").unwrap_or(message);
+        let subject = |rest: &str| rest.trim_end_matches(" (19)").to_string();
+
+        if let Some(rest) = detail
+            .strip_prefix("UNIQUE constraint failed: ")
+            .or_else(|| detail.strip_prefix("PRIMARY KEY constraint failed: "))
+        {
+            Error::UniqueViolation { constraint: subject(rest) }
+        } else if let Some(rest) = detail.strip_prefix("NOT NULL constraint failed: ") {
+            Error::NotNullViolation { column: subject(rest) }
+        } else if let Some(column) =
+            detail.strip_prefix("Parse error: column ").and_then(|rest| rest.strip_suffix(" is not nullable"))
+        {
+            // Literal NULLs are rejected while the statement is compiled.
+            Error::NotNullViolation { column: column.to_string() }
+        } else if detail.eq_ignore_ascii_case("foreign key constraint failed") {
+            Error::ForeignKeyViolation
+        } else if database_error_kind(&err) == ErrorKind::Busy {
+            Error::Busy(message.clone())
+        } else {
+            Error::Database(err)
+        }
+    }
+}
+
+pub(crate) fn is_busy_error(err: &turso::Error) -> bool {
+    database_error_kind(err) == ErrorKind::Busy
+}
+
+/// turso reports execution failures as strings, so they are classified by their message.
+fn database_error_kind(err: &turso::Error) -> ErrorKind {
+    let message = match err {
+        turso::Error::QueryReturnedNoRows => return ErrorKind::NotFound,
+        turso::Error::ToSqlConversionFailure(_) | turso::Error::ConversionFailure(_) => return ErrorKind::Conversion,
+        turso::Error::SqlExecutionFailure(message) => message.as_str(),
+        _ => return ErrorKind::Database,
+    };
+
+    if message.contains("UNIQUE constraint failed")
+        || message.contains("PRIMARY KEY constraint failed")
+        || message.starts_with("Conflict:")
+        || message.contains("Write-write conflict")
+    {
+        ErrorKind::Conflict
+    } else if message.contains("constraint failed") {
+        ErrorKind::Constraint
+    } else if message.contains("Database is busy") || message.contains("is locked") || message.contains("Locking error")
+    {
+        ErrorKind::Busy
+    } else if message.starts_with("Parse error") || message.contains("unexpected token") || message.contains("no such ")
+    {
+        ErrorKind::InvalidQuery
+    } else if message.starts_with("Conversion error") {
+        ErrorKind::Conversion
+    } else {
+        ErrorKind::Database
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_error_display_io() {
+        let err = Error::from(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "truncated"));
+        assert_eq!(format!("{}", err), "IO error: truncated");
+    }
+
     #[test]
     fn test_error_display_type_conversion() {
         let err = Error::TypeConversion {
@@ -47,6 +265,13 @@ mod tests {
         assert!(display.contains("Text(hello)"));
     }
 
+    #[test]
+    fn test_error_display_out_of_range() {
+        let err = Error::OutOfRange { target: "i64", value: "18446744073709551615".to_string() };
+        assert_eq!(err.to_string(), "Value 18446744073709551615 is out of range for i64");
+        assert_eq!(err.kind(), ErrorKind::Conversion);
+    }
+
     #[test]
     fn test_error_display_unexpected_null() {
         let err = Error::UnexpectedNull;
@@ -136,4 +361,73 @@ mod tests {
         let display = format!("{}", err);
         assert!(display.contains("Column not found"));
     }
+
+    #[test]
+    fn test_error_kind_of_crate_errors() {
+        assert_eq!(Error::NoRowsAffected.kind(), ErrorKind::NotFound);
+        assert!(Error::NoRowsAffected.is_not_found());
+        let not_found = Error::RecordNotFound { table: "users", pk: Some("'u1'".to_string()) };
+        assert!(not_found.is_not_found());
+        assert_eq!(not_found.to_string(), "No users record found with primary key 'u1'");
+        assert_eq!(Error::RecordNotFound { table: "users", pk: None }.to_string(), "No users record found");
+        assert!(Error::UnexpectedAffectedRows { expected: 1, actual: 0 }.is_not_found());
+        let too_many = Error::UnexpectedAffectedRows { expected: 1, actual: 40 };
+        assert_eq!(too_many.kind(), ErrorKind::InvalidQuery);
+        assert_eq!(too_many.to_string(), "Expected 1 affected rows, got 40");
+        assert_eq!(Error::UnexpectedNull.kind(), ErrorKind::Conversion);
+        assert_eq!(Error::PrimaryKeyNotSet.kind(), ErrorKind::InvalidQuery);
+        assert_eq!(Error::UnfilteredMutation { table: "users" }.kind(), ErrorKind::InvalidQuery);
+        assert_eq!(Error::from(std::io::Error::other("disk")).kind(), ErrorKind::Io);
+    }
+
+    #[test]
+    fn test_error_kind_of_database_errors() {
+        let database = |message: &str| Error::Database(turso::Error::SqlExecutionFailure(message.to_string()));
+
+        let unique = database("Runtime error: UNIQUE constraint failed: users.email (19)");
+        assert!(unique.is_conflict());
+        assert!(!unique.is_transient());
+
+        assert_eq!(
+            database("Runtime error: NOT NULL constraint failed: users.name (19)").kind(),
+            ErrorKind::Constraint
+        );
+        assert_eq!(database("Parse error: no such column: nope").kind(), ErrorKind::InvalidQuery);
+        assert_eq!(database("Corrupt database: bad page").kind(), ErrorKind::Database);
+        assert!(Error::Database(turso::Error::QueryReturnedNoRows).is_not_found());
+
+        assert!(database("Database is busy").is_transient());
+        assert!(database("Runtime error: database table is locked").is_transient());
+
+        let write_conflict = database("Write-write conflict");
+        assert!(write_conflict.is_conflict());
+        assert!(write_conflict.is_transient());
+    }
+
+    #[test]
+    fn test_typed_database_errors() {
+        let from = |message: &str| Error::from(turso::Error::SqlExecutionFailure(message.to_string()));
+
+        let unique = from("Runtime error: UNIQUE constraint failed: users.email (19)");
+        assert!(matches!(&unique, Error::UniqueViolation { constraint } if constraint == "users.email"));
+        assert!(unique.is_unique_violation());
+        assert!(unique.is_conflict());
+        assert_eq!(unique.to_string(), "Unique constraint violated: users.email");
+
+        let not_null = from("Runtime error: NOT NULL constraint failed: users.name (19)");
+        assert!(matches!(&not_null, Error::NotNullViolation { column } if column == "users.name"));
+        assert!(from("Parse error: column name is not nullable").is_not_null_violation());
+        assert_eq!(not_null.kind(), ErrorKind::Constraint);
+
+        let foreign_key = from("Runtime error: foreign key constraint failed");
+        assert!(foreign_key.is_foreign_key_violation());
+        assert_eq!(foreign_key.kind(), ErrorKind::Constraint);
+
+        let busy = from("Database is busy");
+        assert!(matches!(busy, Error::Busy(_)));
+        assert!(busy.is_busy() && busy.is_transient());
+
+        assert!(matches!(from("Parse error: no such table: nope"), Error::Database(_)));
+        assert!(matches!(Error::from(turso::Error::QueryReturnedNoRows), Error::Database(_)));
+    }
 }
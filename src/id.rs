@@ -0,0 +1,86 @@
+//! Client-side primary key generation for `#[tursorm(primary_key, default_fn = "...")]`.
+//!
+//! `default_fn` accepts the built-in names `uuid_v4`, `uuid_v7` and `nanoid`, or the path of any type implementing
+//! [`IdGenerator`]. The key is generated when a change set is inserted with its primary key left `NotSet`.
+
+/// Produces a new primary key value.
+pub trait IdGenerator {
+    type Id;
+
+    fn generate() -> Self::Id;
+}
+
+/// Random (version 4) UUIDs.
+#[cfg(feature = "with-uuid")]
+pub struct UuidV4;
+
+#[cfg(feature = "with-uuid")]
+impl IdGenerator for UuidV4 {
+    type Id = uuid::Uuid;
+
+    fn generate() -> uuid::Uuid {
+        uuid::Uuid::new_v4()
+    }
+}
+
+/// Time-ordered (version 7) UUIDs, which keep inserts at the end of the primary key index.
+#[cfg(feature = "with-uuid")]
+pub struct UuidV7;
+
+#[cfg(feature = "with-uuid")]
+impl IdGenerator for UuidV7 {
+    type Id = uuid::Uuid;
+
+    fn generate() -> uuid::Uuid {
+        uuid::Uuid::now_v7()
+    }
+}
+
+/// 21-character URL-safe nanoid strings.
+#[cfg(feature = "with-nanoid")]
+pub struct NanoId;
+
+#[cfg(feature = "with-nanoid")]
+impl IdGenerator for NanoId {
+    type Id = String;
+
+    fn generate() -> String {
+        nanoid::nanoid!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sequence;
+
+    impl IdGenerator for Sequence {
+        type Id = i64;
+
+        fn generate() -> i64 {
+            42
+        }
+    }
+
+    #[test]
+    fn test_custom_generator() {
+        assert_eq!(Sequence::generate(), 42);
+    }
+
+    #[cfg(feature = "with-uuid")]
+    #[test]
+    fn test_uuid_generators() {
+        assert_eq!(UuidV4::generate().get_version_num(), 4);
+        assert_eq!(UuidV7::generate().get_version_num(), 7);
+        assert_ne!(UuidV7::generate(), UuidV7::generate());
+    }
+
+    #[cfg(feature = "with-nanoid")]
+    #[test]
+    fn test_nanoid_generator() {
+        let id = NanoId::generate();
+        assert_eq!(id.len(), 21);
+        assert!(id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'));
+    }
+}
@@ -3,13 +3,34 @@
 pub(crate) mod connection;
 pub(crate) mod error;
 pub(crate) mod query;
+pub(crate) mod timer;
 pub(crate) mod traits;
+pub(crate) mod type_registry;
 pub(crate) mod value;
 
+pub mod backup;
+pub mod cached;
+pub mod codec;
+pub mod encryption;
+pub mod id;
+pub mod loader;
 pub mod migration;
+pub mod notify;
+pub mod query_cache;
+#[cfg(any(feature = "with-json", feature = "with-arrays"))]
+pub mod schema;
+pub mod test_utils;
 
 pub mod prelude;
 pub use prelude::*;
+// Used by the code `#[tursorm(serde)]` generates.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde_json;
 pub use traits::record::RecordDeleteExt;
 pub use traits::table::TableDeleteExt;
 pub use traits::table::TableSelectExt;
+pub use traits::table::TableUpsertExt;
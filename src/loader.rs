@@ -0,0 +1,187 @@
+//! Bulk loading of many rows with multi-row `INSERT`s inside one transaction.
+//!
+//! ```ignore
+//! let loaded = Loader::<UserTable>::from_iter(users).batch_size(500).load(&conn).await?;
+//!
+//! // With the `with-csv` feature; the header row names the columns.
+//! let loaded = Loader::<UserTable>::from_csv(std::fs::File::open("users.csv")?)?.load(&conn).await?;
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::ChangeSetTrait;
+use crate::ConnectionLike;
+use crate::Result;
+use crate::StatementKind;
+use crate::TableTrait;
+use crate::Value;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::encryption::encrypt_columns;
+use crate::encryption::write_encryptor;
+use crate::query::insert::UniqueGuard;
+use crate::query::insert::build_insert_batch;
+use crate::traits::table::ensure_sizes;
+use crate::traits::table::ensure_writable;
+
+const DEFAULT_BATCH_SIZE: usize = 500;
+
+type LoadRow = (Vec<&'static str>, Vec<Value>);
+
+pub struct Loader<Table: TableTrait> {
+    rows:       Box<dyn Iterator<Item = Result<LoadRow>> + Send>,
+    batch_size: usize,
+    _table:     PhantomData<Table>,
+}
+
+impl<Table: TableTrait> Loader<Table> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter<I>(records: I) -> Self
+    where
+        I: IntoIterator + 'static,
+        I::IntoIter: Send,
+        I::Item: Into<Table::ChangeSet>,
+    {
+        let rows = records.into_iter().map(|record| {
+            let mut change_set: Table::ChangeSet = record.into();
+            change_set.fill_generated_keys();
+            Ok(change_set.get_insert_columns_and_values())
+        });
+
+        Self::from_rows(rows)
+    }
+
+    fn from_rows(rows: impl Iterator<Item = Result<LoadRow>> + Send + 'static) -> Self {
+        Self { rows: Box::new(rows), batch_size: DEFAULT_BATCH_SIZE, _table: PhantomData }
+    }
+
+    /// Rows per `INSERT` statement, fewer when they would bind more than the connection's
+    /// [`max_parameters`](ConnectionLike::max_parameters); defaults to 500.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Inserts every row and returns how many were inserted. Nothing is kept if any row fails.
+    pub async fn load(self, conn: &impl ConnectionLike) -> Result<u64> {
+        ensure_writable::<Table>()?;
+        conn.in_transaction(self.load_batches(conn)).await
+    }
+
+    async fn load_batches(mut self, conn: &impl ConnectionLike) -> Result<u64> {
+        let encryptor = write_encryptor::<Table>(conn)?;
+        let mut guard = UniqueGuard::<Table>::new(conn, None);
+        let mut total = 0;
+
+        loop {
+            let mut batch = self.rows.by_ref().take(self.batch_size).collect::<Result<Vec<_>>>()?;
+            if batch.is_empty() {
+                return Ok(total);
+            }
+            for (columns, values) in &mut batch {
+                if let Some(guard) = &mut guard {
+                    guard.check(conn, columns, values).await?;
+                }
+                ensure_sizes::<Table>(conn.max_blob_size(), columns, values)?;
+                encrypt_columns::<Table>(encryptor.as_deref(), columns, values)?;
+            }
+
+            for (sql, params) in build_insert_batch::<Table>(batch, None, conn.max_parameters()) {
+                let timer = conn.start_query(Table::table_name(), StatementKind::Insert, &sql);
+                let result = conn.execute_statement(&sql, params).await;
+                timer.finish(result.as_ref().ok().copied());
+
+                total += result?;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "with-csv")]
+mod csv_source {
+    use super::*;
+    use crate::ColumnTrait;
+    use crate::Error;
+    use crate::value::ColumnType;
+
+    impl<Table: TableTrait> Loader<Table> {
+        /// Reads rows from CSV whose header names the table's columns, in any order; columns left out get their
+        /// defaults. Empty fields of nullable columns load as `NULL`.
+        pub fn from_csv(reader: impl std::io::Read + Send + 'static) -> Result<Self> {
+            let mut reader = csv::Reader::from_reader(reader);
+            let columns = reader
+                .headers()?
+                .iter()
+                .map(|header| {
+                    Table::Column::all()
+                        .iter()
+                        .copied()
+                        .find(|column| column.name() == header.trim())
+                        .map(|column| CsvColumn {
+                            name:        column.name(),
+                            column_type: column.column_type(),
+                            nullable:    column.is_nullable(),
+                        })
+                        .ok_or_else(|| Error::ColumnNotFound(header.to_string()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let names: Vec<&'static str> = columns.iter().map(|column| column.name).collect();
+
+            let rows = reader.into_records().map(move |record| {
+                let record = record?;
+                let line = record.position().map(|p| p.line()).unwrap_or_default();
+                let values = columns
+                    .iter()
+                    .zip(record.iter())
+                    .map(|(column, field)| column.parse(field, line))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok((names.clone(), values))
+            });
+
+            Ok(Self::from_rows(rows))
+        }
+    }
+
+    /// The parts of a column needed to parse its fields.
+    struct CsvColumn {
+        name:        &'static str,
+        column_type: ColumnType,
+        nullable:    bool,
+    }
+
+    impl CsvColumn {
+        fn parse(&self, field: &str, line: u64) -> Result<Value> {
+            if field.is_empty() && self.nullable {
+                return Ok(Value::Null);
+            }
+
+            let invalid = |expected: &'static str| Error::TypeConversion {
+                expected,
+                actual: field.to_string(),
+                error: format!("column `{}` on CSV line {}", self.name, line),
+            };
+
+            match self.column_type {
+                ColumnType::Integer => match field.trim() {
+                    "true" => Ok(Value::Integer(1)),
+                    "false" => Ok(Value::Integer(0)),
+                    trimmed => trimmed.parse().map(Value::Integer).map_err(|_| invalid("integer")),
+                },
+                ColumnType::Float => field.trim().parse().map(Value::Real).map_err(|_| invalid("float")),
+                ColumnType::Blob => {
+                    let hex = field.trim();
+                    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+                        return Err(invalid("hex blob"));
+                    }
+                    (0..hex.len())
+                        .step_by(2)
+                        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+                        .collect::<std::result::Result<Vec<u8>, _>>()
+                        .map(Value::Blob)
+                        .map_err(|_| invalid("hex blob"))
+                }
+                ColumnType::Text | ColumnType::Null => Ok(Value::Text(field.to_string())),
+            }
+        }
+    }
+}
@@ -1,14 +1,28 @@
 use std::collections::HashMap;
 
+use crate::Collation;
+use crate::Condition;
 use crate::ForeignKeyInfo;
+use crate::InsertMany;
 use crate::OnDelete;
 use crate::OnUpdate;
+use crate::Select;
+use crate::TableDeleteExt;
+use crate::connection::connection_like::ConnectionLikeExt;
 use crate::error::Result;
 use crate::traits::column::ColumnTrait;
 use crate::traits::table::TableTrait;
 use crate::value::ColumnType;
 
+/// Tables owned by tursorm itself (e.g. notifications); never treated as orphaned.
+pub(crate) const INTERNAL_TABLE_PREFIX: &str = "_tursorm_";
+
+/// Key/value metadata kept by [`Migrator::ensure_current`].
+const METADATA_TABLE: &str = "_tursorm_metadata";
+const SCHEMA_HASH_KEY: &str = "schema_hash";
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DbColumnInfo {
     pub name: String,
 
@@ -19,6 +33,10 @@ pub struct DbColumnInfo {
     pub default_value: Option<String>,
 
     pub is_primary_key: bool,
+
+    /// The `COLLATE` clause of the column definition, uppercased; `None` for the default `BINARY`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub collation: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -30,11 +48,269 @@ pub struct DbTableInfo {
     pub primary_keys: Vec<String>,
 }
 
+/// Why an index exists, as reported by `PRAGMA index_list`'s `origin` column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IndexOrigin {
+    /// Created with `CREATE INDEX`.
+    Created,
+    /// Backs a `UNIQUE` constraint in the table definition.
+    Unique,
+    /// Backs a `PRIMARY KEY` that is not a rowid alias.
+    PrimaryKey,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DbIndexInfo {
+    pub name: String,
+
+    pub columns: Vec<String>,
+
+    pub unique: bool,
+
+    pub origin: IndexOrigin,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DbForeignKeyInfo {
+    pub columns: Vec<String>,
+
+    pub references_table: String,
+
+    /// Empty when the constraint references the parent table's primary key implicitly.
+    pub references_columns: Vec<String>,
+
+    pub on_delete: Option<String>,
+
+    pub on_update: Option<String>,
+}
+
+/// The live schema of a database, as captured by [`Migrator::snapshot`].
+///
+/// A snapshot can be exported as JSON and checked against the compiled entities later with
+/// [`DatabaseSchema::verify`], e.g. in CI against a copy taken from production.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DatabaseSchema {
+    pub tables: Vec<TableSnapshot>,
+
+    /// Whether the database runs with MVCC, which creates no unique indexes; unique columns are then not reported
+    /// as missing their index.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mvcc: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableSnapshot {
+    pub name: String,
+
+    pub columns: Vec<DbColumnInfo>,
+
+    pub primary_keys: Vec<String>,
+
+    pub indexes: Vec<DbIndexInfo>,
+
+    pub foreign_keys: Vec<DbForeignKeyInfo>,
+}
+
+impl DatabaseSchema {
+    pub fn table(&self, name: &str) -> Option<&TableSnapshot> {
+        self.tables.iter().find(|table| table.name == name)
+    }
+
+    /// Compares the snapshot with the entity schemas without touching any database.
+    pub fn verify(&self, schemas: &[TableSchema]) -> DriftReport {
+        let mut report = DriftReport::default();
+
+        for schema in schemas.iter().filter(|schema| schema.view.is_none()) {
+            let table_name = schema.table_name().to_string();
+            let Some(table) = self.table(schema.table_name()) else {
+                report.drifts.push(SchemaDrift::MissingTable { table_name });
+                continue;
+            };
+
+            for entity_col in &schema.columns {
+                match table.columns.iter().find(|c| c.name == entity_col.name) {
+                    None => report.drifts.push(SchemaDrift::MissingColumn {
+                        table_name:  table_name.clone(),
+                        column_name: entity_col.name.to_string(),
+                    }),
+                    Some(db_col) => {
+                        if let Some(message) = Migrator::check_column_compatibility(entity_col, db_col) {
+                            report.drifts.push(SchemaDrift::ColumnMismatch {
+                                table_name: table_name.clone(),
+                                column_name: entity_col.name.to_string(),
+                                message,
+                            });
+                        }
+                    }
+                }
+            }
+
+            for db_col in &table.columns {
+                if !schema.columns.iter().any(|c| c.name == db_col.name) {
+                    report.drifts.push(SchemaDrift::UnexpectedColumn {
+                        table_name:  table_name.clone(),
+                        column_name: db_col.name.clone(),
+                    });
+                }
+            }
+
+            report.drifts.extend(foreign_key_drift(schema, &table.foreign_keys));
+            if self.mvcc {
+                let warnings = mvcc_unique_warnings(schema).map(|message| format!("{}: {}", table_name, message));
+                report.warnings.extend(warnings);
+            } else {
+                report.drifts.extend(unique_index_drift(schema, &table.indexes));
+            }
+        }
+
+        for table in &self.tables {
+            let known =
+                |s: &TableSchema| s.table_name == table.name || s.junction_tables.contains(&table.name.as_str());
+            if !table.name.starts_with(INTERNAL_TABLE_PREFIX) && !schemas.iter().any(known) {
+                report.drifts.push(SchemaDrift::UnexpectedTable { table_name: table.name.clone() });
+            }
+        }
+
+        report
+    }
+
+    #[cfg(any(feature = "with-json", feature = "with-arrays"))]
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    #[cfg(any(feature = "with-json", feature = "with-arrays"))]
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// One way the database differs from the entity definitions.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SchemaDrift {
+    MissingTable { table_name: String },
+
+    UnexpectedTable { table_name: String },
+
+    MissingColumn { table_name: String, column_name: String },
+
+    UnexpectedColumn { table_name: String, column_name: String },
+
+    ColumnMismatch { table_name: String, column_name: String, message: String },
+
+    MissingUniqueIndex { table_name: String, column_name: String },
+
+    UnexpectedUniqueIndex { table_name: String, column_name: String, index_name: String },
+
+    MissingForeignKey { table_name: String, column_name: String, references: String },
+
+    UnexpectedForeignKey { table_name: String, column_name: String, references: String },
+
+    ForeignKeyMismatch { table_name: String, column_name: String, expected: String, actual: String },
+}
+
+impl SchemaDrift {
+    pub fn table_name(&self) -> &str {
+        match self {
+            SchemaDrift::MissingTable { table_name }
+            | SchemaDrift::UnexpectedTable { table_name }
+            | SchemaDrift::MissingColumn { table_name, .. }
+            | SchemaDrift::UnexpectedColumn { table_name, .. }
+            | SchemaDrift::ColumnMismatch { table_name, .. }
+            | SchemaDrift::MissingUniqueIndex { table_name, .. }
+            | SchemaDrift::UnexpectedUniqueIndex { table_name, .. }
+            | SchemaDrift::MissingForeignKey { table_name, .. }
+            | SchemaDrift::UnexpectedForeignKey { table_name, .. }
+            | SchemaDrift::ForeignKeyMismatch { table_name, .. } => table_name,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            SchemaDrift::MissingTable { .. } => "Table is missing in database".to_string(),
+            SchemaDrift::UnexpectedTable { .. } => "Table exists in database but not in any schema".to_string(),
+            SchemaDrift::MissingColumn { column_name, .. } => {
+                format!("Column '{}' is missing in database", column_name)
+            }
+            SchemaDrift::UnexpectedColumn { column_name, .. } => {
+                format!("Column '{}' exists in database but not in entity definition", column_name)
+            }
+            SchemaDrift::ColumnMismatch { message, .. } => message.clone(),
+            SchemaDrift::MissingUniqueIndex { column_name, .. } => {
+                format!("Unique index on column '{}' is missing in database", column_name)
+            }
+            SchemaDrift::UnexpectedUniqueIndex { column_name, index_name, .. } => format!(
+                "Column '{}' has unique index '{}' in database but is not unique in entity definition",
+                column_name, index_name
+            ),
+            SchemaDrift::MissingForeignKey { column_name, references, .. } => {
+                format!("Foreign key on column '{}' referencing '{}' is missing in database", column_name, references)
+            }
+            SchemaDrift::UnexpectedForeignKey { column_name, references, .. } => format!(
+                "Foreign key on column '{}' referencing '{}' exists in database but not in entity definition",
+                column_name, references
+            ),
+            SchemaDrift::ForeignKeyMismatch { column_name, expected, actual, .. } => format!(
+                "Foreign key on column '{}' references '{}' in database but '{}' in entity definition",
+                column_name, actual, expected
+            ),
+        }
+    }
+}
+
+/// The result of [`Migrator::verify`]; empty when the database matches the entities.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriftReport {
+    pub drifts: Vec<SchemaDrift>,
+
+    /// Differences that are expected and don't make the report unclean, such as unique columns without an index
+    /// under MVCC.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub warnings: Vec<String>,
+}
+
+impl DriftReport {
+    pub fn is_clean(&self) -> bool {
+        self.drifts.is_empty()
+    }
+
+    pub fn for_table<'a>(&'a self, table_name: &'a str) -> impl Iterator<Item = &'a SchemaDrift> {
+        self.drifts.iter().filter(move |drift| drift.table_name() == table_name)
+    }
+
+    pub fn summary(&self) -> String {
+        if self.drifts.is_empty() {
+            return "No drift detected".to_string();
+        }
+        self.drifts.iter().map(|d| format!("{}: {}", d.table_name(), d.description())).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Fails with the summary when any drift was found, for deployments that must not start on a stale schema.
+    pub fn ensure_clean(&self) -> Result<()> {
+        if self.is_clean() {
+            Ok(())
+        } else {
+            Err(crate::Error::Query(format!("Schema drift detected:\n{}", self.summary())))
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SchemaChange {
     CreateTable { table_name: String, sql: String },
 
+    RenameTable { old_name: String, new_name: String, sql: String },
+
+    DropTable { table_name: String, sql: String },
+
     AddColumn { table_name: String, column_name: String, sql: String },
 
     DropColumn { table_name: String, column_name: String, sql: String },
@@ -45,6 +321,10 @@ pub enum SchemaChange {
 
     CreateIndex { table_name: String, index_name: String, sql: String },
 
+    CreateView { view_name: String, sql: String },
+
+    DropView { view_name: String, sql: String },
+
     Warning { table_name: String, message: String },
 }
 
@@ -54,6 +334,12 @@ impl SchemaChange {
             SchemaChange::CreateTable { table_name, .. } => {
                 format!("Create table '{}'", table_name)
             }
+            SchemaChange::RenameTable { old_name, new_name, .. } => {
+                format!("Rename table '{}' to '{}'", old_name, new_name)
+            }
+            SchemaChange::DropTable { table_name, .. } => {
+                format!("Drop table '{}'", table_name)
+            }
             SchemaChange::AddColumn { table_name, column_name, .. } => {
                 format!("Add column '{}' to table '{}'", column_name, table_name)
             }
@@ -69,6 +355,12 @@ impl SchemaChange {
             SchemaChange::CreateIndex { table_name, index_name, .. } => {
                 format!("Create index '{}' on table '{}'", index_name, table_name)
             }
+            SchemaChange::CreateView { view_name, .. } => {
+                format!("Create view '{}'", view_name)
+            }
+            SchemaChange::DropView { view_name, .. } => {
+                format!("Drop view '{}'", view_name)
+            }
             SchemaChange::Warning { table_name, message } => {
                 format!("Warning for '{}': {}", table_name, message)
             }
@@ -78,11 +370,15 @@ impl SchemaChange {
     pub fn sql_statements(&self) -> Vec<&str> {
         match self {
             SchemaChange::CreateTable { sql, .. } => vec![sql.as_str()],
+            SchemaChange::RenameTable { sql, .. } => vec![sql.as_str()],
+            SchemaChange::DropTable { sql, .. } => vec![sql.as_str()],
             SchemaChange::AddColumn { sql, .. } => vec![sql.as_str()],
             SchemaChange::DropColumn { sql, .. } => vec![sql.as_str()],
             SchemaChange::RenameColumn { sql, .. } => vec![sql.as_str()],
             SchemaChange::RecreateTable { sql, .. } => sql.iter().map(|s| s.as_str()).collect(),
             SchemaChange::CreateIndex { sql, .. } => vec![sql.as_str()],
+            SchemaChange::CreateView { sql, .. } => vec![sql.as_str()],
+            SchemaChange::DropView { sql, .. } => vec![sql.as_str()],
             SchemaChange::Warning { .. } => vec![],
         }
     }
@@ -120,6 +416,41 @@ impl SchemaDiff {
         self.changes.iter().flat_map(|c| c.sql_statements()).collect()
     }
 
+    pub fn merge(&mut self, other: SchemaDiff) {
+        self.changes.extend(other.changes);
+        self.has_changes |= other.has_changes;
+        self.has_warnings |= other.has_warnings;
+    }
+
+    /// Renders the diff as the SQL script `Migrator::apply` would run, with each change described in a comment.
+    pub fn to_sql_script(&self) -> String {
+        if !self.has_changes {
+            return self.changes.iter().map(|c| format!("-- {}\n", c.description())).collect();
+        }
+
+        let mut script = String::from("PRAGMA foreign_keys = OFF;\n");
+        for change in &self.changes {
+            script.push_str(&format!("\n-- {}\n", change.description()));
+            for sql in change.sql_statements() {
+                script.push_str(sql);
+                script.push_str(";\n");
+            }
+        }
+        script.push_str("\nPRAGMA foreign_keys = ON;\n");
+
+        script
+    }
+
+    #[cfg(any(feature = "with-json", feature = "with-arrays"))]
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    #[cfg(any(feature = "with-json", feature = "with-arrays"))]
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
     pub fn summary(&self) -> String {
         let mut lines = Vec::new();
         for change in &self.changes {
@@ -184,7 +515,7 @@ impl ForeignKeyDiff {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct MigrationOptions {
     pub allow_drop_columns: bool,
 
@@ -195,17 +526,6 @@ pub struct MigrationOptions {
     pub verbose: bool,
 }
 
-impl Default for MigrationOptions {
-    fn default() -> Self {
-        Self {
-            allow_drop_columns: false,
-            allow_drop_tables:  false,
-            dry_run:            false,
-            verbose:            false,
-        }
-    }
-}
-
 impl MigrationOptions {
     pub fn dry_run(mut self, dry_run: bool) -> Self {
         self.dry_run = dry_run;
@@ -229,8 +549,12 @@ impl MigrationOptions {
 }
 
 pub struct TableSchema {
-    table_name: &'static str,
-    columns:    Vec<TableColumnInfo>,
+    table_name:      &'static str,
+    renamed_from:    Option<&'static str>,
+    view:            Option<&'static str>,
+    docs:            Option<&'static str>,
+    junction_tables: &'static [&'static str],
+    columns:         Vec<TableColumnInfo>,
 }
 
 #[derive(Debug, Clone)]
@@ -245,6 +569,9 @@ pub struct TableColumnInfo {
 
     pub renamed_from: Option<&'static str>,
     pub foreign_key:  Option<ForeignKeyInfo>,
+    pub collation:    Option<Collation>,
+    /// The field's doc comment, for schema catalogs and admin UIs.
+    pub docs:         Option<&'static str>,
 }
 
 impl TableSchema {
@@ -262,21 +589,64 @@ impl TableSchema {
                 default_value:     col.default_value(),
                 renamed_from:      col.renamed_from(),
                 foreign_key:       col.foreign_key(),
+                collation:         col.collation(),
+                docs:              col.docs(),
             })
             .collect();
 
-        Self { table_name: Table::table_name(), columns }
+        Self {
+            table_name: Table::table_name(),
+            renamed_from: Table::renamed_from(),
+            view: Table::view_definition(),
+            docs: Table::table_docs(),
+            junction_tables: Table::junction_tables(),
+            columns,
+        }
+    }
+
+    /// The schema of a table without an entity, such as a junction table.
+    pub(crate) fn from_columns(table_name: &'static str, columns: Vec<TableColumnInfo>) -> Self {
+        Self { table_name, renamed_from: None, view: None, docs: None, junction_tables: &[], columns }
     }
 
     pub fn table_name(&self) -> &'static str {
         self.table_name
     }
 
+    pub fn renamed_from(&self) -> Option<&'static str> {
+        self.renamed_from
+    }
+
+    /// The `SELECT` a view-backed table is defined by.
+    pub fn view(&self) -> Option<&'static str> {
+        self.view
+    }
+
+    /// The doc comment of the table's struct.
+    pub fn docs(&self) -> Option<&'static str> {
+        self.docs
+    }
+
+    /// The junction tables of the table's `many_to_many` relations, which the migrator never treats as orphaned.
+    pub fn junction_tables(&self) -> &'static [&'static str] {
+        self.junction_tables
+    }
+
     pub fn columns(&self) -> &[TableColumnInfo] {
         &self.columns
     }
 }
 
+/// Deterministic fixture rows for a table, inserted by [`Migrator::seed_table`].
+pub trait SeedTrait: TableTrait {
+    fn seed_rows() -> Vec<Self::ChangeSet>;
+
+    /// Seeding is skipped when any existing row matches this condition; `None` seeds only empty tables.
+    fn seed_guard() -> Option<Condition> {
+        None
+    }
+}
+
 pub struct Migrator;
 
 impl Migrator {
@@ -308,15 +678,158 @@ impl Migrator {
         let mut combined_diff = SchemaDiff::empty();
 
         for schema in schemas {
-            let diff = Self::migrate_schema(conn, schema, &options).await?;
-            combined_diff.changes.extend(diff.changes);
-            combined_diff.has_changes |= diff.has_changes;
-            combined_diff.has_warnings |= diff.has_warnings;
+            combined_diff.merge(Self::migrate_schema(conn, schema, &options).await?);
+        }
+
+        let orphans = Self::diff_orphaned_tables(conn, schemas, &options).await?;
+        if !options.dry_run {
+            Self::apply_changes(conn, &orphans, options.verbose).await?;
+        }
+        combined_diff.merge(orphans);
+
+        Ok(combined_diff)
+    }
+
+    /// Like [`Migrator::migrate_all`], but skips introspecting the database when `schemas` hash the same as they did
+    /// on the last successful call, which keeps startup cheap with many tables or a remote database.
+    ///
+    /// The hash covers the compiled schemas only, so changes made to the database by other means go unnoticed until
+    /// the schemas change; [`Migrator::verify`] catches those.
+    pub async fn ensure_current(conn: &crate::Connection, schemas: &[TableSchema]) -> Result<SchemaDiff> {
+        let hash = schema_hash(schemas, conn.is_mvcc_enabled());
+
+        let sql = format!("CREATE TABLE IF NOT EXISTS {} (key TEXT PRIMARY KEY, value TEXT NOT NULL)", METADATA_TABLE);
+        conn.execute(&sql, ()).await?;
+
+        let sql = format!("SELECT value FROM {} WHERE key = ?", METADATA_TABLE);
+        let mut rows = conn.query(&sql, [SCHEMA_HASH_KEY]).await?;
+        if let Some(row) = rows.next().await?
+            && row.get_value(0)? == turso::Value::Text(hash.clone())
+        {
+            return Ok(SchemaDiff::empty());
+        }
+        drop(rows);
+
+        let diff = Self::migrate_all(conn, schemas).await?;
+
+        let sql = format!(
+            "INSERT INTO {} (key, value) VALUES (?, ?) ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            METADATA_TABLE
+        );
+        conn.execute(&sql, [SCHEMA_HASH_KEY, hash.as_str()]).await?;
+
+        Ok(diff)
+    }
+
+    /// Computes the changes `migrate_all` would make without modifying the database; pass the result to
+    /// [`Migrator::apply`] once it has been reviewed.
+    pub async fn plan_all(conn: &crate::Connection, schemas: &[TableSchema]) -> Result<SchemaDiff> {
+        Self::plan_all_with_options(conn, schemas, &MigrationOptions::default()).await
+    }
+
+    pub async fn plan_all_with_options(
+        conn: &crate::Connection,
+        schemas: &[TableSchema],
+        options: &MigrationOptions,
+    ) -> Result<SchemaDiff> {
+        let mut combined_diff = SchemaDiff::empty();
+
+        for schema in schemas {
+            combined_diff.merge(Self::diff_schema(conn, schema, options).await?);
         }
+        combined_diff.merge(Self::diff_orphaned_tables(conn, schemas, options).await?);
 
         Ok(combined_diff)
     }
 
+    async fn diff_orphaned_tables(
+        conn: &crate::Connection,
+        schemas: &[TableSchema],
+        options: &MigrationOptions,
+    ) -> Result<SchemaDiff> {
+        let mut diff = SchemaDiff::empty();
+        let known: std::collections::HashSet<&str> = schemas
+            .iter()
+            .flat_map(|schema| {
+                std::iter::once(schema.table_name)
+                    .chain(schema.renamed_from)
+                    .chain(schema.junction_tables.iter().copied())
+            })
+            .collect();
+
+        let mut rows = conn.query("SELECT name FROM sqlite_master WHERE type='table' ORDER BY name", ()).await?;
+        while let Some(row) = rows.next().await? {
+            let turso::Value::Text(table_name) = row.get_value(0)? else {
+                continue;
+            };
+
+            if table_name.starts_with("sqlite_")
+                || table_name.starts_with(INTERNAL_TABLE_PREFIX)
+                || known.contains(table_name.as_str())
+            {
+                continue;
+            }
+
+            if options.allow_drop_tables {
+                let sql = format!("DROP TABLE {}", quote_identifier(&table_name));
+                diff.add_change(SchemaChange::DropTable { table_name, sql });
+            } else {
+                diff.add_change(SchemaChange::Warning {
+                    table_name,
+                    message: "Table exists in database but not in any schema".to_string(),
+                });
+            }
+        }
+
+        Ok(diff)
+    }
+
+    /// Inserts `rows` if the table is empty, returning how many rows were inserted.
+    pub async fn seed<Table: TableTrait>(conn: &crate::Connection, rows: Vec<Table::ChangeSet>) -> Result<u64> {
+        Self::seed_guarded::<Table>(conn, None, rows).await
+    }
+
+    /// Inserts `rows` unless some existing row matches `existing`.
+    pub async fn seed_unless<Table: TableTrait>(
+        conn: &crate::Connection,
+        existing: Condition,
+        rows: Vec<Table::ChangeSet>,
+    ) -> Result<u64> {
+        Self::seed_guarded::<Table>(conn, Some(existing), rows).await
+    }
+
+    pub async fn seed_table<Table: SeedTrait>(conn: &crate::Connection) -> Result<u64> {
+        Self::seed_guarded::<Table>(conn, Table::seed_guard(), Table::seed_rows()).await
+    }
+
+    async fn seed_guarded<Table: TableTrait>(
+        conn: &crate::Connection,
+        guard: Option<Condition>,
+        rows: Vec<Table::ChangeSet>,
+    ) -> Result<u64> {
+        conn.in_transaction(async {
+            let existing = match guard {
+                Some(condition) => Select::<Table>::new().filter(condition),
+                None => Select::<Table>::new(),
+            };
+
+            if existing.exists(conn).await? { Ok(0) } else { InsertMany::<Table>::new(rows).exec(conn).await }
+        })
+        .await
+    }
+
+    pub async fn apply(conn: &crate::Connection, diff: &SchemaDiff) -> Result<()> {
+        Self::apply_changes(conn, diff, false).await
+    }
+
+    /// Deletes every row of the table, optionally resetting its `AUTOINCREMENT` counter so ids start at 1 again.
+    pub async fn truncate_table<Table: TableTrait>(conn: &crate::Connection, reset_autoincrement: bool) -> Result<u64> {
+        let delete = Table::truncate();
+        let delete = if reset_autoincrement { delete.reset_autoincrement() } else { delete };
+
+        delete.exec(conn).await
+    }
+
     pub async fn introspect_table(conn: &crate::Connection, table_name: &str) -> Result<Option<DbTableInfo>> {
         let exists_sql = "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name=?";
         let mut rows = conn.query(exists_sql, [table_name]).await?;
@@ -332,6 +845,12 @@ impl Migrator {
             return Ok(None);
         }
 
+        // PRAGMA table_info doesn't report collations, so they come from the table's definition.
+        let collations = Self::table_sql(conn, table_name)
+            .await?
+            .map(|sql| parse_table_definition(&sql).collations)
+            .unwrap_or_default();
+
         let pragma_sql = format!("PRAGMA table_info({})", table_name);
         let mut rows = conn.query(&pragma_sql, ()).await?;
 
@@ -372,6 +891,7 @@ impl Migrator {
             }
 
             columns.push(DbColumnInfo {
+                collation: collations.get(&name).cloned(),
                 name,
                 column_type: col_type,
                 nullable: !notnull,
@@ -383,6 +903,108 @@ impl Migrator {
         Ok(Some(DbTableInfo { name: table_name.to_string(), columns, primary_keys }))
     }
 
+    /// Lists the indexes on `table_name`, including the automatic ones behind `UNIQUE` and non-rowid `PRIMARY KEY`
+    /// constraints.
+    ///
+    /// turso does not support `PRAGMA index_list`/`index_info`, so this reads the `CREATE` statements in
+    /// `sqlite_master` instead.
+    pub async fn introspect_indexes(conn: &crate::Connection, table_name: &str) -> Result<Vec<DbIndexInfo>> {
+        let sql = "SELECT name, sql FROM sqlite_master WHERE type='index' AND tbl_name=?";
+        let mut rows = conn.query(sql, [table_name]).await?;
+
+        let mut indexes = Vec::new();
+        let mut auto_names = Vec::new();
+
+        while let Some(row) = rows.next().await? {
+            let name = match row.get_value(0)? {
+                turso::Value::Text(s) => s,
+                _ => continue,
+            };
+
+            match row.get_value(1)? {
+                turso::Value::Text(sql) => {
+                    if let Some(index) = parse_create_index_sql(&name, &sql) {
+                        indexes.push(index);
+                    }
+                }
+                _ => auto_names.push(name),
+            }
+        }
+
+        if !auto_names.is_empty()
+            && let Some(table_sql) = Self::table_sql(conn, table_name).await?
+        {
+            auto_names.sort_by_key(|name| name.rsplit('_').next().and_then(|n| n.parse::<u32>().ok()));
+            let constraints = parse_table_definition(&table_sql).indexes;
+            for (name, (columns, origin)) in auto_names.into_iter().zip(constraints) {
+                indexes.push(DbIndexInfo { name, columns, unique: true, origin });
+            }
+        }
+
+        Ok(indexes)
+    }
+
+    /// Lists the foreign keys declared on `table_name`.
+    ///
+    /// turso does not support `PRAGMA foreign_key_list`, so this parses the table's `CREATE TABLE` statement.
+    pub async fn introspect_foreign_keys(conn: &crate::Connection, table_name: &str) -> Result<Vec<DbForeignKeyInfo>> {
+        Ok(Self::table_sql(conn, table_name)
+            .await?
+            .map(|sql| parse_table_definition(&sql).foreign_keys)
+            .unwrap_or_default())
+    }
+
+    /// Captures every table's columns, indexes and foreign keys.
+    pub async fn snapshot(conn: &crate::Connection) -> Result<DatabaseSchema> {
+        let mut table_names = Vec::new();
+        let mut rows = conn.query("SELECT name FROM sqlite_master WHERE type='table' ORDER BY name", ()).await?;
+        while let Some(row) = rows.next().await? {
+            if let turso::Value::Text(name) = row.get_value(0)?
+                && !name.starts_with("sqlite_")
+            {
+                table_names.push(name);
+            }
+        }
+
+        let mut tables = Vec::new();
+        for name in table_names {
+            let Some(info) = Self::introspect_table(conn, &name).await? else { continue };
+            tables.push(TableSnapshot {
+                indexes: Self::introspect_indexes(conn, &name).await?,
+                foreign_keys: Self::introspect_foreign_keys(conn, &name).await?,
+                name,
+                columns: info.columns,
+                primary_keys: info.primary_keys,
+            });
+        }
+
+        Ok(DatabaseSchema { tables, mvcc: conn.is_mvcc_enabled() })
+    }
+
+    /// Reports how the database differs from `schemas` without changing anything.
+    ///
+    /// Pending renames are reported as drift too, since the database does not match until they are applied.
+    pub async fn verify(conn: &crate::Connection, schemas: &[TableSchema]) -> Result<DriftReport> {
+        Ok(Self::snapshot(conn).await?.verify(schemas))
+    }
+
+    async fn table_sql(conn: &crate::Connection, table_name: &str) -> Result<Option<String>> {
+        Self::schema_sql(conn, "table", table_name).await
+    }
+
+    async fn schema_sql(conn: &crate::Connection, kind: &str, name: &str) -> Result<Option<String>> {
+        let sql = "SELECT sql FROM sqlite_master WHERE type=? AND name=?";
+        let mut rows = conn.query(sql, [kind, name]).await?;
+
+        match rows.next().await? {
+            Some(row) => match row.get_value(0)? {
+                turso::Value::Text(sql) => Ok(Some(sql)),
+                _ => Ok(None),
+            },
+            None => Ok(None),
+        }
+    }
+
     pub async fn diff<Table: TableTrait>(conn: &crate::Connection) -> Result<SchemaDiff>
     where Table::Column: 'static {
         let schema = TableSchema::of::<Table>();
@@ -394,15 +1016,35 @@ impl Migrator {
         entity_schema: &TableSchema,
         options: &MigrationOptions,
     ) -> Result<SchemaDiff> {
-        let mut diff = SchemaDiff::empty();
         let table_name = entity_schema.table_name();
+        if let Some(query) = entity_schema.view {
+            return Self::diff_view(conn, table_name, query).await;
+        }
 
-        let db_table = Self::introspect_table(conn, table_name).await?;
+        let mut diff = SchemaDiff::empty();
+        let mut db_table = Self::introspect_table(conn, table_name).await?;
+
+        if db_table.is_none()
+            && let Some(old_name) = entity_schema.renamed_from
+            && let Some(old_table) = Self::introspect_table(conn, old_name).await?
+        {
+            diff.add_change(SchemaChange::RenameTable {
+                old_name: old_name.to_string(),
+                new_name: table_name.to_string(),
+                sql:      format!("ALTER TABLE {} RENAME TO {}", old_name, table_name),
+            });
+            db_table = Some(old_table);
+        }
 
         match db_table {
             None => {
-                let sql = Self::generate_create_table_sql(entity_schema);
+                let sql = Self::generate_create_table_sql(entity_schema, conn.is_mvcc_enabled());
                 diff.add_change(SchemaChange::CreateTable { table_name: table_name.to_string(), sql });
+                if conn.is_mvcc_enabled() {
+                    for message in mvcc_unique_warnings(entity_schema) {
+                        diff.add_change(SchemaChange::Warning { table_name: table_name.to_string(), message });
+                    }
+                }
             }
             Some(db_info) => {
                 let db_columns: HashMap<&str, &DbColumnInfo> =
@@ -478,23 +1120,39 @@ impl Migrator {
                     }
                 }
 
-                if !conn.is_mvcc_enabled() {
-                    for entity_col in &entity_schema.columns {
-                        if entity_col.is_unique && !entity_col.is_primary_key {
-                            let index_name = format!("idx_{}_{}_unique", table_name, entity_col.name);
-                            let has_index = Self::index_exists(conn, &index_name).await?;
+                for drift in
+                    foreign_key_drift(entity_schema, &Self::introspect_foreign_keys(conn, &db_info.name).await?)
+                {
+                    diff.add_change(SchemaChange::Warning {
+                        table_name: table_name.to_string(),
+                        message:    drift.description(),
+                    });
+                }
 
-                            if !has_index {
-                                let sql = format!(
-                                    "CREATE UNIQUE INDEX IF NOT EXISTS {} ON {} ({})",
-                                    index_name, table_name, entity_col.name
-                                );
-                                diff.add_change(SchemaChange::CreateIndex {
-                                    table_name: table_name.to_string(),
-                                    index_name,
-                                    sql,
-                                });
-                            }
+                if conn.is_mvcc_enabled() {
+                    for message in mvcc_unique_warnings(entity_schema) {
+                        diff.add_change(SchemaChange::Warning { table_name: table_name.to_string(), message });
+                    }
+                } else {
+                    let db_indexes = Self::introspect_indexes(conn, &db_info.name).await?;
+
+                    for drift in unique_index_drift(entity_schema, &db_indexes) {
+                        if let SchemaDrift::MissingUniqueIndex { column_name, .. } = &drift {
+                            let index_name = format!("idx_{}_{}_unique", table_name, column_name);
+                            let sql = format!(
+                                "CREATE UNIQUE INDEX IF NOT EXISTS {} ON {} ({})",
+                                index_name, table_name, column_name
+                            );
+                            diff.add_change(SchemaChange::CreateIndex {
+                                table_name: table_name.to_string(),
+                                index_name,
+                                sql,
+                            });
+                        } else {
+                            diff.add_change(SchemaChange::Warning {
+                                table_name: table_name.to_string(),
+                                message:    drift.description(),
+                            });
                         }
                     }
                 }
@@ -504,6 +1162,25 @@ impl Migrator {
         Ok(diff)
     }
 
+    /// Views can't be altered, so one whose definition changed is dropped and created again.
+    async fn diff_view(conn: &crate::Connection, view_name: &str, query: &str) -> Result<SchemaDiff> {
+        let mut diff = SchemaDiff::empty();
+        let create_sql = format!("CREATE VIEW {} AS {}", view_name, query);
+
+        let existing = Self::schema_sql(conn, "view", view_name).await?;
+        if existing.as_deref() != Some(create_sql.as_str()) {
+            if existing.is_some() {
+                diff.add_change(SchemaChange::DropView {
+                    view_name: view_name.to_string(),
+                    sql:       format!("DROP VIEW {}", view_name),
+                });
+            }
+            diff.add_change(SchemaChange::CreateView { view_name: view_name.to_string(), sql: create_sql });
+        }
+
+        Ok(diff)
+    }
+
     async fn migrate_schema(
         conn: &crate::Connection,
         entity_schema: &TableSchema,
@@ -511,19 +1188,23 @@ impl Migrator {
     ) -> Result<SchemaDiff> {
         let diff = Self::diff_schema(conn, entity_schema, options).await?;
 
-        if options.dry_run {
-            return Ok(diff);
+        if !options.dry_run {
+            Self::apply_changes(conn, &diff, options.verbose).await?;
         }
 
+        Ok(diff)
+    }
+
+    async fn apply_changes(conn: &crate::Connection, diff: &SchemaDiff, verbose: bool) -> Result<()> {
         conn.execute("PRAGMA foreign_keys = OFF", ()).await?;
 
         for change in &diff.changes {
-            if options.verbose {
+            if verbose {
                 eprintln!("Migration: {}", change.description());
             }
 
             for sql in change.sql_statements() {
-                if options.verbose {
+                if verbose {
                     eprintln!("  SQL: {}", sql);
                 }
                 conn.execute(sql, ()).await?;
@@ -532,27 +1213,19 @@ impl Migrator {
 
         conn.execute("PRAGMA foreign_keys = ON", ()).await?;
 
-        Ok(diff)
-    }
-
-    async fn index_exists(conn: &crate::Connection, index_name: &str) -> Result<bool> {
-        let sql = "SELECT COUNT(*) FROM sqlite_master WHERE type='index' AND name=?";
-        let mut rows = conn.query(sql, [index_name]).await?;
-
-        if let Some(row) = rows.next().await? {
-            let value = row.get_value(0)?;
-            Ok(matches!(value, turso::Value::Integer(n) if n > 0))
-        } else {
-            Ok(false)
-        }
+        Ok(())
     }
 
-    fn generate_create_table_sql(schema: &TableSchema) -> String {
+    /// Under MVCC, which can't index, unique columns are left unconstrained; inserts check them instead.
+    fn generate_create_table_sql(schema: &TableSchema, mvcc: bool) -> String {
         let mut column_defs = Vec::new();
         let mut primary_keys = Vec::new();
 
         for col in &schema.columns {
             let mut def = format!("{} {}", col.name, column_type_to_sql(col.column_type));
+            if let Some(collation) = col.collation {
+                def.push_str(&format!(" COLLATE {}", collation));
+            }
 
             if col.is_primary_key {
                 primary_keys.push(col.name);
@@ -565,13 +1238,17 @@ impl Migrator {
                 def.push_str(" NOT NULL");
             }
 
-            if col.is_unique && !col.is_primary_key {
+            let inline_unique = col.is_unique && !col.is_primary_key && !mvcc;
+            if inline_unique {
                 def.push_str(" UNIQUE");
             }
 
             if let Some(default) = col.default_value {
                 let default = Self::default_value_to_sql(default, col.column_type);
                 def.push_str(&format!(" DEFAULT {}", default));
+            } else if col.nullable && inline_unique {
+                // turso rejects inserts that omit a nullable inline-UNIQUE column unless the NULL default is explicit.
+                def.push_str(" DEFAULT NULL");
             }
 
             column_defs.push(def);
@@ -616,7 +1293,11 @@ impl Migrator {
     }
 
     fn parse_default_to_text(default: &str) -> Option<String> {
-        Some(format!("'{}'", default))
+        if default.len() >= 2 && default.starts_with('\'') && default.ends_with('\'') {
+            Some(default.to_string())
+        } else {
+            Some(format!("'{}'", default))
+        }
     }
 
     fn parse_default_to_hex_str(default: &str) -> Option<String> {
@@ -665,6 +1346,9 @@ impl Migrator {
     fn generate_add_column_sql(table_name: &str, col: &TableColumnInfo) -> String {
         let mut def =
             format!("ALTER TABLE {} ADD COLUMN {} {}", table_name, col.name, column_type_to_sql(col.column_type));
+        if let Some(collation) = col.collation {
+            def.push_str(&format!(" COLLATE {}", collation));
+        }
 
         if !col.nullable {
             if let Some(default) = col.default_value {
@@ -719,11 +1403,37 @@ impl Migrator {
             ));
         }
 
+        let entity_collation = entity_col.collation.unwrap_or(Collation::Binary).to_string();
+        let db_collation = db_col.collation.as_deref().unwrap_or("BINARY");
+        if entity_collation != db_collation {
+            return Some(format!(
+                "Column '{}' collation mismatch: entity expects {}, database has {}",
+                entity_col.name, entity_collation, db_collation
+            ));
+        }
+
         None
     }
 }
 
-fn column_type_to_sql(col_type: ColumnType) -> &'static str {
+/// FNV-1a over the schemas and the tursorm version, since a new release may migrate the same schemas differently.
+/// Stable across builds, unlike `std`'s hashers.
+fn schema_hash(schemas: &[TableSchema], mvcc: bool) -> String {
+    let mut description = format!("tursorm {} mvcc={}", env!("CARGO_PKG_VERSION"), mvcc);
+    for schema in schemas {
+        description.push_str(&format!(
+            "\n{}|{:?}|{:?}|{:?}",
+            schema.table_name, schema.renamed_from, schema.view, schema.columns
+        ));
+    }
+
+    let hash = description
+        .bytes()
+        .fold(0xcbf29ce484222325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
+
+pub(crate) fn column_type_to_sql(col_type: ColumnType) -> &'static str {
     match col_type {
         ColumnType::Integer => "INTEGER",
         ColumnType::Float => "REAL",
@@ -733,10 +1443,312 @@ fn column_type_to_sql(col_type: ColumnType) -> &'static str {
     }
 }
 
+fn foreign_key_drift(entity_schema: &TableSchema, db_foreign_keys: &[DbForeignKeyInfo]) -> Vec<SchemaDrift> {
+    let table_name = entity_schema.table_name();
+    let mut drifts = Vec::new();
+
+    for entity_col in &entity_schema.columns {
+        let db_fk = db_foreign_keys.iter().find(|fk| fk.columns.len() == 1 && fk.columns[0] == entity_col.name);
+        let column_name = entity_col.name.to_string();
+
+        match (&entity_col.foreign_key, db_fk) {
+            (Some(entity_fk), None) => drifts.push(SchemaDrift::MissingForeignKey {
+                table_name: table_name.to_string(),
+                column_name,
+                references: entity_fk.table_name.clone(),
+            }),
+            (None, Some(db_fk)) => drifts.push(SchemaDrift::UnexpectedForeignKey {
+                table_name: table_name.to_string(),
+                column_name,
+                references: db_fk.references_table.clone(),
+            }),
+            (Some(entity_fk), Some(db_fk)) => {
+                let table_differs = !entity_fk.table_name.eq_ignore_ascii_case(&db_fk.references_table);
+                let column_differs = !entity_fk.column_name.is_empty()
+                    && !db_fk.references_columns.is_empty()
+                    && db_fk.references_columns != [entity_fk.column_name.as_str()];
+
+                if table_differs || column_differs {
+                    drifts.push(SchemaDrift::ForeignKeyMismatch {
+                        table_name: table_name.to_string(),
+                        column_name,
+                        expected: entity_fk.table_name.clone(),
+                        actual: db_fk.references_table.clone(),
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    drifts
+}
+
+/// Tells which unique columns lose their index under MVCC and get checked by inserts instead.
+fn mvcc_unique_warnings(entity_schema: &TableSchema) -> impl Iterator<Item = String> + '_ {
+    entity_schema.columns.iter().filter(|col| col.is_unique && !col.is_primary_key).map(|col| {
+        format!(
+            "Column '{}' is unique but MVCC does not support indexes; inserts check its uniqueness before writing \
+             instead",
+            col.name
+        )
+    })
+}
+
+fn unique_index_drift(entity_schema: &TableSchema, db_indexes: &[DbIndexInfo]) -> Vec<SchemaDrift> {
+    let table_name = entity_schema.table_name();
+    let is_unique_index_on =
+        |index: &DbIndexInfo, column: &str| index.unique && index.columns.len() == 1 && index.columns[0] == column;
+    let mut drifts = Vec::new();
+
+    for entity_col in entity_schema.columns.iter().filter(|c| !c.is_primary_key) {
+        let index = db_indexes.iter().find(|index| is_unique_index_on(index, entity_col.name));
+
+        match index {
+            None if entity_col.is_unique => drifts.push(SchemaDrift::MissingUniqueIndex {
+                table_name:  table_name.to_string(),
+                column_name: entity_col.name.to_string(),
+            }),
+            Some(index) if !entity_col.is_unique && index.origin != IndexOrigin::PrimaryKey => {
+                drifts.push(SchemaDrift::UnexpectedUniqueIndex {
+                    table_name:  table_name.to_string(),
+                    column_name: entity_col.name.to_string(),
+                    index_name:  index.name.clone(),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    drifts
+}
+
+#[derive(Debug, Default)]
+struct TableDefinition {
+    indexes:      Vec<(Vec<String>, IndexOrigin)>,
+    foreign_keys: Vec<DbForeignKeyInfo>,
+    collations:   HashMap<String, String>,
+}
+
+/// Extracts the constraints that back automatic indexes, in declaration order, the foreign keys and the column
+/// collations from a `CREATE TABLE` statement.
+fn parse_table_definition(sql: &str) -> TableDefinition {
+    let mut definition = TableDefinition::default();
+    let Some(body) = sql.find('(').and_then(|start| sql.rfind(')').map(|end| &sql[start + 1..end])) else {
+        return definition;
+    };
+
+    let mut column_types = HashMap::new();
+
+    for part in split_top_level(body) {
+        let mut part = part.trim();
+        if starts_with_keyword(part, "CONSTRAINT") {
+            let rest = part["CONSTRAINT".len()..].trim_start();
+            part = rest.split_once(char::is_whitespace).map(|(_, rest)| rest.trim_start()).unwrap_or("");
+        }
+
+        if starts_with_keyword(part, "PRIMARY") {
+            let columns = parse_column_list(part);
+            let rowid_alias = matches!(columns.as_slice(), [column] if column_types.get(column).is_some_and(|t: &String| t == "INTEGER"));
+            if !rowid_alias {
+                definition.indexes.push((columns, IndexOrigin::PrimaryKey));
+            }
+        } else if starts_with_keyword(part, "UNIQUE") {
+            definition.indexes.push((parse_column_list(part), IndexOrigin::Unique));
+        } else if starts_with_keyword(part, "FOREIGN") {
+            let columns = parse_column_list(part);
+            if let Some(references) = find_keyword(part, "REFERENCES") {
+                definition.foreign_keys.push(parse_references(columns, &part[references..]));
+            }
+        } else if starts_with_keyword(part, "CHECK") {
+            continue;
+        } else {
+            let mut tokens = part.split_whitespace();
+            let Some(name) = tokens.next().map(unquote_identifier) else { continue };
+            let column_type = tokens.next().map(|t| t.to_uppercase()).unwrap_or_default();
+
+            if find_keyword(part, "PRIMARY").is_some() && column_type != "INTEGER" {
+                definition.indexes.push((vec![name.clone()], IndexOrigin::PrimaryKey));
+            }
+            if find_keyword(part, "UNIQUE").is_some() {
+                definition.indexes.push((vec![name.clone()], IndexOrigin::Unique));
+            }
+            if let Some(references) = find_keyword(part, "REFERENCES") {
+                definition.foreign_keys.push(parse_references(vec![name.clone()], &part[references..]));
+            }
+            if let Some(collate) = find_keyword(part, "COLLATE")
+                && let Some(collation) = part[collate + "COLLATE".len()..].split_whitespace().next()
+            {
+                definition.collations.insert(name.clone(), unquote_identifier(collation).to_uppercase());
+            }
+            column_types.insert(name, column_type);
+        }
+    }
+
+    definition
+}
+
+/// Parses `CREATE [UNIQUE] INDEX [IF NOT EXISTS] name ON table (columns)`.
+fn parse_create_index_sql(name: &str, sql: &str) -> Option<DbIndexInfo> {
+    let on = find_keyword(sql, "ON")?;
+    let unique = find_keyword(&sql[..on], "UNIQUE").is_some();
+    let columns = parse_column_list(&sql[on..]);
+
+    Some(DbIndexInfo { name: name.to_string(), columns, unique, origin: IndexOrigin::Created })
+}
+
+/// Parses `REFERENCES table [(columns)] [ON DELETE action] [ON UPDATE action]`.
+fn parse_references(columns: Vec<String>, clause: &str) -> DbForeignKeyInfo {
+    let rest = clause["REFERENCES".len()..].trim_start();
+    let table_end = rest.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(rest.len());
+    let references_table = unquote_identifier(&rest[..table_end]);
+
+    let rest = rest[table_end..].trim_start();
+    let references_columns = if rest.starts_with('(') { parse_column_list(rest) } else { Vec::new() };
+
+    let action = |event: &str| {
+        let mut words = rest.split_whitespace().map(|w| w.trim_end_matches(')').to_uppercase());
+        while let Some(word) = words.next() {
+            if word == "ON" && words.next().as_deref() == Some(event) {
+                return match words.next()?.as_str() {
+                    first @ ("SET" | "NO") => words.next().map(|second| format!("{} {}", first, second)),
+                    action => Some(action.to_string()),
+                };
+            }
+        }
+        None
+    };
+
+    DbForeignKeyInfo {
+        columns,
+        references_table,
+        references_columns,
+        on_delete: action("DELETE"),
+        on_update: action("UPDATE"),
+    }
+}
+
+/// The identifiers inside the first parenthesized list of `sql`.
+fn parse_column_list(sql: &str) -> Vec<String> {
+    let Some(start) = sql.find('(') else { return Vec::new() };
+    let end = sql[start..].find(')').map(|end| start + end).unwrap_or(sql.len());
+
+    sql[start + 1..end]
+        .split(',')
+        .filter_map(|column| column.split_whitespace().next())
+        .map(unquote_identifier)
+        .collect()
+}
+
+fn split_top_level(body: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+
+    for (i, c) in body.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '[') => quote = Some(']'),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&body[start..]);
+
+    parts
+}
+
+fn starts_with_keyword(sql: &str, keyword: &str) -> bool {
+    find_keyword(sql, keyword) == Some(0)
+}
+
+/// The byte offset of `keyword` as a whole word, ignoring case.
+fn find_keyword(sql: &str, keyword: &str) -> Option<usize> {
+    let upper = sql.to_ascii_uppercase();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    upper.match_indices(keyword).map(|(i, _)| i).find(|&i| {
+        !upper[..i].chars().next_back().is_some_and(is_word)
+            && !upper[i + keyword.len()..].chars().next().is_some_and(is_word)
+    })
+}
+
+/// Quotes a name read from the database for use in a statement.
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn unquote_identifier(identifier: &str) -> String {
+    identifier.trim_matches(|c| matches!(c, '"' | '`' | '[' | ']' | '\'')).to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_table_definition() {
+        let definition = parse_table_definition(
+            "CREATE TABLE p (a TEXT, \"b\" TEXT UNIQUE, c INTEGER REFERENCES q (id) ON DELETE SET NULL, d INTEGER, \
+             PRIMARY KEY (a), CONSTRAINT uq UNIQUE (b, c), FOREIGN KEY (d) REFERENCES r)",
+        );
+
+        assert_eq!(
+            definition.indexes,
+            vec![
+                (vec!["b".to_string()], IndexOrigin::Unique),
+                (vec!["a".to_string()], IndexOrigin::PrimaryKey),
+                (vec!["b".to_string(), "c".to_string()], IndexOrigin::Unique),
+            ]
+        );
+        assert_eq!(
+            definition.foreign_keys,
+            vec![
+                DbForeignKeyInfo {
+                    columns:            vec!["c".to_string()],
+                    references_table:   "q".to_string(),
+                    references_columns: vec!["id".to_string()],
+                    on_delete:          Some("SET NULL".to_string()),
+                    on_update:          None,
+                },
+                DbForeignKeyInfo {
+                    columns:            vec!["d".to_string()],
+                    references_table:   "r".to_string(),
+                    references_columns: vec![],
+                    on_delete:          None,
+                    on_update:          None,
+                },
+            ]
+        );
+
+        let definition = parse_table_definition("CREATE TABLE t (id INTEGER PRIMARY KEY, uniqueness TEXT)");
+        assert!(definition.indexes.is_empty());
+        assert!(definition.collations.is_empty());
+
+        let definition =
+            parse_table_definition("CREATE TABLE t (email TEXT NOT NULL COLLATE nocase UNIQUE, code TEXT)");
+        assert_eq!(definition.collations, HashMap::from([("email".to_string(), "NOCASE".to_string())]));
+    }
+
+    #[test]
+    fn test_parse_create_index_sql() {
+        let index =
+            parse_create_index_sql("idx", "CREATE UNIQUE INDEX IF NOT EXISTS idx ON t (a, \"b\" DESC)").unwrap();
+        assert_eq!(index.columns, vec!["a", "b"]);
+        assert!(index.unique);
+        assert_eq!(index.origin, IndexOrigin::Created);
+
+        assert!(!parse_create_index_sql("i", "CREATE INDEX i ON t (unique_code)").unwrap().unique);
+    }
+
     #[test]
     fn test_schema_change_description_create_table() {
         let change = SchemaChange::CreateTable {
@@ -746,6 +1758,27 @@ mod tests {
         assert_eq!(change.description(), "Create table 'users'");
     }
 
+    #[test]
+    fn test_schema_change_drop_table() {
+        let change =
+            SchemaChange::DropTable { table_name: "legacy".to_string(), sql: "DROP TABLE legacy".to_string() };
+        assert_eq!(change.description(), "Drop table 'legacy'");
+        assert_eq!(change.sql_statements(), vec!["DROP TABLE legacy"]);
+        assert!(!change.is_create_table());
+    }
+
+    #[test]
+    fn test_schema_change_rename_table() {
+        let change = SchemaChange::RenameTable {
+            old_name: "people".to_string(),
+            new_name: "users".to_string(),
+            sql:      "ALTER TABLE people RENAME TO users".to_string(),
+        };
+        assert_eq!(change.description(), "Rename table 'people' to 'users'");
+        assert_eq!(change.sql_statements(), vec!["ALTER TABLE people RENAME TO users"]);
+        assert!(!change.is_create_table());
+    }
+
     #[test]
     fn test_schema_change_description_add_column() {
         let change = SchemaChange::AddColumn {
@@ -847,6 +1880,7 @@ mod tests {
             nullable:       true,
             default_value:  None,
             is_primary_key: false,
+            collation:      None,
         };
         let col2 = col1.clone();
         assert_eq!(col1, col2);
@@ -860,6 +1894,7 @@ mod tests {
             nullable:       false,
             default_value:  None,
             is_primary_key: true,
+            collation:      None,
         };
         let debug = format!("{:?}", col);
         assert!(debug.contains("id"));
@@ -877,6 +1912,7 @@ mod tests {
                 nullable:       false,
                 default_value:  None,
                 is_primary_key: true,
+                collation:      None,
             }],
             primary_keys: vec!["id".to_string()],
         };
@@ -973,6 +2009,58 @@ mod tests {
         assert!(summary.contains("\n"));
     }
 
+    #[test]
+    fn test_schema_diff_to_sql_script() {
+        let mut diff = SchemaDiff::empty();
+        diff.add_change(SchemaChange::AddColumn {
+            table_name:  "users".to_string(),
+            column_name: "email".to_string(),
+            sql:         "ALTER TABLE users ADD COLUMN email TEXT".to_string(),
+        });
+        diff.add_change(SchemaChange::Warning { table_name: "users".to_string(), message: "careful".to_string() });
+
+        assert_eq!(
+            diff.to_sql_script(),
+            "PRAGMA foreign_keys = OFF;\n\n-- Add column 'email' to table 'users'\nALTER TABLE users ADD COLUMN email \
+             TEXT;\n\n-- Warning for 'users': careful\n\nPRAGMA foreign_keys = ON;\n"
+        );
+    }
+
+    #[test]
+    fn test_schema_diff_to_sql_script_without_changes() {
+        let mut diff = SchemaDiff::empty();
+        assert_eq!(diff.to_sql_script(), "");
+
+        diff.add_change(SchemaChange::Warning { table_name: "users".to_string(), message: "careful".to_string() });
+        assert_eq!(diff.to_sql_script(), "-- Warning for 'users': careful\n");
+    }
+
+    #[test]
+    fn test_schema_diff_merge() {
+        let mut diff = SchemaDiff::empty();
+        let mut other = SchemaDiff::empty();
+        other.add_change(SchemaChange::Warning { table_name: "users".to_string(), message: "careful".to_string() });
+
+        diff.merge(other);
+        assert_eq!(diff.changes.len(), 1);
+        assert!(diff.has_warnings);
+        assert!(!diff.has_changes);
+    }
+
+    #[cfg(any(feature = "with-json", feature = "with-arrays"))]
+    #[test]
+    fn test_schema_diff_json_round_trip() {
+        let mut diff = SchemaDiff::empty();
+        diff.add_change(SchemaChange::CreateTable {
+            table_name: "users".to_string(),
+            sql:        "CREATE TABLE users (id INTEGER)".to_string(),
+        });
+
+        let restored = SchemaDiff::from_json(&diff.to_json().unwrap()).unwrap();
+        assert_eq!(restored.all_sql(), diff.all_sql());
+        assert!(restored.has_changes);
+    }
+
     #[test]
     fn test_migration_options_default() {
         let opts = MigrationOptions::default();
@@ -982,6 +2070,12 @@ mod tests {
         assert!(!opts.verbose);
     }
 
+    #[test]
+    fn test_text_default_is_quoted_once() {
+        assert_eq!(Migrator::default_value_to_sql("active", ColumnType::Text), "'active'");
+        assert_eq!(Migrator::default_value_to_sql("'active'", ColumnType::Text), "'active'");
+    }
+
     #[test]
     fn test_migration_options_clone() {
         let opts = MigrationOptions {
@@ -1017,6 +2111,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
         let cloned = col.clone();
         assert_eq!(cloned.name, "id");
@@ -1035,6 +2131,8 @@ mod tests {
             default_value:     Some("''"),
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
         let debug = format!("{:?}", col);
         assert!(debug.contains("email"));
@@ -1053,8 +2151,12 @@ mod tests {
     #[test]
     fn test_generate_create_table_sql_basic() {
         let schema = TableSchema {
-            table_name: "users",
-            columns:    vec![
+            table_name:      "users",
+            renamed_from:    None,
+            view:            None,
+            docs:            None,
+            junction_tables: &[],
+            columns:         vec![
                 TableColumnInfo {
                     name:              "id",
                     column_type:       ColumnType::Integer,
@@ -1065,6 +2167,8 @@ mod tests {
                     default_value:     None,
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
                 TableColumnInfo {
                     name:              "name",
@@ -1076,11 +2180,13 @@ mod tests {
                     default_value:     None,
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
             ],
         };
 
-        let sql = Migrator::generate_create_table_sql(&schema);
+        let sql = Migrator::generate_create_table_sql(&schema, false);
         assert!(sql.contains("CREATE TABLE users"));
         assert!(sql.contains("id INTEGER PRIMARY KEY AUTOINCREMENT"));
         assert!(sql.contains("name TEXT NOT NULL"));
@@ -1089,8 +2195,12 @@ mod tests {
     #[test]
     fn test_generate_create_table_sql_with_unique() {
         let schema = TableSchema {
-            table_name: "users",
-            columns:    vec![
+            table_name:      "users",
+            renamed_from:    None,
+            view:            None,
+            docs:            None,
+            junction_tables: &[],
+            columns:         vec![
                 TableColumnInfo {
                     name:              "id",
                     column_type:       ColumnType::Integer,
@@ -1101,6 +2211,8 @@ mod tests {
                     default_value:     None,
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
                 TableColumnInfo {
                     name:              "email",
@@ -1112,19 +2224,55 @@ mod tests {
                     default_value:     None,
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
             ],
         };
 
-        let sql = Migrator::generate_create_table_sql(&schema);
+        let sql = Migrator::generate_create_table_sql(&schema, false);
         assert!(sql.contains("email TEXT NOT NULL UNIQUE"));
     }
 
+    #[test]
+    fn test_generate_create_table_sql_with_nullable_unique() {
+        let schema = TableSchema {
+            table_name:      "users",
+            renamed_from:    None,
+            view:            None,
+            docs:            None,
+            junction_tables: &[],
+            columns:         vec![TableColumnInfo {
+                name:              "email",
+                column_type:       ColumnType::Text,
+                nullable:          true,
+                is_primary_key:    false,
+                is_auto_increment: false,
+                is_unique:         true,
+                default_value:     None,
+                renamed_from:      None,
+                foreign_key:       None,
+                collation:         None,
+                docs:              None,
+            }],
+        };
+
+        let sql = Migrator::generate_create_table_sql(&schema, false);
+        assert!(sql.contains("email TEXT UNIQUE DEFAULT NULL"));
+
+        let mvcc_sql = Migrator::generate_create_table_sql(&schema, true);
+        assert_eq!(mvcc_sql, "CREATE TABLE users (email TEXT)");
+    }
+
     #[test]
     fn test_generate_create_table_sql_with_default() {
         let schema = TableSchema {
-            table_name: "users",
-            columns:    vec![
+            table_name:      "users",
+            renamed_from:    None,
+            view:            None,
+            docs:            None,
+            junction_tables: &[],
+            columns:         vec![
                 TableColumnInfo {
                     name:              "id",
                     column_type:       ColumnType::Integer,
@@ -1135,6 +2283,8 @@ mod tests {
                     default_value:     None,
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
                 TableColumnInfo {
                     name:              "status",
@@ -1146,19 +2296,25 @@ mod tests {
                     default_value:     Some("'active'"),
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
             ],
         };
 
-        let sql = Migrator::generate_create_table_sql(&schema);
+        let sql = Migrator::generate_create_table_sql(&schema, false);
         assert!(sql.contains("status TEXT NOT NULL DEFAULT 'active'"));
     }
 
     #[test]
     fn test_generate_create_table_sql_nullable() {
         let schema = TableSchema {
-            table_name: "users",
-            columns:    vec![
+            table_name:      "users",
+            renamed_from:    None,
+            view:            None,
+            docs:            None,
+            junction_tables: &[],
+            columns:         vec![
                 TableColumnInfo {
                     name:              "id",
                     column_type:       ColumnType::Integer,
@@ -1169,6 +2325,8 @@ mod tests {
                     default_value:     None,
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
                 TableColumnInfo {
                     name:              "bio",
@@ -1180,11 +2338,13 @@ mod tests {
                     default_value:     None,
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
             ],
         };
 
-        let sql = Migrator::generate_create_table_sql(&schema);
+        let sql = Migrator::generate_create_table_sql(&schema, false);
         assert!(sql.contains("bio TEXT"));
         assert!(!sql.contains("bio TEXT NOT NULL"));
     }
@@ -1192,8 +2352,12 @@ mod tests {
     #[test]
     fn test_generate_create_table_sql_non_auto_pk() {
         let schema = TableSchema {
-            table_name: "users",
-            columns:    vec![TableColumnInfo {
+            table_name:      "users",
+            renamed_from:    None,
+            view:            None,
+            docs:            None,
+            junction_tables: &[],
+            columns:         vec![TableColumnInfo {
                 name:              "id",
                 column_type:       ColumnType::Integer,
                 nullable:          false,
@@ -1203,10 +2367,12 @@ mod tests {
                 default_value:     None,
                 renamed_from:      None,
                 foreign_key:       None,
+                collation:         None,
+                docs:              None,
             }],
         };
 
-        let sql = Migrator::generate_create_table_sql(&schema);
+        let sql = Migrator::generate_create_table_sql(&schema, false);
         assert!(sql.contains("PRIMARY KEY (id)"));
         assert!(!sql.contains("AUTOINCREMENT"));
     }
@@ -1223,6 +2389,8 @@ mod tests {
             default_value:     Some("'active'"),
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
 
         let sql = Migrator::generate_add_column_sql("users", &col);
@@ -1241,6 +2409,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
 
         let sql = Migrator::generate_add_column_sql("users", &col);
@@ -1260,6 +2430,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
 
         let sql = Migrator::generate_add_column_sql("users", &col);
@@ -1279,6 +2451,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
 
         let sql = Migrator::generate_add_column_sql("stats", &col);
@@ -1297,6 +2471,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
 
         let sql = Migrator::generate_add_column_sql("products", &col);
@@ -1315,6 +2491,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
 
         let sql = Migrator::generate_add_column_sql("files", &col);
@@ -1333,6 +2511,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
         let db_col = DbColumnInfo {
             name:           "id".to_string(),
@@ -1340,6 +2520,7 @@ mod tests {
             nullable:       false,
             default_value:  None,
             is_primary_key: true,
+            collation:      None,
         };
 
         let result = Migrator::check_column_compatibility(&entity_col, &db_col);
@@ -1358,6 +2539,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
         let db_col = DbColumnInfo {
             name:           "age".to_string(),
@@ -1365,6 +2548,7 @@ mod tests {
             nullable:       false,
             default_value:  None,
             is_primary_key: false,
+            collation:      None,
         };
 
         let result = Migrator::check_column_compatibility(&entity_col, &db_col);
@@ -1384,6 +2568,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
         let db_col = DbColumnInfo {
             name:           "email".to_string(),
@@ -1391,6 +2577,7 @@ mod tests {
             nullable:       true,
             default_value:  None,
             is_primary_key: false,
+            collation:      None,
         };
 
         let result = Migrator::check_column_compatibility(&entity_col, &db_col);
@@ -1398,6 +2585,50 @@ mod tests {
         assert!(result.unwrap().contains("nullability mismatch"));
     }
 
+    #[test]
+    fn test_collation_in_create_table_sql_and_compatibility() {
+        let entity_col = TableColumnInfo {
+            name:              "email",
+            column_type:       ColumnType::Text,
+            nullable:          false,
+            is_primary_key:    false,
+            is_auto_increment: false,
+            is_unique:         true,
+            default_value:     None,
+            renamed_from:      None,
+            foreign_key:       None,
+            collation:         Some(Collation::NoCase),
+            docs:              None,
+        };
+        let schema = TableSchema {
+            table_name:      "users",
+            renamed_from:    None,
+            view:            None,
+            docs:            None,
+            junction_tables: &[],
+            columns:         vec![entity_col.clone()],
+        };
+        assert_eq!(
+            Migrator::generate_create_table_sql(&schema, false),
+            "CREATE TABLE users (email TEXT COLLATE NOCASE NOT NULL UNIQUE)"
+        );
+        assert!(Migrator::generate_add_column_sql("users", &entity_col).contains("email TEXT COLLATE NOCASE NOT NULL"));
+
+        let mut db_col = DbColumnInfo {
+            name:           "email".to_string(),
+            column_type:    "TEXT".to_string(),
+            nullable:       false,
+            default_value:  None,
+            is_primary_key: false,
+            collation:      None,
+        };
+        let result = Migrator::check_column_compatibility(&entity_col, &db_col);
+        assert_eq!(result.unwrap(), "Column 'email' collation mismatch: entity expects NOCASE, database has BINARY");
+
+        db_col.collation = Some("NOCASE".to_string());
+        assert!(Migrator::check_column_compatibility(&entity_col, &db_col).is_none());
+    }
+
     #[test]
     fn test_check_column_compatibility_compatible_types() {
         let entity_col = TableColumnInfo {
@@ -1410,6 +2641,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
         let db_col = DbColumnInfo {
             name:           "id".to_string(),
@@ -1417,6 +2650,7 @@ mod tests {
             nullable:       false,
             default_value:  None,
             is_primary_key: false,
+            collation:      None,
         };
 
         let result = Migrator::check_column_compatibility(&entity_col, &db_col);
@@ -1435,6 +2669,8 @@ mod tests {
             default_value:     None,
             renamed_from:      None,
             foreign_key:       None,
+            collation:         None,
+            docs:              None,
         };
         let db_col = DbColumnInfo {
             name:           "name".to_string(),
@@ -1442,6 +2678,7 @@ mod tests {
             nullable:       false,
             default_value:  None,
             is_primary_key: false,
+            collation:      None,
         };
 
         let result = Migrator::check_column_compatibility(&entity_col, &db_col);
@@ -1450,15 +2687,26 @@ mod tests {
 
     #[test]
     fn test_entity_schema_table_name() {
-        let schema = TableSchema { table_name: "my_table", columns: vec![] };
+        let schema = TableSchema {
+            table_name:      "my_table",
+            renamed_from:    None,
+            view:            None,
+            docs:            None,
+            junction_tables: &[],
+            columns:         vec![],
+        };
         assert_eq!(schema.table_name(), "my_table");
     }
 
     #[test]
     fn test_entity_schema_columns() {
         let schema = TableSchema {
-            table_name: "users",
-            columns:    vec![
+            table_name:      "users",
+            renamed_from:    None,
+            view:            None,
+            docs:            None,
+            junction_tables: &[],
+            columns:         vec![
                 TableColumnInfo {
                     name:              "id",
                     column_type:       ColumnType::Integer,
@@ -1469,6 +2717,8 @@ mod tests {
                     default_value:     None,
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
                 TableColumnInfo {
                     name:              "name",
@@ -1480,6 +2730,8 @@ mod tests {
                     default_value:     None,
                     renamed_from:      None,
                     foreign_key:       None,
+                    collation:         None,
+                    docs:              None,
                 },
             ],
         };
@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use crate::error::Result;
+use crate::timer::sleep;
+
+const NOTIFICATIONS_TABLE: &str = "_tursorm_notifications";
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub id:         i64,
+    pub channel:    String,
+    pub payload:    String,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: i64,
+}
+
+/// Listen/notify emulation on top of a shared table, for processes that share a database but no message broker.
+///
+/// Publishers append rows to `_tursorm_notifications`; subscribers poll for rows newer than the last one they saw.
+pub struct Notify;
+
+impl Notify {
+    /// Appends a notification to `channel`. The table is created by the first publish or subscribe on the database.
+    pub async fn publish(conn: &crate::Connection, channel: &str, payload: &str) -> Result<i64> {
+        let sql = format!("INSERT INTO {} (channel, payload, created_at) VALUES (?, ?, ?)", NOTIFICATIONS_TABLE);
+        let params = (channel, payload, now_millis());
+
+        match conn.execute_cached(&sql, params).await {
+            Err(err) if is_missing_table(&err) => {
+                Self::ensure_table(conn).await?;
+                conn.execute_cached(&sql, params).await?;
+            }
+            result => {
+                result?;
+            }
+        }
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Subscribes to notifications published on `channel` after this call.
+    pub async fn subscribe(conn: &crate::Connection, channel: &str) -> Result<Subscription> {
+        Self::ensure_table(conn).await?;
+
+        let sql = format!("SELECT COALESCE(MAX(id), 0) FROM {}", NOTIFICATIONS_TABLE);
+        let mut rows = conn.query(&sql, ()).await?;
+        let last_id = match rows.next().await? {
+            Some(row) => row.get_value(0)?.as_integer().copied().unwrap_or_default(),
+            None => 0,
+        };
+
+        Ok(Subscription {
+            conn: conn.clone(),
+            channel: channel.to_string(),
+            last_id,
+            pending: VecDeque::new(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        })
+    }
+
+    /// Deletes notifications older than `age`, returning how many were removed.
+    pub async fn prune(conn: &crate::Connection, age: Duration) -> Result<u64> {
+        let cutoff = now_millis().saturating_sub(age.as_millis() as i64);
+        let sql = format!("DELETE FROM {} WHERE created_at < ?", NOTIFICATIONS_TABLE);
+
+        match conn.execute(&sql, [cutoff]).await {
+            Err(err) if is_missing_table(&err) => Ok(0),
+            result => Ok(result?),
+        }
+    }
+
+    async fn ensure_table(conn: &crate::Connection) -> Result<()> {
+        conn.execute(
+            &format!(
+                "CREATE TABLE IF NOT EXISTS {} (id INTEGER PRIMARY KEY AUTOINCREMENT, channel TEXT NOT NULL, payload \
+                 TEXT NOT NULL, created_at INTEGER NOT NULL)",
+                NOTIFICATIONS_TABLE
+            ),
+            (),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+pub struct Subscription {
+    conn:          crate::Connection,
+    channel:       String,
+    last_id:       i64,
+    pending:       VecDeque<Notification>,
+    poll_interval: Duration,
+}
+
+impl Subscription {
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Returns every notification that arrived since the last call without waiting.
+    pub async fn try_recv(&mut self) -> Result<Vec<Notification>> {
+        self.fetch().await?;
+        Ok(self.pending.drain(..).collect())
+    }
+
+    /// Waits for the next notification, polling the table every `poll_interval`.
+    pub async fn recv(&mut self) -> Result<Notification> {
+        loop {
+            if let Some(notification) = self.pending.pop_front() {
+                return Ok(notification);
+            }
+
+            self.fetch().await?;
+            if self.pending.is_empty() {
+                sleep(self.poll_interval).await;
+            }
+        }
+    }
+
+    async fn fetch(&mut self) -> Result<()> {
+        let sql = format!(
+            "SELECT id, channel, payload, created_at FROM {} WHERE channel = ? AND id > ? ORDER BY id",
+            NOTIFICATIONS_TABLE
+        );
+        let mut statement = self.conn.prepare_cached(&sql).await?;
+        let mut rows = statement.query((self.channel.as_str(), self.last_id)).await?;
+
+        while let Some(row) = rows.next().await? {
+            let notification = Notification {
+                id:         row.get(0)?,
+                channel:    row.get(1)?,
+                payload:    row.get(2)?,
+                created_at: row.get(3)?,
+            };
+            self.last_id = notification.id;
+            self.pending.push_back(notification);
+        }
+
+        Ok(())
+    }
+}
+
+/// Nothing has been published or subscribed to on the database yet.
+fn is_missing_table(err: &turso::Error) -> bool {
+    err.to_string().contains(&format!("no such table: {}", NOTIFICATIONS_TABLE))
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn connect() -> crate::Connection {
+        crate::Builder::new_local(":memory:").build().await.unwrap().connect().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_subscription_sees_only_new_notifications_on_its_channel() {
+        let conn = connect().await;
+        Notify::publish(&conn, "cache", "before").await.unwrap();
+
+        let mut subscription = Notify::subscribe(&conn, "cache").await.unwrap();
+        Notify::publish(&conn, "cache", "users:1").await.unwrap();
+        Notify::publish(&conn, "other", "ignored").await.unwrap();
+        Notify::publish(&conn, "cache", "users:2").await.unwrap();
+
+        let payloads: Vec<String> =
+            subscription.try_recv().await.unwrap().into_iter().map(|notification| notification.payload).collect();
+        assert_eq!(payloads, vec!["users:1".to_string(), "users:2".to_string()]);
+        assert!(subscription.try_recv().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recv_waits_for_publish() {
+        let conn = connect().await;
+        let mut subscription = Notify::subscribe(&conn, "jobs").await.unwrap().poll_interval(Duration::from_millis(10));
+
+        let publisher = async {
+            sleep(Duration::from_millis(30)).await;
+            Notify::publish(&conn, "jobs", "wake up").await.unwrap()
+        };
+        let (received, published_id) = tokio::join!(subscription.recv(), publisher);
+
+        let received = received.unwrap();
+        assert_eq!(received.id, published_id);
+        assert_eq!(received.payload, "wake up");
+    }
+
+    #[tokio::test]
+    async fn test_prune() {
+        let conn = connect().await;
+        assert_eq!(Notify::prune(&conn, Duration::ZERO).await.unwrap(), 0);
+        Notify::publish(&conn, "cache", "stale").await.unwrap();
+
+        assert_eq!(Notify::prune(&conn, Duration::from_secs(60)).await.unwrap(), 0);
+
+        sleep(Duration::from_millis(5)).await;
+        assert_eq!(Notify::prune(&conn, Duration::ZERO).await.unwrap(), 1);
+    }
+}
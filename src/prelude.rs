@@ -1,16 +1,25 @@
 pub use turso::Row;
 pub use turso::Rows;
+pub use tursorm_macros::FromRow;
 pub use tursorm_macros::Table;
+pub use tursorm_macros::TableEnum;
 
 pub use crate::connection::prelude::*;
 pub use crate::error::Error;
+pub use crate::error::ErrorKind;
 pub use crate::error::Result;
+pub use crate::id::IdGenerator;
 pub use crate::migration::SchemaDiff;
 pub use crate::query::prelude::*;
 pub use crate::traits::prelude::*;
+pub use crate::type_registry::TypeRegistry;
+pub use crate::type_registry::ValueExt;
 pub use crate::value::ColumnType;
 pub use crate::value::FromValue;
+#[cfg(feature = "with-decimal")]
+pub use crate::value::IntoScaledValue;
 pub use crate::value::IntoValue;
 #[cfg(feature = "with-json")]
 pub use crate::value::Json;
+pub use crate::value::TryIntoValue;
 pub use crate::value::Value;
@@ -0,0 +1,154 @@
+use std::marker::PhantomData;
+
+use crate::ColumnTrait;
+use crate::Condition;
+use crate::ConnectionLike;
+use crate::Delete;
+use crate::Error;
+use crate::IntoValue;
+use crate::ManyToMany;
+use crate::Result;
+use crate::Select;
+use crate::StatementKind;
+use crate::TableTrait;
+use crate::Value;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::traits::table::ensure_writable;
+
+/// Parent keys bound per child `DELETE`.
+const KEY_CHUNK_SIZE: usize = 500;
+
+/// Deletes rows together with the rows of other tables that reference them, children first, in one transaction.
+///
+/// The migrator doesn't emit `ON DELETE CASCADE` yet, so this does in the application what the foreign key would:
+///
+/// ```ignore
+/// DeleteCascade::<UserTable>::by_id(user.id)
+///     .cascade::<PostTable>(PostColumn::UserId)
+///     .cascade::<CommentTable>(CommentColumn::AuthorId)
+///     .exec(&conn)
+///     .await?;
+/// ```
+///
+/// Only direct children are deleted; rows referencing a child need their own `DeleteCascade` on the child table.
+/// The junction rows of a many-to-many relation are deleted with [`DeleteCascade::detach`].
+#[derive(Clone, Debug)]
+pub struct DeleteCascade<Table: TableTrait> {
+    conditions: Vec<Condition>,
+    children:   Vec<CascadeChild>,
+    full_table: bool,
+    _table:     PhantomData<Table>,
+}
+
+/// Builds the `DELETE` of a child table's rows whose column holds one of the keys.
+type BuildDelete = fn(&'static str, &'static str, Vec<Value>) -> (String, Vec<Value>);
+
+/// A child table and the column holding the parent's primary key.
+#[derive(Clone, Copy, Debug)]
+struct CascadeChild {
+    table_name: &'static str,
+    column:     &'static str,
+    build:      BuildDelete,
+    writable:   fn() -> Result<()>,
+}
+
+impl<Table: TableTrait> DeleteCascade<Table> {
+    /// Deletes the parent rows matching the filters; without any, every row of the table.
+    pub fn new() -> Self {
+        Self { conditions: Vec::new(), children: Vec::new(), full_table: false, _table: PhantomData }
+    }
+
+    pub fn by_id<V: IntoValue>(id: V) -> Self {
+        Self::new().filter(Condition::eq(Table::primary_key(), id))
+    }
+
+    pub fn filter(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Allows deleting without a filter on a connection that
+    /// [requires one](crate::Builder::require_filter_on_mutation).
+    pub fn allow_full_table(mut self) -> Self {
+        self.full_table = true;
+        self
+    }
+
+    /// Also deletes the rows of `Child` whose `column` references a deleted row. Children are deleted in the order
+    /// they were added.
+    pub fn cascade<Child: TableTrait>(mut self, column: Child::Column) -> Self {
+        self.children.push(CascadeChild {
+            table_name: Child::table_name(),
+            column:     column.name(),
+            build:      build_child_delete::<Child>,
+            writable:   ensure_writable::<Child>,
+        });
+        self
+    }
+
+    /// Also deletes the junction rows of `relation` that attach a deleted row, i.e. detaches everything from it.
+    pub fn detach<Related: TableTrait>(mut self, relation: &ManyToMany<Table, Related>) -> Self {
+        self.children.push(CascadeChild {
+            table_name: relation.through(),
+            column:     relation.local_column(),
+            build:      build_junction_delete,
+            writable:   || Ok(()),
+        });
+        self
+    }
+
+    /// Deletes the children and then the parents, and returns how many parents were deleted. Nothing is deleted if
+    /// any statement fails.
+    pub async fn exec(self, conn: &impl ConnectionLike) -> Result<u64> {
+        ensure_writable::<Table>()?;
+        self.children.iter().try_for_each(|child| (child.writable)())?;
+        if self.conditions.is_empty() && !self.full_table && conn.requires_filter_on_mutation() {
+            return Err(Error::UnfilteredMutation { table: Table::table_name() });
+        }
+
+        conn.in_transaction(self.exec_cascade(conn)).await
+    }
+
+    async fn exec_cascade(self, conn: &impl ConnectionLike) -> Result<u64> {
+        let select = self.conditions.into_iter().fold(Select::<Table>::new(), Select::filter);
+        let keys = select.primary_keys(conn).await?;
+        let mut deleted = 0;
+
+        for chunk in keys.chunks(KEY_CHUNK_SIZE) {
+            for child in &self.children {
+                let (sql, params) = (child.build)(child.table_name, child.column, chunk.to_vec());
+
+                let timer = conn.start_query(child.table_name, StatementKind::Delete, &sql);
+                let result = conn.execute_statement(&sql, params).await;
+                timer.finish(result.as_ref().ok().copied());
+                result?;
+            }
+
+            deleted += Delete::<Table>::new()
+                .filter(Condition::is_in(Table::primary_key(), chunk.to_vec()))
+                .exec(conn)
+                .await?;
+        }
+
+        Ok(deleted)
+    }
+}
+
+fn build_child_delete<Child: TableTrait>(
+    _table_name: &'static str,
+    column: &'static str,
+    keys: Vec<Value>,
+) -> (String, Vec<Value>) {
+    Delete::<Child>::new().filter(Condition::in_values(column, keys)).build()
+}
+
+fn build_junction_delete(through: &'static str, column: &'static str, keys: Vec<Value>) -> (String, Vec<Value>) {
+    let condition = Condition::in_values(column, keys);
+    (format!("DELETE FROM {} WHERE {}", through, condition.sql()), condition.values().to_vec())
+}
+
+impl<Table: TableTrait> Default for DeleteCascade<Table> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,20 @@
+/// Prepends `comment` to `sql` as a `/* ... */` block. A `*/` inside the comment is broken up so it can't end the
+/// block early.
+pub(crate) fn with_comment(comment: Option<&str>, sql: String) -> String {
+    match comment {
+        Some(comment) => format!("/* {} */ {}", comment.replace("*/", "* /"), sql),
+        None => sql,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_comment() {
+        assert_eq!(with_comment(None, "SELECT 1".to_string()), "SELECT 1");
+        assert_eq!(with_comment(Some("cart:get"), "SELECT 1".to_string()), "/* cart:get */ SELECT 1");
+        assert_eq!(with_comment(Some("a */ DROP"), "SELECT 1".to_string()), "/* a * / DROP */ SELECT 1");
+    }
+}
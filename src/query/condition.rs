@@ -1,111 +1,324 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::ColumnRef;
 use crate::ColumnTrait;
 use crate::IntoValue;
+use crate::Select;
+use crate::TableTrait;
 use crate::Value;
+use crate::value::value_key;
 
 #[derive(Clone, Debug)]
 pub struct Condition {
-    pub(crate) sql:    String,
-    pub(crate) values: Vec<Value>,
+    pub(crate) sql:     String,
+    pub(crate) values:  Vec<Value>,
+    /// What a single-column condition compares, so it can be rebuilt rather than parsed back out of `sql`.
+    pub(crate) operand: Option<Operand>,
+}
+
+/// The column of a single-column condition and how it is compared.
+#[derive(Clone, Debug)]
+pub(crate) struct Operand {
+    pub(crate) column:    String,
+    pub(crate) collation: Option<Collation>,
+    pub(crate) predicate: Predicate,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Predicate {
+    /// The SQL following the column, e.g. `> ?` or `BETWEEN ? AND ?`.
+    Sql(String),
+    /// `IN (?, ...)` over the condition's values, or `NOT IN` when `negated`.
+    List { negated: bool },
+}
+
+impl Operand {
+    fn render(&self, value_count: usize) -> String {
+        let column = match self.collation {
+            Some(collation) => format!("{} COLLATE {}", self.column, collation),
+            None => self.column.clone(),
+        };
+
+        match (&self.predicate, self.collation) {
+            (Predicate::Sql(predicate), _) => format!("{} {}", column, predicate),
+            (Predicate::List { negated }, None) => {
+                let placeholders = vec!["?"; value_count].join(", ");
+                format!("{} {}IN ({})", column, if *negated { "NOT " } else { "" }, placeholders)
+            }
+            // turso hangs on a collated `IN` list, so it is spelled out as equalities.
+            (Predicate::List { negated }, Some(_)) => {
+                let equalities = vec![format!("{} = ?", column); value_count].join(" OR ");
+                if *negated { format!("NOT ({})", equalities) } else { equalities }
+            }
+        }
+    }
 }
 
 impl Condition {
-    pub fn eq<Column: ColumnTrait, V: IntoValue>(column: Column, value: V) -> Self {
-        Self { sql: format!("{} = ?", column.name()), values: vec![value.into_value()] }
+    pub fn eq<Column: ColumnRef, V: IntoValue>(column: Column, value: V) -> Self {
+        Self::on(&column.qualified_name(), "= ?", vec![value.into_value()])
     }
 
-    pub fn ne<Column: ColumnTrait, V: IntoValue>(column: Column, value: V) -> Self {
-        Self { sql: format!("{} != ?", column.name()), values: vec![value.into_value()] }
+    /// Compares two columns, typically of different tables in a join's `ON` clause:
+    /// `Condition::eq_column(MessageColumn::SenderId.of("m"), UserColumn::Id.of("sender"))`.
+    pub fn eq_column<Left: ColumnRef, Right: ColumnRef>(left: Left, right: Right) -> Self {
+        Self::on(&left.qualified_name(), &format!("= {}", right.qualified_name()), vec![])
     }
 
-    pub fn gt<Column: ColumnTrait, V: IntoValue>(column: Column, value: V) -> Self {
-        Self { sql: format!("{} > ?", column.name()), values: vec![value.into_value()] }
+    pub fn ne<Column: ColumnRef, V: IntoValue>(column: Column, value: V) -> Self {
+        Self::on(&column.qualified_name(), "!= ?", vec![value.into_value()])
     }
 
-    pub fn gte<Column: ColumnTrait, V: IntoValue>(column: Column, value: V) -> Self {
-        Self { sql: format!("{} >= ?", column.name()), values: vec![value.into_value()] }
+    pub fn gt<Column: ColumnRef, V: IntoValue>(column: Column, value: V) -> Self {
+        Self::on(&column.qualified_name(), "> ?", vec![value.into_value()])
     }
 
-    pub fn lt<Column: ColumnTrait, V: IntoValue>(column: Column, value: V) -> Self {
-        Self { sql: format!("{} < ?", column.name()), values: vec![value.into_value()] }
+    pub fn gte<Column: ColumnRef, V: IntoValue>(column: Column, value: V) -> Self {
+        Self::on(&column.qualified_name(), ">= ?", vec![value.into_value()])
     }
 
-    pub fn lte<Column: ColumnTrait, V: IntoValue>(column: Column, value: V) -> Self {
-        Self { sql: format!("{} <= ?", column.name()), values: vec![value.into_value()] }
+    pub fn lt<Column: ColumnRef, V: IntoValue>(column: Column, value: V) -> Self {
+        Self::on(&column.qualified_name(), "< ?", vec![value.into_value()])
     }
 
-    pub fn like<Column: ColumnTrait>(column: Column, pattern: impl Into<String>) -> Self {
-        Self { sql: format!("{} LIKE ?", column.name()), values: vec![Value::Text(pattern.into())] }
+    pub fn lte<Column: ColumnRef, V: IntoValue>(column: Column, value: V) -> Self {
+        Self::on(&column.qualified_name(), "<= ?", vec![value.into_value()])
     }
 
-    pub fn not_like<Column: ColumnTrait>(column: Column, pattern: impl Into<String>) -> Self {
-        Self { sql: format!("{} NOT LIKE ?", column.name()), values: vec![Value::Text(pattern.into())] }
+    pub fn like<Column: ColumnRef>(column: Column, pattern: impl Into<String>) -> Self {
+        Self::on(&column.qualified_name(), "LIKE ?", vec![Value::Text(pattern.into())])
     }
 
-    pub fn contains<Column: ColumnTrait>(column: Column, value: impl Into<String>) -> Self {
-        Self { sql: format!("{} LIKE ?", column.name()), values: vec![Value::Text(format!("%{}%", value.into()))] }
+    pub fn not_like<Column: ColumnRef>(column: Column, pattern: impl Into<String>) -> Self {
+        Self::on(&column.qualified_name(), "NOT LIKE ?", vec![Value::Text(pattern.into())])
     }
 
-    pub fn starts_with<Column: ColumnTrait>(column: Column, value: impl Into<String>) -> Self {
-        Self { sql: format!("{} LIKE ?", column.name()), values: vec![Value::Text(format!("{}%", value.into()))] }
+    pub fn contains<Column: ColumnRef>(column: Column, value: impl Into<String>) -> Self {
+        Self::on(&column.qualified_name(), "LIKE ?", vec![Value::Text(format!("%{}%", value.into()))])
     }
 
-    pub fn ends_with<Column: ColumnTrait>(column: Column, value: impl Into<String>) -> Self {
-        Self { sql: format!("{} LIKE ?", column.name()), values: vec![Value::Text(format!("%{}", value.into()))] }
+    pub fn starts_with<Column: ColumnRef>(column: Column, value: impl Into<String>) -> Self {
+        Self::on(&column.qualified_name(), "LIKE ?", vec![Value::Text(format!("{}%", value.into()))])
     }
 
-    pub fn is_null<Column: ColumnTrait>(column: Column) -> Self {
-        Self { sql: format!("{} IS NULL", column.name()), values: vec![] }
+    pub fn ends_with<Column: ColumnRef>(column: Column, value: impl Into<String>) -> Self {
+        Self::on(&column.qualified_name(), "LIKE ?", vec![Value::Text(format!("%{}", value.into()))])
     }
 
-    pub fn is_not_null<Column: ColumnTrait>(column: Column) -> Self {
-        Self { sql: format!("{} IS NOT NULL", column.name()), values: vec![] }
+    pub fn eq_ignore_case<Column: ColumnRef>(column: Column, value: impl Into<String>) -> Self {
+        Self::eq(column, value.into()).collate(Collation::NoCase)
     }
 
-    pub fn is_in<Column: ColumnTrait, V: IntoValue>(column: Column, values: Vec<V>) -> Self {
-        let placeholders: Vec<&str> = values.iter().map(|_| "?").collect();
-        Self {
-            sql:    format!("{} IN ({})", column.name(), placeholders.join(", ")),
-            values: values.into_iter().map(|v| v.into_value()).collect(),
-        }
+    /// Case-insensitive `LIKE` that doesn't depend on the connection's `case_sensitive_like` setting.
+    pub fn like_ignore_case<Column: ColumnRef>(column: Column, pattern: impl Into<String>) -> Self {
+        Self::raw(format!("LOWER({}) LIKE LOWER(?)", column.qualified_name()), vec![Value::Text(pattern.into())])
+    }
+
+    pub fn is_null<Column: ColumnRef>(column: Column) -> Self {
+        Self::on(&column.qualified_name(), "IS NULL", vec![])
     }
 
-    pub fn not_in<Column: ColumnTrait, V: IntoValue>(column: Column, values: Vec<V>) -> Self {
-        let placeholders: Vec<&str> = values.iter().map(|_| "?").collect();
-        Self {
-            sql:    format!("{} NOT IN ({})", column.name(), placeholders.join(", ")),
-            values: values.into_iter().map(|v| v.into_value()).collect(),
+    pub fn is_not_null<Column: ColumnRef>(column: Column) -> Self {
+        Self::on(&column.qualified_name(), "IS NOT NULL", vec![])
+    }
+
+    /// Null-safe equality: `column IS ?`, or `column IS NULL` for `None`.
+    pub fn is<Column: ColumnRef, V: IntoValue>(column: Column, value: Option<V>) -> Self {
+        match value {
+            Some(value) => Self::on(&column.qualified_name(), "IS ?", vec![value.into_value()]),
+            None => Self::is_null(column),
         }
     }
 
-    pub fn between<Column: ColumnTrait, V: IntoValue>(column: Column, low: V, high: V) -> Self {
-        Self { sql: format!("{} BETWEEN ? AND ?", column.name()), values: vec![low.into_value(), high.into_value()] }
+    /// An empty list matches no rows (`1 = 0`); SQL has no empty `IN ()`.
+    pub fn is_in<Column: ColumnRef, V: IntoValue>(column: Column, values: Vec<V>) -> Self {
+        Self::in_values(&column.qualified_name(), values.into_iter().map(|v| v.into_value()).collect())
     }
 
-    pub fn not_between<Column: ColumnTrait, V: IntoValue>(column: Column, low: V, high: V) -> Self {
-        Self {
-            sql:    format!("{} NOT BETWEEN ? AND ?", column.name()),
-            values: vec![low.into_value(), high.into_value()],
+    /// [`Condition::is_in`] by column name, for callers that only kept the name.
+    pub(crate) fn in_values(column: &str, values: Vec<Value>) -> Self {
+        if values.is_empty() {
+            return Self::always_false();
         }
+
+        Self::with_operand(
+            Operand { column: column.to_string(), collation: None, predicate: Predicate::List { negated: false } },
+            values,
+        )
+    }
+
+    /// Splits an `is_in` list into lists of at most `chunk_size` values that are distinct under the list's collation,
+    /// so each row the original matches is matched by exactly one of them. `None` for any other condition.
+    pub(crate) fn split_in(&self, chunk_size: usize) -> Option<Vec<Condition>> {
+        let operand =
+            self.operand.as_ref().filter(|operand| matches!(operand.predicate, Predicate::List { negated: false }))?;
+
+        let mut seen = HashSet::new();
+        let values: Vec<Value> = self
+            .values
+            .iter()
+            .filter(|value| match (value, operand.collation) {
+                (Value::Text(text), Some(collation)) => seen.insert(format!("Text({:?})", collation.normalize(text))),
+                _ => seen.insert(value_key(value)),
+            })
+            .cloned()
+            .collect();
+
+        Some(
+            values.chunks(chunk_size.max(1)).map(|chunk| Self::with_operand(operand.clone(), chunk.to_vec())).collect(),
+        )
+    }
+
+    /// An empty list matches every row (`1 = 1`).
+    pub fn not_in<Column: ColumnRef, V: IntoValue>(column: Column, values: Vec<V>) -> Self {
+        if values.is_empty() {
+            return Self::always_true();
+        }
+
+        let operand = Operand {
+            column:    column.qualified_name().into_owned(),
+            collation: None,
+            predicate: Predicate::List { negated: true },
+        };
+        Self::with_operand(operand, values.into_iter().map(|v| v.into_value()).collect())
+    }
+
+    /// `column IN (subquery)`, where `subquery` selects a single column, e.g.
+    /// `UserTable::find().columns(vec![UserColumn::Id]).filter(...)`. turso only accepts this in selects, not in the
+    /// filters of updates or deletes.
+    pub fn is_in_subquery<Column: ColumnRef, Table: TableTrait>(column: Column, subquery: Select<Table>) -> Self {
+        let (sql, values) = subquery.build();
+        Self::on(&column.qualified_name(), &format!("IN ({})", sql), values)
+    }
+
+    pub fn not_in_subquery<Column: ColumnRef, Table: TableTrait>(column: Column, subquery: Select<Table>) -> Self {
+        let (sql, values) = subquery.build();
+        Self::on(&column.qualified_name(), &format!("NOT IN ({})", sql), values)
+    }
+
+    pub fn between<Column: ColumnRef, V: IntoValue>(column: Column, low: V, high: V) -> Self {
+        Self::on(&column.qualified_name(), "BETWEEN ? AND ?", vec![low.into_value(), high.into_value()])
+    }
+
+    pub fn not_between<Column: ColumnRef, V: IntoValue>(column: Column, low: V, high: V) -> Self {
+        Self::on(&column.qualified_name(), "NOT BETWEEN ? AND ?", vec![low.into_value(), high.into_value()])
+    }
+
+    /// `column predicate`, remembering `column` as the operand.
+    fn on(column: &str, predicate: &str, values: Vec<Value>) -> Self {
+        Self::with_operand(
+            Operand {
+                column:    column.to_string(),
+                collation: None,
+                predicate: Predicate::Sql(predicate.to_string()),
+            },
+            values,
+        )
+    }
+
+    fn with_operand(operand: Operand, values: Vec<Value>) -> Self {
+        Self { sql: operand.render(values.len()), values, operand: Some(operand) }
+    }
+
+    /// Row-value comparison `(a, b) > (?, ?)`, e.g. for keyset pagination on a composite key such as
+    /// `(created_at, id)`. turso can't parse row values yet, so this renders the equivalent lexicographic form
+    /// `(a > ?) OR (a = ? AND b > ?)`.
+    pub fn tuple_gt<C: ColumnTuple, V: ValueTuple>(columns: C, values: V) -> Self {
+        Self::tuple_compare(columns, values, ">", false)
+    }
+
+    pub fn tuple_gte<C: ColumnTuple, V: ValueTuple>(columns: C, values: V) -> Self {
+        Self::tuple_compare(columns, values, ">", true)
+    }
+
+    pub fn tuple_lt<C: ColumnTuple, V: ValueTuple>(columns: C, values: V) -> Self {
+        Self::tuple_compare(columns, values, "<", false)
+    }
+
+    pub fn tuple_lte<C: ColumnTuple, V: ValueTuple>(columns: C, values: V) -> Self {
+        Self::tuple_compare(columns, values, "<", true)
+    }
+
+    fn tuple_compare<C: ColumnTuple, V: ValueTuple>(columns: C, values: V, operator: &str, or_equal: bool) -> Self {
+        const { assert!(C::LEN == V::LEN, "tuple comparisons need as many values as columns") };
+
+        let names = columns.names();
+        let values = values.into_values();
+
+        (0..names.len())
+            .map(|i| {
+                let last_operator =
+                    if or_equal && i + 1 == names.len() { format!("{}=", operator) } else { operator.to_string() };
+                let mut parts: Vec<String> = names[..i].iter().map(|name| format!("{} = ?", name)).collect();
+                parts.push(format!("{} {} ?", names[i], last_operator));
+
+                Self::raw(parts.join(" AND "), values[..=i].to_vec())
+            })
+            .reduce(Self::or)
+            .expect("column tuples are never empty")
     }
 
     pub fn raw(sql: impl Into<String>, values: Vec<Value>) -> Self {
-        Self { sql: sql.into(), values }
+        Self { sql: sql.into(), values, operand: None }
     }
 
+    /// Identity for `and`, e.g. the seed when folding a possibly empty list of filters.
+    pub fn always_true() -> Self {
+        Self::raw("1 = 1", vec![])
+    }
+
+    /// Identity for `or`.
+    pub fn always_false() -> Self {
+        Self::raw("1 = 0", vec![])
+    }
+
+    /// Both sides of `and` and `or` are parenthesized, so chains keep the grouping they were built with, e.g.
+    /// `a.and(b).or(c)` renders `((a) AND (b)) OR (c)`.
     pub fn and(self, other: Condition) -> Self {
         let mut values = self.values;
         values.extend(other.values);
-        Self { sql: format!("({}) AND ({})", self.sql, other.sql), values }
+        Self::raw(format!("({}) AND ({})", self.sql, other.sql), values)
     }
 
     pub fn or(self, other: Condition) -> Self {
         let mut values = self.values;
         values.extend(other.values);
-        Self { sql: format!("({}) OR ({})", self.sql, other.sql), values }
+        Self::raw(format!("({}) OR ({})", self.sql, other.sql), values)
     }
 
+    /// Negates the condition; also callable as `Condition::not(inner)`.
+    #[allow(clippy::should_implement_trait)]
     pub fn not(self) -> Self {
-        Self { sql: format!("NOT ({})", self.sql), values: self.values }
+        Self::raw(format!("NOT ({})", self.sql), self.values)
+    }
+
+    /// `AND`s the group built by `build` as one parenthesized condition:
+    /// `cond.and_group(|g| g.or(a).or(b))` renders `(cond) AND ((a) OR (b))`. An empty group leaves `self` as is.
+    pub fn and_group(self, build: impl FnOnce(ConditionGroup) -> ConditionGroup) -> Self {
+        match build(ConditionGroup::default()).build() {
+            Some(group) => self.and(group),
+            None => self,
+        }
+    }
+
+    /// `OR`s the group built by `build` as one parenthesized condition; an empty group leaves `self` as is.
+    pub fn or_group(self, build: impl FnOnce(ConditionGroup) -> ConditionGroup) -> Self {
+        match build(ConditionGroup::default()).build() {
+            Some(group) => self.or(group),
+            None => self,
+        }
+    }
+
+    /// Compares using `collation`, applied to the column: `name COLLATE NOCASE BETWEEN ? AND ?`. Works on the
+    /// single-column conditions (`eq`, `ne`, `gt`, `between`, `is_in`, ...); SQLite's `LIKE` ignores collations, so
+    /// use [`Condition::like_ignore_case`] for case-insensitive patterns. On other conditions, such as
+    /// [`Condition::raw`] or combinations, `COLLATE` is appended to the end of the SQL.
+    pub fn collate(self, collation: Collation) -> Self {
+        match self.operand {
+            Some(operand) => Self::with_operand(Operand { collation: Some(collation), ..operand }, self.values),
+            None => Self::raw(format!("{} COLLATE {}", self.sql, collation), self.values),
+        }
     }
 
     pub fn sql(&self) -> &str {
@@ -121,6 +334,117 @@ impl Condition {
     }
 }
 
+/// Two to four columns compared as one row value by [`Condition::tuple_gt`] and its siblings.
+pub trait ColumnTuple {
+    const LEN: usize;
+
+    fn names(&self) -> Vec<&'static str>;
+}
+
+/// The values a [`ColumnTuple`] is compared against, as a tuple of the same length.
+pub trait ValueTuple {
+    const LEN: usize;
+
+    fn into_values(self) -> Vec<Value>;
+}
+
+macro_rules! impl_tuples {
+    ($len:expr; $($column:ident $value:ident $idx:tt),+) => {
+        impl<$($column: ColumnTrait),+> ColumnTuple for ($($column,)+) {
+            const LEN: usize = $len;
+
+            fn names(&self) -> Vec<&'static str> {
+                vec![$(self.$idx.name()),+]
+            }
+        }
+
+        impl<$($value: IntoValue),+> ValueTuple for ($($value,)+) {
+            const LEN: usize = $len;
+
+            fn into_values(self) -> Vec<Value> {
+                vec![$(self.$idx.into_value()),+]
+            }
+        }
+    };
+}
+
+impl_tuples!(2; A VA 0, B VB 1);
+impl_tuples!(3; A VA 0, B VB 1, C VC 2);
+impl_tuples!(4; A VA 0, B VB 1, C VC 2, D VD 3);
+
+/// Conditions combined left to right, handed to [`Condition::and_group`] and [`Condition::or_group`].
+#[derive(Clone, Debug, Default)]
+pub struct ConditionGroup {
+    condition: Option<Condition>,
+}
+
+impl ConditionGroup {
+    pub fn and(self, condition: Condition) -> Self {
+        let condition = match self.condition {
+            Some(group) => group.and(condition),
+            None => condition,
+        };
+        Self { condition: Some(condition) }
+    }
+
+    pub fn or(self, condition: Condition) -> Self {
+        let condition = match self.condition {
+            Some(group) => group.or(condition),
+            None => condition,
+        };
+        Self { condition: Some(condition) }
+    }
+
+    pub fn and_group(self, build: impl FnOnce(ConditionGroup) -> ConditionGroup) -> Self {
+        match build(ConditionGroup::default()).build() {
+            Some(group) => self.and(group),
+            None => self,
+        }
+    }
+
+    pub fn or_group(self, build: impl FnOnce(ConditionGroup) -> ConditionGroup) -> Self {
+        match build(ConditionGroup::default()).build() {
+            Some(group) => self.or(group),
+            None => self,
+        }
+    }
+
+    /// The combined condition, or `None` if nothing was added.
+    pub fn build(self) -> Option<Condition> {
+        self.condition
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Collation {
+    Binary,
+    /// ASCII case-insensitive.
+    NoCase,
+    /// Ignores trailing spaces.
+    RTrim,
+}
+
+impl Collation {
+    /// `text` as the collation compares it, so equal strings normalize to the same key.
+    pub(crate) fn normalize(self, text: &str) -> Cow<'_, str> {
+        match self {
+            Collation::Binary => Cow::Borrowed(text),
+            Collation::NoCase => Cow::Owned(text.to_ascii_lowercase()),
+            Collation::RTrim => Cow::Borrowed(text.trim_end_matches(' ')),
+        }
+    }
+}
+
+impl std::fmt::Display for Collation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Collation::Binary => write!(f, "BINARY"),
+            Collation::NoCase => write!(f, "NOCASE"),
+            Collation::RTrim => write!(f, "RTRIM"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Order {
     Asc,
@@ -137,19 +461,72 @@ impl std::fmt::Display for Order {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Nulls {
+    First,
+    Last,
+}
+
 #[derive(Clone, Debug)]
 pub struct OrderBy {
     pub(crate) column:    String,
     pub(crate) direction: Order,
+    pub(crate) nulls:     Option<Nulls>,
 }
 
 impl OrderBy {
-    pub fn asc<Column: ColumnTrait>(column: Column) -> Self {
-        Self { column: column.name().to_string(), direction: Order::Asc }
+    pub fn new<Column: ColumnRef>(column: Column, direction: Order) -> Self {
+        Self { column: column.qualified_name().into_owned(), direction, nulls: None }
+    }
+
+    pub fn asc<Column: ColumnRef>(column: Column) -> Self {
+        Self::new(column, Order::Asc)
     }
 
-    pub fn desc<Column: ColumnTrait>(column: Column) -> Self {
-        Self { column: column.name().to_string(), direction: Order::Desc }
+    pub fn desc<Column: ColumnRef>(column: Column) -> Self {
+        Self::new(column, Order::Desc)
+    }
+
+    pub fn random() -> Self {
+        Self { column: "RANDOM()".to_string(), direction: Order::Asc, nulls: None }
+    }
+
+    /// Without this, SQLite sorts NULLs first in ascending and last in descending order.
+    pub fn nulls(mut self, nulls: Nulls) -> Self {
+        self.nulls = Some(nulls);
+        self
+    }
+
+    pub fn nulls_first(self) -> Self {
+        self.nulls(Nulls::First)
+    }
+
+    pub fn nulls_last(self) -> Self {
+        self.nulls(Nulls::Last)
+    }
+}
+
+impl std::fmt::Display for OrderBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // turso parses `NULLS FIRST/LAST` but ignores it, so NULL placement is expressed as a leading sort key.
+        match self.nulls {
+            Some(Nulls::First) => write!(f, "{} IS NOT NULL, ", self.column)?,
+            Some(Nulls::Last) => write!(f, "{} IS NULL, ", self.column)?,
+            None => {}
+        }
+        write!(f, "{} {}", self.column, self.direction)
+    }
+}
+
+impl<Column: ColumnRef> From<(Column, Order)> for OrderBy {
+    fn from((column, direction): (Column, Order)) -> Self {
+        Self::new(column, direction)
+    }
+}
+
+impl<Column: ColumnRef> From<(Column, Order, Nulls)> for OrderBy {
+    fn from((column, direction, nulls): (Column, Order, Nulls)) -> Self {
+        Self::new(column, direction).nulls(nulls)
     }
 }
 
@@ -306,7 +683,11 @@ mod tests {
     #[test]
     fn test_condition_is_in_empty() {
         let cond = Condition::is_in(TestColumn::Id, Vec::<i64>::new());
-        assert_eq!(cond.sql(), "id IN ()");
+        assert_eq!(cond.sql(), "1 = 0");
+        assert!(cond.values().is_empty());
+
+        let cond = Condition::not_in(TestColumn::Id, Vec::<i64>::new());
+        assert_eq!(cond.sql(), "1 = 1");
         assert!(cond.values().is_empty());
     }
 
@@ -317,6 +698,25 @@ mod tests {
         assert_eq!(cond.values().len(), 1);
     }
 
+    #[test]
+    fn test_condition_split_in() {
+        let chunks = Condition::is_in(TestColumn::Id, vec![1, 2, 2, 3, 4]).split_in(3).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].sql(), "id IN (?, ?, ?)");
+        assert_eq!(chunks[0].values(), &[Value::Integer(1), Value::Integer(2), Value::Integer(3)]);
+        assert_eq!(chunks[1].sql(), "id IN (?)");
+
+        assert!(Condition::not_in(TestColumn::Id, vec![1, 2]).split_in(1).is_none());
+        assert!(Condition::is_in(TestColumn::Id, vec![1]).and(Condition::eq(TestColumn::Age, 2)).split_in(1).is_none());
+        assert!(Condition::is_in(TestColumn::Id, Vec::<i64>::new()).split_in(1).is_none());
+
+        let chunks =
+            Condition::is_in(TestColumn::Name, vec!["a", "A", "b"]).collate(Collation::NoCase).split_in(1).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].sql(), "name COLLATE NOCASE = ?");
+        assert_eq!(chunks[1].values(), &[Value::Text("b".to_string())]);
+    }
+
     #[test]
     fn test_condition_not_in() {
         let cond = Condition::not_in(TestColumn::Id, vec![1, 2]);
@@ -383,6 +783,104 @@ mod tests {
         assert_eq!(cond.values().len(), 1);
     }
 
+    #[test]
+    fn test_condition_not_grouped() {
+        let cond = Condition::not(Condition::eq(TestColumn::Id, 1).or(Condition::eq(TestColumn::Id, 2)));
+        assert_eq!(cond.sql(), "NOT ((id = ?) OR (id = ?))");
+        assert_eq!(cond.values(), &[Value::Integer(1), Value::Integer(2)]);
+    }
+
+    #[test]
+    fn test_condition_groups() {
+        let cond = Condition::eq(TestColumn::Age, 30).and_group(|g| {
+            g.or(Condition::eq(TestColumn::Name, "Alice"))
+                .or(Condition::eq(TestColumn::Name, "Bob"))
+                .or_group(|g| g.and(Condition::is_null(TestColumn::Name)).and(Condition::eq(TestColumn::Id, 1)))
+        });
+        assert_eq!(cond.sql(), "(age = ?) AND (((name = ?) OR (name = ?)) OR ((name IS NULL) AND (id = ?)))");
+        assert_eq!(cond.values().len(), 4);
+
+        let mixed =
+            Condition::eq(TestColumn::Id, 1).and(Condition::eq(TestColumn::Id, 2)).or(Condition::eq(TestColumn::Id, 3));
+        assert_eq!(mixed.sql(), "((id = ?) AND (id = ?)) OR (id = ?)");
+
+        let unchanged = Condition::eq(TestColumn::Id, 1).or_group(|g| g);
+        assert_eq!(unchanged.sql(), "id = ?");
+        assert!(ConditionGroup::default().build().is_none());
+    }
+
+    #[test]
+    fn test_condition_tuple_comparisons() {
+        let cond = Condition::tuple_gt((TestColumn::Age, TestColumn::Id), (30, 7));
+        assert_eq!(cond.sql(), "(age > ?) OR (age = ? AND id > ?)");
+        assert_eq!(cond.values(), &[Value::Integer(30), Value::Integer(30), Value::Integer(7)]);
+
+        let cond = Condition::tuple_lte((TestColumn::Name, TestColumn::Age, TestColumn::Id), ("m", 30, 7));
+        assert_eq!(cond.sql(), "((name < ?) OR (name = ? AND age < ?)) OR (name = ? AND age = ? AND id <= ?)");
+        assert_eq!(cond.values().len(), 6);
+
+        assert_eq!(
+            Condition::tuple_gte((TestColumn::Age, TestColumn::Id), (1, 2)).sql(),
+            "(age > ?) OR (age = ? AND id >= ?)"
+        );
+        assert_eq!(
+            Condition::tuple_lt((TestColumn::Age, TestColumn::Id), (1, 2)).sql(),
+            "(age < ?) OR (age = ? AND id < ?)"
+        );
+    }
+
+    #[test]
+    fn test_condition_is() {
+        let cond = Condition::is(TestColumn::Email, Some("a@b.c"));
+        assert_eq!(cond.sql(), "email IS ?");
+        assert_eq!(cond.values(), &[Value::Text("a@b.c".to_string())]);
+
+        let cond = Condition::is(TestColumn::Email, None::<String>);
+        assert_eq!(cond.sql(), "email IS NULL");
+        assert!(cond.values().is_empty());
+    }
+
+    #[test]
+    fn test_condition_boolean_literals() {
+        let names: Vec<&str> = vec![];
+        let any_name =
+            names.iter().fold(Condition::always_false(), |acc, name| acc.or(Condition::eq(TestColumn::Name, *name)));
+        assert_eq!(any_name.sql(), "1 = 0");
+
+        let cond = Condition::always_true().and(Condition::eq(TestColumn::Id, 1));
+        assert_eq!(cond.sql(), "(1 = 1) AND (id = ?)");
+        assert_eq!(cond.values().len(), 1);
+    }
+
+    #[test]
+    fn test_condition_ignore_case() {
+        let cond = Condition::eq_ignore_case(TestColumn::Email, "Alice@Example.com");
+        assert_eq!(cond.sql(), "email COLLATE NOCASE = ?");
+        assert_eq!(cond.values(), &[Value::Text("Alice@Example.com".to_string())]);
+
+        let cond = Condition::like_ignore_case(TestColumn::Name, "al%");
+        assert_eq!(cond.sql(), "LOWER(name) LIKE LOWER(?)");
+    }
+
+    #[test]
+    fn test_condition_collate() {
+        let cond = Condition::gt(TestColumn::Name, "m").collate(Collation::RTrim);
+        assert_eq!(cond.sql(), "name COLLATE RTRIM > ?");
+        assert_eq!(cond.values().len(), 1);
+
+        let cond = Condition::between(TestColumn::Name, "a", "m").collate(Collation::NoCase);
+        assert_eq!(cond.sql(), "name COLLATE NOCASE BETWEEN ? AND ?");
+
+        let cond = Condition::is_in(TestColumn::Name, vec!["a", "b"]).collate(Collation::NoCase);
+        assert_eq!(cond.sql(), "name COLLATE NOCASE = ? OR name COLLATE NOCASE = ?");
+
+        let cond = Condition::not_in(TestColumn::Name, vec!["a"]).collate(Collation::NoCase);
+        assert_eq!(cond.sql(), "NOT (name COLLATE NOCASE = ?)");
+
+        let cond = Condition::raw("name > ?", vec![]).collate(Collation::Binary);
+        assert_eq!(cond.sql(), "name > ? COLLATE BINARY");
+    }
+
     #[test]
     fn test_condition_chained() {
         let cond = Condition::eq(TestColumn::Age, 25)
@@ -449,6 +947,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_order_clone() {
         let order = Order::Asc;
         let cloned = order.clone();
@@ -476,6 +975,15 @@ mod tests {
         assert_eq!(order_by.direction, Order::Desc);
     }
 
+    #[test]
+    fn test_order_by_nulls() {
+        let order_by = OrderBy::from((TestColumn::Age, Order::Desc, Nulls::Last));
+        assert_eq!(order_by.to_string(), "age IS NULL, age DESC");
+
+        assert_eq!(OrderBy::asc(TestColumn::Age).nulls_first().to_string(), "age IS NOT NULL, age ASC");
+        assert_eq!(OrderBy::from((TestColumn::Name, Order::Asc)).to_string(), "name ASC");
+    }
+
     #[test]
     fn test_order_by_clone() {
         let order_by = OrderBy::asc(TestColumn::Id);
@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::ColumnTrait;
+use crate::ColumnType;
+use crate::Condition;
+use crate::Error;
+use crate::Result;
+use crate::TableTrait;
+use crate::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FilterOperator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Like,
+    Contains,
+    StartsWith,
+    EndsWith,
+    /// Comma-separated list of values.
+    In,
+    /// `true` for `IS NULL`, `false` for `IS NOT NULL`.
+    IsNull,
+}
+
+impl FilterOperator {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "eq" => FilterOperator::Eq,
+            "ne" => FilterOperator::Ne,
+            "gt" => FilterOperator::Gt,
+            "gte" => FilterOperator::Gte,
+            "lt" => FilterOperator::Lt,
+            "lte" => FilterOperator::Lte,
+            "like" => FilterOperator::Like,
+            "contains" => FilterOperator::Contains,
+            "starts_with" => FilterOperator::StartsWith,
+            "ends_with" => FilterOperator::EndsWith,
+            "in" => FilterOperator::In,
+            "is_null" => FilterOperator::IsNull,
+            _ => return None,
+        })
+    }
+}
+
+impl Condition {
+    /// `AND`s an equality condition for every pair; an empty map matches every row.
+    pub fn from_pairs<Column: ColumnTrait + Eq + Hash>(pairs: HashMap<Column, Value>) -> Self {
+        let mut pairs: Vec<(Column, Value)> = pairs.into_iter().collect();
+        // Keep the generated SQL stable so it hits the statement cache.
+        pairs.sort_by_key(|(column, _)| column.name());
+
+        pairs
+            .into_iter()
+            .map(|(column, value)| Condition::eq(column, value))
+            .reduce(Condition::and)
+            .unwrap_or_else(Condition::always_true)
+    }
+}
+
+/// Translates untrusted `key=value` filters, such as a query string, into a parameterized [`Condition`].
+///
+/// Keys are a column name optionally followed by `__` and an operator name, e.g. `email`, `age__gte` or
+/// `name__contains`. Only whitelisted columns and operators are accepted; values are parsed according to the column
+/// type and always bound as parameters.
+#[derive(Debug)]
+pub struct ConditionBuilder<Table: TableTrait> {
+    columns:    Option<Vec<Table::Column>>,
+    operators:  Vec<FilterOperator>,
+    conditions: Vec<Condition>,
+    _table:     PhantomData<Table>,
+}
+
+impl<Table: TableTrait> Clone for ConditionBuilder<Table> {
+    fn clone(&self) -> Self {
+        Self {
+            columns:    self.columns.clone(),
+            operators:  self.operators.clone(),
+            conditions: self.conditions.clone(),
+            _table:     PhantomData,
+        }
+    }
+}
+
+impl<Table: TableTrait> ConditionBuilder<Table> {
+    /// Accepts every column with the `eq` operator only.
+    pub fn new() -> Self {
+        Self { columns: None, operators: vec![FilterOperator::Eq], conditions: Vec::new(), _table: PhantomData }
+    }
+
+    pub fn allow_columns(mut self, columns: impl IntoIterator<Item = Table::Column>) -> Self {
+        self.columns = Some(columns.into_iter().collect());
+        self
+    }
+
+    pub fn allow_operators(mut self, operators: impl IntoIterator<Item = FilterOperator>) -> Self {
+        self.operators = operators.into_iter().collect();
+        self
+    }
+
+    pub fn filter(self, key: &str, value: &str) -> Result<Self> {
+        let (column_name, operator_name) = key.split_once("__").unwrap_or((key, "eq"));
+        self.filter_by(column_name, operator_name, value)
+    }
+
+    /// Like [`ConditionBuilder::filter`], with the column and operator names given separately.
+    pub fn filter_by(mut self, column_name: &str, operator_name: &str, value: &str) -> Result<Self> {
+        let column = self.find_column(column_name)?;
+        let operator = FilterOperator::from_name(operator_name)
+            .filter(|operator| self.operators.contains(operator))
+            .ok_or_else(|| Error::Query(format!("Filter operator `{}` is not allowed", operator_name)))?;
+
+        let condition = match operator {
+            FilterOperator::Eq => Condition::eq(column, parse_value(column, value)?),
+            FilterOperator::Ne => Condition::ne(column, parse_value(column, value)?),
+            FilterOperator::Gt => Condition::gt(column, parse_value(column, value)?),
+            FilterOperator::Gte => Condition::gte(column, parse_value(column, value)?),
+            FilterOperator::Lt => Condition::lt(column, parse_value(column, value)?),
+            FilterOperator::Lte => Condition::lte(column, parse_value(column, value)?),
+            FilterOperator::Like => Condition::like(column, value),
+            FilterOperator::Contains => Condition::contains(column, value),
+            FilterOperator::StartsWith => Condition::starts_with(column, value),
+            FilterOperator::EndsWith => Condition::ends_with(column, value),
+            FilterOperator::In => {
+                let values =
+                    value.split(',').map(|item| parse_value(column, item.trim())).collect::<Result<Vec<_>>>()?;
+                Condition::is_in(column, values)
+            }
+            FilterOperator::IsNull => match value {
+                "true" | "1" => Condition::is_null(column),
+                "false" | "0" => Condition::is_not_null(column),
+                _ => return Err(Error::Query(format!("Expected a boolean for `{}`, got `{}`", column_name, value))),
+            },
+        };
+
+        self.conditions.push(condition);
+        Ok(self)
+    }
+
+    pub fn filters<K: AsRef<str>, V: AsRef<str>>(self, pairs: impl IntoIterator<Item = (K, V)>) -> Result<Self> {
+        pairs.into_iter().try_fold(self, |builder, (key, value)| builder.filter(key.as_ref(), value.as_ref()))
+    }
+
+    /// `AND`s every accepted filter; no filters match every row.
+    pub fn build(self) -> Condition {
+        self.conditions.into_iter().reduce(Condition::and).unwrap_or_else(Condition::always_true)
+    }
+
+    fn find_column(&self, name: &str) -> Result<Table::Column> {
+        let allowed = match &self.columns {
+            Some(columns) => columns.as_slice(),
+            None => Table::Column::all(),
+        };
+
+        allowed
+            .iter()
+            .copied()
+            .find(|column| column.name() == name)
+            .ok_or_else(|| Error::Query(format!("Unknown filter column `{}`", name)))
+    }
+}
+
+impl<Table: TableTrait> Default for ConditionBuilder<Table> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_value<Column: ColumnTrait>(column: Column, input: &str) -> Result<Value> {
+    let invalid = || Error::TypeConversion {
+        expected: match column.column_type() {
+            ColumnType::Integer => "Integer",
+            ColumnType::Float => "Float",
+            _ => "Text",
+        },
+        actual:   input.to_string(),
+        error:    format!("invalid filter value for `{}`", column.name()),
+    };
+
+    match column.column_type() {
+        ColumnType::Integer => match input {
+            "true" => Ok(Value::Integer(1)),
+            "false" => Ok(Value::Integer(0)),
+            _ => input.parse().map(Value::Integer).map_err(|_| invalid()),
+        },
+        ColumnType::Float => input.parse().map(Value::Real).map_err(|_| invalid()),
+        ColumnType::Text => Ok(Value::Text(input.to_string())),
+        ColumnType::Blob | ColumnType::Null => {
+            Err(Error::Query(format!("Column `{}` cannot be filtered by value", column.name())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChangeSetTrait;
+    use crate::FromRow;
+    use crate::RecordTrait;
+
+    #[derive(Clone, Debug)]
+    struct TestRecord;
+
+    impl RecordTrait for TestRecord {
+        type Table = TestTable;
+
+        fn get_primary_key_value(&self) -> Value {
+            Value::Integer(1)
+        }
+    }
+
+    impl FromRow for TestRecord {
+        fn from_row(_row: &turso::Row) -> Result<Self> {
+            Ok(TestRecord)
+        }
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct TestChangeSet;
+
+    impl ChangeSetTrait for TestChangeSet {
+        type Table = TestTable;
+
+        fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<Value>) {
+            (vec![], vec![])
+        }
+
+        fn get_update_sets(&self) -> Vec<(&'static str, Value)> {
+            vec![]
+        }
+
+        fn get_primary_key_value(&self) -> Option<Value> {
+            None
+        }
+
+        fn primary_key_column() -> &'static str {
+            "id"
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum TestColumn {
+        Id,
+        Name,
+        Score,
+        Avatar,
+    }
+
+    impl std::fmt::Display for TestColumn {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.name())
+        }
+    }
+
+    impl ColumnTrait for TestColumn {
+        fn name(&self) -> &'static str {
+            match self {
+                TestColumn::Id => "id",
+                TestColumn::Name => "name",
+                TestColumn::Score => "score",
+                TestColumn::Avatar => "avatar",
+            }
+        }
+
+        fn column_type(&self) -> ColumnType {
+            match self {
+                TestColumn::Id => ColumnType::Integer,
+                TestColumn::Name => ColumnType::Text,
+                TestColumn::Score => ColumnType::Float,
+                TestColumn::Avatar => ColumnType::Blob,
+            }
+        }
+
+        fn all() -> &'static [Self] {
+            &[TestColumn::Id, TestColumn::Name, TestColumn::Score, TestColumn::Avatar]
+        }
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct TestTable;
+
+    impl TableTrait for TestTable {
+        type ChangeSet = TestChangeSet;
+        type Column = TestColumn;
+        type PrimaryKeyType = i64;
+        type Record = TestRecord;
+
+        fn table_name() -> &'static str {
+            "test"
+        }
+
+        fn primary_key() -> Self::Column {
+            TestColumn::Id
+        }
+
+        fn primary_key_to_value(id: &i64) -> Value {
+            Value::Integer(*id)
+        }
+
+        fn primary_key_auto_increment() -> bool {
+            true
+        }
+
+        fn all_columns() -> &'static str {
+            "id, name, score, avatar"
+        }
+
+        fn column_count() -> usize {
+            4
+        }
+    }
+
+    #[test]
+    fn test_from_pairs_is_sorted() {
+        let mut pairs = HashMap::new();
+        pairs.insert(TestColumn::Score, Value::Real(1.5));
+        pairs.insert(TestColumn::Id, Value::Integer(7));
+        pairs.insert(TestColumn::Name, Value::Text("a".to_string()));
+
+        let cond = Condition::from_pairs(pairs);
+        assert_eq!(cond.sql(), "((id = ?) AND (name = ?)) AND (score = ?)");
+        assert_eq!(cond.values(), &[Value::Integer(7), Value::Text("a".to_string()), Value::Real(1.5)]);
+    }
+
+    #[test]
+    fn test_from_pairs_empty() {
+        let cond = Condition::from_pairs(HashMap::<TestColumn, Value>::new());
+        assert_eq!(cond.sql(), "1 = 1");
+    }
+
+    #[test]
+    fn test_builder_parses_by_column_type() {
+        let cond = ConditionBuilder::<TestTable>::new()
+            .allow_operators([FilterOperator::Eq, FilterOperator::Gte, FilterOperator::In])
+            .filters([("id__in", "1, 2"), ("score__gte", "2.5"), ("name", "bob")])
+            .unwrap()
+            .build();
+
+        assert_eq!(cond.sql(), "((id IN (?, ?)) AND (score >= ?)) AND (name = ?)");
+        assert_eq!(
+            cond.values(),
+            &[Value::Integer(1), Value::Integer(2), Value::Real(2.5), Value::Text("bob".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_builder_is_null() {
+        let cond = ConditionBuilder::<TestTable>::new()
+            .allow_operators([FilterOperator::IsNull])
+            .filter("name__is_null", "false")
+            .unwrap()
+            .build();
+        assert_eq!(cond.sql(), "name IS NOT NULL");
+    }
+
+    #[test]
+    fn test_builder_filter_by() {
+        let cond = ConditionBuilder::<TestTable>::new()
+            .allow_operators([FilterOperator::Lt])
+            .filter_by("score", "lt", "3")
+            .unwrap()
+            .build();
+        assert_eq!(cond.sql(), "score < ?");
+        assert_eq!(cond.values(), &[Value::Real(3.0)]);
+
+        assert!(ConditionBuilder::<TestTable>::new().filter_by("score", "gt", "3").is_err());
+    }
+
+    #[test]
+    fn test_builder_rejects_unlisted_operator() {
+        let err = ConditionBuilder::<TestTable>::new().filter("name__contains", "a").unwrap_err();
+        assert!(err.to_string().contains("`contains` is not allowed"));
+
+        assert!(ConditionBuilder::<TestTable>::new().filter("name__drop", "a").is_err());
+    }
+
+    #[test]
+    fn test_builder_rejects_unlisted_column() {
+        let builder = ConditionBuilder::<TestTable>::new().allow_columns([TestColumn::Name]);
+        assert!(builder.clone().filter("name", "a").is_ok());
+
+        let err = builder.filter("id", "1").unwrap_err();
+        assert!(err.to_string().contains("Unknown filter column `id`"));
+        assert!(ConditionBuilder::<TestTable>::new().filter("name; DROP TABLE test", "a").is_err());
+    }
+
+    #[test]
+    fn test_builder_rejects_bad_values() {
+        assert!(ConditionBuilder::<TestTable>::new().filter("id", "abc").is_err());
+        assert!(ConditionBuilder::<TestTable>::new().filter("avatar", "abc").is_err());
+    }
+
+    #[test]
+    fn test_builder_empty() {
+        assert_eq!(ConditionBuilder::<TestTable>::new().build().sql(), "1 = 1");
+    }
+}
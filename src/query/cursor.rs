@@ -0,0 +1,150 @@
+use crate::Error;
+use crate::IntoValue;
+use crate::Result;
+use crate::Value;
+
+/// The key values of the last row of a page, used to fetch the rows after it.
+///
+/// [`Cursor::encode`] turns it into an opaque, URL-safe token that can be handed to clients and read back with
+/// [`Cursor::decode`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cursor {
+    values: Vec<Value>,
+}
+
+impl Cursor {
+    pub fn new(values: Vec<Value>) -> Self {
+        Self { values }
+    }
+
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    pub fn encode(&self) -> String {
+        let mut bytes = Vec::new();
+
+        for value in &self.values {
+            match value {
+                Value::Null => bytes.push(b'n'),
+                Value::Integer(v) => {
+                    bytes.push(b'i');
+                    bytes.extend(v.to_be_bytes());
+                }
+                Value::Real(v) => {
+                    bytes.push(b'r');
+                    bytes.extend(v.to_bits().to_be_bytes());
+                }
+                Value::Text(v) => {
+                    bytes.push(b't');
+                    bytes.extend((v.len() as u32).to_be_bytes());
+                    bytes.extend(v.as_bytes());
+                }
+                Value::Blob(v) => {
+                    bytes.push(b'b');
+                    bytes.extend((v.len() as u32).to_be_bytes());
+                    bytes.extend(v);
+                }
+            }
+        }
+
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    pub fn decode(token: &str) -> Result<Self> {
+        let invalid = || Error::Query("Invalid cursor".to_string());
+
+        if !token.len().is_multiple_of(2) || !token.is_ascii() {
+            return Err(invalid());
+        }
+        let bytes = (0..token.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&token[i..i + 2], 16))
+            .collect::<std::result::Result<Vec<u8>, _>>()
+            .map_err(|_| invalid())?;
+
+        let mut rest = bytes.as_slice();
+        let mut values = Vec::new();
+
+        while let Some((tag, remaining)) = rest.split_first() {
+            rest = remaining;
+            let value = match tag {
+                b'n' => Value::Null,
+                b'i' => Value::Integer(i64::from_be_bytes(take_array(&mut rest).ok_or_else(invalid)?)),
+                b'r' => Value::Real(f64::from_bits(u64::from_be_bytes(take_array(&mut rest).ok_or_else(invalid)?))),
+                b't' | b'b' => {
+                    let len = u32::from_be_bytes(take_array(&mut rest).ok_or_else(invalid)?) as usize;
+                    if rest.len() < len {
+                        return Err(invalid());
+                    }
+                    let (data, remaining) = rest.split_at(len);
+                    rest = remaining;
+
+                    if *tag == b't' {
+                        Value::Text(String::from_utf8(data.to_vec()).map_err(|_| invalid())?)
+                    } else {
+                        Value::Blob(data.to_vec())
+                    }
+                }
+                _ => return Err(invalid()),
+            };
+            values.push(value);
+        }
+
+        Ok(Self { values })
+    }
+}
+
+impl<V: IntoValue> From<V> for Cursor {
+    fn from(value: V) -> Self {
+        Self { values: vec![value.into_value()] }
+    }
+}
+
+fn take_array<const N: usize>(rest: &mut &[u8]) -> Option<[u8; N]> {
+    let (head, tail) = rest.split_first_chunk::<N>()?;
+    *rest = tail;
+    Some(*head)
+}
+
+/// One page of a keyset-paginated select.
+#[derive(Clone, Debug)]
+pub struct Page<Record> {
+    pub items:       Vec<Record>,
+    /// Cursor for the following page, or `None` when this is the last one.
+    pub next_cursor: Option<Cursor>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor::new(vec![
+            Value::Null,
+            Value::Integer(-7),
+            Value::Real(2.5),
+            Value::Text("héllo".to_string()),
+            Value::Blob(vec![0, 255]),
+        ]);
+
+        let token = cursor.encode();
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(Cursor::decode(&token).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_cursor_from_value() {
+        assert_eq!(Cursor::from(42).values(), &[Value::Integer(42)]);
+    }
+
+    #[test]
+    fn test_cursor_decode_rejects_garbage() {
+        assert!(Cursor::decode("zz").is_err());
+        assert!(Cursor::decode("6").is_err());
+        assert!(Cursor::decode("69").is_err());
+        assert!(Cursor::decode("74000000ff").is_err());
+        assert!(Cursor::decode("é").is_err());
+    }
+}
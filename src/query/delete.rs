@@ -1,19 +1,58 @@
 use std::marker::PhantomData;
 
 use crate::Condition;
+use crate::ConnectionLike;
+use crate::Error;
+use crate::OrderBy;
+use crate::RecordTrait;
 use crate::Result;
+use crate::Select;
+use crate::StatementKind;
 use crate::TableTrait;
 use crate::Value;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::query::comment::with_comment;
+use crate::query::interpolate::interpolate;
+use crate::query::update::check_affected;
+use crate::query::update::check_returned;
+use crate::traits::table::ensure_writable;
+use crate::traits::table::qualified_table_name;
+
+/// Primary keys bound per `DELETE` when deleting rows looked up first.
+const KEY_CHUNK_SIZE: usize = 500;
 
 #[derive(Clone, Debug)]
 pub struct Delete<Table: TableTrait> {
-    conditions: Vec<Condition>,
-    _entity:    PhantomData<Table>,
+    conditions:          Vec<Condition>,
+    order_by:            Vec<OrderBy>,
+    limit:               Option<usize>,
+    reset_autoincrement: bool,
+    schema:              Option<String>,
+    comment:             Option<String>,
+    expected:            Option<u64>,
+    full_table:          bool,
+    _entity:             PhantomData<Table>,
 }
 
 impl<Table: TableTrait> Delete<Table> {
     pub fn new() -> Self {
-        Self { conditions: Vec::new(), _entity: PhantomData }
+        Self {
+            conditions:          Vec::new(),
+            order_by:            Vec::new(),
+            limit:               None,
+            reset_autoincrement: false,
+            schema:              None,
+            comment:             None,
+            expected:            None,
+            full_table:          false,
+            _entity:             PhantomData,
+        }
+    }
+
+    /// Deletes from the table in the attached database `schema` instead of the table's own.
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
     }
 
     pub fn filter(mut self, condition: Condition) -> Self {
@@ -21,8 +60,59 @@ impl<Table: TableTrait> Delete<Table> {
         self
     }
 
+    /// Deletes at most `limit` of the matching rows, picked in [`Delete::order_by`] order when one is given.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Decides which rows a [`Delete::limit`] applies to. turso doesn't support `DELETE ... ORDER BY`, so the
+    /// primary keys of those rows are looked up first, within the same transaction.
+    pub fn order_by(mut self, order_by: impl Into<OrderBy>) -> Self {
+        self.order_by.push(order_by.into());
+        self
+    }
+
+    /// Also forgets the table's `AUTOINCREMENT` counter, so the next insert starts again after the highest remaining id
+    /// (at 1 once the table is empty). Typically used with [`TableDeleteExt::truncate`](crate::TableDeleteExt).
+    pub fn reset_autoincrement(mut self) -> Self {
+        self.reset_autoincrement = true;
+        self
+    }
+
+    /// Prepends `/* comment */` to the statements this builder runs, so slow-query logs can be traced back to the
+    /// call site.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Allows a delete without a filter on a connection that
+    /// [requires one](crate::Builder::require_filter_on_mutation).
+    pub fn allow_full_table(mut self) -> Self {
+        self.full_table = true;
+        self
+    }
+
+    /// Makes [`Delete::exec`] and [`Delete::exec_with_returning`] fail with
+    /// [`Error::UnexpectedAffectedRows`](Error::UnexpectedAffectedRows) and roll the delete back unless exactly `rows`
+    /// rows were deleted. Inside a caller's transaction nothing is rolled
+    /// back until the caller does.
+    pub fn expect_affected(mut self, rows: u64) -> Self {
+        self.expected = Some(rows);
+        self
+    }
+
+    /// An ordered, limited delete is shown as the equivalent `DELETE ... WHERE id IN (SELECT id ... ORDER BY ...
+    /// LIMIT n)`. turso rejects subqueries in deletes, so [`Delete::exec`] runs that select first and then deletes the
+    /// keys it returned.
     pub fn build(&self) -> (String, Vec<Value>) {
-        let mut sql = format!("DELETE FROM {}", Table::table_name());
+        if self.limit.is_some() && !self.order_by.is_empty() {
+            let keys = self.limited_select().columns(vec![Table::primary_key()]);
+            return self.restricted_to(Condition::is_in_subquery(Table::primary_key(), keys)).build();
+        }
+
+        let mut sql = format!("DELETE FROM {}", qualified_table_name::<Table>(self.schema.as_deref()));
         let mut params = Vec::new();
 
         if !self.conditions.is_empty() {
@@ -35,14 +125,145 @@ impl<Table: TableTrait> Delete<Table> {
             }
         }
 
-        (sql, params)
+        if let Some(limit) = self.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        (with_comment(self.comment.as_deref(), sql), params)
+    }
+
+    pub async fn exec(mut self, conn: &impl ConnectionLike) -> Result<u64> {
+        ensure_writable::<Table>()?;
+        self.ensure_filtered(conn)?;
+        let Some(expected) = self.expected.take() else {
+            return self.exec_deleting(conn).await;
+        };
+
+        conn.in_transaction(async {
+            self.exec_deleting(conn).await.and_then(|actual| check_affected(expected, actual))
+        })
+        .await
+    }
+
+    fn ensure_filtered(&self, conn: &impl ConnectionLike) -> Result<()> {
+        if self.conditions.is_empty() && !self.full_table && conn.requires_filter_on_mutation() {
+            return Err(Error::UnfilteredMutation { table: Table::table_name() });
+        }
+
+        Ok(())
+    }
+
+    async fn exec_deleting(self, conn: &impl ConnectionLike) -> Result<u64> {
+        if !self.reset_autoincrement {
+            return self.exec_rows(conn).await;
+        }
+
+        let schema = self.schema.clone();
+        conn.in_transaction(async {
+            let deleted = self.exec_rows(conn).await?;
+            reset_sequence::<Table>(conn, schema.as_deref()).await?;
+            Ok(deleted)
+        })
+        .await
+    }
+
+    async fn exec_rows(self, conn: &impl ConnectionLike) -> Result<u64> {
+        if self.limit.is_none() || self.order_by.is_empty() {
+            return self.exec_statement(conn).await;
+        }
+
+        conn.in_transaction(async {
+            let keys = self.limited_select().primary_keys(conn).await?;
+            let mut deleted = 0;
+            for chunk in keys.chunks(KEY_CHUNK_SIZE) {
+                deleted += self.by_keys(chunk.to_vec()).exec_statement(conn).await?;
+            }
+
+            Ok(deleted)
+        })
+        .await
     }
 
-    pub async fn exec(self, conn: &crate::Connection) -> Result<u64> {
+    async fn exec_statement(self, conn: &impl ConnectionLike) -> Result<u64> {
         let (sql, params) = self.build();
         let params: Vec<turso::Value> = params.into_iter().collect();
-        let affected = conn.execute(&sql, params).await?;
-        Ok(affected)
+
+        let timer = conn.start_query(Table::table_name(), StatementKind::Delete, &sql);
+        let result = conn.execute_statement(&sql, params).await;
+        timer.finish(result.as_ref().ok().copied());
+
+        result
+    }
+
+    /// Deletes the matching rows and returns them as they were before deletion.
+    ///
+    /// turso doesn't support `DELETE ... RETURNING` yet, so the rows are selected and then deleted by primary key
+    /// within one immediate transaction (or the caller's open transaction); nothing can change them in between.
+    pub async fn exec_with_returning(mut self, conn: &impl ConnectionLike) -> Result<Vec<Table::Record>> {
+        ensure_writable::<Table>()?;
+        self.ensure_filtered(conn)?;
+        let expected = self.expected.take();
+        conn.in_transaction(async {
+            self.delete_returning(conn).await.and_then(|records| check_returned(expected, records))
+        })
+        .await
+    }
+
+    async fn delete_returning(self, conn: &impl ConnectionLike) -> Result<Vec<Table::Record>> {
+        let records = self.limited_select().all(conn).await?;
+
+        for chunk in records.chunks(KEY_CHUNK_SIZE) {
+            let keys: Vec<Value> = chunk.iter().map(RecordTrait::get_primary_key_value).collect();
+            self.by_keys(keys).exec(conn).await?;
+        }
+        if self.reset_autoincrement {
+            reset_sequence::<Table>(conn, self.schema.as_deref()).await?;
+        }
+
+        Ok(records)
+    }
+
+    fn by_keys(&self, keys: Vec<Value>) -> Delete<Table> {
+        self.restricted_to(Condition::is_in(Table::primary_key(), keys))
+    }
+
+    /// A plain delete of the rows matching `condition`, in the same schema and with the same comment.
+    fn restricted_to(&self, condition: Condition) -> Delete<Table> {
+        Delete { schema: self.schema.clone(), comment: self.comment.clone(), ..Delete::new() }.filter(condition)
+    }
+
+    /// The rows this delete applies to, with its ordering and limit.
+    fn limited_select(&self) -> Select<Table> {
+        let select =
+            self.conditions.iter().cloned().fold(Select::<Table>::new().schema(self.schema.clone()), Select::filter);
+        let select = self.order_by.iter().cloned().fold(select, Select::order_by);
+
+        match self.limit {
+            Some(limit) => select.limit(limit),
+            None => select,
+        }
+    }
+}
+
+/// `sqlite_sequence` only exists once some table uses `AUTOINCREMENT`.
+async fn reset_sequence<Table: TableTrait>(conn: &impl ConnectionLike, schema: Option<&str>) -> Result<()> {
+    let prefix = schema.or(Table::schema_name()).map(|schema| format!("{}.", schema)).unwrap_or_default();
+    let sql = format!("SELECT 1 FROM {}sqlite_schema WHERE type = 'table' AND name = 'sqlite_sequence'", prefix);
+    let mut rows = conn.query_rows(&sql, Vec::new()).await?;
+    if rows.next().await?.is_none() {
+        return Ok(());
+    }
+    drop(rows);
+
+    let sql = format!("DELETE FROM {}sqlite_sequence WHERE name = ?", prefix);
+    conn.execute_statement(&sql, vec![Value::from(Table::table_name())]).await?;
+    Ok(())
+}
+
+impl<Table: TableTrait> std::fmt::Display for Delete<Table> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (sql, params) = self.build();
+        f.write_str(&interpolate(&sql, &params))
     }
 }
 
@@ -60,6 +281,7 @@ mod tests {
     use crate::ColumnType;
     use crate::FieldValue;
     use crate::FromRow;
+    use crate::Order;
     use crate::RecordTrait;
     use crate::Value;
 
@@ -120,7 +342,7 @@ mod tests {
         }
 
         fn get_primary_key_value(&self) -> Option<Value> {
-            self.id.clone().take().map(|v| Value::Integer(v))
+            self.id.clone().take().map(Value::Integer)
         }
 
         fn primary_key_column() -> &'static str {
@@ -168,6 +390,7 @@ mod tests {
     impl TableTrait for TestTable {
         type ChangeSet = TestChangeSet;
         type Column = TestColumn;
+        type PrimaryKeyType = i64;
         type Record = TestRecord;
 
         fn table_name() -> &'static str {
@@ -178,6 +401,10 @@ mod tests {
             TestColumn::Id
         }
 
+        fn primary_key_to_value(id: &i64) -> Value {
+            Value::Integer(*id)
+        }
+
         fn primary_key_auto_increment() -> bool {
             true
         }
@@ -219,6 +446,35 @@ mod tests {
         assert_eq!(params[0], Value::Integer(1));
     }
 
+    #[test]
+    fn test_delete_limit() {
+        let delete = Delete::<TestTable>::new().filter(Condition::lt(TestColumn::Id, 100)).limit(10);
+        let (sql, _) = delete.build();
+
+        assert_eq!(sql, "DELETE FROM test_users WHERE (id < ?) LIMIT 10");
+    }
+
+    #[test]
+    fn test_delete_ordered_limit() {
+        let delete = Delete::<TestTable>::new()
+            .filter(Condition::lt(TestColumn::Id, 100))
+            .order_by((TestColumn::Name, Order::Desc))
+            .limit(10);
+        let (sql, params) = delete.build();
+
+        assert_eq!(
+            sql,
+            "DELETE FROM test_users WHERE (id IN (SELECT id FROM test_users WHERE (id < ?) ORDER BY name DESC LIMIT 10))"
+        );
+        assert_eq!(params, vec![Value::Integer(100)]);
+    }
+
+    #[test]
+    fn test_delete_display() {
+        let delete = Delete::<TestTable>::new().filter(Condition::is_in(TestColumn::Id, vec![1, 2]));
+        assert_eq!(delete.to_string(), "DELETE FROM test_users WHERE (id IN (1, 2))");
+    }
+
     #[test]
     fn test_delete_filter_multiple() {
         let delete = Delete::<TestTable>::new()
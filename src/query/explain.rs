@@ -0,0 +1,78 @@
+use crate::ConnectionLike;
+use crate::Result;
+use crate::Value;
+
+/// One row of `EXPLAIN QUERY PLAN`; `parent` is the `id` of the step this one is nested under, or `0`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryPlanStep {
+    pub id:     i64,
+    pub parent: i64,
+    /// E.g. `SEARCH user USING INDEX idx_user_email (email=?)` or `SCAN user`.
+    pub detail: String,
+}
+
+impl QueryPlanStep {
+    /// Whether the step reads a table through an index or its primary key rather than scanning every row.
+    pub fn uses_index(&self) -> bool {
+        self.detail.starts_with("SEARCH")
+    }
+}
+
+pub(crate) async fn explain_query_plan(
+    conn: &impl ConnectionLike,
+    sql: &str,
+    params: Vec<Value>,
+) -> Result<Vec<QueryPlanStep>> {
+    let mut rows = conn.query_rows(&format!("EXPLAIN QUERY PLAN {}", sql), params).await?;
+    let mut steps = Vec::new();
+
+    while let Some(row) = rows.next().await? {
+        steps.push(QueryPlanStep { id: row.get(0)?, parent: row.get(1)?, detail: row.get(3)? });
+    }
+
+    Ok(steps)
+}
+
+/// Renders `steps` as an indented tree, the way the SQLite shell prints query plans.
+pub(crate) fn render_query_plan(steps: &[QueryPlanStep]) -> String {
+    fn render_children(steps: &[QueryPlanStep], parent: i64, prefix: &str, out: &mut String) {
+        let children: Vec<&QueryPlanStep> = steps.iter().filter(|step| step.parent == parent).collect();
+
+        for (idx, step) in children.iter().enumerate() {
+            let last = idx + 1 == children.len();
+            out.push_str(&format!("\n{}{}{}", prefix, if last { "`--" } else { "|--" }, step.detail));
+            render_children(steps, step.id, &format!("{}{}", prefix, if last { "   " } else { "|  " }), out);
+        }
+    }
+
+    let mut out = String::from("QUERY PLAN");
+    render_children(steps, 0, "", &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: i64, parent: i64, detail: &str) -> QueryPlanStep {
+        QueryPlanStep { id, parent, detail: detail.to_string() }
+    }
+
+    #[test]
+    fn test_render_query_plan() {
+        let steps = [
+            step(2, 0, "SCAN post"),
+            step(5, 0, "LIST SUBQUERY 1"),
+            step(7, 5, "SEARCH user USING INDEX idx_user_email (email=?)"),
+            step(9, 0, "USE TEMP B-TREE FOR ORDER BY"),
+        ];
+
+        assert_eq!(
+            render_query_plan(&steps),
+            "QUERY PLAN\n|--SCAN post\n|--LIST SUBQUERY 1\n|  `--SEARCH user USING INDEX idx_user_email (email=?)\n`--USE \
+             TEMP B-TREE FOR ORDER BY"
+        );
+        assert!(!steps[0].uses_index());
+        assert!(steps[2].uses_index());
+    }
+}
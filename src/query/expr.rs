@@ -0,0 +1,152 @@
+use crate::ColumnRef;
+use crate::IntoValue;
+use crate::Value;
+
+/// A SQL value expression with bound parameters, e.g. the right-hand side of `SET quantity = quantity + ?`.
+#[derive(Clone, Debug)]
+pub struct Expr {
+    pub(crate) sql:    String,
+    pub(crate) values: Vec<Value>,
+}
+
+impl Expr {
+    pub fn col<Column: ColumnRef>(column: Column) -> Self {
+        Self { sql: column.qualified_name().into_owned(), values: Vec::new() }
+    }
+
+    pub fn value<V: IntoValue>(value: V) -> Self {
+        Self { sql: "?".to_string(), values: vec![value.into_value()] }
+    }
+
+    pub fn raw(sql: impl Into<String>, values: Vec<Value>) -> Self {
+        Self { sql: sql.into(), values }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(self, other: impl Into<Expr>) -> Self {
+        self.binary("+", other.into())
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn sub(self, other: impl Into<Expr>) -> Self {
+        self.binary("-", other.into())
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn mul(self, other: impl Into<Expr>) -> Self {
+        self.binary("*", other.into())
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn div(self, other: impl Into<Expr>) -> Self {
+        self.binary("/", other.into())
+    }
+
+    pub fn concat(self, other: impl Into<Expr>) -> Self {
+        self.binary("||", other.into())
+    }
+
+    pub fn coalesce(self, fallback: impl Into<Expr>) -> Self {
+        let fallback = fallback.into();
+        let mut values = self.values;
+        values.extend(fallback.values);
+        Self { sql: format!("COALESCE({}, {})", self.sql, fallback.sql), values }
+    }
+
+    fn binary(self, operator: &str, other: Expr) -> Self {
+        let mut values = self.values;
+        values.extend(other.values);
+        Self { sql: format!("({} {} {})", self.sql, operator, other.sql), values }
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+}
+
+impl<V: IntoValue> From<V> for Expr {
+    fn from(value: V) -> Self {
+        Expr::value(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColumnTrait;
+    use crate::value::ColumnType;
+
+    #[derive(Clone, Copy, Debug)]
+    enum TestColumn {
+        Quantity,
+        Price,
+    }
+
+    impl std::fmt::Display for TestColumn {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.name())
+        }
+    }
+
+    impl ColumnTrait for TestColumn {
+        fn name(&self) -> &'static str {
+            match self {
+                TestColumn::Quantity => "quantity",
+                TestColumn::Price => "price",
+            }
+        }
+
+        fn column_type(&self) -> ColumnType {
+            match self {
+                TestColumn::Quantity => ColumnType::Integer,
+                TestColumn::Price => ColumnType::Float,
+            }
+        }
+
+        fn all() -> &'static [Self] {
+            &[TestColumn::Quantity, TestColumn::Price]
+        }
+    }
+
+    #[test]
+    fn test_expr_col() {
+        let expr = Expr::col(TestColumn::Quantity);
+        assert_eq!(expr.sql(), "quantity");
+        assert!(expr.values().is_empty());
+    }
+
+    #[test]
+    fn test_expr_arithmetic() {
+        let expr = Expr::col(TestColumn::Quantity).add(1).mul(Expr::col(TestColumn::Price));
+        assert_eq!(expr.sql(), "((quantity + ?) * price)");
+        assert_eq!(expr.values(), &[Value::Integer(1)]);
+    }
+
+    #[test]
+    fn test_expr_sub_div() {
+        let expr = Expr::col(TestColumn::Price).sub(0.5).div(2);
+        assert_eq!(expr.sql(), "((price - ?) / ?)");
+        assert_eq!(expr.values(), &[Value::Real(0.5), Value::Integer(2)]);
+    }
+
+    #[test]
+    fn test_expr_coalesce_and_concat() {
+        let expr = Expr::col(TestColumn::Quantity).coalesce(0).add(1);
+        assert_eq!(expr.sql(), "(COALESCE(quantity, ?) + ?)");
+        assert_eq!(expr.values().len(), 2);
+
+        let expr = Expr::value("a").concat("b");
+        assert_eq!(expr.sql(), "(? || ?)");
+    }
+
+    #[test]
+    fn test_expr_raw() {
+        let expr = Expr::raw("MAX(quantity, ?)", vec![Value::Integer(0)]);
+        assert_eq!(expr.sql(), "MAX(quantity, ?)");
+        assert_eq!(expr.values(), &[Value::Integer(0)]);
+    }
+}
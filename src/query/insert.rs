@@ -1,127 +1,429 @@
+use std::collections::HashMap;
 use std::marker::PhantomData;
 
 use crate::ChangeSetTrait;
+use crate::ColumnTrait;
+use crate::ConnectionLike;
 use crate::Error;
+use crate::RecordTrait;
 use crate::Result;
+use crate::StatementKind;
 use crate::TableTrait;
 use crate::Value;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::connection::opts::DEFAULT_MAX_PARAMETERS;
+use crate::encryption::Encryptor;
+use crate::encryption::encrypt_columns;
+use crate::encryption::write_encryptor;
+use crate::query::comment::with_comment;
+use crate::query::interpolate::interpolate;
+use crate::traits::table::ensure_sizes;
+use crate::traits::table::ensure_writable;
+use crate::traits::table::qualified_table_name;
 
 #[derive(Clone, Debug)]
 pub struct Insert<Table: TableTrait> {
-    change_sets: Vec<Table::ChangeSet>,
-    _table:      PhantomData<Table>,
+    change_sets:            Vec<Table::ChangeSet>,
+    on_conflict_do_nothing: bool,
+    schema:                 Option<String>,
+    comment:                Option<String>,
+    _table:                 PhantomData<Table>,
 }
 
 impl<Table: TableTrait> Insert<Table> {
     pub fn new(change_set: Table::ChangeSet) -> Self {
-        Self { change_sets: vec![change_set], _table: PhantomData }
+        Self::empty().add(change_set)
     }
 
     pub fn empty() -> Self {
-        Self { change_sets: Vec::new(), _table: PhantomData }
+        Self {
+            change_sets:            Vec::new(),
+            on_conflict_do_nothing: false,
+            schema:                 None,
+            comment:                None,
+            _table:                 PhantomData,
+        }
     }
 
-    pub fn add(mut self, change_set: Table::ChangeSet) -> Self {
-        self.change_sets.push(change_set);
+    /// Inserts into the table in the attached database `schema` instead of the table's own.
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
         self
     }
 
-    pub fn add_many(mut self, change_sets: impl IntoIterator<Item = Table::ChangeSet>) -> Self {
-        self.change_sets.extend(change_sets);
+    /// Prepends `/* comment */` to the statements this builder runs, so slow-query logs can be traced back to the
+    /// call site.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
         self
     }
 
-    fn build_single(&self, change_set: &Table::ChangeSet) -> (String, Vec<Value>) {
-        let (columns, values) = change_set.get_insert_columns_and_values();
+    pub fn on_conflict_do_nothing(mut self) -> Self {
+        self.on_conflict_do_nothing = true;
+        self
+    }
 
-        if columns.is_empty() {
-            return (format!("INSERT INTO {} DEFAULT VALUES", Table::table_name()), Vec::new());
-        }
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, mut change_set: Table::ChangeSet) -> Self {
+        change_set.fill_generated_keys();
+        self.change_sets.push(change_set);
+        self
+    }
 
-        let placeholders: Vec<&str> = columns.iter().map(|_| "?").collect();
+    pub fn add_many(self, change_sets: impl IntoIterator<Item = Table::ChangeSet>) -> Self {
+        change_sets.into_iter().fold(self, Self::add)
+    }
 
-        let sql = format!(
-            "INSERT INTO {} ({}) VALUES ({})",
-            Table::table_name(),
-            columns.join(", "),
-            placeholders.join(", ")
-        );
+    fn build_single(
+        &self,
+        change_set: &Table::ChangeSet,
+        encryptor: Option<&dyn Encryptor>,
+        max_blob_size: Option<usize>,
+    ) -> Result<(String, Vec<Value>)> {
+        let (columns, mut values) = change_set.get_insert_columns_and_values();
+        ensure_sizes::<Table>(max_blob_size, &columns, &values)?;
+        encrypt_columns::<Table>(encryptor, &columns, &mut values)?;
+
+        let (sql, params) =
+            build_insert::<Table>((columns, values), self.on_conflict_do_nothing, self.schema.as_deref());
+        Ok((with_comment(self.comment.as_deref(), sql), params))
+    }
+
+    /// Whether `change_set` may be inserted past `guard`: a conflict skips it under `on_conflict_do_nothing` and fails
+    /// otherwise.
+    async fn passes_guard(
+        &self,
+        guard: &mut Option<UniqueGuard<Table>>,
+        conn: &impl ConnectionLike,
+        change_set: &Table::ChangeSet,
+    ) -> Result<bool> {
+        let Some(guard) = guard else {
+            return Ok(true);
+        };
+
+        let (columns, values) = change_set.get_insert_columns_and_values();
+        match guard.check(conn, &columns, &values).await {
+            Err(Error::UniqueViolation { .. }) if self.on_conflict_do_nothing => Ok(false),
+            result => result.map(|_| true),
+        }
+    }
 
-        (sql, values)
+    /// Renders the statements [`Insert::exec`] runs, one per change set, separated by `;`. Fails when a value is
+    /// longer than its column's `max_length`. Encrypted columns are rendered in plaintext.
+    pub fn build(&self) -> Result<(String, Vec<Value>)> {
+        let statements = self
+            .change_sets
+            .iter()
+            .map(|change_set| self.build_single(change_set, None, None))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(join_statements(statements.into_iter()))
     }
 
-    pub async fn exec(self, conn: &crate::Connection) -> Result<u64> {
+    pub async fn exec(self, conn: &impl ConnectionLike) -> Result<u64> {
+        ensure_writable::<Table>()?;
         if self.change_sets.is_empty() {
             return Ok(0);
         }
 
+        let encryptor = write_encryptor::<Table>(conn)?;
+        let mut guard = UniqueGuard::new(conn, self.schema.as_deref());
         let mut total_affected = 0u64;
 
         for change_set in &self.change_sets {
-            let (sql, params) = self.build_single(change_set);
+            if !self.passes_guard(&mut guard, conn, change_set).await? {
+                continue;
+            }
+
+            let (sql, params) = self.build_single(change_set, encryptor.as_deref(), conn.max_blob_size())?;
             let params: Vec<turso::Value> = params.into_iter().collect();
-            let affected = conn.execute(&sql, params).await?;
-            total_affected += affected;
+
+            let timer = conn.start_query(Table::table_name(), StatementKind::Insert, &sql);
+            let result = conn.execute_statement(&sql, params).await;
+            timer.finish(result.as_ref().ok().copied());
+
+            total_affected += result?;
         }
 
         Ok(total_affected)
     }
 
-    pub async fn exec_with_last_insert_id(self, conn: &crate::Connection) -> Result<i64> {
+    /// Inserts the first change set and returns its rowid. Fails with [`Error::NoRowsAffected`] when
+    /// `on_conflict_do_nothing` skips the row, since the connection's last rowid then belongs to an earlier insert.
+    pub async fn exec_with_last_insert_id(self, conn: &impl ConnectionLike) -> Result<i64> {
+        ensure_writable::<Table>()?;
         if self.change_sets.is_empty() {
             return Err(Error::Query("No recrods to insert".to_string()));
         }
 
         let change_set = self.change_sets.first().unwrap();
-        let (sql, params) = self.build_single(change_set);
+        if !self.passes_guard(&mut UniqueGuard::new(conn, self.schema.as_deref()), conn, change_set).await? {
+            return Err(Error::NoRowsAffected);
+        }
+
+        let encryptor = write_encryptor::<Table>(conn)?;
+        let (sql, params) = self.build_single(change_set, encryptor.as_deref(), conn.max_blob_size())?;
         tracing::debug!("Insert SQL: {}", sql);
         tracing::debug!("Insert Params: {:?}", params);
 
-        conn.execute(&sql, params).await?;
+        let timer = conn.start_query(Table::table_name(), StatementKind::Insert, &sql);
+        let result = conn.execute_statement(&sql, params).await;
+        timer.finish(result.as_ref().ok().copied());
+
+        if result? == 0 {
+            return Err(Error::NoRowsAffected);
+        }
         Ok(conn.last_insert_rowid())
     }
+
+    pub async fn exec_with_returning(self, conn: &impl ConnectionLike) -> Result<Table::Record> {
+        ensure_writable::<Table>()?;
+        let change_set = self.change_sets.first().ok_or_else(|| Error::Query("No records to insert".to_string()))?;
+        if !self.passes_guard(&mut UniqueGuard::new(conn, self.schema.as_deref()), conn, change_set).await? {
+            return Err(Error::NoRowsAffected);
+        }
+
+        let encryptor = write_encryptor::<Table>(conn)?;
+        let (base_sql, params) = self.build_single(change_set, encryptor.as_deref(), conn.max_blob_size())?;
+        let sql = format!("{} RETURNING {}", base_sql, Table::all_columns());
+
+        let timer = conn.start_query(Table::table_name(), StatementKind::Insert, &sql);
+        let result = async {
+            let mut rows = conn.query_rows(&sql, params).await?;
+            let row = rows.next().await?.ok_or(Error::NoRowsAffected)?;
+
+            // The insert only commits once the statement has run to completion.
+            while rows.next().await?.is_some() {}
+
+            Table::Record::decode_row(&row, encryptor.as_deref())
+        }
+        .await;
+        timer.finish(result.as_ref().ok().map(|_| 1));
+
+        result
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct InsertMany<Table: TableTrait> {
     change_sets: Vec<Table::ChangeSet>,
+    schema:      Option<String>,
+    comment:     Option<String>,
     _table:      PhantomData<Table>,
 }
 
 impl<Table: TableTrait> InsertMany<Table> {
-    pub fn new(change_sets: Vec<Table::ChangeSet>) -> Self {
-        Self { change_sets, _table: PhantomData }
+    pub fn new(mut change_sets: Vec<Table::ChangeSet>) -> Self {
+        change_sets.iter_mut().for_each(ChangeSetTrait::fill_generated_keys);
+        Self { change_sets, schema: None, comment: None, _table: PhantomData }
+    }
+
+    /// Inserts into the table in the attached database `schema` instead of the table's own.
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Prepends `/* comment */` to the statements this builder runs, so slow-query logs can be traced back to the
+    /// call site.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
     }
 
-    pub async fn exec(self, conn: &crate::Connection) -> Result<u64> {
+    /// Inserts the change sets with as few multi-row statements as the connection's
+    /// [`max_parameters`](ConnectionLike::max_parameters) allows, all in one transaction.
+    pub async fn exec(self, conn: &impl ConnectionLike) -> Result<u64> {
+        ensure_writable::<Table>()?;
         if self.change_sets.is_empty() {
             return Ok(0);
         }
 
-        let mut total_affected = 0u64;
+        conn.in_transaction(self.exec_batches(conn)).await
+    }
+
+    async fn exec_batches(&self, conn: &impl ConnectionLike) -> Result<u64> {
+        let encryptor = write_encryptor::<Table>(conn)?;
+        let mut guard = UniqueGuard::<Table>::new(conn, self.schema.as_deref());
+        let mut rows = Vec::with_capacity(self.change_sets.len());
 
         for change_set in &self.change_sets {
-            let (columns, values) = change_set.get_insert_columns_and_values();
-
-            let sql = if columns.is_empty() {
-                format!("INSERT INTO {} DEFAULT VALUES", Table::table_name())
-            } else {
-                let placeholders: Vec<&str> = columns.iter().map(|_| "?").collect();
-                format!(
-                    "INSERT INTO {} ({}) VALUES ({})",
-                    Table::table_name(),
-                    columns.join(", "),
-                    placeholders.join(", ")
-                )
-            };
+            let (columns, mut values) = change_set.get_insert_columns_and_values();
+            if let Some(guard) = &mut guard {
+                guard.check(conn, &columns, &values).await?;
+            }
+            ensure_sizes::<Table>(conn.max_blob_size(), &columns, &values)?;
+            encrypt_columns::<Table>(encryptor.as_deref(), &columns, &mut values)?;
+            rows.push((columns, values));
+        }
+
+        let mut total_affected = 0u64;
+        for (sql, params) in build_insert_batch::<Table>(rows, self.schema.as_deref(), conn.max_parameters()) {
+            let sql = with_comment(self.comment.as_deref(), sql);
 
-            let params: Vec<turso::Value> = values.into_iter().collect();
-            let affected = conn.execute(&sql, params).await?;
-            total_affected += affected;
+            let timer = conn.start_query(Table::table_name(), StatementKind::Insert, &sql);
+            let result = conn.execute_statement(&sql, params).await;
+            timer.finish(result.as_ref().ok().copied());
+
+            total_affected += result?;
         }
 
         Ok(total_affected)
     }
+
+    /// Renders the statements [`InsertMany::exec`] runs under the default parameter limit, separated by `;`. Fails
+    /// when a value is longer than its column's `max_length`. Encrypted columns are rendered in plaintext.
+    pub fn build(&self) -> Result<(String, Vec<Value>)> {
+        let rows: Vec<_> = self.change_sets.iter().map(ChangeSetTrait::get_insert_columns_and_values).collect();
+        for (columns, values) in &rows {
+            ensure_sizes::<Table>(None, columns, values)?;
+        }
+
+        Ok(join_statements(
+            build_insert_batch::<Table>(rows, self.schema.as_deref(), DEFAULT_MAX_PARAMETERS)
+                .into_iter()
+                .map(|(sql, params)| (with_comment(self.comment.as_deref(), sql), params)),
+        ))
+    }
+}
+
+impl<Table: TableTrait> std::fmt::Display for Insert<Table> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.build() {
+            Ok((sql, params)) => f.write_str(&interpolate(&sql, &params)),
+            Err(err) => write!(f, "<invalid insert: {}>", err),
+        }
+    }
+}
+
+impl<Table: TableTrait> std::fmt::Display for InsertMany<Table> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.build() {
+            Ok((sql, params)) => f.write_str(&interpolate(&sql, &params)),
+            Err(err) => write!(f, "<invalid insert: {}>", err),
+        }
+    }
+}
+
+fn build_insert<Table: TableTrait>(
+    (columns, values): (Vec<&'static str>, Vec<Value>),
+    on_conflict_do_nothing: bool,
+    schema: Option<&str>,
+) -> (String, Vec<Value>) {
+    let table = qualified_table_name::<Table>(schema);
+
+    if columns.is_empty() {
+        return (format!("INSERT INTO {} DEFAULT VALUES", table), Vec::new());
+    }
+
+    let placeholders: Vec<&str> = columns.iter().map(|_| "?").collect();
+
+    let mut sql = format!("INSERT INTO {} ({}) VALUES ({})", table, columns.join(", "), placeholders.join(", "));
+
+    // SQLite accepts no upsert clause after DEFAULT VALUES.
+    if on_conflict_do_nothing {
+        sql.push_str(" ON CONFLICT DO NOTHING");
+    }
+
+    (sql, values)
+}
+
+/// Multi-row `INSERT`s for `rows` of `(columns, values)`, one statement per set of columns the rows set, split where
+/// a statement would exceed `max_parameters`. Statements are ordered by the first row of each, so rows setting
+/// different columns may be inserted out of order. A row with more columns than `max_parameters` gets a statement of
+/// its own.
+pub(crate) fn build_insert_batch<Table: TableTrait>(
+    rows: Vec<(Vec<&'static str>, Vec<Value>)>,
+    schema: Option<&str>,
+    max_parameters: usize,
+) -> Vec<(String, Vec<Value>)> {
+    let table = qualified_table_name::<Table>(schema);
+    let mut statements: Vec<(String, Vec<Value>)> = Vec::new();
+    let mut open: HashMap<Vec<&'static str>, usize> = HashMap::new();
+
+    for (columns, values) in rows {
+        if columns.is_empty() {
+            statements.push((format!("INSERT INTO {} DEFAULT VALUES", table), Vec::new()));
+            continue;
+        }
+
+        let placeholders = format!("({})", vec!["?"; columns.len()].join(", "));
+        match open.get(&columns).map(|&index| &mut statements[index]) {
+            Some((sql, params)) if params.len() + values.len() <= max_parameters => {
+                sql.push_str(", ");
+                sql.push_str(&placeholders);
+                params.extend(values);
+            }
+            _ => {
+                statements
+                    .push((format!("INSERT INTO {} ({}) VALUES {}", table, columns.join(", "), placeholders), values));
+                open.insert(columns, statements.len() - 1);
+            }
+        }
+    }
+
+    statements
+}
+
+/// Stands in for unique indexes under MVCC, where turso can't create them: each unique column a row sets is looked
+/// up in the table, and among the rows checked before it, ahead of the insert. Concurrent writers are only kept apart
+/// when the check and insert share a transaction.
+pub(crate) struct UniqueGuard<Table: TableTrait> {
+    table:   String,
+    columns: Vec<&'static str>,
+    checked: Vec<(&'static str, Value)>,
+    _table:  PhantomData<Table>,
+}
+
+impl<Table: TableTrait> UniqueGuard<Table> {
+    /// `None` unless the connection runs in MVCC mode and `Table` has unique columns.
+    pub(crate) fn new(conn: &impl ConnectionLike, schema: Option<&str>) -> Option<Self> {
+        let columns: Vec<&'static str> = Table::Column::all()
+            .iter()
+            .filter(|column| column.is_unique() && !column.is_primary_key())
+            .map(|column| column.name())
+            .collect();
+
+        (conn.is_mvcc_enabled() && !columns.is_empty()).then(|| Self {
+            table: qualified_table_name::<Table>(schema),
+            columns,
+            checked: Vec::new(),
+            _table: PhantomData,
+        })
+    }
+
+    /// Fails with [`Error::UniqueViolation`] if any unique column of the row already holds its value.
+    pub(crate) async fn check(&mut self, conn: &impl ConnectionLike, columns: &[&str], values: &[Value]) -> Result<()> {
+        for (name, value) in columns.iter().zip(values) {
+            let Some(column) = self.columns.iter().copied().find(|column| column == name) else {
+                continue;
+            };
+            if matches!(value, Value::Null) {
+                continue;
+            }
+
+            let pending =
+                self.checked.iter().any(|(checked, checked_value)| *checked == column && checked_value == value);
+            let sql = format!("SELECT 1 FROM {} WHERE {} = ? LIMIT 1", self.table, column);
+            if pending || conn.query_rows(&sql, vec![value.clone()]).await?.next().await?.is_some() {
+                return Err(Error::UniqueViolation { constraint: format!("{}.{}", Table::table_name(), column) });
+            }
+
+            self.checked.push((column, value.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+fn join_statements(statements: impl Iterator<Item = (String, Vec<Value>)>) -> (String, Vec<Value>) {
+    let mut sql = Vec::new();
+    let mut params = Vec::new();
+    for (statement, values) in statements {
+        sql.push(statement);
+        params.extend(values);
+    }
+
+    (sql.join("; "), params)
 }
 
 #[cfg(test)]
@@ -193,7 +495,7 @@ mod tests {
         }
 
         fn get_primary_key_value(&self) -> Option<Value> {
-            self.id.clone().take().map(|v| Value::Integer(v))
+            self.id.clone().take().map(Value::Integer)
         }
 
         fn primary_key_column() -> &'static str {
@@ -241,6 +543,7 @@ mod tests {
     impl TableTrait for TestTable {
         type ChangeSet = TestChangeSet;
         type Column = TestColumn;
+        type PrimaryKeyType = i64;
         type Record = TestRecord;
 
         fn table_name() -> &'static str {
@@ -251,6 +554,10 @@ mod tests {
             TestColumn::Id
         }
 
+        fn primary_key_to_value(id: &i64) -> Value {
+            Value::Integer(*id)
+        }
+
         fn primary_key_auto_increment() -> bool {
             true
         }
@@ -264,6 +571,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_build_insert_batch_groups_rows_by_columns() {
+        let rows = vec![
+            (vec!["name"], vec![Value::Text("a".to_string())]),
+            (vec!["name"], vec![Value::Text("b".to_string())]),
+            (vec!["name", "email"], vec![Value::Text("c".to_string()), Value::Null]),
+            (vec![], vec![]),
+            (vec!["name"], vec![Value::Text("d".to_string())]),
+            (vec!["name", "email"], vec![Value::Text("e".to_string()), Value::Null]),
+        ];
+
+        let statements = build_insert_batch::<TestTable>(rows, None, DEFAULT_MAX_PARAMETERS);
+        let sql: Vec<&str> = statements.iter().map(|(sql, _)| sql.as_str()).collect();
+        assert_eq!(
+            sql,
+            vec![
+                "INSERT INTO test_users (name) VALUES (?), (?), (?)",
+                "INSERT INTO test_users (name, email) VALUES (?, ?), (?, ?)",
+                "INSERT INTO test_users DEFAULT VALUES",
+            ]
+        );
+        assert_eq!(
+            statements[0].1,
+            vec![Value::Text("a".to_string()), Value::Text("b".to_string()), Value::Text("d".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_build_insert_batch_respects_parameter_limit() {
+        let rows: Vec<_> = (0..5)
+            .map(|i| (vec!["name", "email"], vec![Value::Text(format!("n{}", i)), Value::Text(format!("e{}", i))]))
+            .collect();
+
+        let statements = build_insert_batch::<TestTable>(rows, None, 5);
+        let sql: Vec<&str> = statements.iter().map(|(sql, _)| sql.as_str()).collect();
+        assert_eq!(
+            sql,
+            vec![
+                "INSERT INTO test_users (name, email) VALUES (?, ?), (?, ?)",
+                "INSERT INTO test_users (name, email) VALUES (?, ?), (?, ?)",
+                "INSERT INTO test_users (name, email) VALUES (?, ?)",
+            ]
+        );
+
+        let wide = vec![(vec!["name", "email"], vec![Value::Null, Value::Null]); 2];
+        assert_eq!(build_insert_batch::<TestTable>(wide, None, 1).len(), 2);
+    }
+
     #[test]
     fn test_insert_new() {
         let change_set = TestChangeSet {
@@ -331,6 +686,38 @@ mod tests {
         assert!(format!("{:?}", insert).contains("Alice"));
     }
 
+    #[test]
+    fn test_insert_build_single() {
+        let change_set = TestChangeSet { name: set("Alice".to_string()), ..Default::default() };
+        let insert = Insert::<TestTable>::new(change_set.clone());
+
+        let (sql, params) = insert.build_single(&change_set, None, None).unwrap();
+        assert_eq!(sql, "INSERT INTO test_users (name) VALUES (?)");
+        assert_eq!(params, vec![Value::Text("Alice".to_string())]);
+
+        let insert = insert.on_conflict_do_nothing();
+        let (sql, _) = insert.build_single(&change_set, None, None).unwrap();
+        assert_eq!(sql, "INSERT INTO test_users (name) VALUES (?) ON CONFLICT DO NOTHING");
+
+        let (sql, _) = insert.build_single(&TestChangeSet::default(), None, None).unwrap();
+        assert_eq!(sql, "INSERT INTO test_users DEFAULT VALUES");
+    }
+
+    #[test]
+    fn test_insert_build_and_display() {
+        let alice = TestChangeSet { name: set("Alice".to_string()), ..Default::default() };
+        let bob = TestChangeSet { email: set("bob@example.com".to_string()), ..Default::default() };
+        let insert = Insert::<TestTable>::new(alice).add(bob);
+
+        let (sql, params) = insert.build().unwrap();
+        assert_eq!(sql, "INSERT INTO test_users (name) VALUES (?); INSERT INTO test_users (email) VALUES (?)");
+        assert_eq!(params.len(), 2);
+        assert_eq!(
+            insert.to_string(),
+            "INSERT INTO test_users (name) VALUES ('Alice'); INSERT INTO test_users (email) VALUES ('bob@example.com')"
+        );
+    }
+
     #[test]
     fn test_insert_with_empty_change_set() {
         let change_set = TestChangeSet::default();
@@ -0,0 +1,65 @@
+use crate::Value;
+
+/// Substitutes `params` for the `?` placeholders in `sql` as SQL literals, for logging and debugging only.
+///
+/// Placeholders inside quoted strings and identifiers are left alone. Missing parameters leave the `?` in place.
+pub(crate) fn interpolate(sql: &str, params: &[Value]) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut params = params.iter();
+    let mut quote = None;
+
+    for c in sql.chars() {
+        match (quote, c) {
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, '?') => {
+                if let Some(param) = params.next() {
+                    out.push_str(&literal(param));
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+/// `value` as an SQL literal.
+pub(crate) fn literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Integer(v) => v.to_string(),
+        Value::Real(v) => format!("{:?}", v),
+        Value::Text(v) => format!("'{}'", v.replace('\'', "''")),
+        Value::Blob(v) => format!("X'{}'", v.iter().map(|b| format!("{:02X}", b)).collect::<String>()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate_literals() {
+        let sql = interpolate(
+            "SELECT * FROM t WHERE a = ? AND b = ? AND c = ? AND d = ? AND e IS ?",
+            &[
+                Value::Integer(-1),
+                Value::Real(2.0),
+                Value::Text("it's".to_string()),
+                Value::Blob(vec![0, 171]),
+                Value::Null,
+            ],
+        );
+
+        assert_eq!(sql, "SELECT * FROM t WHERE a = -1 AND b = 2.0 AND c = 'it''s' AND d = X'00AB' AND e IS NULL");
+    }
+
+    #[test]
+    fn test_interpolate_skips_quoted_placeholders() {
+        let sql = interpolate("SELECT '?', \"a?\" FROM t WHERE x = ? AND y = ?", &[Value::Integer(1)]);
+        assert_eq!(sql, "SELECT '?', \"a?\" FROM t WHERE x = 1 AND y = ?");
+    }
+}
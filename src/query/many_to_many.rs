@@ -0,0 +1,168 @@
+use std::marker::PhantomData;
+
+use crate::ColumnTrait;
+use crate::ConnectionLike;
+use crate::ForeignKeyInfo;
+use crate::OnDelete;
+use crate::OnUpdate;
+use crate::Result;
+use crate::Select;
+use crate::StatementKind;
+use crate::TableTrait;
+use crate::Value;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::migration::TableColumnInfo;
+use crate::migration::TableSchema;
+
+/// A many-to-many relation between the rows of `Table` and those of `Related`, kept as pairs of primary keys in a
+/// junction table. Declaring it on the table generates the helpers:
+///
+/// ```ignore
+/// #[derive(Clone, Debug, Table)]
+/// #[tursorm(many_to_many(name = "roles", related = "RoleTable", through = "user_roles"))]
+/// pub struct User {
+///     #[tursorm(primary_key, auto_increment)]
+///     pub id:   i64,
+///     pub name: String,
+/// }
+///
+/// user.add_role(&conn, admin.id).await?;
+/// user.set_roles(&conn, vec![admin.id, editor.id]).await?;
+/// let roles = user.roles().all(&conn).await?;
+/// let admins = UserTable::find().with_roles().filter(Condition::eq(RoleColumn::Name.of("roles"), "admin"));
+/// ```
+///
+/// The junction table has one column per side, `user_id` and `role_id` unless set with `local_column` and
+/// `related_column`, which together make up its primary key. Pass [`ManyToMany::schema`], here
+/// `UserTable::roles().schema()`, to the migrator along with the tables' own schemas to create it.
+///
+/// The migrator doesn't emit `ON DELETE CASCADE`, so deleting a user leaves its junction rows behind; delete users with
+/// [`DeleteCascade::detach`](crate::DeleteCascade::detach) to remove them too.
+#[derive(Debug)]
+pub struct ManyToMany<Table, Related> {
+    name:           &'static str,
+    through:        &'static str,
+    local_column:   &'static str,
+    related_column: &'static str,
+    _tables:        PhantomData<fn() -> (Table, Related)>,
+}
+
+impl<Table: TableTrait, Related: TableTrait> ManyToMany<Table, Related> {
+    pub const fn new(
+        name: &'static str,
+        through: &'static str,
+        local_column: &'static str,
+        related_column: &'static str,
+    ) -> Self {
+        Self { name, through, local_column, related_column, _tables: PhantomData }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The junction table.
+    pub fn through(&self) -> &'static str {
+        self.through
+    }
+
+    /// The junction column holding the primary key of `Table`.
+    pub fn local_column(&self) -> &'static str {
+        self.local_column
+    }
+
+    /// The junction column holding the primary key of `Related`.
+    pub fn related_column(&self) -> &'static str {
+        self.related_column
+    }
+
+    /// The schema of the junction table.
+    pub fn schema(&self) -> TableSchema {
+        TableSchema::from_columns(
+            self.through,
+            vec![junction_column::<Table>(self.local_column), junction_column::<Related>(self.related_column)],
+        )
+    }
+
+    /// The rows of `Related` attached to the row of `Table` with primary key `owner`.
+    pub fn related(&self, owner: Value) -> Select<Related> {
+        Select::new().attached_to(self, owner)
+    }
+
+    /// Attaches `related` to `owner`; returns `false` if it already was.
+    #[tracing::instrument(skip(self, conn), fields(through = self.through))]
+    pub async fn attach<Conn: ConnectionLike>(&self, conn: &Conn, owner: Value, related: Value) -> Result<bool> {
+        let sql = format!(
+            "INSERT INTO {} ({}, {}) VALUES (?, ?) ON CONFLICT DO NOTHING",
+            self.through, self.local_column, self.related_column
+        );
+        Ok(self.execute(conn, StatementKind::Insert, &sql, vec![owner, related]).await? > 0)
+    }
+
+    /// Detaches `related` from `owner`; returns `false` if it wasn't attached.
+    #[tracing::instrument(skip(self, conn), fields(through = self.through))]
+    pub async fn detach<Conn: ConnectionLike>(&self, conn: &Conn, owner: Value, related: Value) -> Result<bool> {
+        let sql =
+            format!("DELETE FROM {} WHERE {} = ? AND {} = ?", self.through, self.local_column, self.related_column);
+        Ok(self.execute(conn, StatementKind::Delete, &sql, vec![owner, related]).await? > 0)
+    }
+
+    /// Makes `related` exactly the rows attached to `owner`, in one transaction (or the caller's open one). Pairs
+    /// that are already attached are kept.
+    #[tracing::instrument(skip(self, conn, related), fields(through = self.through))]
+    pub async fn set<Conn: ConnectionLike>(&self, conn: &Conn, owner: Value, related: Vec<Value>) -> Result<()> {
+        conn.in_transaction(async {
+            let mut sql = format!("DELETE FROM {} WHERE {} = ?", self.through, self.local_column);
+            if !related.is_empty() {
+                let placeholders = vec!["?"; related.len()].join(", ");
+                sql.push_str(&format!(" AND {} NOT IN ({})", self.related_column, placeholders));
+            }
+            let params = std::iter::once(owner.clone()).chain(related.iter().cloned()).collect();
+            self.execute(conn, StatementKind::Delete, &sql, params).await?;
+
+            for related in related {
+                self.attach(conn, owner.clone(), related).await?;
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn execute<Conn: ConnectionLike>(
+        &self,
+        conn: &Conn,
+        kind: StatementKind,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64> {
+        let timer = conn.start_query(self.through, kind, sql);
+        let result = conn.execute_statement(sql, params).await;
+        timer.finish(result.as_ref().ok().copied());
+
+        result
+    }
+}
+
+/// A junction column referencing the primary key of `Table`.
+fn junction_column<Table: TableTrait>(name: &'static str) -> TableColumnInfo {
+    let primary_key = Table::primary_key();
+    TableColumnInfo {
+        name,
+        column_type: primary_key.column_type(),
+        nullable: false,
+        is_primary_key: true,
+        is_auto_increment: false,
+        is_unique: false,
+        default_value: None,
+        renamed_from: None,
+        foreign_key: Some(ForeignKeyInfo {
+            table_name:  Table::table_name().to_string(),
+            column_name: primary_key.name().to_string(),
+            on_delete:   OnDelete::None,
+            on_update:   OnUpdate::None,
+        }),
+        collation: None,
+        docs: None,
+    }
+}
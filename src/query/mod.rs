@@ -1,8 +1,19 @@
+pub(crate) mod cascade;
+pub(crate) mod comment;
 pub(crate) mod condition;
+pub(crate) mod condition_builder;
+pub(crate) mod cursor;
 pub(crate) mod delete;
+pub(crate) mod explain;
+pub(crate) mod expr;
 pub(crate) mod insert;
+pub(crate) mod interpolate;
+pub(crate) mod many_to_many;
+pub(crate) mod query_spec;
 pub(crate) mod select;
+pub(crate) mod unit_of_work;
 pub(crate) mod update;
+pub(crate) mod window;
 
 pub(crate) use condition::Condition;
 pub(crate) use delete::Delete;
@@ -11,12 +22,33 @@ pub(crate) use select::Select;
 pub(crate) use update::Update;
 
 pub mod prelude {
+    pub use super::cascade::DeleteCascade;
+    pub use super::condition::Collation;
+    pub use super::condition::ColumnTuple;
     pub use super::condition::Condition;
+    pub use super::condition::ConditionGroup;
+    pub use super::condition::Nulls;
     pub use super::condition::Order;
     pub use super::condition::OrderBy;
+    pub use super::condition::ValueTuple;
+    pub use super::condition_builder::ConditionBuilder;
+    pub use super::condition_builder::FilterOperator;
+    pub use super::cursor::Cursor;
+    pub use super::cursor::Page;
     pub use super::delete::Delete;
+    pub use super::explain::QueryPlanStep;
+    pub use super::expr::Expr;
     pub use super::insert::Insert;
     pub use super::insert::InsertMany;
+    pub use super::many_to_many::ManyToMany;
+    pub use super::query_spec::QueryPolicy;
+    pub use super::query_spec::QuerySpec;
     pub use super::select::Select;
+    pub use super::select::SelectModel;
+    pub use super::unit_of_work::UnitOfWork;
     pub use super::update::Update;
+    pub use super::window::Window;
+    pub use super::window::WindowExpr;
+    pub use super::window::partition_by;
+    pub use super::window::row_number;
 }
@@ -0,0 +1,336 @@
+use crate::ColumnTrait;
+use crate::ConditionBuilder;
+use crate::Error;
+use crate::FilterOperator;
+use crate::Order;
+use crate::OrderBy;
+use crate::Result;
+use crate::Select;
+use crate::TableTrait;
+
+/// A list query described by untrusted input, such as a web API's query parameters, compiled into a [`Select`] by
+/// [`QuerySpec::compile`].
+///
+/// Nothing in a spec reaches the SQL as text: column names must name a whitelisted column of the table, operators a
+/// whitelisted [`FilterOperator`], and values are parsed by column type and bound as parameters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuerySpec {
+    /// `(column, operator, value)` triples, `AND`ed together; operators use [`FilterOperator::from_name`] names.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub filters:  Vec<(String, String, String)>,
+    /// Column names to sort by, each prefixed with `-` to sort descending, e.g. `["-created_at", "id"]`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sort:     Vec<String>,
+    /// 1-based page number.
+    pub page:     Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+impl QuerySpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn filter(mut self, column: impl Into<String>, operator: impl Into<String>, value: impl Into<String>) -> Self {
+        self.filters.push((column.into(), operator.into(), value.into()));
+        self
+    }
+
+    pub fn sort(mut self, key: impl Into<String>) -> Self {
+        self.sort.push(key.into());
+        self
+    }
+
+    pub fn page(mut self, page: usize, per_page: usize) -> Self {
+        self.page = Some(page);
+        self.per_page = Some(per_page);
+        self
+    }
+
+    /// Validates the spec against `policy` and builds the select it describes.
+    pub fn compile<Table: TableTrait>(&self, policy: &QueryPolicy<Table>) -> Result<Select<Table>> {
+        let mut select = Select::new();
+
+        if !self.filters.is_empty() {
+            let conditions =
+                self.filters.iter().try_fold(policy.conditions.clone(), |builder, (column, operator, value)| {
+                    builder.filter_by(column, operator, value)
+                })?;
+            select = select.filter(conditions.build());
+        }
+
+        for key in &self.sort {
+            let (name, direction) = match key.strip_prefix('-') {
+                Some(name) => (name, Order::Desc),
+                None => (key.as_str(), Order::Asc),
+            };
+            select = select.order_by(OrderBy::new(policy.sort_column(name)?, direction));
+        }
+
+        if self.page.is_some() || self.per_page.is_some() {
+            let per_page = self.per_page.unwrap_or(policy.max_per_page);
+            if per_page == 0 || per_page > policy.max_per_page {
+                return Err(Error::Query(format!(
+                    "Page size must be between 1 and {}, got {}",
+                    policy.max_per_page, per_page
+                )));
+            }
+
+            let page = self.page.unwrap_or(1);
+            let offset = page
+                .checked_sub(1)
+                .and_then(|skipped| skipped.checked_mul(per_page))
+                .ok_or_else(|| Error::Query(format!("Invalid page number {}", page)))?;
+
+            select = select.limit(per_page).offset(offset);
+        }
+
+        Ok(select)
+    }
+}
+
+/// The columns and operators a [`QuerySpec`] may use for a table, and the largest page it may ask for.
+///
+/// By default every column can be filtered with `eq`, every column but encrypted ones can be sorted by, and pages
+/// hold at most 100 rows.
+#[derive(Debug)]
+pub struct QueryPolicy<Table: TableTrait> {
+    conditions:   ConditionBuilder<Table>,
+    sort_columns: Option<Vec<Table::Column>>,
+    max_per_page: usize,
+}
+
+impl<Table: TableTrait> Clone for QueryPolicy<Table> {
+    fn clone(&self) -> Self {
+        Self {
+            conditions:   self.conditions.clone(),
+            sort_columns: self.sort_columns.clone(),
+            max_per_page: self.max_per_page,
+        }
+    }
+}
+
+impl<Table: TableTrait> QueryPolicy<Table> {
+    pub fn new() -> Self {
+        Self { conditions: ConditionBuilder::new(), sort_columns: None, max_per_page: 100 }
+    }
+
+    pub fn allow_filter_columns(mut self, columns: impl IntoIterator<Item = Table::Column>) -> Self {
+        self.conditions = self.conditions.allow_columns(columns);
+        self
+    }
+
+    pub fn allow_operators(mut self, operators: impl IntoIterator<Item = FilterOperator>) -> Self {
+        self.conditions = self.conditions.allow_operators(operators);
+        self
+    }
+
+    pub fn allow_sort_columns(mut self, columns: impl IntoIterator<Item = Table::Column>) -> Self {
+        self.sort_columns = Some(columns.into_iter().collect());
+        self
+    }
+
+    /// The largest `per_page` accepted, also used when a spec asks for a page without a size.
+    pub fn max_per_page(mut self, max_per_page: usize) -> Self {
+        self.max_per_page = max_per_page;
+        self
+    }
+
+    fn sort_column(&self, name: &str) -> Result<Table::Column> {
+        let column = match &self.sort_columns {
+            Some(columns) => columns.iter().copied().find(|column| column.name() == name),
+            None => Table::Column::from_name(name).filter(|column| !column.is_encrypted()),
+        };
+
+        column.ok_or_else(|| Error::Query(format!("Unknown sort column `{}`", name)))
+    }
+}
+
+impl<Table: TableTrait> Default for QueryPolicy<Table> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChangeSetTrait;
+    use crate::ColumnType;
+    use crate::FromRow;
+    use crate::RecordTrait;
+    use crate::Value;
+
+    #[derive(Clone, Debug)]
+    struct TestRecord;
+
+    impl RecordTrait for TestRecord {
+        type Table = TestTable;
+
+        fn get_primary_key_value(&self) -> Value {
+            Value::Integer(1)
+        }
+    }
+
+    impl FromRow for TestRecord {
+        fn from_row(_row: &turso::Row) -> Result<Self> {
+            Ok(TestRecord)
+        }
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct TestChangeSet;
+
+    impl ChangeSetTrait for TestChangeSet {
+        type Table = TestTable;
+
+        fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<Value>) {
+            (vec![], vec![])
+        }
+
+        fn get_update_sets(&self) -> Vec<(&'static str, Value)> {
+            vec![]
+        }
+
+        fn get_primary_key_value(&self) -> Option<Value> {
+            None
+        }
+
+        fn primary_key_column() -> &'static str {
+            "id"
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum TestColumn {
+        Id,
+        Name,
+        Ssn,
+    }
+
+    impl std::fmt::Display for TestColumn {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.name())
+        }
+    }
+
+    impl ColumnTrait for TestColumn {
+        fn name(&self) -> &'static str {
+            match self {
+                TestColumn::Id => "id",
+                TestColumn::Name => "name",
+                TestColumn::Ssn => "ssn",
+            }
+        }
+
+        fn column_type(&self) -> ColumnType {
+            match self {
+                TestColumn::Id => ColumnType::Integer,
+                TestColumn::Name | TestColumn::Ssn => ColumnType::Text,
+            }
+        }
+
+        fn is_encrypted(&self) -> bool {
+            matches!(self, TestColumn::Ssn)
+        }
+
+        fn all() -> &'static [Self] {
+            &[TestColumn::Id, TestColumn::Name, TestColumn::Ssn]
+        }
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct TestTable;
+
+    impl TableTrait for TestTable {
+        type ChangeSet = TestChangeSet;
+        type Column = TestColumn;
+        type PrimaryKeyType = i64;
+        type Record = TestRecord;
+
+        fn table_name() -> &'static str {
+            "test"
+        }
+
+        fn primary_key() -> Self::Column {
+            TestColumn::Id
+        }
+
+        fn primary_key_to_value(id: &i64) -> Value {
+            Value::Integer(*id)
+        }
+
+        fn primary_key_auto_increment() -> bool {
+            true
+        }
+
+        fn all_columns() -> &'static str {
+            "id, name, ssn"
+        }
+
+        fn column_count() -> usize {
+            3
+        }
+    }
+
+    fn compile_error(spec: QuerySpec, policy: &QueryPolicy<TestTable>) -> String {
+        spec.compile(policy).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_compile() {
+        let policy = QueryPolicy::<TestTable>::new().allow_operators([FilterOperator::Eq, FilterOperator::Gt]);
+        let spec = QuerySpec::new().filter("id", "gt", "3").filter("name", "eq", "bob").sort("-id").page(3, 20);
+
+        let (sql, params) = spec.compile(&policy).unwrap().build();
+        assert_eq!(
+            sql,
+            "SELECT id, name, ssn FROM test WHERE ((id > ?) AND (name = ?)) ORDER BY id DESC LIMIT 20 OFFSET 40"
+        );
+        assert_eq!(params, vec![Value::Integer(3), Value::Text("bob".to_string())]);
+    }
+
+    #[test]
+    fn test_compile_rejects_disallowed_filters() {
+        let policy = QueryPolicy::<TestTable>::new().allow_filter_columns([TestColumn::Name]);
+
+        assert!(
+            compile_error(QuerySpec::new().filter("id", "eq", "1"), &policy).contains("Unknown filter column `id`")
+        );
+        assert!(
+            compile_error(QuerySpec::new().filter("name", "like", "%a%"), &policy)
+                .contains("Filter operator `like` is not allowed")
+        );
+        assert!(
+            compile_error(QuerySpec::new().filter("name; DROP TABLE test", "eq", "a"), &policy)
+                .contains("Unknown filter column")
+        );
+        assert!(compile_error(QuerySpec::new().filter("name", "drop", "a"), &policy).contains("is not allowed"));
+    }
+
+    #[test]
+    fn test_compile_rejects_disallowed_sorts() {
+        let policy = QueryPolicy::<TestTable>::new();
+        assert!(compile_error(QuerySpec::new().sort("ssn"), &policy).contains("Unknown sort column `ssn`"));
+        assert!(compile_error(QuerySpec::new().sort("-ssn"), &policy).contains("Unknown sort column `ssn`"));
+        assert!(compile_error(QuerySpec::new().sort("id DESC"), &policy).contains("Unknown sort column"));
+        assert!(QuerySpec::new().sort("-name").compile(&policy).is_ok());
+
+        let policy = policy.allow_sort_columns([TestColumn::Id]);
+        assert!(compile_error(QuerySpec::new().sort("name"), &policy).contains("Unknown sort column `name`"));
+        assert!(QuerySpec::new().sort("id").compile(&policy).is_ok());
+    }
+
+    #[test]
+    fn test_compile_rejects_bad_pages() {
+        let policy = QueryPolicy::<TestTable>::new().max_per_page(50);
+        assert!(compile_error(QuerySpec::new().page(1, 0), &policy).contains("between 1 and 50, got 0"));
+        assert!(compile_error(QuerySpec::new().page(1, 51), &policy).contains("between 1 and 50, got 51"));
+        assert!(compile_error(QuerySpec::new().page(0, 10), &policy).contains("Invalid page number 0"));
+        assert!(compile_error(QuerySpec::new().page(usize::MAX, 10), &policy).contains("Invalid page number"));
+
+        let spec = QuerySpec { page: Some(2), ..QuerySpec::new() };
+        assert_eq!(spec.compile(&policy).unwrap().build().0, "SELECT id, name, ssn FROM test LIMIT 50 OFFSET 50");
+    }
+}
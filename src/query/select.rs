@@ -1,36 +1,236 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
+use crate::Collation;
+use crate::ColumnRef;
 use crate::ColumnTrait;
 use crate::Condition;
+use crate::ConnectionLike;
+use crate::Cursor;
+use crate::Error;
+use crate::Expr;
+use crate::Freshness;
 use crate::FromRow;
+use crate::Nulls;
 use crate::Order;
 use crate::OrderBy;
+use crate::Page;
+use crate::QueryRows;
+use crate::RecordTrait;
 use crate::Result;
+use crate::StatementKind;
 use crate::TableTrait;
-
-#[derive(Clone, Debug)]
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::query::comment::with_comment;
+use crate::query::explain::QueryPlanStep;
+use crate::query::interpolate::interpolate;
+use crate::query::interpolate::literal;
+use crate::query::many_to_many::ManyToMany;
+use crate::traits::table::qualified_table_name;
+use crate::value::compare_values;
+
+#[derive(Debug)]
 pub struct Select<Table: TableTrait> {
-    conditions: Vec<Condition>,
-    order_by:   Vec<OrderBy>,
-    limit:      Option<usize>,
-    offset:     Option<usize>,
-    columns:    Option<Vec<String>>,
-    _entity:    PhantomData<Table>,
+    conditions:    Vec<Condition>,
+    order_by:      Vec<OrderBy>,
+    limit:         Option<usize>,
+    offset:        Option<usize>,
+    columns:       Option<Vec<String>>,
+    /// Parameters of the expressions added with [`Select::column_expr`].
+    column_values: Vec<turso::Value>,
+    sample:        Option<usize>,
+    cursor_keys:   Vec<OrderBy>,
+    cursor:        Option<Cursor>,
+    schema:        Option<String>,
+    alias:         Option<String>,
+    joins:         Vec<Join>,
+    compounds:     Vec<(CompoundOperator, Select<Table>)>,
+    comment:       Option<String>,
+    freshness:     Option<Freshness>,
+    /// How long [`Select::cached`] keeps the rows.
+    cache_ttl:     Option<std::time::Duration>,
+    /// The primary key [`TableSelectExt::find_by_id`](crate::TableSelectExt::find_by_id) looks up, reported when
+    /// [`Select::one_or_err`] finds nothing.
+    key:           Option<turso::Value>,
+    _entity:       PhantomData<Table>,
+}
+
+// Not derived, which would require `Table: Clone`.
+impl<Table: TableTrait> Clone for Select<Table> {
+    fn clone(&self) -> Self {
+        Self {
+            conditions:    self.conditions.clone(),
+            order_by:      self.order_by.clone(),
+            limit:         self.limit,
+            offset:        self.offset,
+            columns:       self.columns.clone(),
+            column_values: self.column_values.clone(),
+            sample:        self.sample,
+            cursor_keys:   self.cursor_keys.clone(),
+            cursor:        self.cursor.clone(),
+            schema:        self.schema.clone(),
+            alias:         self.alias.clone(),
+            joins:         self.joins.clone(),
+            compounds:     self.compounds.clone(),
+            comment:       self.comment.clone(),
+            freshness:     self.freshness,
+            cache_ttl:     self.cache_ttl,
+            key:           self.key.clone(),
+            _entity:       PhantomData,
+        }
+    }
 }
 
 impl<Table: TableTrait> Select<Table> {
     pub fn new() -> Self {
         Self {
-            conditions: Vec::new(),
-            order_by:   Vec::new(),
-            limit:      None,
-            offset:     None,
-            columns:    None,
-            _entity:    PhantomData,
+            conditions:    Vec::new(),
+            order_by:      Vec::new(),
+            limit:         None,
+            offset:        None,
+            columns:       None,
+            column_values: Vec::new(),
+            sample:        None,
+            cursor_keys:   Vec::new(),
+            cursor:        None,
+            schema:        None,
+            alias:         None,
+            joins:         Vec::new(),
+            compounds:     Vec::new(),
+            comment:       None,
+            freshness:     None,
+            cache_ttl:     None,
+            key:           None,
+            _entity:       PhantomData,
         }
     }
 
+    /// Reads the table from the attached database `schema` instead of the table's own.
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Names the table `alias` in the query, so its columns can be told apart from those of joined tables with
+    /// [`ColumnTrait::of`], e.g. `UserColumn::Name.of("u")`.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Inner-joins `Other` as `alias`; joining the same table twice under different aliases gives a self-join:
+    ///
+    /// ```ignore
+    /// MessageTable::find()
+    ///     .alias("m")
+    ///     .join::<UserTable>("sender", Condition::eq_column(MessageColumn::SenderId.of("m"), UserColumn::Id.of("sender")))
+    ///     .join::<UserTable>("recipient", Condition::eq_column(MessageColumn::RecipientId.of("m"), UserColumn::Id.of("recipient")))
+    ///     .filter(Condition::eq(UserColumn::Name.of("sender"), "alice"))
+    /// ```
+    ///
+    /// Records still hydrate from the table's own columns; pick joined columns with [`Select::column`] and
+    /// [`Select::into_model`].
+    pub fn join<Other: TableTrait>(self, alias: impl Into<String>, on: Condition) -> Self {
+        self.push_join::<Other>(JoinKind::Inner, alias.into(), on)
+    }
+
+    /// Like [`Select::join`], but keeps rows without a match, with `NULL`s for the joined table's columns.
+    pub fn left_join<Other: TableTrait>(self, alias: impl Into<String>, on: Condition) -> Self {
+        self.push_join::<Other>(JoinKind::Left, alias.into(), on)
+    }
+
+    fn push_join<Other: TableTrait>(mut self, kind: JoinKind, alias: String, on: Condition) -> Self {
+        let table = qualified_table_name::<Other>(None);
+        self.joins.push(Join { kind, table, source: Other::table_name(), alias, on });
+        self
+    }
+
+    /// Inner-joins a junction table, which has no [`TableTrait`] of its own.
+    fn push_junction_join(mut self, through: &'static str, on: String) -> Self {
+        let on = Condition::raw(on, vec![]);
+        self.joins.push(Join {
+            kind: JoinKind::Inner,
+            table: through.to_string(),
+            source: through,
+            alias: through.to_string(),
+            on,
+        });
+        self
+    }
+
+    /// Inner-joins the rows `relation` attaches to each row, through its junction table. The related table is
+    /// aliased with the relation's name, e.g. `RoleColumn::Name.of("roles")` for a relation named `roles`; rows
+    /// with several related rows are returned once per related row.
+    pub fn join_related<Related: TableTrait>(self, relation: &ManyToMany<Table, Related>) -> Self {
+        let owner = self.qualifier().unwrap_or(Table::table_name()).to_string();
+        let through = relation.through();
+        let to_junction =
+            format!("{}.{} = {}.{}", through, relation.local_column(), owner, Table::primary_key().name());
+        let to_related = format!(
+            "{}.{} = {}.{}",
+            relation.name(),
+            Related::primary_key().name(),
+            through,
+            relation.related_column()
+        );
+
+        self.push_junction_join(through, to_junction).push_join::<Related>(
+            JoinKind::Inner,
+            relation.name().to_string(),
+            Condition::raw(to_related, vec![]),
+        )
+    }
+
+    /// Inner-joins the junction table of `relation` so that only rows attached to `owner` are selected.
+    pub(crate) fn attached_to<Owner: TableTrait>(
+        self,
+        relation: &ManyToMany<Owner, Table>,
+        owner: turso::Value,
+    ) -> Self {
+        let through = relation.through();
+        let on = format!(
+            "{}.{} = {}.{}",
+            through,
+            relation.related_column(),
+            Table::table_name(),
+            Table::primary_key().name()
+        );
+        let owned = format!("{}.{} = ?", through, relation.local_column());
+
+        self.push_junction_join(through, on).filter(Condition::raw(owned, vec![owner]))
+    }
+
+    /// Combines this select's rows with `other`'s, dropping duplicates, e.g. to merge several sources into one feed:
+    /// `Select::<T>::new().filter(a).union(Select::<T>::new().filter(b))`.
+    ///
+    /// Ordering, limit and offset set on `self` apply to the combined rows, while those set on `other` only limit
+    /// `other`'s rows. turso 0.3 rejects `ORDER BY` on compound selects, so ordered ones are sorted after fetching all
+    /// their rows, by columns they select. [`Select::count`] counts the fetched rows the same way.
+    pub fn union(self, other: Select<Table>) -> Self {
+        self.push_compound(CompoundOperator::Union, other)
+    }
+
+    /// Like [`Select::union`], but keeps duplicates.
+    pub fn union_all(self, other: Select<Table>) -> Self {
+        self.push_compound(CompoundOperator::UnionAll, other)
+    }
+
+    /// Only keeps rows `other` returns as well.
+    pub fn intersect(self, other: Select<Table>) -> Self {
+        self.push_compound(CompoundOperator::Intersect, other)
+    }
+
+    /// Drops rows `other` returns.
+    pub fn except(self, other: Select<Table>) -> Self {
+        self.push_compound(CompoundOperator::Except, other)
+    }
+
+    fn push_compound(mut self, operator: CompoundOperator, other: Select<Table>) -> Self {
+        self.compounds.push((operator, other));
+        self
+    }
+
     pub fn filter(mut self, condition: Condition) -> Self {
         self.conditions.push(condition);
         self
@@ -40,55 +240,146 @@ impl<Table: TableTrait> Select<Table> {
         self.filter(condition)
     }
 
-    pub fn columns<Column: ColumnTrait>(mut self, columns: Vec<Column>) -> Self {
-        self.columns = Some(columns.iter().map(|c| c.name().to_string()).collect());
+    pub fn columns<Column: ColumnRef>(mut self, columns: Vec<Column>) -> Self {
+        self.columns = Some(columns.iter().map(|c| c.qualified_name().into_owned()).collect());
+        self.column_values.clear();
+        self
+    }
+
+    /// Adds one column to the selected ones, which may come from different tables of a join.
+    pub fn column(mut self, column: impl ColumnRef) -> Self {
+        self.columns.get_or_insert_default().push(column.qualified_name().into_owned());
         self
     }
 
-    pub fn order_by_asc<Column: ColumnTrait>(mut self, column: Column) -> Self {
+    /// Adds `expr AS alias` to the selected columns, e.g. a window function:
+    ///
+    /// ```ignore
+    /// UserTable::find()
+    ///     .columns(vec![UserColumn::Id, UserColumn::Name])
+    ///     .column_expr(row_number().over(partition_by(UserColumn::State).order_by_desc(UserColumn::Id)), "rank")
+    ///     .into_model::<RankedUser>()
+    /// ```
+    pub fn column_expr(mut self, expr: Expr, alias: impl Into<String>) -> Self {
+        let column = format!("{} AS {}", expr.sql, alias.into());
+        self.columns.get_or_insert_default().push(column);
+        self.column_values.extend(expr.values);
+        self
+    }
+
+    pub fn order_by_asc<Column: ColumnRef>(mut self, column: Column) -> Self {
         self.order_by.push(OrderBy::asc(column));
         self
     }
 
-    pub fn order_by_desc<Column: ColumnTrait>(mut self, column: Column) -> Self {
+    pub fn order_by_desc<Column: ColumnRef>(mut self, column: Column) -> Self {
         self.order_by.push(OrderBy::desc(column));
         self
     }
 
-    pub fn order_by<Column: ColumnTrait>(mut self, column: Column, direction: Order) -> Self {
-        self.order_by.push(OrderBy { column: column.name().to_string(), direction });
+    /// Appends a sort key, e.g. `.order_by((Column::Age, Order::Desc, Nulls::Last)).order_by((Column::Id, Order::Asc))`.
+    pub fn order_by(mut self, order_by: impl Into<OrderBy>) -> Self {
+        self.order_by.push(order_by.into());
+        self
+    }
+
+    pub fn order_random(mut self) -> Self {
+        self.order_by.push(OrderBy::random());
         self
     }
 
+    /// Returns up to `n` random rows matching the filters.
+    pub fn sample(self, n: usize) -> Self {
+        self.order_random().limit(n)
+    }
+
+    /// Like [`Select::sample`], but shuffles only rowids in a subquery instead of sorting whole rows, which is much
+    /// cheaper on large tables. Rows come back in the select's own order rather than shuffled.
+    pub fn sample_by_rowid(mut self, n: usize) -> Self {
+        self.sample = Some(n);
+        self
+    }
+
+    /// Orders by `column` and uses it as the next key for keyset pagination; chain further calls for tie-breakers,
+    /// e.g. `.cursor_by(Column::CreatedAt).cursor_by(Column::Id)`.
+    pub fn cursor_by<Column: ColumnRef>(self, column: Column) -> Self {
+        self.push_cursor_key(OrderBy::asc(column))
+    }
+
+    pub fn cursor_by_desc<Column: ColumnRef>(self, column: Column) -> Self {
+        self.push_cursor_key(OrderBy::desc(column))
+    }
+
+    fn push_cursor_key(mut self, key: OrderBy) -> Self {
+        self.order_by.push(key.clone());
+        self.cursor_keys.push(key);
+        self
+    }
+
+    /// Only returns rows that come after `cursor` in cursor order.
+    pub fn after(mut self, cursor: impl Into<Cursor>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    /// Page size for [`Select::page`].
+    pub fn first(self, n: usize) -> Self {
+        self.limit(n)
+    }
+
     pub fn limit(mut self, limit: usize) -> Self {
         self.limit = Some(limit);
         self
     }
 
+    pub fn unlimited(mut self) -> Self {
+        self.limit = None;
+        self
+    }
+
     pub fn offset(mut self, offset: usize) -> Self {
         self.offset = Some(offset);
         self
     }
 
-    pub fn build(&self) -> (String, Vec<turso::Value>) {
-        let columns = self.columns.as_ref().map(|c| c.join(", ")).unwrap_or_else(|| Table::all_columns().to_string());
+    /// Prepends `/* comment */` to the statements this builder runs, so slow-query logs can be traced back to the
+    /// call site.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
 
-        let mut sql = format!("SELECT {} FROM {}", columns, Table::table_name());
-        let mut params = Vec::new();
+    /// On an embedded replica, syncs before reading if the database was written to since the last sync, so the
+    /// select sees those writes. A [`RoutingConnection`](crate::RoutingConnection) reads from its primary instead.
+    pub fn read_your_writes(mut self) -> Self {
+        self.freshness = Some(Freshness::ReadYourWrites);
+        self
+    }
 
-        if !self.conditions.is_empty() {
-            let where_parts: Vec<String> = self.conditions.iter().map(|c| format!("({})", c.sql())).collect();
-            sql.push_str(" WHERE ");
-            sql.push_str(&where_parts.join(" AND "));
+    /// On an embedded replica, syncs before reading if the last sync is older than `max`.
+    pub fn max_staleness(mut self, max: std::time::Duration) -> Self {
+        self.freshness = Some(Freshness::MaxStaleness(max));
+        self
+    }
 
-            for condition in &self.conditions {
-                params.extend(condition.values().iter().cloned());
-            }
-        }
+    /// Keeps the rows for `ttl` in the connection's query cache, or until the query builders write to one of the
+    /// selected or joined tables through the connection; see [`query_cache`](crate::query_cache). Without a cache on
+    /// the connection, this does nothing.
+    pub fn cached(mut self, ttl: std::time::Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    pub fn build(&self) -> (String, Vec<turso::Value>) {
+        let (sql, params) = self.build_statement();
+        (with_comment(self.comment.as_deref(), sql), params)
+    }
+
+    fn build_statement(&self) -> (String, Vec<turso::Value>) {
+        let (mut sql, params) = self.build_compound();
 
         if !self.order_by.is_empty() {
-            let order_parts: Vec<String> =
-                self.order_by.iter().map(|o| format!("{} {}", o.column, o.direction)).collect();
+            let order_parts: Vec<String> = self.order_by.iter().map(OrderBy::to_string).collect();
             sql.push_str(" ORDER BY ");
             sql.push_str(&order_parts.join(", "));
         }
@@ -104,18 +395,404 @@ impl<Table: TableTrait> Select<Table> {
         (sql, params)
     }
 
-    pub async fn all(self, conn: &crate::Connection) -> Result<Vec<Table::Record>> {
+    /// [`Select::build_compound`] with the select's comment, for fetching rows that are sorted or counted afterwards.
+    fn build_unordered_compound(&self) -> (String, Vec<turso::Value>) {
+        let (sql, params) = self.build_compound();
+        (with_comment(self.comment.as_deref(), sql), params)
+    }
+
+    /// The select and its compound parts, without the ordering, limit and offset that apply to all of them.
+    fn build_compound(&self) -> (String, Vec<turso::Value>) {
+        let columns = self.columns.as_ref().map(|c| c.join(", ")).unwrap_or_else(|| self.default_columns().join(", "));
+
+        let mut sql = format!("SELECT {}", columns);
+        let mut params = self.column_values.clone();
+        params.extend(self.push_where_clause(&mut sql));
+
+        for (operator, other) in &self.compounds {
+            let (other_sql, other_params) = other.build_statement();
+            let is_bare = other.order_by.is_empty()
+                && other.limit.is_none()
+                && other.offset.is_none()
+                && other.compounds.is_empty();
+            if is_bare {
+                sql.push_str(&format!(" {} {}", operator, other_sql));
+            } else {
+                sql.push_str(&format!(" {} SELECT * FROM ({})", operator, other_sql));
+            }
+            params.extend(other_params);
+        }
+
+        (sql, params)
+    }
+
+    pub(crate) fn schema(mut self, schema: Option<String>) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    fn table_ref(&self) -> String {
+        qualified_table_name::<Table>(self.schema.as_deref())
+    }
+
+    /// What the table's own columns are qualified with: its alias, or its name once other tables are joined.
+    fn qualifier(&self) -> Option<&str> {
+        match &self.alias {
+            Some(alias) => Some(alias),
+            None if !self.joins.is_empty() => Some(Table::table_name()),
+            None => None,
+        }
+    }
+
+    fn default_columns(&self) -> Vec<String> {
+        let names = Table::Column::all().iter().map(|column| column.name());
+        match self.qualifier() {
+            Some(qualifier) => names.map(|name| format!("{}.{}", qualifier, name)).collect(),
+            None => names.map(str::to_string).collect(),
+        }
+    }
+
+    /// The `FROM` clause with the alias and joins, and the parameters of the joins' `ON` conditions.
+    fn source_clause(&self) -> (String, Vec<turso::Value>) {
+        let mut sql = format!(" FROM {}", self.table_ref());
+        if let Some(alias) = &self.alias {
+            sql.push_str(&format!(" AS {}", alias));
+        }
+
+        let mut params = Vec::new();
+        for join in &self.joins {
+            sql.push_str(&format!(" {} {} AS {} ON ({})", join.kind, join.table, join.alias, join.on.sql()));
+            params.extend(join.on.values().iter().cloned());
+        }
+
+        (sql, params)
+    }
+
+    /// Appends the `FROM` and `WHERE` clauses and returns their parameters.
+    fn push_where_clause(&self, sql: &mut String) -> Vec<turso::Value> {
+        let (from_sql, mut params) = self.source_clause();
+        let mut where_sql = String::new();
+
+        let cursor_condition = self.cursor_condition();
+        let conditions: Vec<&Condition> = self.conditions.iter().chain(cursor_condition.as_ref()).collect();
+
+        if !conditions.is_empty() {
+            let where_parts: Vec<String> = conditions.iter().map(|c| format!("({})", c.sql())).collect();
+            where_sql = format!(" WHERE {}", where_parts.join(" AND "));
+
+            for condition in conditions {
+                params.extend(condition.values().iter().cloned());
+            }
+        }
+
+        match self.sample {
+            Some(n) => {
+                let rowid = match self.qualifier() {
+                    Some(qualifier) => format!("{}.rowid", qualifier),
+                    None => "rowid".to_string(),
+                };
+                sql.push_str(&format!(
+                    "{} WHERE {} IN (SELECT {}{}{} ORDER BY RANDOM() LIMIT {})",
+                    from_sql, rowid, rowid, from_sql, where_sql, n
+                ));
+                // The outer `FROM` binds the join parameters once more, ahead of the subquery's.
+                let join_params: Vec<turso::Value> = self.joins.iter().flat_map(|j| j.on.values().to_vec()).collect();
+                params.splice(0..0, join_params);
+            }
+            None => {
+                sql.push_str(&from_sql);
+                sql.push_str(&where_sql);
+            }
+        }
+
+        params
+    }
+
+    /// Rows after the cursor in lexicographic key order: `(a > ?) OR (a = ? AND b > ?) OR ...`.
+    fn cursor_condition(&self) -> Option<Condition> {
+        let cursor = self.cursor.as_ref()?;
+        let keys: Vec<(&OrderBy, &turso::Value)> = self.cursor_keys.iter().zip(cursor.values()).collect();
+
+        (0..keys.len())
+            .map(|i| {
+                let mut parts: Vec<String> = keys[..i].iter().map(|(key, _)| format!("{} = ?", key.column)).collect();
+                let operator = if keys[i].0.direction == Order::Asc { ">" } else { "<" };
+                parts.push(format!("{} {} ?", keys[i].0.column, operator));
+
+                let values = keys[..=i].iter().map(|(_, value)| (*value).clone()).collect();
+                Condition::raw(parts.join(" AND "), values)
+            })
+            .reduce(Condition::or)
+    }
+
+    /// Fetches one page of a keyset-paginated select set up with [`Select::cursor_by`], [`Select::after`] and
+    /// [`Select::first`]. Unlike `OFFSET`, each page costs the same no matter how deep it is.
+    pub async fn page(self, conn: &impl ConnectionLike) -> Result<Page<Table::Record>> {
+        let page_size = self.limit;
+        // One extra row tells whether there is a next page.
+        let query = match page_size {
+            Some(n) => self.limit(n + 1),
+            None => self,
+        };
+
+        query.fetch_page(conn, page_size).await
+    }
+
+    async fn fetch_page(&self, conn: &impl ConnectionLike, page_size: Option<usize>) -> Result<Page<Table::Record>> {
+        let key_indexes = self.cursor_key_indexes()?;
         let (sql, params) = self.build();
-        let params: Vec<turso::Value> = params.into_iter().collect();
 
-        let mut rows = conn.query(&sql, params).await?;
+        let encryptor = conn.column_encryptor();
+        let timer = conn.start_query(Table::table_name(), StatementKind::Select, &sql);
+        let result = async {
+            let mut rows = self.fetch_chunked_rows(conn, &sql, params).await?;
+
+            let mut items = Vec::new();
+            let mut last_keys = None;
+            while let Some(row) = rows.next().await? {
+                if page_size.is_some_and(|n| items.len() == n) {
+                    let next_cursor = last_keys.map(Cursor::new);
+                    return Ok(Page { items, next_cursor });
+                }
+
+                last_keys = Some(key_indexes.iter().map(|idx| row.get_value(*idx)).collect::<turso::Result<_>>()?);
+                items.push(Table::Record::decode_row(&row, encryptor.as_deref())?);
+            }
+
+            Ok(Page { items, next_cursor: None })
+        }
+        .await;
+        timer.finish(result.as_ref().ok().map(|page| page.items.len() as u64));
+
+        result
+    }
+
+    /// Hands every matching row to `f` in batches of up to `chunk_size`, in primary key order, for backfills and other
+    /// full-table jobs. Each batch is fetched with its own keyset query, so memory stays bounded and rows `f` changes
+    /// don't shift later batches.
+    ///
+    /// Returns the primary key of the last row processed. After an interruption, pass the last key that was
+    /// processed to [`Select::after`] to resume behind it. Any ordering set on the select is replaced.
+    pub async fn for_each_chunk<F, Fut>(
+        mut self,
+        conn: &impl ConnectionLike,
+        chunk_size: usize,
+        mut f: F,
+    ) -> Result<Option<Cursor>>
+    where
+        F: FnMut(Vec<Table::Record>) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        if chunk_size == 0 {
+            return Err(Error::Query("Select::for_each_chunk requires a chunk size above zero".to_string()));
+        }
+
+        self.order_by.clear();
+        self.cursor_keys.clear();
+        // One extra row tells whether there is another chunk.
+        let mut select = self.cursor_by(Table::primary_key()).limit(chunk_size + 1);
+        let mut last_key = None;
+
+        loop {
+            let page = select.fetch_page(conn, Some(chunk_size)).await?;
+            let Some(last) = page.items.last() else { break };
+            last_key = Some(Cursor::new(vec![last.get_primary_key_value()]));
+
+            f(page.items).await?;
+
+            match page.next_cursor {
+                Some(cursor) => select.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        Ok(last_key)
+    }
+
+    fn cursor_key_indexes(&self) -> Result<Vec<usize>> {
+        if self.cursor_keys.is_empty() {
+            return Err(Error::Query("Select::page requires at least one cursor_by column".to_string()));
+        }
+        if let Some(cursor) = &self.cursor
+            && cursor.values().len() != self.cursor_keys.len()
+        {
+            return Err(Error::Query(format!(
+                "Cursor has {} values, expected {}",
+                cursor.values().len(),
+                self.cursor_keys.len()
+            )));
+        }
+
+        self.cursor_keys
+            .iter()
+            .map(|key| {
+                self.selected_index(&key.column)
+                    .ok_or_else(|| Error::Query(format!("Cursor column `{}` is not selected", key.column)))
+            })
+            .collect()
+    }
+
+    /// Where `column` is among the selected columns, qualified or not.
+    fn selected_index(&self, column: &str) -> Option<usize> {
+        let default_columns;
+        let selected: Vec<&str> = match &self.columns {
+            Some(columns) => columns.iter().map(String::as_str).collect(),
+            None => {
+                default_columns = self.default_columns();
+                default_columns.iter().map(String::as_str).collect()
+            }
+        };
+
+        selected.iter().position(|name| *name == column || unqualified(name) == column)
+    }
+
+    pub async fn all(self, conn: &impl ConnectionLike) -> Result<Vec<Table::Record>> {
+        let (sql, params) = self.build();
         let mut results = Vec::new();
 
+        let timer = conn.start_query(Table::table_name(), StatementKind::Select, &sql);
+        let result = self.fetch_into(conn, &sql, params, &mut results).await;
+        timer.finish(result.as_ref().ok().map(|()| results.len() as u64));
+
+        result.map(|()| results)
+    }
+
+    /// When the select binds more than `max_parameters` values, the selects its longest `is_in` filter splits it into,
+    /// each returning the rows of its part of the list up to the select's limit plus offset. Fails when the select
+    /// can't be split: it is sampled or compound, or has no `is_in` filter whose chunks would fit.
+    fn split_parameters(&self, parameters: usize, max_parameters: usize) -> Result<Option<Vec<Self>>> {
+        if parameters <= max_parameters {
+            return Ok(None);
+        }
+
+        let too_many = |reason: &str| {
+            Error::Query(format!(
+                "Select binds {} parameters, more than the connection's limit of {}, and {}",
+                parameters, max_parameters, reason
+            ))
+        };
+        if self.sample.is_some() || !self.compounds.is_empty() {
+            return Err(too_many("a sampled or compound select can't be split"));
+        }
+
+        let (index, chunks) = self
+            .conditions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, condition)| {
+                let others = parameters - condition.values().len();
+                let chunks = condition.split_in(max_parameters.checked_sub(others).filter(|room| *room > 0)?)?;
+                Some((index, chunks))
+            })
+            .min_by_key(|(_, chunks)| chunks.len())
+            .ok_or_else(|| too_many("has no `is_in` filter that can be split to fit"))?;
+
+        let limit = self.limit.map(|limit| limit + self.offset.unwrap_or(0));
+        Ok(Some(
+            chunks
+                .into_iter()
+                .map(|chunk| {
+                    let mut select = self.clone();
+                    select.conditions[index] = chunk;
+                    select.limit = limit;
+                    select.offset = None;
+                    select
+                })
+                .collect(),
+        ))
+    }
+
+    /// The rows of the select, run as the chunks of [`Select::split_parameters`] when it binds too many values, or
+    /// sorted after fetching when it is an ordered compound select.
+    async fn fetch_chunked_rows(
+        &self,
+        conn: &impl ConnectionLike,
+        sql: &str,
+        params: Vec<turso::Value>,
+    ) -> Result<QueryRows> {
+        match self.split_parameters(params.len(), conn.max_parameters())? {
+            Some(chunks) => self.fetch_sorted(conn, chunks.iter().map(Select::build).collect()).await,
+            None if !self.compounds.is_empty() && !self.order_by.is_empty() => {
+                self.fetch_sorted(conn, vec![self.build_unordered_compound()]).await
+            }
+            None => self.fetch_rows(conn, sql, params).await,
+        }
+    }
+
+    /// Runs `statements` in one read transaction, then sorts their rows by the select's ordering and applies its
+    /// offset and limit, so they come back as a single ordered statement would return them.
+    async fn fetch_sorted(
+        &self,
+        conn: &impl ConnectionLike,
+        statements: Vec<(String, Vec<turso::Value>)>,
+    ) -> Result<QueryRows> {
+        let sort_keys = self.sort_keys()?;
+
+        let (columns, mut rows) = conn
+            .in_read_transaction(async {
+                let mut columns = Vec::new();
+                let mut rows = Vec::new();
+                for (sql, params) in statements {
+                    let mut chunk_rows = self.fetch_rows(conn, &sql, params).await?;
+                    columns = chunk_rows.columns().names().to_vec();
+
+                    while let Some(row) = chunk_rows.next().await? {
+                        rows.push(
+                            (0..row.column_count()).map(|idx| row.get_value(idx)).collect::<turso::Result<Vec<_>>>()?,
+                        );
+                    }
+                }
+
+                Ok((columns, rows))
+            })
+            .await?;
+
+        // Each chunk of a split select is already sorted, so the stable sort only merges them.
+        rows.sort_by(|a, b| {
+            sort_keys
+                .iter()
+                .map(|(index, order, collation)| compare_sort_values(&a[*index], &b[*index], order, *collation))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        let rows = rows.into_iter().skip(self.offset.unwrap_or(0)).take(self.limit.unwrap_or(usize::MAX)).collect();
+
+        Ok(QueryRows::from_named_values(columns, rows))
+    }
+
+    /// The position among the selected columns of each `ORDER BY` column, with the collation it sorts by.
+    fn sort_keys(&self) -> Result<Vec<(usize, &OrderBy, Option<Collation>)>> {
+        self.order_by
+            .iter()
+            .map(|order| {
+                let index = self.selected_index(&order.column).ok_or_else(|| {
+                    Error::Query(format!(
+                        "Can't sort the rows of a split `is_in` or compound select by `{}`, which is not selected",
+                        order.column
+                    ))
+                })?;
+                let collation = Table::Column::from_name(unqualified(&order.column)).and_then(|c| c.collation());
+                Ok((index, order, collation))
+            })
+            .collect()
+    }
+
+    async fn fetch_into(
+        &self,
+        conn: &impl ConnectionLike,
+        sql: &str,
+        params: Vec<turso::Value>,
+        results: &mut Vec<Table::Record>,
+    ) -> Result<()> {
+        let encryptor = conn.column_encryptor();
+        let mut rows = self.fetch_chunked_rows(conn, sql, params).await?;
+
         while let Some(row) = rows.next().await? {
-            match Table::Record::from_row(&row) {
+            match Table::Record::decode_row(&row, encryptor.as_deref()) {
                 Ok(parsed_row) => results.push(parsed_row),
+                Err(e) if cfg!(feature = "strict-decode") => return Err(e),
                 Err(e) => {
-                    let values = self.from_raw_row(&row)?;
+                    let values = self.raw_row_values(&row)?;
 
                     #[cfg(feature = "serde")]
                     tracing::warn!("Failed to parse row: {}", serde_json::to_string_pretty(&values)?);
@@ -130,57 +807,320 @@ impl<Table: TableTrait> Select<Table> {
             }
         }
 
-        Ok(results)
+        Ok(())
     }
 
-    pub async fn one(self, conn: &crate::Connection) -> Result<Option<Table::Record>> {
+    pub async fn one(self, conn: &impl ConnectionLike) -> Result<Option<Table::Record>> {
         let query = self.limit(1);
         let (sql, params) = query.build();
         tracing::trace!("SQL: {}", sql);
         tracing::trace!("Params: {:?}", params);
 
-        let mut rows = conn.query(&sql, params).await?;
-        let row = rows.next().await?;
-        tracing::trace!("Row: {:?}", row);
+        let timer = conn.start_query(Table::table_name(), StatementKind::Select, &sql);
+        let result = async {
+            let mut rows = query.fetch_chunked_rows(conn, &sql, params).await?;
+            let row = rows.next().await?;
+            tracing::trace!("Row: {:?}", row);
 
-        row.map(|r| Table::Record::from_row(&r)).transpose()
+            row.map(|r| Table::Record::decode_row(&r, conn.column_encryptor().as_deref())).transpose()
+        }
+        .await;
+        timer.finish(result.as_ref().ok().map(|record| record.is_some() as u64));
+
+        result
     }
 
-    pub async fn count(self, conn: &crate::Connection) -> Result<i64> {
-        let mut sql = format!("SELECT COUNT(*) FROM {}", Table::table_name());
-        let mut params = Vec::new();
+    /// Like [`Select::one`], but fails with [`Error::RecordNotFound`] when no row matches.
+    pub async fn one_or_err(self, conn: &impl ConnectionLike) -> Result<Table::Record> {
+        let pk = self.key.as_ref().map(literal);
+        self.one(conn).await?.ok_or(Error::RecordNotFound { table: Table::table_name(), pk })
+    }
 
-        if !self.conditions.is_empty() {
-            let where_parts: Vec<String> = self.conditions.iter().map(|c| format!("({})", c.sql())).collect();
-            sql.push_str(" WHERE ");
-            sql.push_str(&where_parts.join(" AND "));
+    /// [`Select::one_or_err`], reading better after `find_by_id`: `UserTable::find_by_id(id).required(&conn)`.
+    pub async fn required(self, conn: &impl ConnectionLike) -> Result<Table::Record> {
+        self.one_or_err(conn).await
+    }
 
-            for condition in &self.conditions {
-                params.extend(condition.values().iter().cloned());
+    pub(crate) fn by_key(mut self, key: turso::Value) -> Self {
+        self.conditions.push(Condition::eq(Table::primary_key(), key.clone()));
+        self.key = Some(key);
+        self
+    }
+
+    /// Hydrates rows into `Model` instead of the table's record, typically a `#[derive(FromRow)]` DTO whose fields
+    /// match the columns picked with [`Select::columns`] in order.
+    pub fn into_model<Model: FromRow>(self) -> SelectModel<Table, Model> {
+        SelectModel { select: self, _model: PhantomData }
+    }
+
+    pub async fn count(self, conn: &impl ConnectionLike) -> Result<u64> {
+        self.count_of(conn, "*".to_string()).await
+    }
+
+    /// Number of distinct non-null values of `column` among the matching rows.
+    pub async fn count_distinct<Column: ColumnRef>(self, conn: &impl ConnectionLike, column: Column) -> Result<u64> {
+        self.count_of(conn, format!("DISTINCT {}", column.qualified_name())).await
+    }
+
+    /// Number of matching rows where `column` is not null.
+    pub async fn count_column<Column: ColumnRef>(self, conn: &impl ConnectionLike, column: Column) -> Result<u64> {
+        self.count_of(conn, column.qualified_name().into_owned()).await
+    }
+
+    async fn count_of(self, conn: &impl ConnectionLike, argument: String) -> Result<u64> {
+        if !self.compounds.is_empty() {
+            return self.count_compound(conn, &argument).await;
+        }
+
+        let (sql, params) = self.build_count(&argument);
+        let Some(chunks) = self.split_parameters(params.len(), conn.max_parameters())? else {
+            return self.fetch_count(conn, &sql, params).await;
+        };
+        if argument.starts_with("DISTINCT ") {
+            return Err(Error::Query(format!(
+                "Select binds {} parameters, more than the connection's limit of {}, and distinct counts of its \
+                 chunks can't be added up",
+                params.len(),
+                conn.max_parameters()
+            )));
+        }
+
+        // The chunks match disjoint rows, so their counts add up to the select's.
+        conn.in_read_transaction(async {
+            let mut total = 0;
+            for chunk in &chunks {
+                let (sql, params) = chunk.build_count(&argument);
+                total += chunk.fetch_count(conn, &sql, params).await?;
+            }
+            Ok(total)
+        })
+        .await
+    }
+
+    fn build_count(&self, argument: &str) -> (String, Vec<turso::Value>) {
+        let mut sql = format!("SELECT COUNT({})", argument);
+        let params = self.push_where_clause(&mut sql);
+        (with_comment(self.comment.as_deref(), sql), params)
+    }
+
+    /// turso 0.3 can't select from a compound select, so its rows are fetched and counted.
+    async fn count_compound(self, conn: &impl ConnectionLike, argument: &str) -> Result<u64> {
+        if argument != "*" {
+            return Err(Error::Query(format!("Can't count `{}` of a compound select, only its rows", argument)));
+        }
+
+        let (sql, params) = self.build_unordered_compound();
+        let timer = conn.start_query(Table::table_name(), StatementKind::Select, &sql);
+        let result = async {
+            let mut rows = self.fetch_rows(conn, &sql, params).await?;
+            let mut count = 0;
+            while rows.next().await?.is_some() {
+                count += 1;
             }
+            Ok(count)
         }
+        .await;
+        timer.finish(result.as_ref().ok().map(|_| 1));
+
+        result
+    }
 
-        let params: Vec<turso::Value> = params.into_iter().collect();
-        let mut rows = conn.query(&sql, params).await?;
+    async fn fetch_count(&self, conn: &impl ConnectionLike, sql: &str, params: Vec<turso::Value>) -> Result<u64> {
+        let timer = conn.start_query(Table::table_name(), StatementKind::Select, sql);
+        let result: Result<u64> = async {
+            let mut rows = self.fetch_rows(conn, sql, params).await?;
 
-        if let Some(row) = rows.next().await? {
-            let value = row.get_value(0)?;
-            match value {
-                turso::Value::Integer(count) => Ok(count),
-                _ => Ok(0),
+            if let Some(row) = rows.next().await? {
+                let value = row.get_value(0)?;
+                match value {
+                    turso::Value::Integer(count) => Ok(u64::try_from(count).unwrap_or_default()),
+                    _ => Ok(0),
+                }
+            } else {
+                Ok(0)
             }
-        } else {
-            Ok(0)
         }
+        .await;
+        timer.finish(result.as_ref().ok().map(|_| 1));
+
+        result
+    }
+
+    /// The steps of `EXPLAIN QUERY PLAN` for this select, e.g. to check that its filters use an index.
+    pub async fn explain_query_plan(&self, conn: &impl ConnectionLike) -> Result<Vec<QueryPlanStep>> {
+        let (sql, params) = self.build();
+        crate::query::explain::explain_query_plan(conn, &sql, params).await
     }
 
-    pub async fn exists(self, conn: &crate::Connection) -> Result<bool> {
+    /// The query plan rendered as an indented tree, for logging.
+    pub async fn explain(&self, conn: &impl ConnectionLike) -> Result<String> {
+        Ok(crate::query::explain::render_query_plan(&self.explain_query_plan(conn).await?))
+    }
+
+    pub async fn exists(self, conn: &impl ConnectionLike) -> Result<bool> {
         let count = self.limit(1).count(conn).await?;
         Ok(count > 0)
     }
 
+    /// Streams the matching rows to `writer` as CSV with a header row, without collecting them first. Blobs are
+    /// written as hex, so the output loads back with [`Loader::from_csv`](crate::loader::Loader::from_csv). Returns
+    /// the number of rows written.
+    #[cfg(feature = "with-csv")]
+    pub async fn export_csv(self, conn: &impl ConnectionLike, writer: impl std::io::Write) -> Result<u64> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(self.column_names())?;
+
+        let count = self
+            .for_each_row(conn, |row| {
+                let mut fields = Vec::with_capacity(row.column_count());
+                for idx in 0..row.column_count() {
+                    fields.push(match row.get_value(idx)? {
+                        turso::Value::Null => String::new(),
+                        turso::Value::Integer(v) => v.to_string(),
+                        turso::Value::Real(v) => v.to_string(),
+                        turso::Value::Text(v) => v,
+                        turso::Value::Blob(v) => v.iter().map(|b| format!("{:02X}", b)).collect(),
+                    });
+                }
+                Ok(writer.write_record(fields)?)
+            })
+            .await?;
+
+        writer.flush()?;
+        Ok(count)
+    }
+
+    /// Streams the matching rows to `writer` as JSON lines, one object per row keyed by column name. Returns the
+    /// number of rows written.
+    #[cfg(any(feature = "with-json", feature = "with-arrays"))]
+    pub async fn export_jsonl(self, conn: &impl ConnectionLike, mut writer: impl std::io::Write) -> Result<u64> {
+        let names = self.column_names();
+
+        let count = self
+            .for_each_row(conn, |row| {
+                let mut line = String::from("{");
+                for (idx, name) in names.iter().enumerate() {
+                    let value = match row.get_value(idx)? {
+                        turso::Value::Null => serde_json::Value::Null,
+                        turso::Value::Integer(v) => v.into(),
+                        turso::Value::Real(v) => v.into(),
+                        turso::Value::Text(v) => v.into(),
+                        turso::Value::Blob(v) => v.into(),
+                    };
+                    if idx > 0 {
+                        line.push(',');
+                    }
+                    line.push_str(&serde_json::to_string(name)?);
+                    line.push(':');
+                    line.push_str(&value.to_string());
+                }
+                line.push('}');
+                Ok(writeln!(writer, "{}", line)?)
+            })
+            .await?;
+
+        writer.flush()?;
+        Ok(count)
+    }
+
+    #[cfg(any(feature = "with-csv", feature = "with-json", feature = "with-arrays"))]
+    fn column_names(&self) -> Vec<String> {
+        match &self.columns {
+            Some(columns) => columns.clone(),
+            None => Table::all_columns().split(", ").map(str::to_string).collect(),
+        }
+    }
+
+    /// Runs the select and hands each raw row to `f` as it is read.
+    #[cfg(any(feature = "with-csv", feature = "with-json", feature = "with-arrays"))]
+    async fn for_each_row(
+        self,
+        conn: &impl ConnectionLike,
+        mut f: impl FnMut(turso::Row) -> Result<()>,
+    ) -> Result<u64> {
+        let (sql, params) = self.build();
+
+        let timer = conn.start_query(Table::table_name(), StatementKind::Select, &sql);
+        let result = async {
+            let mut rows = self.fetch_chunked_rows(conn, &sql, params).await?;
+
+            let mut count = 0;
+            while let Some(row) = rows.next().await? {
+                f(row)?;
+                count += 1;
+            }
+
+            Ok(count)
+        }
+        .await;
+        timer.finish(result.as_ref().ok().copied());
+
+        result
+    }
+
+    /// Primary keys of the matching rows, for mutations that resolve `ORDER BY ... LIMIT` with a key lookup.
+    pub(crate) async fn primary_keys(self, conn: &impl ConnectionLike) -> Result<Vec<turso::Value>> {
+        let select = self.columns(vec![Table::primary_key()]);
+        let (sql, params) = select.build();
+
+        let timer = conn.start_query(Table::table_name(), StatementKind::Select, &sql);
+        let result = async {
+            let mut rows = select.fetch_chunked_rows(conn, &sql, params).await?;
+
+            let mut keys = Vec::new();
+            while let Some(row) = rows.next().await? {
+                keys.push(row.get_value(0)?);
+            }
+
+            Ok(keys)
+        }
+        .await;
+        timer.finish(result.as_ref().ok().map(|keys: &Vec<turso::Value>| keys.len() as u64));
+
+        result
+    }
+
+    async fn fetch_rows(&self, conn: &impl ConnectionLike, sql: &str, params: Vec<turso::Value>) -> Result<QueryRows> {
+        let (Some(ttl), Some(cache)) = (self.cache_ttl, conn.query_cache()) else {
+            return self.fetch_uncached_rows(conn, sql, params).await;
+        };
+
+        let key = cache.key(&self.read_tables(), sql, &params);
+        if let Some(rows) = cache.get(&key) {
+            return Ok(rows);
+        }
+
+        let rows = self.fetch_uncached_rows(conn, sql, params).await?;
+        cache.put(key, rows, ttl).await
+    }
+
+    async fn fetch_uncached_rows(
+        &self,
+        conn: &impl ConnectionLike,
+        sql: &str,
+        params: Vec<turso::Value>,
+    ) -> Result<QueryRows> {
+        match self.freshness {
+            Some(freshness) => conn.query_rows_fresh(sql, params, freshness).await,
+            None => conn.query_rows(sql, params).await,
+        }
+    }
+
+    /// The tables whose writes invalidate the cached rows.
+    fn read_tables(&self) -> Vec<&'static str> {
+        let mut tables = vec![Table::table_name()];
+        tables.extend(self.joins.iter().map(|join| join.source));
+        for (_, select) in &self.compounds {
+            tables.extend(select.read_tables());
+        }
+
+        tables.sort_unstable();
+        tables.dedup();
+        tables
+    }
+
     #[cfg(feature = "serde")]
-    fn from_raw_row(&self, row: &turso::Row) -> Result<serde_json::Value> {
+    fn raw_row_values(&self, row: &turso::Row) -> Result<serde_json::Value> {
         use serde_json::json;
 
         let column_names = Table::all_columns().split(", ").collect::<Vec<&str>>();
@@ -210,7 +1150,7 @@ impl<Table: TableTrait> Select<Table> {
     }
 
     #[cfg(not(feature = "serde"))]
-    fn from_raw_row(&self, row: &turso::Row) -> Result<HashMap<String, String>> {
+    fn raw_row_values(&self, row: &turso::Row) -> Result<HashMap<String, String>> {
         let column_names = self.columns.as_ref().cloned().unwrap_or_default();
         let column_count = row.column_count();
         let mut values = HashMap::new();
@@ -223,7 +1163,7 @@ impl<Table: TableTrait> Select<Table> {
                 turso::Value::Real(real) => format!("REAL: {}", real),
                 turso::Value::Text(text) => format!("TEXT: {}", text),
                 turso::Value::Blob(blob) => format!("BLOB: {:?}", blob),
-                turso::Value::Null => format!("NULL"),
+                turso::Value::Null => "NULL".to_string(),
             };
 
             values.insert(column_name, value.to_string());
@@ -233,12 +1173,137 @@ impl<Table: TableTrait> Select<Table> {
     }
 }
 
+/// Compares two values of an `ORDER BY` column the way the database sorts them.
+fn compare_sort_values(a: &turso::Value, b: &turso::Value, order: &OrderBy, collation: Option<Collation>) -> Ordering {
+    let nulls_first = order.nulls.map_or(order.direction == Order::Asc, |nulls| nulls == Nulls::First);
+    match (a, b) {
+        (turso::Value::Null, turso::Value::Null) => Ordering::Equal,
+        (turso::Value::Null, _) if nulls_first => Ordering::Less,
+        (turso::Value::Null, _) => Ordering::Greater,
+        (_, turso::Value::Null) if nulls_first => Ordering::Greater,
+        (_, turso::Value::Null) => Ordering::Less,
+        _ => {
+            let ordering = compare_values(a, b, collation);
+            if order.direction == Order::Desc { ordering.reverse() } else { ordering }
+        }
+    }
+}
+
+fn unqualified(name: &str) -> &str {
+    name.rsplit_once('.').map_or(name, |(_, column)| column)
+}
+
+#[derive(Clone, Copy, Debug)]
+enum JoinKind {
+    Inner,
+    Left,
+}
+
+impl std::fmt::Display for JoinKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JoinKind::Inner => write!(f, "INNER JOIN"),
+            JoinKind::Left => write!(f, "LEFT JOIN"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum CompoundOperator {
+    Union,
+    UnionAll,
+    Intersect,
+    Except,
+}
+
+impl std::fmt::Display for CompoundOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompoundOperator::Union => write!(f, "UNION"),
+            CompoundOperator::UnionAll => write!(f, "UNION ALL"),
+            CompoundOperator::Intersect => write!(f, "INTERSECT"),
+            CompoundOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Join {
+    kind:   JoinKind,
+    table:  String,
+    source: &'static str,
+    alias:  String,
+    on:     Condition,
+}
+
+impl<Table: TableTrait> std::fmt::Display for Select<Table> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (sql, params) = self.build();
+        f.write_str(&interpolate(&sql, &params))
+    }
+}
+
+impl<Table: TableTrait, Model: FromRow> std::fmt::Display for SelectModel<Table, Model> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.select.fmt(f)
+    }
+}
+
 impl<Table: TableTrait> Default for Select<Table> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+pub struct SelectModel<Table: TableTrait, Model: FromRow> {
+    select: Select<Table>,
+    _model: PhantomData<fn() -> Model>,
+}
+
+impl<Table: TableTrait, Model: FromRow> SelectModel<Table, Model> {
+    pub fn build(&self) -> (String, Vec<turso::Value>) {
+        self.select.build()
+    }
+
+    pub async fn all(self, conn: &impl ConnectionLike) -> Result<Vec<Model>> {
+        let (sql, params) = self.select.build();
+
+        let timer = conn.start_query(Table::table_name(), StatementKind::Select, &sql);
+        let result = async {
+            let mut rows = self.select.fetch_chunked_rows(conn, &sql, params).await?;
+            let columns = rows.columns();
+
+            let mut models = Vec::new();
+            while let Some(row) = rows.next().await? {
+                models.push(Model::from_named_row(&row, &columns)?);
+            }
+
+            Ok(models)
+        }
+        .await;
+        timer.finish(result.as_ref().ok().map(|models: &Vec<Model>| models.len() as u64));
+
+        result
+    }
+
+    pub async fn one(self, conn: &impl ConnectionLike) -> Result<Option<Model>> {
+        let select = self.select.limit(1);
+        let (sql, params) = select.build();
+
+        let timer = conn.start_query(Table::table_name(), StatementKind::Select, &sql);
+        let result = async {
+            let mut rows = select.fetch_chunked_rows(conn, &sql, params).await?;
+            let columns = rows.columns();
+
+            rows.next().await?.map(|row| Model::from_named_row(&row, &columns)).transpose()
+        }
+        .await;
+        timer.finish(result.as_ref().ok().map(|model| model.is_some() as u64));
+
+        result
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,7 +1312,9 @@ mod tests {
     use crate::FieldValue;
     use crate::FromRow;
     use crate::IntoValue;
+    use crate::Nulls;
     use crate::RecordTrait;
+    use crate::Value;
 
     #[derive(Clone, Debug, PartialEq)]
     struct TestRecord {
@@ -307,7 +1374,7 @@ mod tests {
         }
 
         fn get_primary_key_value(&self) -> Option<turso::Value> {
-            self.id.clone().take().map(|v| turso::Value::Integer(v))
+            self.id.clone().take().map(turso::Value::Integer)
         }
 
         fn primary_key_column() -> &'static str {
@@ -357,6 +1424,7 @@ mod tests {
     impl TableTrait for TestTable {
         type ChangeSet = TestChangeSet;
         type Column = TestColumn;
+        type PrimaryKeyType = i64;
         type Record = TestRecord;
 
         fn table_name() -> &'static str {
@@ -367,6 +1435,10 @@ mod tests {
             TestColumn::Id
         }
 
+        fn primary_key_to_value(id: &i64) -> Value {
+            Value::Integer(*id)
+        }
+
         fn primary_key_auto_increment() -> bool {
             true
         }
@@ -405,6 +1477,13 @@ mod tests {
         assert_eq!(params[0], turso::Value::Integer(1));
     }
 
+    #[test]
+    fn test_select_display_interpolates_params() {
+        let select = Select::<TestTable>::new().filter(Condition::eq(TestColumn::Name, "O'Brien")).limit(1);
+
+        assert_eq!(select.to_string(), "SELECT id, name, email, age FROM test_users WHERE (name = 'O''Brien') LIMIT 1");
+    }
+
     #[test]
     fn test_select_filter_multiple() {
         let select = Select::<TestTable>::new()
@@ -435,6 +1514,92 @@ mod tests {
         assert_eq!(sql, "SELECT id, name FROM test_users");
     }
 
+    #[test]
+    fn test_select_alias_and_self_join() {
+        let select = Select::<TestTable>::new()
+            .alias("u")
+            .left_join::<TestTable>(
+                "referrer",
+                Condition::eq_column(TestColumn::Age.of("u"), TestColumn::Id.of("referrer"))
+                    .and(Condition::is_not_null(TestColumn::Email.of("referrer"))),
+            )
+            .filter(Condition::eq(TestColumn::Name.of("referrer"), "Alice"))
+            .order_by_desc(TestColumn::Id.of("u"));
+        let (sql, params) = select.build();
+
+        assert_eq!(
+            sql,
+            "SELECT u.id, u.name, u.email, u.age FROM test_users AS u LEFT JOIN test_users AS referrer ON ((u.age = \
+             referrer.id) AND (referrer.email IS NOT NULL)) WHERE (referrer.name = ?) ORDER BY u.id DESC"
+        );
+        assert_eq!(params, vec![Value::Text("Alice".to_string())]);
+
+        let (sql, _) = Select::<TestTable>::new()
+            .join::<TestTable>("other", Condition::eq_column(TestColumn::Name, TestColumn::Name.of("other")))
+            .columns(vec![TestColumn::Id.of("other")])
+            .column(TestColumn::Name)
+            .build();
+        assert_eq!(sql, "SELECT other.id, name FROM test_users INNER JOIN test_users AS other ON (name = other.name)");
+    }
+
+    #[test]
+    fn test_select_join_params_precede_filters() {
+        let select = Select::<TestTable>::new()
+            .alias("u")
+            .join::<TestTable>("other", Condition::eq(TestColumn::Age.of("other"), 30))
+            .filter(Condition::eq(TestColumn::Age.of("u"), 40));
+        let (_, params) = select.build();
+
+        assert_eq!(params, vec![Value::Integer(30), Value::Integer(40)]);
+    }
+
+    #[test]
+    fn test_select_compound() {
+        let select = Select::<TestTable>::new()
+            .filter(Condition::eq(TestColumn::Name, "Alice"))
+            .union(Select::new().filter(Condition::gt(TestColumn::Age, 30)))
+            .except(Select::new().filter(Condition::is_null(TestColumn::Email)))
+            .order_by_asc(TestColumn::Id)
+            .limit(10);
+        let (sql, params) = select.build();
+
+        assert_eq!(
+            sql,
+            "SELECT id, name, email, age FROM test_users WHERE (name = ?) UNION SELECT id, name, email, age FROM \
+             test_users WHERE (age > ?) EXCEPT SELECT id, name, email, age FROM test_users WHERE (email IS NULL) ORDER \
+             BY id ASC LIMIT 10"
+        );
+        assert_eq!(params, vec![Value::Text("Alice".to_string()), Value::Integer(30)]);
+    }
+
+    #[test]
+    fn test_select_compound_limits_other_select_in_subquery() {
+        let latest = Select::<TestTable>::new().order_by_desc(TestColumn::Id).limit(1);
+        let (sql, _) = Select::<TestTable>::new()
+            .columns(vec![TestColumn::Id])
+            .union_all(latest.columns(vec![TestColumn::Id]))
+            .intersect(Select::new().columns(vec![TestColumn::Id]))
+            .build();
+
+        assert_eq!(
+            sql,
+            "SELECT id FROM test_users UNION ALL SELECT * FROM (SELECT id FROM test_users ORDER BY id DESC LIMIT 1) \
+             INTERSECT SELECT id FROM test_users"
+        );
+    }
+
+    #[test]
+    fn test_select_column_expr() {
+        let select = Select::<TestTable>::new()
+            .columns(vec![TestColumn::Id])
+            .column_expr(Expr::col(TestColumn::Age).add(1), "next_age")
+            .filter(Condition::eq(TestColumn::Name, "Alice"));
+        let (sql, params) = select.build();
+
+        assert_eq!(sql, "SELECT id, (age + ?) AS next_age FROM test_users WHERE (name = ?)");
+        assert_eq!(params, vec![Value::Integer(1), Value::Text("Alice".to_string())]);
+    }
+
     #[test]
     fn test_select_order_by_asc() {
         let select = Select::<TestTable>::new().order_by_asc(TestColumn::Name);
@@ -453,12 +1618,23 @@ mod tests {
 
     #[test]
     fn test_select_order_by_with_direction() {
-        let select = Select::<TestTable>::new().order_by(TestColumn::Id, Order::Desc);
+        let select = Select::<TestTable>::new().order_by((TestColumn::Id, Order::Desc));
         let (sql, _) = select.build();
 
         assert!(sql.contains("ORDER BY id DESC"));
     }
 
+    #[test]
+    fn test_select_order_by_nulls() {
+        let select = Select::<TestTable>::new()
+            .order_by((TestColumn::Age, Order::Desc, Nulls::Last))
+            .order_by(OrderBy::asc(TestColumn::Name).nulls_first())
+            .order_by((TestColumn::Id, Order::Asc));
+        let (sql, _) = select.build();
+
+        assert!(sql.ends_with("ORDER BY age IS NULL, age DESC, name IS NOT NULL, name ASC, id ASC"));
+    }
+
     #[test]
     fn test_select_multiple_order_by() {
         let select = Select::<TestTable>::new().order_by_asc(TestColumn::Name).order_by_desc(TestColumn::Age);
@@ -467,6 +1643,63 @@ mod tests {
         assert!(sql.contains("ORDER BY name ASC, age DESC"));
     }
 
+    #[test]
+    fn test_select_sample() {
+        let (sql, _) = Select::<TestTable>::new().filter(Condition::eq(TestColumn::Name, "Alice")).sample(5).build();
+
+        assert_eq!(sql, "SELECT id, name, email, age FROM test_users WHERE (name = ?) ORDER BY RANDOM() ASC LIMIT 5");
+    }
+
+    #[test]
+    fn test_select_sample_by_rowid() {
+        let (sql, params) = Select::<TestTable>::new()
+            .filter(Condition::gt(TestColumn::Age, 18))
+            .order_by_asc(TestColumn::Id)
+            .sample_by_rowid(3)
+            .build();
+
+        assert_eq!(
+            sql,
+            "SELECT id, name, email, age FROM test_users WHERE rowid IN (SELECT rowid FROM test_users WHERE (age > ?) \
+             ORDER BY RANDOM() LIMIT 3) ORDER BY id ASC"
+        );
+        assert_eq!(params, vec![turso::Value::Integer(18)]);
+    }
+
+    #[test]
+    fn test_select_cursor() {
+        let select = Select::<TestTable>::new().cursor_by(TestColumn::Id).after(10).first(50);
+        let (sql, params) = select.build();
+
+        assert_eq!(sql, "SELECT id, name, email, age FROM test_users WHERE (id > ?) ORDER BY id ASC LIMIT 50");
+        assert_eq!(params, vec![turso::Value::Integer(10)]);
+    }
+
+    #[test]
+    fn test_select_cursor_multiple_keys() {
+        let select = Select::<TestTable>::new()
+            .filter(Condition::is_not_null(TestColumn::Age))
+            .cursor_by_desc(TestColumn::Age)
+            .cursor_by(TestColumn::Id)
+            .after(Cursor::new(vec![30.into_value(), 7.into_value()]));
+        let (sql, params) = select.build();
+
+        assert_eq!(
+            sql,
+            "SELECT id, name, email, age FROM test_users WHERE (age IS NOT NULL) AND ((age < ?) OR (age = ? AND id > \
+             ?)) ORDER BY age DESC, id ASC"
+        );
+        assert_eq!(params, vec![turso::Value::Integer(30), turso::Value::Integer(30), turso::Value::Integer(7)]);
+    }
+
+    #[test]
+    fn test_select_cursor_key_must_be_selected() {
+        let select = Select::<TestTable>::new().columns(vec![TestColumn::Name]).cursor_by(TestColumn::Id);
+        assert!(select.cursor_key_indexes().is_err());
+        assert!(Select::<TestTable>::new().cursor_key_indexes().is_err());
+        assert_eq!(Select::<TestTable>::new().cursor_by(TestColumn::Age).cursor_key_indexes().unwrap(), vec![3]);
+    }
+
     #[test]
     fn test_select_limit() {
         let select = Select::<TestTable>::new().limit(10);
@@ -475,6 +1708,13 @@ mod tests {
         assert!(sql.contains("LIMIT 10"));
     }
 
+    #[test]
+    fn test_select_unlimited() {
+        let (sql, _) = Select::<TestTable>::new().limit(10).unlimited().build();
+
+        assert_eq!(sql, "SELECT id, name, email, age FROM test_users");
+    }
+
     #[test]
     fn test_select_offset() {
         let select = Select::<TestTable>::new().offset(20);
@@ -568,6 +1808,45 @@ mod tests {
         assert_eq!(params.len(), 3);
     }
 
+    #[test]
+    fn test_select_split_parameters() {
+        let ids = || Condition::is_in(TestColumn::Id, vec![1, 2, 3, 4, 5]);
+        let select = Select::<TestTable>::new().filter(ids()).order_by_asc(TestColumn::Name).limit(2).offset(1);
+        let chunks = select.split_parameters(5, 3).unwrap().unwrap();
+        let sql: Vec<String> = chunks.iter().map(|chunk| chunk.build().0).collect();
+        assert_eq!(
+            sql,
+            vec![
+                "SELECT id, name, email, age FROM test_users WHERE (id IN (?, ?, ?)) ORDER BY name ASC LIMIT 3",
+                "SELECT id, name, email, age FROM test_users WHERE (id IN (?, ?)) ORDER BY name ASC LIMIT 3",
+            ]
+        );
+
+        assert!(Select::<TestTable>::new().filter(ids()).split_parameters(5, 5).unwrap().is_none());
+        assert!(Select::<TestTable>::new().filter(ids()).sample_by_rowid(2).split_parameters(5, 3).is_err());
+        let not_in = Select::<TestTable>::new().filter(Condition::not_in(TestColumn::Id, vec![1, 2, 3, 4, 5]));
+        assert!(not_in.split_parameters(5, 3).is_err());
+    }
+
+    #[test]
+    fn test_compare_sort_values() {
+        let null = turso::Value::Null;
+        let one = turso::Value::Integer(1);
+        let upper = turso::Value::Text("B".to_string());
+        let lower = turso::Value::Text("a".to_string());
+
+        let asc = OrderBy::asc(TestColumn::Name);
+        assert_eq!(compare_sort_values(&null, &one, &asc, None), Ordering::Less);
+        assert_eq!(compare_sort_values(&one, &lower, &asc, None), Ordering::Less);
+        assert_eq!(compare_sort_values(&upper, &lower, &asc, None), Ordering::Less);
+        assert_eq!(compare_sort_values(&upper, &lower, &asc, Some(Collation::NoCase)), Ordering::Greater);
+
+        let desc = OrderBy::desc(TestColumn::Name);
+        assert_eq!(compare_sort_values(&null, &one, &desc, None), Ordering::Greater);
+        assert_eq!(compare_sort_values(&null, &one, &desc.nulls_first(), None), Ordering::Less);
+        assert_eq!(compare_sort_values(&one, &turso::Value::Real(1.5), &asc, None), Ordering::Less);
+    }
+
     #[test]
     fn test_select_with_between_condition() {
         let select = Select::<TestTable>::new().filter(Condition::between(TestColumn::Age, 18, 65));
@@ -0,0 +1,233 @@
+use std::any::Any;
+
+use crate::ChangeSetTrait;
+use crate::ColumnTrait;
+use crate::ConnectionLike;
+use crate::Result;
+use crate::TableTrait;
+use crate::Value;
+use crate::connection::connection_like::ConnectionLikeExt;
+
+/// Collects the writes of a request and flushes them together, in one transaction, on [`UnitOfWork::commit`]:
+///
+/// ```ignore
+/// let mut work = UnitOfWork::new();
+/// work.insert(UserChangeSet { id: set(7), name: set("alice".to_string()), ..Default::default() });
+/// work.insert(PostChangeSet { user_id: set(7), title: set("hello".to_string()), ..Default::default() });
+/// work.update(UserChangeSet { id: set(7), email: set(Some("a@example.com".to_string())), ..Default::default() });
+/// work.commit(&conn).await?;
+/// ```
+///
+/// Writes to a row whose primary key is already pending are folded into the pending write: an update merges its
+/// fields into a pending insert or update, a delete cancels a pending insert and replaces anything else, and an
+/// insert after a delete replaces the row. Inserts without a primary key always stay separate writes.
+///
+/// Inserts and updates are flushed parent tables first, following the foreign keys between the tables in the unit,
+/// and deletes afterwards, child tables first. Writes to one table keep the order they were added in.
+#[derive(Default)]
+pub struct UnitOfWork {
+    writes: Vec<Box<dyn PendingWrite>>,
+}
+
+impl UnitOfWork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert<ChangeSet: ChangeSetTrait>(&mut self, change_set: ChangeSet) -> &mut Self {
+        self.push(WriteOp::Insert, change_set)
+    }
+
+    /// Updates the row with the change set's primary key; committing fails if it isn't set.
+    pub fn update<ChangeSet: ChangeSetTrait>(&mut self, change_set: ChangeSet) -> &mut Self {
+        self.push(WriteOp::Update, change_set)
+    }
+
+    /// Deletes the row with the change set's primary key; committing fails if it isn't set.
+    pub fn delete<ChangeSet: ChangeSetTrait>(&mut self, change_set: ChangeSet) -> &mut Self {
+        self.push(WriteOp::Delete, change_set)
+    }
+
+    /// Pending writes, after folding writes to the same row.
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Runs the pending writes in one transaction (or the caller's open one) and returns how many rows they changed.
+    /// Nothing is written if any of them fails.
+    #[tracing::instrument(skip_all, fields(writes = self.writes.len()))]
+    pub async fn commit<Conn: ConnectionLike>(self, conn: &Conn) -> Result<u64> {
+        if self.writes.is_empty() {
+            return Ok(0);
+        }
+
+        let writes = self.flush_order();
+        conn.in_transaction(async {
+            let mut affected = 0;
+            for write in writes {
+                affected += write.flush(&conn as &dyn ConnectionLike).await?;
+            }
+            Ok(affected)
+        })
+        .await
+    }
+
+    fn push<ChangeSet: ChangeSetTrait>(&mut self, op: WriteOp, change_set: ChangeSet) -> &mut Self {
+        let write = TableWrite { op, key: change_set.get_primary_key_value(), change_set };
+        let pending = write.key.as_ref().and_then(|key| {
+            self.writes
+                .iter_mut()
+                .position(|pending| pending.key() == Some(key) && pending.as_any_mut().is::<TableWrite<ChangeSet>>())
+        });
+
+        let Some(idx) = pending else {
+            self.writes.push(Box::new(write));
+            return self;
+        };
+
+        let pending = self.writes[idx].as_any_mut().downcast_mut::<TableWrite<ChangeSet>>().expect("same table");
+        if !pending.fold(write) {
+            self.writes.remove(idx);
+        }
+        self
+    }
+
+    /// Saves by table, parents first, then deletes by table, children first.
+    fn flush_order(self) -> Vec<Box<dyn PendingWrite>> {
+        let mut tables: Vec<&'static str> = Vec::new();
+        for write in &self.writes {
+            if !tables.contains(&write.table()) {
+                tables.push(write.table());
+            }
+        }
+
+        let tables = dependency_order(&tables, |table| {
+            self.writes.iter().find(|write| write.table() == table).map(|write| write.references()).unwrap_or_default()
+        });
+
+        let (mut deletes, mut saves): (Vec<_>, Vec<_>) =
+            self.writes.into_iter().partition(|write| write.op() == WriteOp::Delete);
+        let rank = |write: &dyn PendingWrite| tables.iter().position(|table| *table == write.table());
+        saves.sort_by_key(|write| rank(&**write));
+        deletes.sort_by_key(|write| std::cmp::Reverse(rank(&**write)));
+
+        saves.extend(deletes);
+        saves
+    }
+}
+
+impl std::fmt::Debug for UnitOfWork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let writes: Vec<_> = self.writes.iter().map(|write| (write.op(), write.table())).collect();
+        f.debug_struct("UnitOfWork").field("writes", &writes).finish()
+    }
+}
+
+/// Orders `tables` so each comes after the tables it references. Tables in a reference cycle keep their order.
+fn dependency_order(tables: &[&'static str], references: impl Fn(&'static str) -> Vec<String>) -> Vec<&'static str> {
+    let mut remaining = tables.to_vec();
+    let mut ordered = Vec::with_capacity(tables.len());
+
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|table| {
+            references(table).iter().all(|parent| parent == table || !remaining.contains(&parent.as_str()))
+        });
+        ordered.push(remaining.remove(ready.unwrap_or(0)));
+    }
+
+    ordered
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WriteOp {
+    Insert,
+    Update,
+    Delete,
+    /// A delete followed by an insert of the same row.
+    Replace,
+}
+
+struct TableWrite<ChangeSet> {
+    op:         WriteOp,
+    key:        Option<Value>,
+    change_set: ChangeSet,
+}
+
+impl<ChangeSet: ChangeSetTrait> TableWrite<ChangeSet> {
+    /// Folds a later write to the same row into this one; returns `false` if the two cancel out.
+    fn fold(&mut self, later: Self) -> bool {
+        match (self.op, later.op) {
+            (WriteOp::Insert, WriteOp::Delete) => return false,
+            (_, WriteOp::Delete) => {
+                self.op = WriteOp::Delete;
+                self.change_set = later.change_set;
+            }
+            (WriteOp::Delete, WriteOp::Insert) => {
+                self.op = WriteOp::Replace;
+                self.change_set = later.change_set;
+            }
+            (WriteOp::Delete, _) => {}
+            (_, WriteOp::Insert | WriteOp::Update | WriteOp::Replace) => self.change_set.merge(later.change_set),
+        }
+
+        true
+    }
+}
+
+#[async_trait::async_trait]
+trait PendingWrite: Send + Sync {
+    fn table(&self) -> &'static str;
+
+    fn key(&self) -> Option<&Value>;
+
+    fn op(&self) -> WriteOp;
+
+    /// The tables this one has foreign keys to.
+    fn references(&self) -> Vec<String>;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    async fn flush(self: Box<Self>, conn: &dyn ConnectionLike) -> Result<u64>;
+}
+
+#[async_trait::async_trait]
+impl<ChangeSet: ChangeSetTrait> PendingWrite for TableWrite<ChangeSet> {
+    fn table(&self) -> &'static str {
+        ChangeSet::Table::table_name()
+    }
+
+    fn key(&self) -> Option<&Value> {
+        self.key.as_ref()
+    }
+
+    fn op(&self) -> WriteOp {
+        self.op
+    }
+
+    fn references(&self) -> Vec<String> {
+        <ChangeSet::Table as TableTrait>::Column::all()
+            .iter()
+            .filter_map(|column| column.foreign_key().map(|fk| fk.table_name))
+            .collect()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    async fn flush(self: Box<Self>, conn: &dyn ConnectionLike) -> Result<u64> {
+        match self.op {
+            WriteOp::Insert => self.change_set.insert_exec(&conn).await,
+            WriteOp::Update => self.change_set.update_exec(&conn).await,
+            WriteOp::Delete => self.change_set.delete(&conn).await,
+            WriteOp::Replace => {
+                let deleted = self.change_set.clone().delete(&conn).await?;
+                Ok(deleted + self.change_set.insert_exec(&conn).await?)
+            }
+        }
+    }
+}
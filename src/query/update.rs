@@ -3,62 +3,181 @@ use std::marker::PhantomData;
 use crate::ChangeSetTrait;
 use crate::ColumnTrait;
 use crate::Condition;
+use crate::ConnectionLike;
 use crate::Error;
-use crate::FromRow;
+use crate::Expr;
 use crate::IntoValue;
+use crate::OrderBy;
+use crate::RecordTrait;
 use crate::Result;
+use crate::Select;
+use crate::StatementKind;
 use crate::TableTrait;
 use crate::Value;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::encryption::Encryptor;
+use crate::encryption::encrypt_columns;
+use crate::encryption::encrypt_value;
+use crate::encryption::is_encrypted;
+use crate::encryption::write_encryptor;
+use crate::query::comment::with_comment;
+use crate::query::interpolate::interpolate;
+use crate::traits::table::ensure_sizes;
+use crate::traits::table::ensure_writable;
+use crate::traits::table::qualified_table_name;
 
 #[derive(Clone, Debug)]
 pub struct Update<Table: TableTrait> {
     change_set: Option<Table::ChangeSet>,
-    changes:    Vec<(String, Value)>,
+    changes:    Vec<(String, Expr)>,
     conditions: Vec<Condition>,
+    order_by:   Vec<OrderBy>,
+    limit:      Option<usize>,
+    schema:     Option<String>,
+    comment:    Option<String>,
+    expected:   Option<u64>,
+    full_table: bool,
     _table:     PhantomData<Table>,
 }
 
 impl<Table: TableTrait> Update<Table> {
     pub fn new(change_set: Table::ChangeSet) -> Self {
-        Self { change_set: Some(change_set), changes: Vec::new(), conditions: Vec::new(), _table: PhantomData }
+        Self {
+            change_set: Some(change_set),
+            changes:    Vec::new(),
+            conditions: Vec::new(),
+            order_by:   Vec::new(),
+            limit:      None,
+            schema:     None,
+            comment:    None,
+            expected:   None,
+            full_table: false,
+            _table:     PhantomData,
+        }
     }
 
     pub fn many() -> Self {
-        Self { change_set: None, changes: Vec::new(), conditions: Vec::new(), _table: PhantomData }
+        Self {
+            change_set: None,
+            changes:    Vec::new(),
+            conditions: Vec::new(),
+            order_by:   Vec::new(),
+            limit:      None,
+            schema:     None,
+            comment:    None,
+            expected:   None,
+            full_table: false,
+            _table:     PhantomData,
+        }
+    }
+
+    /// Updates the table in the attached database `schema` instead of the table's own.
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
     }
 
     pub fn set<Column: ColumnTrait, Value: IntoValue>(mut self, column: Column, value: Value) -> Self {
-        self.changes.push((column.name().to_string(), value.into_value()));
+        self.changes.push((column.name().to_string(), Expr::value(value)));
+        self
+    }
+
+    pub fn set_expr<Column: ColumnTrait>(mut self, column: Column, expr: Expr) -> Self {
+        self.changes.push((column.name().to_string(), expr));
         self
     }
 
+    pub fn inc<Column: ColumnTrait, Value: IntoValue>(self, column: Column, by: Value) -> Self {
+        self.set_expr(column, Expr::col(column).add(Expr::value(by)))
+    }
+
+    pub fn dec<Column: ColumnTrait, Value: IntoValue>(self, column: Column, by: Value) -> Self {
+        self.set_expr(column, Expr::col(column).sub(Expr::value(by)))
+    }
+
     pub fn filter(mut self, condition: Condition) -> Self {
         self.conditions.push(condition);
         self
     }
 
-    fn build(&self) -> Result<(String, Vec<Value>)> {
+    /// Updates at most `limit` of the matching rows, picked in [`Update::order_by`] order when one is given.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Decides which rows a [`Update::limit`] applies to. turso doesn't support `UPDATE ... ORDER BY`, so the
+    /// primary keys of those rows are looked up first, within the same transaction.
+    pub fn order_by(mut self, order_by: impl Into<OrderBy>) -> Self {
+        self.order_by.push(order_by.into());
+        self
+    }
+
+    /// Prepends `/* comment */` to the statements this builder runs, so slow-query logs can be traced back to the
+    /// call site.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Allows an update without a filter on a connection that
+    /// [requires one](crate::Builder::require_filter_on_mutation).
+    pub fn allow_full_table(mut self) -> Self {
+        self.full_table = true;
+        self
+    }
+
+    /// Makes [`Update::exec`] and the `exec_with_returning*` methods fail with [`Error::UnexpectedAffectedRows`] and
+    /// roll the update back unless exactly `rows` rows were updated. Inside a caller's transaction nothing is rolled
+    /// back until the caller does.
+    pub fn expect_affected(mut self, rows: u64) -> Self {
+        self.expected = Some(rows);
+        self
+    }
+
+    /// Renders the statement [`Update::exec`] runs. Fails when nothing is set, when a value is longer than its column's
+    /// `max_length`, or when a change set has no primary key and no filter, rather than rendering an update of every
+    /// row. Encrypted columns are rendered in plaintext.
+    pub fn build(&self) -> Result<(String, Vec<Value>)> {
+        self.build_encrypted(None, None)
+    }
+
+    fn build_encrypted(
+        &self,
+        encryptor: Option<&dyn Encryptor>,
+        max_blob_size: Option<usize>,
+    ) -> Result<(String, Vec<Value>)> {
         let mut set_parts = Vec::new();
         let mut params = Vec::new();
 
         if let Some(ref change_set) = self.change_set {
-            let change_set_changes = change_set.get_update_sets();
-            for (col, val) in change_set_changes {
-                set_parts.push(format!("{} = ?", col));
-                params.push(val);
-            }
+            let (columns, mut values): (Vec<_>, Vec<_>) = change_set.get_update_sets().into_iter().unzip();
+            ensure_sizes::<Table>(max_blob_size, &columns, &values)?;
+            encrypt_columns::<Table>(encryptor, &columns, &mut values)?;
+            set_parts.extend(columns.iter().map(|col| format!("{} = ?", col)));
+            params.extend(values);
         }
 
-        for (col, val) in &self.changes {
-            set_parts.push(format!("{} = ?", col));
-            params.push(val.clone());
+        for (col, expr) in &self.changes {
+            ensure_sizes::<Table>(max_blob_size, &vec![col.as_str(); expr.values().len()], expr.values())?;
+            set_parts.push(format!("{} = {}", col, expr.sql()));
+            match (encryptor, expr.values()) {
+                (Some(encryptor), [value]) if expr.sql() == "?" && is_encrypted::<Table>(col) => {
+                    params.push(encrypt_value(encryptor, value.clone())?)
+                }
+                (Some(_), _) if is_encrypted::<Table>(col) => {
+                    return Err(Error::Query(format!("Encrypted column `{}` can only be set to a value", col)));
+                }
+                _ => params.extend(expr.values().iter().cloned()),
+            }
         }
 
         if set_parts.is_empty() {
             return Err(Error::Query("No columns to update".to_string()));
         }
 
-        let mut sql = format!("UPDATE {} SET {}", Table::table_name(), set_parts.join(", "));
+        let table = qualified_table_name::<Table>(self.schema.as_deref());
+        let mut sql = format!("UPDATE {} SET {}", table, set_parts.join(", "));
 
         let mut where_conditions = self.conditions.clone();
 
@@ -81,24 +200,152 @@ impl<Table: TableTrait> Update<Table> {
             }
         }
 
-        Ok((sql, params))
+        if let Some(limit) = self.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        Ok((with_comment(self.comment.as_deref(), sql), params))
+    }
+
+    pub async fn exec(mut self, conn: &impl ConnectionLike) -> Result<u64> {
+        ensure_writable::<Table>()?;
+        self.ensure_filtered(conn)?;
+        let Some(expected) = self.expected.take() else {
+            return self.exec_rows(conn).await;
+        };
+
+        conn.in_transaction(async { self.exec_rows(conn).await.and_then(|actual| check_affected(expected, actual)) })
+            .await
+    }
+
+    fn ensure_filtered(&self, conn: &impl ConnectionLike) -> Result<()> {
+        let unfiltered = self.change_set.is_none() && self.conditions.is_empty();
+        if unfiltered && !self.full_table && conn.requires_filter_on_mutation() {
+            return Err(Error::UnfilteredMutation { table: Table::table_name() });
+        }
+
+        Ok(())
     }
 
-    pub async fn exec(self, conn: &crate::Connection) -> Result<u64> {
-        let (sql, params) = self.build()?;
+    async fn exec_rows(mut self, conn: &impl ConnectionLike) -> Result<u64> {
+        if self.limit.is_none() || self.order_by.is_empty() {
+            return self.exec_statement(conn).await;
+        }
+
+        conn.in_transaction(async {
+            if !self.restrict_to_limited_keys(conn).await? {
+                return Ok(0);
+            }
+            self.exec_statement(conn).await
+        })
+        .await
+    }
+
+    async fn exec_statement(self, conn: &impl ConnectionLike) -> Result<u64> {
+        let (sql, params) = self.build_encrypted(write_encryptor::<Table>(conn)?.as_deref(), conn.max_blob_size())?;
         let params: Vec<turso::Value> = params.into_iter().collect();
-        let affected = conn.execute(&sql, params).await?;
-        Ok(affected)
+
+        let timer = conn.start_query(Table::table_name(), StatementKind::Update, &sql);
+        let result = conn.execute_statement(&sql, params).await;
+        timer.finish(result.as_ref().ok().copied());
+
+        result
     }
 
-    pub async fn exec_with_returning(self, conn: &crate::Connection) -> Result<Table::Record> {
-        let (base_sql, params) = self.build()?;
-        let sql = format!("{} RETURNING {}", base_sql, Table::all_columns());
+    /// Replaces `limit` and `order_by` with a filter on the primary keys of the rows they select. Returns `false`
+    /// when no row matches.
+    async fn restrict_to_limited_keys(&mut self, conn: &impl ConnectionLike) -> Result<bool> {
+        let Some(limit) = self.limit.take() else {
+            return Ok(true);
+        };
 
+        let select =
+            self.conditions.iter().cloned().fold(Select::<Table>::new().schema(self.schema.clone()), Select::filter);
+        let keys = self.order_by.drain(..).fold(select, Select::order_by).limit(limit).primary_keys(conn).await?;
+        if keys.is_empty() {
+            return Ok(false);
+        }
+
+        self.conditions.push(Condition::is_in(Table::primary_key(), keys));
+        Ok(true)
+    }
+
+    /// Updates the matching rows and returns the first of them as updated. Fails with [`Error::NoRowsAffected`]
+    /// when no row matches.
+    pub async fn exec_with_returning(self, conn: &impl ConnectionLike) -> Result<Table::Record> {
+        self.exec_with_returning_all(conn).await?.into_iter().next().ok_or(Error::NoRowsAffected)
+    }
+
+    /// Updates the matching rows and returns all of them as updated, e.g. to claim up to `limit` queued jobs:
+    ///
+    /// ```ignore
+    /// let jobs = Update::<JobTable>::many()
+    ///     .set(JobColumn::Worker, worker_id)
+    ///     .filter(Condition::is_null(JobColumn::Worker))
+    ///     .limit(10)
+    ///     .exec_with_returning_all(&conn)
+    ///     .await?;
+    /// ```
+    ///
+    /// turso rejects `LIMIT` together with `RETURNING`, so a limited update selects the primary keys first and then
+    /// updates those rows within one immediate transaction (or the caller's open transaction). The filters are applied
+    /// again by the update, so as long as they exclude claimed rows, concurrent workers never receive the same row.
+    pub async fn exec_with_returning_all(mut self, conn: &impl ConnectionLike) -> Result<Vec<Table::Record>> {
+        ensure_writable::<Table>()?;
+        self.ensure_filtered(conn)?;
+        let expected = self.expected.take();
+        conn.in_transaction(async {
+            self.update_returning(conn).await.and_then(|records| check_returned(expected, records))
+        })
+        .await
+    }
+
+    async fn update_returning(mut self, conn: &impl ConnectionLike) -> Result<Vec<Table::Record>> {
+        if !self.restrict_to_limited_keys(conn).await? {
+            return Ok(Vec::new());
+        }
+
+        let encryptor = write_encryptor::<Table>(conn)?;
+        let (base_sql, params) = self.build_encrypted(encryptor.as_deref(), conn.max_blob_size())?;
+        let sql = format!("{} RETURNING {}", base_sql, Table::all_columns());
         let params: Vec<turso::Value> = params.into_iter().collect();
-        let mut rows = conn.query(&sql, params).await?;
 
-        if let Some(row) = rows.next().await? { Table::Record::from_row(&row) } else { Err(Error::NoRowsAffected) }
+        let timer = conn.start_query(Table::table_name(), StatementKind::Update, &sql);
+        let result = async {
+            let mut rows = conn.query_rows(&sql, params).await?;
+
+            let mut records = Vec::new();
+            while let Some(row) = rows.next().await? {
+                records.push(Table::Record::decode_row(&row, encryptor.as_deref())?);
+            }
+
+            Ok(records)
+        }
+        .await;
+        timer.finish(result.as_ref().ok().map(|records| records.len() as u64));
+
+        result
+    }
+}
+
+pub(crate) fn check_affected(expected: u64, actual: u64) -> Result<u64> {
+    if actual == expected { Ok(actual) } else { Err(Error::UnexpectedAffectedRows { expected, actual }) }
+}
+
+/// [`check_affected`] for the rows a `RETURNING` write produced, when an expected count was set.
+pub(crate) fn check_returned<Record>(expected: Option<u64>, records: Vec<Record>) -> Result<Vec<Record>> {
+    match expected {
+        Some(expected) => check_affected(expected, records.len() as u64).map(|_| records),
+        None => Ok(records),
+    }
+}
+
+impl<Table: TableTrait> std::fmt::Display for Update<Table> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.build() {
+            Ok((sql, params)) => f.write_str(&interpolate(&sql, &params)),
+            Err(err) => write!(f, "<invalid update: {}>", err),
+        }
     }
 }
 
@@ -174,7 +421,7 @@ mod tests {
         }
 
         fn get_primary_key_value(&self) -> Option<Value> {
-            self.id.clone().take().map(|v| Value::Integer(v))
+            self.id.clone().take().map(Value::Integer)
         }
 
         fn primary_key_column() -> &'static str {
@@ -222,6 +469,7 @@ mod tests {
     impl TableTrait for TestTable {
         type ChangeSet = TestChangeSet;
         type Column = TestColumn;
+        type PrimaryKeyType = i64;
         type Record = TestRecord;
 
         fn table_name() -> &'static str {
@@ -232,6 +480,10 @@ mod tests {
             TestColumn::Id
         }
 
+        fn primary_key_to_value(id: &i64) -> Value {
+            Value::Integer(*id)
+        }
+
         fn primary_key_auto_increment() -> bool {
             true
         }
@@ -289,6 +541,27 @@ mod tests {
         assert_eq!(params.len(), 3);
     }
 
+    #[test]
+    fn test_update_set_expr() {
+        let (sql, params) = Update::<TestTable>::many()
+            .set_expr(TestColumn::Name, Expr::col(TestColumn::Name).concat("!"))
+            .inc(TestColumn::Id, 1)
+            .filter(Condition::eq(TestColumn::Email, "a@b.c"))
+            .build()
+            .unwrap();
+
+        assert_eq!(sql, "UPDATE test_users SET name = (name || ?), id = (id + ?) WHERE (email = ?)");
+        assert_eq!(params, vec![Value::Text("!".to_string()), Value::Integer(1), Value::Text("a@b.c".to_string())]);
+    }
+
+    #[test]
+    fn test_update_dec() {
+        let (sql, params) = Update::<TestTable>::many().dec(TestColumn::Id, 2).build().unwrap();
+
+        assert_eq!(sql, "UPDATE test_users SET id = (id - ?)");
+        assert_eq!(params, vec![Value::Integer(2)]);
+    }
+
     #[test]
     fn test_update_filter() {
         let update =
@@ -300,6 +573,26 @@ mod tests {
         assert!(sql.contains("WHERE (id > ?)"));
     }
 
+    #[test]
+    fn test_update_limit() {
+        let (sql, _) = Update::<TestTable>::many()
+            .set(TestColumn::Name, "Test")
+            .filter(Condition::gt(TestColumn::Id, 10))
+            .limit(5)
+            .build()
+            .unwrap();
+
+        assert_eq!(sql, "UPDATE test_users SET name = ? WHERE (id > ?) LIMIT 5");
+    }
+
+    #[test]
+    fn test_update_display() {
+        let update = Update::<TestTable>::many().inc(TestColumn::Id, 1).filter(Condition::eq(TestColumn::Name, "a"));
+        assert_eq!(update.to_string(), "UPDATE test_users SET id = (id + 1) WHERE (name = 'a')");
+
+        assert_eq!(Update::<TestTable>::many().to_string(), "<invalid update: Query error: No columns to update>");
+    }
+
     #[test]
     fn test_update_multiple_filters() {
         let update = Update::<TestTable>::many()
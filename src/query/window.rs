@@ -0,0 +1,162 @@
+use crate::ColumnRef;
+use crate::Expr;
+use crate::OrderBy;
+use crate::Value;
+
+/// The `OVER (...)` clause of a window function: `partition_by(Column::UserId).order_by_desc(Column::CreatedAt)`.
+#[derive(Clone, Debug, Default)]
+pub struct Window {
+    partition_by: Vec<String>,
+    order_by:     Vec<OrderBy>,
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn partition_by(mut self, column: impl ColumnRef) -> Self {
+        self.partition_by.push(column.qualified_name().into_owned());
+        self
+    }
+
+    pub fn order_by_asc(self, column: impl ColumnRef) -> Self {
+        self.order_by(OrderBy::asc(column))
+    }
+
+    pub fn order_by_desc(self, column: impl ColumnRef) -> Self {
+        self.order_by(OrderBy::desc(column))
+    }
+
+    pub fn order_by(mut self, order_by: impl Into<OrderBy>) -> Self {
+        self.order_by.push(order_by.into());
+        self
+    }
+}
+
+impl std::fmt::Display for Window {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut clauses = Vec::new();
+        if !self.partition_by.is_empty() {
+            clauses.push(format!("PARTITION BY {}", self.partition_by.join(", ")));
+        }
+        if !self.order_by.is_empty() {
+            let order_parts: Vec<String> = self.order_by.iter().map(OrderBy::to_string).collect();
+            clauses.push(format!("ORDER BY {}", order_parts.join(", ")));
+        }
+
+        f.write_str(&clauses.join(" "))
+    }
+}
+
+/// Shorthand for `Window::new().partition_by(column)`.
+pub fn partition_by(column: impl ColumnRef) -> Window {
+    Window::new().partition_by(column)
+}
+
+/// A function evaluated over a [`Window`] of rows; [`WindowExpr::over`] turns it into an [`Expr`] for
+/// [`Select::column_expr`](crate::Select::column_expr).
+#[derive(Clone, Debug)]
+pub struct WindowExpr {
+    sql:    String,
+    values: Vec<Value>,
+}
+
+impl WindowExpr {
+    /// The 1-based position of the row in its partition, e.g. `row_number().over(partition_by(..).order_by_desc(..))`
+    /// numbers each group's rows newest first.
+    ///
+    /// turso has no `ROW_NUMBER()` yet, so this renders `COUNT(*) OVER (...)`, which only matches it when the
+    /// window's ordering is unique within each partition; end it with the primary key to break ties.
+    pub fn row_number() -> Self {
+        Self { sql: "COUNT(*)".to_string(), values: Vec::new() }
+    }
+
+    pub fn count() -> Self {
+        Self { sql: "COUNT(*)".to_string(), values: Vec::new() }
+    }
+
+    /// Running total with an ordered window, the partition's total without.
+    pub fn sum(column: impl ColumnRef) -> Self {
+        Self::aggregate("SUM", column)
+    }
+
+    pub fn avg(column: impl ColumnRef) -> Self {
+        Self::aggregate("AVG", column)
+    }
+
+    pub fn min(column: impl ColumnRef) -> Self {
+        Self::aggregate("MIN", column)
+    }
+
+    pub fn max(column: impl ColumnRef) -> Self {
+        Self::aggregate("MAX", column)
+    }
+
+    /// Any other function the engine accepts in a window, e.g. `WindowExpr::function(Expr::raw("TOTAL(price)", vec![]))`.
+    pub fn function(expr: Expr) -> Self {
+        Self { sql: expr.sql, values: expr.values }
+    }
+
+    fn aggregate(function: &str, column: impl ColumnRef) -> Self {
+        Self { sql: format!("{}({})", function, column.qualified_name()), values: Vec::new() }
+    }
+
+    pub fn over(self, window: Window) -> Expr {
+        Expr::raw(format!("{} OVER ({})", self.sql, window), self.values)
+    }
+}
+
+/// Shorthand for [`WindowExpr::row_number`].
+pub fn row_number() -> WindowExpr {
+    WindowExpr::row_number()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColumnTrait;
+    use crate::Nulls;
+    use crate::Order;
+    use crate::value::ColumnType;
+
+    #[derive(Clone, Copy, Debug)]
+    enum TestColumn {
+        Id,
+        UserId,
+        Amount,
+    }
+
+    impl ColumnTrait for TestColumn {
+        fn name(&self) -> &'static str {
+            match self {
+                TestColumn::Id => "id",
+                TestColumn::UserId => "user_id",
+                TestColumn::Amount => "amount",
+            }
+        }
+
+        fn column_type(&self) -> ColumnType {
+            ColumnType::Integer
+        }
+
+        fn all() -> &'static [Self] {
+            &[TestColumn::Id, TestColumn::UserId, TestColumn::Amount]
+        }
+    }
+
+    #[test]
+    fn test_window_expressions() {
+        let expr = row_number().over(partition_by(TestColumn::UserId).order_by_desc(TestColumn::Id));
+        assert_eq!(expr.sql(), "COUNT(*) OVER (PARTITION BY user_id ORDER BY id DESC)");
+
+        let expr = WindowExpr::sum(TestColumn::Amount.of("o")).over(
+            Window::new().partition_by(TestColumn::UserId.of("o")).order_by((TestColumn::Id, Order::Asc, Nulls::Last)),
+        );
+        assert_eq!(expr.sql(), "SUM(o.amount) OVER (PARTITION BY o.user_id ORDER BY id IS NULL, id ASC)");
+
+        let expr = WindowExpr::function(Expr::raw("MAX(amount, ?)", vec![Value::Integer(0)])).over(Window::new());
+        assert_eq!(expr.sql(), "MAX(amount, ?) OVER ()");
+        assert_eq!(expr.values(), &[Value::Integer(0)]);
+    }
+}
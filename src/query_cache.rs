@@ -0,0 +1,230 @@
+//! Caching of select results, for hot reads that tolerate a bounded delay.
+//!
+//! Give a connection a [`CacheStore`] and mark selects with [`Select::cached`](crate::Select::cached); their rows are
+//! then kept for the given time, keyed by SQL and parameters:
+//!
+//! ```ignore
+//! let conn = conn.with_query_cache(LruCacheStore::new(1_000));
+//! let active = UserTable::find().filter(Condition::eq(UserColumn::Active, true)).cached(Duration::from_secs(60));
+//! let active = active.all(&conn).await?;
+//! ```
+//!
+//! Every insert, update and delete the query builders run through the connection or its clones invalidates the
+//! cached selects of its table. Writes made with raw SQL, through other connections or by other processes are not
+//! seen, and neither are tables a select only references in raw conditions or subqueries.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::Result;
+use crate::Value;
+use crate::connection::connection_like::QueryRows;
+
+/// The rows of a cached select.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedRows {
+    pub columns: Vec<String>,
+    pub rows:    Vec<Vec<Value>>,
+}
+
+/// Where cached select results are kept.
+///
+/// Keys already encode which writes they predate, so a store never has to invalidate entries itself; it only has to
+/// drop them once their time to live is up, or earlier to stay within its capacity.
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<Arc<CachedRows>>;
+
+    fn put(&self, key: String, rows: Arc<CachedRows>, ttl: Duration);
+}
+
+/// An in-memory [`CacheStore`] that evicts the least recently used entry once it holds `capacity` entries.
+pub struct LruCacheStore {
+    capacity: usize,
+    entries:  Mutex<LruEntries>,
+}
+
+#[derive(Default)]
+struct LruEntries {
+    tick:    u64,
+    entries: HashMap<String, LruEntry>,
+}
+
+struct LruEntry {
+    rows:       Arc<CachedRows>,
+    expires_at: Instant,
+    used_at:    u64,
+}
+
+impl LruCacheStore {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, entries: Mutex::default() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CacheStore for LruCacheStore {
+    fn get(&self, key: &str) -> Option<Arc<CachedRows>> {
+        let mut lru = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        lru.tick += 1;
+        let tick = lru.tick;
+
+        let entry = lru.entries.get_mut(key)?;
+        if entry.expires_at <= Instant::now() {
+            lru.entries.remove(key);
+            return None;
+        }
+
+        entry.used_at = tick;
+        Some(entry.rows.clone())
+    }
+
+    fn put(&self, key: String, rows: Arc<CachedRows>, ttl: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut lru = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        lru.tick += 1;
+        let tick = lru.tick;
+
+        if !lru.entries.contains_key(&key) && lru.entries.len() >= self.capacity {
+            let now = Instant::now();
+            lru.entries.retain(|_, entry| entry.expires_at > now);
+            if lru.entries.len() >= self.capacity {
+                let oldest = lru.entries.iter().min_by_key(|(_, entry)| entry.used_at).map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    lru.entries.remove(&oldest);
+                }
+            }
+        }
+
+        lru.entries.insert(key, LruEntry { rows, expires_at: Instant::now() + ttl, used_at: tick });
+    }
+}
+
+impl std::fmt::Debug for LruCacheStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LruCacheStore").field("capacity", &self.capacity).field("len", &self.len()).finish()
+    }
+}
+
+/// The select cache of a connection and its clones, from
+/// [`Connection::with_query_cache`](crate::Connection::with_query_cache).
+///
+/// Writes bump a generation counter per table, and cache keys include the generations of the tables a select reads,
+/// so entries cached before a write are never found again.
+pub struct QueryCache {
+    store:       Box<dyn CacheStore>,
+    generations: Mutex<HashMap<String, u64>>,
+    /// Bumped to invalidate every table at once.
+    epoch:       AtomicU64,
+}
+
+impl QueryCache {
+    pub(crate) fn new(store: impl CacheStore + 'static) -> Self {
+        Self { store: Box::new(store), generations: Mutex::default(), epoch: AtomicU64::new(0) }
+    }
+
+    pub(crate) fn invalidate(&self, table: &str) {
+        *self.generations.lock().unwrap_or_else(|e| e.into_inner()).entry(table.to_string()).or_default() += 1;
+    }
+
+    pub(crate) fn invalidate_all(&self) {
+        self.epoch.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The key of a select reading `tables`, as of the writes seen so far.
+    pub(crate) fn key(&self, tables: &[&str], sql: &str, params: &[Value]) -> String {
+        let generations = self.generations.lock().unwrap_or_else(|e| e.into_inner());
+        let mut key = format!("{}:", self.epoch.load(Ordering::Relaxed));
+        for table in tables {
+            key.push_str(&format!("{}@{},", table, generations.get(*table).copied().unwrap_or_default()));
+        }
+        format!("{}\n{}\n{:?}", key, sql, params)
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<QueryRows> {
+        let cached = self.store.get(key)?;
+        Some(QueryRows::from_named_values(cached.columns.clone(), cached.rows.clone()))
+    }
+
+    /// Reads `rows` into the cache under `key` and returns them again.
+    pub(crate) async fn put(&self, key: String, mut rows: QueryRows, ttl: Duration) -> Result<QueryRows> {
+        let columns = rows.columns().names().to_vec();
+        let mut values = Vec::new();
+        while let Some(row) = rows.next().await? {
+            values.push((0..row.column_count()).map(|idx| row.get_value(idx)).collect::<turso::Result<Vec<_>>>()?);
+        }
+        drop(rows);
+
+        let cached = CachedRows { columns: columns.clone(), rows: values.clone() };
+        self.store.put(key, Arc::new(cached), ttl);
+        Ok(QueryRows::from_named_values(columns, values))
+    }
+}
+
+impl std::fmt::Debug for QueryCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryCache").field("epoch", &self.epoch.load(Ordering::Relaxed)).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(n: i64) -> Arc<CachedRows> {
+        Arc::new(CachedRows { columns: vec!["n".to_string()], rows: vec![vec![Value::Integer(n)]] })
+    }
+
+    #[test]
+    fn test_lru_cache_store_evicts_least_recently_used() {
+        let store = LruCacheStore::new(2);
+        store.put("a".to_string(), rows(1), Duration::from_secs(60));
+        store.put("b".to_string(), rows(2), Duration::from_secs(60));
+        assert!(store.get("a").is_some());
+
+        store.put("c".to_string(), rows(3), Duration::from_secs(60));
+        assert_eq!(store.len(), 2);
+        assert!(store.get("b").is_none());
+        assert_eq!(store.get("a"), Some(rows(1)));
+        assert_eq!(store.get("c"), Some(rows(3)));
+    }
+
+    #[test]
+    fn test_lru_cache_store_expires_entries() {
+        let store = LruCacheStore::new(2);
+        store.put("a".to_string(), rows(1), Duration::ZERO);
+        assert!(store.get("a").is_none());
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_query_cache_keys_change_with_writes() {
+        let cache = QueryCache::new(LruCacheStore::new(8));
+        let key = cache.key(&["user", "post"], "SELECT 1", &[Value::Integer(1)]);
+        assert_eq!(cache.key(&["user", "post"], "SELECT 1", &[Value::Integer(1)]), key);
+        assert_ne!(cache.key(&["user", "post"], "SELECT 1", &[Value::Integer(2)]), key);
+
+        cache.invalidate("comment");
+        assert_eq!(cache.key(&["user", "post"], "SELECT 1", &[Value::Integer(1)]), key);
+        cache.invalidate("post");
+        assert_ne!(cache.key(&["user", "post"], "SELECT 1", &[Value::Integer(1)]), key);
+
+        let key = cache.key(&["user"], "SELECT 1", &[]);
+        cache.invalidate_all();
+        assert_ne!(cache.key(&["user"], "SELECT 1", &[]), key);
+    }
+}
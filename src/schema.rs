@@ -0,0 +1,83 @@
+//! Entity metadata as JSON, for documentation generators and frontends that render forms from it.
+//!
+//! ```ignore
+//! let description = schema::describe_all(&[TableSchema::of::<UserTable>(), TableSchema::of::<PostTable>()]);
+//! std::fs::write("schema.json", serde_json::to_string_pretty(&description)?)?;
+//! ```
+
+use serde_json::Value as JsonValue;
+use serde_json::json;
+
+use crate::migration::TableColumnInfo;
+use crate::migration::TableSchema;
+use crate::migration::column_type_to_sql;
+
+/// Describes the tables, their columns and how they reference each other:
+///
+/// ```json
+/// {"tables": [{
+///     "name": "post", "docs": null, "view": false, "primary_key": "id",
+///     "columns": [{"name": "user_id", "type": "INTEGER", "nullable": false, "primary_key": false,
+///                  "auto_increment": false, "unique": false, "default": null, "collation": null, "docs": null}],
+///     "relations": [{"kind": "belongs_to", "column": "user_id", "table": "user", "related_column": "id"}]
+/// }]}
+/// ```
+///
+/// A `belongs_to` relation is a foreign key of the table itself; a `has_many` one is a foreign key of another of the
+/// described tables pointing at it, so it only shows up when both tables are passed in.
+pub fn describe_all(schemas: &[TableSchema]) -> JsonValue {
+    let tables: Vec<JsonValue> = schemas.iter().map(|schema| describe_table(schema, schemas)).collect();
+    json!({ "tables": tables })
+}
+
+fn describe_table(schema: &TableSchema, schemas: &[TableSchema]) -> JsonValue {
+    let primary_key = schema.columns().iter().find(|col| col.is_primary_key).map(|col| col.name);
+    let columns: Vec<JsonValue> = schema.columns().iter().map(describe_column).collect();
+
+    let belongs_to = schema.columns().iter().filter_map(|col| {
+        col.foreign_key.as_ref().map(|fk| {
+            json!({
+                "kind": "belongs_to",
+                "column": col.name,
+                "table": fk.table_name,
+                "related_column": fk.column_name,
+            })
+        })
+    });
+    let has_many = schemas.iter().flat_map(|other| {
+        other.columns().iter().filter_map(move |col| {
+            col.foreign_key.as_ref().filter(|fk| fk.table_name == schema.table_name()).map(|fk| {
+                json!({
+                    "kind": "has_many",
+                    "column": fk.column_name,
+                    "table": other.table_name(),
+                    "related_column": col.name,
+                })
+            })
+        })
+    });
+    let relations: Vec<JsonValue> = belongs_to.chain(has_many).collect();
+
+    json!({
+        "name": schema.table_name(),
+        "docs": schema.docs(),
+        "view": schema.view().is_some(),
+        "primary_key": primary_key,
+        "columns": columns,
+        "relations": relations,
+    })
+}
+
+fn describe_column(col: &TableColumnInfo) -> JsonValue {
+    json!({
+        "name": col.name,
+        "type": column_type_to_sql(col.column_type),
+        "nullable": col.nullable,
+        "primary_key": col.is_primary_key,
+        "auto_increment": col.is_auto_increment,
+        "unique": col.is_unique,
+        "default": col.default_value,
+        "collation": col.collation.map(|collation| collation.to_string()),
+        "docs": col.docs,
+    })
+}
@@ -0,0 +1,239 @@
+//! Helpers for testing code built on tursorm.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use crate::Builder;
+use crate::Connection;
+use crate::ConnectionLike;
+use crate::Error;
+use crate::InsertStrategy;
+use crate::QueryRows;
+use crate::Result;
+use crate::Value;
+use crate::connection::database::Database;
+use crate::connection::opts::DEFAULT_MAX_PARAMETERS;
+use crate::migration::Migrator;
+use crate::migration::TableSchema;
+
+/// A private in-memory database with the given tables migrated, for tests that need a real engine.
+///
+/// Every fixture gets its own database; connections from [`TestDb::connect`] share it. It is discarded when the
+/// fixture and all of those connections are dropped, so nothing leaks between tests.
+#[derive(Debug)]
+pub struct TestDb {
+    database: Database,
+    conn:     Connection,
+}
+
+impl TestDb {
+    pub async fn new(schemas: &[TableSchema]) -> Result<Self> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        let name = format!("tursorm-test-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        let database = Builder::new_memory_shared(&name).build().await?;
+        let conn = database.clone().connect()?;
+        Migrator::migrate_all(&conn, schemas).await?;
+
+        Ok(Self { database, conn })
+    }
+
+    pub fn conn(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Opens another connection to the same database.
+    pub fn connect(&self) -> Result<Connection> {
+        Ok(self.database.clone().connect()?)
+    }
+}
+
+impl std::ops::Deref for TestDb {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.conn
+    }
+}
+
+/// A statement received by a [`MockConnection`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedStatement {
+    pub sql:    String,
+    pub params: Vec<Value>,
+}
+
+/// A canned result handed out by a [`MockConnection`], in the order the results were queued.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MockResult {
+    Rows(Vec<Vec<Value>>),
+    Affected(u64),
+    /// Fails the statement with a database error carrying this message.
+    Error(String),
+}
+
+/// An executor that runs nothing: it records every statement it receives and answers with queued results.
+///
+/// ```ignore
+/// let conn = MockConnection::new();
+/// conn.push_rows(vec![vec![Value::Integer(1), Value::Text("Alice".to_string())]]);
+///
+/// let user = UserTable::find().filter(Condition::eq(UserColumn::Id, 1)).one(&conn).await?;
+/// assert_eq!(conn.statements()[0].sql, "SELECT id, name FROM user WHERE (id = ?) LIMIT 1");
+/// ```
+///
+/// Once the queue is empty, queries return no rows and other statements report zero changes.
+#[derive(Debug, Default)]
+pub struct MockConnection {
+    state:           Mutex<MockState>,
+    insert_strategy: InsertStrategy,
+    require_filter:  bool,
+    max_parameters:  Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    statements:        Vec<RecordedStatement>,
+    results:           VecDeque<MockResult>,
+    last_insert_rowid: i64,
+}
+
+impl MockConnection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_strategy(mut self, strategy: InsertStrategy) -> Self {
+        self.insert_strategy = strategy;
+        self
+    }
+
+    pub fn require_filter_on_mutation(mut self, require: bool) -> Self {
+        self.require_filter = require;
+        self
+    }
+
+    /// Lowers the parameter limit, e.g. to check how a select with a long `is_in` list is chunked.
+    pub fn max_parameters(mut self, max: usize) -> Self {
+        self.max_parameters = Some(max);
+        self
+    }
+
+    pub fn push_result(&self, result: MockResult) -> &Self {
+        self.state().results.push_back(result);
+        self
+    }
+
+    pub fn push_rows(&self, rows: Vec<Vec<Value>>) -> &Self {
+        self.push_result(MockResult::Rows(rows))
+    }
+
+    pub fn push_affected(&self, affected: u64) -> &Self {
+        self.push_result(MockResult::Affected(affected))
+    }
+
+    pub fn push_error(&self, message: impl Into<String>) -> &Self {
+        self.push_result(MockResult::Error(message.into()))
+    }
+
+    pub fn set_last_insert_rowid(&self, rowid: i64) {
+        self.state().last_insert_rowid = rowid;
+    }
+
+    pub fn statements(&self) -> Vec<RecordedStatement> {
+        self.state().statements.clone()
+    }
+
+    pub fn sql(&self) -> Vec<String> {
+        self.state().statements.iter().map(|statement| statement.sql.clone()).collect()
+    }
+
+    /// Forgets the recorded statements and any results still queued.
+    pub fn reset(&self) {
+        let mut state = self.state();
+        state.statements.clear();
+        state.results.clear();
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, sql: &str, params: Vec<Value>) -> Option<MockResult> {
+        let mut state = self.state();
+        state.statements.push(RecordedStatement { sql: sql.to_string(), params });
+        state.results.pop_front()
+    }
+}
+
+fn mock_error(message: String) -> Error {
+    Error::from(turso::Error::SqlExecutionFailure(message))
+}
+
+#[async_trait::async_trait]
+impl ConnectionLike for MockConnection {
+    async fn query_rows(&self, sql: &str, params: Vec<Value>) -> Result<QueryRows> {
+        match self.record(sql, params) {
+            Some(MockResult::Rows(rows)) => Ok(QueryRows::from_values(rows)),
+            Some(MockResult::Error(message)) => Err(mock_error(message)),
+            Some(MockResult::Affected(_)) | None => Ok(QueryRows::from_values(Vec::new())),
+        }
+    }
+
+    async fn execute_statement(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+        match self.record(sql, params) {
+            Some(MockResult::Affected(affected)) => Ok(affected),
+            Some(MockResult::Rows(rows)) => Ok(rows.len() as u64),
+            Some(MockResult::Error(message)) => Err(mock_error(message)),
+            None => Ok(0),
+        }
+    }
+
+    fn last_insert_rowid(&self) -> i64 {
+        self.state().last_insert_rowid
+    }
+
+    fn insert_strategy(&self) -> InsertStrategy {
+        self.insert_strategy
+    }
+
+    fn requires_filter_on_mutation(&self) -> bool {
+        self.require_filter
+    }
+
+    fn max_parameters(&self) -> usize {
+        self.max_parameters.unwrap_or(DEFAULT_MAX_PARAMETERS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[tokio::test]
+    async fn test_mock_connection_records_and_replays() {
+        let conn = MockConnection::new();
+        conn.push_rows(vec![vec![Value::Integer(1)]]).push_affected(3).push_error("UNIQUE constraint failed: t.id");
+
+        let mut rows = conn.query_rows("SELECT id FROM t WHERE id = ?", vec![Value::Integer(1)]).await.unwrap();
+        assert_eq!(rows.next().await.unwrap().unwrap().get_value(0).unwrap(), Value::Integer(1));
+        assert!(rows.next().await.unwrap().is_none());
+
+        assert_eq!(conn.execute_statement("DELETE FROM t", Vec::new()).await.unwrap(), 3);
+        let err = conn.execute_statement("INSERT INTO t (id) VALUES (1)", Vec::new()).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Conflict);
+        assert_eq!(conn.execute_statement("DELETE FROM t", Vec::new()).await.unwrap(), 0);
+
+        assert_eq!(
+            conn.statements()[0],
+            RecordedStatement { sql: "SELECT id FROM t WHERE id = ?".to_string(), params: vec![Value::Integer(1)] }
+        );
+        assert_eq!(conn.sql().len(), 4);
+
+        conn.reset();
+        assert!(conn.statements().is_empty());
+    }
+}
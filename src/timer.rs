@@ -0,0 +1,195 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::Once;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Waits without blocking the executor and without depending on a particular async runtime. Every sleep is served
+/// by one background thread, started on first use.
+pub(crate) fn sleep(duration: Duration) -> Sleep {
+    Sleep { deadline: Instant::now() + duration, state: None }
+}
+
+pub(crate) struct Sleep {
+    deadline: Instant,
+    state:    Option<Arc<Mutex<SleepState>>>,
+}
+
+#[derive(Default)]
+struct SleepState {
+    elapsed: bool,
+    waker:   Option<Waker>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        let deadline = self.deadline;
+        let state = self.state.get_or_insert_with(|| {
+            let state = Arc::new(Mutex::new(SleepState::default()));
+            Timer::shared().schedule(deadline, state.clone());
+            state
+        });
+
+        let mut state = lock(state);
+        if state.elapsed {
+            return Poll::Ready(());
+        }
+
+        // The task may have moved to another executor thread since the last poll.
+        match &mut state.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            slot => *slot = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+struct Timer {
+    entries: Mutex<BinaryHeap<Entry>>,
+    changed: Condvar,
+}
+
+impl Timer {
+    fn shared() -> &'static Timer {
+        static TIMER: Timer = Timer { entries: Mutex::new(BinaryHeap::new()), changed: Condvar::new() };
+        static STARTED: Once = Once::new();
+
+        STARTED.call_once(|| {
+            std::thread::Builder::new()
+                .name("tursorm-timer".to_string())
+                .spawn(|| TIMER.run())
+                .expect("failed to spawn the timer thread");
+        });
+        &TIMER
+    }
+
+    fn schedule(&self, deadline: Instant, state: Arc<Mutex<SleepState>>) {
+        lock(&self.entries).push(Entry { deadline, state });
+        self.changed.notify_one();
+    }
+
+    fn run(&self) {
+        loop {
+            for entry in self.wait_for_due() {
+                let waker = {
+                    let mut state = lock(&entry.state);
+                    state.elapsed = true;
+                    state.waker.take()
+                };
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+        }
+    }
+
+    /// Blocks until at least one sleep is due, then takes the due ones off the heap.
+    fn wait_for_due(&self) -> Vec<Entry> {
+        let mut entries = lock(&self.entries);
+        loop {
+            let now = Instant::now();
+            let mut due = Vec::new();
+            while entries.peek().is_some_and(|entry| entry.deadline <= now) {
+                due.extend(entries.pop());
+            }
+            if !due.is_empty() {
+                return due;
+            }
+
+            entries = match entries.peek() {
+                Some(entry) => {
+                    let timeout = entry.deadline.saturating_duration_since(now);
+                    self.changed.wait_timeout(entries, timeout).unwrap_or_else(|e| e.into_inner()).0
+                }
+                None => self.changed.wait(entries).unwrap_or_else(|e| e.into_inner()),
+            };
+        }
+    }
+}
+
+/// A pending sleep, ordered so the heap's top is the earliest deadline.
+struct Entry {
+    deadline: Instant,
+    state:    Arc<Mutex<SleepState>>,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering as AtomicOrdering;
+    use std::task::Wake;
+
+    use super::*;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, AtomicOrdering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sleep_waits_for_its_duration() {
+        let started = Instant::now();
+        tokio::join!(sleep(Duration::from_millis(10)), sleep(Duration::from_millis(30)));
+        assert!(started.elapsed() >= Duration::from_millis(30));
+
+        sleep(Duration::ZERO).await;
+    }
+
+    #[test]
+    fn test_sleep_wakes_the_latest_waker() {
+        let first = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let second = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let mut delay = sleep(Duration::from_millis(10));
+
+        let first_waker = Waker::from(first.clone());
+        assert!(Pin::new(&mut delay).poll(&mut Context::from_waker(&first_waker)).is_pending());
+        let second_waker = Waker::from(second.clone());
+        assert!(Pin::new(&mut delay).poll(&mut Context::from_waker(&second_waker)).is_pending());
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(first.0.load(AtomicOrdering::SeqCst), 0);
+        assert_eq!(second.0.load(AtomicOrdering::SeqCst), 1);
+        assert!(Pin::new(&mut delay).poll(&mut Context::from_waker(&second_waker)).is_ready());
+    }
+}
@@ -1,5 +1,8 @@
+use super::column::ColumnTrait;
 use super::table::TableTrait;
+use crate::InsertStrategy;
 use crate::IntoValue;
+use crate::connection::connection_like::ConnectionLikeExt;
 use crate::error::Result;
 use crate::traits::column::RowIdColumn;
 use crate::value::Value;
@@ -16,24 +19,103 @@ pub trait ChangeSetTrait: std::fmt::Debug + Default + Clone + Send + Sync + Size
 
     fn primary_key_column() -> &'static str;
 
+    /// Fills a `NotSet` primary key from its `default_fn` generator, if the table declares one.
+    fn fill_generated_keys(&mut self) {}
+
+    /// Overwrites the fields `later` sets, keeping the others. Derived change sets merge field by field; this default
+    /// replaces the whole change set.
+    fn merge(&mut self, later: Self) {
+        *self = later;
+    }
+
+    /// Sets the table's discriminator column to `value`; fails if the table declares none.
+    fn set_discriminator(&mut self, value: &str) -> Result<()> {
+        let _ = value;
+        Err(crate::error::Error::Query(format!("Table {} has no discriminator column", Self::Table::table_name())))
+    }
+
+    /// Non-primary-key columns that are set, i.e. the ones an update would write.
+    fn changed_columns(&self) -> Vec<<Self::Table as TableTrait>::Column> {
+        let sets = self.get_update_sets();
+
+        <Self::Table as TableTrait>::Column::all()
+            .iter()
+            .copied()
+            .filter(|column| sets.iter().any(|(name, _)| *name == column.name()))
+            .collect()
+    }
+
     #[tracing::instrument(skip(self, conn))]
-    async fn insert(self, conn: &crate::Connection) -> Result<<Self::Table as TableTrait>::Record>
+    async fn insert<Conn: crate::ConnectionLike>(self, conn: &Conn) -> Result<<Self::Table as TableTrait>::Record>
     where <Self::Table as TableTrait>::Record: Send {
         tracing::trace!("Inserting record");
 
-        let db_row_id = crate::query::Insert::<Self::Table>::new(self).exec_with_last_insert_id(conn).await?;
-
-        let row = crate::query::Select::<Self::Table>::new()
-            .filter(crate::query::Condition::eq(RowIdColumn, db_row_id.into_value()))
-            .one(conn)
-            .await?;
+        match conn.insert_strategy() {
+            InsertStrategy::Returning => crate::query::Insert::<Self::Table>::new(self).exec_with_returning(conn).await,
+            InsertStrategy::LastInsertRowid => {
+                conn.in_transaction(async {
+                    let db_row_id =
+                        crate::query::Insert::<Self::Table>::new(self).exec_with_last_insert_id(conn).await?;
+
+                    let row = crate::query::Select::<Self::Table>::new()
+                        .filter(crate::query::Condition::eq(RowIdColumn, db_row_id.into_value()))
+                        .one(conn)
+                        .await?;
+
+                    tracing::trace!("Row: {:?}", row);
+                    row.ok_or(crate::error::Error::NoRowsAffected)
+                })
+                .await
+            }
+        }
+    }
 
-        tracing::trace!("Row: {:?}", row);
-        row.ok_or(crate::error::Error::NoRowsAffected)
+    /// Returns the row matching this change set's unique or primary key columns, inserting it first if none exists.
+    /// Fails when those columns match more than one row.
+    #[tracing::instrument(skip(self, conn))]
+    async fn get_or_insert<Conn: crate::ConnectionLike>(
+        mut self,
+        conn: &Conn,
+    ) -> Result<<Self::Table as TableTrait>::Record>
+    where
+        <Self::Table as TableTrait>::Record: Send,
+    {
+        self.fill_generated_keys();
+        let (columns, values) = self.get_insert_columns_and_values();
+
+        let lookup = columns
+            .iter()
+            .zip(values)
+            .filter_map(|(name, value)| {
+                <Self::Table as TableTrait>::Column::all()
+                    .iter()
+                    .find(|column| column.name() == *name && (column.is_unique() || column.is_primary_key()))
+                    .map(|column| crate::query::Condition::eq(*column, value))
+            })
+            .reduce(|lookup, condition| lookup.or(condition))
+            .ok_or_else(|| {
+                crate::error::Error::Query(
+                    "get_or_insert requires a unique or primary key column to be set".to_string(),
+                )
+            })?;
+
+        conn.in_transaction(async {
+            crate::query::Insert::<Self::Table>::new(self).on_conflict_do_nothing().exec(conn).await?;
+            let mut matches = crate::query::Select::<Self::Table>::new().filter(lookup).limit(2).all(conn).await?;
+            if matches.len() > 1 {
+                return Err(crate::error::Error::Query(format!(
+                    "get_or_insert matched more than one {} row",
+                    Self::Table::table_name()
+                )));
+            }
+
+            matches.pop().ok_or(crate::error::Error::NoRowsAffected)
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self, conn))]
-    async fn insert_exec(self, conn: &crate::Connection) -> Result<u64> {
+    async fn insert_exec<Conn: crate::ConnectionLike>(self, conn: &Conn) -> Result<u64> {
         tracing::trace!("Inserting record");
         let affected = crate::query::Insert::<Self::Table>::new(self).exec(conn).await?;
 
@@ -42,7 +124,7 @@ pub trait ChangeSetTrait: std::fmt::Debug + Default + Clone + Send + Sync + Size
     }
 
     #[tracing::instrument(skip(self, conn))]
-    async fn update(self, conn: &crate::Connection) -> Result<<Self::Table as TableTrait>::Record>
+    async fn update<Conn: crate::ConnectionLike>(self, conn: &Conn) -> Result<<Self::Table as TableTrait>::Record>
     where <Self::Table as TableTrait>::Record: Send {
         let pk_value = self.get_primary_key_value().ok_or(crate::error::Error::PrimaryKeyNotSet)?;
 
@@ -58,7 +140,7 @@ pub trait ChangeSetTrait: std::fmt::Debug + Default + Clone + Send + Sync + Size
     }
 
     #[tracing::instrument(skip(self, conn))]
-    async fn update_exec(self, conn: &crate::Connection) -> Result<u64> {
+    async fn update_exec<Conn: crate::ConnectionLike>(self, conn: &Conn) -> Result<u64> {
         tracing::trace!("Updating record");
         let affected = crate::query::Update::<Self::Table>::new(self).exec(conn).await?;
 
@@ -66,8 +148,32 @@ pub trait ChangeSetTrait: std::fmt::Debug + Default + Clone + Send + Sync + Size
         Ok(affected)
     }
 
+    /// Updates the row with this change set's primary key if it exists and inserts it otherwise, returning the stored
+    /// record. The lookup and the write run in one transaction (or the caller's open one).
     #[tracing::instrument(skip(self, conn))]
-    async fn delete(self, conn: &crate::Connection) -> Result<u64> {
+    async fn save<Conn: crate::ConnectionLike>(self, conn: &Conn) -> Result<<Self::Table as TableTrait>::Record>
+    where <Self::Table as TableTrait>::Record: Send {
+        let Some(pk_value) = self.get_primary_key_value() else {
+            return self.insert(conn).await;
+        };
+
+        conn.in_transaction(async {
+            let existing = crate::query::Select::<Self::Table>::new()
+                .filter(crate::query::Condition::eq(Self::Table::primary_key(), pk_value))
+                .one(conn)
+                .await?;
+
+            match existing {
+                Some(record) if self.get_update_sets().is_empty() => Ok(record),
+                Some(_) => self.update(conn).await,
+                None => self.insert(conn).await,
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self, conn))]
+    async fn delete<Conn: crate::ConnectionLike>(self, conn: &Conn) -> Result<u64> {
         let pk_value = self.get_primary_key_value().ok_or(crate::error::Error::PrimaryKeyNotSet)?;
         tracing::trace!("Deleting record");
         let affected = crate::query::Delete::<Self::Table>::new()
@@ -79,3 +185,45 @@ pub trait ChangeSetTrait: std::fmt::Debug + Default + Clone + Send + Sync + Size
         Ok(affected)
     }
 }
+
+/// The change set of a `#[tursorm(readonly)]` table. It sets no columns, and writing with it fails with
+/// [`Error::ReadOnlyTable`](crate::Error::ReadOnlyTable).
+pub struct ReadOnlyChangeSet<Table>(std::marker::PhantomData<fn() -> Table>);
+
+impl<Table> Default for ReadOnlyChangeSet<Table> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<Table> Clone for ReadOnlyChangeSet<Table> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<Table> std::fmt::Debug for ReadOnlyChangeSet<Table> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ReadOnlyChangeSet")
+    }
+}
+
+impl<Table: TableTrait<ChangeSet = Self>> ChangeSetTrait for ReadOnlyChangeSet<Table> {
+    type Table = Table;
+
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<Value>) {
+        (Vec::new(), Vec::new())
+    }
+
+    fn get_update_sets(&self) -> Vec<(&'static str, Value)> {
+        Vec::new()
+    }
+
+    fn get_primary_key_value(&self) -> Option<Value> {
+        None
+    }
+
+    fn primary_key_column() -> &'static str {
+        Table::primary_key().name()
+    }
+}
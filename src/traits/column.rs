@@ -1,3 +1,6 @@
+use std::borrow::Cow;
+
+use crate::query::condition::Collation;
 use crate::value::ColumnType;
 
 // Not yet implemented, ignored
@@ -30,7 +33,7 @@ pub struct ForeignKeyInfo {
     pub on_update:   OnUpdate,
 }
 
-pub trait ColumnTrait: std::fmt::Debug + Copy + Clone + std::fmt::Display + 'static {
+pub trait ColumnTrait: std::fmt::Debug + Copy + Clone + 'static {
     fn name(&self) -> &'static str;
 
     fn column_type(&self) -> ColumnType;
@@ -63,7 +66,78 @@ pub trait ColumnTrait: std::fmt::Debug + Copy + Clone + std::fmt::Display + 'sta
         None
     }
 
+    /// Set by `#[tursorm(collate = "...")]`; the column's comparisons, sorting and unique constraint use it.
+    fn collation(&self) -> Option<Collation> {
+        None
+    }
+
+    /// Set by `#[tursorm(max_length = ...)]`: the longest text, in characters, or blob, in bytes, a write may store.
+    fn max_length(&self) -> Option<usize> {
+        None
+    }
+
+    /// The doc comment of the column's field.
+    fn docs(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Whether values are encrypted with the connection's [`Encryptor`](crate::encryption::Encryptor) before they
+    /// are stored.
+    fn is_encrypted(&self) -> bool {
+        false
+    }
+
     fn all() -> &'static [Self];
+
+    /// The column whose database name is `name`, e.g. to turn a sort or filter parameter into a typed column.
+    fn from_name(name: &str) -> Option<Self> {
+        Self::iter().find(|column| column.name() == name)
+    }
+
+    /// Iterates the table's columns in declaration order.
+    fn iter() -> std::iter::Copied<std::slice::Iter<'static, Self>> {
+        Self::all().iter().copied()
+    }
+
+    /// The column qualified with a table alias set by [`Select::alias`](crate::Select::alias) or a join, e.g.
+    /// `UserColumn::Name.of("sender")` renders `sender.name`.
+    fn of(self, alias: &'static str) -> AliasedColumn<Self> {
+        AliasedColumn { alias, column: self }
+    }
+}
+
+/// A column as conditions, ordering and expressions render it: either a table's own column or an [`AliasedColumn`].
+pub trait ColumnRef: Copy {
+    fn qualified_name(&self) -> Cow<'static, str>;
+}
+
+impl<Column: ColumnTrait> ColumnRef for Column {
+    fn qualified_name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.name())
+    }
+}
+
+/// A column of an aliased table, created with [`ColumnTrait::of`].
+#[derive(Debug, Clone, Copy)]
+pub struct AliasedColumn<Column: ColumnTrait> {
+    alias:  &'static str,
+    column: Column,
+}
+
+impl<Column: ColumnTrait> AliasedColumn<Column> {
+    pub fn alias(&self) -> &'static str {
+        self.alias
+    }
+
+    pub fn column(&self) -> Column {
+        self.column
+    }
+}
+
+impl<Column: ColumnTrait> ColumnRef for AliasedColumn<Column> {
+    fn qualified_name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("{}.{}", self.alias, self.column.name()))
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -14,6 +14,7 @@ impl<V: PartialEq> PartialEq for FieldValue<V> {
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl<V: PartialEq> Default for FieldValue<V> {
     fn default() -> Self {
         FieldValue::NotSet
@@ -61,6 +62,26 @@ impl<V: PartialEq> From<V> for FieldValue<V> {
     }
 }
 
+/// Serializes the value of a `Set` field; `NotSet` fields are meant to be skipped with
+/// `#[serde(skip_serializing_if = "FieldValue::is_not_set")]` and serialize as `null` otherwise.
+#[cfg(feature = "serde")]
+impl<V: PartialEq + serde::Serialize> serde::Serialize for FieldValue<V> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            FieldValue::Set(v) => v.serialize(serializer),
+            FieldValue::NotSet => serializer.serialize_none(),
+        }
+    }
+}
+
+/// A present value, even `null`, deserializes as `Set`; pair with `#[serde(default)]` to leave absent keys `NotSet`.
+#[cfg(feature = "serde")]
+impl<'de, V: PartialEq + serde::Deserialize<'de>> serde::Deserialize<'de> for FieldValue<V> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        V::deserialize(deserializer).map(FieldValue::Set)
+    }
+}
+
 pub fn set<V: PartialEq>(value: V) -> FieldValue<V> {
     FieldValue::Set(value)
 }
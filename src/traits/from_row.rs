@@ -1,5 +1,37 @@
+use crate::Error;
 use crate::error::Result;
 
 pub trait FromRow: Sized {
     fn from_row(row: &turso::Row) -> Result<Self>;
+
+    /// Reads a row of a result whose columns are named `columns`. Reads by position unless derived with
+    /// `#[tursorm(from_row = "by_name")]`, which looks every field up by its column name instead.
+    fn from_named_row(row: &turso::Row, columns: &RowColumns) -> Result<Self> {
+        let _ = columns;
+        Self::from_row(row)
+    }
+}
+
+/// The column names of a query's result, from [`QueryRows::columns`](crate::QueryRows::columns).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RowColumns {
+    names: Vec<String>,
+}
+
+impl RowColumns {
+    pub fn new(names: Vec<String>) -> Self {
+        Self { names }
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The position of the column called `name`, ignoring ASCII case like SQL does.
+    pub fn index(&self, name: &str) -> Result<usize> {
+        self.names
+            .iter()
+            .position(|column| column.eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::ColumnNotFound(name.to_string()))
+    }
 }
@@ -2,11 +2,17 @@ pub(crate) mod change_set;
 pub(crate) mod column;
 pub(crate) mod field_value;
 pub(crate) mod from_row;
+pub(crate) mod polymorphic;
 pub(crate) mod record;
 pub(crate) mod table;
+pub(crate) mod table_enum;
+pub(crate) mod tree;
 
 pub mod prelude {
     pub use super::change_set::ChangeSetTrait;
+    pub use super::change_set::ReadOnlyChangeSet;
+    pub use super::column::AliasedColumn;
+    pub use super::column::ColumnRef;
     pub use super::column::ColumnTrait;
     pub use super::column::ForeignKeyInfo;
     pub use super::column::OnDelete;
@@ -15,8 +21,13 @@ pub mod prelude {
     pub use super::field_value::not_set;
     pub use super::field_value::set;
     pub use super::from_row::FromRow;
+    pub use super::from_row::RowColumns;
+    pub use super::polymorphic::PolymorphicTrait;
     pub use super::record::RecordTrait;
     pub use super::table::TableTrait;
+    pub use super::table_enum::TableEnumTrait;
+    pub use super::tree::TreeRecordExt;
+    pub use super::tree::TreeTrait;
 }
 
 #[cfg(test)]
@@ -0,0 +1,44 @@
+use super::record::RecordTrait;
+use super::table::TableTrait;
+use crate::Condition;
+use crate::Select;
+use crate::value::Value;
+
+/// Tables whose rows belong to one of several owner tables, identified by a type column holding the owner's table
+/// name and an id column holding the owner's primary key.
+pub trait PolymorphicTrait: TableTrait {
+    fn owner_type_column() -> Self::Column;
+
+    fn owner_id_column() -> Self::Column;
+
+    fn owner_of(record: &Self::Record) -> (Value, Value);
+
+    fn owner_type<Owner: RecordTrait>() -> &'static str {
+        <Owner::Table as TableTrait>::table_name()
+    }
+
+    fn of<Owner: RecordTrait>(owner: &Owner) -> Condition {
+        Condition::eq(Self::owner_type_column(), Self::owner_type::<Owner>())
+            .and(Condition::eq(Self::owner_id_column(), owner.get_primary_key_value()))
+    }
+
+    fn of_any<Owner: RecordTrait>(owners: &[Owner]) -> Condition {
+        Condition::eq(Self::owner_type_column(), Self::owner_type::<Owner>()).and(Condition::is_in(
+            Self::owner_id_column(),
+            owners.iter().map(|owner| owner.get_primary_key_value()).collect(),
+        ))
+    }
+
+    fn find_for<Owner: RecordTrait>(owner: &Owner) -> Select<Self> {
+        Select::new().filter(Self::of(owner))
+    }
+
+    fn find_for_any<Owner: RecordTrait>(owners: &[Owner]) -> Select<Self> {
+        Select::new().filter(Self::of_any(owners))
+    }
+
+    fn belongs_to<Owner: RecordTrait>(record: &Self::Record, owner: &Owner) -> bool {
+        let (owner_type, owner_id) = Self::owner_of(record);
+        owner_type == Value::Text(Self::owner_type::<Owner>().to_string()) && owner_id == owner.get_primary_key_value()
+    }
+}
@@ -1,6 +1,9 @@
 use super::table::TableTrait;
 use crate::Condition;
 use crate::Delete;
+use crate::FromRow;
+use crate::encryption::Encryptor;
+use crate::error::Result;
 use crate::value::Value;
 
 pub trait RecordTrait: std::fmt::Debug + Clone + Send + Sync {
@@ -8,6 +11,12 @@ pub trait RecordTrait: std::fmt::Debug + Clone + Send + Sync {
 
     fn get_primary_key_value(&self) -> Value;
 
+    /// Loads a record from a row, decrypting its encrypted columns with `encryptor`.
+    fn decode_row(row: &turso::Row, _encryptor: Option<&dyn Encryptor>) -> Result<Self>
+    where Self: FromRow {
+        Self::from_row(row)
+    }
+
     fn into_change_set(self) -> <Self::Table as TableTrait>::ChangeSet
     where <Self::Table as TableTrait>::ChangeSet: From<Self> {
         <Self::Table as TableTrait>::ChangeSet::from(self)
@@ -1,11 +1,22 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+
 use super::change_set::ChangeSetTrait;
 use super::column::ColumnTrait;
 use super::from_row::FromRow;
 use super::record::RecordTrait;
 use crate::Condition;
+use crate::ConnectionLike;
 use crate::Delete;
+use crate::Error;
 use crate::IntoValue;
+use crate::Result;
 use crate::Select;
+use crate::Update;
+use crate::Value;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::value::value_key;
 
 pub trait TableTrait: std::fmt::Debug + Default + Send + Sync + 'static {
     type Record: RecordTrait<Table = Self> + FromRow + Send;
@@ -14,30 +25,185 @@ pub trait TableTrait: std::fmt::Debug + Default + Send + Sync + 'static {
 
     type ChangeSet: ChangeSetTrait<Table = Self>;
 
+    /// The Rust type of the primary key field, which [`TableSelectExt::find_by_id`] takes.
+    type PrimaryKeyType: Send;
+
+    /// Limit applied to selects started with `find()`; override per query with `limit` or `unlimited`.
+    const DEFAULT_LIMIT: Option<usize> = None;
+
+    /// Set by `#[tursorm(readonly)]`; inserts, updates and deletes then fail with [`Error::ReadOnlyTable`].
+    const READ_ONLY: bool = false;
+
     fn table_name() -> &'static str;
 
     fn primary_key() -> Self::Column;
 
+    /// Converts a primary key the way the field is stored, e.g. honoring `#[tursorm(with = ...)]`.
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> Value;
+
     fn primary_key_auto_increment() -> bool;
 
     fn all_columns() -> &'static str;
 
     fn column_count() -> usize;
+
+    fn renamed_from() -> Option<&'static str> {
+        None
+    }
+
+    /// The `SELECT` behind a table declared with `#[tursorm(view = "...")]`, which the migrator creates as a view.
+    fn view_definition() -> Option<&'static str> {
+        None
+    }
+
+    /// The doc comment of the table's struct, e.g. for admin UIs and schema catalogs.
+    fn table_docs() -> Option<&'static str> {
+        None
+    }
+
+    /// The junction tables of the table's `many_to_many` relations.
+    fn junction_tables() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// The attached database the table lives in, set with `#[tursorm(database = "...")]`; `None` means `main`.
+    fn schema_name() -> Option<&'static str> {
+        None
+    }
+
+    /// The column set with `#[tursorm(discriminator = "...")]`, whose value picks the variant of a
+    /// [`TableEnumTrait`](crate::TableEnumTrait) reading the table.
+    fn discriminator_column() -> Option<Self::Column> {
+        None
+    }
+}
+
+/// `schema.table` for tables outside `main`; an explicit `schema` overrides the table's own.
+pub(crate) fn qualified_table_name<Table: TableTrait>(schema: Option<&str>) -> String {
+    match schema.or(Table::schema_name()) {
+        Some(schema) => format!("{}.{}", schema, Table::table_name()),
+        None => Table::table_name().to_string(),
+    }
+}
+
+pub(crate) fn ensure_writable<Table: TableTrait>() -> Result<()> {
+    if Table::READ_ONLY { Err(Error::ReadOnlyTable { table: Table::table_name() }) } else { Ok(()) }
+}
+
+/// Fails with [`Error::ValueTooLarge`] when a value written to `columns` is longer than its column's `max_length` or
+/// bigger than `max_blob_size` bytes, before the statement runs. Other values are not checked.
+pub(crate) fn ensure_sizes<Table: TableTrait>(
+    max_blob_size: Option<usize>,
+    columns: &[&str],
+    values: &[Value],
+) -> Result<()> {
+    for (name, value) in columns.iter().zip(values) {
+        let (length, bytes) = match value {
+            Value::Text(text) => (text.chars().count(), text.len()),
+            Value::Blob(blob) => (blob.len(), blob.len()),
+            _ => continue,
+        };
+        let too_large = |size: usize, max: usize| Error::ValueTooLarge {
+            column: format!("{}.{}", Table::table_name(), name),
+            size,
+            max,
+        };
+
+        let max_length = Table::Column::all().iter().find(|column| column.name() == *name).and_then(|c| c.max_length());
+        if let Some(max) = max_length.filter(|max| length > *max) {
+            return Err(too_large(length, max));
+        }
+        if let Some(max) = max_blob_size.filter(|max| bytes > *max) {
+            return Err(too_large(bytes, max));
+        }
+    }
+
+    Ok(())
 }
 
+#[async_trait::async_trait]
 pub trait TableSelectExt: TableTrait {
     #[tracing::instrument]
     fn find() -> Select<Self> {
-        Select::new()
+        match Self::DEFAULT_LIMIT {
+            Some(limit) => Select::new().limit(limit),
+            None => Select::new(),
+        }
     }
 
-    #[tracing::instrument]
-    fn find_by_id<V: crate::value::IntoValue>(id: V) -> Select<Self>
-    where Self::Column: ColumnTrait {
-        Select::new().filter(Condition::eq(<Self>::primary_key(), id))
+    #[tracing::instrument(skip(id))]
+    fn find_by_id(id: impl Into<Self::PrimaryKeyType>) -> Select<Self> {
+        Select::new().by_key(Self::primary_key_to_value(&id.into()))
+    }
+
+    /// Loads the rows with the given primary keys in one query, in the order of `ids`. Missing rows are left out, and
+    /// a key given more than once yields its row each time.
+    #[tracing::instrument(skip_all, fields(ids = ids.len()))]
+    async fn find_by_ids<Conn: ConnectionLike, Id: Into<Self::PrimaryKeyType> + Send>(
+        conn: &Conn,
+        ids: Vec<Id>,
+    ) -> Result<Vec<Self::Record>> {
+        let keys: Vec<Value> = ids.into_iter().map(|id| Self::primary_key_to_value(&id.into())).collect();
+        let mut records = find_keyed::<Self, Conn>(conn, &keys).await?;
+
+        let keys: Vec<String> = keys.iter().map(value_key).collect();
+        let mut remaining: HashMap<&str, usize> = HashMap::new();
+        for key in &keys {
+            *remaining.entry(key).or_default() += 1;
+        }
+
+        // Only the last occurrence of a key takes the record; earlier ones clone it.
+        Ok(keys
+            .iter()
+            .filter_map(|key| {
+                let left = remaining.get_mut(key.as_str())?;
+                *left -= 1;
+                if *left == 0 { records.remove(key) } else { records.get(key).cloned() }
+            })
+            .collect())
+    }
+
+    /// Like [`TableSelectExt::find_by_ids`], keyed by the given primary keys.
+    #[tracing::instrument(skip_all, fields(ids = ids.len()))]
+    async fn find_map_by_ids<Conn: ConnectionLike>(
+        conn: &Conn,
+        ids: Vec<Self::PrimaryKeyType>,
+    ) -> Result<HashMap<Self::PrimaryKeyType, Self::Record>>
+    where
+        Self::PrimaryKeyType: Eq + Hash,
+    {
+        let keys: Vec<Value> = ids.iter().map(Self::primary_key_to_value).collect();
+        let mut records = find_keyed::<Self, Conn>(conn, &keys).await?;
+
+        Ok(ids
+            .into_iter()
+            .zip(&keys)
+            .filter_map(|(id, key)| records.remove(&value_key(key)).map(|record| (id, record)))
+            .collect())
     }
 }
 
+/// The rows with the given primary keys, by [`value_key`] of the key.
+async fn find_keyed<Table: TableTrait, Conn: ConnectionLike>(
+    conn: &Conn,
+    keys: &[Value],
+) -> Result<HashMap<String, Table::Record>> {
+    let mut unique: Vec<Value> = Vec::with_capacity(keys.len());
+    let mut seen = HashSet::new();
+    for key in keys {
+        if seen.insert(value_key(key)) {
+            unique.push(key.clone());
+        }
+    }
+
+    if unique.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let records = Select::<Table>::new().filter(Condition::is_in(Table::primary_key(), unique)).all(conn).await?;
+    Ok(records.into_iter().map(|record| (value_key(&record.get_primary_key_value()), record)).collect())
+}
+
 impl<Table: TableTrait> TableSelectExt for Table {}
 
 pub trait TableDeleteExt: TableTrait {
@@ -54,8 +220,65 @@ pub trait TableDeleteExt: TableTrait {
 
     #[tracing::instrument]
     fn truncate() -> Delete<Self> {
-        Delete::new()
+        Delete::new().allow_full_table()
     }
 }
 
 impl<Table: TableTrait> TableDeleteExt for Table {}
+
+/// Lookups that fall back to inserting, run in one immediate transaction (or the caller's open one) so concurrent
+/// callers can't both insert.
+#[async_trait::async_trait]
+pub trait TableUpsertExt: TableTrait {
+    /// Returns the first row matching `condition`, inserting `create()` if there is none.
+    ///
+    /// When the insert hits a unique constraint, e.g. because a row was inserted since the lookup inside the caller's
+    /// transaction, the lookup is retried before the error is returned.
+    #[tracing::instrument(skip(conn, create))]
+    async fn find_or_create<Conn: ConnectionLike>(
+        conn: &Conn,
+        condition: Condition,
+        create: impl FnOnce() -> Self::ChangeSet + Send,
+    ) -> Result<Self::Record> {
+        conn.in_transaction(async {
+            if let Some(record) = Select::<Self>::new().filter(condition.clone()).one(conn).await? {
+                return Ok(record);
+            }
+
+            match create().insert(conn).await {
+                Err(error @ Error::UniqueViolation { .. }) => {
+                    Select::<Self>::new().filter(condition).one(conn).await?.ok_or(error)
+                }
+                result => result,
+            }
+        })
+        .await
+    }
+
+    /// Applies `change_set` to the first row matching `condition` and returns it updated, or inserts `change_set` if
+    /// no row matches. The change set should therefore also set the columns `condition` looks for.
+    #[tracing::instrument(skip(conn))]
+    async fn update_or_create<Conn: ConnectionLike>(
+        conn: &Conn,
+        condition: Condition,
+        change_set: Self::ChangeSet,
+    ) -> Result<Self::Record> {
+        conn.in_transaction(async {
+            let existing = Select::<Self>::new().filter(condition).one(conn).await?;
+
+            match existing {
+                Some(record) if change_set.get_update_sets().is_empty() => Ok(record),
+                Some(record) => {
+                    Update::<Self>::new(change_set)
+                        .filter(Condition::eq(Self::primary_key(), record.get_primary_key_value()))
+                        .exec_with_returning(conn)
+                        .await
+                }
+                None => change_set.insert(conn).await,
+            }
+        })
+        .await
+    }
+}
+
+impl<Table: TableTrait> TableUpsertExt for Table {}
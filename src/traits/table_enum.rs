@@ -0,0 +1,99 @@
+use super::change_set::ChangeSetTrait;
+use super::column::ColumnTrait;
+use super::from_row::FromRow;
+use super::from_row::RowColumns;
+use super::record::RecordTrait;
+use super::table::TableTrait;
+use crate::Condition;
+use crate::ConnectionLike;
+use crate::Error;
+use crate::FromValue;
+use crate::Result;
+use crate::Select;
+
+/// An enum over the kinds of rows sharing one table, told apart by the table's discriminator column (single-table
+/// inheritance). Derive it with `#[derive(TableEnum)]`:
+///
+/// ```ignore
+/// #[derive(Clone, Debug, Table)]
+/// #[tursorm(discriminator = "kind")]
+/// pub struct Event {
+///     #[tursorm(primary_key, auto_increment)]
+///     pub id:   i64,
+///     pub kind: String,
+///     pub x:    Option<i64>,
+///     pub url:  Option<String>,
+/// }
+///
+/// #[derive(Clone, Debug, FromRow)]
+/// #[tursorm(from_row = "by_name")]
+/// pub struct Click {
+///     pub id: i64,
+///     pub x:  i64,
+/// }
+///
+/// impl From<Click> for EventChangeSet {
+///     fn from(click: Click) -> Self {
+///         EventChangeSet { x: set(Some(click.x)), ..Default::default() }
+///     }
+/// }
+///
+/// #[derive(Clone, Debug, TableEnum)]
+/// #[tursorm(table = "EventTable")]
+/// pub enum EventKind {
+///     Click(Click),
+///     #[tursorm(discriminator = "page_view")]
+///     View(View),
+/// }
+///
+/// EventKind::Click(Click { id: 0, x: 3 }).insert(&conn).await?;
+/// let events = EventKind::find().into_model::<EventKind>().all(&conn).await?;
+/// ```
+///
+/// Each variant wraps one type, read from the row with [`FromRow::from_named_row`], so its fields are looked up by
+/// column name, and inserted through its `Into` conversion to the table's change set, after which the discriminator
+/// is set to the variant's. Discriminators default to the variant name in snake case.
+#[async_trait::async_trait]
+pub trait TableEnumTrait: FromRow + Send + Sync + Sized + 'static {
+    type Table: TableTrait;
+
+    /// The discriminators of the variants, in declaration order.
+    fn discriminators() -> &'static [&'static str];
+
+    fn discriminator(&self) -> &'static str;
+
+    /// The change set inserting this value, with the discriminator column set.
+    fn into_change_set(self) -> Result<<Self::Table as TableTrait>::ChangeSet>;
+
+    /// The discriminator of a row of the table.
+    fn discriminator_of(row: &turso::Row, columns: &RowColumns) -> Result<String> {
+        let column = discriminator_column::<Self::Table>()?;
+        String::from_value(row.get_value(columns.index(column.name())?)?)
+    }
+
+    /// Selects the rows whose discriminator belongs to one of the variants; read them with
+    /// [`Select::into_model`].
+    fn find() -> Select<Self::Table> {
+        match <Self::Table as TableTrait>::discriminator_column() {
+            Some(column) => Select::new().filter(Condition::is_in(column, Self::discriminators().to_vec())),
+            None => Select::new(),
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn insert<Conn: ConnectionLike>(self, conn: &Conn) -> Result<Self> {
+        let record = self.into_change_set()?.insert(conn).await?;
+        let key = Condition::eq(<Self::Table as TableTrait>::primary_key(), record.get_primary_key_value());
+        Self::find().filter(key).into_model::<Self>().one(conn).await?.ok_or(Error::NoRowsAffected)
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn insert_exec<Conn: ConnectionLike>(self, conn: &Conn) -> Result<u64> {
+        self.into_change_set()?.insert_exec(conn).await
+    }
+}
+
+fn discriminator_column<Table: TableTrait>() -> Result<Table::Column> {
+    Table::discriminator_column()
+        .ok_or_else(|| Error::Query(format!("Table {} has no discriminator column", Table::table_name())))
+}
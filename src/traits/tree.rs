@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+
+use super::record::RecordTrait;
+use super::table::TableTrait;
+use crate::Condition;
+use crate::ConnectionLike;
+use crate::Error;
+use crate::Result;
+use crate::Select;
+use crate::Update;
+use crate::connection::connection_like::ConnectionLikeExt;
+use crate::value::Value;
+use crate::value::value_key;
+
+/// Tables whose rows form a tree through a nullable column holding the parent row's primary key, declared with
+/// `#[tursorm(tree = "parent_id")]`; roots have no parent.
+///
+/// turso can't run recursive CTEs yet, so [`TreeTrait::descendants`] reads the subtree one level per query and
+/// [`TreeTrait::ancestors`] one row per query. Both stop at rows they have already seen, should the parent column
+/// contain a cycle.
+#[async_trait::async_trait]
+pub trait TreeTrait: TableTrait {
+    fn parent_column() -> Self::Column;
+
+    /// The primary key of the record's parent, or `NULL` for a root.
+    fn parent_of(record: &Self::Record) -> Value;
+
+    fn roots() -> Select<Self> {
+        Select::new().filter(Condition::is_null(Self::parent_column()))
+    }
+
+    fn children(record: &Self::Record) -> Select<Self> {
+        Select::new().filter(Condition::eq(Self::parent_column(), record.get_primary_key_value()))
+    }
+
+    /// The record's parent, its parent's parent and so on up to the root.
+    #[tracing::instrument(skip_all)]
+    async fn ancestors<Conn: ConnectionLike>(conn: &Conn, record: &Self::Record) -> Result<Vec<Self::Record>> {
+        let mut seen = HashSet::from([value_key(&record.get_primary_key_value())]);
+        let mut ancestors = Vec::new();
+
+        let mut parent = Self::parent_of(record);
+        while parent != Value::Null && seen.insert(value_key(&parent)) {
+            let Some(record) = Select::<Self>::new().by_key(parent).one(conn).await? else {
+                break;
+            };
+            parent = Self::parent_of(&record);
+            ancestors.push(record);
+        }
+
+        Ok(ancestors)
+    }
+
+    /// Every row below the record, level by level.
+    #[tracing::instrument(skip_all)]
+    async fn descendants<Conn: ConnectionLike>(conn: &Conn, record: &Self::Record) -> Result<Vec<Self::Record>> {
+        let mut seen = HashSet::from([value_key(&record.get_primary_key_value())]);
+        let mut descendants = Vec::new();
+
+        let mut level = vec![record.get_primary_key_value()];
+        while !level.is_empty() {
+            // turso 0.3 matches `NULL` against any `IN` list, so roots have to be excluded explicitly.
+            let children = Select::<Self>::new()
+                .filter(Condition::is_not_null(Self::parent_column()))
+                .filter(Condition::is_in(Self::parent_column(), level))
+                .all(conn)
+                .await?;
+            let children: Vec<_> =
+                children.into_iter().filter(|child| seen.insert(value_key(&child.get_primary_key_value()))).collect();
+
+            level = children.iter().map(|child| child.get_primary_key_value()).collect();
+            descendants.extend(children);
+        }
+
+        Ok(descendants)
+    }
+
+    /// Moves the record and everything below it under `new_parent`, or makes it a root. Fails if `new_parent` is the
+    /// record itself or one of its descendants.
+    #[tracing::instrument(skip_all)]
+    async fn move_subtree<Conn: ConnectionLike>(
+        conn: &Conn,
+        record: &Self::Record,
+        new_parent: Option<&Self::Record>,
+    ) -> Result<u64> {
+        conn.in_transaction(async {
+            let parent = match new_parent {
+                Some(parent) => {
+                    let parent_key = parent.get_primary_key_value();
+                    let descendants = Self::descendants(conn, record).await?;
+                    let inside = parent_key == record.get_primary_key_value()
+                        || descendants.iter().any(|descendant| descendant.get_primary_key_value() == parent_key);
+                    if inside {
+                        return Err(Error::Query(format!(
+                            "Can't move {} {:?} under its own subtree",
+                            Self::table_name(),
+                            record.get_primary_key_value()
+                        )));
+                    }
+                    parent_key
+                }
+                None => Value::Null,
+            };
+
+            Update::<Self>::many()
+                .set(Self::parent_column(), parent)
+                .filter(Condition::eq(Self::primary_key(), record.get_primary_key_value()))
+                .exec(conn)
+                .await
+        })
+        .await
+    }
+}
+
+/// [`TreeTrait`] lookups called on a record, e.g. `category.ancestors(&conn)`.
+#[async_trait::async_trait]
+pub trait TreeRecordExt: RecordTrait
+where Self::Table: TreeTrait<Record = Self>
+{
+    fn parent_key(&self) -> Value {
+        Self::Table::parent_of(self)
+    }
+
+    fn children(&self) -> Select<Self::Table> {
+        Self::Table::children(self)
+    }
+
+    async fn ancestors<Conn: ConnectionLike>(&self, conn: &Conn) -> Result<Vec<Self>> {
+        Self::Table::ancestors(conn, self).await
+    }
+
+    async fn descendants<Conn: ConnectionLike>(&self, conn: &Conn) -> Result<Vec<Self>> {
+        Self::Table::descendants(conn, self).await
+    }
+
+    async fn move_subtree<Conn: ConnectionLike>(&self, conn: &Conn, new_parent: Option<&Self>) -> Result<u64> {
+        Self::Table::move_subtree(conn, self, new_parent).await
+    }
+}
+
+impl<Record> TreeRecordExt for Record
+where
+    Record: RecordTrait,
+    Record::Table: TreeTrait<Record = Record>,
+{
+}
@@ -0,0 +1,176 @@
+//! Conversions between [`Value`]s and Rust types registered at runtime.
+//!
+//! Derived tables convert their fields with [`IntoValue`] and [`FromValue`] or a `#[tursorm(with = ...)]` module. The
+//! registry covers the places without a derive, such as parameters and results of raw queries, for types that can't
+//! implement those traits:
+//!
+//! ```ignore
+//! conn.type_registry().register::<Money>(|money| Value::Integer(money.cents), |value| {
+//!     Ok(Money { cents: i64::from_value(value)? })
+//! });
+//!
+//! let price = conn.type_registry().encode(&Money { cents: 250 })?;
+//! let mut rows = conn.query("SELECT price FROM products WHERE price > ?", vec![price]).await?;
+//! while let Some(row) = rows.next().await? {
+//!     let price: Money = row.get_value(0)?.decode(conn.type_registry())?;
+//! }
+//! ```
+
+use std::any::Any;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use crate::Error;
+use crate::FromValue;
+use crate::IntoValue;
+use crate::Result;
+use crate::Value;
+
+type EncodeFn = dyn Fn(&dyn Any) -> Value + Send + Sync;
+type DecodeFn = dyn Fn(Value) -> Result<Box<dyn Any>> + Send + Sync;
+
+#[derive(Clone)]
+struct Conversion {
+    type_name: &'static str,
+    encode:    Arc<EncodeFn>,
+    decode:    Arc<DecodeFn>,
+}
+
+/// Type-erased conversions keyed by Rust type, shared by every connection of a database.
+///
+/// A new registry already knows the integer, float, `bool`, `String`, `Vec<u8>` and [`Value`] types and their
+/// `Option`s. Registering a type again replaces its conversion.
+pub struct TypeRegistry {
+    conversions: RwLock<HashMap<TypeId, Conversion>>,
+}
+
+impl TypeRegistry {
+    pub fn new() -> Self {
+        let registry = Self { conversions: RwLock::new(HashMap::new()) };
+
+        macro_rules! register_builtin {
+            ($($ty:ty),+) => {$(
+                registry.register_value_type::<$ty>();
+                registry.register_value_type::<Option<$ty>>();
+            )+};
+        }
+        register_builtin!(i64, i32, i16, i8, u64, u32, u16, u8, f64, f32, bool, String, Vec<u8>, Value);
+
+        registry
+    }
+
+    pub fn register<T: Any + Send + Sync>(
+        &self,
+        encode: impl Fn(&T) -> Value + Send + Sync + 'static,
+        decode: impl Fn(Value) -> Result<T> + Send + Sync + 'static,
+    ) {
+        let conversion = Conversion {
+            type_name: std::any::type_name::<T>(),
+            encode:    Arc::new(move |value| encode(value.downcast_ref::<T>().expect("registered under its TypeId"))),
+            decode:    Arc::new(move |value| decode(value).map(|decoded| Box::new(decoded) as Box<dyn Any>)),
+        };
+
+        self.conversions.write().unwrap_or_else(|e| e.into_inner()).insert(TypeId::of::<T>(), conversion);
+    }
+
+    /// Registers a type through its own [`IntoValue`] and [`FromValue`] implementations.
+    pub fn register_value_type<T: IntoValue + FromValue + Clone + Send + Sync + 'static>(&self) {
+        self.register::<T>(|value| value.clone().into_value(), T::from_value);
+    }
+
+    pub fn is_registered<T: Any>(&self) -> bool {
+        self.conversions.read().unwrap_or_else(|e| e.into_inner()).contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn encode<T: Any>(&self, value: &T) -> Result<Value> {
+        let conversion = self.conversion::<T>(|| format!("{} value", std::any::type_name::<T>()))?;
+        Ok((conversion.encode)(value))
+    }
+
+    pub fn decode<T: Any>(&self, value: Value) -> Result<T> {
+        let conversion = self.conversion::<T>(|| format!("{:?}", value))?;
+        let decoded = (conversion.decode)(value)?;
+
+        Ok(*decoded.downcast::<T>().expect("registered under its TypeId"))
+    }
+
+    fn conversion<T: Any>(&self, actual: impl FnOnce() -> String) -> Result<Conversion> {
+        self.conversions.read().unwrap_or_else(|e| e.into_inner()).get(&TypeId::of::<T>()).cloned().ok_or_else(|| {
+            Error::TypeConversion {
+                expected: std::any::type_name::<T>(),
+                actual:   actual(),
+                error:    "no conversion registered for the type".to_string(),
+            }
+        })
+    }
+}
+
+impl Default for TypeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for TypeRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let conversions = self.conversions.read().unwrap_or_else(|e| e.into_inner());
+        let mut types: Vec<&str> = conversions.values().map(|conversion| conversion.type_name).collect();
+        types.sort_unstable();
+
+        f.debug_struct("TypeRegistry").field("types", &types).finish()
+    }
+}
+
+/// Decoding of a [`Value`] through a [`TypeRegistry`].
+pub trait ValueExt {
+    fn decode<T: Any>(self, registry: &TypeRegistry) -> Result<T>;
+}
+
+impl ValueExt for Value {
+    fn decode<T: Any>(self, registry: &TypeRegistry) -> Result<T> {
+        registry.decode(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Money {
+        cents: i64,
+    }
+
+    fn registry_with_money() -> TypeRegistry {
+        let registry = TypeRegistry::new();
+        registry.register::<Money>(
+            |money| Value::Integer(money.cents),
+            |value| Ok(Money { cents: i64::from_value(value)? }),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_type_registry_round_trips_registered_types() {
+        let registry = registry_with_money();
+
+        assert_eq!(registry.encode(&Money { cents: 250 }).unwrap(), Value::Integer(250));
+        assert_eq!(Value::Integer(250).decode::<Money>(&registry).unwrap(), Money { cents: 250 });
+        assert!(Value::Text("oops".to_string()).decode::<Money>(&registry).is_err());
+    }
+
+    #[test]
+    fn test_type_registry_builtins_and_unregistered_types() {
+        let registry = TypeRegistry::new();
+
+        assert_eq!(Value::Integer(7).decode::<i64>(&registry).unwrap(), 7);
+        assert_eq!(Value::Null.decode::<Option<String>>(&registry).unwrap(), None);
+        assert_eq!(registry.encode(&true).unwrap(), Value::Integer(1));
+
+        assert!(!registry.is_registered::<Money>());
+        assert!(matches!(registry.encode(&Money { cents: 1 }), Err(Error::TypeConversion { .. })));
+        assert!(matches!(Value::Integer(1).decode::<Money>(&registry), Err(Error::TypeConversion { .. })));
+    }
+}
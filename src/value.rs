@@ -1,7 +1,10 @@
+use std::cmp::Ordering;
+
 pub use turso::Value;
 
 use crate::error::Error;
 use crate::error::Result;
+use crate::query::condition::Collation;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ColumnType {
@@ -19,12 +22,25 @@ pub trait IntoValue: std::fmt::Debug {
 pub trait FromValue: std::fmt::Debug + Sized {
     fn from_value(value: Value) -> Result<Self>;
 
+    /// Reads NULL as `Self::default()`, or fails with [`Error::UnexpectedNull`] with the `strict-decode` feature.
     fn from_value_opt(value: Value) -> Result<Self>
     where Self: Default {
-        if matches!(value, Value::Null) { Ok(Self::default()) } else { Self::from_value(value) }
+        match value {
+            Value::Null if cfg!(feature = "strict-decode") => Err(Error::UnexpectedNull),
+            Value::Null => Ok(Self::default()),
+            value => Self::from_value(value),
+        }
     }
 }
 
+/// Fallible conversion for integers that don't always fit SQLite's signed 64-bit INTEGER.
+///
+/// Fails with [`Error::OutOfRange`] instead of wrapping; use the [`crate::codec`] modules to store such values
+/// losslessly as TEXT or BLOB.
+pub trait TryIntoValue: std::fmt::Debug {
+    fn try_into_value(self) -> Result<Value>;
+}
+
 impl IntoValue for i64 {
     fn into_value(self) -> Value {
         Value::Integer(self)
@@ -67,6 +83,8 @@ impl IntoValue for u8 {
     }
 }
 
+/// Values above `i64::MAX` wrap to negative integers, which [`FromValue`] then rejects; prefer
+/// [`TryIntoValue::try_into_value`] or a lossless [`crate::codec`] module when the full range is needed.
 impl IntoValue for u64 {
     fn into_value(self) -> Value {
         Value::Integer(self as i64)
@@ -146,7 +164,7 @@ impl FromValue for i64 {
     fn from_value(value: Value) -> Result<Self> {
         match value {
             Value::Integer(v) => Ok(v),
-            Value::Real(v) => Ok(v as i64),
+            Value::Real(v) => real_to_integer(v),
             Value::Null => Err(Error::UnexpectedNull),
             other => Err(Error::TypeConversion {
                 expected: "Integer",
@@ -159,55 +177,158 @@ impl FromValue for i64 {
 
 impl FromValue for i32 {
     fn from_value(value: Value) -> Result<Self> {
-        i64::from_value(value).map(|v| v as i32)
+        checked_integer(value, "i32")
     }
 }
 
 impl FromValue for i16 {
     fn from_value(value: Value) -> Result<Self> {
-        i64::from_value(value).map(|v| v as i16)
+        checked_integer(value, "i16")
     }
 }
 
 impl FromValue for i8 {
     fn from_value(value: Value) -> Result<Self> {
-        i64::from_value(value).map(|v| v as i8)
+        checked_integer(value, "i8")
     }
 }
 
 impl FromValue for u32 {
     fn from_value(value: Value) -> Result<Self> {
-        i64::from_value(value).map(|v| v as u32)
+        checked_integer(value, "u32")
     }
 }
 
 impl FromValue for u16 {
     fn from_value(value: Value) -> Result<Self> {
-        i64::from_value(value).map(|v| v as u16)
+        checked_integer(value, "u16")
     }
 }
 
 impl FromValue for u8 {
     fn from_value(value: Value) -> Result<Self> {
-        i64::from_value(value).map(|v| v as u8)
+        checked_integer(value, "u8")
     }
 }
 
 impl FromValue for u64 {
     fn from_value(value: Value) -> Result<Self> {
-        i64::from_value(value).map(|v| v as u64)
+        checked_integer(value, "u64")
     }
 }
 
 impl FromValue for isize {
     fn from_value(value: Value) -> Result<Self> {
-        i64::from_value(value).map(|v| v as isize)
+        checked_integer(value, "isize")
     }
 }
 
 impl FromValue for usize {
     fn from_value(value: Value) -> Result<Self> {
-        i64::from_value(value).map(|v| v as usize)
+        checked_integer(value, "usize")
+    }
+}
+
+impl FromValue for i128 {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Text(s) => s.parse().map_err(|_| Error::TypeConversion {
+                expected: "i128",
+                actual:   s,
+                error:    "Expected decimal integer".to_string(),
+            }),
+            other => i64::from_value(other).map(i128::from),
+        }
+    }
+}
+
+impl FromValue for u128 {
+    fn from_value(value: Value) -> Result<Self> {
+        let v = i128::from_value(value)?;
+        u128::try_from(v).map_err(|_| Error::OutOfRange { target: "u128", value: v.to_string() })
+    }
+}
+
+/// A value as a set member or map key, since [`Value`] isn't `Hash`.
+pub(crate) fn value_key(value: &Value) -> String {
+    format!("{:?}", value)
+}
+
+/// Orders values the way SQLite sorts them: NULLs, then numbers, then text compared under `collation`, then blobs.
+pub(crate) fn compare_values(a: &Value, b: &Value, collation: Option<Collation>) -> Ordering {
+    let rank = |value: &Value| match value {
+        Value::Null => 0,
+        Value::Integer(_) | Value::Real(_) => 1,
+        Value::Text(_) => 2,
+        Value::Blob(_) => 3,
+    };
+
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+        (Value::Integer(a), Value::Real(b)) => (*a as f64).total_cmp(b),
+        (Value::Real(a), Value::Integer(b)) => a.total_cmp(&(*b as f64)),
+        (Value::Real(a), Value::Real(b)) => a.total_cmp(b),
+        (Value::Text(a), Value::Text(b)) => match collation {
+            Some(collation) => collation.normalize(a).cmp(&collation.normalize(b)),
+            None => a.cmp(b),
+        },
+        (Value::Blob(a), Value::Blob(b)) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// `as` drops the fraction and saturates out-of-range values; with `strict-decode` those fail instead.
+fn real_to_integer(v: f64) -> Result<i64> {
+    let lossless = v.fract() == 0.0 && (i64::MIN as f64..i64::MAX as f64).contains(&v);
+    if cfg!(feature = "strict-decode") && !lossless {
+        return Err(Error::OutOfRange { target: "i64", value: v.to_string() });
+    }
+
+    Ok(v as i64)
+}
+
+/// Reads an integer and checks that it fits `T`, rather than truncating or reinterpreting the sign.
+fn checked_integer<T: TryFrom<i64>>(value: Value, target: &'static str) -> Result<T> {
+    let v = i64::from_value(value)?;
+    T::try_from(v).map_err(|_| Error::OutOfRange { target, value: v.to_string() })
+}
+
+impl TryIntoValue for u64 {
+    fn try_into_value(self) -> Result<Value> {
+        i64::try_from(self)
+            .map(Value::Integer)
+            .map_err(|_| Error::OutOfRange { target: "i64", value: self.to_string() })
+    }
+}
+
+impl TryIntoValue for usize {
+    fn try_into_value(self) -> Result<Value> {
+        (self as u64).try_into_value()
+    }
+}
+
+impl TryIntoValue for i128 {
+    fn try_into_value(self) -> Result<Value> {
+        i64::try_from(self)
+            .map(Value::Integer)
+            .map_err(|_| Error::OutOfRange { target: "i64", value: self.to_string() })
+    }
+}
+
+impl TryIntoValue for u128 {
+    fn try_into_value(self) -> Result<Value> {
+        i64::try_from(self)
+            .map(Value::Integer)
+            .map_err(|_| Error::OutOfRange { target: "i64", value: self.to_string() })
+    }
+}
+
+impl<V: TryIntoValue> TryIntoValue for Option<V> {
+    fn try_into_value(self) -> Result<Value> {
+        match self {
+            Some(v) => v.try_into_value(),
+            None => Ok(Value::Null),
+        }
     }
 }
 
@@ -228,7 +349,12 @@ impl FromValue for f64 {
 
 impl FromValue for f32 {
     fn from_value(value: Value) -> Result<Self> {
-        f64::from_value(value).map(|v| v as f32)
+        let v = f64::from_value(value)?;
+        if cfg!(feature = "strict-decode") && v.is_finite() && (v as f32).is_infinite() {
+            return Err(Error::OutOfRange { target: "f32", value: v.to_string() });
+        }
+
+        Ok(v as f32)
     }
 }
 
@@ -263,7 +389,11 @@ impl FromValue for Vec<u8> {
 impl FromValue for bool {
     fn from_value(value: Value) -> Result<Self> {
         match value {
-            Value::Integer(v) => Ok(v != 0),
+            Value::Integer(v @ (0 | 1)) => Ok(v == 1),
+            Value::Integer(v) if cfg!(feature = "strict-decode") => {
+                Err(Error::OutOfRange { target: "bool", value: v.to_string() })
+            }
+            Value::Integer(_) => Ok(true),
             Value::Null => Err(Error::UnexpectedNull),
             other => Err(Error::TypeConversion {
                 expected: "Integer (boolean)",
@@ -296,41 +426,111 @@ impl FromValue for Value {
 #[cfg(feature = "with-chrono")]
 mod chrono_impl {
     use chrono::DateTime;
+    use chrono::FixedOffset;
+    use chrono::Local;
     use chrono::NaiveDate;
     use chrono::NaiveDateTime;
     use chrono::NaiveTime;
+    use chrono::SecondsFormat;
     use chrono::Utc;
 
     use super::*;
 
+    const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+    /// Parses `YYYY-MM-DD HH:MM:SS[.fff]`, the `T`-separated ISO form, or RFC 3339, returning the wall-clock time as
+    /// written along with its offset when one is present.
+    fn parse_datetime(s: &str) -> Option<(NaiveDateTime, Option<FixedOffset>)> {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+            return Some((dt.naive_local(), Some(*dt.offset())));
+        }
+        NaiveDateTime::parse_from_str(s, DATETIME_FORMAT)
+            .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f"))
+            .ok()
+            .map(|ndt| (ndt, None))
+    }
+
+    /// Reads any supported datetime text; values without an offset are taken to be UTC.
+    fn datetime_from_value(value: Value, expected: &'static str) -> Result<DateTime<FixedOffset>> {
+        match value {
+            Value::Text(s) => match parse_datetime(&s) {
+                Some((ndt, Some(offset))) => ndt.and_local_timezone(offset).single().ok_or(()),
+                Some((ndt, None)) => Ok(ndt.and_utc().fixed_offset()),
+                None => Err(()),
+            }
+            .map_err(|_| Error::TypeConversion {
+                expected,
+                actual: s,
+                error: "Expected a datetime such as 2024-01-31 12:00:00 or RFC 3339".to_string(),
+            }),
+            Value::Null => Err(Error::UnexpectedNull),
+            other => Err(Error::TypeConversion {
+                expected: "Text (datetime)",
+                actual:   format!("{:?}", other),
+                error:    "Expected datetime".to_string(),
+            }),
+        }
+    }
+
     impl IntoValue for NaiveDateTime {
         fn into_value(self) -> Value {
-            Value::Text(self.format("%Y-%m-%d %H:%M:%S").to_string())
+            Value::Text(self.format(DATETIME_FORMAT).to_string())
         }
     }
 
+    /// Offsets in the stored text are dropped, keeping the wall-clock time as written.
     impl FromValue for NaiveDateTime {
         fn from_value(value: Value) -> Result<Self> {
             match value {
-                Value::Text(s) => NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S")
-                    .or_else(|_| NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S"))
-                    .map_err(|_| Error::TypeConversion { expected: "NaiveDateTime", actual: s }),
+                Value::Text(s) => parse_datetime(&s).map(|(ndt, _)| ndt).ok_or_else(|| Error::TypeConversion {
+                    expected: "NaiveDateTime",
+                    actual:   s,
+                    error:    "Expected a datetime such as 2024-01-31 12:00:00".to_string(),
+                }),
                 Value::Null => Err(Error::UnexpectedNull),
-                other => Err(Error::TypeConversion { expected: "Text (datetime)", actual: format!("{:?}", other) }),
+                other => Err(Error::TypeConversion {
+                    expected: "Text (datetime)",
+                    actual:   format!("{:?}", other),
+                    error:    "Expected datetime".to_string(),
+                }),
             }
         }
     }
 
     impl IntoValue for DateTime<Utc> {
         fn into_value(self) -> Value {
-            Value::Text(self.format("%Y-%m-%d %H:%M:%S").to_string())
+            self.naive_utc().into_value()
         }
     }
 
     impl FromValue for DateTime<Utc> {
         fn from_value(value: Value) -> Result<Self> {
-            let ndt = NaiveDateTime::from_value(value)?;
-            Ok(DateTime::from_naive_utc_and_offset(ndt, Utc))
+            datetime_from_value(value, "DateTime<Utc>").map(|dt| dt.with_timezone(&Utc))
+        }
+    }
+
+    /// Stored as RFC 3339 so the offset survives the round trip.
+    impl IntoValue for DateTime<FixedOffset> {
+        fn into_value(self) -> Value {
+            Value::Text(self.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+        }
+    }
+
+    impl FromValue for DateTime<FixedOffset> {
+        fn from_value(value: Value) -> Result<Self> {
+            datetime_from_value(value, "DateTime<FixedOffset>")
+        }
+    }
+
+    impl IntoValue for DateTime<Local> {
+        fn into_value(self) -> Value {
+            self.fixed_offset().into_value()
+        }
+    }
+
+    impl FromValue for DateTime<Local> {
+        fn from_value(value: Value) -> Result<Self> {
+            datetime_from_value(value, "DateTime<Local>").map(|dt| dt.with_timezone(&Local))
         }
     }
 
@@ -343,27 +543,228 @@ mod chrono_impl {
     impl FromValue for NaiveDate {
         fn from_value(value: Value) -> Result<Self> {
             match value {
-                Value::Text(s) => NaiveDate::parse_from_str(&s, "%Y-%m-%d")
-                    .map_err(|_| Error::TypeConversion { expected: "NaiveDate", actual: s }),
+                Value::Text(s) => NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(|e| Error::TypeConversion {
+                    expected: "NaiveDate",
+                    actual:   s,
+                    error:    e.to_string(),
+                }),
                 Value::Null => Err(Error::UnexpectedNull),
-                other => Err(Error::TypeConversion { expected: "Text (date)", actual: format!("{:?}", other) }),
+                other => Err(Error::TypeConversion {
+                    expected: "Text (date)",
+                    actual:   format!("{:?}", other),
+                    error:    "Expected date".to_string(),
+                }),
             }
         }
     }
 
     impl IntoValue for NaiveTime {
         fn into_value(self) -> Value {
-            Value::Text(self.format("%H:%M:%S").to_string())
+            Value::Text(self.format("%H:%M:%S%.f").to_string())
         }
     }
 
     impl FromValue for NaiveTime {
         fn from_value(value: Value) -> Result<Self> {
             match value {
-                Value::Text(s) => NaiveTime::parse_from_str(&s, "%H:%M:%S")
-                    .map_err(|_| Error::TypeConversion { expected: "NaiveTime", actual: s }),
+                Value::Text(s) => NaiveTime::parse_from_str(&s, "%H:%M:%S%.f").map_err(|e| Error::TypeConversion {
+                    expected: "NaiveTime",
+                    actual:   s,
+                    error:    e.to_string(),
+                }),
+                Value::Null => Err(Error::UnexpectedNull),
+                other => Err(Error::TypeConversion {
+                    expected: "Text (time)",
+                    actual:   format!("{:?}", other),
+                    error:    "Expected time".to_string(),
+                }),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "with-time")]
+mod time_impl {
+    use time::Date;
+    use time::OffsetDateTime;
+    use time::PrimitiveDateTime;
+    use time::Time;
+    use time::format_description::BorrowedFormatItem;
+    use time::format_description::well_known::Rfc3339;
+    use time::macros::format_description;
+
+    use super::*;
+
+    const DATETIME_FORMAT: &[BorrowedFormatItem<'_>] =
+        format_description!("[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]]");
+    const DATETIME_FORMAT_ISO: &[BorrowedFormatItem<'_>] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]");
+    const OFFSET_FORMAT: &[BorrowedFormatItem<'_>] =
+        format_description!("[offset_hour sign:mandatory]:[offset_minute]");
+    const DATE_FORMAT: &[BorrowedFormatItem<'_>] = format_description!("[year]-[month]-[day]");
+    const TIME_FORMAT: &[BorrowedFormatItem<'_>] =
+        format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]");
+    const WHOLE_TIME_FORMAT: &[BorrowedFormatItem<'_>] = format_description!("[hour]:[minute]:[second]");
+
+    /// Formats like chrono's `%H:%M:%S%.f`: the fraction is only written when there is one.
+    fn format_time(time: Time) -> String {
+        let format = if time.nanosecond() == 0 { WHOLE_TIME_FORMAT } else { TIME_FORMAT };
+        time.format(format).unwrap_or_default()
+    }
+
+    fn format_date(date: Date) -> String {
+        date.format(DATE_FORMAT).unwrap_or_default()
+    }
+
+    fn parse_primitive(s: &str) -> Option<PrimitiveDateTime> {
+        PrimitiveDateTime::parse(s, DATETIME_FORMAT).or_else(|_| PrimitiveDateTime::parse(s, DATETIME_FORMAT_ISO)).ok()
+    }
+
+    fn text_from_value(value: Value, expected: &'static str) -> Result<String> {
+        match value {
+            Value::Text(s) => Ok(s),
+            Value::Null => Err(Error::UnexpectedNull),
+            other => Err(Error::TypeConversion {
+                expected,
+                actual: format!("{:?}", other),
+                error: "Expected text".to_string(),
+            }),
+        }
+    }
+
+    fn invalid(expected: &'static str, actual: String) -> Error {
+        Error::TypeConversion { expected, actual, error: "Unrecognized date/time format".to_string() }
+    }
+
+    impl IntoValue for PrimitiveDateTime {
+        fn into_value(self) -> Value {
+            Value::Text(format!("{} {}", format_date(self.date()), format_time(self.time())))
+        }
+    }
+
+    /// Offsets in the stored text are dropped, keeping the wall-clock time as written.
+    impl FromValue for PrimitiveDateTime {
+        fn from_value(value: Value) -> Result<Self> {
+            let s = text_from_value(value, "Text (datetime)")?;
+            parse_primitive(&s)
+                .or_else(|| {
+                    OffsetDateTime::parse(&s, &Rfc3339).ok().map(|dt| PrimitiveDateTime::new(dt.date(), dt.time()))
+                })
+                .ok_or_else(|| invalid("PrimitiveDateTime", s))
+        }
+    }
+
+    /// Stored as RFC 3339 so the offset survives the round trip.
+    impl IntoValue for OffsetDateTime {
+        fn into_value(self) -> Value {
+            Value::Text(format!(
+                "{}T{}{}",
+                format_date(self.date()),
+                format_time(self.time()),
+                self.offset().format(OFFSET_FORMAT).unwrap_or_default()
+            ))
+        }
+    }
+
+    /// Values stored without an offset are taken to be UTC.
+    impl FromValue for OffsetDateTime {
+        fn from_value(value: Value) -> Result<Self> {
+            let s = text_from_value(value, "Text (datetime)")?;
+            OffsetDateTime::parse(&s, &Rfc3339)
+                .ok()
+                .or_else(|| parse_primitive(&s).map(PrimitiveDateTime::assume_utc))
+                .ok_or_else(|| invalid("OffsetDateTime", s))
+        }
+    }
+
+    impl IntoValue for Date {
+        fn into_value(self) -> Value {
+            Value::Text(format_date(self))
+        }
+    }
+
+    impl FromValue for Date {
+        fn from_value(value: Value) -> Result<Self> {
+            let s = text_from_value(value, "Text (date)")?;
+            Date::parse(&s, DATE_FORMAT).map_err(|_| invalid("Date", s))
+        }
+    }
+
+    impl IntoValue for Time {
+        fn into_value(self) -> Value {
+            Value::Text(format_time(self))
+        }
+    }
+
+    impl FromValue for Time {
+        fn from_value(value: Value) -> Result<Self> {
+            let s = text_from_value(value, "Text (time)")?;
+            Time::parse(&s, TIME_FORMAT).map_err(|_| invalid("Time", s))
+        }
+    }
+}
+
+#[cfg(feature = "with-decimal")]
+pub use decimal_impl::IntoScaledValue;
+
+#[cfg(feature = "with-decimal")]
+mod decimal_impl {
+    use std::str::FromStr;
+
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    /// Decimals are stored as TEXT so that amounts never pass through `f64`.
+    impl IntoValue for Decimal {
+        fn into_value(self) -> Value {
+            Value::Text(self.to_string())
+        }
+    }
+
+    impl FromValue for Decimal {
+        fn from_value(value: Value) -> Result<Self> {
+            match value {
+                Value::Text(s) => Decimal::from_str(&s).map_err(|e| Error::TypeConversion {
+                    expected: "Decimal",
+                    actual:   s,
+                    error:    e.to_string(),
+                }),
+                Value::Integer(v) => Ok(Decimal::from(v)),
+                Value::Real(v) => Decimal::try_from(v).map_err(|e| Error::TypeConversion {
+                    expected: "Decimal",
+                    actual:   v.to_string(),
+                    error:    e.to_string(),
+                }),
                 Value::Null => Err(Error::UnexpectedNull),
-                other => Err(Error::TypeConversion { expected: "Text (time)", actual: format!("{:?}", other) }),
+                other => Err(Error::TypeConversion {
+                    expected: "Text (decimal)",
+                    actual:   format!("{:?}", other),
+                    error:    "Expected decimal".to_string(),
+                }),
+            }
+        }
+    }
+
+    /// Converts a decimal rounded or padded to a fixed number of fractional digits, used by `#[tursorm(scale = N)]`.
+    ///
+    /// A fixed scale keeps the stored TEXT canonical, so equal amounts are always stored as the same string.
+    pub trait IntoScaledValue {
+        fn into_scaled_value(self, scale: u32) -> Value;
+    }
+
+    impl IntoScaledValue for Decimal {
+        fn into_scaled_value(mut self, scale: u32) -> Value {
+            self.rescale(scale);
+            self.into_value()
+        }
+    }
+
+    impl IntoScaledValue for Option<Decimal> {
+        fn into_scaled_value(self, scale: u32) -> Value {
+            match self {
+                Some(v) => v.into_scaled_value(scale),
+                None => Value::Null,
             }
         }
     }
@@ -631,6 +1032,7 @@ mod arrays_impl {
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
@@ -645,6 +1047,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_column_type_clone() {
         let ct = ColumnType::Integer;
         let cloned = ct.clone();
@@ -773,6 +1176,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "strict-decode"))]
     fn test_i64_from_real_value() {
         let val = Value::Real(42.7);
         assert_eq!(i64::from_value(val).unwrap(), 42);
@@ -826,6 +1230,33 @@ mod tests {
         assert_eq!(u8::from_value(val).unwrap(), 42);
     }
 
+    #[test]
+    fn test_narrowing_from_value_is_checked() {
+        assert!(matches!(u64::from_value(Value::Integer(-1)), Err(Error::OutOfRange { target: "u64", .. })));
+        assert!(matches!(u8::from_value(Value::Integer(256)), Err(Error::OutOfRange { target: "u8", .. })));
+        assert!(matches!(i32::from_value(Value::Integer(i64::MAX)), Err(Error::OutOfRange { target: "i32", .. })));
+        assert_eq!(u64::from_value(Value::Integer(i64::MAX)).unwrap(), i64::MAX as u64);
+        assert_eq!(i8::from_value(Value::Integer(-128)).unwrap(), -128);
+    }
+
+    #[test]
+    fn test_try_into_value() {
+        assert_eq!((i64::MAX as u64).try_into_value().unwrap(), Value::Integer(i64::MAX));
+        assert!(matches!(u64::MAX.try_into_value(), Err(Error::OutOfRange { target: "i64", .. })));
+        assert_eq!((-5i128).try_into_value().unwrap(), Value::Integer(-5));
+        assert!(i128::MAX.try_into_value().is_err());
+        assert!(u128::MAX.try_into_value().is_err());
+        assert_eq!(None::<u64>.try_into_value().unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_i128_from_value() {
+        assert_eq!(i128::from_value(Value::Integer(-3)).unwrap(), -3);
+        assert_eq!(i128::from_value(Value::Text(i128::MIN.to_string())).unwrap(), i128::MIN);
+        assert!(i128::from_value(Value::Text("nope".to_string())).is_err());
+        assert!(matches!(u128::from_value(Value::Integer(-1)), Err(Error::OutOfRange { target: "u128", .. })));
+    }
+
     #[test]
     fn test_f64_from_value() {
         let val = Value::Real(3.14);
@@ -884,6 +1315,7 @@ mod tests {
     fn test_bool_from_value() {
         assert!(bool::from_value(Value::Integer(1)).unwrap());
         assert!(!bool::from_value(Value::Integer(0)).unwrap());
+        #[cfg(not(feature = "strict-decode"))]
         assert!(bool::from_value(Value::Integer(42)).unwrap());
     }
 
@@ -918,11 +1350,36 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "strict-decode"))]
     fn test_from_value_opt_with_null() {
         let val = Value::Null;
         assert_eq!(i64::from_value_opt(val).unwrap(), 0);
     }
 
+    #[test]
+    #[cfg(feature = "strict-decode")]
+    fn test_strict_decode_rejects_lossy_values() {
+        assert!(matches!(i64::from_value_opt(Value::Null), Err(Error::UnexpectedNull)));
+        assert_eq!(Option::<i64>::from_value_opt(Value::Null).unwrap(), None);
+
+        assert_eq!(i64::from_value(Value::Real(-3.0)).unwrap(), -3);
+        assert!(matches!(i64::from_value(Value::Real(2.5)), Err(Error::OutOfRange { target: "i64", .. })));
+        assert!(matches!(i64::from_value(Value::Real(1e19)), Err(Error::OutOfRange { target: "i64", .. })));
+        assert!(matches!(i32::from_value(Value::Real(f64::NAN)), Err(Error::OutOfRange { target: "i64", .. })));
+
+        assert!(matches!(f32::from_value(Value::Real(1e300)), Err(Error::OutOfRange { target: "f32", .. })));
+        assert_eq!(f32::from_value(Value::Real(f64::INFINITY)).unwrap(), f32::INFINITY);
+
+        assert!(matches!(bool::from_value(Value::Integer(2)), Err(Error::OutOfRange { target: "bool", .. })));
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict-decode"))]
+    fn test_lenient_decode_of_lossy_values() {
+        assert_eq!(f32::from_value(Value::Real(1e300)).unwrap(), f32::INFINITY);
+        assert!(bool::from_value(Value::Integer(2)).unwrap());
+    }
+
     #[test]
     fn test_negative_integers() {
         let val: i64 = -42;
@@ -960,6 +1417,157 @@ mod tests {
         assert_eq!(val.into_value(), Value::Real(f64::NEG_INFINITY));
     }
 
+    #[cfg(feature = "with-chrono")]
+    mod chrono_tests {
+        use chrono::DateTime;
+        use chrono::FixedOffset;
+        use chrono::Local;
+        use chrono::NaiveDate;
+        use chrono::NaiveDateTime;
+        use chrono::NaiveTime;
+        use chrono::Utc;
+
+        use super::*;
+
+        fn text(s: &str) -> Value {
+            Value::Text(s.to_string())
+        }
+
+        #[test]
+        fn test_naive_datetime_keeps_fractional_seconds() {
+            let ndt = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap().and_hms_milli_opt(12, 0, 0, 250).unwrap();
+            assert_eq!(ndt.into_value(), text("2024-01-31 12:00:00.250"));
+            assert_eq!(NaiveDateTime::from_value(ndt.into_value()).unwrap(), ndt);
+
+            let whole = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap().and_hms_opt(12, 0, 0).unwrap();
+            assert_eq!(whole.into_value(), text("2024-01-31 12:00:00"));
+        }
+
+        #[test]
+        fn test_datetime_parses_iso_and_rfc3339() {
+            let expected = "2024-01-31T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+            for stored in
+                ["2024-01-31 10:00:00", "2024-01-31T10:00:00", "2024-01-31T10:00:00Z", "2024-01-31T12:00:00+02:00"]
+            {
+                assert_eq!(DateTime::<Utc>::from_value(text(stored)).unwrap(), expected, "{}", stored);
+            }
+            assert!(DateTime::<Utc>::from_value(text("yesterday")).is_err());
+        }
+
+        #[test]
+        fn test_datetime_with_offset_round_trips() {
+            let dt = DateTime::parse_from_rfc3339("2024-01-31T12:00:00.5+02:00").unwrap();
+            assert_eq!(dt.into_value(), text("2024-01-31T12:00:00.500+02:00"));
+            let parsed = DateTime::<FixedOffset>::from_value(dt.into_value()).unwrap();
+            assert_eq!(parsed, dt);
+            assert_eq!(parsed.offset(), dt.offset());
+
+            let local = dt.with_timezone(&Local);
+            assert_eq!(DateTime::<Local>::from_value(local.into_value()).unwrap(), local);
+        }
+
+        #[test]
+        fn test_naive_time_and_date() {
+            let time = NaiveTime::from_hms_micro_opt(8, 30, 0, 1).unwrap();
+            assert_eq!(NaiveTime::from_value(time.into_value()).unwrap(), time);
+            assert_eq!(NaiveTime::from_value(text("08:30:00")).unwrap(), NaiveTime::from_hms_opt(8, 30, 0).unwrap());
+
+            let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+            assert_eq!(NaiveDate::from_value(date.into_value()).unwrap(), date);
+            assert!(NaiveDate::from_value(Value::Integer(1)).is_err());
+        }
+    }
+
+    #[cfg(feature = "with-time")]
+    mod time_tests {
+        use time::Date;
+        use time::Month;
+        use time::OffsetDateTime;
+        use time::PrimitiveDateTime;
+        use time::Time;
+        use time::UtcOffset;
+
+        use super::*;
+
+        fn text(s: &str) -> Value {
+            Value::Text(s.to_string())
+        }
+
+        fn noon() -> PrimitiveDateTime {
+            PrimitiveDateTime::new(
+                Date::from_calendar_date(2024, Month::January, 31).unwrap(),
+                Time::from_hms(12, 0, 0).unwrap(),
+            )
+        }
+
+        #[test]
+        fn test_primitive_datetime_round_trip() {
+            assert_eq!(noon().into_value(), text("2024-01-31 12:00:00"));
+            assert_eq!(PrimitiveDateTime::from_value(noon().into_value()).unwrap(), noon());
+
+            let precise = noon().replace_millisecond(250).unwrap();
+            assert_eq!(precise.into_value(), text("2024-01-31 12:00:00.25"));
+            assert_eq!(PrimitiveDateTime::from_value(precise.into_value()).unwrap(), precise);
+            assert_eq!(PrimitiveDateTime::from_value(text("2024-01-31T12:00:00")).unwrap(), noon());
+        }
+
+        #[test]
+        fn test_offset_datetime_round_trip() {
+            let dt = noon().assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+            assert_eq!(dt.into_value(), text("2024-01-31T12:00:00+02:00"));
+
+            let parsed = OffsetDateTime::from_value(dt.into_value()).unwrap();
+            assert_eq!(parsed, dt);
+            assert_eq!(parsed.offset(), dt.offset());
+
+            assert_eq!(OffsetDateTime::from_value(text("2024-01-31 12:00:00")).unwrap(), noon().assume_utc());
+            assert_eq!(OffsetDateTime::from_value(text("2024-01-31T12:00:00Z")).unwrap(), noon().assume_utc());
+            assert!(OffsetDateTime::from_value(text("noon")).is_err());
+        }
+
+        #[test]
+        fn test_date_and_time() {
+            let date = Date::from_calendar_date(2024, Month::February, 29).unwrap();
+            assert_eq!(date.into_value(), text("2024-02-29"));
+            assert_eq!(Date::from_value(date.into_value()).unwrap(), date);
+
+            let time = Time::from_hms_micro(8, 30, 0, 1).unwrap();
+            assert_eq!(Time::from_value(time.into_value()).unwrap(), time);
+            assert!(matches!(Time::from_value(Value::Null), Err(Error::UnexpectedNull)));
+        }
+    }
+
+    #[cfg(feature = "with-decimal")]
+    mod decimal_tests {
+        use std::str::FromStr;
+
+        use rust_decimal::Decimal;
+
+        use super::*;
+
+        #[test]
+        fn test_decimal_round_trip_is_exact() {
+            let amount = Decimal::from_str("12345678901234567.89").unwrap();
+            assert_eq!(amount.into_value(), Value::Text("12345678901234567.89".to_string()));
+            assert_eq!(Decimal::from_value(amount.into_value()).unwrap(), amount);
+        }
+
+        #[test]
+        fn test_decimal_from_numeric_values() {
+            assert_eq!(Decimal::from_value(Value::Integer(3)).unwrap(), Decimal::from(3));
+            assert_eq!(Decimal::from_value(Value::Real(0.5)).unwrap(), Decimal::from_str("0.5").unwrap());
+            assert!(Decimal::from_value(Value::Text("abc".to_string())).is_err());
+            assert!(matches!(Decimal::from_value(Value::Null), Err(Error::UnexpectedNull)));
+        }
+
+        #[test]
+        fn test_decimal_into_scaled_value() {
+            assert_eq!(Decimal::from_str("1.5").unwrap().into_scaled_value(2), Value::Text("1.50".to_string()));
+            assert_eq!(Decimal::from_str("2.345").unwrap().into_scaled_value(2), Value::Text("2.35".to_string()));
+            assert_eq!(None::<Decimal>.into_scaled_value(2), Value::Null);
+        }
+    }
+
     #[cfg(feature = "with-arrays")]
     mod vec_tests {
         use super::*;
@@ -0,0 +1,2977 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use tursorm::TableDeleteExt;
+use tursorm::TableSelectExt;
+use tursorm::TableUpsertExt;
+use tursorm::backup::RestoreMode;
+use tursorm::backup::TableBackup;
+use tursorm::cached::CacheSource;
+use tursorm::cached::CachedTableTrait;
+#[cfg(any(feature = "with-json", feature = "with-arrays"))]
+use tursorm::migration::DatabaseSchema;
+use tursorm::migration::IndexOrigin;
+use tursorm::migration::MigrationOptions;
+use tursorm::migration::Migrator;
+use tursorm::migration::SchemaChange;
+#[cfg(any(feature = "with-json", feature = "with-arrays"))]
+use tursorm::migration::SchemaDiff;
+#[cfg(any(feature = "with-json", feature = "with-arrays"))]
+use tursorm::migration::SchemaDrift;
+use tursorm::migration::SeedTrait;
+use tursorm::migration::TableSchema;
+use tursorm::notify::Notify;
+use tursorm::prelude::*;
+use tursorm::query_cache::LruCacheStore;
+use tursorm::test_utils::MockConnection;
+use tursorm::test_utils::TestDb;
+
+#[ctor::ctor]
+fn init() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_test_writer()
+        .try_init();
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct User {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:    i64,
+    pub name:  String,
+    #[tursorm(default = "'active'")]
+    pub state: String,
+    #[tursorm(unique)]
+    pub email: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Post {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:      i64,
+    #[tursorm(foreign_key, references = "user.id")]
+    pub user_id: i64,
+    pub title:   String,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(table_name = "user", database = "archive")]
+pub struct ArchivedUser {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:    i64,
+    pub name:  String,
+    pub state: String,
+    pub email: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(table_name = "people_v1")]
+pub struct LegacyPerson {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:   i64,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(table_name = "people", renamed_from = "people_v1", default_limit = 2)]
+pub struct Person {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:        i64,
+    #[tursorm(renamed_from = "name")]
+    pub full_name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Product {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:       i64,
+    pub name:     String,
+    pub quantity: i64,
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+pub struct UserSummary {
+    pub id:    i64,
+    pub email: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tags(pub Vec<String>);
+
+mod comma_separated {
+    use tursorm::prelude::*;
+
+    use super::Tags;
+
+    pub fn to_value(tags: &Tags) -> Value {
+        Value::Text(tags.0.join(","))
+    }
+
+    pub fn from_value(value: Value) -> Result<Tags> {
+        let text = String::from_value(value)?;
+        Ok(Tags(text.split(',').filter(|tag| !tag.is_empty()).map(str::to_string).collect()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Document {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:   i64,
+    #[tursorm(with = "comma_separated")]
+    pub tags: Tags,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Account {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:          i64,
+    #[tursorm(with = "tursorm::codec::u64_blob")]
+    pub external_id: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Job {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:     i64,
+    pub worker: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(serde)]
+pub struct Contact {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:       i64,
+    #[tursorm(column_name = "full_name")]
+    pub name:     String,
+    pub phone:    Option<String>,
+    #[tursorm(skip)]
+    pub selected: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Patient {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:    i64,
+    pub name:  String,
+    #[tursorm(encrypted)]
+    pub ssn:   String,
+    #[tursorm(encrypted)]
+    pub notes: Option<String>,
+}
+
+/// Reverses the bytes; enough to tell stored ciphertext from plaintext.
+#[derive(Debug)]
+struct ReversingEncryptor;
+
+impl tursorm::encryption::Encryptor for ReversingEncryptor {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        Ok(plaintext.iter().rev().copied().collect())
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.encrypt(ciphertext)
+    }
+}
+
+static NEXT_TICKET: AtomicU64 = AtomicU64::new(1);
+
+pub struct TicketCodes;
+
+impl IdGenerator for TicketCodes {
+    type Id = String;
+
+    fn generate() -> String {
+        format!("T-{}", NEXT_TICKET.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Ticket {
+    #[tursorm(primary_key, default_fn = "TicketCodes")]
+    pub code:  String,
+    pub title: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(discriminator = "kind")]
+pub struct Event {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:   i64,
+    pub kind: String,
+    pub x:    Option<i64>,
+    pub url:  Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+#[tursorm(from_row = "by_name")]
+pub struct Click {
+    pub id: i64,
+    pub x:  i64,
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+#[tursorm(from_row = "by_name")]
+pub struct PageView {
+    pub id:  i64,
+    pub url: String,
+}
+
+impl From<Click> for EventChangeSet {
+    fn from(click: Click) -> Self {
+        EventChangeSet { x: set(Some(click.x)), ..Default::default() }
+    }
+}
+
+impl From<PageView> for EventChangeSet {
+    fn from(view: PageView) -> Self {
+        EventChangeSet { url: set(Some(view.url)), ..Default::default() }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, TableEnum)]
+#[tursorm(table = "EventTable")]
+pub enum EventKind {
+    Click(Click),
+    #[tursorm(discriminator = "view")]
+    PageView(PageView),
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(many_to_many(name = "roles", related = "RoleTable", through = "member_roles"))]
+pub struct Member {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:   i64,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Role {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:   i64,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(tree = "parent_id")]
+pub struct Category {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:        i64,
+    #[tursorm(foreign_key, references = "category.id")]
+    pub parent_id: Option<i64>,
+    pub name:      String,
+}
+
+async fn connect_with(builder: Builder) -> Connection {
+    let conn = builder.build().await.unwrap().connect().unwrap();
+    Migrator::migrate::<UserTable>(&conn).await.unwrap();
+    conn
+}
+
+async fn connect() -> Connection {
+    connect_with(Builder::new_local(":memory:")).await
+}
+
+fn new_user(name: &str) -> UserChangeSet {
+    UserChangeSet { name: set(name.to_string()), ..Default::default() }
+}
+
+#[derive(Default)]
+struct RecordingObserver {
+    queries: Mutex<Vec<(StatementKind, String, u64)>>,
+}
+
+impl MetricsObserver for RecordingObserver {
+    fn on_query_end(&self, event: &QueryEvent<'_>, outcome: &QueryOutcome) {
+        self.queries.lock().unwrap().push((event.kind, event.table_name.to_string(), outcome.rows));
+    }
+}
+
+#[tokio::test]
+async fn test_insert_returning_reads_back_full_row() {
+    let conn = connect().await;
+    assert_eq!(conn.insert_strategy(), InsertStrategy::Returning);
+
+    let first = new_user("alice").insert(&conn).await.unwrap();
+    let second = new_user("bob").insert(&conn).await.unwrap();
+
+    assert_eq!(first, User { id: 1, name: "alice".to_string(), state: "active".to_string(), email: None });
+    assert_eq!(second.id, 2);
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_insert_last_insert_rowid_strategy() {
+    let conn = connect_with(Builder::new_local(":memory:").insert_strategy(InsertStrategy::LastInsertRowid)).await;
+
+    let user = new_user("carol").insert(&conn).await.unwrap();
+
+    assert_eq!(user.name, "carol");
+    assert_eq!(user.state, "active");
+    assert!(conn.is_autocommit().unwrap());
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_insert_last_insert_rowid_joins_open_transaction() {
+    let conn = connect_with(Builder::new_local(":memory:").insert_strategy(InsertStrategy::LastInsertRowid)).await;
+
+    conn.execute("BEGIN", ()).await.unwrap();
+    new_user("dave").insert(&conn).await.unwrap();
+    assert!(!conn.is_autocommit().unwrap());
+    conn.execute("ROLLBACK", ()).await.unwrap();
+
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_update_returning_persists() {
+    let conn = connect().await;
+    let user = new_user("erin").insert(&conn).await.unwrap();
+
+    let updated = Update::<UserTable>::new(UserChangeSet {
+        id: set(user.id),
+        state: set("banned".to_string()),
+        ..Default::default()
+    })
+    .exec_with_returning(&conn)
+    .await
+    .unwrap();
+    assert_eq!(updated.state, "banned");
+
+    let reloaded = UserTable::find_by_id(user.id).one(&conn).await.unwrap().unwrap();
+    assert_eq!(reloaded.state, "banned");
+}
+
+#[tokio::test]
+async fn test_metrics_observer_sees_builder_queries() {
+    let observer = Arc::new(RecordingObserver::default());
+    let conn = connect_with(Builder::new_local(":memory:").with_metrics_observer(observer.clone())).await;
+
+    new_user("frank").insert(&conn).await.unwrap();
+    new_user("grace").insert_exec(&conn).await.unwrap();
+    UserTable::find().all(&conn).await.unwrap();
+    UserTable::truncate().exec(&conn).await.unwrap();
+
+    let queries = observer.queries.lock().unwrap();
+    assert_eq!(
+        *queries,
+        vec![
+            (StatementKind::Insert, "user".to_string(), 1),
+            (StatementKind::Insert, "user".to_string(), 1),
+            (StatementKind::Select, "user".to_string(), 2),
+            (StatementKind::Delete, "user".to_string(), 2),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_on_change_sees_committed_writes() {
+    let conn = connect().await;
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let seen = changes.clone();
+    conn.on_change(move |table, op, rowid| seen.lock().unwrap().push((table.to_string(), op, rowid))).await.unwrap();
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let seen = records.clone();
+    conn.on_record_change::<UserTable>(move |op, _, user| seen.lock().unwrap().push((op, user.map(|u| u.name))))
+        .await
+        .unwrap();
+    fn take<T>(log: &Mutex<Vec<T>>) -> Vec<T> {
+        std::mem::take(&mut *log.lock().unwrap())
+    }
+
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    assert_eq!(alice.name, "alice");
+    assert_eq!(take(&changes), vec![("user".to_string(), ChangeOp::Insert, alice.id)]);
+    assert_eq!(take(&records), vec![(ChangeOp::Insert, Some("alice".to_string()))]);
+
+    UserChangeSet { id: set(alice.id), name: set("alicia".to_string()), ..Default::default() }
+        .update(&conn)
+        .await
+        .unwrap();
+    assert_eq!(take(&records), vec![(ChangeOp::Update, Some("alicia".to_string()))]);
+    assert_eq!(take(&changes), vec![("user".to_string(), ChangeOp::Update, alice.id)]);
+
+    assert!(conn.begin_if_autocommit().await.unwrap());
+    new_user("bob").insert_exec(&conn).await.unwrap();
+    assert!(take(&changes).is_empty());
+    conn.end_transaction(false).await.unwrap();
+    assert!(take(&changes).is_empty());
+
+    assert!(conn.begin_if_autocommit().await.unwrap());
+    let bob = new_user("bob").insert(&conn).await.unwrap();
+    assert!(take(&records).is_empty());
+    conn.end_transaction(true).await.unwrap();
+    assert_eq!(take(&records), vec![(ChangeOp::Insert, Some("bob".to_string()))]);
+    assert_eq!(take(&changes), vec![("user".to_string(), ChangeOp::Insert, bob.id)]);
+
+    UserTable::delete_many_by_ids(vec![alice.id]).exec(&conn).await.unwrap();
+    assert_eq!(take(&changes), vec![("user".to_string(), ChangeOp::Delete, alice.id)]);
+    assert_eq!(take(&records), vec![(ChangeOp::Delete, None)]);
+
+    let mut statement = conn.prepare("INSERT INTO user (name) VALUES ('carol')").await.unwrap();
+    statement.execute(()).await.unwrap();
+    assert!(take(&changes).is_empty());
+    assert_eq!(conn.poll_changes().await.unwrap(), 1);
+    assert_eq!(take(&records), vec![(ChangeOp::Insert, Some("carol".to_string()))]);
+    assert_eq!(conn.poll_changes().await.unwrap(), 0);
+}
+
+#[derive(Clone, Default)]
+struct CountingSync {
+    syncs: Arc<AtomicU64>,
+}
+
+#[async_trait::async_trait]
+impl ReplicaSync for CountingSync {
+    async fn sync(&self) -> Result<()> {
+        self.syncs.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_replica_freshness_syncs_before_reads() {
+    let sync = CountingSync::default();
+    let conn = connect_with(Builder::new_local(":memory:").replica_sync(sync.clone())).await;
+    let syncs = || sync.syncs.load(Ordering::SeqCst);
+
+    new_user("alice").insert(&conn).await.unwrap();
+    let status = conn.sync_status().unwrap();
+    assert!(status.pending_writes);
+    assert_eq!(status.last_synced_at, None);
+
+    UserTable::find().all(&conn).await.unwrap();
+    assert_eq!(syncs(), 0);
+
+    assert_eq!(UserTable::find().read_your_writes().all(&conn).await.unwrap().len(), 1);
+    assert_eq!(syncs(), 1);
+    UserTable::find().read_your_writes().count(&conn).await.unwrap();
+    assert_eq!(syncs(), 1);
+
+    let status = conn.sync_status().unwrap();
+    assert!(!status.pending_writes);
+    assert!(status.last_synced_at.is_some());
+    assert_eq!(status.syncs, 1);
+
+    UserTable::find().max_staleness(Duration::from_secs(3600)).one(&conn).await.unwrap();
+    assert_eq!(syncs(), 1);
+    UserTable::find().max_staleness(Duration::ZERO).one(&conn).await.unwrap();
+    assert_eq!(syncs(), 2);
+
+    new_user("bob").insert(&conn).await.unwrap();
+    let select = UserTable::find().read_your_writes().columns(vec![UserColumn::Id, UserColumn::Email]);
+    select.into_model::<UserSummary>().all(&conn).await.unwrap();
+    assert_eq!(syncs(), 3);
+
+    conn.sync().await.unwrap();
+    assert_eq!(conn.sync_status().unwrap().syncs, 4);
+}
+
+#[tokio::test]
+async fn test_replica_freshness_without_replica() {
+    let conn = connect().await;
+    assert_eq!(conn.sync_status(), None);
+    assert!(conn.sync().await.is_err());
+    assert_eq!(UserTable::find().read_your_writes().count(&conn).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_cached_select_invalidated_by_writes() {
+    let conn = connect().await.with_query_cache(LruCacheStore::new(16));
+    Migrator::migrate::<PostTable>(&conn).await.unwrap();
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    PostChangeSet { user_id: set(alice.id), title: set("hello".to_string()), ..Default::default() }
+        .insert(&conn)
+        .await
+        .unwrap();
+
+    let users = || UserTable::find().cached(Duration::from_secs(60));
+    assert_eq!(users().count(&conn).await.unwrap(), 1);
+
+    // Raw SQL bypasses invalidation, so the cached count stays.
+    conn.execute("INSERT INTO user (name) VALUES ('raw')", ()).await.unwrap();
+    assert_eq!(users().count(&conn).await.unwrap(), 1);
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 2);
+
+    new_user("bob").insert(&conn).await.unwrap();
+    assert_eq!(users().count(&conn).await.unwrap(), 3);
+
+    let titles_by = |name: &str| {
+        PostTable::find()
+            .alias("p")
+            .join::<UserTable>("u", Condition::eq_column(PostColumn::UserId.of("p"), UserColumn::Id.of("u")))
+            .filter(Condition::eq(UserColumn::Name.of("u"), name))
+            .cached(Duration::from_secs(60))
+    };
+    assert_eq!(titles_by("alice").count(&conn).await.unwrap(), 1);
+    Update::<UserTable>::many()
+        .set(UserColumn::Name, "alicia")
+        .filter(Condition::eq(UserColumn::Id, alice.id))
+        .exec(&conn)
+        .await
+        .unwrap();
+    assert_eq!(titles_by("alice").count(&conn).await.unwrap(), 0);
+
+    // Rows a select cached inside a rolled back transaction are dropped with it.
+    assert!(conn.begin_if_autocommit().await.unwrap());
+    new_user("ghost").insert(&conn).await.unwrap();
+    assert_eq!(users().count(&conn).await.unwrap(), 4);
+    conn.end_transaction(false).await.unwrap();
+    assert_eq!(users().count(&conn).await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn test_table_backup_round_trip() {
+    let source = connect().await;
+    new_user("o'brien").insert(&source).await.unwrap();
+    UserChangeSet { name: set("line\nbreak".to_string()), state: set("idle".to_string()), ..Default::default() }
+        .insert(&source)
+        .await
+        .unwrap();
+
+    let mut dump = Vec::new();
+    assert_eq!(TableBackup::dump::<UserTable>(&source, &mut dump).await.unwrap(), 2);
+    let dump = String::from_utf8(dump).unwrap();
+    assert!(dump.starts_with("-- tursorm table backup: user (id, name, state, email)\n"));
+    assert!(dump.contains("INSERT INTO user (id, name, state, email) VALUES (1, 'o''brien', 'active', NULL);"));
+
+    let target = connect().await;
+    new_user("stale").insert(&target).await.unwrap();
+    new_user("extra").insert(&target).await.unwrap();
+    new_user("more").insert(&target).await.unwrap();
+
+    let restored = TableBackup::restore::<UserTable>(&target, dump.as_bytes(), RestoreMode::Replace).await.unwrap();
+    assert_eq!(restored, 2);
+    assert_eq!(UserTable::find().all(&target).await.unwrap(), UserTable::find().all(&source).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_table_backup_merge() {
+    let source = connect().await;
+    new_user("alice").insert(&source).await.unwrap();
+    let mut dump = Vec::new();
+    TableBackup::dump::<UserTable>(&source, &mut dump).await.unwrap();
+
+    let target = connect().await;
+    new_user("someone else").insert(&target).await.unwrap();
+    new_user("bob").insert(&target).await.unwrap();
+
+    TableBackup::restore::<UserTable>(&target, dump.as_slice(), RestoreMode::Merge).await.unwrap();
+
+    let names: Vec<String> = UserTable::find().all(&target).await.unwrap().into_iter().map(|u| u.name).collect();
+    assert_eq!(names, vec!["alice".to_string(), "bob".to_string()]);
+}
+
+#[tokio::test]
+async fn test_table_backup_rejects_foreign_dump() {
+    let conn = connect().await;
+
+    let wrong_table = "-- tursorm table backup: post (id, title)\n";
+    assert!(TableBackup::restore::<UserTable>(&conn, wrong_table.as_bytes(), RestoreMode::Replace).await.is_err());
+
+    let injected = "-- tursorm table backup: user (id, name, state, email)\nDROP TABLE user;\n";
+    assert!(TableBackup::restore::<UserTable>(&conn, injected.as_bytes(), RestoreMode::Replace).await.is_err());
+
+    let truncated = "-- tursorm table backup: user (id, name, state, email)\nINSERT INTO user (id, name, state, email) VALUES (1, 'a');\n";
+    assert!(TableBackup::restore::<UserTable>(&conn, truncated.as_bytes(), RestoreMode::Replace).await.is_err());
+    assert!(conn.is_autocommit().unwrap());
+}
+
+#[tokio::test]
+async fn test_find_by_unique_column() {
+    let conn = connect().await;
+    let change_set = UserChangeSet { email: set(Some("a@example.com".to_string())), ..new_user("alice") };
+    let alice = change_set.insert(&conn).await.unwrap();
+    new_user("bob").insert(&conn).await.unwrap();
+
+    let found = UserTable::find_by_email("a@example.com").one(&conn).await.unwrap();
+    assert_eq!(found, Some(alice));
+    assert!(UserTable::find_by_email("missing@example.com").one(&conn).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_find_by_ids_keeps_input_order() {
+    let conn = connect().await;
+    let mut users = Vec::new();
+    for name in ["alice", "bob", "carol"] {
+        users.push(new_user(name).insert(&conn).await.unwrap());
+    }
+
+    let found = UserTable::find_by_ids(&conn, vec![3, 1, 99, 3]).await.unwrap();
+    assert_eq!(found, vec![users[2].clone(), users[0].clone(), users[2].clone()]);
+    assert!(UserTable::find_by_ids(&conn, Vec::<i64>::new()).await.unwrap().is_empty());
+
+    let by_id = UserTable::find_map_by_ids(&conn, vec![2, 99, 1]).await.unwrap();
+    assert_eq!(by_id.len(), 2);
+    assert_eq!(by_id[&2], users[1]);
+    assert_eq!(by_id[&1], users[0]);
+}
+
+#[tokio::test]
+async fn test_oversized_in_list_runs_in_chunks() {
+    let conn = connect_with(Builder::new_local(":memory:").max_parameters(3)).await;
+    for i in 0..8 {
+        new_user(&format!("user-{}", i)).insert(&conn).await.unwrap();
+    }
+
+    let mut names: Vec<String> = UserTable::find()
+        .filter(Condition::is_in(UserColumn::Id, vec![1, 2, 3, 4, 5, 6, 6, 99]))
+        .filter(Condition::ne(UserColumn::Name, "user-0"))
+        .all(&conn)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|user| user.name)
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["user-1", "user-2", "user-3", "user-4", "user-5"]);
+
+    let found = UserTable::find_by_ids(&conn, vec![8, 2, 5, 7, 1]).await.unwrap();
+    assert_eq!(found.iter().map(|user| user.id).collect::<Vec<_>>(), vec![8, 2, 5, 7, 1]);
+
+    let mock = MockConnection::new().max_parameters(4);
+    UserTable::find().filter(Condition::is_in(UserColumn::Id, (1..=10).collect())).all(&mock).await.unwrap();
+    assert_eq!(mock.statements().iter().map(|statement| statement.params.len()).collect::<Vec<_>>(), vec![4, 4, 2]);
+}
+
+#[tokio::test]
+async fn test_oversized_in_list_keeps_order_limit_and_counts() {
+    let conn = connect_with(Builder::new_local(":memory:").max_parameters(3)).await;
+    for i in 0..8 {
+        new_user(&format!("user-{}", i)).insert(&conn).await.unwrap();
+    }
+    let all_ids = || Condition::is_in(UserColumn::Id, (1..=8).collect());
+    let names = |users: Vec<User>| users.into_iter().map(|user| user.name).collect::<Vec<_>>();
+
+    let page = UserTable::find().filter(all_ids()).order_by_desc(UserColumn::Name).limit(3).offset(2);
+    assert_eq!(names(page.all(&conn).await.unwrap()), vec!["user-5", "user-4", "user-3"]);
+    assert!(conn.is_autocommit().unwrap());
+
+    let first = UserTable::find().filter(all_ids()).order_by_desc(UserColumn::Id).one(&conn).await.unwrap();
+    assert_eq!(first.unwrap().name, "user-7");
+
+    let page = UserTable::find().filter(all_ids()).cursor_by(UserColumn::Id).first(5).page(&conn).await.unwrap();
+    assert_eq!(page.items.iter().map(|user| user.id).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    let rest = UserTable::find()
+        .filter(all_ids())
+        .cursor_by(UserColumn::Id)
+        .after(page.next_cursor.unwrap())
+        .first(5)
+        .page(&conn)
+        .await
+        .unwrap();
+    assert_eq!(rest.items.iter().map(|user| user.id).collect::<Vec<_>>(), vec![6, 7, 8]);
+    assert!(rest.next_cursor.is_none());
+
+    assert!(matches!(UserTable::find().filter(all_ids()).order_random().all(&conn).await, Err(Error::Query(_))));
+    assert!(matches!(UserTable::find().filter(all_ids()).sample(2).all(&conn).await, Err(Error::Query(_))));
+    assert!(matches!(
+        UserTable::find().filter(all_ids()).count_distinct(&conn, UserColumn::Name).await,
+        Err(Error::Query(_))
+    ));
+
+    let mock = MockConnection::new().max_parameters(3);
+    mock.push_rows(vec![vec![Value::Integer(3)]])
+        .push_rows(vec![vec![Value::Integer(3)]])
+        .push_rows(vec![vec![Value::Integer(1)]]);
+    assert_eq!(UserTable::find().filter(all_ids()).count(&mock).await.unwrap(), 7);
+    assert_eq!(
+        mock.sql(),
+        vec![
+            "SELECT COUNT(*) FROM user WHERE (id IN (?, ?, ?))",
+            "SELECT COUNT(*) FROM user WHERE (id IN (?, ?, ?))",
+            "SELECT COUNT(*) FROM user WHERE (id IN (?, ?))",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_insert_many_splits_by_parameter_limit() {
+    let conn = connect_with(Builder::new_local(":memory:").max_parameters(2)).await;
+    let users: Vec<UserChangeSet> = (0..5).map(|i| new_user(&format!("user-{}", i))).collect();
+    assert_eq!(InsertMany::<UserTable>::new(users.clone()).exec(&conn).await.unwrap(), 5);
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 5);
+
+    let mock = MockConnection::new().max_parameters(2);
+    InsertMany::<UserTable>::new(users).exec(&mock).await.unwrap();
+    let inserts: Vec<usize> = mock
+        .statements()
+        .iter()
+        .filter(|statement| statement.sql.starts_with("INSERT"))
+        .map(|statement| statement.params.len())
+        .collect();
+    assert_eq!(inserts, vec![2, 2, 1]);
+}
+
+#[tokio::test]
+async fn test_ordered_limited_delete_deletes_keys_in_chunks() {
+    let mock = MockConnection::new();
+    mock.push_rows((1..=1200).map(|id| vec![Value::Integer(id)]).collect());
+    mock.push_affected(500).push_affected(500).push_affected(200);
+
+    let deleted =
+        Delete::<JobTable>::new().order_by((JobColumn::Id, Order::Asc)).limit(1200).exec(&mock).await.unwrap();
+    assert_eq!(deleted, 1200);
+
+    let deletes: Vec<usize> = mock
+        .statements()
+        .iter()
+        .filter(|statement| statement.sql.starts_with("DELETE"))
+        .map(|statement| statement.params.len())
+        .collect();
+    assert_eq!(deletes, vec![500, 500, 200]);
+}
+
+/// A [`MockConnection`] that opens a transaction for every write and can't roll it back.
+struct BrokenRollback(MockConnection);
+
+#[async_trait::async_trait]
+impl ConnectionLike for BrokenRollback {
+    async fn query_rows(&self, sql: &str, params: Vec<Value>) -> Result<QueryRows> {
+        self.0.query_rows(sql, params).await
+    }
+
+    async fn execute_statement(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+        self.0.execute_statement(sql, params).await
+    }
+
+    fn last_insert_rowid(&self) -> i64 {
+        self.0.last_insert_rowid()
+    }
+
+    async fn begin_if_autocommit(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn end_transaction(&self, commit: bool) -> Result<()> {
+        if commit { Ok(()) } else { Err(Error::Query("cannot roll back".to_string())) }
+    }
+}
+
+#[tokio::test]
+async fn test_writes_keep_their_error_when_rollback_fails() {
+    let conn = BrokenRollback(MockConnection::new());
+    conn.0.push_error("UNIQUE constraint failed: user.email");
+
+    let err = InsertMany::<UserTable>::new(vec![new_user("alice")]).exec(&conn).await.unwrap_err();
+    assert!(err.is_unique_violation(), "{:?}", err);
+
+    conn.0.push_affected(2);
+    let delete = Delete::<UserTable>::new().filter(Condition::eq(UserColumn::Name, "alice")).expect_affected(1);
+    let err = delete.exec(&conn).await.unwrap_err();
+    assert!(matches!(err, Error::UnexpectedAffectedRows { expected: 1, actual: 2 }), "{:?}", err);
+
+    conn.0.push_error("UNIQUE constraint failed: user.email");
+    let err = tursorm::loader::Loader::<UserTable>::from_iter(vec![new_user("bob")]).load(&conn).await.unwrap_err();
+    assert!(err.is_unique_violation(), "{:?}", err);
+}
+
+/// A remote database that can be switched off, as an occasionally connected app sees it.
+#[derive(Clone)]
+struct SwitchableRemote {
+    conn:   Connection,
+    online: Arc<AtomicBool>,
+}
+
+impl SwitchableRemote {
+    fn check(&self) -> Result<()> {
+        if self.online.load(Ordering::SeqCst) {
+            Ok(())
+        } else {
+            Err(std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ConnectionLike for SwitchableRemote {
+    async fn query_rows(&self, sql: &str, params: Vec<Value>) -> Result<QueryRows> {
+        self.check()?;
+        self.conn.query_rows(sql, params).await
+    }
+
+    async fn execute_statement(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+        self.check()?;
+        self.conn.execute_statement(sql, params).await
+    }
+
+    fn last_insert_rowid(&self) -> i64 {
+        self.conn.last_insert_rowid()
+    }
+
+    async fn begin_if_autocommit(&self) -> Result<bool> {
+        self.check()?;
+        ConnectionLike::begin_if_autocommit(&self.conn).await
+    }
+
+    async fn end_transaction(&self, commit: bool) -> Result<()> {
+        ConnectionLike::end_transaction(&self.conn, commit).await
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_write_queue_clones_queue_concurrently() {
+    const INSERT: &str = "INSERT INTO notes (body) VALUES (?)";
+    let body = |text: String| vec![Value::Text(text)];
+
+    let remote = connect().await;
+    remote.execute("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT NOT NULL)", ()).await.unwrap();
+    let remote = SwitchableRemote { conn: remote, online: Arc::new(AtomicBool::new(false)) };
+    let queue = WriteQueue::open(connect().await, remote.clone()).await.unwrap();
+
+    // One clone rolls back an offline transaction and commits another while the other queues single writes.
+    let batches = tokio::spawn({
+        let queue = queue.clone();
+        async move {
+            assert!(queue.begin_if_autocommit().await.unwrap());
+            for i in 0..10 {
+                queue.execute_statement(INSERT, body(format!("rolled back {}", i))).await.unwrap();
+                tokio::task::yield_now().await;
+            }
+            queue.end_transaction(false).await.unwrap();
+
+            assert!(queue.begin_if_autocommit().await.unwrap());
+            for i in 0..10 {
+                assert_eq!(queue.execute_statement(INSERT, body(format!("batch {}", i))).await.unwrap(), 0);
+                tokio::task::yield_now().await;
+            }
+            queue.end_transaction(true).await.unwrap_err()
+        }
+    });
+    let singles = tokio::spawn({
+        let queue = queue.clone();
+        async move {
+            for i in 0..10 {
+                let error = queue.execute_statement(INSERT, body(format!("single {}", i))).await.unwrap_err();
+                assert!(error.is_queued(), "{:?}", error);
+                tokio::task::yield_now().await;
+            }
+        }
+    });
+    assert!(batches.await.unwrap().is_queued());
+    singles.await.unwrap();
+
+    let pending = queue.pending().await.unwrap();
+    let texts = |prefix: &str| {
+        pending
+            .iter()
+            .filter(|write| matches!(&write.params[..], [Value::Text(text)] if text.starts_with(prefix)))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(pending.len(), 20);
+    assert!(pending.iter().all(|write| write.sql == INSERT && write.params.len() == 1));
+    assert!(texts("rolled back").is_empty());
+    let batch = texts("batch ");
+    assert_eq!(
+        batch.iter().map(|write| write.params.clone()).collect::<Vec<_>>(),
+        (0..10).map(|i| body(format!("batch {}", i))).collect::<Vec<_>>()
+    );
+    assert!(batch.iter().all(|write| write.batch.is_some() && write.batch == batch[0].batch));
+    assert!(texts("single ").iter().all(|write| write.batch.is_none()));
+
+    remote.online.store(true, Ordering::SeqCst);
+    let report = queue.replay().await.unwrap();
+    assert_eq!((report.replayed, report.pending), (20, 0));
+    let rows = remote.conn.query_dynamic("SELECT body FROM notes ORDER BY id", Vec::new()).await.unwrap();
+    let mut bodies = rows.iter().map(|row| row.get_index::<String>(0).unwrap()).collect::<Vec<_>>();
+    bodies.sort();
+    let mut expected = (0..10).flat_map(|i| [format!("batch {}", i), format!("single {}", i)]).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(bodies, expected);
+}
+
+#[tokio::test]
+async fn test_explain_query_plan() {
+    let conn = connect().await;
+
+    let by_email = UserTable::find_by_email("a@example.com").explain_query_plan(&conn).await.unwrap();
+    assert!(by_email.iter().any(|step| step.uses_index()), "{:?}", by_email);
+
+    let by_name = UserTable::find().filter(Condition::eq(UserColumn::Name, "alice")).order_by_asc(UserColumn::Name);
+    let steps = by_name.explain_query_plan(&conn).await.unwrap();
+    assert!(steps.iter().all(|step| !step.uses_index()));
+    assert!(by_name.explain(&conn).await.unwrap().starts_with("QUERY PLAN\n|--SCAN user"));
+}
+
+#[tokio::test]
+async fn test_statement_comments() {
+    let conn = MockConnection::new();
+    UserTable::find().comment("users:list").all(&conn).await.unwrap();
+    UserTable::find().comment("users:count").count(&conn).await.unwrap();
+    Insert::<UserTable>::new(new_user("alice")).comment("users:create").exec(&conn).await.unwrap();
+    Update::<UserTable>::many().set(UserColumn::State, "gone").comment("users:retire */").exec(&conn).await.unwrap();
+    Delete::<UserTable>::new().comment("users:purge").exec(&conn).await.unwrap();
+
+    assert_eq!(
+        conn.sql(),
+        vec![
+            "/* users:list */ SELECT id, name, state, email FROM user",
+            "/* users:count */ SELECT COUNT(*) FROM user",
+            "/* users:create */ INSERT INTO user (name) VALUES (?)",
+            "/* users:retire * / */ UPDATE user SET state = ?",
+            "/* users:purge */ DELETE FROM user",
+        ]
+    );
+
+    let conn = connect_with(Builder::new_local(":memory:").application_name("checkout")).await;
+    assert_eq!(conn.application_name(), Some("checkout"));
+    new_user("alice").insert(&conn).await.unwrap();
+    assert_eq!(UserTable::find().comment("users:count").count(&conn).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_in_conditions_with_empty_lists_and_subqueries() {
+    let conn = connect().await;
+    Migrator::migrate::<PostTable>(&conn).await.unwrap();
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    new_user("bob").insert(&conn).await.unwrap();
+    PostChangeSet { user_id: set(alice.id), title: set("hello".to_string()), ..Default::default() }
+        .insert(&conn)
+        .await
+        .unwrap();
+
+    let none = UserTable::find().filter(Condition::is_in(UserColumn::Id, Vec::<i64>::new()));
+    assert_eq!(none.count(&conn).await.unwrap(), 0);
+    let all = UserTable::find().filter(Condition::not_in(UserColumn::Id, Vec::<i64>::new()));
+    assert_eq!(all.count(&conn).await.unwrap(), 2);
+
+    let authors = || {
+        Select::<PostTable>::new().columns(vec![PostColumn::UserId]).filter(Condition::like(PostColumn::Title, "h%"))
+    };
+    let with_posts = UserTable::find().filter(Condition::is_in_subquery(UserColumn::Id, authors())).all(&conn).await;
+    assert_eq!(with_posts.unwrap().iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), vec!["alice"]);
+    let without_posts =
+        UserTable::find().filter(Condition::not_in_subquery(UserColumn::Id, authors())).all(&conn).await;
+    assert_eq!(without_posts.unwrap().iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), vec!["bob"]);
+}
+
+#[tokio::test]
+async fn test_tuple_comparison_pages_through_composite_key() {
+    let conn = connect().await;
+    for (name, state) in [("a", "active"), ("b", "idle"), ("c", "active"), ("d", "idle")] {
+        UserChangeSet { state: set(state.to_string()), ..new_user(name) }.insert(&conn).await.unwrap();
+    }
+
+    let after = |state: &str, id: i64| {
+        UserTable::find()
+            .filter(Condition::tuple_gt((UserColumn::State, UserColumn::Id), (state.to_string(), id)))
+            .order_by_asc(UserColumn::State)
+            .order_by_asc(UserColumn::Id)
+            .limit(2)
+    };
+
+    let first = after("", 0).all(&conn).await.unwrap();
+    assert_eq!(first.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), vec!["a", "c"]);
+    let last = first.last().unwrap();
+    let second = after(&last.state, last.id).all(&conn).await.unwrap();
+    assert_eq!(second.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), vec!["b", "d"]);
+}
+
+#[tokio::test]
+async fn test_get_or_insert() {
+    let conn = connect().await;
+    let provision = |name: &str| UserChangeSet { email: set(Some("shared@example.com".to_string())), ..new_user(name) };
+
+    let created = provision("first").get_or_insert(&conn).await.unwrap();
+    assert_eq!(created.name, "first");
+
+    let existing = provision("second").get_or_insert(&conn).await.unwrap();
+    assert_eq!(existing, created);
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 1);
+    assert!(conn.is_autocommit().unwrap());
+
+    let by_id = UserChangeSet { id: set(created.id), ..new_user("ignored") }.get_or_insert(&conn).await.unwrap();
+    assert_eq!(by_id, created);
+
+    assert!(new_user("no key").get_or_insert(&conn).await.is_err());
+
+    let other = UserChangeSet { email: set(Some("other@example.com".to_string())), ..new_user("other") };
+    let other = other.insert(&conn).await.unwrap();
+    let ambiguous = UserChangeSet { id: set(created.id), email: set(other.email), ..new_user("ambiguous") };
+    assert!(
+        matches!(ambiguous.get_or_insert(&conn).await, Err(Error::Query(message)) if message.contains("more than one"))
+    );
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_count_distinct_and_by_column() {
+    let conn = connect().await;
+    for (name, email) in [("a", Some("x@example.com")), ("a", Some("y@example.com")), ("b", None), ("c", None)] {
+        UserChangeSet { email: set(email.map(str::to_string)), ..new_user(name) }.insert(&conn).await.unwrap();
+    }
+
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 4);
+    assert_eq!(UserTable::find().count_distinct(&conn, UserColumn::Name).await.unwrap(), 3);
+    assert_eq!(UserTable::find().count_column(&conn, UserColumn::Email).await.unwrap(), 2);
+    let named_a = UserTable::find().filter(Condition::eq(UserColumn::Name, "a"));
+    assert_eq!(named_a.count_distinct(&conn, UserColumn::Email).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_one_or_err_reports_missing_records() {
+    let conn = connect().await;
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+
+    assert_eq!(UserTable::find_by_id(alice.id).required(&conn).await.unwrap(), alice);
+    let missing = UserTable::find_by_id(alice.id + 1).required(&conn).await.unwrap_err();
+    assert!(
+        matches!(&missing, Error::RecordNotFound { table: "user", pk: Some(pk) } if *pk == (alice.id + 1).to_string())
+    );
+    assert!(missing.is_not_found());
+
+    let err = UserTable::find().filter(Condition::eq(UserColumn::Name, "bob")).one_or_err(&conn).await.unwrap_err();
+    assert!(matches!(err, Error::RecordNotFound { table: "user", pk: None }));
+}
+
+#[tokio::test]
+async fn test_find_or_create_and_update_or_create() {
+    let conn = connect().await;
+    let by_email = || Condition::eq(UserColumn::Email, "a@example.com");
+    let with_email = |name: &str| UserChangeSet { email: set(Some("a@example.com".to_string())), ..new_user(name) };
+
+    let created = UserTable::find_or_create(&conn, by_email(), || with_email("first")).await.unwrap();
+    assert_eq!(created.name, "first");
+    let found = UserTable::find_or_create(&conn, by_email(), || unreachable!("the row already exists")).await.unwrap();
+    assert_eq!(found, created);
+    assert!(conn.is_autocommit().unwrap());
+
+    let conflicting =
+        UserTable::find_or_create(&conn, Condition::eq(UserColumn::Name, "second"), || with_email("second")).await;
+    assert!(matches!(conflicting, Err(Error::UniqueViolation { .. })));
+
+    let updated = UserTable::update_or_create(&conn, by_email(), with_email("renamed")).await.unwrap();
+    assert_eq!((updated.id, updated.name.as_str()), (created.id, "renamed"));
+
+    let inserted =
+        UserTable::update_or_create(&conn, Condition::eq(UserColumn::Name, "bob"), new_user("bob")).await.unwrap();
+    assert_ne!(inserted.id, created.id);
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_mvcc_checks_unique_columns_before_insert() {
+    let conn = Builder::new_local(":memory:").with_mvcc(true).build().await.unwrap().connect().unwrap();
+    let diff = Migrator::migrate::<UserTable>(&conn).await.unwrap();
+    assert!(diff.has_warnings);
+    assert!(diff.changes.iter().any(|change| matches!(
+        change,
+        SchemaChange::Warning { message, .. } if message.contains("'email'") && message.contains("inserts check")
+    )));
+    assert!(Migrator::diff::<UserTable>(&conn).await.unwrap().has_warnings);
+
+    let report = Migrator::verify(&conn, &[TableSchema::of::<UserTable>()]).await.unwrap();
+    assert!(report.is_clean(), "{}", report.summary());
+    assert!(report.warnings.iter().any(|warning| warning.starts_with("user: Column 'email' is unique")));
+
+    let with_email = |name: &str, email: &str| UserChangeSet { email: set(Some(email.to_string())), ..new_user(name) };
+    with_email("alice", "a@example.com").insert(&conn).await.unwrap();
+    new_user("no email").insert(&conn).await.unwrap();
+    new_user("no email either").insert(&conn).await.unwrap();
+
+    let duplicate = with_email("mallory", "a@example.com").insert(&conn).await.unwrap_err();
+    assert!(matches!(&duplicate, Error::UniqueViolation { constraint } if constraint == "user.email"));
+    assert!(
+        tursorm::loader::Loader::<UserTable>::from_iter(vec![
+            with_email("b", "b@example.com"),
+            with_email("c", "b@example.com")
+        ])
+        .load(&conn)
+        .await
+        .unwrap_err()
+        .is_unique_violation()
+    );
+    assert_eq!(
+        Insert::<UserTable>::new(with_email("mallory", "a@example.com"))
+            .on_conflict_do_nothing()
+            .exec(&conn)
+            .await
+            .unwrap(),
+        0
+    );
+    assert!(matches!(
+        Insert::<UserTable>::new(with_email("mallory", "a@example.com"))
+            .on_conflict_do_nothing()
+            .exec_with_last_insert_id(&conn)
+            .await,
+        Err(Error::NoRowsAffected)
+    ));
+
+    let existing = with_email("second", "a@example.com").get_or_insert(&conn).await.unwrap();
+    assert_eq!(existing.name, "alice");
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn test_migrate_renamed_table_keeps_rows() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    Migrator::migrate::<LegacyPersonTable>(&conn).await.unwrap();
+    LegacyPersonChangeSet { name: set("ada".to_string()), ..Default::default() }.insert(&conn).await.unwrap();
+
+    let diff = Migrator::migrate::<PersonTable>(&conn).await.unwrap();
+    assert_eq!(
+        diff.all_sql()[..2],
+        ["ALTER TABLE people_v1 RENAME TO people", "ALTER TABLE people RENAME COLUMN name TO full_name"]
+    );
+
+    let people = PersonTable::find().all(&conn).await.unwrap();
+    assert_eq!(people, vec![Person { id: 1, full_name: "ada".to_string() }]);
+    assert!(Migrator::introspect_table(&conn, "people_v1").await.unwrap().is_none());
+    assert!(!Migrator::diff::<PersonTable>(&conn).await.unwrap().has_changes);
+}
+
+#[tokio::test]
+async fn test_select_sampling() {
+    let conn = connect().await;
+    for idx in 0..10 {
+        new_user(&format!("user{}", idx)).insert(&conn).await.unwrap();
+    }
+
+    let mut sampled: Vec<i64> =
+        UserTable::find().sample(4).all(&conn).await.unwrap().into_iter().map(|u| u.id).collect();
+    sampled.sort();
+    sampled.dedup();
+    assert_eq!(sampled.len(), 4);
+
+    let cohort = UserTable::find()
+        .filter(Condition::gt(UserColumn::Id, 5))
+        .order_by_asc(UserColumn::Id)
+        .sample_by_rowid(3)
+        .all(&conn)
+        .await
+        .unwrap();
+    assert_eq!(cohort.len(), 3);
+    assert!(cohort.windows(2).all(|pair| pair[0].id < pair[1].id));
+    assert!(cohort.iter().all(|user| user.id > 5));
+
+    assert_eq!(UserTable::find().sample_by_rowid(3).count(&conn).await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn test_default_limit() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    Migrator::migrate::<PersonTable>(&conn).await.unwrap();
+    for name in ["a", "b", "c"] {
+        PersonChangeSet { full_name: set(name.to_string()), ..Default::default() }.insert(&conn).await.unwrap();
+    }
+
+    assert_eq!(PersonTable::find().all(&conn).await.unwrap().len(), 2);
+    assert_eq!(PersonTable::find().limit(3).all(&conn).await.unwrap().len(), 3);
+    assert_eq!(PersonTable::find().unlimited().all(&conn).await.unwrap().len(), 3);
+    assert_eq!(UserTable::DEFAULT_LIMIT, None);
+}
+
+#[cfg(any(feature = "with-json", feature = "with-arrays"))]
+#[tokio::test]
+async fn test_plan_all_then_apply() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    let schemas = [TableSchema::of::<UserTable>(), TableSchema::of::<PersonTable>()];
+
+    let plan = Migrator::plan_all(&conn, &schemas).await.unwrap();
+    assert!(Migrator::introspect_table(&conn, "user").await.unwrap().is_none());
+
+    let script = plan.to_sql_script();
+    assert!(script.contains("-- Create table 'user'\nCREATE TABLE user ("));
+    assert!(script.contains("-- Create table 'people'\nCREATE TABLE people ("));
+
+    let reviewed = SchemaDiff::from_json(&plan.to_json().unwrap()).unwrap();
+    Migrator::apply(&conn, &reviewed).await.unwrap();
+
+    new_user("alice").insert(&conn).await.unwrap();
+    let replan = Migrator::plan_all(&conn, &schemas).await.unwrap();
+    assert!(!replan.changes.iter().any(|change| change.is_create_table()));
+}
+
+#[tokio::test]
+async fn test_introspect_indexes_and_foreign_keys() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    Migrator::migrate_all(&conn, &[TableSchema::of::<UserTable>(), TableSchema::of::<PostTable>()]).await.unwrap();
+
+    let indexes = Migrator::introspect_indexes(&conn, "user").await.unwrap();
+    assert_eq!(indexes.len(), 1);
+    assert_eq!(indexes[0].columns, vec!["email"]);
+    assert!(indexes[0].unique);
+    assert_eq!(indexes[0].origin, IndexOrigin::Unique);
+
+    let foreign_keys = Migrator::introspect_foreign_keys(&conn, "post").await.unwrap();
+    assert_eq!(foreign_keys.len(), 1);
+    assert_eq!(foreign_keys[0].columns, vec!["user_id"]);
+    assert_eq!(foreign_keys[0].references_table, "user");
+    assert!(Migrator::introspect_foreign_keys(&conn, "missing").await.unwrap().is_empty());
+
+    let diff =
+        Migrator::plan_all(&conn, &[TableSchema::of::<UserTable>(), TableSchema::of::<PostTable>()]).await.unwrap();
+    assert!(!diff.has_changes && !diff.has_warnings, "{}", diff.summary());
+}
+
+#[tokio::test]
+async fn test_diff_detects_index_and_foreign_key_drift() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    conn.execute("CREATE TABLE user (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL, state TEXT NOT NULL DEFAULT 'active', email TEXT)", ())
+        .await
+        .unwrap();
+    conn.execute("CREATE UNIQUE INDEX idx_user_name ON user (name)", ()).await.unwrap();
+    conn.execute(
+        "CREATE TABLE post (id INTEGER PRIMARY KEY AUTOINCREMENT, user_id INTEGER NOT NULL, title TEXT NOT NULL)",
+        (),
+    )
+    .await
+    .unwrap();
+
+    let diff = Migrator::diff::<UserTable>(&conn).await.unwrap();
+    assert!(diff.all_sql().contains(&"CREATE UNIQUE INDEX IF NOT EXISTS idx_user_email_unique ON user (email)"));
+    assert!(diff.summary().contains("Column 'name' has unique index 'idx_user_name'"));
+
+    let diff = Migrator::diff::<PostTable>(&conn).await.unwrap();
+    assert!(diff.has_warnings);
+    assert!(diff.summary().contains("Foreign key on column 'user_id' referencing 'user' is missing in database"));
+}
+
+#[cfg(any(feature = "with-json", feature = "with-arrays"))]
+#[tokio::test]
+async fn test_snapshot_and_verify_schema() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    let schemas = [TableSchema::of::<UserTable>(), TableSchema::of::<PostTable>()];
+
+    let report = Migrator::verify(&conn, &schemas).await.unwrap();
+    assert_eq!(
+        report.drifts,
+        vec![
+            SchemaDrift::MissingTable { table_name: "user".to_string() },
+            SchemaDrift::MissingTable { table_name: "post".to_string() },
+        ]
+    );
+    assert!(report.ensure_clean().is_err());
+
+    Migrator::migrate_all(&conn, &schemas).await.unwrap();
+    let snapshot = Migrator::snapshot(&conn).await.unwrap();
+    assert_eq!(snapshot.tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["post", "user"]);
+    assert_eq!(snapshot.table("post").unwrap().foreign_keys[0].references_table, "user");
+    assert!(Migrator::verify(&conn, &schemas).await.unwrap().ensure_clean().is_ok());
+
+    conn.execute("ALTER TABLE user ADD COLUMN nickname TEXT", ()).await.unwrap();
+    conn.execute("CREATE TABLE audit (id INTEGER PRIMARY KEY)", ()).await.unwrap();
+    let exported = Migrator::snapshot(&conn).await.unwrap().to_json().unwrap();
+
+    let report = DatabaseSchema::from_json(&exported).unwrap().verify(&schemas);
+    assert_eq!(
+        report.drifts,
+        vec![
+            SchemaDrift::UnexpectedColumn { table_name: "user".to_string(), column_name: "nickname".to_string() },
+            SchemaDrift::UnexpectedTable { table_name: "audit".to_string() },
+        ]
+    );
+    assert_eq!(report.for_table("user").count(), 1);
+    assert!(report.summary().contains("user: Column 'nickname' exists in database but not in entity definition"));
+}
+
+#[tokio::test]
+async fn test_migrate_all_orphaned_tables() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    conn.execute("CREATE TABLE legacy (id INTEGER PRIMARY KEY)", ()).await.unwrap();
+    tursorm::notify::Notify::publish(&conn, "cache", "warm").await.unwrap();
+    let schemas = [TableSchema::of::<UserTable>()];
+
+    let diff = Migrator::migrate_all(&conn, &schemas).await.unwrap();
+    assert!(diff.has_warnings);
+    assert!(diff.summary().contains("Warning for 'legacy'"));
+    assert!(Migrator::introspect_table(&conn, "legacy").await.unwrap().is_some());
+
+    let options = MigrationOptions::default().allow_drop_tables(true);
+    let plan = Migrator::plan_all_with_options(&conn, &schemas, &options).await.unwrap();
+    assert!(plan.all_sql().contains(&"DROP TABLE \"legacy\""));
+    assert!(Migrator::introspect_table(&conn, "legacy").await.unwrap().is_some());
+
+    Migrator::migrate_all_with_options(&conn, &schemas, options).await.unwrap();
+    assert!(Migrator::introspect_table(&conn, "legacy").await.unwrap().is_none());
+    assert!(Migrator::introspect_table(&conn, "_tursorm_notifications").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_migrate_all_keeps_junction_tables() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    conn.execute("CREATE TABLE member_roles (member_id INTEGER, role_id INTEGER)", ()).await.unwrap();
+    conn.execute("INSERT INTO member_roles VALUES (1, 2)", ()).await.unwrap();
+    conn.execute("CREATE TABLE \"old members\" (id INTEGER PRIMARY KEY)", ()).await.unwrap();
+
+    // The junction table's schema isn't passed; the relation on Member registers it.
+    assert_eq!(TableSchema::of::<MemberTable>().junction_tables(), &["member_roles"]);
+    let schemas = [TableSchema::of::<MemberTable>(), TableSchema::of::<RoleTable>()];
+    let options = MigrationOptions::default().allow_drop_tables(true);
+    let diff = Migrator::migrate_all_with_options(&conn, &schemas, options).await.unwrap();
+
+    assert_eq!(
+        diff.all_sql().into_iter().filter(|sql| sql.starts_with("DROP TABLE")).collect::<Vec<_>>(),
+        vec!["DROP TABLE \"old members\""]
+    );
+    assert!(Migrator::introspect_table(&conn, "old members").await.unwrap().is_none());
+    assert_eq!(conn.query_dynamic("SELECT * FROM member_roles", Vec::new()).await.unwrap().len(), 1);
+    assert!(Migrator::verify(&conn, &schemas).await.unwrap().drifts.is_empty());
+}
+
+#[tokio::test]
+async fn test_ensure_current_skips_unchanged_schemas() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    let schemas = [TableSchema::of::<UserTable>()];
+
+    let diff = Migrator::ensure_current(&conn, &schemas).await.unwrap();
+    assert!(diff.has_changes);
+
+    // An unchanged hash skips introspection, so drift made behind the migrator's back goes unnoticed.
+    conn.execute("ALTER TABLE user DROP COLUMN state", ()).await.unwrap();
+    assert!(!Migrator::ensure_current(&conn, &schemas).await.unwrap().has_changes);
+    assert!(Migrator::verify(&conn, &schemas).await.unwrap().ensure_clean().is_err());
+
+    let schemas = [TableSchema::of::<UserTable>(), TableSchema::of::<PostTable>()];
+    let diff = Migrator::ensure_current(&conn, &schemas).await.unwrap();
+    assert!(diff.all_sql().iter().any(|sql| sql.starts_with("CREATE TABLE post")));
+    assert!(diff.all_sql().iter().any(|sql| sql.starts_with("ALTER TABLE user ADD COLUMN state")));
+    assert!(Migrator::verify(&conn, &schemas).await.unwrap().ensure_clean().is_ok());
+    assert!(!Migrator::ensure_current(&conn, &schemas).await.unwrap().has_changes);
+}
+
+impl SeedTrait for UserTable {
+    fn seed_rows() -> Vec<UserChangeSet> {
+        vec![UserChangeSet { email: set(Some("admin@example.com".to_string())), ..new_user("admin") }]
+    }
+
+    fn seed_guard() -> Option<Condition> {
+        Some(Condition::eq(UserColumn::Email, "admin@example.com"))
+    }
+}
+
+#[tokio::test]
+async fn test_seed_only_empty_tables() {
+    let conn = connect().await;
+
+    assert_eq!(Migrator::seed::<UserTable>(&conn, vec![new_user("a"), new_user("b")]).await.unwrap(), 2);
+    assert_eq!(Migrator::seed::<UserTable>(&conn, vec![new_user("c")]).await.unwrap(), 0);
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 2);
+
+    let guard = Condition::eq(UserColumn::Name, "c");
+    assert_eq!(Migrator::seed_unless::<UserTable>(&conn, guard.clone(), vec![new_user("c")]).await.unwrap(), 1);
+    assert_eq!(Migrator::seed_unless::<UserTable>(&conn, guard, vec![new_user("c")]).await.unwrap(), 0);
+    assert!(conn.is_autocommit().unwrap());
+}
+
+#[tokio::test]
+async fn test_seed_table() {
+    let conn = connect().await;
+    new_user("existing").insert(&conn).await.unwrap();
+
+    assert_eq!(Migrator::seed_table::<UserTable>(&conn).await.unwrap(), 1);
+    assert_eq!(Migrator::seed_table::<UserTable>(&conn).await.unwrap(), 0);
+    assert!(UserTable::find_by_email("admin@example.com").one(&conn).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_update_counters_with_expressions() {
+    let conn = connect().await;
+    Migrator::migrate::<ProductTable>(&conn).await.unwrap();
+
+    let product = ProductChangeSet { name: set("widget".to_string()), quantity: set(10), ..Default::default() }
+        .insert(&conn)
+        .await
+        .unwrap();
+
+    let affected = Update::<ProductTable>::many()
+        .inc(ProductColumn::Quantity, 5)
+        .filter(Condition::eq(ProductColumn::Id, product.id))
+        .exec(&conn)
+        .await
+        .unwrap();
+    assert_eq!(affected, 1);
+
+    Update::<ProductTable>::many()
+        .dec(ProductColumn::Quantity, 3)
+        .set_expr(ProductColumn::Name, Expr::col(ProductColumn::Name).concat(" (restocked)"))
+        .exec(&conn)
+        .await
+        .unwrap();
+
+    Update::<ProductTable>::many()
+        .set_expr(ProductColumn::Quantity, Expr::col(ProductColumn::Quantity).mul(2))
+        .exec(&conn)
+        .await
+        .unwrap();
+
+    let reloaded = ProductTable::find_by_id(product.id).one(&conn).await.unwrap().unwrap();
+    assert_eq!(reloaded.quantity, 24);
+    assert_eq!(reloaded.name, "widget (restocked)");
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(table_name = "user", readonly)]
+pub struct UserReport {
+    #[tursorm(primary_key)]
+    pub id:   i64,
+    pub name: String,
+}
+
+#[tokio::test]
+async fn test_readonly_table_rejects_writes() {
+    let conn = connect().await;
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+
+    let report = UserReportTable::find_by_id(alice.id).required(&conn).await.unwrap();
+    assert_eq!(report, UserReport { id: alice.id, name: "alice".to_string() });
+
+    let insert = Insert::<UserReportTable>::new(ReadOnlyChangeSet::default()).exec(&conn).await;
+    assert!(matches!(insert, Err(Error::ReadOnlyTable { table: "user" })));
+    let update = Update::<UserReportTable>::many().set(UserReportColumn::Name, "bob").filter(Condition::always_true());
+    assert!(matches!(update.exec(&conn).await, Err(Error::ReadOnlyTable { .. })));
+    assert!(matches!(UserReportTable::truncate().exec(&conn).await, Err(Error::ReadOnlyTable { .. })));
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 1);
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(table_name = "active_user", view = "SELECT id, name FROM user WHERE state = 'active'")]
+pub struct ActiveUser {
+    #[tursorm(primary_key)]
+    pub id:   i64,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(table_name = "active_user", view = "SELECT id, upper(name) AS name FROM user WHERE state = 'active'")]
+pub struct ShoutingActiveUser {
+    #[tursorm(primary_key)]
+    pub id:   i64,
+    pub name: String,
+}
+
+#[tokio::test]
+async fn test_view_backed_table() {
+    let conn = connect().await;
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    UserChangeSet { state: set("banned".to_string()), ..new_user("bob") }.insert(&conn).await.unwrap();
+
+    let diff = Migrator::migrate::<ActiveUserTable>(&conn).await.unwrap();
+    assert!(
+        matches!(diff.changes.as_slice(), [SchemaChange::CreateView { view_name, .. }] if view_name == "active_user")
+    );
+    assert!(!Migrator::migrate::<ActiveUserTable>(&conn).await.unwrap().has_changes);
+
+    let active = ActiveUserTable::find().order_by_asc(ActiveUserColumn::Id).all(&conn).await.unwrap();
+    assert_eq!(active, vec![ActiveUser { id: alice.id, name: "alice".to_string() }]);
+    let delete = ActiveUserTable::delete_many_by_ids(vec![alice.id]).exec(&conn).await;
+    assert!(matches!(delete, Err(Error::ReadOnlyTable { table: "active_user" })));
+
+    let diff = Migrator::migrate::<ShoutingActiveUserTable>(&conn).await.unwrap();
+    assert!(matches!(diff.changes.as_slice(), [SchemaChange::DropView { .. }, SchemaChange::CreateView { .. }]));
+    let shouting = ShoutingActiveUserTable::find_by_id(alice.id).required(&conn).await.unwrap();
+    assert_eq!(shouting.name, "ALICE");
+
+    let report = Migrator::verify(&conn, &[TableSchema::of::<UserTable>(), TableSchema::of::<ActiveUserTable>()]).await;
+    assert!(report.unwrap().is_clean());
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(readonly)]
+pub struct UserStateCount {
+    #[tursorm(primary_key)]
+    pub state: String,
+    pub total: i64,
+}
+
+impl CachedTableTrait for UserStateCountTable {
+    fn source() -> CacheSource {
+        CacheSource::sql("SELECT state, COUNT(*) FROM user GROUP BY state")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+#[tursorm(readonly)]
+pub struct NamedUser {
+    #[tursorm(primary_key)]
+    pub id:   i64,
+    pub name: String,
+}
+
+impl CachedTableTrait for NamedUserTable {
+    fn source() -> CacheSource {
+        UserTable::find()
+            .columns(vec![UserColumn::Id, UserColumn::Name])
+            .filter(Condition::ne(UserColumn::State, "banned"))
+            .into()
+    }
+}
+
+#[tokio::test]
+async fn test_cached_table_refresh() {
+    let conn = connect().await;
+    Migrator::migrate::<UserStateCountTable>(&conn).await.unwrap();
+    Migrator::migrate::<NamedUserTable>(&conn).await.unwrap();
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    new_user("bob").insert(&conn).await.unwrap();
+    UserChangeSet { state: set("banned".to_string()), ..new_user("carol") }.insert(&conn).await.unwrap();
+
+    assert_eq!(UserStateCountTable::refresh(&conn).await.unwrap(), 2);
+    let counts = UserStateCountTable::find().order_by_asc(UserStateCountColumn::State).all(&conn).await.unwrap();
+    assert_eq!(
+        counts,
+        vec![
+            UserStateCount { state: "active".to_string(), total: 2 },
+            UserStateCount { state: "banned".to_string(), total: 1 },
+        ]
+    );
+
+    UserTable::delete_many_by_ids(vec![alice.id]).exec(&conn).await.unwrap();
+    assert_eq!(UserStateCountTable::refresh(&conn).await.unwrap(), 2);
+    assert_eq!(UserStateCountTable::find_by_id("active").required(&conn).await.unwrap().total, 1);
+
+    assert_eq!(NamedUserTable::refresh(&conn).await.unwrap(), 1);
+    assert_eq!(NamedUserTable::find().one(&conn).await.unwrap().unwrap().name, "bob");
+}
+
+#[tokio::test]
+async fn test_expect_affected_rolls_back_mismatched_writes() {
+    let conn = connect().await;
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    new_user("bob").insert(&conn).await.unwrap();
+
+    let update =
+        Update::<UserTable>::many().set(UserColumn::State, "banned").filter(Condition::eq(UserColumn::State, "active"));
+    let err = update.expect_affected(1).exec(&conn).await.unwrap_err();
+    assert!(matches!(err, Error::UnexpectedAffectedRows { expected: 1, actual: 2 }));
+    assert_eq!(UserTable::find().filter(Condition::eq(UserColumn::State, "banned")).count(&conn).await.unwrap(), 0);
+
+    let deleted = Delete::<UserTable>::new().filter(Condition::eq(UserColumn::Id, alice.id)).expect_affected(1);
+    assert_eq!(deleted.exec(&conn).await.unwrap(), 1);
+    let err = Delete::<UserTable>::new().expect_affected(0).exec(&conn).await.unwrap_err();
+    assert!(matches!(err, Error::UnexpectedAffectedRows { expected: 0, actual: 1 }));
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_expect_affected_rolls_back_mismatched_returning_writes() {
+    let conn = connect().await;
+    new_user("alice").insert(&conn).await.unwrap();
+    new_user("bob").insert(&conn).await.unwrap();
+
+    let update =
+        Update::<UserTable>::many().set(UserColumn::State, "banned").filter(Condition::eq(UserColumn::State, "active"));
+    let err = update.clone().expect_affected(1).exec_with_returning_all(&conn).await.unwrap_err();
+    assert!(matches!(err, Error::UnexpectedAffectedRows { expected: 1, actual: 2 }));
+    let err = update.expect_affected(3).exec_with_returning(&conn).await.unwrap_err();
+    assert!(matches!(err, Error::UnexpectedAffectedRows { expected: 3, actual: 2 }));
+    assert_eq!(UserTable::find().filter(Condition::eq(UserColumn::State, "banned")).count(&conn).await.unwrap(), 0);
+
+    let err = Delete::<UserTable>::new().expect_affected(1).exec_with_returning(&conn).await.unwrap_err();
+    assert!(matches!(err, Error::UnexpectedAffectedRows { expected: 1, actual: 2 }));
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 2);
+
+    let deleted = Delete::<UserTable>::new().expect_affected(2).exec_with_returning(&conn).await.unwrap();
+    assert_eq!(deleted.len(), 2);
+}
+
+#[cfg(feature = "strict-decode")]
+#[tokio::test]
+async fn test_strict_decode_fails_query_on_null_in_required_column() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    conn.execute("CREATE TABLE user (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT, state TEXT NOT NULL DEFAULT 'active', email TEXT)", ())
+        .await
+        .unwrap();
+    conn.execute("INSERT INTO user (name) VALUES ('alice'), (NULL)", ()).await.unwrap();
+
+    assert!(matches!(UserTable::find().all(&conn).await, Err(Error::UnexpectedNull)));
+    assert!(matches!(UserTable::find_by_id(2).one(&conn).await, Err(Error::UnexpectedNull)));
+    assert_eq!(UserTable::find_by_id(1).one(&conn).await.unwrap().unwrap().name, "alice");
+}
+
+#[tokio::test]
+async fn test_require_filter_on_mutation() {
+    let conn = connect_with(Builder::new_local(":memory:").require_filter_on_mutation(true)).await;
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    new_user("bob").insert(&conn).await.unwrap();
+
+    let update = Update::<UserTable>::many().set(UserColumn::State, "banned");
+    assert!(matches!(update.clone().exec(&conn).await, Err(Error::UnfilteredMutation { table: "user" })));
+    assert!(matches!(update.clone().exec_with_returning(&conn).await, Err(Error::UnfilteredMutation { .. })));
+    assert!(matches!(Delete::<UserTable>::new().exec(&conn).await, Err(Error::UnfilteredMutation { .. })));
+    assert_eq!(UserTable::find().filter(Condition::eq(UserColumn::State, "active")).count(&conn).await.unwrap(), 2);
+
+    let filtered = update.clone().filter(Condition::eq(UserColumn::Id, alice.id));
+    assert_eq!(filtered.exec(&conn).await.unwrap(), 1);
+    assert_eq!(update.allow_full_table().exec(&conn).await.unwrap(), 2);
+    assert_eq!(Delete::<UserTable>::new().allow_full_table().exec(&conn).await.unwrap(), 2);
+    assert_eq!(UserTable::truncate().exec(&conn).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_for_each_chunk_processes_and_resumes() {
+    let conn = connect().await;
+    let mut ids = Vec::new();
+    for name in ["a", "b", "c", "d", "e"] {
+        ids.push(new_user(name).insert(&conn).await.unwrap().id);
+    }
+
+    let mut batches = Vec::new();
+    let last = UserTable::find()
+        .order_by_desc(UserColumn::Name)
+        .for_each_chunk(&conn, 2, |users| {
+            batches.push(users.iter().map(|user| user.name.clone()).collect::<Vec<_>>());
+            let conn = &conn;
+            async move {
+                let keys: Vec<i64> = users.iter().map(|user| user.id).collect();
+                Update::<UserTable>::many()
+                    .set(UserColumn::State, "migrated")
+                    .filter(Condition::is_in(UserColumn::Id, keys))
+                    .exec(conn)
+                    .await?;
+                Ok(())
+            }
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(batches, vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]]);
+    assert_eq!(last, Some(Cursor::from(ids[4])));
+    assert_eq!(UserTable::find().filter(Condition::eq(UserColumn::State, "migrated")).count(&conn).await.unwrap(), 5);
+
+    let mut resumed = Vec::new();
+    let last = UserTable::find()
+        .after(ids[2])
+        .for_each_chunk(&conn, 10, |users| {
+            resumed.extend(users.into_iter().map(|user| user.name));
+            async { Ok(()) }
+        })
+        .await
+        .unwrap();
+    assert_eq!(resumed, vec!["d", "e"]);
+    assert_eq!(last, Some(Cursor::from(ids[4])));
+
+    let none =
+        UserTable::find().filter(Condition::eq(UserColumn::Name, "zed")).for_each_chunk(&conn, 2, |_| async { Ok(()) });
+    assert_eq!(none.await.unwrap(), None);
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Subscriber {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:    i64,
+    #[tursorm(unique, collate = "NOCASE")]
+    pub email: String,
+}
+
+#[tokio::test]
+async fn test_collated_column() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    Migrator::migrate::<SubscriberTable>(&conn).await.unwrap();
+    assert!(!Migrator::migrate::<SubscriberTable>(&conn).await.unwrap().has_warnings);
+
+    let info = Migrator::introspect_table(&conn, "subscriber").await.unwrap().unwrap();
+    assert_eq!(info.columns[1].collation.as_deref(), Some("NOCASE"));
+
+    SubscriberChangeSet { email: set("Ann@Example.com".to_string()), ..Default::default() }
+        .insert(&conn)
+        .await
+        .unwrap();
+    let duplicate = SubscriberChangeSet { email: set("ann@example.COM".to_string()), ..Default::default() };
+    assert!(duplicate.insert(&conn).await.unwrap_err().is_unique_violation());
+    assert!(SubscriberTable::find_by_email("ANN@EXAMPLE.COM").one(&conn).await.unwrap().is_some());
+}
+
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Attachment {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:    i64,
+    #[tursorm(max_length = 8)]
+    pub name:  String,
+    pub bytes: Option<Vec<u8>>,
+}
+
+#[tokio::test]
+async fn test_value_size_guards() {
+    let conn = Builder::new_local(":memory:").max_blob_size(16).build().await.unwrap().connect().unwrap();
+    Migrator::migrate::<AttachmentTable>(&conn).await.unwrap();
+
+    let attachment = |name: &str, bytes: usize| AttachmentChangeSet {
+        name: set(name.to_string()),
+        bytes: set(Some(vec![0; bytes])),
+        ..Default::default()
+    };
+
+    let saved = attachment("naïve.md", 16).insert(&conn).await.unwrap();
+    let err = attachment("notes.txt", 1).insert(&conn).await.unwrap_err();
+    assert!(matches!(&err, Error::ValueTooLarge { column, size: 9, max: 8 } if column == "attachment.name"));
+    assert_eq!(err.kind(), ErrorKind::Constraint);
+
+    let err = InsertMany::<AttachmentTable>::new(vec![attachment("a", 1), attachment("b", 17)])
+        .exec(&conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::ValueTooLarge { size: 17, max: 16, .. }));
+
+    let err = Update::<AttachmentTable>::many()
+        .set(AttachmentColumn::Bytes, vec![0u8; 32])
+        .filter(Condition::eq(AttachmentColumn::Id, saved.id))
+        .exec(&conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::ValueTooLarge { size: 32, .. }));
+
+    let err = Update::<AttachmentTable>::many()
+        .set_expr(AttachmentColumn::Name, Expr::col(AttachmentColumn::Name).concat("-archived"))
+        .filter(Condition::eq(AttachmentColumn::Id, saved.id))
+        .exec(&conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(&err, Error::ValueTooLarge { column, size: 9, max: 8 } if column == "attachment.name"));
+    assert_eq!(AttachmentTable::find().count(&conn).await.unwrap(), 1);
+
+    let err = Insert::<AttachmentTable>::new(attachment("notes.txt", 1)).build().unwrap_err();
+    assert!(matches!(err, Error::ValueTooLarge { size: 9, max: 8, .. }));
+    let err =
+        InsertMany::<AttachmentTable>::new(vec![attachment("a", 1), attachment("notes.txt", 1)]).build().unwrap_err();
+    assert!(matches!(err, Error::ValueTooLarge { size: 9, max: 8, .. }));
+}
+
+/// A direct message between two users.
+#[derive(Clone, Debug, PartialEq, Table)]
+pub struct Message {
+    #[tursorm(primary_key, auto_increment)]
+    pub id:           i64,
+    /// The user who wrote the message.
+    pub sender_id:    i64,
+    pub recipient_id: i64,
+    pub body:         String,
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+pub struct MessageLine {
+    pub body:      String,
+    pub sender:    String,
+    pub recipient: String,
+}
+
+#[tokio::test]
+async fn test_self_join_with_aliases() {
+    let conn = connect().await;
+    Migrator::migrate::<MessageTable>(&conn).await.unwrap();
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    let bob = new_user("bob").insert(&conn).await.unwrap();
+    for (sender, recipient, body) in [(&alice, &bob, "hi bob"), (&bob, &alice, "hi alice"), (&alice, &alice, "note")] {
+        MessageChangeSet {
+            sender_id: set(sender.id),
+            recipient_id: set(recipient.id),
+            body: set(body.to_string()),
+            ..Default::default()
+        }
+        .insert(&conn)
+        .await
+        .unwrap();
+    }
+
+    let conversation = || {
+        MessageTable::find()
+            .alias("m")
+            .join::<UserTable>(
+                "sender",
+                Condition::eq_column(MessageColumn::SenderId.of("m"), UserColumn::Id.of("sender")),
+            )
+            .join::<UserTable>(
+                "recipient",
+                Condition::eq_column(MessageColumn::RecipientId.of("m"), UserColumn::Id.of("recipient")),
+            )
+            .filter(Condition::eq_column(UserColumn::Id.of("sender"), UserColumn::Id.of("recipient")).not())
+            .order_by_asc(MessageColumn::Id.of("m"))
+    };
+
+    let lines = conversation()
+        .columns(vec![MessageColumn::Body.of("m")])
+        .column(UserColumn::Name.of("sender"))
+        .column(UserColumn::Name.of("recipient"))
+        .into_model::<MessageLine>()
+        .all(&conn)
+        .await
+        .unwrap();
+    assert_eq!(
+        lines,
+        vec![
+            MessageLine {
+                body:      "hi bob".to_string(),
+                sender:    "alice".to_string(),
+                recipient: "bob".to_string(),
+            },
+            MessageLine {
+                body:      "hi alice".to_string(),
+                sender:    "bob".to_string(),
+                recipient: "alice".to_string(),
+            },
+        ]
+    );
+
+    let from_alice = conversation().filter(Condition::eq(UserColumn::Name.of("sender"), "alice"));
+    assert_eq!(from_alice.all(&conn).await.unwrap()[0].body, "hi bob");
+    assert_eq!(conversation().count(&conn).await.unwrap(), 2);
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+#[tursorm(from_row = "by_name")]
+pub struct NamedMessageLine {
+    pub recipient: String,
+    #[tursorm(column_name = "body")]
+    pub text:      String,
+    pub sender:    String,
+}
+
+#[test]
+fn test_table_and_column_docs() {
+    assert_eq!(MessageTable::table_docs(), Some("A direct message between two users."));
+    assert_eq!(MessageColumn::SenderId.docs(), Some("The user who wrote the message."));
+    assert_eq!(MessageColumn::Body.docs(), None);
+    assert_eq!(UserTable::table_docs(), None);
+
+    let schema = TableSchema::of::<MessageTable>();
+    assert_eq!(schema.docs(), Some("A direct message between two users."));
+    assert_eq!(schema.columns()[1].docs, Some("The user who wrote the message."));
+}
+
+#[cfg(any(feature = "with-json", feature = "with-arrays"))]
+#[test]
+fn test_describe_all() {
+    let description = tursorm::schema::describe_all(&[
+        TableSchema::of::<UserTable>(),
+        TableSchema::of::<PostTable>(),
+        TableSchema::of::<MessageTable>(),
+    ]);
+    let tables = description["tables"].as_array().unwrap();
+    assert_eq!(tables.len(), 3);
+
+    let user = &tables[0];
+    assert_eq!(user["name"], "user");
+    assert_eq!(user["primary_key"], "id");
+    assert_eq!(
+        user["columns"][3],
+        serde_json::json!({
+            "name": "email", "type": "TEXT", "nullable": true, "primary_key": false, "auto_increment": false,
+            "unique": true, "default": null, "collation": null, "docs": null,
+        })
+    );
+    assert_eq!(user["columns"][2]["default"], "'active'");
+    assert_eq!(
+        user["relations"],
+        serde_json::json!([{ "kind": "has_many", "column": "id", "table": "post", "related_column": "user_id" }])
+    );
+
+    assert_eq!(
+        tables[1]["relations"],
+        serde_json::json!([{ "kind": "belongs_to", "column": "user_id", "table": "user", "related_column": "id" }])
+    );
+    assert_eq!(tables[2]["docs"], "A direct message between two users.");
+    assert_eq!(tables[2]["columns"][1]["docs"], "The user who wrote the message.");
+    assert_eq!(tables[2]["view"], false);
+}
+
+#[tokio::test]
+async fn test_from_row_by_name() {
+    let conn = connect().await;
+    Migrator::migrate::<MessageTable>(&conn).await.unwrap();
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    let bob = new_user("bob").insert(&conn).await.unwrap();
+    MessageChangeSet {
+        sender_id: set(alice.id),
+        recipient_id: set(bob.id),
+        body: set("hi bob".to_string()),
+        ..Default::default()
+    }
+    .insert(&conn)
+    .await
+    .unwrap();
+
+    let lines = MessageTable::find()
+        .alias("m")
+        .join::<UserTable>("s", Condition::eq_column(MessageColumn::SenderId.of("m"), UserColumn::Id.of("s")))
+        .join::<UserTable>("r", Condition::eq_column(MessageColumn::RecipientId.of("m"), UserColumn::Id.of("r")))
+        .column_expr(Expr::col(UserColumn::Name.of("s")), "sender")
+        .column(MessageColumn::Body.of("m"))
+        .column_expr(Expr::col(UserColumn::Name.of("r")), "recipient")
+        .into_model::<NamedMessageLine>()
+        .all(&conn)
+        .await
+        .unwrap();
+    assert_eq!(
+        lines,
+        vec![NamedMessageLine {
+            recipient: "bob".to_string(),
+            text:      "hi bob".to_string(),
+            sender:    "alice".to_string(),
+        }]
+    );
+
+    let missing = MessageTable::find().columns(vec![MessageColumn::Body]).into_model::<NamedMessageLine>();
+    assert!(matches!(missing.all(&conn).await, Err(Error::ColumnNotFound(column)) if column == "recipient"));
+
+    let mut rows = conn.query_rows("SELECT 'x' AS sender, 'y' AS BODY, 'z' AS recipient", Vec::new()).await.unwrap();
+    let columns = rows.columns();
+    let row = rows.next().await.unwrap().unwrap();
+    assert_eq!(NamedMessageLine::from_named_row(&row, &columns).unwrap().text, "y");
+    assert!(NamedMessageLine::from_row(&row).is_err());
+}
+
+#[tokio::test]
+async fn test_compound_selects() {
+    let conn = connect().await;
+    for (name, state) in [("alice", "active"), ("bob", "banned"), ("carol", "banned")] {
+        UserChangeSet { state: set(state.to_string()), ..new_user(name) }.insert(&conn).await.unwrap();
+    }
+    let named = |name: &str| UserTable::find().filter(Condition::eq(UserColumn::Name, name.to_string()));
+    let banned = || UserTable::find().filter(Condition::eq(UserColumn::State, "banned"));
+    let names = |users: Vec<User>| {
+        let mut names: Vec<String> = users.into_iter().map(|user| user.name).collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(names(named("alice").union(banned()).all(&conn).await.unwrap()), vec!["alice", "bob", "carol"]);
+    assert_eq!(names(named("bob").union(banned()).all(&conn).await.unwrap()), vec!["bob", "carol"]);
+    assert_eq!(names(named("bob").union_all(banned()).all(&conn).await.unwrap()), vec!["bob", "bob", "carol"]);
+    assert_eq!(names(named("bob").intersect(banned()).all(&conn).await.unwrap()), vec!["bob"]);
+    assert_eq!(names(banned().except(named("bob")).all(&conn).await.unwrap()), vec!["carol"]);
+    assert_eq!(named("alice").union(banned()).limit(2).all(&conn).await.unwrap().len(), 2);
+
+    let latest_banned = banned().order_by_desc(UserColumn::Id).limit(1);
+    assert_eq!(names(named("alice").union_all(latest_banned).all(&conn).await.unwrap()), vec!["alice", "carol"]);
+
+    let feed = named("alice").union_all(banned()).union_all(named("bob")).order_by_desc(UserColumn::Name);
+    let ordered: Vec<String> = feed.clone().all(&conn).await.unwrap().into_iter().map(|user| user.name).collect();
+    assert_eq!(ordered, vec!["carol", "bob", "bob", "alice"]);
+    let page = feed.clone().offset(1).limit(2).all(&conn).await.unwrap();
+    assert_eq!(page.into_iter().map(|user| user.name).collect::<Vec<_>>(), vec!["bob", "bob"]);
+    assert_eq!(feed.clone().one(&conn).await.unwrap().unwrap().name, "carol");
+
+    assert_eq!(feed.clone().count(&conn).await.unwrap(), 4);
+    assert_eq!(named("alice").union(banned()).union(named("bob")).count(&conn).await.unwrap(), 3);
+    assert_eq!(banned().except(named("bob")).count(&conn).await.unwrap(), 1);
+    assert!(matches!(feed.count_distinct(&conn, UserColumn::Name).await, Err(Error::Query(_))));
+
+    let summary = vec![UserColumn::Id, UserColumn::Email];
+    let unselected =
+        named("alice").columns(summary.clone()).union(banned().columns(summary)).order_by_asc(UserColumn::Name);
+    assert!(matches!(unselected.into_model::<UserSummary>().all(&conn).await, Err(Error::Query(_))));
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+pub struct RankedUser {
+    pub name:          String,
+    pub rank_in_state: i64,
+    pub state_count:   i64,
+}
+
+#[tokio::test]
+async fn test_window_functions() {
+    let conn = connect().await;
+    for (name, state) in [("alice", "active"), ("bob", "banned"), ("carol", "active"), ("dave", "active")] {
+        UserChangeSet { state: set(state.to_string()), ..new_user(name) }.insert(&conn).await.unwrap();
+    }
+
+    let newest_first = partition_by(UserColumn::State).order_by_desc(UserColumn::Id);
+    let ranked = UserTable::find()
+        .columns(vec![UserColumn::Name])
+        .column_expr(row_number().over(newest_first), "rank_in_state")
+        .column_expr(WindowExpr::count().over(partition_by(UserColumn::State)), "state_count")
+        .order_by_asc(UserColumn::Id)
+        .into_model::<RankedUser>()
+        .all(&conn)
+        .await
+        .unwrap();
+
+    let ranks: Vec<(&str, i64, i64)> =
+        ranked.iter().map(|user| (user.name.as_str(), user.rank_in_state, user.state_count)).collect();
+    assert_eq!(ranks, vec![("alice", 3, 3), ("bob", 1, 1), ("carol", 2, 3), ("dave", 1, 3)]);
+}
+
+#[tokio::test]
+async fn test_unique_violation_is_conflict() {
+    let conn = connect().await;
+    let user = UserChangeSet { email: set(Some("dup@example.com".to_string())), ..new_user("first") };
+    user.clone().insert(&conn).await.unwrap();
+
+    let err = UserChangeSet { name: set("second".to_string()), ..user.clone() }.insert(&conn).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+    assert!(err.is_conflict());
+    assert!(!err.is_transient());
+    assert!(matches!(&err, Error::UniqueViolation { constraint } if constraint == "user.email"));
+
+    new_user("other").insert(&conn).await.unwrap();
+    let skipped = Insert::<UserTable>::new(user).on_conflict_do_nothing().exec_with_last_insert_id(&conn).await;
+    assert!(matches!(skipped, Err(Error::NoRowsAffected)));
+}
+
+#[tokio::test]
+async fn test_delete_returning_deleted_records() {
+    let conn = connect().await;
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    let bob = new_user("bob").insert(&conn).await.unwrap();
+    new_user("carol").insert(&conn).await.unwrap();
+
+    let deleted = Delete::<UserTable>::new()
+        .filter(Condition::is_in(UserColumn::Name, vec!["alice", "bob"]))
+        .exec_with_returning(&conn)
+        .await
+        .unwrap();
+    assert_eq!(deleted, vec![alice, bob]);
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 1);
+    assert!(conn.is_autocommit().unwrap());
+
+    let deleted = Delete::<UserTable>::new()
+        .filter(Condition::eq(UserColumn::Name, "nobody"))
+        .exec_with_returning(&conn)
+        .await
+        .unwrap();
+    assert!(deleted.is_empty());
+}
+
+#[tokio::test]
+async fn test_delete_cascade_deletes_children_first() {
+    let conn = connect().await;
+    Migrator::migrate::<PostTable>(&conn).await.unwrap();
+    let alice = new_user("alice").insert(&conn).await.unwrap();
+    let bob = new_user("bob").insert(&conn).await.unwrap();
+    for (user_id, title) in [(alice.id, "a1"), (alice.id, "a2"), (bob.id, "b1")] {
+        PostChangeSet { user_id: set(user_id), title: set(title.to_string()), ..Default::default() }
+            .insert(&conn)
+            .await
+            .unwrap();
+    }
+
+    let deleted =
+        DeleteCascade::<UserTable>::by_id(alice.id).cascade::<PostTable>(PostColumn::UserId).exec(&conn).await.unwrap();
+    assert_eq!(deleted, 1);
+    assert_eq!(UserTable::find().all(&conn).await.unwrap(), vec![bob.clone()]);
+    let titles = PostTable::find().all(&conn).await.unwrap().into_iter().map(|post| post.title).collect::<Vec<_>>();
+    assert_eq!(titles, vec!["b1"]);
+    assert!(conn.is_autocommit().unwrap());
+
+    let missing = DeleteCascade::<UserTable>::new()
+        .filter(Condition::eq(UserColumn::Name, "nobody"))
+        .cascade::<PostTable>(PostColumn::UserId)
+        .exec(&conn)
+        .await
+        .unwrap();
+    assert_eq!(missing, 0);
+    assert_eq!(PostTable::find().count(&conn).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_unit_of_work_flushes_in_dependency_order() {
+    let post = |id: i64| PostChangeSet { id: set(id), ..Default::default() };
+    let user = |id: i64| UserChangeSet { id: set(id), ..Default::default() };
+
+    let mut work = UnitOfWork::new();
+    work.insert(PostChangeSet { user_id: set(7), title: set("hello".to_string()), ..post(1) })
+        .insert(UserChangeSet { name: set("alice".to_string()), ..user(7) })
+        .delete(user(9))
+        .delete(post(3))
+        .update(UserChangeSet { email: set(Some("a@example.com".to_string())), ..user(7) })
+        .insert(UserChangeSet { name: set("ghost".to_string()), ..user(8) })
+        .delete(user(8));
+    assert_eq!(work.len(), 4);
+
+    let conn = MockConnection::new();
+    work.commit(&conn).await.unwrap();
+    assert_eq!(
+        conn.sql(),
+        vec![
+            "INSERT INTO user (id, name, email) VALUES (?, ?, ?)",
+            "INSERT INTO post (id, user_id, title) VALUES (?, ?, ?)",
+            "DELETE FROM post WHERE (id = ?)",
+            "DELETE FROM user WHERE (id = ?)",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_unit_of_work_commits_atomically() {
+    let conn = connect().await;
+    Migrator::migrate::<PostTable>(&conn).await.unwrap();
+    let alice = UserChangeSet { email: set(Some("a@example.com".to_string())), ..new_user("alice") }
+        .insert(&conn)
+        .await
+        .unwrap();
+
+    let mut work = UnitOfWork::new();
+    work.update(UserChangeSet { id: set(alice.id), name: set("alicia".to_string()), ..Default::default() })
+        .insert(PostChangeSet { user_id: set(alice.id), title: set("hello".to_string()), ..Default::default() })
+        .insert(UserChangeSet { email: set(Some("a@example.com".to_string())), ..new_user("copycat") });
+    assert!(work.commit(&conn).await.unwrap_err().is_conflict());
+    assert!(conn.is_autocommit().unwrap());
+    assert_eq!(UserTable::find().all(&conn).await.unwrap(), vec![alice.clone()]);
+    assert_eq!(PostTable::find().count(&conn).await.unwrap(), 0);
+
+    let mut work = UnitOfWork::new();
+    work.update(UserChangeSet { id: set(alice.id), name: set("alicia".to_string()), ..Default::default() })
+        .insert(PostChangeSet { user_id: set(alice.id), title: set("hello".to_string()), ..Default::default() })
+        .update(UserChangeSet { id: set(alice.id), state: set("away".to_string()), ..Default::default() });
+    assert_eq!(work.commit(&conn).await.unwrap(), 2);
+
+    let alice = UserTable::find_by_id(alice.id).one(&conn).await.unwrap().unwrap();
+    assert_eq!((alice.name.as_str(), alice.state.as_str()), ("alicia", "away"));
+    assert_eq!(PostTable::find().count(&conn).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_table_enum_reads_and_writes_variants() {
+    let conn = connect().await;
+    Migrator::migrate::<EventTable>(&conn).await.unwrap();
+
+    let click = EventKind::Click(Click { id: 0, x: 3 }).insert(&conn).await.unwrap();
+    assert_eq!(click, EventKind::Click(Click { id: 1, x: 3 }));
+    let view = PageView { id: 0, url: "/home".to_string() };
+    assert_eq!(EventKind::PageView(view).insert_exec(&conn).await.unwrap(), 1);
+    EventChangeSet { kind: set("scroll".to_string()), ..Default::default() }.insert(&conn).await.unwrap();
+
+    let stored = EventTable::find().all(&conn).await.unwrap();
+    assert_eq!(stored.iter().map(|event| event.kind.as_str()).collect::<Vec<_>>(), ["click", "view", "scroll"]);
+
+    let events = EventKind::find().order_by_asc(EventColumn::Id).into_model::<EventKind>().all(&conn).await.unwrap();
+    assert_eq!(
+        events,
+        vec![
+            EventKind::Click(Click { id: 1, x: 3 }),
+            EventKind::PageView(PageView { id: 2, url: "/home".to_string() }),
+        ]
+    );
+
+    let unknown = EventTable::find().into_model::<EventKind>().all(&conn).await.unwrap_err();
+    assert!(matches!(unknown, Error::TypeConversion { actual, .. } if actual == "scroll"));
+}
+
+#[tokio::test]
+async fn test_many_to_many_helpers() {
+    let conn = Builder::new_local(":memory:").build().await.unwrap().connect().unwrap();
+    let schemas = [TableSchema::of::<MemberTable>(), TableSchema::of::<RoleTable>(), MemberTable::roles().schema()];
+    Migrator::migrate_all(&conn, &schemas).await.unwrap();
+
+    let alice = MemberChangeSet { name: set("alice".to_string()), ..Default::default() }.insert(&conn).await.unwrap();
+    let bob = MemberChangeSet { name: set("bob".to_string()), ..Default::default() }.insert(&conn).await.unwrap();
+    let mut roles = Vec::new();
+    for name in ["admin", "editor", "viewer"] {
+        roles.push(RoleChangeSet { name: set(name.to_string()), ..Default::default() }.insert(&conn).await.unwrap());
+    }
+    let names = |roles: Vec<Role>| roles.into_iter().map(|role| role.name).collect::<Vec<_>>();
+
+    assert!(alice.add_role(&conn, roles[0].id).await.unwrap());
+    assert!(!alice.add_role(&conn, roles[0].id).await.unwrap());
+    assert!(alice.add_role(&conn, roles[1].id).await.unwrap());
+    bob.add_role(&conn, roles[1].id).await.unwrap();
+    let alice_roles = alice.roles().order_by_asc(RoleColumn::Id).all(&conn).await.unwrap();
+    assert_eq!(names(alice_roles), ["admin", "editor"]);
+
+    assert!(alice.remove_role(&conn, roles[0].id).await.unwrap());
+    assert!(!alice.remove_role(&conn, roles[0].id).await.unwrap());
+    assert_eq!(names(alice.roles().all(&conn).await.unwrap()), ["editor"]);
+
+    alice.set_roles(&conn, vec![roles[2].id, roles[1].id]).await.unwrap();
+    assert_eq!(names(alice.roles().order_by_asc(RoleColumn::Id).all(&conn).await.unwrap()), ["editor", "viewer"]);
+    assert_eq!(names(bob.roles().all(&conn).await.unwrap()), ["editor"]);
+
+    let editors = MemberTable::find()
+        .with_roles()
+        .filter(Condition::eq(RoleColumn::Name.of("roles"), "editor"))
+        .order_by_asc(MemberColumn::Id.of("member"))
+        .all(&conn)
+        .await
+        .unwrap();
+    assert_eq!(editors, vec![alice.clone(), bob.clone()]);
+
+    bob.set_roles(&conn, Vec::<i64>::new()).await.unwrap();
+    assert!(bob.roles().all(&conn).await.unwrap().is_empty());
+    assert_eq!(MemberTable::find().with_roles().count(&conn).await.unwrap(), 2);
+
+    bob.add_role(&conn, roles[0].id).await.unwrap();
+    let deleted =
+        DeleteCascade::<MemberTable>::by_id(alice.id).detach(&MemberTable::roles()).exec(&conn).await.unwrap();
+    assert_eq!(deleted, 1);
+    let mut rows = conn.query("SELECT member_id FROM member_roles", ()).await.unwrap();
+    assert_eq!(rows.next().await.unwrap().unwrap().get_value(0).unwrap(), turso::Value::Integer(bob.id));
+    assert!(rows.next().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_tree_helpers() {
+    let conn = connect().await;
+    Migrator::migrate::<CategoryTable>(&conn).await.unwrap();
+
+    let mut categories: Vec<Category> = Vec::new();
+    for (name, parent) in
+        [("root", None), ("books", Some(0)), ("fiction", Some(1)), ("poetry", Some(1)), ("music", Some(0))]
+    {
+        let parent_id = parent.map(|idx: usize| categories[idx].id);
+        let change_set =
+            CategoryChangeSet { parent_id: set(parent_id), name: set(name.to_string()), ..Default::default() };
+        categories.push(change_set.insert(&conn).await.unwrap());
+    }
+    let names = |categories: Vec<Category>| categories.into_iter().map(|category| category.name).collect::<Vec<_>>();
+    let [root, books, fiction, _, music] = categories.as_slice() else { unreachable!() };
+
+    assert_eq!(names(CategoryTable::roots().all(&conn).await.unwrap()), ["root"]);
+    assert_eq!(
+        names(books.children().order_by_asc(CategoryColumn::Id).all(&conn).await.unwrap()),
+        ["fiction", "poetry"]
+    );
+    assert_eq!(names(fiction.ancestors(&conn).await.unwrap()), ["books", "root"]);
+    assert!(root.ancestors(&conn).await.unwrap().is_empty());
+    assert_eq!(names(root.descendants(&conn).await.unwrap()), ["books", "music", "fiction", "poetry"]);
+
+    assert!(books.move_subtree(&conn, Some(fiction)).await.is_err());
+    assert!(books.move_subtree(&conn, Some(books)).await.is_err());
+    assert_eq!(books.move_subtree(&conn, Some(music)).await.unwrap(), 1);
+    assert_eq!(names(fiction.ancestors(&conn).await.unwrap()), ["books", "music", "root"]);
+
+    let books = CategoryTable::find_by_id(books.id).one(&conn).await.unwrap().unwrap();
+    books.move_subtree(&conn, None).await.unwrap();
+    assert_eq!(
+        names(CategoryTable::roots().order_by_asc(CategoryColumn::Id).all(&conn).await.unwrap()),
+        ["root", "books"]
+    );
+    assert_eq!(names(books.descendants(&conn).await.unwrap()), ["fiction", "poetry"]);
+}
+
+#[tokio::test]
+async fn test_case_insensitive_lookup() {
+    let conn = connect().await;
+    let user = UserChangeSet { email: set(Some("Alice@Example.com".to_string())), ..new_user("Alice") }
+        .insert(&conn)
+        .await
+        .unwrap();
+
+    let found =
+        UserTable::find().filter(Condition::eq_ignore_case(UserColumn::Email, "alice@example.COM")).one(&conn).await;
+    assert_eq!(found.unwrap(), Some(user.clone()));
+
+    let found = UserTable::find().filter(Condition::like_ignore_case(UserColumn::Name, "ALI%")).all(&conn).await;
+    assert_eq!(found.unwrap(), vec![user]);
+
+    let exact = UserTable::find().filter(Condition::eq(UserColumn::Email, "alice@example.com")).one(&conn).await;
+    assert_eq!(exact.unwrap(), None);
+
+    let collated = |condition: Condition| UserTable::find().filter(condition.collate(Collation::NoCase)).count(&conn);
+    assert_eq!(collated(Condition::between(UserColumn::Email, "alice@a", "ALICE@Z")).await.unwrap(), 1);
+    assert_eq!(collated(Condition::between(UserColumn::Email, "ALICE@F", "alice@z")).await.unwrap(), 0);
+    assert_eq!(collated(Condition::is_in(UserColumn::Email, vec!["ALICE@EXAMPLE.COM", "bob"])).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_null_safe_and_literal_conditions() {
+    let conn = connect().await;
+    let with_email =
+        UserChangeSet { email: set(Some("a@example.com".to_string())), ..new_user("a") }.insert(&conn).await.unwrap();
+    let without_email = new_user("b").insert(&conn).await.unwrap();
+
+    let found = UserTable::find().filter(Condition::is(UserColumn::Email, None::<String>)).all(&conn).await.unwrap();
+    assert_eq!(found, vec![without_email]);
+
+    let found = UserTable::find().filter(Condition::is(UserColumn::Email, Some("a@example.com"))).all(&conn).await;
+    assert_eq!(found.unwrap(), vec![with_email]);
+
+    assert_eq!(UserTable::find().filter(Condition::always_false()).count(&conn).await.unwrap(), 0);
+    assert_eq!(UserTable::find().filter(Condition::always_true()).count(&conn).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_condition_builder_from_query_string() {
+    let conn = connect().await;
+    new_user("alice").insert(&conn).await.unwrap();
+    let bob = new_user("bob").insert(&conn).await.unwrap();
+
+    let query = [("name__starts_with", "b"), ("state", "active")];
+    let condition = ConditionBuilder::<UserTable>::new()
+        .allow_columns([UserColumn::Name, UserColumn::State])
+        .allow_operators([FilterOperator::Eq, FilterOperator::StartsWith])
+        .filters(query)
+        .unwrap()
+        .build();
+
+    assert_eq!(UserTable::find().filter(condition).all(&conn).await.unwrap(), vec![bob]);
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn test_query_spec_compiles_whitelisted_select() {
+    let conn = connect().await;
+    for name in ["alice", "bob", "bella", "brian"] {
+        new_user(name).insert(&conn).await.unwrap();
+    }
+
+    let policy = QueryPolicy::<UserTable>::new()
+        .allow_filter_columns([UserColumn::Name, UserColumn::State])
+        .allow_operators([FilterOperator::Eq, FilterOperator::StartsWith])
+        .allow_sort_columns([UserColumn::Name, UserColumn::Id])
+        .max_per_page(2);
+
+    let spec: QuerySpec = serde_json::from_value(serde_json::json!({
+        "filters": [["name", "starts_with", "b"], ["state", "eq", "active"]],
+        "sort": ["-name"],
+        "page": 2,
+        "per_page": 2,
+    }))
+    .unwrap();
+    let names: Vec<_> =
+        spec.compile(&policy).unwrap().all(&conn).await.unwrap().into_iter().map(|user| user.name).collect();
+    assert_eq!(names, ["bella"]);
+
+    let everyone = QuerySpec::new().sort("id").compile(&QueryPolicy::<UserTable>::new()).unwrap();
+    assert_eq!(everyone.count(&conn).await.unwrap(), 4);
+
+    let rejected = [
+        QuerySpec::new().filter("email", "eq", "a@example.com"),
+        QuerySpec::new().filter("name", "like", "%"),
+        QuerySpec::new().filter("name; DROP TABLE user", "eq", "a"),
+        QuerySpec::new().sort("state"),
+        QuerySpec::new().sort("-name desc"),
+        QuerySpec::new().page(1, 3),
+        QuerySpec::new().page(0, 2),
+    ];
+    for spec in rejected {
+        assert!(spec.compile(&policy).is_err(), "{:?}", spec);
+    }
+}
+
+#[tokio::test]
+async fn test_keyset_pagination() {
+    let conn = connect().await;
+    for name in ["a", "b", "c", "d", "e"] {
+        new_user(name).insert(&conn).await.unwrap();
+    }
+
+    let mut names = Vec::new();
+    let mut cursor: Option<Cursor> = None;
+    loop {
+        let mut query = UserTable::find().cursor_by(UserColumn::Id).first(2);
+        if let Some(token) = cursor.as_ref().map(Cursor::encode) {
+            query = query.after(Cursor::decode(&token).unwrap());
+        }
+
+        let page = query.page(&conn).await.unwrap();
+        names.extend(page.items.into_iter().map(|user| user.name));
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(names, vec!["a", "b", "c", "d", "e"]);
+
+    let page = UserTable::find().cursor_by_desc(UserColumn::Name).after("c").first(10).page(&conn).await.unwrap();
+    let names: Vec<String> = page.items.into_iter().map(|user| user.name).collect();
+    assert_eq!(names, vec!["b", "a"]);
+    assert!(page.next_cursor.is_none());
+}
+
+#[tokio::test]
+async fn test_order_by_nulls_last() {
+    let conn = connect().await;
+    new_user("no-email").insert(&conn).await.unwrap();
+    UserChangeSet { email: set(Some("b@example.com".to_string())), ..new_user("b") }.insert(&conn).await.unwrap();
+    UserChangeSet { email: set(Some("a@example.com".to_string())), ..new_user("a") }.insert(&conn).await.unwrap();
+
+    let names = |users: Vec<User>| users.into_iter().map(|user| user.name).collect::<Vec<_>>();
+
+    let users = UserTable::find().order_by((UserColumn::Email, Order::Asc)).all(&conn).await.unwrap();
+    assert_eq!(names(users), vec!["no-email", "a", "b"]);
+
+    let users = UserTable::find()
+        .order_by((UserColumn::Email, Order::Asc, Nulls::Last))
+        .order_by((UserColumn::Id, Order::Asc))
+        .all(&conn)
+        .await
+        .unwrap();
+    assert_eq!(names(users), vec!["a", "b", "no-email"]);
+}
+
+#[tokio::test]
+async fn test_select_into_model() {
+    let conn = connect().await;
+    let user =
+        UserChangeSet { email: set(Some("a@example.com".to_string())), ..new_user("a") }.insert(&conn).await.unwrap();
+    new_user("b").insert(&conn).await.unwrap();
+
+    let summaries = UserTable::find()
+        .columns(vec![UserColumn::Id, UserColumn::Email])
+        .order_by_asc(UserColumn::Id)
+        .into_model::<UserSummary>()
+        .all(&conn)
+        .await
+        .unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[0], UserSummary { id: user.id, email: Some("a@example.com".to_string()) });
+    assert_eq!(summaries[1].email, None);
+
+    let summary = UserTable::find()
+        .filter(Condition::eq(UserColumn::Name, "b"))
+        .columns(vec![UserColumn::Id, UserColumn::Email])
+        .into_model::<UserSummary>()
+        .one(&conn)
+        .await
+        .unwrap();
+    assert!(summary.is_some_and(|summary| summary.email.is_none()));
+}
+
+#[tokio::test]
+async fn test_change_set_diff_writes_only_changed_columns() {
+    let conn = connect().await;
+    let original = new_user("erin").insert(&conn).await.unwrap();
+
+    let mut edited = original.clone();
+    edited.name = "erin b.".to_string();
+
+    let change_set = edited.clone().into_change_set_diff(&original);
+    assert_eq!(change_set.changed_columns(), vec![UserColumn::Name]);
+    assert!(original.clone().into_change_set_diff(&original).changed_columns().is_empty());
+
+    // A concurrent write to another column must survive the diff update.
+    Update::<UserTable>::many()
+        .set(UserColumn::State, "banned")
+        .filter(Condition::eq(UserColumn::Id, original.id))
+        .exec(&conn)
+        .await
+        .unwrap();
+
+    let updated = change_set.update(&conn).await.unwrap();
+    assert_eq!(updated.name, "erin b.");
+    assert_eq!(updated.state, "banned");
+}
+
+#[tokio::test]
+async fn test_field_codec_module() {
+    let conn = connect().await;
+    Migrator::migrate::<DocumentTable>(&conn).await.unwrap();
+
+    let tags = Tags(vec!["rust".to_string(), "orm".to_string()]);
+    let document = DocumentChangeSet { tags: set(tags.clone()), ..Default::default() }.insert(&conn).await.unwrap();
+    assert_eq!(document.tags, tags);
+
+    let mut rows = conn.query("SELECT tags FROM document", ()).await.unwrap();
+    let stored = rows.next().await.unwrap().unwrap().get_value(0).unwrap();
+    assert_eq!(stored, Value::Text("rust,orm".to_string()));
+
+    let mut edited = document.clone();
+    edited.tags.0.push("sql".to_string());
+    let updated = edited.into_change_set_diff(&document).update(&conn).await.unwrap();
+    assert_eq!(updated.tags.0, vec!["rust", "orm", "sql"]);
+}
+
+#[tokio::test]
+async fn test_u64_beyond_i64_round_trips_losslessly() {
+    let conn = connect().await;
+    Migrator::migrate::<AccountTable>(&conn).await.unwrap();
+
+    for external_id in [1, u64::MAX] {
+        AccountChangeSet { external_id: set(external_id), ..Default::default() }.insert(&conn).await.unwrap();
+    }
+
+    let accounts = AccountTable::find().order_by((AccountColumn::ExternalId, Order::Desc)).all(&conn).await.unwrap();
+    let ids: Vec<u64> = accounts.iter().map(|account| account.external_id).collect();
+    assert_eq!(ids, vec![u64::MAX, 1]);
+
+    let mut rows = conn.query("SELECT -1", ()).await.unwrap();
+    let negative = rows.next().await.unwrap().unwrap().get_value(0).unwrap();
+    assert_eq!(u64::from_value(negative).unwrap_err().kind(), ErrorKind::Conversion);
+}
+
+#[tokio::test]
+async fn test_generated_primary_key() {
+    let conn = connect().await;
+    Migrator::migrate::<TicketTable>(&conn).await.unwrap();
+
+    let ticket =
+        TicketChangeSet { title: set("Broken build".to_string()), ..Default::default() }.insert(&conn).await.unwrap();
+    assert!(ticket.code.starts_with("T-"));
+
+    let explicit = TicketChangeSet { code: set("MANUAL".to_string()), title: set("Typo".to_string()) }
+        .insert(&conn)
+        .await
+        .unwrap();
+    assert_eq!(explicit.code, "MANUAL");
+
+    let batch = (0..2).map(|i| TicketChangeSet { title: set(format!("Batch {}", i)), ..Default::default() });
+    Insert::<TicketTable>::empty().add_many(batch).exec(&conn).await.unwrap();
+
+    let codes: Vec<String> = TicketTable::find().all(&conn).await.unwrap().into_iter().map(|t| t.code).collect();
+    assert_eq!(codes.len(), 4);
+    assert!(codes.iter().all(|code| code == "MANUAL" || code.starts_with("T-")));
+}
+
+#[tokio::test]
+async fn test_claim_rows_with_limited_update() {
+    let conn = connect().await;
+    Migrator::migrate::<JobTable>(&conn).await.unwrap();
+    for _ in 0..5 {
+        JobChangeSet::default().insert(&conn).await.unwrap();
+    }
+
+    let claim = |worker: &'static str, limit: usize| {
+        Update::<JobTable>::many()
+            .set(JobColumn::Worker, worker)
+            .filter(Condition::is_null(JobColumn::Worker))
+            .limit(limit)
+            .exec_with_returning_all(&conn)
+    };
+
+    let first = claim("a", 2).await.unwrap();
+    let second = claim("b", 2).await.unwrap();
+    assert_eq!(first.len(), 2);
+    assert_eq!(second.len(), 2);
+    assert!(first.iter().all(|job| job.worker.as_deref() == Some("a")));
+    assert!(second.iter().all(|job| job.worker.as_deref() == Some("b") && !first.iter().any(|f| f.id == job.id)));
+
+    assert_eq!(claim("c", 10).await.unwrap().len(), 1);
+    assert!(claim("d", 10).await.unwrap().is_empty());
+
+    let released =
+        Update::<JobTable>::many().set(JobColumn::Worker, None::<String>).limit(3).exec(&conn).await.unwrap();
+    assert_eq!(released, 3);
+
+    let oldest = Update::<JobTable>::many()
+        .set(JobColumn::Worker, "e")
+        .order_by((JobColumn::Id, Order::Asc))
+        .limit(1)
+        .exec_with_returning(&conn)
+        .await
+        .unwrap();
+    assert_eq!((oldest.id, oldest.worker.as_deref()), (1, Some("e")));
+}
+
+#[tokio::test]
+async fn test_bounded_update_and_delete() {
+    let conn = connect().await;
+    Migrator::migrate::<JobTable>(&conn).await.unwrap();
+    for _ in 0..6 {
+        JobChangeSet::default().insert(&conn).await.unwrap();
+    }
+
+    let updated = Update::<JobTable>::many()
+        .set(JobColumn::Worker, "newest")
+        .order_by((JobColumn::Id, Order::Desc))
+        .limit(2)
+        .exec(&conn)
+        .await
+        .unwrap();
+    assert_eq!(updated, 2);
+
+    let newest = JobTable::find().filter(Condition::eq(JobColumn::Worker, "newest")).all(&conn).await.unwrap();
+    assert_eq!(newest.iter().map(|job| job.id).collect::<Vec<_>>(), vec![5, 6]);
+
+    let deleted = Delete::<JobTable>::new().order_by((JobColumn::Id, Order::Asc)).limit(3).exec(&conn).await.unwrap();
+    assert_eq!(deleted, 3);
+
+    let deleted =
+        Delete::<JobTable>::new().filter(Condition::is_null(JobColumn::Worker)).limit(5).exec(&conn).await.unwrap();
+    assert_eq!(deleted, 1);
+
+    let remaining = JobTable::find().all(&conn).await.unwrap();
+    assert_eq!(remaining.iter().map(|job| job.id).collect::<Vec<_>>(), vec![5, 6]);
+
+    let removed = Delete::<JobTable>::new()
+        .order_by((JobColumn::Id, Order::Desc))
+        .limit(1)
+        .exec_with_returning(&conn)
+        .await
+        .unwrap();
+    assert_eq!(removed.iter().map(|job| job.id).collect::<Vec<_>>(), vec![6]);
+}
+
+#[tokio::test]
+async fn test_builders_run_against_mock_connection() {
+    let conn = MockConnection::new();
+    conn.push_rows(vec![vec![
+        Value::Integer(1),
+        Value::Text("alice".to_string()),
+        Value::Text("active".to_string()),
+        Value::Null,
+    ]]);
+
+    let user = new_user("alice").insert(&conn).await.unwrap();
+    assert_eq!(user, User { id: 1, name: "alice".to_string(), state: "active".to_string(), email: None });
+
+    let found = UserTable::find().filter(Condition::eq(UserColumn::Id, 1)).one(&conn).await.unwrap();
+    assert_eq!(found, None);
+
+    let statements = conn.statements();
+    assert_eq!(statements[0].sql, "INSERT INTO user (name) VALUES (?) RETURNING id, name, state, email");
+    assert_eq!(statements[0].params, vec![Value::Text("alice".to_string())]);
+    assert_eq!(statements[1].sql, "SELECT id, name, state, email FROM user WHERE (id = ?) LIMIT 1");
+}
+
+#[tokio::test]
+async fn test_test_db_fixture() {
+    let db = TestDb::new(&[TableSchema::of::<UserTable>(), TableSchema::of::<ProductTable>()]).await.unwrap();
+    new_user("alice").insert(db.conn()).await.unwrap();
+
+    let other = db.connect().unwrap();
+    assert_eq!(UserTable::find().count(&other).await.unwrap(), 1);
+
+    let fresh = TestDb::new(&[TableSchema::of::<UserTable>()]).await.unwrap();
+    assert_eq!(UserTable::find().count(&*fresh).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_retry_policy_waits_out_locked_database() {
+    let dir = std::env::temp_dir().join(format!("tursorm_retry_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("retry.db");
+
+    let policy = RetryPolicy::new(50, std::time::Duration::from_millis(5));
+    let db = Builder::new_local(path.to_str().unwrap()).retry_policy(policy).build().await.unwrap();
+    let writer = db.clone().connect().unwrap();
+    Migrator::migrate::<UserTable>(&writer).await.unwrap();
+    let contender = db.connect().unwrap();
+    assert_eq!(contender.retry_policy(), policy);
+
+    writer.execute("BEGIN IMMEDIATE", ()).await.unwrap();
+    new_user("first").insert_exec(&writer).await.unwrap();
+
+    let release = tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        writer.execute("COMMIT", ()).await.unwrap();
+        writer
+    });
+
+    new_user("second").insert_exec(&contender).await.unwrap();
+    let writer = release.await.unwrap();
+    assert_eq!(UserTable::find().count(&contender).await.unwrap(), 2);
+
+    writer.execute("BEGIN IMMEDIATE", ()).await.unwrap();
+    let release = tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        writer.execute("COMMIT", ()).await.unwrap();
+    });
+
+    let mut rows = contender.query("INSERT INTO user (name) VALUES ('third') RETURNING name", ()).await.unwrap();
+    assert_eq!(rows.next().await.unwrap().unwrap().get::<String>(0).unwrap(), "third");
+    assert!(rows.next().await.unwrap().is_none());
+    release.await.unwrap();
+    assert_eq!(UserTable::find().count(&contender).await.unwrap(), 3);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn test_attached_database_tables() {
+    let dir = std::env::temp_dir().join(format!("tursorm_attach_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("archive.db");
+    {
+        let archive = Builder::new_local(path.to_str().unwrap()).build().await.unwrap().connect().unwrap();
+        Migrator::migrate::<UserTable>(&archive).await.unwrap();
+        new_user("archived").insert_exec(&archive).await.unwrap();
+        archive.close().await.unwrap();
+    }
+
+    let conn = connect().await;
+    new_user("live").insert_exec(&conn).await.unwrap();
+    conn.attach(path.to_str().unwrap(), "archive").await.unwrap();
+
+    let archived = ArchivedUserTable::find().all(&conn).await.unwrap();
+    assert_eq!(archived.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), vec!["archived"]);
+    assert_eq!(UserTable::find().with_schema("archive").count(&conn).await.unwrap(), 1);
+    assert_eq!(UserTable::find().one(&conn).await.unwrap().unwrap().name, "live");
+
+    assert_eq!(
+        ArchivedUserTable::find().filter(Condition::eq(ArchivedUserColumn::Id, 1)).build().0,
+        "SELECT id, name, state, email FROM archive.user WHERE (id = ?)"
+    );
+    assert_eq!(Delete::<UserTable>::new().with_schema("archive").build().0, "DELETE FROM archive.user");
+    assert_eq!(
+        Insert::<UserTable>::new(new_user("x")).with_schema("archive").build().unwrap().0,
+        "INSERT INTO archive.user (name) VALUES (?)"
+    );
+    assert!(conn.attach(path.to_str().unwrap(), "archive; DROP TABLE user").await.is_err());
+
+    conn.detach("archive").await.unwrap();
+    assert!(ArchivedUserTable::find().all(&conn).await.is_err());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn test_loader_inserts_in_batches() {
+    let conn = connect().await;
+    let observer = Arc::new(RecordingObserver::default());
+    let conn = conn.with_metrics_observer(observer.clone());
+
+    let users: Vec<UserChangeSet> = (0..1200).map(|i| new_user(&format!("user{}", i))).collect();
+    let loaded = tursorm::loader::Loader::<UserTable>::from_iter(users).batch_size(500).load(&conn).await.unwrap();
+    assert_eq!(loaded, 1200);
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 1200);
+    let inserts = observer.queries.lock().unwrap().iter().filter(|(kind, ..)| *kind == StatementKind::Insert).count();
+    assert_eq!(inserts, 3);
+
+    let duplicates = vec![
+        UserChangeSet { email: set(Some("dup@example.com".to_string())), ..new_user("a") },
+        UserChangeSet { email: set(Some("dup@example.com".to_string())), ..new_user("b") },
+    ];
+    let err = tursorm::loader::Loader::<UserTable>::from_iter(duplicates).batch_size(1).load(&conn).await.unwrap_err();
+    assert!(err.is_unique_violation());
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 1200);
+}
+
+#[cfg(feature = "with-csv")]
+#[tokio::test]
+async fn test_loader_reads_csv_by_header() {
+    let conn = connect().await;
+    let csv = "email,name\nalice@example.com,alice\n,bob\n";
+
+    let loaded = tursorm::loader::Loader::<UserTable>::from_csv(std::io::Cursor::new(csv)).unwrap().load(&conn).await;
+    assert_eq!(loaded.unwrap(), 2);
+
+    let users = UserTable::find().order_by_asc(UserColumn::Id).all(&conn).await.unwrap();
+    assert_eq!(users[0].email.as_deref(), Some("alice@example.com"));
+    assert_eq!(users[1].email, None);
+    assert_eq!(users[1].state, "active");
+
+    let unknown = tursorm::loader::Loader::<UserTable>::from_csv(std::io::Cursor::new("nickname\nx\n"));
+    assert!(matches!(unknown.err(), Some(Error::ColumnNotFound(column)) if column == "nickname"));
+}
+
+#[cfg(any(feature = "with-json", feature = "with-arrays"))]
+#[tokio::test]
+async fn test_export_jsonl_streams_rows() {
+    let conn = connect().await;
+    new_user("alice").insert_exec(&conn).await.unwrap();
+    UserChangeSet { email: set(Some("b\"ob@example.com".to_string())), ..new_user("bob") }
+        .insert_exec(&conn)
+        .await
+        .unwrap();
+
+    let mut out = Vec::new();
+    let written = UserTable::find().order_by_asc(UserColumn::Id).export_jsonl(&conn, &mut out).await.unwrap();
+    assert_eq!(written, 2);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "{\"id\":1,\"name\":\"alice\",\"state\":\"active\",\"email\":null}\n\
+         {\"id\":2,\"name\":\"bob\",\"state\":\"active\",\"email\":\"b\\\"ob@example.com\"}\n"
+    );
+
+    let mut out = Vec::new();
+    UserTable::find().columns(vec![UserColumn::Name]).limit(1).export_jsonl(&conn, &mut out).await.unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "{\"name\":\"alice\"}\n");
+}
+
+#[cfg(feature = "with-csv")]
+#[tokio::test]
+async fn test_export_csv_round_trips_through_loader() {
+    let conn = connect().await;
+    new_user("alice").insert_exec(&conn).await.unwrap();
+    UserChangeSet { email: set(Some("bob@example.com".to_string())), ..new_user("bob, jr") }
+        .insert_exec(&conn)
+        .await
+        .unwrap();
+
+    let mut out = Vec::new();
+    let written = UserTable::find().order_by_asc(UserColumn::Id).export_csv(&conn, &mut out).await.unwrap();
+    assert_eq!(written, 2);
+    let csv = String::from_utf8(out).unwrap();
+    assert_eq!(csv, "id,name,state,email\n1,alice,active,\n2,\"bob, jr\",active,bob@example.com\n");
+
+    let copy = connect().await;
+    let loaded = tursorm::loader::Loader::<UserTable>::from_csv(std::io::Cursor::new(csv)).unwrap().load(&copy).await;
+    assert_eq!(loaded.unwrap(), 2);
+    assert_eq!(UserTable::find().all(&copy).await.unwrap(), UserTable::find().all(&conn).await.unwrap());
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn test_serde_records_and_partial_change_sets() {
+    let conn = connect().await;
+    Migrator::migrate::<ContactTable>(&conn).await.unwrap();
+
+    let contact = ContactChangeSet::from_json(serde_json::json!({ "full_name": "alice", "phone": "555-0100" }))
+        .unwrap()
+        .insert(&conn)
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&contact).unwrap(),
+        serde_json::json!({ "id": 1, "full_name": "alice", "phone": "555-0100" })
+    );
+
+    let patch = ContactChangeSet::from_json(serde_json::json!({ "id": contact.id, "phone": null })).unwrap();
+    assert!(patch.name.is_not_set());
+    assert_eq!(patch.phone, set(None));
+    assert_eq!(serde_json::to_value(&patch).unwrap(), serde_json::json!({ "id": 1, "phone": null }));
+
+    let updated = patch.update(&conn).await.unwrap();
+    assert_eq!((updated.name.as_str(), updated.phone), ("alice", None));
+
+    let parsed: Contact = serde_json::from_value(serde_json::json!({ "id": 2, "full_name": "bob" })).unwrap();
+    assert_eq!(parsed, Contact { id: 2, name: "bob".to_string(), phone: None, selected: false });
+    assert!(
+        ContactChangeSet::from_json(serde_json::json!({ "full_name": 5 })).unwrap_err().kind()
+            == ErrorKind::Serialization
+    );
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn test_apply_json_sets_only_whitelisted_columns() {
+    let conn = connect().await;
+    Migrator::migrate::<ContactTable>(&conn).await.unwrap();
+    let contact =
+        ContactChangeSet::from_json(serde_json::json!({ "full_name": "alice" })).unwrap().insert(&conn).await.unwrap();
+
+    let body = serde_json::json!({ "id": 99, "full_name": "alicia", "phone": "555-0100", "admin": true });
+    let mut patch = ContactChangeSet { id: set(contact.id), ..Default::default() };
+    let changed = patch.apply_json(&body, &[ContactColumn::Name, ContactColumn::Phone, ContactColumn::Name]).unwrap();
+    assert_eq!(changed, vec![ContactColumn::Name, ContactColumn::Phone]);
+    assert_eq!(patch.id, set(contact.id));
+
+    let updated = patch.update(&conn).await.unwrap();
+    assert_eq!((updated.id, updated.name.as_str(), updated.phone.as_deref()), (1, "alicia", Some("555-0100")));
+
+    let mut patch = ContactChangeSet::default();
+    assert!(patch.apply_json(&serde_json::json!({ "phone": null }), &[ContactColumn::Name]).unwrap().is_empty());
+    assert!(patch.apply_json(&serde_json::json!({ "full_name": 1, "phone": null }), ContactColumn::all()).is_err());
+    assert!(patch.phone.is_not_set());
+    assert!(patch.apply_json(&serde_json::json!([1]), &[ContactColumn::Name]).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_column_from_name_and_serde() {
+    assert_eq!(ContactColumn::from_name("full_name"), Some(ContactColumn::Name));
+    assert_eq!(ContactColumn::from_name("name"), None);
+    assert_eq!("phone".parse::<ContactColumn>().unwrap(), ContactColumn::Phone);
+    assert!(matches!("selected".parse::<ContactColumn>(), Err(Error::ColumnNotFound(name)) if name == "selected"));
+    assert_eq!(ContactColumn::iter().map(|column| column.name()).collect::<Vec<_>>(), ["id", "full_name", "phone"]);
+
+    assert_eq!(serde_json::to_value(ContactColumn::Name).unwrap(), serde_json::json!("full_name"));
+    let sort: Vec<ContactColumn> = serde_json::from_value(serde_json::json!(["phone", "id"])).unwrap();
+    assert_eq!(sort, vec![ContactColumn::Phone, ContactColumn::Id]);
+    assert!(serde_json::from_value::<ContactColumn>(serde_json::json!("admin")).is_err());
+}
+
+#[tokio::test]
+async fn test_encrypted_columns_round_trip() {
+    let conn = connect_with(Builder::new_memory_shared("patients").column_encryptor(ReversingEncryptor)).await;
+    Migrator::migrate::<PatientTable>(&conn).await.unwrap();
+
+    let patient = PatientChangeSet {
+        name: set("alice".to_string()),
+        ssn: set("123-45-6789".to_string()),
+        notes: set(None),
+        ..Default::default()
+    }
+    .insert(&conn)
+    .await
+    .unwrap();
+    assert_eq!((patient.ssn.as_str(), patient.notes.as_deref()), ("123-45-6789", None));
+
+    let mut rows = conn.query_rows("SELECT ssn, notes FROM patient", Vec::new()).await.unwrap();
+    let row = rows.next().await.unwrap().unwrap();
+    assert!(matches!(row.get_value(0).unwrap(), Value::Blob(ssn) if !ssn.windows(3).any(|w| w == b"123")));
+    assert_eq!(row.get_value(1).unwrap(), Value::Null);
+    drop(rows);
+
+    let updated =
+        PatientChangeSet { id: set(patient.id), notes: set(Some("allergic".to_string())), ..Default::default() }
+            .update(&conn)
+            .await
+            .unwrap();
+    assert_eq!(updated.notes.as_deref(), Some("allergic"));
+
+    Update::<PatientTable>::many().set(PatientColumn::Ssn, "987-65-4321").exec(&conn).await.unwrap();
+    let stored = PatientTable::find().one(&conn).await.unwrap().unwrap();
+    assert_eq!((stored.name.as_str(), stored.ssn.as_str()), ("alice", "987-65-4321"));
+
+    let concat = Expr::col(PatientColumn::Ssn).add(Expr::value(1));
+    assert!(Update::<PatientTable>::many().set_expr(PatientColumn::Ssn, concat).exec(&conn).await.is_err());
+
+    let plain = connect_with(Builder::new_memory_shared("patients")).await;
+    let err = PatientChangeSet { ssn: set("x".to_string()), ..Default::default() }.insert_exec(&plain).await;
+    assert!(err.unwrap_err().to_string().contains("`ssn` is encrypted"));
+    assert!(PatientTable::find().one(&plain).await.is_err());
+}
+
+#[tokio::test]
+async fn test_save_inserts_or_updates() {
+    let conn = connect().await;
+
+    let created = new_user("alice").save(&conn).await.unwrap();
+    assert_eq!(created.id, 1);
+
+    let renamed =
+        UserChangeSet { id: set(created.id), name: set("alicia".to_string()), ..Default::default() }.save(&conn).await;
+    assert_eq!(renamed.unwrap().name, "alicia");
+
+    let unchanged = UserChangeSet { id: set(created.id), ..Default::default() }.save(&conn).await.unwrap();
+    assert_eq!(unchanged.name, "alicia");
+
+    let explicit = UserChangeSet { id: set(10), ..new_user("bob") }.save(&conn).await.unwrap();
+    assert_eq!((explicit.id, explicit.name.as_str()), (10, "bob"));
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_truncate_table_resets_autoincrement() {
+    let conn = connect().await;
+    for name in ["a", "b", "c"] {
+        new_user(name).insert(&conn).await.unwrap();
+    }
+
+    assert_eq!(Migrator::truncate_table::<UserTable>(&conn, false).await.unwrap(), 3);
+    assert_eq!(new_user("d").insert(&conn).await.unwrap().id, 4);
+
+    assert_eq!(Migrator::truncate_table::<UserTable>(&conn, true).await.unwrap(), 1);
+    assert_eq!(new_user("e").insert(&conn).await.unwrap().id, 1);
+
+    UserTable::truncate().reset_autoincrement().exec(&conn).await.unwrap();
+    assert_eq!(UserTable::find().count(&conn).await.unwrap(), 0);
+    assert_eq!(new_user("f").insert(&conn).await.unwrap().id, 1);
+
+    let deleted = UserTable::truncate().reset_autoincrement().exec_with_returning(&conn).await.unwrap();
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(new_user("g").insert(&conn).await.unwrap().id, 1);
+}
+
+#[derive(Debug, PartialEq)]
+struct Cents(i64);
+
+#[tokio::test]
+async fn test_type_registry_converts_raw_query_values() {
+    let db = Builder::new_local(":memory:").build().await.unwrap();
+    let conn = db.clone().connect().unwrap();
+    conn.type_registry().register::<Cents>(|cents| Value::Integer(cents.0), |value| Ok(Cents(i64::from_value(value)?)));
+    conn.execute("CREATE TABLE prices (amount INTEGER)", ()).await.unwrap();
+
+    let other = db.connect().unwrap();
+    assert!(other.type_registry().is_registered::<Cents>());
+    let amount = other.type_registry().encode(&Cents(250)).unwrap();
+    other.execute("INSERT INTO prices (amount) VALUES (?)", vec![amount]).await.unwrap();
+
+    let mut rows = conn.query("SELECT amount FROM prices", ()).await.unwrap();
+    let value = rows.next().await.unwrap().unwrap().get_value(0).unwrap();
+    assert_eq!(value.decode::<Cents>(conn.type_registry()).unwrap(), Cents(250));
+}
+
+#[tokio::test]
+async fn test_notify_between_connections() {
+    let dir = std::env::temp_dir().join(format!("tursorm_notify_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("events.db");
+    let db = Builder::new_local(path.to_str().unwrap()).build().await.unwrap();
+    let (publisher, listener) = (db.clone().connect().unwrap(), db.connect().unwrap());
+
+    let first = Notify::publish(&publisher, "jobs", "created the table").await.unwrap();
+    let mut subscription = Notify::subscribe(&listener, "jobs").await.unwrap().poll_interval(Duration::from_millis(10));
+
+    let publish = async {
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        Notify::publish(&publisher, "other", "ignored").await.unwrap();
+        Notify::publish(&publisher, "jobs", "run").await.unwrap()
+    };
+    let (received, published) = tokio::join!(subscription.recv(), publish);
+
+    let received = received.unwrap();
+    assert_eq!((received.id, received.payload.as_str()), (published, "run"));
+    assert!(published > first);
+    assert!(subscription.try_recv().await.unwrap().is_empty());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
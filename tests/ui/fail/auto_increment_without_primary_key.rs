@@ -0,0 +1,11 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key)]
+    id:       i64,
+    #[tursorm(auto_increment)]
+    sequence: i64,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct Event {
+    #[tursorm(primary_key)]
+    id:         i64,
+    #[tursorm(timestamp_seconds, timestamp_millis)]
+    created_at: i64,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(discriminator = "type")]
+struct Event {
+    #[tursorm(primary_key, auto_increment)]
+    id:   i64,
+    kind: String,
+}
+
+fn main() {}
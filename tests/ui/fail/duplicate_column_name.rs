@@ -0,0 +1,12 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key)]
+    id:    i64,
+    #[tursorm(column_name = "name")]
+    title: String,
+    name:  String,
+}
+
+fn main() {}
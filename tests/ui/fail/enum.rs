@@ -0,0 +1,9 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+enum User {
+    Admin,
+    Guest,
+}
+
+fn main() {}
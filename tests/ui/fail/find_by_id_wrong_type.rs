@@ -0,0 +1,17 @@
+use tursorm::prelude::*;
+use tursorm::TableSelectExt;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key)]
+    id: i64,
+}
+
+// `find_by_id` takes `impl Into<PrimaryKeyType>`; the key type itself is what rejects a `String` id.
+fn find_user(id: <UserTable as TableTrait>::PrimaryKeyType) -> Select<UserTable> {
+    UserTable::find_by_id(id)
+}
+
+fn main() {
+    let _ = find_user("1".to_string());
+}
@@ -0,0 +1,29 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Email(String);
+
+impl IntoValue for Email {
+    fn into_value(self) -> Value {
+        Value::Text(self.0)
+    }
+}
+
+impl FromValue for Email {
+    fn from_value(value: Value) -> tursorm::Result<Self> {
+        String::from_value(value).map(Email)
+    }
+}
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key)]
+    id:    i64,
+    #[tursorm(unique)]
+    email: Email,
+}
+
+fn main() {
+    let _ = UserTable::find_by_email(Email("a@example.com".to_string()));
+    let _ = UserTable::find_by_email(42);
+}
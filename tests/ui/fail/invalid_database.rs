@@ -0,0 +1,10 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(database = "archive.main")]
+struct ArchivedUser {
+    #[tursorm(primary_key)]
+    id: i64,
+}
+
+fn main() {}
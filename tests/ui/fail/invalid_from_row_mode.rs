@@ -0,0 +1,15 @@
+use tursorm::prelude::*;
+
+#[derive(Debug, FromRow)]
+#[tursorm(from_row = "by_label")]
+struct UserSummary {
+    id: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct PositionalSummary {
+    #[tursorm(column_name = "user_id")]
+    id: i64,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct Post {
+    #[tursorm(primary_key)]
+    id:      i64,
+    #[tursorm(foreign_key, references = "main.user.id")]
+    user_id: i64,
+}
+
+fn main() {}
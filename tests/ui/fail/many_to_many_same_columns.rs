@@ -0,0 +1,10 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(many_to_many(name = "friends", related = "UserTable", through = "friendships"))]
+struct User {
+    #[tursorm(primary_key, auto_increment)]
+    id: i64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    id:   i64,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(polymorphic(owner_type))]
+struct Comment {
+    #[tursorm(primary_key)]
+    id:         i64,
+    owner_type: String,
+    owner_id:   i64,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(readonly)]
+struct DailyTotal {
+    #[tursorm(primary_key)]
+    day:   String,
+    total: i64,
+}
+
+fn main() {
+    let _ = DailyTotalChangeSet { total: set(1), ..Default::default() };
+}
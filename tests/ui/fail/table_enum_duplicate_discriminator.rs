@@ -0,0 +1,24 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(discriminator = "kind")]
+struct Event {
+    #[tursorm(primary_key, auto_increment)]
+    id:   i64,
+    kind: String,
+}
+
+#[derive(Clone, Debug, FromRow)]
+struct Click {
+    id: i64,
+}
+
+#[derive(Clone, Debug, TableEnum)]
+#[tursorm(table = "EventTable")]
+enum EventKind {
+    Click(Click),
+    #[tursorm(discriminator = "click")]
+    DoubleClick(Click),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(tree = "parent_id")]
+struct Category {
+    #[tursorm(primary_key, auto_increment)]
+    id:        i64,
+    parent_id: i64,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct User(i64, String);
+
+fn main() {}
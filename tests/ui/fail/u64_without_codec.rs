@@ -0,0 +1,10 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct Download {
+    #[tursorm(primary_key)]
+    id:    i64,
+    bytes: u64,
+}
+
+fn main() {}
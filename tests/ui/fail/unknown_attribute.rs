@@ -0,0 +1,9 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key, indexed)]
+    id: i64,
+}
+
+fn main() {}
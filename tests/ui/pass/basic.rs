@@ -0,0 +1,16 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key, auto_increment)]
+    id:    i64,
+    name:  String,
+    email: Option<String>,
+}
+
+fn main() {
+    assert_eq!(UserTable::table_name(), "user");
+    assert_eq!(UserTable::all_columns(), "id, name, email");
+    assert!(UserTable::primary_key_auto_increment());
+    assert!(UserColumn::Email.is_nullable());
+}
@@ -0,0 +1,19 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key, auto_increment)]
+    id:   i64,
+    name: String,
+}
+
+fn main() {
+    let change_set = UserChangeSet { name: set("alice".to_string()), ..Default::default() };
+    let (columns, _) = change_set.get_insert_columns_and_values();
+    assert_eq!(columns, vec!["name"]);
+
+    let record = User { id: 7, name: "bob".to_string() };
+    let change_set = record.into_change_set();
+    assert_eq!(change_set.get_primary_key_value(), Some(Value::Integer(7)));
+    assert_eq!(UserChangeSet::primary_key_column(), "id");
+}
@@ -0,0 +1,25 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(table_name = "accounts")]
+struct Account {
+    #[tursorm(primary_key, column_name = "account_id")]
+    id:     i64,
+    #[tursorm(unique, column_name = "email_address", renamed_from = "mail")]
+    email:  String,
+    #[tursorm(default = "'active'")]
+    status: String,
+    #[tursorm(default = "0", renamed_from = "points")]
+    score:  Option<f64>,
+}
+
+fn main() {
+    assert_eq!(AccountTable::table_name(), "accounts");
+    assert_eq!(AccountTable::primary_key().name(), "account_id");
+    assert!(!AccountTable::primary_key_auto_increment());
+    assert_eq!(AccountColumn::Email.name(), "email_address");
+    assert!(AccountColumn::Email.is_unique());
+    assert_eq!(AccountColumn::Email.renamed_from(), Some("mail"));
+    assert_eq!(AccountColumn::Status.default_value(), Some("'active'"));
+    assert_eq!(AccountColumn::Score.column_type(), ColumnType::Float);
+}
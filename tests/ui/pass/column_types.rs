@@ -0,0 +1,24 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct Sample {
+    #[tursorm(primary_key)]
+    id:     i32,
+    small:  i8,
+    count:  u32,
+    ratio:  f32,
+    flag:   bool,
+    data:   Vec<u8>,
+    maybe:  Option<Vec<u8>>,
+    label:  Option<String>,
+}
+
+fn main() {
+    assert_eq!(SampleColumn::Small.column_type(), ColumnType::Integer);
+    assert_eq!(SampleColumn::Count.column_type(), ColumnType::Integer);
+    assert_eq!(SampleColumn::Ratio.column_type(), ColumnType::Float);
+    assert_eq!(SampleColumn::Flag.column_type(), ColumnType::Integer);
+    assert_eq!(SampleColumn::Data.column_type(), ColumnType::Blob);
+    assert_eq!(SampleColumn::Maybe.column_type(), ColumnType::Blob);
+    assert_eq!(SampleTable::column_count(), 8);
+}
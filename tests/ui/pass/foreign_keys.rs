@@ -0,0 +1,28 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct Post {
+    #[tursorm(primary_key, auto_increment)]
+    id:          i64,
+    #[tursorm(foreign_key, references = "user", column_name = "author")]
+    author_id:   i64,
+    #[tursorm(foreign_key, references = "category.code", on_delete = "set_null", on_update = "cascade")]
+    category:    Option<String>,
+    #[tursorm(foreign_key, references = "post.id", on_delete = "restrict", on_update = "set_default", unique)]
+    replaces_id: Option<i64>,
+}
+
+fn main() {
+    let author = PostColumn::AuthorId.foreign_key().unwrap();
+    assert_eq!(PostColumn::AuthorId.name(), "author");
+    assert_eq!(author.table_name, "user");
+    assert_eq!(author.column_name, "id");
+
+    let category = PostColumn::Category.foreign_key().unwrap();
+    assert_eq!(category.column_name, "code");
+    assert!(matches!(category.on_delete, OnDelete::SetNull));
+    assert!(matches!(category.on_update, OnUpdate::Cascade));
+
+    assert!(PostColumn::ReplacesId.is_unique());
+    assert!(PostColumn::Id.foreign_key().is_none());
+}
@@ -0,0 +1,35 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key, auto_increment)]
+    id:    i64,
+    name:  String,
+    email: Option<String>,
+    bio:   String,
+}
+
+#[derive(Debug, FromRow)]
+struct UserSummary {
+    id:    i64,
+    email: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct Labeled<T: FromValue> {
+    label: T,
+}
+
+#[derive(Debug, FromRow)]
+#[tursorm(from_row = "by_name")]
+struct NamedSummary {
+    email:   Option<String>,
+    #[tursorm(column_name = "id")]
+    user_id: i64,
+}
+
+fn main() {
+    let _ = Select::<UserTable>::new().columns(vec![UserColumn::Id, UserColumn::Email]).into_model::<UserSummary>();
+    let _ = Select::<UserTable>::new().columns(vec![UserColumn::Name]).into_model::<Labeled<String>>();
+    let _ = Select::<UserTable>::new().columns(vec![UserColumn::Id, UserColumn::Email]).into_model::<NamedSummary>();
+}
@@ -0,0 +1,54 @@
+use tursorm::TableSelectExt;
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct Tag {
+    #[tursorm(primary_key, auto_increment)]
+    id:   i64,
+    name: String,
+}
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(many_to_many(name = "tags", related = "TagTable", through = "post_tags"))]
+#[tursorm(many_to_many(
+    name = "related_posts",
+    related = "PostTable",
+    through = "post_links",
+    related_column = "linked_id",
+    singular = "related_post"
+))]
+struct Post {
+    #[tursorm(primary_key, auto_increment)]
+    id:    i64,
+    title: String,
+}
+
+fn main() {
+    let tags = PostTable::tags();
+    assert_eq!((tags.through(), tags.local_column(), tags.related_column()), ("post_tags", "post_id", "tag_id"));
+
+    let (sql, _) = PostTable::find().with_tags().filter(Condition::eq(TagColumn::Name.of("tags"), "rust")).build();
+    assert_eq!(
+        sql,
+        "SELECT post.id, post.title FROM post INNER JOIN post_tags AS post_tags ON (post_tags.post_id = post.id) \
+         INNER JOIN tag AS tags ON (tags.id = post_tags.tag_id) WHERE (tags.name = ?)"
+    );
+
+    let post = Post { id: 7, title: "hello".to_string() };
+    let (sql, params) = post.tags().build();
+    assert_eq!(
+        sql,
+        "SELECT tag.id, tag.name FROM tag INNER JOIN post_tags AS post_tags ON (post_tags.tag_id = tag.id) \
+         WHERE (post_tags.post_id = ?)"
+    );
+    assert_eq!(params, vec![Value::Integer(7)]);
+
+    let (sql, _) = post.related_posts().build();
+    assert_eq!(
+        sql,
+        "SELECT post.id, post.title FROM post INNER JOIN post_links AS post_links ON (post_links.linked_id = post.id) \
+         WHERE (post_links.post_id = ?)"
+    );
+
+    let _ = post.add_related_post(&tursorm::test_utils::MockConnection::new(), 8);
+}
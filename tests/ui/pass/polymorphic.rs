@@ -0,0 +1,72 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct Post {
+    #[tursorm(primary_key, auto_increment)]
+    id:    i64,
+    title: String,
+}
+
+#[derive(Clone, Debug, Table)]
+struct Photo {
+    #[tursorm(primary_key, auto_increment)]
+    id:  i64,
+    url: String,
+}
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(polymorphic(commentable_type, commentable_id))]
+struct Comment {
+    #[tursorm(primary_key, auto_increment)]
+    id:               i64,
+    #[tursorm(column_name = "owner_kind")]
+    commentable_type: String,
+    commentable_id:   i64,
+    body:             String,
+}
+
+#[derive(Clone, Debug, Table)]
+struct Ledger {
+    #[tursorm(primary_key, with = "tursorm::codec::i128_text")]
+    id: i128,
+}
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(polymorphic(subject_type, subject_id))]
+struct Note {
+    #[tursorm(primary_key, auto_increment)]
+    id:           i64,
+    subject_type: String,
+    #[tursorm(with = "tursorm::codec::i128_text")]
+    subject_id:   i128,
+}
+
+fn main() {
+    let post = Post { id: 3, title: "hello".to_string() };
+    let photo = Photo { id: 3, url: "cat.png".to_string() };
+
+    let condition = Comment::of(&post);
+    assert_eq!(condition.sql(), "(owner_kind = ?) AND (commentable_id = ?)");
+    assert_eq!(condition.values(), &[Value::Text("post".to_string()), Value::Integer(3)]);
+
+    let condition = CommentTable::of_any(&[post.clone(), Post { id: 4, title: "again".to_string() }]);
+    assert_eq!(condition.sql(), "(owner_kind = ?) AND (commentable_id IN (?, ?))");
+
+    let (sql, _) = CommentTable::find_for(&photo).build();
+    assert_eq!(sql, "SELECT id, owner_kind, commentable_id, body FROM comment WHERE ((owner_kind = ?) AND (commentable_id = ?))");
+
+    let comment = Comment { id: 1, commentable_type: "post".to_string(), commentable_id: 3, body: "nice".to_string() };
+    assert!(CommentTable::belongs_to(&comment, &post));
+    assert!(!CommentTable::belongs_to(&comment, &photo));
+
+    let change_set = CommentChangeSet { body: set("wow".to_string()), ..Default::default() }.with_owner(&photo).unwrap();
+    assert_eq!(change_set.commentable_type, FieldValue::Set("photo".to_string()));
+    assert_eq!(change_set.commentable_id, FieldValue::Set(3));
+
+    let ledger = Ledger { id: i128::MAX };
+    let note = NoteChangeSet::default().with_owner(&ledger).unwrap();
+    assert_eq!(note.subject_id, FieldValue::Set(i128::MAX));
+    let note = Note { id: 1, subject_type: "ledger".to_string(), subject_id: i128::MAX };
+    assert_eq!(NoteTable::owner_of(&note).1, Value::Text(i128::MAX.to_string()));
+    assert!(NoteTable::belongs_to(&note, &ledger));
+}
@@ -0,0 +1,18 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(readonly, no_column_enum_display, serde)]
+struct DailyTotal {
+    #[tursorm(primary_key)]
+    day:   String,
+    total: i64,
+}
+
+fn main() {
+    const { assert!(DailyTotalTable::READ_ONLY) };
+    assert_eq!(DailyTotalColumn::Total.name(), "total");
+
+    let change_set = ReadOnlyChangeSet::<DailyTotalTable>::default();
+    assert!(change_set.get_insert_columns_and_values().0.is_empty());
+    assert_eq!(ReadOnlyChangeSet::<DailyTotalTable>::primary_key_column(), "day");
+}
@@ -0,0 +1,17 @@
+use tursorm::prelude::*;
+
+// A local `std` module must not capture the paths the derive emits.
+mod std {}
+
+#[derive(Clone, Debug, Table)]
+struct Tag {
+    #[tursorm(primary_key, auto_increment)]
+    id:   i64,
+    name: String,
+}
+
+fn main() {
+    let column: TagColumn = "name".parse().unwrap();
+    assert_eq!(column, TagColumn::Name);
+    assert_eq!(column.to_string(), "name");
+}
@@ -0,0 +1,24 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key, auto_increment)]
+    id:           i64,
+    #[tursorm(skip)]
+    display_name: String,
+    name:         String,
+    #[tursorm(skip)]
+    cached:       Option<Vec<u8>>,
+}
+
+fn main() {
+    assert_eq!(UserTable::all_columns(), "id, name");
+    assert_eq!(UserTable::column_count(), 2);
+    assert_eq!(UserColumn::all().len(), 2);
+
+    let change_set = UserChangeSet { name: set("a".to_string()), ..Default::default() };
+    assert_eq!(change_set.get_insert_columns_and_values().0, vec!["name"]);
+
+    let user = User { id: 1, display_name: "A".to_string(), name: "a".to_string(), cached: None };
+    let _ = UserChangeSet::from(user);
+}
@@ -0,0 +1,59 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(discriminator = "kind")]
+struct Event {
+    #[tursorm(primary_key, auto_increment)]
+    id:   i64,
+    kind: String,
+    x:    Option<i64>,
+    url:  Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+#[tursorm(from_row = "by_name")]
+struct Click {
+    id: i64,
+    x:  i64,
+}
+
+#[derive(Clone, Debug, PartialEq, FromRow)]
+#[tursorm(from_row = "by_name")]
+struct PageView {
+    id:  i64,
+    url: String,
+}
+
+impl From<Click> for EventChangeSet {
+    fn from(click: Click) -> Self {
+        EventChangeSet { x: set(Some(click.x)), ..Default::default() }
+    }
+}
+
+impl From<PageView> for EventChangeSet {
+    fn from(view: PageView) -> Self {
+        EventChangeSet { url: set(Some(view.url)), ..Default::default() }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, TableEnum)]
+#[tursorm(table = "EventTable")]
+enum EventKind {
+    Click(Click),
+    #[tursorm(discriminator = "view")]
+    PageView(PageView),
+}
+
+fn main() {
+    assert_eq!(EventTable::discriminator_column(), Some(EventColumn::Kind));
+    assert_eq!(EventKind::discriminators(), &["click", "view"]);
+
+    let click = EventKind::Click(Click { id: 0, x: 3 });
+    assert_eq!(click.discriminator(), "click");
+    let change_set = click.into_change_set().unwrap();
+    assert_eq!(change_set.kind, FieldValue::Set("click".to_string()));
+    assert_eq!(change_set.x, FieldValue::Set(Some(3)));
+
+    let (sql, _) = EventKind::find().build();
+    assert_eq!(sql, "SELECT id, kind, x, url FROM event WHERE (kind IN (?, ?))");
+}
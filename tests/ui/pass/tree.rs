@@ -0,0 +1,51 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(tree = "manager_id")]
+struct Employee {
+    #[tursorm(primary_key, auto_increment)]
+    id:         i64,
+    #[tursorm(foreign_key, references = "employee.id")]
+    manager_id: Option<i64>,
+    name:       String,
+}
+
+mod hex {
+    use tursorm::prelude::*;
+
+    pub fn to_value(value: &Option<i64>) -> Value {
+        value.map_or(Value::Null, |value| Value::Text(format!("{:x}", value)))
+    }
+
+    pub fn from_value(value: Value) -> Result<Option<i64>> {
+        Option::<String>::from_value_opt(value)?
+            .map(|text| i64::from_str_radix(&text, 16).map_err(|_| Error::OutOfRange { target: "i64", value: text }))
+            .transpose()
+    }
+}
+
+#[derive(Clone, Debug, Table)]
+#[tursorm(tree = "parent")]
+struct Folder {
+    #[tursorm(primary_key, auto_increment)]
+    id:     i64,
+    #[tursorm(with = "hex")]
+    parent: Option<i64>,
+}
+
+fn main() {
+    let ceo = Employee { id: 1, manager_id: None, name: "ada".to_string() };
+    let cto = Employee { id: 2, manager_id: Some(1), name: "grace".to_string() };
+    assert_eq!(ceo.parent_key(), Value::Null);
+    assert_eq!(cto.parent_key(), Value::Integer(1));
+
+    let (sql, params) = ceo.children().build();
+    assert_eq!(sql, "SELECT id, manager_id, name FROM employee WHERE (manager_id = ?)");
+    assert_eq!(params, vec![Value::Integer(1)]);
+
+    let (sql, _) = EmployeeTable::roots().build();
+    assert_eq!(sql, "SELECT id, manager_id, name FROM employee WHERE (manager_id IS NULL)");
+
+    let folder = Folder { id: 2, parent: Some(255) };
+    assert_eq!(folder.parent_key(), Value::Text("ff".to_string()));
+}
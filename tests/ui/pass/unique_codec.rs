@@ -0,0 +1,19 @@
+use tursorm::prelude::*;
+
+#[derive(Clone, Debug, Table)]
+struct Account {
+    #[tursorm(primary_key, auto_increment)]
+    id:       i64,
+    #[tursorm(unique, with = "tursorm::codec::i128_text")]
+    big:      i128,
+    #[tursorm(unique, with = "tursorm::codec::u64_blob")]
+    checksum: u64,
+}
+
+fn main() {
+    let (_, params) = AccountTable::find_by_big(-1i128).build();
+    assert_eq!(params, vec![Value::Text("-1".to_string())]);
+
+    let (_, params) = AccountTable::find_by_checksum(7u64).build();
+    assert!(matches!(params.as_slice(), [Value::Blob(_)]));
+}
@@ -0,0 +1,14 @@
+use tursorm::prelude::*;
+use tursorm::TableSelectExt;
+
+#[derive(Clone, Debug, Table)]
+struct User {
+    #[tursorm(primary_key, unique)]
+    id:   i64,
+    name: String,
+}
+
+fn main() {
+    assert!(UserColumn::Id.is_unique());
+    let _ = UserTable::find_by_id(1);
+}
@@ -0,0 +1,792 @@
+//! The `#[tursorm(...)]` attribute receivers and their validation into `TableInfo`.
+
+use darling::FromDeriveInput;
+use darling::FromField;
+use darling::FromMeta;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::ToTokens;
+use quote::format_ident;
+use quote::quote;
+use syn::Type;
+
+use crate::types::is_option_type;
+use crate::types::is_wrapping_integer_type;
+
+// Not yet implemented, ignored
+#[derive(Debug, Clone, Copy, Default, FromMeta)]
+pub(crate) enum OnDelete {
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+    #[default]
+    None,
+}
+
+// Not yet implemented, ignored
+#[derive(Debug, Clone, Copy, Default, FromMeta)]
+pub(crate) enum OnUpdate {
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+    #[default]
+    None,
+}
+
+#[derive(Debug, FromField)]
+#[darling(attributes(tursorm), forward_attrs(doc))]
+pub(crate) struct FieldReceiver {
+    pub ident: Option<Ident>,
+    pub ty:    Type,
+    pub attrs: Vec<syn::Attribute>,
+
+    #[darling(default)]
+    pub primary_key: bool,
+
+    #[darling(default)]
+    pub auto_increment: bool,
+
+    #[darling(default)]
+    pub unique: bool,
+
+    #[darling(default)]
+    pub column_name: Option<String>,
+
+    #[darling(default)]
+    pub renamed_from: Option<String>,
+
+    #[darling(default)]
+    pub default: Option<String>,
+
+    #[darling(default)]
+    pub foreign_key: bool,
+
+    #[darling(default)]
+    pub references: Option<String>,
+
+    #[darling(default)]
+    pub on_delete: Option<OnDelete>,
+
+    #[darling(default)]
+    pub on_update: Option<OnUpdate>,
+
+    #[darling(default)]
+    pub skip: bool,
+
+    #[darling(default)]
+    pub with: Option<syn::Path>,
+
+    #[darling(default)]
+    pub scale: Option<u32>,
+
+    #[darling(default)]
+    pub timestamp_seconds: bool,
+
+    #[darling(default)]
+    pub timestamp_millis: bool,
+
+    #[darling(default)]
+    pub uuid_blob: bool,
+
+    #[darling(default)]
+    pub default_fn: Option<String>,
+
+    #[darling(default)]
+    pub encrypted: bool,
+
+    #[darling(default)]
+    pub collate: Option<String>,
+
+    #[darling(default)]
+    pub max_length: Option<usize>,
+}
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(tursorm), supports(struct_named), forward_attrs(doc))]
+pub(crate) struct TableReceiver {
+    pub ident: Ident,
+    pub vis:   syn::Visibility,
+    pub data:  darling::ast::Data<(), FieldReceiver>,
+    pub attrs: Vec<syn::Attribute>,
+
+    #[darling(default)]
+    pub table_name: Option<String>,
+
+    #[darling(default)]
+    pub polymorphic: Option<darling::util::PathList>,
+
+    #[darling(default)]
+    pub discriminator: Option<syn::LitStr>,
+
+    #[darling(multiple)]
+    pub many_to_many: Vec<ManyToManyReceiver>,
+
+    #[darling(default)]
+    pub tree: Option<syn::LitStr>,
+
+    #[darling(default)]
+    pub renamed_from: Option<String>,
+
+    #[darling(default)]
+    pub default_limit: Option<usize>,
+
+    #[darling(default)]
+    pub database: Option<String>,
+
+    #[darling(default)]
+    pub serde: bool,
+
+    #[darling(default)]
+    pub readonly: bool,
+
+    #[darling(default)]
+    pub view: Option<String>,
+
+    #[darling(default)]
+    pub no_column_enum_display: bool,
+}
+
+/// `#[tursorm(many_to_many(name = "roles", related = "RoleTable", through = "user_roles"))]`
+#[derive(Debug, FromMeta)]
+pub(crate) struct ManyToManyReceiver {
+    pub name:    String,
+    pub related: syn::Path,
+    pub through: String,
+
+    #[darling(default)]
+    pub local_column: Option<String>,
+
+    #[darling(default)]
+    pub related_column: Option<String>,
+
+    /// Names the helpers for one related row, e.g. `add_role`; defaults to the related table's struct in snake case.
+    #[darling(default)]
+    pub singular: Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ManyToManyInfo {
+    pub name:           Ident,
+    pub related:        syn::Path,
+    pub through:        String,
+    pub local_column:   String,
+    pub related_column: String,
+    pub singular:       Ident,
+}
+
+#[derive(Debug)]
+pub(crate) struct ForeignKeyInfo {
+    pub table_name:  String,
+    pub column_name: String,
+    pub on_delete:   OnDelete,
+    pub on_update:   OnUpdate,
+}
+
+impl ToTokens for OnDelete {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let variant = match self {
+            OnDelete::Restrict => quote! { tursorm::OnDelete::Restrict },
+            OnDelete::Cascade => quote! { tursorm::OnDelete::Cascade },
+            OnDelete::SetNull => quote! { tursorm::OnDelete::SetNull },
+            OnDelete::SetDefault => quote! { tursorm::OnDelete::SetDefault },
+            OnDelete::None => quote! { tursorm::OnDelete::None },
+        };
+        tokens.extend(variant);
+    }
+}
+
+impl ToTokens for OnUpdate {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let variant = match self {
+            OnUpdate::Restrict => quote! { tursorm::OnUpdate::Restrict },
+            OnUpdate::Cascade => quote! { tursorm::OnUpdate::Cascade },
+            OnUpdate::SetNull => quote! { tursorm::OnUpdate::SetNull },
+            OnUpdate::SetDefault => quote! { tursorm::OnUpdate::SetDefault },
+            OnUpdate::None => quote! { tursorm::OnUpdate::None },
+        };
+        tokens.extend(variant);
+    }
+}
+
+impl ToTokens for ForeignKeyInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let table_name = &self.table_name;
+        let column_name = &self.column_name;
+        let on_delete = &self.on_delete;
+        let on_update = &self.on_update;
+        tokens.extend(quote! {
+            tursorm::ForeignKeyInfo {
+                table_name: #table_name,
+                column_name: #column_name,
+                on_delete: #on_delete,
+                on_update: #on_update,
+            }
+        });
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct FieldInfo {
+    pub field_name:        Ident,
+    pub variant_name:      Ident,
+    pub column_name:       String,
+    pub field_type:        Type,
+    pub is_primary_key:    bool,
+    pub is_optional:       bool,
+    pub is_auto_increment: bool,
+    pub is_unique:         bool,
+    pub default_value:     Option<String>,
+    pub renamed_from:      Option<String>,
+    pub foreign_key:       Option<ForeignKeyInfo>,
+    /// Module providing `to_value(&T) -> Value` and `from_value(Value) -> Result<T>` for this field.
+    pub with:              Option<syn::Path>,
+    /// Fixed number of fractional digits for a `Decimal` field.
+    pub scale:             Option<u32>,
+    /// `IdGenerator` that fills the primary key on insert when it is left `NotSet`.
+    pub id_generator:      Option<syn::Path>,
+    /// Column type forced by a storage attribute such as `timestamp_millis` or `uuid_blob`, instead of one inferred
+    /// from the type.
+    pub storage_type:      Option<TokenStream2>,
+    /// Stored encrypted with the connection's column encryptor.
+    pub is_encrypted:      bool,
+    /// `Collation` variant from `#[tursorm(collate = "...")]`.
+    pub collation:         Option<Ident>,
+    /// Longest text (in characters) or blob (in bytes) a write may store.
+    pub max_length:        Option<usize>,
+    /// The field's doc comment.
+    pub docs:              Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct TableInfo {
+    pub struct_name:   Ident,
+    pub vis:           syn::Visibility,
+    pub table_name:    String,
+    pub fields:        Vec<FieldInfo>,
+    pub polymorphic:   Option<(Ident, Ident)>,
+    /// The field whose value picks the variant of a `TableEnum` wrapping the table.
+    pub discriminator: Option<Ident>,
+    pub many_to_many:  Vec<ManyToManyInfo>,
+    /// The optional field holding the primary key of the row's parent.
+    pub tree:          Option<Ident>,
+    pub renamed_from:  Option<String>,
+    pub default_limit: Option<usize>,
+    /// The attached database holding the table, e.g. `archive`.
+    pub database:      Option<String>,
+    /// Implements `Serialize` and `Deserialize` for the record and its change set.
+    pub serde:         bool,
+    /// `#[tursorm(skip)]` fields, filled with `Default::default()` when loading rows.
+    pub skipped:       Vec<Ident>,
+    /// Generates the record and select support only; the table's change set is `ReadOnlyChangeSet`.
+    pub readonly:      bool,
+    /// The `SELECT` of a view-backed table; implies `readonly`.
+    pub view:          Option<String>,
+    /// Leaves out the `Display` impl of the column enum.
+    pub no_display:    bool,
+    /// The struct's doc comment.
+    pub docs:          Option<String>,
+}
+
+impl FieldReceiver {
+    fn has_column_attributes(&self) -> bool {
+        self.primary_key
+            || self.auto_increment
+            || self.unique
+            || self.column_name.is_some()
+            || self.renamed_from.is_some()
+            || self.default.is_some()
+            || self.foreign_key
+            || self.references.is_some()
+            || self.on_delete.is_some()
+            || self.on_update.is_some()
+            || self.with.is_some()
+            || self.scale.is_some()
+            || self.timestamp_seconds
+            || self.timestamp_millis
+            || self.uuid_blob
+            || self.default_fn.is_some()
+            || self.encrypted
+            || self.collate.is_some()
+            || self.max_length.is_some()
+    }
+
+    pub fn into_field_info(self) -> darling::Result<FieldInfo> {
+        let field_name = self.ident.ok_or_else(|| darling::Error::custom("Expected named field"))?;
+        let is_optional = is_option_type(&self.ty);
+        let variant_name = to_pascal_case(&field_name);
+
+        let column_name = self.column_name.unwrap_or_else(|| field_name.to_string());
+
+        let mut errors = darling::Error::accumulator();
+
+        if column_name.is_empty() {
+            errors.push(darling::Error::custom("`column_name` must not be empty").with_span(&field_name));
+        }
+
+        if self.auto_increment && !self.primary_key {
+            errors.push(
+                darling::Error::custom("`auto_increment` is only supported on the `primary_key` field")
+                    .with_span(&field_name),
+            );
+        }
+
+        if !self.foreign_key && (self.references.is_some() || self.on_delete.is_some() || self.on_update.is_some()) {
+            errors.push(
+                darling::Error::custom("`references`, `on_delete` and `on_update` require `foreign_key`")
+                    .with_span(&field_name),
+            );
+        }
+
+        if self.with.is_some() && self.scale.is_some() {
+            errors.push(
+                darling::Error::custom("`scale` cannot be combined with `with`; apply it in the codec instead")
+                    .with_span(&field_name),
+            );
+        }
+
+        let storage_modes = [
+            (self.timestamp_seconds, "timestamp_seconds", quote! { Integer }),
+            (self.timestamp_millis, "timestamp_millis", quote! { Integer }),
+            (self.uuid_blob, "uuid_blob", quote! { Blob }),
+        ];
+        let mut storage_modes = storage_modes.into_iter().filter(|(enabled, ..)| *enabled);
+        let storage = storage_modes.next().map(|(_, codec, column_type)| (codec, column_type));
+
+        if storage_modes.next().is_some() {
+            errors.push(
+                darling::Error::custom(
+                    "`timestamp_seconds`, `timestamp_millis` and `uuid_blob` are mutually exclusive",
+                )
+                .with_span(&field_name),
+            );
+        }
+
+        if storage.is_some() && (self.with.is_some() || self.scale.is_some()) {
+            errors.push(
+                darling::Error::custom("storage attributes cannot be combined with `with` or `scale`")
+                    .with_span(&field_name),
+            );
+        }
+
+        if storage.is_none() && self.with.is_none() && is_wrapping_integer_type(&self.ty) {
+            errors.push(
+                darling::Error::custom(
+                    "`u64` and `usize` fields need a codec such as `with = \"tursorm::codec::u64_text\"`, since \
+                     INTEGER columns only hold values up to `i64::MAX`",
+                )
+                .with_span(&field_name),
+            );
+        }
+
+        if self.encrypted && (self.primary_key || self.unique || self.default.is_some() || self.foreign_key) {
+            errors.push(
+                darling::Error::custom(
+                    "`encrypted` cannot be combined with `primary_key`, `unique`, `default` or `foreign_key`",
+                )
+                .with_span(&field_name),
+            );
+        }
+
+        let id_generator = match self.default_fn.as_deref() {
+            Some(_) if !self.primary_key || self.auto_increment => {
+                errors.push(
+                    darling::Error::custom("`default_fn` is only supported on a non-`auto_increment` `primary_key`")
+                        .with_span(&field_name),
+                );
+                None
+            }
+            Some("uuid_v4") => Some(syn::parse_quote! { tursorm::id::UuidV4 }),
+            Some("uuid_v7") => Some(syn::parse_quote! { tursorm::id::UuidV7 }),
+            Some("nanoid") => Some(syn::parse_quote! { tursorm::id::NanoId }),
+            Some(path) => match syn::parse_str::<syn::Path>(path) {
+                Ok(path) => Some(path),
+                Err(_) => {
+                    errors.push(
+                        darling::Error::custom(format!(
+                            "Invalid `default_fn` `{}`, expected `uuid_v4`, `uuid_v7`, `nanoid` or an `IdGenerator` path",
+                            path
+                        ))
+                        .with_span(&field_name),
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let collation = self.collate.as_deref().and_then(|collate| {
+            let variant = match collate.to_ascii_uppercase().as_str() {
+                "BINARY" => "Binary",
+                "NOCASE" => "NoCase",
+                "RTRIM" => "RTrim",
+                _ => {
+                    errors.push(
+                        darling::Error::custom(format!(
+                            "Unknown collation `{}`, expected `BINARY`, `NOCASE` or `RTRIM`",
+                            collate
+                        ))
+                        .with_span(&field_name),
+                    );
+                    return None;
+                }
+            };
+            Some(format_ident!("{}", variant))
+        });
+
+        if self.max_length == Some(0) {
+            errors.push(darling::Error::custom("`max_length` must be greater than zero").with_span(&field_name));
+        }
+
+        let foreign_key = if self.foreign_key {
+            match self.references {
+                Some(references) => match parse_references(&references) {
+                    Some((table, col)) => Some(ForeignKeyInfo {
+                        table_name:  table,
+                        column_name: col,
+                        on_delete:   self.on_delete.unwrap_or_default(),
+                        on_update:   self.on_update.unwrap_or_default(),
+                    }),
+                    None => {
+                        errors.push(
+                            darling::Error::custom(format!(
+                                "Invalid references format `{}`, expected `table` or `table.column`",
+                                references
+                            ))
+                            .with_span(&field_name),
+                        );
+                        None
+                    }
+                },
+                None => {
+                    errors.push(
+                        darling::Error::custom("Foreign key must have a references attribute").with_span(&field_name),
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        errors.finish()?;
+
+        Ok(FieldInfo {
+            field_name,
+            variant_name,
+            column_name,
+            field_type: self.ty,
+            is_primary_key: self.primary_key,
+            is_optional,
+            is_auto_increment: self.auto_increment,
+            is_unique: self.unique,
+            default_value: self.default,
+            renamed_from: self.renamed_from,
+            foreign_key,
+            id_generator,
+            storage_type: match (&storage, self.encrypted) {
+                (_, true) => Some(quote! { tursorm::ColumnType::Blob }),
+                (Some((_, column_type)), false) => Some(quote! { tursorm::ColumnType::#column_type }),
+                (None, false) => None,
+            },
+            is_encrypted: self.encrypted,
+            collation,
+            max_length: self.max_length,
+            docs: doc_comment(&self.attrs),
+            with: match storage {
+                Some((codec, _)) => Some(syn::parse_str(&format!("tursorm::codec::{}", codec))?),
+                None => self.with,
+            },
+            scale: self.scale,
+        })
+    }
+}
+
+impl TableReceiver {
+    pub fn into_table_info(self) -> darling::Result<TableInfo> {
+        let table_name = self.table_name.unwrap_or_else(|| to_snake_case(&self.ident));
+
+        let mut errors = darling::Error::accumulator();
+
+        let (skipped, receivers): (Vec<FieldReceiver>, Vec<FieldReceiver>) = self
+            .data
+            .take_struct()
+            .ok_or_else(|| darling::Error::unsupported_shape("Expected struct"))?
+            .fields
+            .into_iter()
+            .partition(|f| f.skip);
+
+        for field in &skipped {
+            if field.has_column_attributes() {
+                let error = darling::Error::custom("`skip` cannot be combined with column attributes");
+                errors.push(match &field.ident {
+                    Some(ident) => error.with_span(ident),
+                    None => error,
+                });
+            }
+        }
+        let skipped = skipped.into_iter().filter_map(|f| f.ident).collect();
+
+        let primary_keys = receivers.iter().filter(|f| f.primary_key).collect::<Vec<_>>();
+
+        if primary_keys.is_empty() {
+            errors.push(
+                darling::Error::custom("Table must have a primary key field marked with #[tursorm(primary_key)]")
+                    .with_span(&self.ident),
+            );
+        }
+
+        for extra in primary_keys.iter().skip(1) {
+            let error = darling::Error::custom(
+                "Table must have only one primary key field marked with #[tursorm(primary_key)]",
+            );
+            errors.push(match &extra.ident {
+                Some(ident) => error.with_span(ident),
+                None => error,
+            });
+        }
+
+        let fields: Vec<FieldInfo> = receivers.into_iter().filter_map(|f| errors.handle(f.into_field_info())).collect();
+
+        for (idx, field) in fields.iter().enumerate() {
+            if fields[..idx].iter().any(|f| f.column_name == field.column_name) {
+                errors.push(
+                    darling::Error::custom(format!("Duplicate column name `{}`", field.column_name))
+                        .with_span(&field.field_name),
+                );
+            }
+        }
+
+        let polymorphic = self.polymorphic.and_then(|paths| errors.handle(parse_polymorphic(&paths, &fields)));
+
+        let discriminator = self.discriminator.and_then(|name| {
+            let field = fields.iter().find(|f| f.field_name == name.value());
+            if field.is_none() {
+                errors.push(darling::Error::custom(format!("Unknown field `{}`", name.value())).with_span(&name));
+            }
+            field.map(|f| f.field_name.clone())
+        });
+
+        let tree = self.tree.and_then(|name| match fields.iter().find(|f| f.field_name == name.value()) {
+            Some(field) if field.is_optional => Some(field.field_name.clone()),
+            Some(_) => {
+                errors.push(
+                    darling::Error::custom(format!(
+                        "`tree` expects an optional parent field, `{}` isn't",
+                        name.value()
+                    ))
+                    .with_span(&name),
+                );
+                None
+            }
+            None => {
+                errors.push(darling::Error::custom(format!("Unknown field `{}`", name.value())).with_span(&name));
+                None
+            }
+        });
+
+        let mut many_to_many: Vec<ManyToManyInfo> = Vec::new();
+        for relation in self.many_to_many {
+            if let Some(relation) = errors.handle(relation.into_info(&self.ident, &table_name)) {
+                if many_to_many.iter().any(|other| other.name == relation.name) {
+                    errors.push(
+                        darling::Error::custom(format!("Duplicate relation `{}`", relation.name))
+                            .with_span(&self.ident),
+                    );
+                }
+                many_to_many.push(relation);
+            }
+        }
+
+        if let Some(renamed_from) = &self.renamed_from {
+            if renamed_from.is_empty() {
+                errors.push(darling::Error::custom("`renamed_from` must not be empty").with_span(&self.ident));
+            } else if *renamed_from == table_name {
+                errors.push(
+                    darling::Error::custom("`renamed_from` must differ from the table name").with_span(&self.ident),
+                );
+            }
+        }
+
+        if let Some(view) = &self.view {
+            if view.trim().is_empty() {
+                errors.push(darling::Error::custom("`view` must not be empty").with_span(&self.ident));
+            } else if self.renamed_from.is_some() {
+                errors.push(darling::Error::custom("`renamed_from` can't be used with `view`").with_span(&self.ident));
+            }
+        }
+
+        if let Some(database) = &self.database
+            && !is_identifier(database)
+        {
+            errors.push(
+                darling::Error::custom(format!("Invalid database `{}`, expected an identifier", database))
+                    .with_span(&self.ident),
+            );
+        }
+
+        if self.default_limit == Some(0) {
+            errors.push(darling::Error::custom("`default_limit` must be greater than zero").with_span(&self.ident));
+        }
+
+        errors.finish()?;
+
+        Ok(TableInfo {
+            struct_name: self.ident,
+            vis: self.vis,
+            table_name,
+            fields,
+            polymorphic,
+            discriminator,
+            many_to_many,
+            tree,
+            renamed_from: self.renamed_from,
+            default_limit: self.default_limit,
+            database: self.database,
+            serde: self.serde,
+            skipped,
+            readonly: self.readonly || self.view.is_some(),
+            view: self.view,
+            no_display: self.no_column_enum_display,
+            docs: doc_comment(&self.attrs),
+        })
+    }
+}
+
+impl ManyToManyReceiver {
+    fn into_info(self, struct_name: &Ident, table_name: &str) -> darling::Result<ManyToManyInfo> {
+        // `RoleTable` is the table of `Role`, whose table is `role` unless renamed.
+        let related_struct = self
+            .related
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .map(|name| name.strip_suffix("Table").map(str::to_string).unwrap_or(name))
+            .map(|name| to_snake_case(&format_ident!("{}", name)))
+            .unwrap_or_default();
+
+        let local_column = self.local_column.unwrap_or_else(|| format!("{}_id", table_name));
+        let related_column = self.related_column.unwrap_or_else(|| format!("{}_id", related_struct));
+        let singular = self.singular.unwrap_or(related_struct);
+
+        for (what, name) in [
+            ("relation name", &self.name),
+            ("through", &self.through),
+            ("local_column", &local_column),
+            ("related_column", &related_column),
+            ("singular", &singular),
+        ] {
+            if !is_identifier(name) {
+                return Err(darling::Error::custom(format!("Invalid {} `{}`, expected an identifier", what, name))
+                    .with_span(struct_name));
+            }
+        }
+
+        if local_column == related_column {
+            return Err(darling::Error::custom(format!(
+                "The junction columns of `{}` are both `{}`; set `local_column` or `related_column`",
+                self.name, local_column
+            ))
+            .with_span(struct_name));
+        }
+
+        Ok(ManyToManyInfo {
+            name: format_ident!("{}", self.name),
+            related: self.related,
+            through: self.through,
+            local_column,
+            related_column,
+            singular: format_ident!("{}", singular),
+        })
+    }
+}
+
+/// The text of `///` comments, one line per attribute with the space after `///` removed.
+fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(text), .. }),
+                ..
+            }) => Some(text.value()),
+            _ => None,
+        })
+        .flat_map(|text| {
+            text.split('\n')
+                .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let docs = lines.join("\n").trim_matches('\n').to_string();
+    (!docs.is_empty()).then_some(docs)
+}
+
+fn to_pascal_case(ident: &Ident) -> Ident {
+    let s = ident.to_string();
+    let pascal: String = s
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) => c.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    Ident::new(&pascal, ident.span())
+}
+
+pub(crate) fn to_snake_case(ident: &Ident) -> String {
+    let s = ident.to_string();
+    let mut result = String::new();
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.push(c.to_lowercase().next().unwrap());
+    }
+    result
+}
+
+fn parse_polymorphic(paths: &darling::util::PathList, fields: &[FieldInfo]) -> darling::Result<(Ident, Ident)> {
+    let idents = paths
+        .iter()
+        .map(|path| {
+            let ident =
+                path.get_ident().ok_or_else(|| darling::Error::custom("Expected a field name").with_span(path))?;
+            if !fields.iter().any(|f| f.field_name == *ident) {
+                return Err(darling::Error::custom(format!("Unknown field `{}`", ident)).with_span(ident));
+            }
+            Ok(ident.clone())
+        })
+        .collect::<darling::Result<Vec<_>>>()?;
+
+    match idents.as_slice() {
+        [owner_type, owner_id] if owner_type != owner_id => Ok((owner_type.clone(), owner_id.clone())),
+        _ => Err(darling::Error::custom(
+            "`polymorphic` expects two distinct fields: `polymorphic(owner_type_field, owner_id_field)`",
+        )),
+    }
+}
+
+pub(crate) fn parse_references(refs: &str) -> Option<(String, String)> {
+    let (table, column) = match refs.split_once('.') {
+        Some((table, column)) => (table, column),
+        None => (refs, "id"),
+    };
+
+    if table.is_empty() || column.is_empty() || column.contains('.') {
+        return None;
+    }
+
+    Some((table.to_string(), column.to_string()))
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,104 @@
+//! The `FromRow` derive for plain structs.
+
+use darling::FromDeriveInput;
+use darling::FromField;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::DeriveInput;
+use syn::Type;
+
+use crate::types::from_row_field;
+use crate::types::is_option_type;
+
+#[derive(Debug, FromField)]
+#[darling(attributes(tursorm))]
+struct ModelFieldReceiver {
+    pub ident: Option<Ident>,
+    pub ty:    Type,
+
+    #[darling(default)]
+    pub with:        Option<syn::Path>,
+    #[darling(default)]
+    pub column_name: Option<String>,
+}
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(tursorm), supports(struct_named))]
+struct ModelReceiver {
+    pub ident:    Ident,
+    pub generics: syn::Generics,
+    pub data:     darling::ast::Data<(), ModelFieldReceiver>,
+    #[darling(default)]
+    pub from_row: Option<syn::LitStr>,
+}
+
+pub(crate) fn expand_from_row(input: &DeriveInput) -> TokenStream2 {
+    let model = match ModelReceiver::from_derive_input(input) {
+        Ok(model) => model,
+        Err(e) => return e.write_errors(),
+    };
+
+    let by_name = match model.from_row.as_ref().map(|mode| (mode.value(), mode)) {
+        None => false,
+        Some((mode, _)) if mode == "by_position" => false,
+        Some((mode, _)) if mode == "by_name" => true,
+        Some((_, lit)) => {
+            return darling::Error::custom("`from_row` must be \"by_name\" or \"by_position\"")
+                .with_span(lit)
+                .write_errors();
+        }
+    };
+
+    let struct_name = &model.ident;
+    let (impl_generics, ty_generics, where_clause) = model.generics.split_for_impl();
+    let fields = model.data.take_struct().map(|fields| fields.fields).unwrap_or_default();
+
+    if !by_name && let Some(field) = fields.iter().find(|f| f.column_name.is_some()) {
+        return darling::Error::custom("`column_name` requires `#[tursorm(from_row = \"by_name\")]`")
+            .with_span(&field.ident)
+            .write_errors();
+    }
+
+    let from_row_fields: Vec<_> = fields
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, f)| {
+            let field_name = f.ident.as_ref()?;
+            let index = if by_name {
+                let column_name = f.column_name.clone().unwrap_or_else(|| field_name.to_string());
+                quote! { columns.index(#column_name)? }
+            } else {
+                quote! { #idx }
+            };
+            Some(from_row_field(index, field_name, &f.ty, is_option_type(&f.ty), f.with.as_ref(), None))
+        })
+        .collect();
+
+    if by_name {
+        let message = format!("{} reads columns by name; decode it with FromRow::from_named_row", struct_name);
+        return quote! {
+            impl #impl_generics tursorm::FromRow for #struct_name #ty_generics #where_clause {
+                fn from_row(_row: &tursorm::Row) -> tursorm::Result<Self> {
+                    Err(tursorm::Error::Query(#message.to_string()))
+                }
+
+                fn from_named_row(row: &tursorm::Row, columns: &tursorm::RowColumns) -> tursorm::Result<Self> {
+                    Ok(Self {
+                        #(#from_row_fields),*
+                    })
+                }
+            }
+        };
+    }
+
+    quote! {
+        impl #impl_generics tursorm::FromRow for #struct_name #ty_generics #where_clause {
+            fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+                Ok(Self {
+                    #(#from_row_fields),*
+                })
+            }
+        }
+    }
+}
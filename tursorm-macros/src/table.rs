@@ -0,0 +1,1043 @@
+//! The `Table` derive: column enum, table and change set types and their trait impls.
+
+use darling::FromDeriveInput;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::format_ident;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::attributes::FieldInfo;
+use crate::attributes::TableInfo;
+use crate::attributes::TableReceiver;
+use crate::types::extract_option_inner_type;
+use crate::types::from_row_field;
+use crate::types::rust_type_to_column_type;
+
+pub(crate) fn expand(input: &DeriveInput) -> TokenStream2 {
+    match TableReceiver::from_derive_input(input).and_then(TableReceiver::into_table_info) {
+        Ok(entity_info) => impl_entity(&entity_info),
+        Err(e) => e.write_errors(),
+    }
+}
+
+fn impl_entity(entity_info: &TableInfo) -> TokenStream2 {
+    let struct_name = &entity_info.struct_name;
+    let vis = &entity_info.vis;
+    let table_name = format_ident!("{}Table", struct_name);
+    let column_enum_name = format_ident!("{}Column", struct_name);
+    let change_set_name = format_ident!("{}ChangeSet", struct_name);
+
+    let db_table_name = entity_info.table_name.clone();
+    let default_limit_const = entity_info.default_limit.map(|limit| {
+        quote! {
+            const DEFAULT_LIMIT: Option<usize> = Some(#limit);
+        }
+    });
+    let schema_name_fn = entity_info.database.as_ref().map(|database| {
+        quote! {
+            fn schema_name() -> Option<&'static str> {
+                Some(#database)
+            }
+        }
+    });
+    let renamed_from_fn = entity_info.renamed_from.as_ref().map(|old_name| {
+        quote! {
+            fn renamed_from() -> Option<&'static str> {
+                Some(#old_name)
+            }
+        }
+    });
+
+    let view_definition_fn = entity_info.view.as_ref().map(|query| {
+        quote! {
+            fn view_definition() -> Option<&'static str> {
+                Some(#query)
+            }
+        }
+    });
+
+    let table_docs_fn = entity_info.docs.as_ref().map(|docs| {
+        quote! {
+            fn table_docs() -> Option<&'static str> {
+                Some(#docs)
+            }
+        }
+    });
+
+    let junction_tables_fn = (!entity_info.many_to_many.is_empty()).then(|| {
+        let through = entity_info.many_to_many.iter().map(|relation| &relation.through);
+        quote! {
+            fn junction_tables() -> &'static [&'static str] {
+                &[#(#through),*]
+            }
+        }
+    });
+
+    let discriminator_field =
+        entity_info.discriminator.as_ref().and_then(|ident| entity_info.fields.iter().find(|f| f.field_name == *ident));
+    let discriminator_column_fn = discriminator_field.map(|f| {
+        let variant_name = &f.variant_name;
+        quote! {
+            fn discriminator_column() -> Option<Self::Column> {
+                Some(#column_enum_name::#variant_name)
+            }
+        }
+    });
+    let set_discriminator_fn = discriminator_field.map(|f| {
+        let field_name = &f.field_name;
+        quote! {
+            fn set_discriminator(&mut self, value: &str) -> tursorm::Result<()> {
+                self.#field_name =
+                    tursorm::FieldValue::Set(tursorm::FromValue::from_value(tursorm::Value::Text(value.to_string()))?);
+                Ok(())
+            }
+        }
+    });
+
+    let column_variants: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            quote! { #variant_name }
+        })
+        .collect();
+
+    let column_name_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            let col_name = &f.column_name;
+            quote! { Self::#variant_name => #col_name }
+        })
+        .collect();
+
+    let column_type_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            let col_type = match &f.storage_type {
+                Some(storage_type) => storage_type.clone(),
+                None => rust_type_to_column_type(&f.field_type, f.is_optional),
+            };
+            quote! { Self::#variant_name => #col_type }
+        })
+        .collect();
+
+    let primary_key_field = entity_info.fields.iter().find(|f| f.is_primary_key).expect("validated primary key");
+
+    let pk_variant = &primary_key_field.variant_name;
+    let pk_field_name = &primary_key_field.field_name;
+
+    let from_row_fields: Vec<_> = entity_info
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(idx, f)| {
+            let encrypted_column = f.is_encrypted.then_some(f.column_name.as_str());
+            from_row_field(
+                quote! { #idx },
+                &f.field_name,
+                &f.field_type,
+                f.is_optional,
+                f.with.as_ref(),
+                encrypted_column,
+            )
+        })
+        .chain(entity_info.skipped.iter().map(|field_name| quote! { #field_name: Default::default() }))
+        .collect();
+
+    let encrypted_variants: Vec<_> =
+        entity_info.fields.iter().filter(|f| f.is_encrypted).map(|f| &f.variant_name).collect();
+    let (is_encrypted_fn, from_row_impl, decode_row_fn) = if encrypted_variants.is_empty() {
+        let from_row_impl = quote! {
+            impl tursorm::FromRow for #struct_name {
+                fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+                    Ok(Self {
+                        #(#from_row_fields),*
+                    })
+                }
+            }
+        };
+        (None, from_row_impl, None)
+    } else {
+        let is_encrypted_fn = quote! {
+            fn is_encrypted(&self) -> bool {
+                matches!(self, #(Self::#encrypted_variants)|*)
+            }
+        };
+        // Without an encryptor, encrypted columns only load when they are NULL.
+        let from_row_impl = quote! {
+            impl tursorm::FromRow for #struct_name {
+                fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+                    <Self as tursorm::RecordTrait>::decode_row(row, None)
+                }
+            }
+        };
+        let decode_row_fn = quote! {
+            fn decode_row(
+                row: &tursorm::Row,
+                encryptor: Option<&dyn tursorm::encryption::Encryptor>,
+            ) -> tursorm::Result<Self> {
+                Ok(Self {
+                    #(#from_row_fields),*
+                })
+            }
+        };
+        (Some(is_encrypted_fn), from_row_impl, Some(decode_row_fn))
+    };
+
+    let change_set_fields: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let field_name = &f.field_name;
+            let field_type = &f.field_type;
+            let column_name = &f.column_name;
+            let serde_attr = entity_info.serde.then(|| {
+                quote! {
+                    #[serde(rename = #column_name, default, skip_serializing_if = "tursorm::FieldValue::is_not_set")]
+                }
+            });
+            quote! {
+                #serde_attr
+                pub #field_name: tursorm::FieldValue<#field_type>
+            }
+        })
+        .collect();
+
+    let change_set_from_record_fields: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let field_name = &f.field_name;
+            quote! {
+                #field_name: tursorm::FieldValue::Set(record.#field_name.clone())
+            }
+        })
+        .collect();
+
+    let change_set_merge_fields: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let field_name = &f.field_name;
+            quote! {
+                if later.#field_name.is_changed() {
+                    self.#field_name = later.#field_name;
+                }
+            }
+        })
+        .collect();
+
+    let change_set_diff_fields: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let field_name = &f.field_name;
+            if f.is_primary_key {
+                quote! {
+                    #field_name: tursorm::FieldValue::Set(self.#field_name)
+                }
+            } else {
+                let current = to_value(f, quote! { &self.#field_name });
+                let previous = to_value(f, quote! { &original.#field_name });
+                quote! {
+                    #field_name: if #current == #previous {
+                        tursorm::FieldValue::NotSet
+                    } else {
+                        tursorm::FieldValue::Set(self.#field_name)
+                    }
+                }
+            }
+        })
+        .collect();
+
+    let change_set_serde_attrs = entity_info.serde.then(|| {
+        quote! {
+            #[derive(tursorm::serde::Serialize, tursorm::serde::Deserialize)]
+            #[serde(crate = "tursorm::serde")]
+        }
+    });
+    let serde_impls = entity_info.serde.then(|| serde_impls(entity_info, &column_enum_name, &change_set_name));
+
+    let insert_set_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let field_name = &f.field_name;
+            let col_name = &f.column_name;
+            let value = to_value(f, quote! { v });
+            quote! {
+                if let tursorm::FieldValue::Set(ref v) = self.#field_name {
+                    columns.push(#col_name);
+                    values.push(#value);
+                }
+            }
+        })
+        .collect();
+
+    let update_set_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .filter(|f| !f.is_primary_key)
+        .map(|f| {
+            let field_name = &f.field_name;
+            let col_name = &f.column_name;
+            let value = to_value(f, quote! { v });
+            quote! {
+                if let tursorm::FieldValue::Set(ref v) = self.#field_name {
+                    sets.push((#col_name, #value));
+                }
+            }
+        })
+        .collect();
+
+    let pk_column_name = &primary_key_field.column_name;
+    let record_pk_value = to_value(primary_key_field, quote! { &self.#pk_field_name });
+    let pk_type = &primary_key_field.field_type;
+    let pk_to_value = to_value(primary_key_field, quote! { id });
+    let change_set_pk_value = to_value(primary_key_field, quote! { v });
+    let pk_is_auto_increment = primary_key_field.is_auto_increment;
+    let fill_generated_keys = primary_key_field.id_generator.as_ref().map(|generator| {
+        quote! {
+            fn fill_generated_keys(&mut self) {
+                if self.#pk_field_name.is_not_set() {
+                    self.#pk_field_name = tursorm::FieldValue::Set(<#generator as tursorm::IdGenerator>::generate());
+                }
+            }
+        }
+    });
+
+    let many_to_many_impls = many_to_many_impls(entity_info, &table_name);
+
+    let tree_impl = entity_info.tree.as_ref().map(|parent| {
+        let parent_field = entity_info.fields.iter().find(|f| f.field_name == *parent).unwrap();
+        let parent_variant = &parent_field.variant_name;
+        let parent_value = to_value(parent_field, quote! { &record.#parent });
+        quote! {
+            impl tursorm::TreeTrait for #table_name {
+                fn parent_column() -> Self::Column {
+                    #column_enum_name::#parent_variant
+                }
+
+                fn parent_of(record: &#struct_name) -> tursorm::Value {
+                    #parent_value
+                }
+            }
+        }
+    });
+
+    let polymorphic_impl = entity_info.polymorphic.as_ref().map(|(owner_type, owner_id)| {
+        let field_of = |ident: &Ident| entity_info.fields.iter().find(|f| f.field_name == *ident).unwrap();
+        let (owner_type_field, owner_id_field) = (field_of(owner_type), field_of(owner_id));
+        let owner_type_variant = &owner_type_field.variant_name;
+        let owner_id_variant = &owner_id_field.variant_name;
+        let owner_type_value = to_value(owner_type_field, quote! { &record.#owner_type });
+        let owner_id_value = to_value(owner_id_field, quote! { &record.#owner_id });
+        let owner_type_of = from_value(owner_type_field, quote! { tursorm::Value::Text(owner_type.to_string()) });
+        let owner_id_of = from_value(owner_id_field, quote! { owner.get_primary_key_value() });
+
+        let with_owner = (!entity_info.readonly).then(|| {
+            quote! {
+                impl #change_set_name {
+                    pub fn with_owner<Owner: tursorm::RecordTrait>(mut self, owner: &Owner) -> tursorm::Result<Self> {
+                        let owner_type = <#table_name as tursorm::PolymorphicTrait>::owner_type::<Owner>();
+                        self.#owner_type = tursorm::FieldValue::Set(#owner_type_of);
+                        self.#owner_id = tursorm::FieldValue::Set(#owner_id_of);
+                        Ok(self)
+                    }
+                }
+            }
+        });
+
+        quote! {
+            impl tursorm::PolymorphicTrait for #table_name {
+                fn owner_type_column() -> Self::Column {
+                    #column_enum_name::#owner_type_variant
+                }
+
+                fn owner_id_column() -> Self::Column {
+                    #column_enum_name::#owner_id_variant
+                }
+
+                fn owner_of(record: &#struct_name) -> (tursorm::Value, tursorm::Value) {
+                    (#owner_type_value, #owner_id_value)
+                }
+            }
+
+            impl #struct_name {
+                pub fn of<Owner: tursorm::RecordTrait>(owner: &Owner) -> tursorm::Condition {
+                    <#table_name as tursorm::PolymorphicTrait>::of(owner)
+                }
+            }
+
+            #with_owner
+        }
+    });
+
+    let unique_finders: Vec<_> = entity_info
+        .fields
+        .iter()
+        .filter(|f| f.is_unique && !f.is_primary_key)
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            let finder_name = format_ident!("find_by_{}", f.field_name);
+            let field_type = &f.field_type;
+            // An optional unique column is looked up by its inner type; `= NULL` never matches anyway.
+            let (value_type, field_value) = if f.is_optional {
+                (extract_option_inner_type(field_type).unwrap_or(field_type), quote! { Some(value.into()) })
+            } else {
+                (field_type, quote! { value.into() })
+            };
+            let value = to_value(f, quote! { &value });
+            quote! {
+                pub fn #finder_name(value: impl Into<#value_type>) -> tursorm::Select<Self> {
+                    let value: #field_type = #field_value;
+                    tursorm::Select::new().filter(tursorm::Condition::eq(#column_enum_name::#variant_name, #value))
+                }
+            }
+        })
+        .collect();
+    let unique_finders_impl = (!unique_finders.is_empty()).then(|| {
+        quote! {
+            impl #table_name {
+                #(#unique_finders)*
+            }
+        }
+    });
+
+    let all_columns: Vec<_> = entity_info.fields.iter().map(|f| f.column_name.as_str()).collect();
+    let all_columns_str = all_columns.join(", ");
+
+    let column_count = entity_info.fields.len();
+
+    let is_nullable_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            let is_nullable = f.is_optional;
+            quote! { Self::#variant_name => #is_nullable }
+        })
+        .collect();
+
+    let is_primary_key_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            let is_pk = f.is_primary_key;
+            quote! { Self::#variant_name => #is_pk }
+        })
+        .collect();
+
+    let is_auto_increment_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            let is_auto = f.is_auto_increment;
+            quote! { Self::#variant_name => #is_auto }
+        })
+        .collect();
+
+    let is_unique_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            let is_unique = f.is_unique;
+            quote! { Self::#variant_name => #is_unique }
+        })
+        .collect();
+
+    let default_value_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            match &f.default_value {
+                Some(val) => quote! { Self::#variant_name => Some(#val) },
+                None => quote! { Self::#variant_name => None },
+            }
+        })
+        .collect();
+
+    let renamed_from_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            match &f.renamed_from {
+                Some(old_name) => quote! { Self::#variant_name => Some(#old_name) },
+                None => quote! { Self::#variant_name => None },
+            }
+        })
+        .collect();
+
+    let collation_fn = entity_info.fields.iter().any(|f| f.collation.is_some()).then(|| {
+        let collation_arms = entity_info.fields.iter().map(|f| {
+            let variant_name = &f.variant_name;
+            match &f.collation {
+                Some(collation) => quote! { Self::#variant_name => Some(tursorm::Collation::#collation) },
+                None => quote! { Self::#variant_name => None },
+            }
+        });
+
+        quote! {
+            fn collation(&self) -> Option<tursorm::Collation> {
+                match self {
+                    #(#collation_arms),*
+                }
+            }
+        }
+    });
+
+    let max_length_fn = entity_info.fields.iter().any(|f| f.max_length.is_some()).then(|| {
+        let max_length_arms = entity_info.fields.iter().map(|f| {
+            let variant_name = &f.variant_name;
+            match f.max_length {
+                Some(max_length) => quote! { Self::#variant_name => Some(#max_length) },
+                None => quote! { Self::#variant_name => None },
+            }
+        });
+
+        quote! {
+            fn max_length(&self) -> Option<usize> {
+                match self {
+                    #(#max_length_arms),*
+                }
+            }
+        }
+    });
+
+    let docs_fn = entity_info.fields.iter().any(|f| f.docs.is_some()).then(|| {
+        let docs_arms = entity_info.fields.iter().map(|f| {
+            let variant_name = &f.variant_name;
+            match &f.docs {
+                Some(docs) => quote! { Self::#variant_name => Some(#docs) },
+                None => quote! { Self::#variant_name => None },
+            }
+        });
+
+        quote! {
+            fn docs(&self) -> Option<&'static str> {
+                match self {
+                    #(#docs_arms),*
+                }
+            }
+        }
+    });
+
+    let foreign_key_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            match &f.foreign_key {
+                Some(fk) => {
+                    let table_name = &fk.table_name;
+                    let column_name = &fk.column_name;
+                    let on_delete = fk.on_delete;
+                    let on_update = fk.on_update;
+                    quote! {
+                        Self::#variant_name => Some(tursorm::ForeignKeyInfo {
+                            table_name: String::from(#table_name),
+                            column_name: String::from(#column_name),
+                            on_delete: #on_delete,
+                            on_update: #on_update,
+                        })
+                    }
+                }
+                None => quote! { Self::#variant_name => None },
+            }
+        })
+        .collect();
+
+    let display_impl = (!entity_info.no_display).then(|| {
+        quote! {
+            impl ::std::fmt::Display for #column_enum_name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    write!(f, "{}", self.name())
+                }
+            }
+        }
+    });
+
+    let (change_set_type, read_only_const, change_set_items) = if entity_info.readonly {
+        (quote! { tursorm::ReadOnlyChangeSet<Self> }, Some(quote! { const READ_ONLY: bool = true; }), None)
+    } else {
+        let change_set_items = quote! {
+            impl #struct_name {
+                /// Converts this record into a change set that keeps the primary key and only the fields that differ
+                /// from `original`, so updating with it writes just the edited columns.
+                #vis fn into_change_set_diff(self, original: &Self) -> #change_set_name {
+                    #change_set_name {
+                        #(#change_set_diff_fields),*
+                    }
+                }
+            }
+
+            impl #table_name {
+                pub fn change_set() -> #change_set_name {
+                    #change_set_name::default()
+                }
+            }
+
+            #[derive(Clone, Debug, Default)]
+            #change_set_serde_attrs
+            #vis struct #change_set_name {
+                #(#change_set_fields),*
+            }
+
+            impl tursorm::ChangeSetTrait for #change_set_name {
+                type Table = #table_name;
+
+                fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+                    let mut columns = Vec::new();
+                    let mut values = Vec::new();
+                    #(#insert_set_arms)*
+                    (columns, values)
+                }
+
+                fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+                    let mut sets = Vec::new();
+                    #(#update_set_arms)*
+                    sets
+                }
+
+                fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+                    match &self.#pk_field_name {
+                        tursorm::FieldValue::Set(v) => Some(#change_set_pk_value),
+                        tursorm::FieldValue::NotSet => None,
+                    }
+                }
+
+                fn primary_key_column() -> &'static str {
+                    #pk_column_name
+                }
+
+                fn merge(&mut self, later: Self) {
+                    #(#change_set_merge_fields)*
+                }
+
+                #set_discriminator_fn
+
+                #fill_generated_keys
+            }
+
+            impl From<#struct_name> for #change_set_name {
+                fn from(record: #struct_name) -> Self {
+                    Self {
+                        #(#change_set_from_record_fields),*
+                    }
+                }
+            }
+        };
+        (quote! { #change_set_name }, None, Some(change_set_items))
+    };
+
+    quote! {
+
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis enum #column_enum_name {
+            #(#column_variants),*
+        }
+
+        impl tursorm::ColumnTrait for #column_enum_name {
+            fn name(&self) -> &'static str {
+                match self {
+                    #(#column_name_arms),*
+                }
+            }
+
+            fn column_type(&self) -> tursorm::ColumnType {
+                match self {
+                    #(#column_type_arms),*
+                }
+            }
+
+            fn is_nullable(&self) -> bool {
+                match self {
+                    #(#is_nullable_arms),*
+                }
+            }
+
+            fn is_primary_key(&self) -> bool {
+                match self {
+                    #(#is_primary_key_arms),*
+                }
+            }
+
+            fn is_auto_increment(&self) -> bool {
+                match self {
+                    #(#is_auto_increment_arms),*
+                }
+            }
+
+            fn is_unique(&self) -> bool {
+                match self {
+                    #(#is_unique_arms),*
+                }
+            }
+
+            fn default_value(&self) -> Option<&'static str> {
+                match self {
+                    #(#default_value_arms),*
+                }
+            }
+
+            fn renamed_from(&self) -> Option<&'static str> {
+                match self {
+                    #(#renamed_from_arms),*
+                }
+            }
+
+            fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+                match self {
+                    #(#foreign_key_arms),*
+                }
+            }
+
+            #is_encrypted_fn
+
+            #collation_fn
+
+            #max_length_fn
+
+            #docs_fn
+
+            fn all() -> &'static [Self] {
+                &[#(Self::#column_variants),*]
+            }
+        }
+
+        #display_impl
+
+        impl ::std::str::FromStr for #column_enum_name {
+            type Err = tursorm::Error;
+
+            fn from_str(name: &str) -> tursorm::Result<Self> {
+                <Self as tursorm::ColumnTrait>::from_name(name)
+                    .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+            }
+        }
+
+        #[derive(Clone, Copy, Debug, Default)]
+        #vis struct #table_name;
+
+        impl tursorm::TableTrait for #table_name {
+            type Record = #struct_name;
+            type Column = #column_enum_name;
+            type ChangeSet = #change_set_type;
+            type PrimaryKeyType = #pk_type;
+
+            #default_limit_const
+
+            #read_only_const
+
+            fn table_name() -> &'static str {
+                #db_table_name
+            }
+
+            fn primary_key() -> Self::Column {
+                #column_enum_name::#pk_variant
+            }
+
+            fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+                #pk_to_value
+            }
+
+            fn primary_key_auto_increment() -> bool {
+                #pk_is_auto_increment
+            }
+
+            fn all_columns() -> &'static str {
+                #all_columns_str
+            }
+
+            fn column_count() -> usize {
+                #column_count
+            }
+
+            #renamed_from_fn
+
+            #view_definition_fn
+
+            #table_docs_fn
+
+            #junction_tables_fn
+
+            #schema_name_fn
+
+            #discriminator_column_fn
+        }
+
+        #from_row_impl
+
+        impl tursorm::RecordTrait for #struct_name {
+            type Table = #table_name;
+
+            fn get_primary_key_value(&self) -> tursorm::Value {
+                #record_pk_value
+            }
+
+            #decode_row_fn
+        }
+
+        #unique_finders_impl
+
+        #change_set_items
+
+        #polymorphic_impl
+
+        #many_to_many_impls
+
+        #tree_impl
+
+        #serde_impls
+    }
+}
+
+/// The `ManyToMany` of each relation on the table, attach and detach helpers on the record, and a trait adding
+/// `with_<relation>` joins to the table's selects.
+fn many_to_many_impls(entity_info: &TableInfo, table_name: &Ident) -> Option<TokenStream2> {
+    if entity_info.many_to_many.is_empty() {
+        return None;
+    }
+
+    let struct_name = &entity_info.struct_name;
+    let vis = &entity_info.vis;
+    let relations_trait = format_ident!("{}Relations", struct_name);
+
+    let mut relations = Vec::new();
+    let mut helpers = Vec::new();
+    let mut joins = Vec::new();
+    let mut join_impls = Vec::new();
+    for relation in &entity_info.many_to_many {
+        let name = &relation.name;
+        let name_str = name.to_string();
+        let related = &relation.related;
+        let through = &relation.through;
+        let local_column = &relation.local_column;
+        let related_column = &relation.related_column;
+        let add = format_ident!("add_{}", relation.singular);
+        let remove = format_ident!("remove_{}", relation.singular);
+        let set = format_ident!("set_{}", name);
+        let with = format_ident!("with_{}", name);
+
+        relations.push(quote! {
+            pub fn #name() -> tursorm::ManyToMany<#table_name, #related> {
+                tursorm::ManyToMany::new(#name_str, #through, #local_column, #related_column)
+            }
+        });
+
+        helpers.push(quote! {
+            pub async fn #add<Conn: tursorm::ConnectionLike>(
+                &self,
+                conn: &Conn,
+                id: impl Into<<#related as tursorm::TableTrait>::PrimaryKeyType>,
+            ) -> tursorm::Result<bool> {
+                let id = <#related as tursorm::TableTrait>::primary_key_to_value(&id.into());
+                #table_name::#name().attach(conn, tursorm::RecordTrait::get_primary_key_value(self), id).await
+            }
+
+            pub async fn #remove<Conn: tursorm::ConnectionLike>(
+                &self,
+                conn: &Conn,
+                id: impl Into<<#related as tursorm::TableTrait>::PrimaryKeyType>,
+            ) -> tursorm::Result<bool> {
+                let id = <#related as tursorm::TableTrait>::primary_key_to_value(&id.into());
+                #table_name::#name().detach(conn, tursorm::RecordTrait::get_primary_key_value(self), id).await
+            }
+
+            pub async fn #set<Conn: tursorm::ConnectionLike>(
+                &self,
+                conn: &Conn,
+                ids: impl IntoIterator<Item = impl Into<<#related as tursorm::TableTrait>::PrimaryKeyType>>,
+            ) -> tursorm::Result<()> {
+                let ids = ids
+                    .into_iter()
+                    .map(|id| <#related as tursorm::TableTrait>::primary_key_to_value(&id.into()))
+                    .collect();
+                #table_name::#name().set(conn, tursorm::RecordTrait::get_primary_key_value(self), ids).await
+            }
+
+            pub fn #name(&self) -> tursorm::Select<#related> {
+                #table_name::#name().related(tursorm::RecordTrait::get_primary_key_value(self))
+            }
+        });
+
+        joins.push(quote! {
+            fn #with(self) -> Self;
+        });
+
+        join_impls.push(quote! {
+            fn #with(self) -> Self {
+                self.join_related(&#table_name::#name())
+            }
+        });
+    }
+
+    Some(quote! {
+        impl #table_name {
+            #(#relations)*
+        }
+
+        impl #struct_name {
+            #(#helpers)*
+        }
+
+        #vis trait #relations_trait {
+            #(#joins)*
+        }
+
+        impl #relations_trait for tursorm::Select<#table_name> {
+            #(#join_impls)*
+        }
+    })
+}
+
+/// Serializes the record through a borrowed mirror struct and deserializes it through an owned one, since a derive
+/// cannot add `#[serde(...)]` attributes to the struct it is applied to. Skipped fields are left out and filled with
+/// `Default::default()`. The column enum (de)serializes as the column name.
+fn serde_impls(entity_info: &TableInfo, column_enum_name: &Ident, change_set_name: &Ident) -> TokenStream2 {
+    let struct_name = &entity_info.struct_name;
+    let vis = &entity_info.vis;
+    let field_names: Vec<_> = entity_info.fields.iter().map(|f| &f.field_name).collect();
+    let field_types: Vec<_> = entity_info.fields.iter().map(|f| &f.field_type).collect();
+    let column_names: Vec<_> = entity_info.fields.iter().map(|f| &f.column_name).collect();
+    let skipped = &entity_info.skipped;
+
+    let apply_json_arms: Vec<_> = entity_info
+        .fields
+        .iter()
+        .map(|f| {
+            let variant_name = &f.variant_name;
+            let field_name = &f.field_name;
+            let column_name = &f.column_name;
+            quote! {
+                #column_enum_name::#variant_name => match object.get(#column_name) {
+                    Some(value) => patched.#field_name = tursorm::FieldValue::Set(tursorm::serde_json::from_value(value.clone())?),
+                    None => continue,
+                }
+            }
+        })
+        .collect();
+
+    let change_set_json = (!entity_info.readonly).then(|| {
+        quote! {
+            impl #change_set_name {
+                /// Builds a change set from a JSON object keyed by column name, setting only the keys that are present.
+                #vis fn from_json(value: tursorm::serde_json::Value) -> tursorm::Result<Self> {
+                    Ok(tursorm::serde_json::from_value(value)?)
+                }
+
+                /// Sets the `allowed` columns whose keys are present in the JSON object and returns the columns it set.
+                /// Other keys are ignored, so a client cannot write columns outside the whitelist. Nothing is set if any
+                /// value fails to deserialize.
+                #vis fn apply_json(
+                    &mut self,
+                    json: &tursorm::serde_json::Value,
+                    allowed: &[#column_enum_name],
+                ) -> tursorm::Result<Vec<#column_enum_name>> {
+                    let object = json
+                        .as_object()
+                        .ok_or_else(|| tursorm::Error::Query(format!("Expected a JSON object, got {}", json)))?;
+                    let mut patched = self.clone();
+                    let mut changed = Vec::new();
+
+                    for column in allowed {
+                        if changed.contains(column) {
+                            continue;
+                        }
+
+                        match column {
+                            #(#apply_json_arms),*
+                        }
+                        changed.push(*column);
+                    }
+
+                    *self = patched;
+                    Ok(changed)
+                }
+            }
+        }
+    });
+
+    quote! {
+        const _: () = {
+            #[derive(tursorm::serde::Serialize)]
+            #[serde(crate = "tursorm::serde")]
+            struct RecordRef<'a> {
+                #(
+                    #[serde(rename = #column_names)]
+                    #field_names: &'a #field_types,
+                )*
+            }
+
+            #[derive(tursorm::serde::Deserialize)]
+            #[serde(crate = "tursorm::serde")]
+            struct OwnedRecord {
+                #(
+                    #[serde(rename = #column_names)]
+                    #field_names: #field_types,
+                )*
+            }
+
+            impl tursorm::serde::Serialize for #struct_name {
+                fn serialize<S: tursorm::serde::Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> {
+                    tursorm::serde::Serialize::serialize(&RecordRef { #(#field_names: &self.#field_names),* }, serializer)
+                }
+            }
+
+            impl tursorm::serde::Serialize for #column_enum_name {
+                fn serialize<S: tursorm::serde::Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> {
+                    serializer.serialize_str(tursorm::ColumnTrait::name(self))
+                }
+            }
+
+            impl<'de> tursorm::serde::Deserialize<'de> for #column_enum_name {
+                fn deserialize<D: tursorm::serde::Deserializer<'de>>(deserializer: D) -> ::std::result::Result<Self, D::Error> {
+                    let name = <String as tursorm::serde::Deserialize>::deserialize(deserializer)?;
+                    <Self as tursorm::ColumnTrait>::from_name(&name).ok_or_else(|| {
+                        <D::Error as tursorm::serde::de::Error>::custom(format_args!("unknown column `{}`", name))
+                    })
+                }
+            }
+
+            impl<'de> tursorm::serde::Deserialize<'de> for #struct_name {
+                fn deserialize<D: tursorm::serde::Deserializer<'de>>(deserializer: D) -> ::std::result::Result<Self, D::Error> {
+                    let record = <OwnedRecord as tursorm::serde::Deserialize>::deserialize(deserializer)?;
+                    Ok(Self {
+                        #(#field_names: record.#field_names,)*
+                        #(#skipped: Default::default(),)*
+                    })
+                }
+            }
+        };
+
+        #change_set_json
+    }
+}
+
+/// Converts the field behind the reference `expr` into a `tursorm::Value`.
+/// Decodes `expr`, a `tursorm::Value`, into the field's type, propagating conversion errors with `?`.
+fn from_value(field: &FieldInfo, expr: TokenStream2) -> TokenStream2 {
+    match &field.with {
+        Some(with) => quote! { #with::from_value(#expr)? },
+        None => quote! { tursorm::FromValue::from_value(#expr)? },
+    }
+}
+
+fn to_value(field: &FieldInfo, expr: TokenStream2) -> TokenStream2 {
+    match (&field.with, field.scale) {
+        (Some(with), _) => quote! { #with::to_value(#expr) },
+        (None, Some(scale)) => quote! { tursorm::IntoScaledValue::into_scaled_value((#expr).clone(), #scale) },
+        (None, None) => quote! { tursorm::IntoValue::into_value((#expr).clone()) },
+    }
+}
@@ -0,0 +1,102 @@
+//! The `TableEnum` derive for single-table inheritance enums.
+
+use darling::FromDeriveInput;
+use darling::FromVariant;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::attributes::to_snake_case;
+
+#[derive(Debug, FromVariant)]
+#[darling(attributes(tursorm))]
+struct TableEnumVariantReceiver {
+    pub ident: Ident,
+
+    #[darling(default)]
+    pub discriminator: Option<syn::LitStr>,
+}
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(tursorm), supports(enum_newtype))]
+struct TableEnumReceiver {
+    pub ident: Ident,
+    pub data:  darling::ast::Data<TableEnumVariantReceiver, ()>,
+    pub table: syn::Path,
+}
+
+pub(crate) fn expand_table_enum(input: &DeriveInput) -> TokenStream2 {
+    let table_enum = match TableEnumReceiver::from_derive_input(input) {
+        Ok(table_enum) => table_enum,
+        Err(e) => return e.write_errors(),
+    };
+
+    let enum_name = &table_enum.ident;
+    let table = &table_enum.table;
+    let variants = table_enum.data.take_enum().unwrap_or_default();
+    let discriminators: Vec<String> = variants
+        .iter()
+        .map(|v| v.discriminator.as_ref().map(|lit| lit.value()).unwrap_or_else(|| to_snake_case(&v.ident)))
+        .collect();
+
+    let mut errors = darling::Error::accumulator();
+    for (idx, variant) in variants.iter().enumerate() {
+        if discriminators[..idx].contains(&discriminators[idx]) {
+            errors.push(
+                darling::Error::custom(format!("Duplicate discriminator `{}`", discriminators[idx]))
+                    .with_span(&variant.ident),
+            );
+        }
+    }
+    if let Err(e) = errors.finish() {
+        return e.write_errors();
+    }
+
+    let variant_names: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+    let message = format!("{} reads columns by name; decode it with FromRow::from_named_row", enum_name);
+    let expected = format!("a discriminator of {}", enum_name);
+
+    quote! {
+        impl tursorm::FromRow for #enum_name {
+            fn from_row(_row: &tursorm::Row) -> tursorm::Result<Self> {
+                Err(tursorm::Error::Query(#message.to_string()))
+            }
+
+            fn from_named_row(row: &tursorm::Row, columns: &tursorm::RowColumns) -> tursorm::Result<Self> {
+                let discriminator = <Self as tursorm::TableEnumTrait>::discriminator_of(row, columns)?;
+                match discriminator.as_str() {
+                    #(#discriminators => Ok(Self::#variant_names(tursorm::FromRow::from_named_row(row, columns)?)),)*
+                    _ => Err(tursorm::Error::TypeConversion {
+                        expected: #expected,
+                        actual:   discriminator,
+                        error:    "unknown discriminator".to_string(),
+                    }),
+                }
+            }
+        }
+
+        impl tursorm::TableEnumTrait for #enum_name {
+            type Table = #table;
+
+            fn discriminators() -> &'static [&'static str] {
+                &[#(#discriminators),*]
+            }
+
+            fn discriminator(&self) -> &'static str {
+                match self {
+                    #(Self::#variant_names(_) => #discriminators,)*
+                }
+            }
+
+            fn into_change_set(self) -> tursorm::Result<<#table as tursorm::TableTrait>::ChangeSet> {
+                let discriminator = self.discriminator();
+                let mut change_set: <#table as tursorm::TableTrait>::ChangeSet = match self {
+                    #(Self::#variant_names(value) => value.into(),)*
+                };
+                tursorm::ChangeSetTrait::set_discriminator(&mut change_set, discriminator)?;
+                Ok(change_set)
+            }
+        }
+    }
+}
@@ -0,0 +1,675 @@
+use syn::DeriveInput;
+use syn::parse_quote;
+
+use crate::attributes::parse_references;
+use crate::from_row::expand_from_row;
+use crate::table::expand;
+
+fn expand_compact(input: DeriveInput) -> String {
+    expand(&input).to_string().chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn contains(expanded: &str, needle: &str) -> bool {
+    let needle: String = needle.chars().filter(|c| !c.is_whitespace()).collect();
+    expanded.contains(&needle)
+}
+
+#[test]
+fn test_expand_basic_table() {
+    let expanded = expand_compact(parse_quote! {
+        pub struct UserProfile {
+            #[tursorm(primary_key, auto_increment)]
+            id: i64,
+            name: String,
+            email: Option<String>,
+        }
+    });
+
+    assert!(contains(&expanded, "pub enum UserProfileColumn { Id, Name, Email }"));
+    assert!(contains(&expanded, "pub struct UserProfileTable"));
+    assert!(contains(&expanded, "impl ::std::str::FromStr for UserProfileColumn"));
+    assert!(contains(&expanded, "pub struct UserProfileChangeSet"));
+    assert!(contains(&expanded, r#"fn table_name() -> &'static str { "user_profile" }"#));
+    assert!(contains(&expanded, r#"fn all_columns() -> &'static str { "id, name, email" }"#));
+    assert!(contains(&expanded, "Self::Email => true"));
+    assert!(contains(&expanded, "fn primary_key_auto_increment() -> bool { true }"));
+    assert!(contains(&expanded, "type PrimaryKeyType = i64;"));
+    assert!(contains(
+        &expanded,
+        "fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value { \
+         tursorm::IntoValue::into_value((id).clone()) }"
+    ));
+    assert!(contains(&expanded, "pub fn into_change_set_diff(self, original: &Self) -> UserProfileChangeSet"));
+    assert!(contains(&expanded, "id: tursorm::FieldValue::Set(self.id)"));
+    assert!(!expanded.contains("compile_error"));
+}
+
+#[test]
+fn test_expand_readonly() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(readonly, no_column_enum_display)]
+        struct DailyTotal {
+            #[tursorm(primary_key)]
+            day: String,
+            #[tursorm(unique)]
+            total: i64,
+        }
+    });
+
+    assert!(contains(&expanded, "type ChangeSet = tursorm::ReadOnlyChangeSet<Self>;"));
+    assert!(contains(&expanded, "const READ_ONLY: bool = true;"));
+    assert!(contains(&expanded, "pub fn find_by_total(value: impl Into<i64>)"));
+    assert!(!expanded.contains("DailyTotalChangeSet"));
+    assert!(!expanded.contains("std::fmt::Display"));
+}
+
+#[test]
+fn test_expand_view() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(table_name = "active_users", view = "SELECT id, name FROM users WHERE active = 1")]
+        struct ActiveUser {
+            #[tursorm(primary_key)]
+            id: i64,
+            name: String,
+        }
+    });
+
+    assert!(contains(
+        &expanded,
+        r#"fn view_definition() -> Option<&'static str> { Some("SELECT id, name FROM users WHERE active = 1") }"#
+    ));
+    assert!(contains(&expanded, "const READ_ONLY: bool = true;"));
+    assert!(!expanded.contains("ActiveUserChangeSet"));
+}
+
+#[test]
+fn test_expand_collate() {
+    let expanded = expand_compact(parse_quote! {
+        struct Subscriber {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(unique, collate = "nocase")]
+            email: String,
+        }
+    });
+
+    assert!(contains(
+        &expanded,
+        "fn collation(&self) -> Option<tursorm::Collation> { match self { Self::Id => None, \
+         Self::Email => Some(tursorm::Collation::NoCase) } }"
+    ));
+}
+
+#[test]
+fn test_expand_max_length() {
+    let expanded = expand_compact(parse_quote! {
+        struct Subscriber {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(max_length = 255)]
+            email: String,
+        }
+    });
+
+    assert!(contains(
+        &expanded,
+        "fn max_length(&self) -> Option<usize> { match self { Self::Id => None, Self::Email => Some(255usize) } }"
+    ));
+}
+
+#[test]
+fn test_expand_docs() {
+    let expanded = expand_compact(parse_quote! {
+        /// A newsletter subscriber.
+        ///
+        /// Rows are kept after unsubscribing.
+        struct Subscriber {
+            #[tursorm(primary_key)]
+            id: i64,
+            /// Where the newsletter is sent.
+            #[tursorm(unique)]
+            email: String,
+        }
+    });
+
+    assert!(contains(
+        &expanded,
+        r#"fn table_docs() -> Option<&'static str> { Some("A newsletter subscriber.\n\nRows are kept after unsubscribing.") }"#
+    ));
+    assert!(contains(
+        &expanded,
+        r#"fn docs(&self) -> Option<&'static str> { match self { Self::Id => None, Self::Email => Some("Where the newsletter is sent.") } }"#
+    ));
+
+    let expanded = expand_compact(parse_quote! {
+        struct User {
+            #[tursorm(primary_key)]
+            id: i64,
+        }
+    });
+    assert!(!expanded.contains("fn table_docs"));
+    assert!(!expanded.contains("fn docs"));
+}
+
+#[test]
+fn test_expand_discriminator() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(discriminator = "kind")]
+        struct Event {
+            #[tursorm(primary_key)]
+            id: i64,
+            kind: String,
+        }
+    });
+
+    assert!(contains(&expanded, "fn discriminator_column() -> Option<Self::Column> { Some(EventColumn::Kind) }"));
+    assert!(contains(&expanded, "fn set_discriminator(&mut self, value: &str) -> tursorm::Result<()> { self.kind ="));
+}
+
+#[test]
+fn test_expand_tree() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(tree = "parent_id")]
+        struct Category {
+            #[tursorm(primary_key)]
+            id: i64,
+            parent_id: Option<i64>,
+        }
+    });
+
+    assert!(contains(
+        &expanded,
+        "impl tursorm::TreeTrait for CategoryTable { fn parent_column() -> Self::Column { CategoryColumn::ParentId }"
+    ));
+    assert!(contains(
+        &expanded,
+        "fn parent_of(record: &Category) -> tursorm::Value { tursorm::IntoValue::into_value((&record.parent_id).clone()) }"
+    ));
+}
+
+#[test]
+fn test_expand_many_to_many() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(table_name = "members", many_to_many(name = "roles", related = "crate::RoleTable", through = "member_roles"))]
+        pub struct Member {
+            #[tursorm(primary_key)]
+            id: i64,
+        }
+    });
+
+    assert!(contains(
+        &expanded,
+        r#"pub fn roles() -> tursorm::ManyToMany<MemberTable, crate::RoleTable> { tursorm::ManyToMany::new("roles", "member_roles", "members_id", "role_id") }"#
+    ));
+    assert!(contains(&expanded, "pub async fn add_role<Conn: tursorm::ConnectionLike>"));
+    assert!(contains(&expanded, "pub async fn remove_role<Conn: tursorm::ConnectionLike>"));
+    assert!(contains(&expanded, "pub async fn set_roles<Conn: tursorm::ConnectionLike>"));
+    assert!(contains(&expanded, "pub trait MemberRelations { fn with_roles(self) -> Self; }"));
+
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(many_to_many(name = "roles", related = "RoleTable", through = "member-roles"))]
+        struct Member {
+            #[tursorm(primary_key)]
+            id: i64,
+        }
+    });
+    assert!(contains(&expanded, "Invalid through `member-roles`"));
+}
+
+#[test]
+fn test_expand_skip() {
+    let expanded = expand_compact(parse_quote! {
+        struct User {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(skip)]
+            display_name: String,
+        }
+    });
+
+    assert!(contains(&expanded, "enum UserColumn { Id }"));
+    assert!(contains(&expanded, "display_name: Default::default()"));
+    assert!(!contains(&expanded, "display_name: tursorm::FieldValue"));
+}
+
+#[test]
+fn test_expand_with_module() {
+    let expanded = expand_compact(parse_quote! {
+        struct Document {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(with = "codecs::csv")]
+            tags: Tags,
+        }
+    });
+
+    assert!(contains(&expanded, "tags: codecs::csv::from_value(row.get_value(1usize)?)?"));
+    assert!(contains(&expanded, r#"values.push(codecs::csv::to_value(v));"#));
+    assert!(contains(&expanded, "if codecs::csv::to_value(&self.tags) == codecs::csv::to_value(&original.tags)"));
+}
+
+#[test]
+fn test_expand_decimal_scale() {
+    let expanded = expand_compact(parse_quote! {
+        struct Invoice {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(scale = 2)]
+            total: Decimal,
+        }
+    });
+
+    assert!(contains(&expanded, "Self::Total => tursorm::ColumnType::Text"));
+    assert!(contains(&expanded, "values.push(tursorm::IntoScaledValue::into_scaled_value((v).clone(), 2u32));"));
+}
+
+#[test]
+fn test_expand_timestamp_storage() {
+    let expanded = expand_compact(parse_quote! {
+        struct Event {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(timestamp_millis)]
+            created_at: DateTime<Utc>,
+            #[tursorm(timestamp_seconds)]
+            deleted_at: Option<DateTime<Utc>>,
+        }
+    });
+
+    assert!(contains(&expanded, "Self::CreatedAt => tursorm::ColumnType::Integer"));
+    assert!(contains(&expanded, "Self::DeletedAt => tursorm::ColumnType::Integer"));
+    assert!(contains(&expanded, "created_at: tursorm::codec::timestamp_millis::from_value(row.get_value(1usize)?)?"));
+    assert!(contains(&expanded, "values.push(tursorm::codec::timestamp_seconds::to_value(v));"));
+}
+
+#[test]
+fn test_expand_uuid_blob() {
+    let expanded = expand_compact(parse_quote! {
+        struct Session {
+            #[tursorm(primary_key, uuid_blob)]
+            id: Uuid,
+        }
+    });
+
+    assert!(contains(&expanded, "Self::Id => tursorm::ColumnType::Blob"));
+    assert!(contains(&expanded, "id: tursorm::codec::uuid_blob::from_value(row.get_value(0usize)?)?"));
+}
+
+#[test]
+fn test_expand_default_fn() {
+    let expanded = expand_compact(parse_quote! {
+        struct Session {
+            #[tursorm(primary_key, default_fn = "uuid_v7")]
+            id: Uuid,
+        }
+    });
+
+    assert!(contains(
+        &expanded,
+        "self.id = tursorm::FieldValue::Set(<tursorm::id::UuidV7 as tursorm::IdGenerator>::generate());"
+    ));
+
+    let expanded = expand_compact(parse_quote! {
+        struct Order {
+            #[tursorm(primary_key, default_fn = "ids::Snowflake")]
+            id: i64,
+        }
+    });
+
+    assert!(contains(&expanded, "<ids::Snowflake as tursorm::IdGenerator>::generate()"));
+}
+
+#[test]
+fn test_expand_from_row() {
+    let expanded: String = expand_from_row(&parse_quote! {
+        struct UserSummary {
+            id: i64,
+            email: Option<String>,
+        }
+    })
+    .to_string()
+    .chars()
+    .filter(|c| !c.is_whitespace())
+    .collect();
+
+    assert!(contains(&expanded, "impl tursorm::FromRow for UserSummary"));
+    assert!(contains(&expanded, "id: tursorm::FromValue::from_value(row.get_value(0usize)"));
+    assert!(contains(&expanded, "email: tursorm::FromValue::from_value_opt(row.get_value(1usize)?)"));
+}
+
+#[test]
+fn test_expand_from_row_by_name() {
+    let expanded: String = expand_from_row(&parse_quote! {
+        #[tursorm(from_row = "by_name")]
+        struct UserSummary {
+            email: Option<String>,
+            #[tursorm(column_name = "id")]
+            user_id: i64,
+        }
+    })
+    .to_string()
+    .chars()
+    .filter(|c| !c.is_whitespace())
+    .collect();
+
+    assert!(contains(&expanded, "fnfrom_named_row(row:&tursorm::Row,columns:&tursorm::RowColumns)"));
+    assert!(contains(&expanded, "email:tursorm::FromValue::from_value_opt(row.get_value(columns.index(\"email\")?)?)"));
+    assert!(contains(&expanded, "user_id:tursorm::FromValue::from_value(row.get_value(columns.index(\"id\")?)"));
+    assert!(contains(&expanded, "UserSummaryreadscolumnsbyname"));
+
+    let expanded = expand_from_row(&parse_quote! {
+        #[tursorm(from_row = "by_label")]
+        struct UserSummary {
+            id: i64,
+        }
+    })
+    .to_string();
+    assert!(expanded.contains("must be \\\"by_name\\\" or \\\"by_position\\\""));
+}
+
+#[test]
+fn test_expand_inherits_visibility() {
+    let expanded = expand_compact(parse_quote! {
+        pub(crate) struct Session {
+            #[tursorm(primary_key)]
+            id: i64,
+        }
+    });
+
+    assert!(contains(&expanded, "pub(crate) enum SessionColumn"));
+    assert!(contains(&expanded, "pub(crate) struct SessionTable;"));
+    assert!(contains(&expanded, "pub(crate) struct SessionChangeSet"));
+}
+
+#[test]
+fn test_expand_table_name_override() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(table_name = "people")]
+        struct Person {
+            #[tursorm(primary_key)]
+            id: i64,
+        }
+    });
+
+    assert!(contains(&expanded, r#"fn table_name() -> &'static str { "people" }"#));
+    assert!(contains(&expanded, "fn primary_key_auto_increment() -> bool { false }"));
+    assert!(!expanded.contains("fn renamed_from() ->"));
+}
+
+#[test]
+fn test_expand_table_renamed_from() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(table_name = "people", renamed_from = "persons")]
+        struct Person {
+            #[tursorm(primary_key)]
+            id: i64,
+        }
+    });
+
+    assert!(contains(&expanded, r#"fn renamed_from() -> Option<&'static str> { Some("persons") }"#));
+}
+
+#[test]
+fn test_expand_table_database() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(table_name = "users", database = "archive")]
+        struct ArchivedUser {
+            #[tursorm(primary_key)]
+            id: i64,
+        }
+    });
+
+    assert!(contains(&expanded, r#"fn schema_name() -> Option<&'static str> { Some("archive") }"#));
+}
+
+#[test]
+fn test_expand_table_serde() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(serde)]
+        struct Account {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(column_name = "email_address")]
+            email: String,
+        }
+    });
+
+    assert!(contains(&expanded, "impl tursorm::serde::Serialize for Account"));
+    assert!(contains(&expanded, "impl<'de> tursorm::serde::Deserialize<'de> for Account"));
+    assert!(contains(&expanded, r#"#[serde(rename = "email_address")] email: &'a String"#));
+    assert!(contains(
+        &expanded,
+        r#"#[serde(rename = "email_address", default, skip_serializing_if = "tursorm::FieldValue::is_not_set")] pub email: tursorm::FieldValue<String>"#
+    ));
+    assert!(contains(&expanded, "fn from_json(value: tursorm::serde_json::Value) -> tursorm::Result<Self>"));
+    assert!(contains(&expanded, "impl tursorm::serde::Serialize for AccountColumn"));
+    assert!(contains(&expanded, "impl<'de> tursorm::serde::Deserialize<'de> for AccountColumn"));
+    assert!(contains(
+        &expanded,
+        r#"AccountColumn::Email => match object.get("email_address") { Some(value) => patched.email ="#
+    ));
+
+    let plain = expand_compact(parse_quote! {
+        struct Account {
+            #[tursorm(primary_key)]
+            id: i64,
+        }
+    });
+    assert!(!plain.contains("serde"));
+}
+
+#[test]
+fn test_expand_default_limit() {
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(default_limit = 50)]
+        struct Event {
+            #[tursorm(primary_key)]
+            id: i64,
+        }
+    });
+
+    assert!(contains(&expanded, "const DEFAULT_LIMIT: Option<usize> = Some(50usize);"));
+}
+
+#[test]
+fn test_expand_encrypted_column() {
+    let expanded = expand_compact(parse_quote! {
+        struct Patient {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(encrypted)]
+            ssn: String,
+        }
+    });
+
+    assert!(contains(&expanded, "Self::Ssn => tursorm::ColumnType::Blob"));
+    assert!(contains(&expanded, "fn is_encrypted(&self) -> bool { matches!(self, Self::Ssn) }"));
+    assert!(contains(&expanded, "<Self as tursorm::RecordTrait>::decode_row(row, None)"));
+    assert!(contains(&expanded, r#"tursorm::encryption::decrypt_value(encryptor, "ssn", row.get_value(1usize)?)"#));
+    assert!(!contains(&expanded, r#"decrypt_value(encryptor, "id""#));
+}
+
+#[test]
+fn test_expand_column_name_unique_default_renamed() {
+    let expanded = expand_compact(parse_quote! {
+        struct Account {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(column_name = "email_address", unique, renamed_from = "mail", default = "'none'")]
+            email: String,
+        }
+    });
+
+    assert!(contains(&expanded, r#"Self::Email => "email_address""#));
+    assert!(contains(&expanded, r#"columns.push("email_address")"#));
+    assert!(contains(&expanded, r#"Self::Email => Some("mail")"#));
+    assert!(contains(&expanded, r#"Self::Email => Some("'none'")"#));
+    assert!(contains(&expanded, "Self::Email => true"));
+    assert!(contains(
+        &expanded,
+        "pub fn find_by_email(value: impl Into<String>) -> tursorm::Select<Self> { let value: String = value.into(); \
+         tursorm::Select::new().filter(tursorm::Condition::eq(AccountColumn::Email, \
+         tursorm::IntoValue::into_value((&value).clone()))) }"
+    ));
+    assert!(!expanded.contains("find_by_id"));
+}
+
+#[test]
+fn test_expand_foreign_key_with_column_name() {
+    let expanded = expand_compact(parse_quote! {
+        struct Post {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(foreign_key, references = "users.uid", column_name = "author", on_delete = "cascade")]
+            author_id: i64,
+        }
+    });
+
+    assert!(contains(&expanded, r#"Self::AuthorId => "author""#));
+    assert!(contains(&expanded, r#"table_name: String::from("users")"#));
+    assert!(contains(&expanded, r#"column_name: String::from("uid")"#));
+    assert!(contains(&expanded, "on_delete: tursorm::OnDelete::Cascade"));
+    assert!(contains(&expanded, "on_update: tursorm::OnUpdate::None"));
+}
+
+#[test]
+fn test_expand_foreign_key_defaults_to_id() {
+    let expanded = expand_compact(parse_quote! {
+        struct Post {
+            #[tursorm(primary_key)]
+            id: i64,
+            #[tursorm(foreign_key, references = "users")]
+            user_id: i64,
+        }
+    });
+
+    assert!(contains(&expanded, r#"column_name: String::from("id")"#));
+}
+
+#[test]
+fn test_expand_polymorphic() {
+    let expanded = expand_compact(parse_quote! {
+        struct Comment {
+            #[tursorm(primary_key)]
+            id: i64,
+            owner_type: String,
+            owner_id: i64,
+        }
+    });
+    assert!(!expanded.contains("PolymorphicTrait"));
+
+    let expanded = expand_compact(parse_quote! {
+        #[tursorm(polymorphic(owner_type, owner_id))]
+        struct Comment {
+            #[tursorm(primary_key)]
+            id: i64,
+            owner_type: String,
+            owner_id: i64,
+        }
+    });
+
+    assert!(contains(&expanded, "impl tursorm::PolymorphicTrait for CommentTable"));
+    assert!(contains(&expanded, "fn owner_type_column() -> Self::Column { CommentColumn::OwnerType }"));
+    assert!(contains(&expanded, "fn owner_id_column() -> Self::Column { CommentColumn::OwnerId }"));
+    assert!(contains(&expanded, "pub fn of<Owner: tursorm::RecordTrait>(owner: &Owner) -> tursorm::Condition"));
+    assert!(contains(&expanded, "pub fn with_owner<Owner: tursorm::RecordTrait>"));
+}
+
+#[test]
+fn test_expand_column_types() {
+    let expanded = expand_compact(parse_quote! {
+        struct Sample {
+            #[tursorm(primary_key)]
+            id: i64,
+            score: f64,
+            data: Vec<u8>,
+            tags: Vec<String>,
+            active: bool,
+            note: Option<String>,
+        }
+    });
+
+    assert!(contains(&expanded, "Self::Score => tursorm::ColumnType::Float"));
+    assert!(contains(&expanded, "Self::Data => tursorm::ColumnType::Blob"));
+    assert!(contains(&expanded, "Self::Tags => tursorm::ColumnType::Text"));
+    assert!(contains(&expanded, "Self::Active => tursorm::ColumnType::Integer"));
+    assert!(contains(&expanded, "Self::Note => tursorm::ColumnType::Text"));
+}
+
+#[test]
+fn test_expand_errors() {
+    let cases: Vec<(DeriveInput, &str)> = vec![
+        (parse_quote! { struct A { id: i64 } }, "must have a primary key"),
+        (
+            parse_quote! { struct A { #[tursorm(primary_key)] id: i64, #[tursorm(primary_key)] other: i64 } },
+            "only one primary key",
+        ),
+        (
+            parse_quote! { struct A { #[tursorm(primary_key)] id: i64, #[tursorm(auto_increment)] n: i64 } },
+            "`auto_increment` is only supported",
+        ),
+        (
+            parse_quote! { struct A { #[tursorm(primary_key)] id: i64, #[tursorm(foreign_key)] b: i64 } },
+            "must have a references attribute",
+        ),
+        (
+            parse_quote! { struct A { #[tursorm(primary_key)] id: i64, #[tursorm(references = "b")] b: i64 } },
+            "require `foreign_key`",
+        ),
+        (
+            parse_quote! { struct A { #[tursorm(primary_key)] id: i64, #[tursorm(foreign_key, references = "a.b.c")] b: i64 } },
+            "Invalid references format",
+        ),
+        (
+            parse_quote! { struct A { #[tursorm(primary_key)] id: i64, #[tursorm(column_name = "id")] b: i64 } },
+            "Duplicate column name",
+        ),
+        (parse_quote! { struct A(i64); }, "Unsupported shape"),
+        (
+            parse_quote! { #[tursorm(polymorphic(kind))] struct A { #[tursorm(primary_key)] id: i64, kind: String } },
+            "expects two distinct fields",
+        ),
+        (
+            parse_quote! { #[tursorm(polymorphic(kind, owner))] struct A { #[tursorm(primary_key)] id: i64, kind: String } },
+            "Unknown field `owner`",
+        ),
+        (parse_quote! { struct A { #[tursorm(primary_key, bogus)] id: i64 } }, "Unknown field"),
+        (
+            parse_quote! { #[tursorm(renamed_from = "a")] struct A { #[tursorm(primary_key)] id: i64 } },
+            "must differ from the table name",
+        ),
+        (
+            parse_quote! { #[tursorm(default_limit = 0)] struct A { #[tursorm(primary_key)] id: i64 } },
+            "must be greater than zero",
+        ),
+        (
+            parse_quote! { struct A { #[tursorm(primary_key)] id: i64, #[tursorm(collate = "UNICODE")] name: String } },
+            "Unknown collation `UNICODE`",
+        ),
+        (
+            parse_quote! { struct A { #[tursorm(primary_key)] id: i64, #[tursorm(max_length = 0)] name: String } },
+            "`max_length` must be greater than zero",
+        ),
+        (parse_quote! { #[tursorm(view = " ")] struct A { #[tursorm(primary_key)] id: i64 } }, "must not be empty"),
+        (
+            parse_quote! { #[tursorm(view = "SELECT 1 AS id", renamed_from = "b")] struct A { #[tursorm(primary_key)] id: i64 } },
+            "can't be used with `view`",
+        ),
+    ];
+
+    for (input, message) in cases {
+        let expanded = expand(&input).to_string();
+        assert!(expanded.contains("compile_error"), "expected an error for {}", message);
+        assert!(expanded.contains(message), "expected `{}` in {}", message, expanded);
+    }
+}
+
+#[test]
+fn test_parse_references() {
+    assert_eq!(parse_references("users"), Some(("users".to_string(), "id".to_string())));
+    assert_eq!(parse_references("users.uid"), Some(("users".to_string(), "uid".to_string())));
+    assert_eq!(parse_references(""), None);
+    assert_eq!(parse_references("users."), None);
+    assert_eq!(parse_references(".id"), None);
+    assert_eq!(parse_references("a.b.c"), None);
+}
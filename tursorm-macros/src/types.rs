@@ -0,0 +1,154 @@
+//! How field types map to column types and how fields are read from a row.
+
+use proc_macro2::Ident;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::Type;
+
+pub(crate) fn rust_type_to_column_type(ty: &Type, is_optional: bool) -> TokenStream2 {
+    let inner_type = if is_optional { extract_option_inner_type(ty).unwrap_or(ty) } else { ty };
+
+    match inner_type {
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last().unwrap();
+            let type_name = segment.ident.to_string();
+            match type_name.as_str() {
+                "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" => {
+                    quote! { tursorm::ColumnType::Integer }
+                }
+                "f32" | "f64" => quote! { tursorm::ColumnType::Float },
+                "String" | "str" | "Decimal" => quote! { tursorm::ColumnType::Text },
+                "Vec" => {
+                    if let Some(Type::Path(inner_path)) = extract_vec_inner_type(inner_type)
+                        && let Some(seg) = inner_path.path.segments.last()
+                        && seg.ident == "u8"
+                    {
+                        return quote! { tursorm::ColumnType::Blob };
+                    }
+                    quote! { tursorm::ColumnType::Text }
+                }
+                "bool" => quote! { tursorm::ColumnType::Integer },
+                _ => quote! { tursorm::ColumnType::Text },
+            }
+        }
+        _ => quote! { tursorm::ColumnType::Text },
+    }
+}
+
+/// Reads a field from `row`; an `encrypted_column` is decrypted with the `encryptor` in scope first.
+pub(crate) fn from_row_field(
+    index: TokenStream2,
+    field_name: &Ident,
+    field_type: &Type,
+    is_optional: bool,
+    with: Option<&syn::Path>,
+    encrypted_column: Option<&str>,
+) -> TokenStream2 {
+    let expected = rust_type_to_column_type_label(field_type, is_optional);
+    let value = match encrypted_column {
+        Some(column_name) => quote! {
+            tursorm::encryption::decrypt_value(encryptor, #column_name, row.get_value(#index)?)
+        },
+        None => quote! { row.get_value(#index) },
+    };
+
+    if let Some(with) = with {
+        quote! {
+            #field_name: #with::from_value(#value?)?
+        }
+    } else if is_optional {
+        quote! {
+            #field_name: tursorm::FromValue::from_value_opt(
+                #value?
+            ).map_err(|e| tursorm::Error::TypeConversion {
+                expected: #expected,
+                actual: format!("{:?}", e),
+                error: "Conversion error".to_string()
+            })?
+        }
+    } else {
+        quote! {
+            #field_name: tursorm::FromValue::from_value(
+                #value.map_err(|e| tursorm::Error::TypeConversion {
+                    expected: #expected,
+                    actual: format!("{:?}", e),
+                    error: "Conversion error".to_string()
+                })?
+            )?
+        }
+    }
+}
+
+pub(crate) fn rust_type_to_column_type_label(ty: &Type, is_optional: bool) -> String {
+    let inner_type = if is_optional { extract_option_inner_type(ty).unwrap_or(ty) } else { ty };
+
+    let base_type = match inner_type {
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last().unwrap();
+            let type_name = segment.ident.to_string();
+            match type_name.as_str() {
+                "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" => "Integer",
+                "f32" | "f64" => "Real",
+                "String" | "str" | "Decimal" => "Text",
+                "Vec" => {
+                    if let Some(Type::Path(inner_path)) = extract_vec_inner_type(inner_type)
+                        && let Some(seg) = inner_path.path.segments.last()
+                        && seg.ident == "u8"
+                    {
+                        return "Blob".to_string();
+                    }
+                    "Text"
+                }
+                "bool" => "Integer",
+                _ => "Text",
+            }
+        }
+        _ => "Text",
+    };
+
+    base_type.to_string()
+}
+
+pub(crate) fn extract_option_inner_type(ty: &Type) -> Option<&Type> {
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+        && segment.ident == "Option"
+        && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(syn::GenericArgument::Type(inner)) = args.args.first()
+    {
+        return Some(inner);
+    }
+    None
+}
+
+pub(crate) fn extract_vec_inner_type(ty: &Type) -> Option<&Type> {
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+        && segment.ident == "Vec"
+        && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(syn::GenericArgument::Type(inner)) = args.args.first()
+    {
+        return Some(inner);
+    }
+    None
+}
+
+/// `u64` and `usize`, alone or in an `Option`: `IntoValue` stores them with `as i64`, which wraps above `i64::MAX`.
+pub(crate) fn is_wrapping_integer_type(ty: &Type) -> bool {
+    let inner_type = extract_option_inner_type(ty).unwrap_or(ty);
+    if let Type::Path(type_path) = inner_type
+        && let Some(segment) = type_path.path.segments.last()
+    {
+        return segment.ident == "u64" || segment.ident == "usize";
+    }
+    false
+}
+
+pub(crate) fn is_option_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+    {
+        return segment.ident == "Option";
+    }
+    false
+}
@@ -0,0 +1,6 @@
+/// Compares each derive in `tests/expand/*.rs` with its `.expanded.rs` snapshot; needs `cargo expand`. Missing
+/// snapshots are written on the first run, and `MACROTEST=overwrite` rewrites the ones that changed.
+#[test]
+fn test_expand() {
+    macrotest::expand("tests/expand/*.rs");
+}
@@ -0,0 +1,363 @@
+use tursorm::Table;
+pub struct User {
+    #[tursorm(primary_key, auto_increment)]
+    id: i64,
+    name: String,
+    email: Option<String>,
+}
+pub enum UserColumn {
+    Id,
+    Name,
+    Email,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for UserColumn {
+    #[inline]
+    fn clone(&self) -> UserColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for UserColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for UserColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                UserColumn::Id => "Id",
+                UserColumn::Name => "Name",
+                UserColumn::Email => "Email",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for UserColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for UserColumn {
+    #[inline]
+    fn eq(&self, other: &UserColumn) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for UserColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for UserColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        ::core::hash::Hash::hash(&__self_discr, state)
+    }
+}
+impl tursorm::ColumnTrait for UserColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Name => "name",
+            Self::Email => "email",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Id => tursorm::ColumnType::Integer,
+            Self::Name => tursorm::ColumnType::Text,
+            Self::Email => tursorm::ColumnType::Text,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Name => false,
+            Self::Email => true,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Id => true,
+            Self::Name => false,
+            Self::Email => false,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Id => true,
+            Self::Name => false,
+            Self::Email => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Name => false,
+            Self::Email => false,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::Name => None,
+            Self::Email => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::Name => None,
+            Self::Email => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Id => None,
+            Self::Name => None,
+            Self::Email => None,
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Id, Self::Name, Self::Email]
+    }
+}
+impl ::std::fmt::Display for UserColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for UserColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+pub struct UserTable;
+#[automatically_derived]
+impl ::core::clone::Clone for UserTable {
+    #[inline]
+    fn clone(&self) -> UserTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for UserTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for UserTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "UserTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for UserTable {
+    #[inline]
+    fn default() -> UserTable {
+        UserTable {}
+    }
+}
+impl tursorm::TableTrait for UserTable {
+    type Record = User;
+    type Column = UserColumn;
+    type ChangeSet = UserChangeSet;
+    type PrimaryKeyType = i64;
+    fn table_name() -> &'static str {
+        "user"
+    }
+    fn primary_key() -> Self::Column {
+        UserColumn::Id
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        true
+    }
+    fn all_columns() -> &'static str {
+        "id, name, email"
+    }
+    fn column_count() -> usize {
+        3usize
+    }
+}
+impl tursorm::FromRow for User {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            id: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            name: tursorm::FromValue::from_value(
+                row
+                    .get_value(1usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            email: tursorm::FromValue::from_value_opt(row.get_value(2usize)?)
+                .map_err(|e| tursorm::Error::TypeConversion {
+                    expected: "Text",
+                    actual: ::alloc::__export::must_use({
+                        ::alloc::fmt::format(format_args!("{0:?}", e))
+                    }),
+                    error: "Conversion error".to_string(),
+                })?,
+        })
+    }
+}
+impl tursorm::RecordTrait for User {
+    type Table = UserTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.id).clone())
+    }
+}
+impl User {
+    /// Converts this record into a change set that keeps the primary key and only the fields that differ
+    /// from `original`, so updating with it writes just the edited columns.
+    pub fn into_change_set_diff(self, original: &Self) -> UserChangeSet {
+        UserChangeSet {
+            id: tursorm::FieldValue::Set(self.id),
+            name: if tursorm::IntoValue::into_value((&self.name).clone())
+                == tursorm::IntoValue::into_value((&original.name).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.name)
+            },
+            email: if tursorm::IntoValue::into_value((&self.email).clone())
+                == tursorm::IntoValue::into_value((&original.email).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.email)
+            },
+        }
+    }
+}
+impl UserTable {
+    pub fn change_set() -> UserChangeSet {
+        UserChangeSet::default()
+    }
+}
+pub struct UserChangeSet {
+    pub id: tursorm::FieldValue<i64>,
+    pub name: tursorm::FieldValue<String>,
+    pub email: tursorm::FieldValue<Option<String>>,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for UserChangeSet {
+    #[inline]
+    fn clone(&self) -> UserChangeSet {
+        UserChangeSet {
+            id: ::core::clone::Clone::clone(&self.id),
+            name: ::core::clone::Clone::clone(&self.name),
+            email: ::core::clone::Clone::clone(&self.email),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for UserChangeSet {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field3_finish(
+            f,
+            "UserChangeSet",
+            "id",
+            &self.id,
+            "name",
+            &self.name,
+            "email",
+            &&self.email,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for UserChangeSet {
+    #[inline]
+    fn default() -> UserChangeSet {
+        UserChangeSet {
+            id: ::core::default::Default::default(),
+            name: ::core::default::Default::default(),
+            email: ::core::default::Default::default(),
+        }
+    }
+}
+impl tursorm::ChangeSetTrait for UserChangeSet {
+    type Table = UserTable;
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.id {
+            columns.push("id");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.name {
+            columns.push("name");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.email {
+            columns.push("email");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        (columns, values)
+    }
+    fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+        let mut sets = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.name {
+            sets.push(("name", tursorm::IntoValue::into_value((v).clone())));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.email {
+            sets.push(("email", tursorm::IntoValue::into_value((v).clone())));
+        }
+        sets
+    }
+    fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+        match &self.id {
+            tursorm::FieldValue::Set(v) => {
+                Some(tursorm::IntoValue::into_value((v).clone()))
+            }
+            tursorm::FieldValue::NotSet => None,
+        }
+    }
+    fn primary_key_column() -> &'static str {
+        "id"
+    }
+    fn merge(&mut self, later: Self) {
+        if later.id.is_changed() {
+            self.id = later.id;
+        }
+        if later.name.is_changed() {
+            self.name = later.name;
+        }
+        if later.email.is_changed() {
+            self.email = later.email;
+        }
+    }
+}
+impl From<User> for UserChangeSet {
+    fn from(record: User) -> Self {
+        Self {
+            id: tursorm::FieldValue::Set(record.id.clone()),
+            name: tursorm::FieldValue::Set(record.name.clone()),
+            email: tursorm::FieldValue::Set(record.email.clone()),
+        }
+    }
+}
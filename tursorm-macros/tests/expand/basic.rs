@@ -0,0 +1,9 @@
+use tursorm::Table;
+
+#[derive(Table)]
+pub struct User {
+    #[tursorm(primary_key, auto_increment)]
+    id: i64,
+    name: String,
+    email: Option<String>,
+}
@@ -0,0 +1,536 @@
+use tursorm::Table;
+struct Event {
+    #[tursorm(primary_key, default_fn = "uuid_v7")]
+    id: String,
+    #[tursorm(column_name = "title", renamed_from = "name", default = "'untitled'")]
+    label: String,
+    #[tursorm(timestamp_millis)]
+    starts_at: chrono::DateTime<chrono::Utc>,
+    #[tursorm(scale = 2)]
+    price: rust_decimal::Decimal,
+    #[tursorm(with = "codecs::csv")]
+    tags: Vec<String>,
+    #[tursorm(encrypted)]
+    notes: Option<String>,
+    #[tursorm(skip)]
+    cached: bool,
+}
+enum EventColumn {
+    Id,
+    Label,
+    StartsAt,
+    Price,
+    Tags,
+    Notes,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for EventColumn {
+    #[inline]
+    fn clone(&self) -> EventColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for EventColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for EventColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                EventColumn::Id => "Id",
+                EventColumn::Label => "Label",
+                EventColumn::StartsAt => "StartsAt",
+                EventColumn::Price => "Price",
+                EventColumn::Tags => "Tags",
+                EventColumn::Notes => "Notes",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for EventColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for EventColumn {
+    #[inline]
+    fn eq(&self, other: &EventColumn) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for EventColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for EventColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        ::core::hash::Hash::hash(&__self_discr, state)
+    }
+}
+impl tursorm::ColumnTrait for EventColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Label => "title",
+            Self::StartsAt => "starts_at",
+            Self::Price => "price",
+            Self::Tags => "tags",
+            Self::Notes => "notes",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Id => tursorm::ColumnType::Text,
+            Self::Label => tursorm::ColumnType::Text,
+            Self::StartsAt => tursorm::ColumnType::Integer,
+            Self::Price => tursorm::ColumnType::Text,
+            Self::Tags => tursorm::ColumnType::Text,
+            Self::Notes => tursorm::ColumnType::Blob,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Label => false,
+            Self::StartsAt => false,
+            Self::Price => false,
+            Self::Tags => false,
+            Self::Notes => true,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Id => true,
+            Self::Label => false,
+            Self::StartsAt => false,
+            Self::Price => false,
+            Self::Tags => false,
+            Self::Notes => false,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Label => false,
+            Self::StartsAt => false,
+            Self::Price => false,
+            Self::Tags => false,
+            Self::Notes => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Label => false,
+            Self::StartsAt => false,
+            Self::Price => false,
+            Self::Tags => false,
+            Self::Notes => false,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::Label => Some("'untitled'"),
+            Self::StartsAt => None,
+            Self::Price => None,
+            Self::Tags => None,
+            Self::Notes => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::Label => Some("name"),
+            Self::StartsAt => None,
+            Self::Price => None,
+            Self::Tags => None,
+            Self::Notes => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Id => None,
+            Self::Label => None,
+            Self::StartsAt => None,
+            Self::Price => None,
+            Self::Tags => None,
+            Self::Notes => None,
+        }
+    }
+    fn is_encrypted(&self) -> bool {
+        #[allow(non_exhaustive_omitted_patterns)]
+        match self {
+            Self::Notes => true,
+            _ => false,
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Id, Self::Label, Self::StartsAt, Self::Price, Self::Tags, Self::Notes]
+    }
+}
+impl ::std::fmt::Display for EventColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for EventColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+struct EventTable;
+#[automatically_derived]
+impl ::core::clone::Clone for EventTable {
+    #[inline]
+    fn clone(&self) -> EventTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for EventTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for EventTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "EventTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for EventTable {
+    #[inline]
+    fn default() -> EventTable {
+        EventTable {}
+    }
+}
+impl tursorm::TableTrait for EventTable {
+    type Record = Event;
+    type Column = EventColumn;
+    type ChangeSet = EventChangeSet;
+    type PrimaryKeyType = String;
+    fn table_name() -> &'static str {
+        "event"
+    }
+    fn primary_key() -> Self::Column {
+        EventColumn::Id
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "id, title, starts_at, price, tags, notes"
+    }
+    fn column_count() -> usize {
+        6usize
+    }
+}
+impl tursorm::FromRow for Event {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        <Self as tursorm::RecordTrait>::decode_row(row, None)
+    }
+}
+impl tursorm::RecordTrait for Event {
+    type Table = EventTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.id).clone())
+    }
+    fn decode_row(
+        row: &tursorm::Row,
+        encryptor: Option<&dyn tursorm::encryption::Encryptor>,
+    ) -> tursorm::Result<Self> {
+        Ok(Self {
+            id: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            label: tursorm::FromValue::from_value(
+                row
+                    .get_value(1usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            starts_at: tursorm::codec::timestamp_millis::from_value(
+                row.get_value(2usize)?,
+            )?,
+            price: tursorm::FromValue::from_value(
+                row
+                    .get_value(3usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            tags: codecs::csv::from_value(row.get_value(4usize)?)?,
+            notes: tursorm::FromValue::from_value_opt(
+                    tursorm::encryption::decrypt_value(
+                        encryptor,
+                        "notes",
+                        row.get_value(5usize)?,
+                    )?,
+                )
+                .map_err(|e| tursorm::Error::TypeConversion {
+                    expected: "Text",
+                    actual: ::alloc::__export::must_use({
+                        ::alloc::fmt::format(format_args!("{0:?}", e))
+                    }),
+                    error: "Conversion error".to_string(),
+                })?,
+            cached: Default::default(),
+        })
+    }
+}
+impl Event {
+    /// Converts this record into a change set that keeps the primary key and only the fields that differ
+    /// from `original`, so updating with it writes just the edited columns.
+    fn into_change_set_diff(self, original: &Self) -> EventChangeSet {
+        EventChangeSet {
+            id: tursorm::FieldValue::Set(self.id),
+            label: if tursorm::IntoValue::into_value((&self.label).clone())
+                == tursorm::IntoValue::into_value((&original.label).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.label)
+            },
+            starts_at: if tursorm::codec::timestamp_millis::to_value(&self.starts_at)
+                == tursorm::codec::timestamp_millis::to_value(&original.starts_at)
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.starts_at)
+            },
+            price: if tursorm::IntoScaledValue::into_scaled_value(
+                (&self.price).clone(),
+                2u32,
+            )
+                == tursorm::IntoScaledValue::into_scaled_value(
+                    (&original.price).clone(),
+                    2u32,
+                )
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.price)
+            },
+            tags: if codecs::csv::to_value(&self.tags)
+                == codecs::csv::to_value(&original.tags)
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.tags)
+            },
+            notes: if tursorm::IntoValue::into_value((&self.notes).clone())
+                == tursorm::IntoValue::into_value((&original.notes).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.notes)
+            },
+        }
+    }
+}
+impl EventTable {
+    pub fn change_set() -> EventChangeSet {
+        EventChangeSet::default()
+    }
+}
+struct EventChangeSet {
+    pub id: tursorm::FieldValue<String>,
+    pub label: tursorm::FieldValue<String>,
+    pub starts_at: tursorm::FieldValue<chrono::DateTime<chrono::Utc>>,
+    pub price: tursorm::FieldValue<rust_decimal::Decimal>,
+    pub tags: tursorm::FieldValue<Vec<String>>,
+    pub notes: tursorm::FieldValue<Option<String>>,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for EventChangeSet {
+    #[inline]
+    fn clone(&self) -> EventChangeSet {
+        EventChangeSet {
+            id: ::core::clone::Clone::clone(&self.id),
+            label: ::core::clone::Clone::clone(&self.label),
+            starts_at: ::core::clone::Clone::clone(&self.starts_at),
+            price: ::core::clone::Clone::clone(&self.price),
+            tags: ::core::clone::Clone::clone(&self.tags),
+            notes: ::core::clone::Clone::clone(&self.notes),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for EventChangeSet {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        let names: &'static _ = &["id", "label", "starts_at", "price", "tags", "notes"];
+        let values: &[&dyn ::core::fmt::Debug] = &[
+            &self.id,
+            &self.label,
+            &self.starts_at,
+            &self.price,
+            &self.tags,
+            &&self.notes,
+        ];
+        ::core::fmt::Formatter::debug_struct_fields_finish(
+            f,
+            "EventChangeSet",
+            names,
+            values,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for EventChangeSet {
+    #[inline]
+    fn default() -> EventChangeSet {
+        EventChangeSet {
+            id: ::core::default::Default::default(),
+            label: ::core::default::Default::default(),
+            starts_at: ::core::default::Default::default(),
+            price: ::core::default::Default::default(),
+            tags: ::core::default::Default::default(),
+            notes: ::core::default::Default::default(),
+        }
+    }
+}
+impl tursorm::ChangeSetTrait for EventChangeSet {
+    type Table = EventTable;
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.id {
+            columns.push("id");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.label {
+            columns.push("title");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.starts_at {
+            columns.push("starts_at");
+            values.push(tursorm::codec::timestamp_millis::to_value(v));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.price {
+            columns.push("price");
+            values.push(tursorm::IntoScaledValue::into_scaled_value((v).clone(), 2u32));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.tags {
+            columns.push("tags");
+            values.push(codecs::csv::to_value(v));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.notes {
+            columns.push("notes");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        (columns, values)
+    }
+    fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+        let mut sets = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.label {
+            sets.push(("title", tursorm::IntoValue::into_value((v).clone())));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.starts_at {
+            sets.push(("starts_at", tursorm::codec::timestamp_millis::to_value(v)));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.price {
+            sets.push((
+                "price",
+                tursorm::IntoScaledValue::into_scaled_value((v).clone(), 2u32),
+            ));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.tags {
+            sets.push(("tags", codecs::csv::to_value(v)));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.notes {
+            sets.push(("notes", tursorm::IntoValue::into_value((v).clone())));
+        }
+        sets
+    }
+    fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+        match &self.id {
+            tursorm::FieldValue::Set(v) => {
+                Some(tursorm::IntoValue::into_value((v).clone()))
+            }
+            tursorm::FieldValue::NotSet => None,
+        }
+    }
+    fn primary_key_column() -> &'static str {
+        "id"
+    }
+    fn merge(&mut self, later: Self) {
+        if later.id.is_changed() {
+            self.id = later.id;
+        }
+        if later.label.is_changed() {
+            self.label = later.label;
+        }
+        if later.starts_at.is_changed() {
+            self.starts_at = later.starts_at;
+        }
+        if later.price.is_changed() {
+            self.price = later.price;
+        }
+        if later.tags.is_changed() {
+            self.tags = later.tags;
+        }
+        if later.notes.is_changed() {
+            self.notes = later.notes;
+        }
+    }
+    fn fill_generated_keys(&mut self) {
+        if self.id.is_not_set() {
+            self.id = tursorm::FieldValue::Set(
+                <tursorm::id::UuidV7 as tursorm::IdGenerator>::generate(),
+            );
+        }
+    }
+}
+impl From<Event> for EventChangeSet {
+    fn from(record: Event) -> Self {
+        Self {
+            id: tursorm::FieldValue::Set(record.id.clone()),
+            label: tursorm::FieldValue::Set(record.label.clone()),
+            starts_at: tursorm::FieldValue::Set(record.starts_at.clone()),
+            price: tursorm::FieldValue::Set(record.price.clone()),
+            tags: tursorm::FieldValue::Set(record.tags.clone()),
+            notes: tursorm::FieldValue::Set(record.notes.clone()),
+        }
+    }
+}
+mod codecs {
+    pub mod csv {
+        use tursorm::prelude::*;
+        pub fn to_value(tags: &[String]) -> Value {
+            Value::Text(tags.join(","))
+        }
+        pub fn from_value(value: Value) -> Result<Vec<String>> {
+            Ok(String::from_value(value)?.split(',').map(str::to_string).collect())
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use tursorm::Table;
+
+#[derive(Table)]
+struct Event {
+    #[tursorm(primary_key, default_fn = "uuid_v7")]
+    id: String,
+    #[tursorm(column_name = "title", renamed_from = "name", default = "'untitled'")]
+    label: String,
+    #[tursorm(timestamp_millis)]
+    starts_at: chrono::DateTime<chrono::Utc>,
+    #[tursorm(scale = 2)]
+    price: rust_decimal::Decimal,
+    #[tursorm(with = "codecs::csv")]
+    tags: Vec<String>,
+    #[tursorm(encrypted)]
+    notes: Option<String>,
+    #[tursorm(skip)]
+    cached: bool,
+}
+
+mod codecs {
+    pub mod csv {
+        use tursorm::prelude::*;
+
+        pub fn to_value(tags: &[String]) -> Value {
+            Value::Text(tags.join(","))
+        }
+
+        pub fn from_value(value: Value) -> Result<Vec<String>> {
+            Ok(String::from_value(value)?.split(',').map(str::to_string).collect())
+        }
+    }
+}
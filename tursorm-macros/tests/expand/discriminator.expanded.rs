@@ -0,0 +1,330 @@
+use tursorm::Table;
+#[tursorm(discriminator = "kind")]
+struct Vehicle {
+    #[tursorm(primary_key)]
+    id: i64,
+    kind: String,
+}
+enum VehicleColumn {
+    Id,
+    Kind,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for VehicleColumn {
+    #[inline]
+    fn clone(&self) -> VehicleColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for VehicleColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for VehicleColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                VehicleColumn::Id => "Id",
+                VehicleColumn::Kind => "Kind",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for VehicleColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for VehicleColumn {
+    #[inline]
+    fn eq(&self, other: &VehicleColumn) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for VehicleColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for VehicleColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        ::core::hash::Hash::hash(&__self_discr, state)
+    }
+}
+impl tursorm::ColumnTrait for VehicleColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Kind => "kind",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Id => tursorm::ColumnType::Integer,
+            Self::Kind => tursorm::ColumnType::Text,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Kind => false,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Id => true,
+            Self::Kind => false,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Kind => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Kind => false,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::Kind => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::Kind => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Id => None,
+            Self::Kind => None,
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Id, Self::Kind]
+    }
+}
+impl ::std::fmt::Display for VehicleColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for VehicleColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+struct VehicleTable;
+#[automatically_derived]
+impl ::core::clone::Clone for VehicleTable {
+    #[inline]
+    fn clone(&self) -> VehicleTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for VehicleTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for VehicleTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "VehicleTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for VehicleTable {
+    #[inline]
+    fn default() -> VehicleTable {
+        VehicleTable {}
+    }
+}
+impl tursorm::TableTrait for VehicleTable {
+    type Record = Vehicle;
+    type Column = VehicleColumn;
+    type ChangeSet = VehicleChangeSet;
+    type PrimaryKeyType = i64;
+    fn table_name() -> &'static str {
+        "vehicle"
+    }
+    fn primary_key() -> Self::Column {
+        VehicleColumn::Id
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "id, kind"
+    }
+    fn column_count() -> usize {
+        2usize
+    }
+    fn discriminator_column() -> Option<Self::Column> {
+        Some(VehicleColumn::Kind)
+    }
+}
+impl tursorm::FromRow for Vehicle {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            id: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            kind: tursorm::FromValue::from_value(
+                row
+                    .get_value(1usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+        })
+    }
+}
+impl tursorm::RecordTrait for Vehicle {
+    type Table = VehicleTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.id).clone())
+    }
+}
+impl Vehicle {
+    /// Converts this record into a change set that keeps the primary key and only the fields that differ
+    /// from `original`, so updating with it writes just the edited columns.
+    fn into_change_set_diff(self, original: &Self) -> VehicleChangeSet {
+        VehicleChangeSet {
+            id: tursorm::FieldValue::Set(self.id),
+            kind: if tursorm::IntoValue::into_value((&self.kind).clone())
+                == tursorm::IntoValue::into_value((&original.kind).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.kind)
+            },
+        }
+    }
+}
+impl VehicleTable {
+    pub fn change_set() -> VehicleChangeSet {
+        VehicleChangeSet::default()
+    }
+}
+struct VehicleChangeSet {
+    pub id: tursorm::FieldValue<i64>,
+    pub kind: tursorm::FieldValue<String>,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for VehicleChangeSet {
+    #[inline]
+    fn clone(&self) -> VehicleChangeSet {
+        VehicleChangeSet {
+            id: ::core::clone::Clone::clone(&self.id),
+            kind: ::core::clone::Clone::clone(&self.kind),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for VehicleChangeSet {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field2_finish(
+            f,
+            "VehicleChangeSet",
+            "id",
+            &self.id,
+            "kind",
+            &&self.kind,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for VehicleChangeSet {
+    #[inline]
+    fn default() -> VehicleChangeSet {
+        VehicleChangeSet {
+            id: ::core::default::Default::default(),
+            kind: ::core::default::Default::default(),
+        }
+    }
+}
+impl tursorm::ChangeSetTrait for VehicleChangeSet {
+    type Table = VehicleTable;
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.id {
+            columns.push("id");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.kind {
+            columns.push("kind");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        (columns, values)
+    }
+    fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+        let mut sets = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.kind {
+            sets.push(("kind", tursorm::IntoValue::into_value((v).clone())));
+        }
+        sets
+    }
+    fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+        match &self.id {
+            tursorm::FieldValue::Set(v) => {
+                Some(tursorm::IntoValue::into_value((v).clone()))
+            }
+            tursorm::FieldValue::NotSet => None,
+        }
+    }
+    fn primary_key_column() -> &'static str {
+        "id"
+    }
+    fn merge(&mut self, later: Self) {
+        if later.id.is_changed() {
+            self.id = later.id;
+        }
+        if later.kind.is_changed() {
+            self.kind = later.kind;
+        }
+    }
+    fn set_discriminator(&mut self, value: &str) -> tursorm::Result<()> {
+        self.kind = tursorm::FieldValue::Set(
+            tursorm::FromValue::from_value(tursorm::Value::Text(value.to_string()))?,
+        );
+        Ok(())
+    }
+}
+impl From<Vehicle> for VehicleChangeSet {
+    fn from(record: Vehicle) -> Self {
+        Self {
+            id: tursorm::FieldValue::Set(record.id.clone()),
+            kind: tursorm::FieldValue::Set(record.kind.clone()),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use tursorm::Table;
+
+#[derive(Table)]
+#[tursorm(discriminator = "kind")]
+struct Vehicle {
+    #[tursorm(primary_key)]
+    id: i64,
+    kind: String,
+}
@@ -0,0 +1,390 @@
+use tursorm::Table;
+#[tursorm(table_name = "posts")]
+struct Post {
+    #[tursorm(primary_key)]
+    id: i64,
+    #[tursorm(
+        foreign_key,
+        references = "users.uid",
+        column_name = "author",
+        on_delete = "cascade"
+    )]
+    author_id: i64,
+    #[tursorm(
+        foreign_key,
+        references = "posts",
+        on_delete = "set_null",
+        on_update = "cascade"
+    )]
+    reply_to: Option<i64>,
+}
+enum PostColumn {
+    Id,
+    AuthorId,
+    ReplyTo,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for PostColumn {
+    #[inline]
+    fn clone(&self) -> PostColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for PostColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for PostColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                PostColumn::Id => "Id",
+                PostColumn::AuthorId => "AuthorId",
+                PostColumn::ReplyTo => "ReplyTo",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for PostColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for PostColumn {
+    #[inline]
+    fn eq(&self, other: &PostColumn) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for PostColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for PostColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        ::core::hash::Hash::hash(&__self_discr, state)
+    }
+}
+impl tursorm::ColumnTrait for PostColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::AuthorId => "author",
+            Self::ReplyTo => "reply_to",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Id => tursorm::ColumnType::Integer,
+            Self::AuthorId => tursorm::ColumnType::Integer,
+            Self::ReplyTo => tursorm::ColumnType::Integer,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::AuthorId => false,
+            Self::ReplyTo => true,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Id => true,
+            Self::AuthorId => false,
+            Self::ReplyTo => false,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::AuthorId => false,
+            Self::ReplyTo => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::AuthorId => false,
+            Self::ReplyTo => false,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::AuthorId => None,
+            Self::ReplyTo => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::AuthorId => None,
+            Self::ReplyTo => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Id => None,
+            Self::AuthorId => {
+                Some(tursorm::ForeignKeyInfo {
+                    table_name: String::from("users"),
+                    column_name: String::from("uid"),
+                    on_delete: tursorm::OnDelete::Cascade,
+                    on_update: tursorm::OnUpdate::None,
+                })
+            }
+            Self::ReplyTo => {
+                Some(tursorm::ForeignKeyInfo {
+                    table_name: String::from("posts"),
+                    column_name: String::from("id"),
+                    on_delete: tursorm::OnDelete::SetNull,
+                    on_update: tursorm::OnUpdate::Cascade,
+                })
+            }
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Id, Self::AuthorId, Self::ReplyTo]
+    }
+}
+impl ::std::fmt::Display for PostColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for PostColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+struct PostTable;
+#[automatically_derived]
+impl ::core::clone::Clone for PostTable {
+    #[inline]
+    fn clone(&self) -> PostTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for PostTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for PostTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "PostTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for PostTable {
+    #[inline]
+    fn default() -> PostTable {
+        PostTable {}
+    }
+}
+impl tursorm::TableTrait for PostTable {
+    type Record = Post;
+    type Column = PostColumn;
+    type ChangeSet = PostChangeSet;
+    type PrimaryKeyType = i64;
+    fn table_name() -> &'static str {
+        "posts"
+    }
+    fn primary_key() -> Self::Column {
+        PostColumn::Id
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "id, author, reply_to"
+    }
+    fn column_count() -> usize {
+        3usize
+    }
+}
+impl tursorm::FromRow for Post {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            id: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            author_id: tursorm::FromValue::from_value(
+                row
+                    .get_value(1usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            reply_to: tursorm::FromValue::from_value_opt(row.get_value(2usize)?)
+                .map_err(|e| tursorm::Error::TypeConversion {
+                    expected: "Integer",
+                    actual: ::alloc::__export::must_use({
+                        ::alloc::fmt::format(format_args!("{0:?}", e))
+                    }),
+                    error: "Conversion error".to_string(),
+                })?,
+        })
+    }
+}
+impl tursorm::RecordTrait for Post {
+    type Table = PostTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.id).clone())
+    }
+}
+impl Post {
+    /// Converts this record into a change set that keeps the primary key and only the fields that differ
+    /// from `original`, so updating with it writes just the edited columns.
+    fn into_change_set_diff(self, original: &Self) -> PostChangeSet {
+        PostChangeSet {
+            id: tursorm::FieldValue::Set(self.id),
+            author_id: if tursorm::IntoValue::into_value((&self.author_id).clone())
+                == tursorm::IntoValue::into_value((&original.author_id).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.author_id)
+            },
+            reply_to: if tursorm::IntoValue::into_value((&self.reply_to).clone())
+                == tursorm::IntoValue::into_value((&original.reply_to).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.reply_to)
+            },
+        }
+    }
+}
+impl PostTable {
+    pub fn change_set() -> PostChangeSet {
+        PostChangeSet::default()
+    }
+}
+struct PostChangeSet {
+    pub id: tursorm::FieldValue<i64>,
+    pub author_id: tursorm::FieldValue<i64>,
+    pub reply_to: tursorm::FieldValue<Option<i64>>,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for PostChangeSet {
+    #[inline]
+    fn clone(&self) -> PostChangeSet {
+        PostChangeSet {
+            id: ::core::clone::Clone::clone(&self.id),
+            author_id: ::core::clone::Clone::clone(&self.author_id),
+            reply_to: ::core::clone::Clone::clone(&self.reply_to),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for PostChangeSet {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field3_finish(
+            f,
+            "PostChangeSet",
+            "id",
+            &self.id,
+            "author_id",
+            &self.author_id,
+            "reply_to",
+            &&self.reply_to,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for PostChangeSet {
+    #[inline]
+    fn default() -> PostChangeSet {
+        PostChangeSet {
+            id: ::core::default::Default::default(),
+            author_id: ::core::default::Default::default(),
+            reply_to: ::core::default::Default::default(),
+        }
+    }
+}
+impl tursorm::ChangeSetTrait for PostChangeSet {
+    type Table = PostTable;
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.id {
+            columns.push("id");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.author_id {
+            columns.push("author");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.reply_to {
+            columns.push("reply_to");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        (columns, values)
+    }
+    fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+        let mut sets = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.author_id {
+            sets.push(("author", tursorm::IntoValue::into_value((v).clone())));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.reply_to {
+            sets.push(("reply_to", tursorm::IntoValue::into_value((v).clone())));
+        }
+        sets
+    }
+    fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+        match &self.id {
+            tursorm::FieldValue::Set(v) => {
+                Some(tursorm::IntoValue::into_value((v).clone()))
+            }
+            tursorm::FieldValue::NotSet => None,
+        }
+    }
+    fn primary_key_column() -> &'static str {
+        "id"
+    }
+    fn merge(&mut self, later: Self) {
+        if later.id.is_changed() {
+            self.id = later.id;
+        }
+        if later.author_id.is_changed() {
+            self.author_id = later.author_id;
+        }
+        if later.reply_to.is_changed() {
+            self.reply_to = later.reply_to;
+        }
+    }
+}
+impl From<Post> for PostChangeSet {
+    fn from(record: Post) -> Self {
+        Self {
+            id: tursorm::FieldValue::Set(record.id.clone()),
+            author_id: tursorm::FieldValue::Set(record.author_id.clone()),
+            reply_to: tursorm::FieldValue::Set(record.reply_to.clone()),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use tursorm::Table;
+
+#[derive(Table)]
+#[tursorm(table_name = "posts")]
+struct Post {
+    #[tursorm(primary_key)]
+    id: i64,
+    #[tursorm(foreign_key, references = "users.uid", column_name = "author", on_delete = "cascade")]
+    author_id: i64,
+    #[tursorm(foreign_key, references = "posts", on_delete = "set_null", on_update = "cascade")]
+    reply_to: Option<i64>,
+}
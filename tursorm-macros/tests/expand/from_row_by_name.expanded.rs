@@ -0,0 +1,59 @@
+use tursorm::FromRow;
+#[tursorm(from_row = "by_name")]
+struct Summary {
+    #[tursorm(column_name = "n")]
+    count: i64,
+    label: Option<String>,
+    #[tursorm(with = "codecs::csv")]
+    tags: Vec<String>,
+}
+impl tursorm::FromRow for Summary {
+    fn from_row(_row: &tursorm::Row) -> tursorm::Result<Self> {
+        Err(
+            tursorm::Error::Query(
+                "Summary reads columns by name; decode it with FromRow::from_named_row"
+                    .to_string(),
+            ),
+        )
+    }
+    fn from_named_row(
+        row: &tursorm::Row,
+        columns: &tursorm::RowColumns,
+    ) -> tursorm::Result<Self> {
+        Ok(Self {
+            count: tursorm::FromValue::from_value(
+                row
+                    .get_value(columns.index("n")?)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            label: tursorm::FromValue::from_value_opt(
+                    row.get_value(columns.index("label")?)?,
+                )
+                .map_err(|e| tursorm::Error::TypeConversion {
+                    expected: "Text",
+                    actual: ::alloc::__export::must_use({
+                        ::alloc::fmt::format(format_args!("{0:?}", e))
+                    }),
+                    error: "Conversion error".to_string(),
+                })?,
+            tags: codecs::csv::from_value(row.get_value(columns.index("tags")?)?)?,
+        })
+    }
+}
+mod codecs {
+    pub mod csv {
+        use tursorm::prelude::*;
+        pub fn to_value(tags: &[String]) -> Value {
+            Value::Text(tags.join(","))
+        }
+        pub fn from_value(value: Value) -> Result<Vec<String>> {
+            Ok(String::from_value(value)?.split(',').map(str::to_string).collect())
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use tursorm::FromRow;
+
+#[derive(FromRow)]
+#[tursorm(from_row = "by_name")]
+struct Summary {
+    #[tursorm(column_name = "n")]
+    count: i64,
+    label: Option<String>,
+    #[tursorm(with = "codecs::csv")]
+    tags: Vec<String>,
+}
+
+mod codecs {
+    pub mod csv {
+        use tursorm::prelude::*;
+
+        pub fn to_value(tags: &[String]) -> Value {
+            Value::Text(tags.join(","))
+        }
+
+        pub fn from_value(value: Value) -> Result<Vec<String>> {
+            Ok(String::from_value(value)?.split(',').map(str::to_string).collect())
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use tursorm::Table;
+struct Account {
+    #[tursorm(primary_key, unique)]
+    id: i64,
+}
+enum AccountColumn {
+    Id,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for AccountColumn {
+    #[inline]
+    fn clone(&self) -> AccountColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for AccountColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for AccountColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "Id")
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for AccountColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for AccountColumn {
+    #[inline]
+    fn eq(&self, other: &AccountColumn) -> bool {
+        true
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for AccountColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for AccountColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {}
+}
+impl tursorm::ColumnTrait for AccountColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Id => tursorm::ColumnType::Integer,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Id => false,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Id => true,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Id => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Id => true,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Id => None,
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Id]
+    }
+}
+impl ::std::fmt::Display for AccountColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for AccountColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+struct AccountTable;
+#[automatically_derived]
+impl ::core::clone::Clone for AccountTable {
+    #[inline]
+    fn clone(&self) -> AccountTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for AccountTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for AccountTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "AccountTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for AccountTable {
+    #[inline]
+    fn default() -> AccountTable {
+        AccountTable {}
+    }
+}
+impl tursorm::TableTrait for AccountTable {
+    type Record = Account;
+    type Column = AccountColumn;
+    type ChangeSet = AccountChangeSet;
+    type PrimaryKeyType = i64;
+    fn table_name() -> &'static str {
+        "account"
+    }
+    fn primary_key() -> Self::Column {
+        AccountColumn::Id
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "id"
+    }
+    fn column_count() -> usize {
+        1usize
+    }
+}
+impl tursorm::FromRow for Account {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            id: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+        })
+    }
+}
+impl tursorm::RecordTrait for Account {
+    type Table = AccountTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.id).clone())
+    }
+}
+impl Account {
+    /// Converts this record into a change set that keeps the primary key and only the fields that differ
+    /// from `original`, so updating with it writes just the edited columns.
+    fn into_change_set_diff(self, original: &Self) -> AccountChangeSet {
+        AccountChangeSet {
+            id: tursorm::FieldValue::Set(self.id),
+        }
+    }
+}
+impl AccountTable {
+    pub fn change_set() -> AccountChangeSet {
+        AccountChangeSet::default()
+    }
+}
+struct AccountChangeSet {
+    pub id: tursorm::FieldValue<i64>,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for AccountChangeSet {
+    #[inline]
+    fn clone(&self) -> AccountChangeSet {
+        AccountChangeSet {
+            id: ::core::clone::Clone::clone(&self.id),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for AccountChangeSet {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field1_finish(
+            f,
+            "AccountChangeSet",
+            "id",
+            &&self.id,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for AccountChangeSet {
+    #[inline]
+    fn default() -> AccountChangeSet {
+        AccountChangeSet {
+            id: ::core::default::Default::default(),
+        }
+    }
+}
+impl tursorm::ChangeSetTrait for AccountChangeSet {
+    type Table = AccountTable;
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.id {
+            columns.push("id");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        (columns, values)
+    }
+    fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+        let mut sets = Vec::new();
+        sets
+    }
+    fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+        match &self.id {
+            tursorm::FieldValue::Set(v) => {
+                Some(tursorm::IntoValue::into_value((v).clone()))
+            }
+            tursorm::FieldValue::NotSet => None,
+        }
+    }
+    fn primary_key_column() -> &'static str {
+        "id"
+    }
+    fn merge(&mut self, later: Self) {
+        if later.id.is_changed() {
+            self.id = later.id;
+        }
+    }
+}
+impl From<Account> for AccountChangeSet {
+    fn from(record: Account) -> Self {
+        Self {
+            id: tursorm::FieldValue::Set(record.id.clone()),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use tursorm::Table;
+
+#[derive(Table)]
+struct Account {
+    #[tursorm(primary_key, unique)]
+    id: i64,
+}
@@ -0,0 +1,208 @@
+use tursorm::Table;
+#[tursorm(readonly, no_column_enum_display)]
+struct DailyTotal {
+    #[tursorm(primary_key)]
+    day: String,
+    total: i64,
+}
+enum DailyTotalColumn {
+    Day,
+    Total,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for DailyTotalColumn {
+    #[inline]
+    fn clone(&self) -> DailyTotalColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for DailyTotalColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for DailyTotalColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                DailyTotalColumn::Day => "Day",
+                DailyTotalColumn::Total => "Total",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for DailyTotalColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for DailyTotalColumn {
+    #[inline]
+    fn eq(&self, other: &DailyTotalColumn) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for DailyTotalColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for DailyTotalColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        ::core::hash::Hash::hash(&__self_discr, state)
+    }
+}
+impl tursorm::ColumnTrait for DailyTotalColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Total => "total",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Day => tursorm::ColumnType::Text,
+            Self::Total => tursorm::ColumnType::Integer,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Day => false,
+            Self::Total => false,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Day => true,
+            Self::Total => false,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Day => false,
+            Self::Total => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Day => false,
+            Self::Total => false,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Day => None,
+            Self::Total => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Day => None,
+            Self::Total => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Day => None,
+            Self::Total => None,
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Day, Self::Total]
+    }
+}
+impl ::std::str::FromStr for DailyTotalColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+struct DailyTotalTable;
+#[automatically_derived]
+impl ::core::clone::Clone for DailyTotalTable {
+    #[inline]
+    fn clone(&self) -> DailyTotalTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for DailyTotalTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for DailyTotalTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "DailyTotalTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for DailyTotalTable {
+    #[inline]
+    fn default() -> DailyTotalTable {
+        DailyTotalTable {}
+    }
+}
+impl tursorm::TableTrait for DailyTotalTable {
+    type Record = DailyTotal;
+    type Column = DailyTotalColumn;
+    type ChangeSet = tursorm::ReadOnlyChangeSet<Self>;
+    type PrimaryKeyType = String;
+    const READ_ONLY: bool = true;
+    fn table_name() -> &'static str {
+        "daily_total"
+    }
+    fn primary_key() -> Self::Column {
+        DailyTotalColumn::Day
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "day, total"
+    }
+    fn column_count() -> usize {
+        2usize
+    }
+}
+impl tursorm::FromRow for DailyTotal {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            day: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            total: tursorm::FromValue::from_value(
+                row
+                    .get_value(1usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+        })
+    }
+}
+impl tursorm::RecordTrait for DailyTotal {
+    type Table = DailyTotalTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.day).clone())
+    }
+}
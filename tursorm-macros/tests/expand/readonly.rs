@@ -0,0 +1,9 @@
+use tursorm::Table;
+
+#[derive(Table)]
+#[tursorm(readonly, no_column_enum_display)]
+struct DailyTotal {
+    #[tursorm(primary_key)]
+    day: String,
+    total: i64,
+}
@@ -0,0 +1,779 @@
+use tursorm::Table;
+#[tursorm(
+    tree = "parent_id",
+    polymorphic(owner_type, owner_id),
+    many_to_many(name = "tags", related = "TagTable", through = "category_tags")
+)]
+struct Category {
+    #[tursorm(primary_key)]
+    id: i64,
+    parent_id: Option<i64>,
+    owner_type: String,
+    owner_id: i64,
+}
+enum CategoryColumn {
+    Id,
+    ParentId,
+    OwnerType,
+    OwnerId,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for CategoryColumn {
+    #[inline]
+    fn clone(&self) -> CategoryColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for CategoryColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for CategoryColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                CategoryColumn::Id => "Id",
+                CategoryColumn::ParentId => "ParentId",
+                CategoryColumn::OwnerType => "OwnerType",
+                CategoryColumn::OwnerId => "OwnerId",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for CategoryColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for CategoryColumn {
+    #[inline]
+    fn eq(&self, other: &CategoryColumn) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for CategoryColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for CategoryColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        ::core::hash::Hash::hash(&__self_discr, state)
+    }
+}
+impl tursorm::ColumnTrait for CategoryColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::ParentId => "parent_id",
+            Self::OwnerType => "owner_type",
+            Self::OwnerId => "owner_id",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Id => tursorm::ColumnType::Integer,
+            Self::ParentId => tursorm::ColumnType::Integer,
+            Self::OwnerType => tursorm::ColumnType::Text,
+            Self::OwnerId => tursorm::ColumnType::Integer,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::ParentId => true,
+            Self::OwnerType => false,
+            Self::OwnerId => false,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Id => true,
+            Self::ParentId => false,
+            Self::OwnerType => false,
+            Self::OwnerId => false,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::ParentId => false,
+            Self::OwnerType => false,
+            Self::OwnerId => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::ParentId => false,
+            Self::OwnerType => false,
+            Self::OwnerId => false,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::ParentId => None,
+            Self::OwnerType => None,
+            Self::OwnerId => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::ParentId => None,
+            Self::OwnerType => None,
+            Self::OwnerId => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Id => None,
+            Self::ParentId => None,
+            Self::OwnerType => None,
+            Self::OwnerId => None,
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Id, Self::ParentId, Self::OwnerType, Self::OwnerId]
+    }
+}
+impl ::std::fmt::Display for CategoryColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for CategoryColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+struct CategoryTable;
+#[automatically_derived]
+impl ::core::clone::Clone for CategoryTable {
+    #[inline]
+    fn clone(&self) -> CategoryTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for CategoryTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for CategoryTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "CategoryTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for CategoryTable {
+    #[inline]
+    fn default() -> CategoryTable {
+        CategoryTable {}
+    }
+}
+impl tursorm::TableTrait for CategoryTable {
+    type Record = Category;
+    type Column = CategoryColumn;
+    type ChangeSet = CategoryChangeSet;
+    type PrimaryKeyType = i64;
+    fn table_name() -> &'static str {
+        "category"
+    }
+    fn primary_key() -> Self::Column {
+        CategoryColumn::Id
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "id, parent_id, owner_type, owner_id"
+    }
+    fn column_count() -> usize {
+        4usize
+    }
+    fn junction_tables() -> &'static [&'static str] {
+        &["category_tags"]
+    }
+}
+impl tursorm::FromRow for Category {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            id: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            parent_id: tursorm::FromValue::from_value_opt(row.get_value(1usize)?)
+                .map_err(|e| tursorm::Error::TypeConversion {
+                    expected: "Integer",
+                    actual: ::alloc::__export::must_use({
+                        ::alloc::fmt::format(format_args!("{0:?}", e))
+                    }),
+                    error: "Conversion error".to_string(),
+                })?,
+            owner_type: tursorm::FromValue::from_value(
+                row
+                    .get_value(2usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            owner_id: tursorm::FromValue::from_value(
+                row
+                    .get_value(3usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+        })
+    }
+}
+impl tursorm::RecordTrait for Category {
+    type Table = CategoryTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.id).clone())
+    }
+}
+impl Category {
+    /// Converts this record into a change set that keeps the primary key and only the fields that differ
+    /// from `original`, so updating with it writes just the edited columns.
+    fn into_change_set_diff(self, original: &Self) -> CategoryChangeSet {
+        CategoryChangeSet {
+            id: tursorm::FieldValue::Set(self.id),
+            parent_id: if tursorm::IntoValue::into_value((&self.parent_id).clone())
+                == tursorm::IntoValue::into_value((&original.parent_id).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.parent_id)
+            },
+            owner_type: if tursorm::IntoValue::into_value((&self.owner_type).clone())
+                == tursorm::IntoValue::into_value((&original.owner_type).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.owner_type)
+            },
+            owner_id: if tursorm::IntoValue::into_value((&self.owner_id).clone())
+                == tursorm::IntoValue::into_value((&original.owner_id).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.owner_id)
+            },
+        }
+    }
+}
+impl CategoryTable {
+    pub fn change_set() -> CategoryChangeSet {
+        CategoryChangeSet::default()
+    }
+}
+struct CategoryChangeSet {
+    pub id: tursorm::FieldValue<i64>,
+    pub parent_id: tursorm::FieldValue<Option<i64>>,
+    pub owner_type: tursorm::FieldValue<String>,
+    pub owner_id: tursorm::FieldValue<i64>,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for CategoryChangeSet {
+    #[inline]
+    fn clone(&self) -> CategoryChangeSet {
+        CategoryChangeSet {
+            id: ::core::clone::Clone::clone(&self.id),
+            parent_id: ::core::clone::Clone::clone(&self.parent_id),
+            owner_type: ::core::clone::Clone::clone(&self.owner_type),
+            owner_id: ::core::clone::Clone::clone(&self.owner_id),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for CategoryChangeSet {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field4_finish(
+            f,
+            "CategoryChangeSet",
+            "id",
+            &self.id,
+            "parent_id",
+            &self.parent_id,
+            "owner_type",
+            &self.owner_type,
+            "owner_id",
+            &&self.owner_id,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for CategoryChangeSet {
+    #[inline]
+    fn default() -> CategoryChangeSet {
+        CategoryChangeSet {
+            id: ::core::default::Default::default(),
+            parent_id: ::core::default::Default::default(),
+            owner_type: ::core::default::Default::default(),
+            owner_id: ::core::default::Default::default(),
+        }
+    }
+}
+impl tursorm::ChangeSetTrait for CategoryChangeSet {
+    type Table = CategoryTable;
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.id {
+            columns.push("id");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.parent_id {
+            columns.push("parent_id");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.owner_type {
+            columns.push("owner_type");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.owner_id {
+            columns.push("owner_id");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        (columns, values)
+    }
+    fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+        let mut sets = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.parent_id {
+            sets.push(("parent_id", tursorm::IntoValue::into_value((v).clone())));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.owner_type {
+            sets.push(("owner_type", tursorm::IntoValue::into_value((v).clone())));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.owner_id {
+            sets.push(("owner_id", tursorm::IntoValue::into_value((v).clone())));
+        }
+        sets
+    }
+    fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+        match &self.id {
+            tursorm::FieldValue::Set(v) => {
+                Some(tursorm::IntoValue::into_value((v).clone()))
+            }
+            tursorm::FieldValue::NotSet => None,
+        }
+    }
+    fn primary_key_column() -> &'static str {
+        "id"
+    }
+    fn merge(&mut self, later: Self) {
+        if later.id.is_changed() {
+            self.id = later.id;
+        }
+        if later.parent_id.is_changed() {
+            self.parent_id = later.parent_id;
+        }
+        if later.owner_type.is_changed() {
+            self.owner_type = later.owner_type;
+        }
+        if later.owner_id.is_changed() {
+            self.owner_id = later.owner_id;
+        }
+    }
+}
+impl From<Category> for CategoryChangeSet {
+    fn from(record: Category) -> Self {
+        Self {
+            id: tursorm::FieldValue::Set(record.id.clone()),
+            parent_id: tursorm::FieldValue::Set(record.parent_id.clone()),
+            owner_type: tursorm::FieldValue::Set(record.owner_type.clone()),
+            owner_id: tursorm::FieldValue::Set(record.owner_id.clone()),
+        }
+    }
+}
+impl tursorm::PolymorphicTrait for CategoryTable {
+    fn owner_type_column() -> Self::Column {
+        CategoryColumn::OwnerType
+    }
+    fn owner_id_column() -> Self::Column {
+        CategoryColumn::OwnerId
+    }
+    fn owner_of(record: &Category) -> (tursorm::Value, tursorm::Value) {
+        (
+            tursorm::IntoValue::into_value((&record.owner_type).clone()),
+            tursorm::IntoValue::into_value((&record.owner_id).clone()),
+        )
+    }
+}
+impl Category {
+    pub fn of<Owner: tursorm::RecordTrait>(owner: &Owner) -> tursorm::Condition {
+        <CategoryTable as tursorm::PolymorphicTrait>::of(owner)
+    }
+}
+impl CategoryChangeSet {
+    pub fn with_owner<Owner: tursorm::RecordTrait>(
+        mut self,
+        owner: &Owner,
+    ) -> tursorm::Result<Self> {
+        let owner_type = <CategoryTable as tursorm::PolymorphicTrait>::owner_type::<
+            Owner,
+        >();
+        self.owner_type = tursorm::FieldValue::Set(
+            tursorm::FromValue::from_value(tursorm::Value::Text(owner_type.to_string()))?,
+        );
+        self.owner_id = tursorm::FieldValue::Set(
+            tursorm::FromValue::from_value(owner.get_primary_key_value())?,
+        );
+        Ok(self)
+    }
+}
+impl CategoryTable {
+    pub fn tags() -> tursorm::ManyToMany<CategoryTable, TagTable> {
+        tursorm::ManyToMany::new("tags", "category_tags", "category_id", "tag_id")
+    }
+}
+impl Category {
+    pub async fn add_tag<Conn: tursorm::ConnectionLike>(
+        &self,
+        conn: &Conn,
+        id: impl Into<<TagTable as tursorm::TableTrait>::PrimaryKeyType>,
+    ) -> tursorm::Result<bool> {
+        let id = <TagTable as tursorm::TableTrait>::primary_key_to_value(&id.into());
+        CategoryTable::tags()
+            .attach(conn, tursorm::RecordTrait::get_primary_key_value(self), id)
+            .await
+    }
+    pub async fn remove_tag<Conn: tursorm::ConnectionLike>(
+        &self,
+        conn: &Conn,
+        id: impl Into<<TagTable as tursorm::TableTrait>::PrimaryKeyType>,
+    ) -> tursorm::Result<bool> {
+        let id = <TagTable as tursorm::TableTrait>::primary_key_to_value(&id.into());
+        CategoryTable::tags()
+            .detach(conn, tursorm::RecordTrait::get_primary_key_value(self), id)
+            .await
+    }
+    pub async fn set_tags<Conn: tursorm::ConnectionLike>(
+        &self,
+        conn: &Conn,
+        ids: impl IntoIterator<
+            Item = impl Into<<TagTable as tursorm::TableTrait>::PrimaryKeyType>,
+        >,
+    ) -> tursorm::Result<()> {
+        let ids = ids
+            .into_iter()
+            .map(|id| <TagTable as tursorm::TableTrait>::primary_key_to_value(
+                &id.into(),
+            ))
+            .collect();
+        CategoryTable::tags()
+            .set(conn, tursorm::RecordTrait::get_primary_key_value(self), ids)
+            .await
+    }
+    pub fn tags(&self) -> tursorm::Select<TagTable> {
+        CategoryTable::tags().related(tursorm::RecordTrait::get_primary_key_value(self))
+    }
+}
+trait CategoryRelations {
+    fn with_tags(self) -> Self;
+}
+impl CategoryRelations for tursorm::Select<CategoryTable> {
+    fn with_tags(self) -> Self {
+        self.join_related(&CategoryTable::tags())
+    }
+}
+impl tursorm::TreeTrait for CategoryTable {
+    fn parent_column() -> Self::Column {
+        CategoryColumn::ParentId
+    }
+    fn parent_of(record: &Category) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&record.parent_id).clone())
+    }
+}
+struct Tag {
+    #[tursorm(primary_key)]
+    id: i64,
+}
+enum TagColumn {
+    Id,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for TagColumn {
+    #[inline]
+    fn clone(&self) -> TagColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for TagColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for TagColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "Id")
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for TagColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for TagColumn {
+    #[inline]
+    fn eq(&self, other: &TagColumn) -> bool {
+        true
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for TagColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for TagColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {}
+}
+impl tursorm::ColumnTrait for TagColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Id => tursorm::ColumnType::Integer,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Id => false,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Id => true,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Id => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Id => false,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Id => None,
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Id]
+    }
+}
+impl ::std::fmt::Display for TagColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for TagColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+struct TagTable;
+#[automatically_derived]
+impl ::core::clone::Clone for TagTable {
+    #[inline]
+    fn clone(&self) -> TagTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for TagTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for TagTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "TagTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for TagTable {
+    #[inline]
+    fn default() -> TagTable {
+        TagTable {}
+    }
+}
+impl tursorm::TableTrait for TagTable {
+    type Record = Tag;
+    type Column = TagColumn;
+    type ChangeSet = TagChangeSet;
+    type PrimaryKeyType = i64;
+    fn table_name() -> &'static str {
+        "tag"
+    }
+    fn primary_key() -> Self::Column {
+        TagColumn::Id
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "id"
+    }
+    fn column_count() -> usize {
+        1usize
+    }
+}
+impl tursorm::FromRow for Tag {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            id: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+        })
+    }
+}
+impl tursorm::RecordTrait for Tag {
+    type Table = TagTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.id).clone())
+    }
+}
+impl Tag {
+    /// Converts this record into a change set that keeps the primary key and only the fields that differ
+    /// from `original`, so updating with it writes just the edited columns.
+    fn into_change_set_diff(self, original: &Self) -> TagChangeSet {
+        TagChangeSet {
+            id: tursorm::FieldValue::Set(self.id),
+        }
+    }
+}
+impl TagTable {
+    pub fn change_set() -> TagChangeSet {
+        TagChangeSet::default()
+    }
+}
+struct TagChangeSet {
+    pub id: tursorm::FieldValue<i64>,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for TagChangeSet {
+    #[inline]
+    fn clone(&self) -> TagChangeSet {
+        TagChangeSet {
+            id: ::core::clone::Clone::clone(&self.id),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for TagChangeSet {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field1_finish(
+            f,
+            "TagChangeSet",
+            "id",
+            &&self.id,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for TagChangeSet {
+    #[inline]
+    fn default() -> TagChangeSet {
+        TagChangeSet {
+            id: ::core::default::Default::default(),
+        }
+    }
+}
+impl tursorm::ChangeSetTrait for TagChangeSet {
+    type Table = TagTable;
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.id {
+            columns.push("id");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        (columns, values)
+    }
+    fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+        let mut sets = Vec::new();
+        sets
+    }
+    fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+        match &self.id {
+            tursorm::FieldValue::Set(v) => {
+                Some(tursorm::IntoValue::into_value((v).clone()))
+            }
+            tursorm::FieldValue::NotSet => None,
+        }
+    }
+    fn primary_key_column() -> &'static str {
+        "id"
+    }
+    fn merge(&mut self, later: Self) {
+        if later.id.is_changed() {
+            self.id = later.id;
+        }
+    }
+}
+impl From<Tag> for TagChangeSet {
+    fn from(record: Tag) -> Self {
+        Self {
+            id: tursorm::FieldValue::Set(record.id.clone()),
+        }
+    }
+}
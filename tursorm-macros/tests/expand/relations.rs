@@ -0,0 +1,21 @@
+use tursorm::Table;
+
+#[derive(Table)]
+#[tursorm(
+    tree = "parent_id",
+    polymorphic(owner_type, owner_id),
+    many_to_many(name = "tags", related = "TagTable", through = "category_tags")
+)]
+struct Category {
+    #[tursorm(primary_key)]
+    id: i64,
+    parent_id: Option<i64>,
+    owner_type: String,
+    owner_id: i64,
+}
+
+#[derive(Table)]
+struct Tag {
+    #[tursorm(primary_key)]
+    id: i64,
+}
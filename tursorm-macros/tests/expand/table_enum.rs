@@ -0,0 +1,39 @@
+use tursorm::prelude::*;
+
+#[derive(Table)]
+#[tursorm(discriminator = "kind")]
+struct Event {
+    #[tursorm(primary_key)]
+    id: i64,
+    kind: String,
+}
+
+#[derive(FromRow)]
+struct Click {
+    id: i64,
+}
+
+#[derive(FromRow)]
+struct PageView {
+    id: i64,
+}
+
+impl From<Click> for EventChangeSet {
+    fn from(click: Click) -> Self {
+        EventChangeSet { id: set(click.id), ..Default::default() }
+    }
+}
+
+impl From<PageView> for EventChangeSet {
+    fn from(view: PageView) -> Self {
+        EventChangeSet { id: set(view.id), ..Default::default() }
+    }
+}
+
+#[derive(TableEnum)]
+#[tursorm(table = "EventTable")]
+enum EventKind {
+    Click(Click),
+    #[tursorm(discriminator = "view")]
+    PageView(PageView),
+}
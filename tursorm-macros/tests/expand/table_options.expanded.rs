@@ -0,0 +1,986 @@
+use tursorm::Table;
+/// Archived people.
+#[tursorm(
+    table_name = "people",
+    renamed_from = "persons",
+    database = "archive",
+    default_limit = 50,
+    serde
+)]
+pub struct Person {
+    #[tursorm(primary_key, uuid_blob)]
+    id: uuid::Uuid,
+    #[tursorm(timestamp_seconds)]
+    born_at: chrono::NaiveDateTime,
+}
+pub enum PersonColumn {
+    Id,
+    BornAt,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for PersonColumn {
+    #[inline]
+    fn clone(&self) -> PersonColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for PersonColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for PersonColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                PersonColumn::Id => "Id",
+                PersonColumn::BornAt => "BornAt",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for PersonColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for PersonColumn {
+    #[inline]
+    fn eq(&self, other: &PersonColumn) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for PersonColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for PersonColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        ::core::hash::Hash::hash(&__self_discr, state)
+    }
+}
+impl tursorm::ColumnTrait for PersonColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::BornAt => "born_at",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Id => tursorm::ColumnType::Blob,
+            Self::BornAt => tursorm::ColumnType::Integer,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::BornAt => false,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Id => true,
+            Self::BornAt => false,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::BornAt => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::BornAt => false,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::BornAt => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::BornAt => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Id => None,
+            Self::BornAt => None,
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Id, Self::BornAt]
+    }
+}
+impl ::std::fmt::Display for PersonColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for PersonColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+pub struct PersonTable;
+#[automatically_derived]
+impl ::core::clone::Clone for PersonTable {
+    #[inline]
+    fn clone(&self) -> PersonTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for PersonTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for PersonTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "PersonTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for PersonTable {
+    #[inline]
+    fn default() -> PersonTable {
+        PersonTable {}
+    }
+}
+impl tursorm::TableTrait for PersonTable {
+    type Record = Person;
+    type Column = PersonColumn;
+    type ChangeSet = PersonChangeSet;
+    type PrimaryKeyType = uuid::Uuid;
+    const DEFAULT_LIMIT: Option<usize> = Some(50usize);
+    fn table_name() -> &'static str {
+        "people"
+    }
+    fn primary_key() -> Self::Column {
+        PersonColumn::Id
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::codec::uuid_blob::to_value(id)
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "id, born_at"
+    }
+    fn column_count() -> usize {
+        2usize
+    }
+    fn renamed_from() -> Option<&'static str> {
+        Some("persons")
+    }
+    fn table_docs() -> Option<&'static str> {
+        Some("Archived people.")
+    }
+    fn schema_name() -> Option<&'static str> {
+        Some("archive")
+    }
+}
+impl tursorm::FromRow for Person {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            id: tursorm::codec::uuid_blob::from_value(row.get_value(0usize)?)?,
+            born_at: tursorm::codec::timestamp_seconds::from_value(
+                row.get_value(1usize)?,
+            )?,
+        })
+    }
+}
+impl tursorm::RecordTrait for Person {
+    type Table = PersonTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::codec::uuid_blob::to_value(&self.id)
+    }
+}
+impl Person {
+    /// Converts this record into a change set that keeps the primary key and only the fields that differ
+    /// from `original`, so updating with it writes just the edited columns.
+    pub fn into_change_set_diff(self, original: &Self) -> PersonChangeSet {
+        PersonChangeSet {
+            id: tursorm::FieldValue::Set(self.id),
+            born_at: if tursorm::codec::timestamp_seconds::to_value(&self.born_at)
+                == tursorm::codec::timestamp_seconds::to_value(&original.born_at)
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.born_at)
+            },
+        }
+    }
+}
+impl PersonTable {
+    pub fn change_set() -> PersonChangeSet {
+        PersonChangeSet::default()
+    }
+}
+#[serde(crate = "tursorm::serde")]
+pub struct PersonChangeSet {
+    #[serde(
+        rename = "id",
+        default,
+        skip_serializing_if = "tursorm::FieldValue::is_not_set"
+    )]
+    pub id: tursorm::FieldValue<uuid::Uuid>,
+    #[serde(
+        rename = "born_at",
+        default,
+        skip_serializing_if = "tursorm::FieldValue::is_not_set"
+    )]
+    pub born_at: tursorm::FieldValue<chrono::NaiveDateTime>,
+}
+#[doc(hidden)]
+#[allow(
+    non_upper_case_globals,
+    unused_attributes,
+    unused_qualifications,
+    clippy::absolute_paths,
+)]
+const _: () = {
+    use tursorm::serde as _serde;
+    #[automatically_derived]
+    impl _serde::Serialize for PersonChangeSet {
+        fn serialize<__S>(
+            &self,
+            __serializer: __S,
+        ) -> _serde::__private228::Result<__S::Ok, __S::Error>
+        where
+            __S: _serde::Serializer,
+        {
+            let mut __serde_state = _serde::Serializer::serialize_struct(
+                __serializer,
+                "PersonChangeSet",
+                false as usize
+                    + if tursorm::FieldValue::is_not_set(&self.id) { 0 } else { 1 }
+                    + if tursorm::FieldValue::is_not_set(&self.born_at) { 0 } else { 1 },
+            )?;
+            if !tursorm::FieldValue::is_not_set(&self.id) {
+                _serde::ser::SerializeStruct::serialize_field(
+                    &mut __serde_state,
+                    "id",
+                    &self.id,
+                )?;
+            } else {
+                _serde::ser::SerializeStruct::skip_field(&mut __serde_state, "id")?;
+            }
+            if !tursorm::FieldValue::is_not_set(&self.born_at) {
+                _serde::ser::SerializeStruct::serialize_field(
+                    &mut __serde_state,
+                    "born_at",
+                    &self.born_at,
+                )?;
+            } else {
+                _serde::ser::SerializeStruct::skip_field(&mut __serde_state, "born_at")?;
+            }
+            _serde::ser::SerializeStruct::end(__serde_state)
+        }
+    }
+};
+#[doc(hidden)]
+#[allow(
+    non_upper_case_globals,
+    unused_attributes,
+    unused_qualifications,
+    clippy::absolute_paths,
+)]
+const _: () = {
+    use tursorm::serde as _serde;
+    #[automatically_derived]
+    impl<'de> _serde::Deserialize<'de> for PersonChangeSet {
+        fn deserialize<__D>(
+            __deserializer: __D,
+        ) -> _serde::__private228::Result<Self, __D::Error>
+        where
+            __D: _serde::Deserializer<'de>,
+        {
+            #[allow(non_camel_case_types)]
+            #[doc(hidden)]
+            enum __Field {
+                __field0,
+                __field1,
+                __ignore,
+            }
+            #[doc(hidden)]
+            struct __FieldVisitor;
+            #[automatically_derived]
+            impl<'de> _serde::de::Visitor<'de> for __FieldVisitor {
+                type Value = __Field;
+                fn expecting(
+                    &self,
+                    __formatter: &mut _serde::__private228::Formatter,
+                ) -> _serde::__private228::fmt::Result {
+                    _serde::__private228::Formatter::write_str(
+                        __formatter,
+                        "field identifier",
+                    )
+                }
+                fn visit_u64<__E>(
+                    self,
+                    __value: u64,
+                ) -> _serde::__private228::Result<Self::Value, __E>
+                where
+                    __E: _serde::de::Error,
+                {
+                    match __value {
+                        0u64 => _serde::__private228::Ok(__Field::__field0),
+                        1u64 => _serde::__private228::Ok(__Field::__field1),
+                        _ => _serde::__private228::Ok(__Field::__ignore),
+                    }
+                }
+                fn visit_str<__E>(
+                    self,
+                    __value: &str,
+                ) -> _serde::__private228::Result<Self::Value, __E>
+                where
+                    __E: _serde::de::Error,
+                {
+                    match __value {
+                        "id" => _serde::__private228::Ok(__Field::__field0),
+                        "born_at" => _serde::__private228::Ok(__Field::__field1),
+                        _ => _serde::__private228::Ok(__Field::__ignore),
+                    }
+                }
+                fn visit_bytes<__E>(
+                    self,
+                    __value: &[u8],
+                ) -> _serde::__private228::Result<Self::Value, __E>
+                where
+                    __E: _serde::de::Error,
+                {
+                    match __value {
+                        b"id" => _serde::__private228::Ok(__Field::__field0),
+                        b"born_at" => _serde::__private228::Ok(__Field::__field1),
+                        _ => _serde::__private228::Ok(__Field::__ignore),
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl<'de> _serde::Deserialize<'de> for __Field {
+                #[inline]
+                fn deserialize<__D>(
+                    __deserializer: __D,
+                ) -> _serde::__private228::Result<Self, __D::Error>
+                where
+                    __D: _serde::Deserializer<'de>,
+                {
+                    _serde::Deserializer::deserialize_identifier(
+                        __deserializer,
+                        __FieldVisitor,
+                    )
+                }
+            }
+            #[doc(hidden)]
+            struct __Visitor<'de> {
+                marker: _serde::__private228::PhantomData<PersonChangeSet>,
+                lifetime: _serde::__private228::PhantomData<&'de ()>,
+            }
+            #[automatically_derived]
+            impl<'de> _serde::de::Visitor<'de> for __Visitor<'de> {
+                type Value = PersonChangeSet;
+                fn expecting(
+                    &self,
+                    __formatter: &mut _serde::__private228::Formatter,
+                ) -> _serde::__private228::fmt::Result {
+                    _serde::__private228::Formatter::write_str(
+                        __formatter,
+                        "struct PersonChangeSet",
+                    )
+                }
+                #[inline]
+                fn visit_seq<__A>(
+                    self,
+                    mut __seq: __A,
+                ) -> _serde::__private228::Result<Self::Value, __A::Error>
+                where
+                    __A: _serde::de::SeqAccess<'de>,
+                {
+                    let __field0 = match _serde::de::SeqAccess::next_element::<
+                        tursorm::FieldValue<uuid::Uuid>,
+                    >(&mut __seq)? {
+                        _serde::__private228::Some(__value) => __value,
+                        _serde::__private228::None => {
+                            _serde::__private228::Default::default()
+                        }
+                    };
+                    let __field1 = match _serde::de::SeqAccess::next_element::<
+                        tursorm::FieldValue<chrono::NaiveDateTime>,
+                    >(&mut __seq)? {
+                        _serde::__private228::Some(__value) => __value,
+                        _serde::__private228::None => {
+                            _serde::__private228::Default::default()
+                        }
+                    };
+                    _serde::__private228::Ok(PersonChangeSet {
+                        id: __field0,
+                        born_at: __field1,
+                    })
+                }
+                #[inline]
+                fn visit_map<__A>(
+                    self,
+                    mut __map: __A,
+                ) -> _serde::__private228::Result<Self::Value, __A::Error>
+                where
+                    __A: _serde::de::MapAccess<'de>,
+                {
+                    let mut __field0: _serde::__private228::Option<
+                        tursorm::FieldValue<uuid::Uuid>,
+                    > = _serde::__private228::None;
+                    let mut __field1: _serde::__private228::Option<
+                        tursorm::FieldValue<chrono::NaiveDateTime>,
+                    > = _serde::__private228::None;
+                    while let _serde::__private228::Some(__key) = _serde::de::MapAccess::next_key::<
+                        __Field,
+                    >(&mut __map)? {
+                        match __key {
+                            __Field::__field0 => {
+                                if _serde::__private228::Option::is_some(&__field0) {
+                                    return _serde::__private228::Err(
+                                        <__A::Error as _serde::de::Error>::duplicate_field("id"),
+                                    );
+                                }
+                                __field0 = _serde::__private228::Some(
+                                    _serde::de::MapAccess::next_value::<
+                                        tursorm::FieldValue<uuid::Uuid>,
+                                    >(&mut __map)?,
+                                );
+                            }
+                            __Field::__field1 => {
+                                if _serde::__private228::Option::is_some(&__field1) {
+                                    return _serde::__private228::Err(
+                                        <__A::Error as _serde::de::Error>::duplicate_field(
+                                            "born_at",
+                                        ),
+                                    );
+                                }
+                                __field1 = _serde::__private228::Some(
+                                    _serde::de::MapAccess::next_value::<
+                                        tursorm::FieldValue<chrono::NaiveDateTime>,
+                                    >(&mut __map)?,
+                                );
+                            }
+                            _ => {
+                                let _ = _serde::de::MapAccess::next_value::<
+                                    _serde::de::IgnoredAny,
+                                >(&mut __map)?;
+                            }
+                        }
+                    }
+                    let __field0 = match __field0 {
+                        _serde::__private228::Some(__field0) => __field0,
+                        _serde::__private228::None => {
+                            _serde::__private228::Default::default()
+                        }
+                    };
+                    let __field1 = match __field1 {
+                        _serde::__private228::Some(__field1) => __field1,
+                        _serde::__private228::None => {
+                            _serde::__private228::Default::default()
+                        }
+                    };
+                    _serde::__private228::Ok(PersonChangeSet {
+                        id: __field0,
+                        born_at: __field1,
+                    })
+                }
+            }
+            #[doc(hidden)]
+            const FIELDS: &'static [&'static str] = &["id", "born_at"];
+            _serde::Deserializer::deserialize_struct(
+                __deserializer,
+                "PersonChangeSet",
+                FIELDS,
+                __Visitor {
+                    marker: _serde::__private228::PhantomData::<PersonChangeSet>,
+                    lifetime: _serde::__private228::PhantomData,
+                },
+            )
+        }
+    }
+};
+#[automatically_derived]
+impl ::core::clone::Clone for PersonChangeSet {
+    #[inline]
+    fn clone(&self) -> PersonChangeSet {
+        PersonChangeSet {
+            id: ::core::clone::Clone::clone(&self.id),
+            born_at: ::core::clone::Clone::clone(&self.born_at),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for PersonChangeSet {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field2_finish(
+            f,
+            "PersonChangeSet",
+            "id",
+            &self.id,
+            "born_at",
+            &&self.born_at,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for PersonChangeSet {
+    #[inline]
+    fn default() -> PersonChangeSet {
+        PersonChangeSet {
+            id: ::core::default::Default::default(),
+            born_at: ::core::default::Default::default(),
+        }
+    }
+}
+impl tursorm::ChangeSetTrait for PersonChangeSet {
+    type Table = PersonTable;
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.id {
+            columns.push("id");
+            values.push(tursorm::codec::uuid_blob::to_value(v));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.born_at {
+            columns.push("born_at");
+            values.push(tursorm::codec::timestamp_seconds::to_value(v));
+        }
+        (columns, values)
+    }
+    fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+        let mut sets = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.born_at {
+            sets.push(("born_at", tursorm::codec::timestamp_seconds::to_value(v)));
+        }
+        sets
+    }
+    fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+        match &self.id {
+            tursorm::FieldValue::Set(v) => Some(tursorm::codec::uuid_blob::to_value(v)),
+            tursorm::FieldValue::NotSet => None,
+        }
+    }
+    fn primary_key_column() -> &'static str {
+        "id"
+    }
+    fn merge(&mut self, later: Self) {
+        if later.id.is_changed() {
+            self.id = later.id;
+        }
+        if later.born_at.is_changed() {
+            self.born_at = later.born_at;
+        }
+    }
+}
+impl From<Person> for PersonChangeSet {
+    fn from(record: Person) -> Self {
+        Self {
+            id: tursorm::FieldValue::Set(record.id.clone()),
+            born_at: tursorm::FieldValue::Set(record.born_at.clone()),
+        }
+    }
+}
+const _: () = {
+    #[serde(crate = "tursorm::serde")]
+    struct RecordRef<'a> {
+        #[serde(rename = "id")]
+        id: &'a uuid::Uuid,
+        #[serde(rename = "born_at")]
+        born_at: &'a chrono::NaiveDateTime,
+    }
+    #[doc(hidden)]
+    #[allow(
+        non_upper_case_globals,
+        unused_attributes,
+        unused_qualifications,
+        clippy::absolute_paths,
+    )]
+    const _: () = {
+        use tursorm::serde as _serde;
+        #[automatically_derived]
+        impl<'a> _serde::Serialize for RecordRef<'a> {
+            fn serialize<__S>(
+                &self,
+                __serializer: __S,
+            ) -> _serde::__private228::Result<__S::Ok, __S::Error>
+            where
+                __S: _serde::Serializer,
+            {
+                let mut __serde_state = _serde::Serializer::serialize_struct(
+                    __serializer,
+                    "RecordRef",
+                    false as usize + 1 + 1,
+                )?;
+                _serde::ser::SerializeStruct::serialize_field(
+                    &mut __serde_state,
+                    "id",
+                    &self.id,
+                )?;
+                _serde::ser::SerializeStruct::serialize_field(
+                    &mut __serde_state,
+                    "born_at",
+                    &self.born_at,
+                )?;
+                _serde::ser::SerializeStruct::end(__serde_state)
+            }
+        }
+    };
+    #[serde(crate = "tursorm::serde")]
+    struct OwnedRecord {
+        #[serde(rename = "id")]
+        id: uuid::Uuid,
+        #[serde(rename = "born_at")]
+        born_at: chrono::NaiveDateTime,
+    }
+    #[doc(hidden)]
+    #[allow(
+        non_upper_case_globals,
+        unused_attributes,
+        unused_qualifications,
+        clippy::absolute_paths,
+    )]
+    const _: () = {
+        use tursorm::serde as _serde;
+        #[automatically_derived]
+        impl<'de> _serde::Deserialize<'de> for OwnedRecord {
+            fn deserialize<__D>(
+                __deserializer: __D,
+            ) -> _serde::__private228::Result<Self, __D::Error>
+            where
+                __D: _serde::Deserializer<'de>,
+            {
+                #[allow(non_camel_case_types)]
+                #[doc(hidden)]
+                enum __Field {
+                    __field0,
+                    __field1,
+                    __ignore,
+                }
+                #[doc(hidden)]
+                struct __FieldVisitor;
+                #[automatically_derived]
+                impl<'de> _serde::de::Visitor<'de> for __FieldVisitor {
+                    type Value = __Field;
+                    fn expecting(
+                        &self,
+                        __formatter: &mut _serde::__private228::Formatter,
+                    ) -> _serde::__private228::fmt::Result {
+                        _serde::__private228::Formatter::write_str(
+                            __formatter,
+                            "field identifier",
+                        )
+                    }
+                    fn visit_u64<__E>(
+                        self,
+                        __value: u64,
+                    ) -> _serde::__private228::Result<Self::Value, __E>
+                    where
+                        __E: _serde::de::Error,
+                    {
+                        match __value {
+                            0u64 => _serde::__private228::Ok(__Field::__field0),
+                            1u64 => _serde::__private228::Ok(__Field::__field1),
+                            _ => _serde::__private228::Ok(__Field::__ignore),
+                        }
+                    }
+                    fn visit_str<__E>(
+                        self,
+                        __value: &str,
+                    ) -> _serde::__private228::Result<Self::Value, __E>
+                    where
+                        __E: _serde::de::Error,
+                    {
+                        match __value {
+                            "id" => _serde::__private228::Ok(__Field::__field0),
+                            "born_at" => _serde::__private228::Ok(__Field::__field1),
+                            _ => _serde::__private228::Ok(__Field::__ignore),
+                        }
+                    }
+                    fn visit_bytes<__E>(
+                        self,
+                        __value: &[u8],
+                    ) -> _serde::__private228::Result<Self::Value, __E>
+                    where
+                        __E: _serde::de::Error,
+                    {
+                        match __value {
+                            b"id" => _serde::__private228::Ok(__Field::__field0),
+                            b"born_at" => _serde::__private228::Ok(__Field::__field1),
+                            _ => _serde::__private228::Ok(__Field::__ignore),
+                        }
+                    }
+                }
+                #[automatically_derived]
+                impl<'de> _serde::Deserialize<'de> for __Field {
+                    #[inline]
+                    fn deserialize<__D>(
+                        __deserializer: __D,
+                    ) -> _serde::__private228::Result<Self, __D::Error>
+                    where
+                        __D: _serde::Deserializer<'de>,
+                    {
+                        _serde::Deserializer::deserialize_identifier(
+                            __deserializer,
+                            __FieldVisitor,
+                        )
+                    }
+                }
+                #[doc(hidden)]
+                struct __Visitor<'de> {
+                    marker: _serde::__private228::PhantomData<OwnedRecord>,
+                    lifetime: _serde::__private228::PhantomData<&'de ()>,
+                }
+                #[automatically_derived]
+                impl<'de> _serde::de::Visitor<'de> for __Visitor<'de> {
+                    type Value = OwnedRecord;
+                    fn expecting(
+                        &self,
+                        __formatter: &mut _serde::__private228::Formatter,
+                    ) -> _serde::__private228::fmt::Result {
+                        _serde::__private228::Formatter::write_str(
+                            __formatter,
+                            "struct OwnedRecord",
+                        )
+                    }
+                    #[inline]
+                    fn visit_seq<__A>(
+                        self,
+                        mut __seq: __A,
+                    ) -> _serde::__private228::Result<Self::Value, __A::Error>
+                    where
+                        __A: _serde::de::SeqAccess<'de>,
+                    {
+                        let __field0 = match _serde::de::SeqAccess::next_element::<
+                            uuid::Uuid,
+                        >(&mut __seq)? {
+                            _serde::__private228::Some(__value) => __value,
+                            _serde::__private228::None => {
+                                return _serde::__private228::Err(
+                                    _serde::de::Error::invalid_length(
+                                        0usize,
+                                        &"struct OwnedRecord with 2 elements",
+                                    ),
+                                );
+                            }
+                        };
+                        let __field1 = match _serde::de::SeqAccess::next_element::<
+                            chrono::NaiveDateTime,
+                        >(&mut __seq)? {
+                            _serde::__private228::Some(__value) => __value,
+                            _serde::__private228::None => {
+                                return _serde::__private228::Err(
+                                    _serde::de::Error::invalid_length(
+                                        1usize,
+                                        &"struct OwnedRecord with 2 elements",
+                                    ),
+                                );
+                            }
+                        };
+                        _serde::__private228::Ok(OwnedRecord {
+                            id: __field0,
+                            born_at: __field1,
+                        })
+                    }
+                    #[inline]
+                    fn visit_map<__A>(
+                        self,
+                        mut __map: __A,
+                    ) -> _serde::__private228::Result<Self::Value, __A::Error>
+                    where
+                        __A: _serde::de::MapAccess<'de>,
+                    {
+                        let mut __field0: _serde::__private228::Option<uuid::Uuid> = _serde::__private228::None;
+                        let mut __field1: _serde::__private228::Option<
+                            chrono::NaiveDateTime,
+                        > = _serde::__private228::None;
+                        while let _serde::__private228::Some(__key) = _serde::de::MapAccess::next_key::<
+                            __Field,
+                        >(&mut __map)? {
+                            match __key {
+                                __Field::__field0 => {
+                                    if _serde::__private228::Option::is_some(&__field0) {
+                                        return _serde::__private228::Err(
+                                            <__A::Error as _serde::de::Error>::duplicate_field("id"),
+                                        );
+                                    }
+                                    __field0 = _serde::__private228::Some(
+                                        _serde::de::MapAccess::next_value::<uuid::Uuid>(&mut __map)?,
+                                    );
+                                }
+                                __Field::__field1 => {
+                                    if _serde::__private228::Option::is_some(&__field1) {
+                                        return _serde::__private228::Err(
+                                            <__A::Error as _serde::de::Error>::duplicate_field(
+                                                "born_at",
+                                            ),
+                                        );
+                                    }
+                                    __field1 = _serde::__private228::Some(
+                                        _serde::de::MapAccess::next_value::<
+                                            chrono::NaiveDateTime,
+                                        >(&mut __map)?,
+                                    );
+                                }
+                                _ => {
+                                    let _ = _serde::de::MapAccess::next_value::<
+                                        _serde::de::IgnoredAny,
+                                    >(&mut __map)?;
+                                }
+                            }
+                        }
+                        let __field0 = match __field0 {
+                            _serde::__private228::Some(__field0) => __field0,
+                            _serde::__private228::None => {
+                                _serde::__private228::de::missing_field("id")?
+                            }
+                        };
+                        let __field1 = match __field1 {
+                            _serde::__private228::Some(__field1) => __field1,
+                            _serde::__private228::None => {
+                                _serde::__private228::de::missing_field("born_at")?
+                            }
+                        };
+                        _serde::__private228::Ok(OwnedRecord {
+                            id: __field0,
+                            born_at: __field1,
+                        })
+                    }
+                }
+                #[doc(hidden)]
+                const FIELDS: &'static [&'static str] = &["id", "born_at"];
+                _serde::Deserializer::deserialize_struct(
+                    __deserializer,
+                    "OwnedRecord",
+                    FIELDS,
+                    __Visitor {
+                        marker: _serde::__private228::PhantomData::<OwnedRecord>,
+                        lifetime: _serde::__private228::PhantomData,
+                    },
+                )
+            }
+        }
+    };
+    impl tursorm::serde::Serialize for Person {
+        fn serialize<S: tursorm::serde::Serializer>(
+            &self,
+            serializer: S,
+        ) -> ::std::result::Result<S::Ok, S::Error> {
+            tursorm::serde::Serialize::serialize(
+                &RecordRef {
+                    id: &self.id,
+                    born_at: &self.born_at,
+                },
+                serializer,
+            )
+        }
+    }
+    impl tursorm::serde::Serialize for PersonColumn {
+        fn serialize<S: tursorm::serde::Serializer>(
+            &self,
+            serializer: S,
+        ) -> ::std::result::Result<S::Ok, S::Error> {
+            serializer.serialize_str(tursorm::ColumnTrait::name(self))
+        }
+    }
+    impl<'de> tursorm::serde::Deserialize<'de> for PersonColumn {
+        fn deserialize<D: tursorm::serde::Deserializer<'de>>(
+            deserializer: D,
+        ) -> ::std::result::Result<Self, D::Error> {
+            let name = <String as tursorm::serde::Deserialize>::deserialize(
+                deserializer,
+            )?;
+            <Self as tursorm::ColumnTrait>::from_name(&name)
+                .ok_or_else(|| {
+                    <D::Error as tursorm::serde::de::Error>::custom(
+                        format_args!("unknown column `{0}`", name),
+                    )
+                })
+        }
+    }
+    impl<'de> tursorm::serde::Deserialize<'de> for Person {
+        fn deserialize<D: tursorm::serde::Deserializer<'de>>(
+            deserializer: D,
+        ) -> ::std::result::Result<Self, D::Error> {
+            let record = <OwnedRecord as tursorm::serde::Deserialize>::deserialize(
+                deserializer,
+            )?;
+            Ok(Self {
+                id: record.id,
+                born_at: record.born_at,
+            })
+        }
+    }
+};
+impl PersonChangeSet {
+    /// Builds a change set from a JSON object keyed by column name, setting only the keys that are present.
+    pub fn from_json(value: tursorm::serde_json::Value) -> tursorm::Result<Self> {
+        Ok(tursorm::serde_json::from_value(value)?)
+    }
+    /// Sets the `allowed` columns whose keys are present in the JSON object and returns the columns it set.
+    /// Other keys are ignored, so a client cannot write columns outside the whitelist. Nothing is set if any
+    /// value fails to deserialize.
+    pub fn apply_json(
+        &mut self,
+        json: &tursorm::serde_json::Value,
+        allowed: &[PersonColumn],
+    ) -> tursorm::Result<Vec<PersonColumn>> {
+        let object = json
+            .as_object()
+            .ok_or_else(|| tursorm::Error::Query(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!("Expected a JSON object, got {0}", json),
+                    )
+                }),
+            ))?;
+        let mut patched = self.clone();
+        let mut changed = Vec::new();
+        for column in allowed {
+            if changed.contains(column) {
+                continue;
+            }
+            match column {
+                PersonColumn::Id => {
+                    match object.get("id") {
+                        Some(value) => {
+                            patched.id = tursorm::FieldValue::Set(
+                                tursorm::serde_json::from_value(value.clone())?,
+                            );
+                        }
+                        None => continue,
+                    }
+                }
+                PersonColumn::BornAt => {
+                    match object.get("born_at") {
+                        Some(value) => {
+                            patched.born_at = tursorm::FieldValue::Set(
+                                tursorm::serde_json::from_value(value.clone())?,
+                            );
+                        }
+                        None => continue,
+                    }
+                }
+            }
+            changed.push(*column);
+        }
+        *self = patched;
+        Ok(changed)
+    }
+}
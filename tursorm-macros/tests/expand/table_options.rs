@@ -0,0 +1,11 @@
+use tursorm::Table;
+
+/// Archived people.
+#[derive(Table)]
+#[tursorm(table_name = "people", renamed_from = "persons", database = "archive", default_limit = 50, serde)]
+pub struct Person {
+    #[tursorm(primary_key, uuid_blob)]
+    id: uuid::Uuid,
+    #[tursorm(timestamp_seconds)]
+    born_at: chrono::NaiveDateTime,
+}
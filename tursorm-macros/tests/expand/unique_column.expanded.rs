@@ -0,0 +1,345 @@
+use tursorm::Table;
+struct Account {
+    #[tursorm(primary_key)]
+    code: String,
+    #[tursorm(unique, collate = "nocase", max_length = 255)]
+    email: String,
+}
+enum AccountColumn {
+    Code,
+    Email,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for AccountColumn {
+    #[inline]
+    fn clone(&self) -> AccountColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for AccountColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for AccountColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                AccountColumn::Code => "Code",
+                AccountColumn::Email => "Email",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for AccountColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for AccountColumn {
+    #[inline]
+    fn eq(&self, other: &AccountColumn) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for AccountColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for AccountColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        ::core::hash::Hash::hash(&__self_discr, state)
+    }
+}
+impl tursorm::ColumnTrait for AccountColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Code => "code",
+            Self::Email => "email",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Code => tursorm::ColumnType::Text,
+            Self::Email => tursorm::ColumnType::Text,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Code => false,
+            Self::Email => false,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Code => true,
+            Self::Email => false,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Code => false,
+            Self::Email => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Code => false,
+            Self::Email => true,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Code => None,
+            Self::Email => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Code => None,
+            Self::Email => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Code => None,
+            Self::Email => None,
+        }
+    }
+    fn collation(&self) -> Option<tursorm::Collation> {
+        match self {
+            Self::Code => None,
+            Self::Email => Some(tursorm::Collation::NoCase),
+        }
+    }
+    fn max_length(&self) -> Option<usize> {
+        match self {
+            Self::Code => None,
+            Self::Email => Some(255usize),
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Code, Self::Email]
+    }
+}
+impl ::std::fmt::Display for AccountColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for AccountColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+struct AccountTable;
+#[automatically_derived]
+impl ::core::clone::Clone for AccountTable {
+    #[inline]
+    fn clone(&self) -> AccountTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for AccountTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for AccountTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "AccountTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for AccountTable {
+    #[inline]
+    fn default() -> AccountTable {
+        AccountTable {}
+    }
+}
+impl tursorm::TableTrait for AccountTable {
+    type Record = Account;
+    type Column = AccountColumn;
+    type ChangeSet = AccountChangeSet;
+    type PrimaryKeyType = String;
+    fn table_name() -> &'static str {
+        "account"
+    }
+    fn primary_key() -> Self::Column {
+        AccountColumn::Code
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "code, email"
+    }
+    fn column_count() -> usize {
+        2usize
+    }
+}
+impl tursorm::FromRow for Account {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            code: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            email: tursorm::FromValue::from_value(
+                row
+                    .get_value(1usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+        })
+    }
+}
+impl tursorm::RecordTrait for Account {
+    type Table = AccountTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.code).clone())
+    }
+}
+impl AccountTable {
+    pub fn find_by_email(value: impl Into<String>) -> tursorm::Select<Self> {
+        let value: String = value.into();
+        tursorm::Select::new()
+            .filter(
+                tursorm::Condition::eq(
+                    AccountColumn::Email,
+                    tursorm::IntoValue::into_value((&value).clone()),
+                ),
+            )
+    }
+}
+impl Account {
+    /// Converts this record into a change set that keeps the primary key and only the fields that differ
+    /// from `original`, so updating with it writes just the edited columns.
+    fn into_change_set_diff(self, original: &Self) -> AccountChangeSet {
+        AccountChangeSet {
+            code: tursorm::FieldValue::Set(self.code),
+            email: if tursorm::IntoValue::into_value((&self.email).clone())
+                == tursorm::IntoValue::into_value((&original.email).clone())
+            {
+                tursorm::FieldValue::NotSet
+            } else {
+                tursorm::FieldValue::Set(self.email)
+            },
+        }
+    }
+}
+impl AccountTable {
+    pub fn change_set() -> AccountChangeSet {
+        AccountChangeSet::default()
+    }
+}
+struct AccountChangeSet {
+    pub code: tursorm::FieldValue<String>,
+    pub email: tursorm::FieldValue<String>,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for AccountChangeSet {
+    #[inline]
+    fn clone(&self) -> AccountChangeSet {
+        AccountChangeSet {
+            code: ::core::clone::Clone::clone(&self.code),
+            email: ::core::clone::Clone::clone(&self.email),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for AccountChangeSet {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field2_finish(
+            f,
+            "AccountChangeSet",
+            "code",
+            &self.code,
+            "email",
+            &&self.email,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for AccountChangeSet {
+    #[inline]
+    fn default() -> AccountChangeSet {
+        AccountChangeSet {
+            code: ::core::default::Default::default(),
+            email: ::core::default::Default::default(),
+        }
+    }
+}
+impl tursorm::ChangeSetTrait for AccountChangeSet {
+    type Table = AccountTable;
+    fn get_insert_columns_and_values(&self) -> (Vec<&'static str>, Vec<tursorm::Value>) {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.code {
+            columns.push("code");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        if let tursorm::FieldValue::Set(ref v) = self.email {
+            columns.push("email");
+            values.push(tursorm::IntoValue::into_value((v).clone()));
+        }
+        (columns, values)
+    }
+    fn get_update_sets(&self) -> Vec<(&'static str, tursorm::Value)> {
+        let mut sets = Vec::new();
+        if let tursorm::FieldValue::Set(ref v) = self.email {
+            sets.push(("email", tursorm::IntoValue::into_value((v).clone())));
+        }
+        sets
+    }
+    fn get_primary_key_value(&self) -> Option<tursorm::Value> {
+        match &self.code {
+            tursorm::FieldValue::Set(v) => {
+                Some(tursorm::IntoValue::into_value((v).clone()))
+            }
+            tursorm::FieldValue::NotSet => None,
+        }
+    }
+    fn primary_key_column() -> &'static str {
+        "code"
+    }
+    fn merge(&mut self, later: Self) {
+        if later.code.is_changed() {
+            self.code = later.code;
+        }
+        if later.email.is_changed() {
+            self.email = later.email;
+        }
+    }
+}
+impl From<Account> for AccountChangeSet {
+    fn from(record: Account) -> Self {
+        Self {
+            code: tursorm::FieldValue::Set(record.code.clone()),
+            email: tursorm::FieldValue::Set(record.email.clone()),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use tursorm::Table;
+
+#[derive(Table)]
+struct Account {
+    #[tursorm(primary_key)]
+    code: String,
+    #[tursorm(unique, collate = "nocase", max_length = 255)]
+    email: String,
+}
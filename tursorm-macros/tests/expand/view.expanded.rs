@@ -0,0 +1,219 @@
+use tursorm::Table;
+#[tursorm(
+    table_name = "active_users",
+    view = "SELECT id, name FROM users WHERE active = 1"
+)]
+struct ActiveUser {
+    #[tursorm(primary_key)]
+    id: i64,
+    name: String,
+}
+enum ActiveUserColumn {
+    Id,
+    Name,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for ActiveUserColumn {
+    #[inline]
+    fn clone(&self) -> ActiveUserColumn {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for ActiveUserColumn {}
+#[automatically_derived]
+impl ::core::fmt::Debug for ActiveUserColumn {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                ActiveUserColumn::Id => "Id",
+                ActiveUserColumn::Name => "Name",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for ActiveUserColumn {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for ActiveUserColumn {
+    #[inline]
+    fn eq(&self, other: &ActiveUserColumn) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for ActiveUserColumn {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::hash::Hash for ActiveUserColumn {
+    #[inline]
+    fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) -> () {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        ::core::hash::Hash::hash(&__self_discr, state)
+    }
+}
+impl tursorm::ColumnTrait for ActiveUserColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Name => "name",
+        }
+    }
+    fn column_type(&self) -> tursorm::ColumnType {
+        match self {
+            Self::Id => tursorm::ColumnType::Integer,
+            Self::Name => tursorm::ColumnType::Text,
+        }
+    }
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Name => false,
+        }
+    }
+    fn is_primary_key(&self) -> bool {
+        match self {
+            Self::Id => true,
+            Self::Name => false,
+        }
+    }
+    fn is_auto_increment(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Name => false,
+        }
+    }
+    fn is_unique(&self) -> bool {
+        match self {
+            Self::Id => false,
+            Self::Name => false,
+        }
+    }
+    fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::Name => None,
+        }
+    }
+    fn renamed_from(&self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::Name => None,
+        }
+    }
+    fn foreign_key(&self) -> Option<tursorm::ForeignKeyInfo> {
+        match self {
+            Self::Id => None,
+            Self::Name => None,
+        }
+    }
+    fn all() -> &'static [Self] {
+        &[Self::Id, Self::Name]
+    }
+}
+impl ::std::fmt::Display for ActiveUserColumn {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_fmt(format_args!("{0}", self.name()))
+    }
+}
+impl ::std::str::FromStr for ActiveUserColumn {
+    type Err = tursorm::Error;
+    fn from_str(name: &str) -> tursorm::Result<Self> {
+        <Self as tursorm::ColumnTrait>::from_name(name)
+            .ok_or_else(|| tursorm::Error::ColumnNotFound(name.to_string()))
+    }
+}
+struct ActiveUserTable;
+#[automatically_derived]
+impl ::core::clone::Clone for ActiveUserTable {
+    #[inline]
+    fn clone(&self) -> ActiveUserTable {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for ActiveUserTable {}
+#[automatically_derived]
+impl ::core::fmt::Debug for ActiveUserTable {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(f, "ActiveUserTable")
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for ActiveUserTable {
+    #[inline]
+    fn default() -> ActiveUserTable {
+        ActiveUserTable {}
+    }
+}
+impl tursorm::TableTrait for ActiveUserTable {
+    type Record = ActiveUser;
+    type Column = ActiveUserColumn;
+    type ChangeSet = tursorm::ReadOnlyChangeSet<Self>;
+    type PrimaryKeyType = i64;
+    const READ_ONLY: bool = true;
+    fn table_name() -> &'static str {
+        "active_users"
+    }
+    fn primary_key() -> Self::Column {
+        ActiveUserColumn::Id
+    }
+    fn primary_key_to_value(id: &Self::PrimaryKeyType) -> tursorm::Value {
+        tursorm::IntoValue::into_value((id).clone())
+    }
+    fn primary_key_auto_increment() -> bool {
+        false
+    }
+    fn all_columns() -> &'static str {
+        "id, name"
+    }
+    fn column_count() -> usize {
+        2usize
+    }
+    fn view_definition() -> Option<&'static str> {
+        Some("SELECT id, name FROM users WHERE active = 1")
+    }
+}
+impl tursorm::FromRow for ActiveUser {
+    fn from_row(row: &tursorm::Row) -> tursorm::Result<Self> {
+        Ok(Self {
+            id: tursorm::FromValue::from_value(
+                row
+                    .get_value(0usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Integer",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+            name: tursorm::FromValue::from_value(
+                row
+                    .get_value(1usize)
+                    .map_err(|e| tursorm::Error::TypeConversion {
+                        expected: "Text",
+                        actual: ::alloc::__export::must_use({
+                            ::alloc::fmt::format(format_args!("{0:?}", e))
+                        }),
+                        error: "Conversion error".to_string(),
+                    })?,
+            )?,
+        })
+    }
+}
+impl tursorm::RecordTrait for ActiveUser {
+    type Table = ActiveUserTable;
+    fn get_primary_key_value(&self) -> tursorm::Value {
+        tursorm::IntoValue::into_value((&self.id).clone())
+    }
+}
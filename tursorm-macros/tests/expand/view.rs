@@ -0,0 +1,9 @@
+use tursorm::Table;
+
+#[derive(Table)]
+#[tursorm(table_name = "active_users", view = "SELECT id, name FROM users WHERE active = 1")]
+struct ActiveUser {
+    #[tursorm(primary_key)]
+    id: i64,
+    name: String,
+}